@@ -0,0 +1,100 @@
+//! Compares parsing into nodes that are each allocated on the heap with
+//! parsing into an arena, which allocates them from a bump allocator and
+//! frees them all at once.
+//!
+//! Run with `cargo bench --bench parse [-- <file.js>]`. Without a file, a
+//! few megabytes of generated code are parsed instead.
+
+extern crate jsparse;
+extern crate time;
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::mem;
+
+use jsparse::arena::Arena;
+use jsparse::parser::{Parser, ParserOptions};
+use jsparse::IntoTokenizer;
+
+const ITERATIONS: usize = 10;
+
+const SAMPLE: &str = r#"
+var counter = 0, names = ["a", "b", "c"];
+function Widget(options) {
+    this.options = options || {};
+    this.items = [];
+}
+Widget.prototype.add = function (item, index) {
+    if (typeof index === "number" && index >= 0) {
+        this.items.splice(index, 0, item);
+    } else {
+        this.items.push(item);
+    }
+    counter += 1;
+    return this;
+};
+var render = (items) => items.map((item, i) => `<li data-i="${i}">${item.name}</li>`).join("");
+for (let i = 0; i < names.length; i++) {
+    try {
+        new Widget({ name: names[i], size: i * 2 + 1 }).add({ name: names[i] }, -1);
+    } catch (err) {
+        console.error(err.message);
+    }
+}
+"#;
+
+// Time a closure, in milliseconds.
+fn measure<T, F: FnOnce() -> T>(f: F) -> (T, f64) {
+    let start = time::precise_time_ns();
+    let value = f();
+    (value, (time::precise_time_ns() - start) as f64 / 1e6)
+}
+
+fn report(name: &str, bytes: usize, parse: f64, drop: f64) {
+    let total = parse + drop;
+    println!(
+        "{:<6} parse {:>9.2}ms  drop {:>9.2}ms  total {:>9.2}ms  ({:.1} MB/s)",
+        name,
+        parse,
+        drop,
+        total,
+        bytes as f64 / 1e6 / (total / 1e3)
+    );
+}
+
+fn main() {
+    let code = match env::args().skip(1).find(|arg| !arg.starts_with("--")) {
+        Some(filename) => {
+            let mut code = String::new();
+            File::open(filename).unwrap().read_to_string(&mut code).unwrap();
+            code
+        }
+        None => SAMPLE.repeat(4096),
+    };
+    println!("Parsing {:.1} MB, best of {} runs", code.len() as f64 / 1e6, ITERATIONS);
+
+    let mut best = (f64::MAX, f64::MAX);
+    for _ in 0..ITERATIONS {
+        let (script, parse) = measure(|| Parser::new(code.as_str().into_tokenizer()).parse_script().unwrap());
+        let ((), drop) = measure(|| mem::drop(script));
+        if parse + drop < best.0 + best.1 {
+            best = (parse, drop);
+        }
+    }
+    report("boxed", code.len(), best.0, best.1);
+
+    let mut best = (f64::MAX, f64::MAX);
+    for _ in 0..ITERATIONS {
+        let (arena, parse) = measure(|| {
+            let arena = Arena::new();
+            arena.parse_script(&code, &ParserOptions::script()).unwrap();
+            arena
+        });
+        let ((), drop) = measure(|| mem::drop(arena));
+        if parse + drop < best.0 + best.1 {
+            best = (parse, drop);
+        }
+    }
+    report("arena", code.len(), best.0, best.1);
+}
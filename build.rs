@@ -0,0 +1,132 @@
+// Collects the nodes declared with `node!` and `node_enum!` in src/ast, and
+// writes the lists that `ast::visit` and `ast::fold` generate their traits
+// from, so that declaring a node is all it takes to visit or fold it.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+const AST_DIR: &str = "src/ast";
+
+// Modules whose node names mirror those of JavaScript nodes, and always get
+// their methods prefixed with the module name.
+const PREFIXED_MODULES: &[&str] = &["jsx"];
+
+struct Declaration {
+    module: String,
+    name: String,
+    is_enum: bool,
+    labels: Vec<String>,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", AST_DIR);
+
+    let mut paths: Vec<_> = fs::read_dir(AST_DIR)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    paths.sort();
+
+    let mut declarations = vec![];
+    for path in &paths {
+        let module = path.file_stem().unwrap().to_str().unwrap();
+        let source = fs::read_to_string(path).unwrap();
+        declarations.extend(source.lines().filter_map(|line| parse_declaration(module, line)));
+    }
+
+    let mut counts = HashMap::new();
+    for decl in declarations.iter().filter(|decl| !PREFIXED_MODULES.contains(&&decl.module[..])) {
+        *counts.entry(decl.name.clone()).or_insert(0) += 1;
+    }
+    let method = |prefix: &str, decl: &Declaration| {
+        // Names used in more than one module are prefixed, except for the
+        // enums in "alias" that most code deals with.
+        let prefixed = PREFIXED_MODULES.contains(&&decl.module[..])
+            || (counts[&decl.name] > 1 && decl.module != "alias");
+        if prefixed {
+            format!("{}_{}_{}", prefix, decl.module, snake_case(&decl.name))
+        } else {
+            format!("{}_{}", prefix, snake_case(&decl.name))
+        }
+    };
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    let mut visitors = String::from("visitors! {\n");
+    for decl in declarations.iter().filter(|decl| !decl.is_enum) {
+        visitors += &format!("    {}::{}: {},\n", decl.module, decl.name, method("visit", decl));
+    }
+    visitors += "}\n";
+    write(&Path::new(&out_dir).join("visitors.rs"), &visitors);
+
+    // Enums labelled "@list" only appear in lists, and are listed after the
+    // others.
+    let (lists, enums): (Vec<_>, Vec<_>) = declarations
+        .iter()
+        .filter(|decl| decl.is_enum)
+        .partition(|decl| decl.labels.iter().any(|label| label == "list"));
+    let mut folders = String::from("folders! {\n");
+    for decl in enums {
+        folders += &format!("    {}::{}: {},\n", decl.module, decl.name, method("fold", decl));
+    }
+    folders += "    ;\n";
+    for decl in lists {
+        folders += &format!("    {}::{}: {},\n", decl.module, decl.name, method("fold", decl));
+    }
+    folders += "}\n";
+    write(&Path::new(&out_dir).join("folders.rs"), &folders);
+}
+
+// Reads a line like "node!(#[derive(Default)] pub struct Name {" or
+// "node_enum!(@boxed pub enum Name {". The macros' own definitions are
+// indented, and never match.
+fn parse_declaration(module: &str, line: &str) -> Option<Declaration> {
+    let (is_enum, rest) = if let Some(rest) = line.strip_prefix("node!(") {
+        (false, rest)
+    } else if let Some(rest) = line.strip_prefix("node_enum!(") {
+        (true, rest)
+    } else {
+        return None;
+    };
+
+    let keyword = if is_enum { "pub enum " } else { "pub struct " };
+    let index = rest.find(keyword)?;
+    let labels = rest[..index]
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .map(String::from)
+        .collect();
+    let name = rest[index + keyword.len()..]
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()?;
+
+    Some(Declaration {
+        module: module.to_string(),
+        name: name.to_string(),
+        is_enum,
+        labels,
+    })
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i != 0 {
+            out.push('_');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+// Only writes files that changed, to leave their timestamps alone otherwise.
+fn write(path: &Path, contents: &str) {
+    if fs::read_to_string(path).ok().as_deref() == Some(contents) {
+        return;
+    }
+    fs::File::create(path).unwrap().write_all(contents.as_bytes()).unwrap();
+}
@@ -0,0 +1,3 @@
+//! Analyses that run over a parsed AST.
+
+pub mod scope;
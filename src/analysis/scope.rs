@@ -0,0 +1,1005 @@
+//! Scope analysis: which names each scope declares, and which declaration
+//! each identifier reference resolves to.
+//!
+//! References are resolved once the whole tree has been walked, so hoisted
+//! declarations like `var` and functions are found regardless of where in
+//! their scope they appear. Functions other than arrows declare an implicit
+//! `arguments` binding, unless a parameter or declaration of theirs already
+//! has that name.
+//!
+//! When the parameters of a function contain expressions, like default
+//! values, the declarations in its body get a scope of their own, which the
+//! expressions can't see: in `function f(p = x) { var x; }`, `x` in the
+//! default value is free.
+//!
+//! In sloppy mode code, function declarations in blocks are also bound in the
+//! enclosing function as Annex B requires, unless that would conflict with a
+//! lexical declaration of the same name.
+
+use std::collections::HashMap;
+
+use ast::visit::{Visit, Visitable, Walk};
+use ast::{classes, expression, functions, general, modules, objects, root, statement};
+use ast::{Node, NodePosition, Str};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScopeId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BindingId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReferenceId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Global,
+    Module,
+    Function,
+    /// The body of a function whose parameters contain expressions, holding
+    /// the declarations of the body apart from the parameters.
+    FunctionBody,
+    Block,
+    Catch,
+    Class,
+    With,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclarationKind {
+    Var,
+    Let,
+    Const,
+    Function,
+    Class,
+    Param,
+    CatchParam,
+    Import,
+    /// The implicit `arguments` binding of a function.
+    Arguments,
+}
+
+/// How a reference uses its variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    // e.g. "a += 1" or "a++"
+    ReadWrite,
+}
+
+#[derive(Debug)]
+pub struct Scope<'src> {
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    pub children: Vec<ScopeId>,
+    pub bindings: Vec<BindingId>,
+
+    names: HashMap<Str<'src>, BindingId>,
+}
+
+/// A declared name. Redeclarations in the same scope, like "var a; var a;",
+/// share a single binding that keeps the kind of the first declaration.
+#[derive(Debug)]
+pub struct Binding<'src> {
+    pub name: Str<'src>,
+    pub kind: DeclarationKind,
+    pub scope: ScopeId,
+    pub position: Option<NodePosition>,
+    pub references: Vec<ReferenceId>,
+    /// True for bindings that are tied to a binding of the same name in
+    /// another scope, which must keep the same name as each other: functions
+    /// that Annex B also declares in the enclosing function, catch
+    /// parameters along with "var" declarations of the same name in the
+    /// catch block, whose initializers assign the parameter, and parameters
+    /// along with "var" declarations of the same name in a function body
+    /// with a scope of its own, which start with the parameter's value.
+    pub linked: bool,
+}
+
+#[derive(Debug)]
+pub struct Reference<'src> {
+    pub name: Str<'src>,
+    pub scope: ScopeId,
+    pub access: Access,
+    pub binding: Option<BindingId>,
+    /// True if the reference is inside a "with" statement that could shadow
+    /// the binding it resolves to.
+    pub dynamic: bool,
+    pub position: Option<NodePosition>,
+}
+
+/// The scopes of a script or module, with every declaration and reference
+/// in it. References are stored in the order that `Visit` visits them.
+#[derive(Debug)]
+pub struct ScopeTree<'src> {
+    scopes: Vec<Scope<'src>>,
+    bindings: Vec<Binding<'src>>,
+    references: Vec<Reference<'src>>,
+    declarations: Vec<Option<BindingId>>,
+}
+impl<'src> ScopeTree<'src> {
+    fn new() -> ScopeTree<'src> {
+        let mut tree = ScopeTree {
+            scopes: vec![],
+            bindings: vec![],
+            references: vec![],
+            declarations: vec![],
+        };
+        tree.add_scope(ScopeKind::Global, None);
+        tree
+    }
+
+    /// The global scope, which holds the top-level declarations of scripts.
+    pub fn root(&self) -> ScopeId {
+        ScopeId(0)
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope<'src> {
+        &self.scopes[id.0]
+    }
+    pub fn binding(&self, id: BindingId) -> &Binding<'src> {
+        &self.bindings[id.0]
+    }
+    pub fn reference(&self, id: ReferenceId) -> &Reference<'src> {
+        &self.references[id.0]
+    }
+
+    pub fn scopes(&self) -> impl Iterator<Item = (ScopeId, &Scope<'src>)> {
+        self.scopes.iter().enumerate().map(|(i, scope)| (ScopeId(i), scope))
+    }
+    pub fn bindings(&self) -> impl Iterator<Item = (BindingId, &Binding<'src>)> {
+        self.bindings.iter().enumerate().map(|(i, binding)| (BindingId(i), binding))
+    }
+    pub fn references(&self) -> impl Iterator<Item = (ReferenceId, &Reference<'src>)> {
+        self.references.iter().enumerate().map(|(i, reference)| (ReferenceId(i), reference))
+    }
+
+    /// The binding declared by each `BindingIdentifier`, in the order that
+    /// `Visit` visits them.
+    pub fn declarations(&self) -> &[Option<BindingId>] {
+        &self.declarations
+    }
+
+    /// Find the binding that a name refers to from inside the given scope.
+    pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<BindingId> {
+        self.ancestors(scope).filter_map(|id| self.scope(id).names.get(name).cloned()).next()
+    }
+
+    /// The scope and each of its parents, innermost first.
+    pub fn ancestors(&self, scope: ScopeId) -> impl Iterator<Item = ScopeId> + '_ {
+        let mut next = Some(scope);
+        ::std::iter::from_fn(move || {
+            let id = next?;
+            next = self.scope(id).parent;
+            Some(id)
+        })
+    }
+
+    /// The names that are referenced without being declared anywhere, which
+    /// are implicitly globals, in the order they first appear.
+    pub fn free_variables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for reference in self.references.iter().filter(|r| r.binding.is_none()) {
+            if !names.contains(&reference.name.as_str()) {
+                names.push(&reference.name);
+            }
+        }
+        names
+    }
+
+    fn add_scope(&mut self, kind: ScopeKind, parent: Option<ScopeId>) -> ScopeId {
+        let id = ScopeId(self.scopes.len());
+        self.scopes.push(Scope {
+            kind,
+            parent,
+            children: vec![],
+            bindings: vec![],
+            names: HashMap::new(),
+        });
+        if let Some(parent) = parent {
+            self.scopes[parent.0].children.push(id);
+        }
+        id
+    }
+
+    fn declare(&mut self, scope: ScopeId, kind: DeclarationKind, id: &general::BindingIdentifier<'src>) -> BindingId {
+        self.declare_name(scope, kind, &id.value, id.position().cloned())
+    }
+
+    fn declare_name(&mut self, scope: ScopeId, kind: DeclarationKind, name: &Str<'src>, position: Option<NodePosition>) -> BindingId {
+        if let Some(&binding) = self.scopes[scope.0].names.get(name) {
+            return binding;
+        }
+
+        let binding = BindingId(self.bindings.len());
+        self.bindings.push(Binding {
+            name: name.clone(),
+            kind,
+            scope,
+            position,
+            references: vec![],
+            linked: false,
+        });
+        self.scopes[scope.0].bindings.push(binding);
+        self.scopes[scope.0].names.insert(name.clone(), binding);
+        binding
+    }
+
+    // Bind a function declared in a block in the enclosing function too,
+    // unless a lexical declaration of the same name is in the way.
+    fn hoist_block_function(&mut self, function: BindingId) {
+        let name = self.binding(function).name.clone();
+        let mut scope = self.binding(function).scope;
+        loop {
+            scope = self.scope(scope).parent.unwrap();
+            let existing = self.scope(scope).names.get(&name).map(|&id| self.binding(id).kind);
+            match self.scope(scope).kind {
+                ScopeKind::Block | ScopeKind::Catch | ScopeKind::Class | ScopeKind::With => match existing {
+                    None | Some(DeclarationKind::CatchParam) => {}
+                    Some(_) => return,
+                },
+                _ => match existing {
+                    None | Some(DeclarationKind::Var) | Some(DeclarationKind::Function) => break,
+                    Some(_) => return,
+                },
+            }
+        }
+
+        let position = self.binding(function).position.clone();
+        let binding = self.declare_name(scope, DeclarationKind::Var, &name, position);
+        self.bindings[binding.0].linked = true;
+        self.bindings[function.0].linked = true;
+    }
+
+    // Tie the "var" declarations in the scope of a function body to the
+    // parameters of the same name, whose values they start with.
+    fn tie_vars_to_params(&mut self, params: ScopeId, body: ScopeId) {
+        let tied: Vec<(BindingId, BindingId)> = self.scope(body).bindings.iter()
+            .filter(|&&var| self.binding(var).kind == DeclarationKind::Var)
+            .filter_map(|&var| Some((*self.scope(params).names.get(&self.binding(var).name)?, var)))
+            .collect();
+        for (param, var) in tied {
+            self.bindings[param.0].linked = true;
+            self.bindings[var.0].linked = true;
+        }
+    }
+
+    fn resolve(&mut self) {
+        for i in 0..self.references.len() {
+            let mut dynamic = false;
+            let mut binding = None;
+            for id in self.ancestors(self.references[i].scope) {
+                let scope = self.scope(id);
+                if let Some(&found) = scope.names.get(&self.references[i].name) {
+                    binding = Some(found);
+                    break;
+                }
+                if scope.kind == ScopeKind::With {
+                    dynamic = true;
+                }
+            }
+
+            if let Some(binding) = binding {
+                self.bindings[binding.0].references.push(ReferenceId(i));
+            }
+            self.references[i].binding = binding;
+            self.references[i].dynamic = dynamic;
+        }
+    }
+}
+
+/// Build the scope tree for a script.
+pub fn analyze_script<'src>(script: &root::Script<'src>) -> ScopeTree<'src> {
+    let mut builder = Builder::new();
+    builder.visit_script(script);
+    builder.finish()
+}
+
+/// Build the scope tree for a module. Its declarations are held in a module
+/// scope below the global scope.
+pub fn analyze_module<'src>(module: &root::Module<'src>) -> ScopeTree<'src> {
+    let mut builder = Builder::new();
+    builder.visit_module(module);
+    builder.finish()
+}
+
+struct Builder<'src> {
+    tree: ScopeTree<'src>,
+    current: ScopeId,
+
+    // The kind of declaration that binding identifiers are currently part of.
+    declaring: Option<DeclarationKind>,
+    // How reference identifiers are currently being used.
+    access: Access,
+    // Whether the code being visited is strict mode code.
+    strict: bool,
+    // Functions declared in blocks of sloppy mode code.
+    block_functions: Vec<BindingId>,
+}
+
+// Visit the children of declarations with the matching declaration kind.
+macro_rules! declarations {
+    ($($method:ident($node:ty): $kind:ident,)*) => {
+        $(
+            fn $method(&mut self, node: &$node) {
+                self.declaring(DeclarationKind::$kind, |b| node.walk(b));
+            }
+        )*
+    };
+}
+
+// Visit the target of assignment-like expressions with the given access, and
+// the remaining children as reads.
+macro_rules! assignments {
+    ($($method:ident($node:ty): $access:ident $target:ident $(, $rest:ident)*;)*) => {
+        $(
+            fn $method(&mut self, node: &$node) {
+                self.accessing(Access::$access, |b| node.$target.accept(b));
+                $(node.$rest.accept(self);)*
+            }
+        )*
+    };
+}
+
+impl<'src> Builder<'src> {
+    fn new() -> Builder<'src> {
+        let tree = ScopeTree::new();
+        Builder {
+            current: tree.root(),
+            tree,
+            declaring: None,
+            access: Access::Read,
+            strict: false,
+            block_functions: vec![],
+        }
+    }
+
+    fn finish(mut self) -> ScopeTree<'src> {
+        for &function in &self.block_functions {
+            self.tree.hoist_block_function(function);
+        }
+        self.tree.resolve();
+        self.tree
+    }
+
+    fn scoped<F: FnOnce(&mut Builder<'src>)>(&mut self, kind: ScopeKind, f: F) {
+        let parent = self.current;
+        self.current = self.tree.add_scope(kind, Some(parent));
+        f(self);
+        self.current = parent;
+    }
+
+    fn declaring<F: FnOnce(&mut Builder<'src>)>(&mut self, kind: DeclarationKind, f: F) {
+        let previous = self.declaring.replace(kind);
+        f(self);
+        self.declaring = previous;
+    }
+
+    fn strict<F: FnOnce(&mut Builder<'src>)>(&mut self, strict: bool, f: F) {
+        let previous = self.strict;
+        self.strict = previous || strict;
+        f(self);
+        self.strict = previous;
+    }
+
+    fn accessing<F: FnOnce(&mut Builder<'src>)>(&mut self, access: Access, f: F) {
+        let previous = self.access;
+        self.access = access;
+        f(self);
+        self.access = previous;
+    }
+
+    // Visit a node that is evaluated as an expression, like a default value,
+    // even though it may be nested inside of a declaration or assignment.
+    fn reading<F: FnOnce(&mut Builder<'src>)>(&mut self, f: F) {
+        let declaring = self.declaring.take();
+        self.accessing(Access::Read, f);
+        self.declaring = declaring;
+    }
+
+    fn declare(&mut self, kind: DeclarationKind, id: &general::BindingIdentifier<'src>) -> BindingId {
+        let binding = self.bind(kind, id);
+        self.tree.declarations.push(Some(binding));
+        binding
+    }
+
+    // Declare a binding without recording the identifier's position in the
+    // visit order.
+    fn bind(&mut self, kind: DeclarationKind, id: &general::BindingIdentifier<'src>) -> BindingId {
+        let mut scope = self.current;
+        let mut catch_params = vec![];
+        if kind == DeclarationKind::Var {
+            // "var" declarations are hoisted to the nearest function.
+            while let ScopeKind::Block | ScopeKind::Catch | ScopeKind::Class | ScopeKind::With =
+                self.tree.scope(scope).kind
+            {
+                if let Some(&param) = self.tree.scope(scope).names.get(&id.value) {
+                    if self.tree.binding(param).kind == DeclarationKind::CatchParam {
+                        catch_params.push(param);
+                    }
+                }
+                scope = self.tree.scope(scope).parent.unwrap();
+            }
+        }
+
+        let binding = self.tree.declare(scope, kind, id);
+        if !catch_params.is_empty() {
+            for param in catch_params {
+                self.tree.bindings[param.0].linked = true;
+            }
+            self.tree.bindings[binding.0].linked = true;
+        }
+        binding
+    }
+
+    fn function(
+        &mut self,
+        name: Option<&general::BindingIdentifier<'src>>,
+        params: &functions::FunctionParams<'src>,
+        body: &functions::FunctionBody<'src>,
+    ) {
+        let strict = has_use_strict(&body.directives);
+        self.function_scope(name, params, true, strict, |b| body.accept(b));
+    }
+
+    // Visit the parameters and body of a function in a scope of their own,
+    // with a separate scope for the body if the parameters contain
+    // expressions.
+    fn function_scope<P, F>(
+        &mut self,
+        name: Option<&general::BindingIdentifier<'src>>,
+        params: &P,
+        arguments: bool,
+        strict: bool,
+        body: F,
+    )
+    where
+        P: Visitable<'src>,
+        F: FnOnce(&mut Builder<'src>),
+    {
+        self.scoped(ScopeKind::Function, |b| {
+            let slot = b.tree.declarations.len();
+            if name.is_some() {
+                b.tree.declarations.push(None);
+            }
+
+            b.strict(strict, |b| {
+                b.declaring(DeclarationKind::Param, |b| params.accept(b));
+                if arguments {
+                    b.tree.declare_name(b.current, DeclarationKind::Arguments, &Str::borrowed("arguments"), None);
+                }
+
+                if has_expressions(params) {
+                    b.scoped(ScopeKind::FunctionBody, body);
+                    let scope = *b.tree.scope(b.current).children.last().unwrap();
+                    b.tree.tie_vars_to_params(b.current, scope);
+                } else {
+                    body(b);
+                }
+            });
+
+            // The name of a function expression is visible inside of it,
+            // unless shadowed by a parameter or declaration, so it is only
+            // bound once the rest of the function has been declared.
+            if let Some(name) = name {
+                b.tree.declarations[slot] = Some(b.bind(DeclarationKind::Function, name));
+            }
+        });
+    }
+
+    fn class(
+        &mut self,
+        name: Option<&classes::ClassName<'src>>,
+        heritage: &Option<classes::ClassHeritage<'src>>,
+        body: &classes::ClassBody<'src>,
+    ) {
+        self.scoped(ScopeKind::Class, |b| b.strict(true, |b| {
+            if let Some(name) = name {
+                b.declare(DeclarationKind::Class, &name.id);
+            }
+            heritage.accept(b);
+            body.accept(b);
+        }));
+    }
+}
+
+impl<'src> Visit<'src> for Builder<'src> {
+    fn visit_script(&mut self, node: &root::Script<'src>) {
+        self.strict(has_use_strict(&node.directives), |b| node.walk(b));
+    }
+    fn visit_module(&mut self, node: &root::Module<'src>) {
+        self.scoped(ScopeKind::Module, |b| b.strict(true, |b| node.walk(b)));
+    }
+
+    fn visit_binding_identifier(&mut self, node: &general::BindingIdentifier<'src>) {
+        match self.declaring {
+            Some(kind) => {
+                self.declare(kind, node);
+            }
+            None => self.tree.declarations.push(None),
+        }
+    }
+
+    fn visit_reference_identifier(&mut self, node: &general::ReferenceIdentifier<'src>) {
+        self.tree.references.push(Reference {
+            name: node.value.clone(),
+            scope: self.current,
+            access: self.access,
+            binding: None,
+            dynamic: false,
+            position: node.position().cloned(),
+        });
+    }
+
+    fn visit_initializer(&mut self, node: &general::Initializer<'src>) {
+        self.reading(|b| node.walk(b));
+    }
+    fn visit_computed_property_name(&mut self, node: &general::ComputedPropertyName<'src>) {
+        self.reading(|b| node.walk(b));
+    }
+    fn visit_member_expression(&mut self, node: &expression::MemberExpression<'src>) {
+        self.reading(|b| node.walk(b));
+    }
+    fn visit_function_param_decorator(&mut self, node: &functions::FunctionParamDecorator<'src>) {
+        self.reading(|b| node.walk(b));
+    }
+
+    // Functions
+
+    fn visit_function_declaration(&mut self, node: &functions::FunctionDeclaration<'src>) {
+        let binding = self.declare(DeclarationKind::Function, &node.id);
+        if !self.strict && self.tree.scope(self.current).kind == ScopeKind::Block {
+            self.block_functions.push(binding);
+        }
+        self.function(None, &node.params, &node.body);
+    }
+    fn visit_export_default_function_declaration(&mut self, node: &functions::ExportDefaultFunctionDeclaration<'src>) {
+        if let Some(ref id) = node.id {
+            self.declare(DeclarationKind::Function, id);
+        }
+        self.function(None, &node.params, &node.body);
+    }
+    fn visit_function_expression(&mut self, node: &functions::FunctionExpression<'src>) {
+        self.function(node.id.as_ref(), &node.params, &node.body);
+    }
+    fn visit_arrow_function_expression(&mut self, node: &functions::ArrowFunctionExpression<'src>) {
+        let strict = match node.body {
+            functions::ArrowFunctionBody::Block(ref body) => has_use_strict(&body.directives),
+            functions::ArrowFunctionBody::Expression(_) => false,
+        };
+        self.function_scope(None, &node.params, false, strict, |b| node.body.accept(b));
+    }
+    fn visit_object_method(&mut self, node: &objects::ObjectMethod<'src>) {
+        node.id.accept(self);
+        self.function(None, &node.params, &node.body);
+    }
+    fn visit_class_method(&mut self, node: &classes::ClassMethod<'src>) {
+        node.decorators.accept(self);
+        node.id.accept(self);
+        self.function(None, &node.params, &node.body);
+    }
+
+    // Classes
+
+    fn visit_class_declaration(&mut self, node: &classes::ClassDeclaration<'src>) {
+        node.decorators.accept(self);
+        self.declare(DeclarationKind::Class, &node.id.id);
+        self.class(None, &node.heritage, &node.body);
+    }
+    fn visit_export_default_class_declaration(&mut self, node: &classes::ExportDefaultClassDeclaration<'src>) {
+        node.decorators.accept(self);
+        if let Some(ref id) = node.id {
+            self.declare(DeclarationKind::Class, &id.id);
+        }
+        self.class(None, &node.heritage, &node.body);
+    }
+    fn visit_class_expression(&mut self, node: &classes::ClassExpression<'src>) {
+        node.decorators.accept(self);
+        self.class(node.id.as_ref(), &node.heritage, &node.body);
+    }
+
+    // Declarations
+
+    declarations! {
+        visit_variable_declarator(statement::VariableDeclarator<'src>): Var,
+        visit_let_declarator(statement::LetDeclarator<'src>): Let,
+        visit_const_declarator(statement::ConstDeclarator<'src>): Const,
+        visit_for_in_var_pattern(statement::ForInVarPattern<'src>): Var,
+        visit_for_var_pattern(statement::ForVarPattern<'src>): Var,
+        visit_for_let_pattern(statement::ForLetPattern<'src>): Let,
+        visit_for_const_pattern(statement::ForConstPattern<'src>): Const,
+        visit_catch_param(statement::CatchParam<'src>): CatchParam,
+        visit_import_named_declaration(modules::ImportNamedDeclaration<'src>): Import,
+        visit_import_named_and_namespace_declaration(modules::ImportNamedAndNamespaceDeclaration<'src>): Import,
+        visit_import_namespace_declaration(modules::ImportNamespaceDeclaration<'src>): Import,
+        visit_import_named_and_specifiers_declaration(modules::ImportNamedAndSpecifiersDeclaration<'src>): Import,
+        visit_import_specifiers_declaration(modules::ImportSpecifiersDeclaration<'src>): Import,
+    }
+
+    // Assignments
+
+    assignments! {
+        visit_assignment_expression(expression::AssignmentExpression<'src>): Write left, right;
+        visit_add_assign_expression(expression::AddAssignExpression<'src>): ReadWrite left, right;
+        visit_subtract_assign_expression(expression::SubtractAssignExpression<'src>): ReadWrite left, right;
+        visit_left_shift_assign_expression(expression::LeftShiftAssignExpression<'src>): ReadWrite left, right;
+        visit_right_shift_assign_expression(expression::RightShiftAssignExpression<'src>): ReadWrite left, right;
+        visit_right_shift_signed_assign_expression(expression::RightShiftSignedAssignExpression<'src>): ReadWrite left, right;
+        visit_divide_assign_expression(expression::DivideAssignExpression<'src>): ReadWrite left, right;
+        visit_multiply_assign_expression(expression::MultiplyAssignExpression<'src>): ReadWrite left, right;
+        visit_modulus_assign_expression(expression::ModulusAssignExpression<'src>): ReadWrite left, right;
+        visit_bit_and_assign_expression(expression::BitAndAssignExpression<'src>): ReadWrite left, right;
+        visit_bit_or_assign_expression(expression::BitOrAssignExpression<'src>): ReadWrite left, right;
+        visit_bit_xor_assign_expression(expression::BitXorAssignExpression<'src>): ReadWrite left, right;
+        visit_power_assign_expression(expression::PowerAssignExpression<'src>): ReadWrite left, right;
+        visit_pre_increment_expression(expression::PreIncrementExpression<'src>): ReadWrite value;
+        visit_pre_decrement_expression(expression::PreDecrementExpression<'src>): ReadWrite value;
+        visit_post_increment_expression(expression::PostIncrementExpression<'src>): ReadWrite value;
+        visit_post_decrement_expression(expression::PostDecrementExpression<'src>): ReadWrite value;
+    }
+
+    // Scopes
+
+    fn visit_block_statement(&mut self, node: &statement::BlockStatement<'src>) {
+        self.scoped(ScopeKind::Block, |b| node.walk(b));
+    }
+    fn visit_for_statement(&mut self, node: &statement::ForStatement<'src>) {
+        match node.init {
+            Some(statement::ForInit::Let(_)) | Some(statement::ForInit::Const(_)) => {
+                self.scoped(ScopeKind::Block, |b| node.walk(b))
+            }
+            _ => node.walk(self),
+        }
+    }
+    fn visit_for_in_statement(&mut self, node: &statement::ForInStatement<'src>) {
+        let visit = |b: &mut Builder<'src>| {
+            match node.left {
+                statement::ForInInit::Complex(ref left) => b.accessing(Access::Write, |b| left.accept(b)),
+                ref left => left.accept(b),
+            }
+            node.right.accept(b);
+            node.body.accept(b);
+        };
+        match node.left {
+            statement::ForInInit::Let(_) | statement::ForInInit::Const(_) => self.scoped(ScopeKind::Block, visit),
+            _ => visit(self),
+        }
+    }
+    fn visit_for_of_statement(&mut self, node: &statement::ForOfStatement<'src>) {
+        self.for_of(&node.left, &node.right, &node.body);
+    }
+    fn visit_for_await_statement(&mut self, node: &statement::ForAwaitStatement<'src>) {
+        self.for_of(&node.left, &node.right, &node.body);
+    }
+    fn visit_switch_statement(&mut self, node: &statement::SwitchStatement<'src>) {
+        node.discriminant.accept(self);
+        self.scoped(ScopeKind::Block, |b| node.cases.accept(b));
+    }
+    fn visit_with_statement(&mut self, node: &statement::WithStatement<'src>) {
+        node.object.accept(self);
+        self.scoped(ScopeKind::With, |b| node.body.accept(b));
+    }
+    fn visit_catch_clause(&mut self, node: &statement::CatchClause<'src>) {
+        self.scoped(ScopeKind::Catch, |b| node.walk(b));
+    }
+}
+
+impl<'src> Builder<'src> {
+    fn for_of(
+        &mut self,
+        left: &statement::ForOfInit<'src>,
+        right: &::ast::alias::Expression<'src>,
+        body: &::ast::alias::Statement<'src>,
+    ) {
+        let visit = |b: &mut Builder<'src>| {
+            match *left {
+                statement::ForOfInit::Complex(ref left) => b.accessing(Access::Write, |b| left.accept(b)),
+                ref left => left.accept(b),
+            }
+            right.accept(b);
+            body.accept(b);
+        };
+        match *left {
+            statement::ForOfInit::Let(_) | statement::ForOfInit::Const(_) => self.scoped(ScopeKind::Block, visit),
+            _ => visit(self),
+        }
+    }
+}
+
+fn has_use_strict(directives: &[functions::Directive<'_>]) -> bool {
+    directives.iter().any(|directive| directive.value.value == "use strict")
+}
+
+// Whether parameters contain expressions, like default values or computed
+// keys in patterns.
+fn has_expressions<'src, P: Visitable<'src>>(params: &P) -> bool {
+    struct Finder(bool);
+    impl<'src> Visit<'src> for Finder {
+        fn visit_initializer(&mut self, _: &general::Initializer<'src>) {
+            self.0 = true;
+        }
+        fn visit_computed_property_name(&mut self, _: &general::ComputedPropertyName<'src>) {
+            self.0 = true;
+        }
+    }
+
+    let mut finder = Finder(false);
+    params.accept(&mut finder);
+    finder.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
+
+    fn script(code: &str) -> ScopeTree<'_> {
+        analyze_script(&Parser::new(code.into_tokenizer()).parse_script().unwrap())
+    }
+    fn module(code: &str) -> ScopeTree<'_> {
+        analyze_module(&Parser::new(code.into_tokenizer()).parse_module().unwrap())
+    }
+
+    // The names declared in each scope, in creation order.
+    fn declarations<'a>(tree: &'a ScopeTree<'_>) -> Vec<(ScopeKind, Vec<&'a str>)> {
+        tree.scopes()
+            .map(|(_, scope)| {
+                let names = scope.bindings.iter().map(|&id| tree.binding(id).name.as_str()).collect();
+                (scope.kind, names)
+            })
+            .collect()
+    }
+
+    // Each reference with the kind of scope its binding is in.
+    fn resolutions<'a>(tree: &'a ScopeTree<'_>) -> Vec<(&'a str, Option<ScopeKind>)> {
+        tree.references()
+            .map(|(_, reference)| {
+                let scope = reference.binding.map(|id| tree.scope(tree.binding(id).scope).kind);
+                (reference.name.as_str(), scope)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_builds_scopes() {
+        let tree = script("
+            var a;
+            function f(b, {c = a}) { let d; { const e = d; } }
+            try {} catch (g) {}
+            for (let h of i) {}
+            with (j) { k; }
+        ");
+
+        assert_eq!(
+            declarations(&tree),
+            vec![
+                (ScopeKind::Global, vec!["a", "f"]),
+                (ScopeKind::Function, vec!["b", "c", "arguments"]),
+                (ScopeKind::FunctionBody, vec!["d"]),
+                (ScopeKind::Block, vec!["e"]),
+                (ScopeKind::Block, vec![]),
+                (ScopeKind::Catch, vec!["g"]),
+                (ScopeKind::Block, vec![]),
+                (ScopeKind::Block, vec!["h"]),
+                (ScopeKind::Block, vec![]),
+                (ScopeKind::With, vec![]),
+                (ScopeKind::Block, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_hoists_declarations() {
+        let tree = script("
+            'use strict';
+            f(a, b);
+            { var a = 1; let b = 2; function f() {} }
+        ");
+
+        assert_eq!(declarations(&tree)[0], (ScopeKind::Global, vec!["a"]));
+        assert_eq!(
+            resolutions(&tree),
+            vec![("f", None), ("a", Some(ScopeKind::Global)), ("b", None)]
+        );
+        assert_eq!(tree.free_variables(), vec!["f", "b"]);
+    }
+
+    #[test]
+    fn it_hoists_block_functions_in_sloppy_mode() {
+        let tree = script("
+            f(g, h);
+            { function f() {} }
+            { let g; { function g() {} } }
+            function h(i) { f(i); { function i() {} } }
+            (function () { 'use strict'; { function j() {} } j(); });
+        ");
+
+        assert_eq!(declarations(&tree)[0], (ScopeKind::Global, vec!["h", "f"]));
+        assert_eq!(
+            resolutions(&tree),
+            vec![
+                ("f", Some(ScopeKind::Global)),
+                ("g", None),
+                ("h", Some(ScopeKind::Global)),
+                ("f", Some(ScopeKind::Global)),
+                ("i", Some(ScopeKind::Function)),
+                ("j", None),
+            ]
+        );
+        let hoisted: Vec<&str> = tree.bindings().filter(|&(_, b)| b.linked).map(|(_, b)| b.name.as_str()).collect();
+        assert_eq!(hoisted, vec!["f", "f"]);
+    }
+
+    #[test]
+    fn it_resolves_shadowed_names() {
+        let tree = script("
+            let a = 1;
+            (function a(b) { return a + b; });
+            (function c() { var c; return c; });
+            class D extends D {}
+            (class E { m() { return E; } });
+        ");
+
+        assert_eq!(
+            resolutions(&tree),
+            vec![
+                ("a", Some(ScopeKind::Function)),
+                ("b", Some(ScopeKind::Function)),
+                ("c", Some(ScopeKind::Function)),
+                ("D", Some(ScopeKind::Global)),
+                ("E", Some(ScopeKind::Class)),
+            ]
+        );
+
+        let a = tree.lookup(tree.root(), "a").unwrap();
+        assert_eq!(tree.binding(a).kind, DeclarationKind::Let);
+        assert!(tree.binding(a).references.is_empty());
+    }
+
+    #[test]
+    fn it_ties_catch_params_to_vars() {
+        let tree = script("
+            try {} catch (e) { var e = 1; var f; }
+            try {} catch ({ g }) { { var h; } }
+        ");
+
+        assert_eq!(declarations(&tree)[0], (ScopeKind::Global, vec!["e", "f", "h"]));
+        let tied: Vec<(&str, DeclarationKind)> = tree.bindings()
+            .filter(|&(_, b)| b.linked)
+            .map(|(_, b)| (b.name.as_str(), b.kind))
+            .collect();
+        assert_eq!(tied, vec![("e", DeclarationKind::CatchParam), ("e", DeclarationKind::Var)]);
+    }
+
+    #[test]
+    fn it_keeps_body_declarations_out_of_parameter_expressions() {
+        let tree = script("
+            function f(a = b, c = () => d) { var b, d, a; }
+            (e = f) => { var f; };
+            function g(h) { var i; }
+        ");
+
+        assert_eq!(
+            declarations(&tree),
+            vec![
+                (ScopeKind::Global, vec!["f", "g"]),
+                (ScopeKind::Function, vec!["a", "c", "arguments"]),
+                (ScopeKind::Function, vec![]),
+                (ScopeKind::FunctionBody, vec!["b", "d", "a"]),
+                (ScopeKind::Function, vec!["e"]),
+                (ScopeKind::FunctionBody, vec!["f"]),
+                (ScopeKind::Function, vec!["h", "arguments", "i"]),
+            ]
+        );
+        assert_eq!(
+            resolutions(&tree),
+            vec![("b", None), ("d", None), ("f", Some(ScopeKind::Global))]
+        );
+        let linked: Vec<(&str, DeclarationKind)> = tree.bindings()
+            .filter(|&(_, b)| b.linked)
+            .map(|(_, b)| (b.name.as_str(), b.kind))
+            .collect();
+        assert_eq!(linked, vec![("a", DeclarationKind::Param), ("a", DeclarationKind::Var)]);
+    }
+
+    #[test]
+    fn it_binds_arguments() {
+        let tree = script("
+            function f() { return arguments; }
+            function g(arguments) { return arguments; }
+            function h() { return () => arguments; }
+            arguments;
+        ");
+
+        let bindings: Vec<_> = tree.references()
+            .map(|(_, r)| r.binding.map(|id| tree.binding(id).kind))
+            .collect();
+        assert_eq!(
+            bindings,
+            vec![Some(DeclarationKind::Arguments), Some(DeclarationKind::Param), Some(DeclarationKind::Arguments), None]
+        );
+        assert_eq!(tree.free_variables(), vec!["arguments"]);
+    }
+
+    #[test]
+    fn it_tracks_writes() {
+        let tree = script("
+            var a, b, c;
+            a = b;
+            c += 1;
+            a++;
+            [a, b = c] = a.b;
+            ({a, b: c[d]} = e);
+            for (a in b);
+        ");
+
+        let accesses: Vec<_> = tree.references().map(|(_, r)| (r.name.as_str(), r.access)).collect();
+        assert_eq!(
+            accesses,
+            vec![
+                ("a", Access::Write),
+                ("b", Access::Read),
+                ("c", Access::ReadWrite),
+                ("a", Access::ReadWrite),
+                ("a", Access::Write),
+                ("b", Access::Write),
+                ("c", Access::Read),
+                ("a", Access::Read),
+                ("a", Access::Write),
+                ("c", Access::Read),
+                ("d", Access::Read),
+                ("e", Access::Read),
+                ("a", Access::Write),
+                ("b", Access::Read),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_records_declarations_in_visit_order() {
+        let tree = script("
+            var a = function b(c) { return b; };
+            function d() { try {} catch (e) {} }
+            (function (f) {});
+        ");
+
+        let names: Vec<_> = tree.declarations()
+            .iter()
+            .map(|id| id.map(|id| tree.binding(id).name.as_str()))
+            .collect();
+        assert_eq!(names, vec![Some("a"), Some("b"), Some("c"), Some("d"), Some("e"), Some("f")]);
+    }
+
+    #[test]
+    fn it_marks_dynamic_references() {
+        let tree = script("var a; with (b) { a; c; }");
+
+        let dynamic: Vec<_> = tree.references().map(|(_, r)| (r.name.as_str(), r.dynamic)).collect();
+        assert_eq!(dynamic, vec![("b", false), ("a", true), ("c", true)]);
+        assert_eq!(tree.free_variables(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn it_binds_imports() {
+        let tree = module("
+            import a, {b as c} from 'x';
+            import * as d from 'y';
+            export {a as e};
+            var f = c(d);
+        ");
+
+        assert_eq!(
+            declarations(&tree),
+            vec![
+                (ScopeKind::Global, vec![]),
+                (ScopeKind::Module, vec!["a", "c", "d", "f"]),
+            ]
+        );
+        let scope = tree.scope(tree.root()).children[0];
+        assert_eq!(tree.lookup(tree.root(), "a"), None);
+        assert_eq!(tree.binding(tree.lookup(scope, "a").unwrap()).kind, DeclarationKind::Import);
+        assert!(tree.free_variables().is_empty());
+    }
+}
@@ -0,0 +1,387 @@
+//! Parsing into a bump arena.
+//!
+//! An `Arena<T>` owns every value allocated in it and hands out plain
+//! references that live as long as the arena, so parsed trees can be shared
+//! and linked to each other without reference counting. Values are stored in
+//! a few chunks that are never moved or grown in place, and are all dropped
+//! together along with the arena.
+//!
+//! ```ignore
+//! let arena = Arena::new();
+//! let script: &root::Script = arena.parse_script("var a = 1;", &ParserOptions::script())?;
+//! ```
+//!
+//! Nodes hold their children and positions in `NodeBox`es. While parsing
+//! into an arena, those are allocated from a bump allocator that the arena
+//! owns, rather than one at a time on the heap, and their memory is freed all
+//! at once along with the arena. The vectors of lists like statements and
+//! arguments are still allocated on the heap.
+
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+use std::{cmp, fmt, mem};
+
+use bumpalo::Bump;
+use failure::Error;
+
+use ast::root;
+use parser::{self, ParserOptions};
+
+const MIN_CHUNK_BYTES: usize = 4 * 1024;
+const MAX_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// A bump allocator for values of a single type, and for the nodes of the
+/// trees parsed into it.
+pub struct Arena<T> {
+    // The values are dropped first, since the boxes in them point into the
+    // nodes' memory.
+    chunks: RefCell<Chunks<T>>,
+    nodes: Bump,
+}
+
+struct Chunks<T> {
+    // The chunk being allocated from. It never grows past its capacity, so
+    // references into it stay valid while it is filled up.
+    current: Vec<T>,
+    rest: Vec<Vec<T>>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Arena<T> {
+        Arena {
+            chunks: RefCell::new(Chunks {
+                current: Vec::with_capacity(chunk_len::<T>(0)),
+                rest: vec![],
+            }),
+            nodes: Bump::new(),
+        }
+    }
+
+    /// Move a value into the arena.
+    pub fn alloc(&self, value: T) -> &T {
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks.current.len() == chunks.current.capacity() {
+            let len = chunk_len::<T>(chunks.current.capacity());
+            let full = mem::replace(&mut chunks.current, Vec::with_capacity(len));
+            chunks.rest.push(full);
+        }
+
+        let index = chunks.current.len();
+        chunks.current.push(value);
+
+        // The value is never moved, since the chunk is never reallocated, and
+        // is only dropped along with the arena.
+        unsafe { &*chunks.current.as_ptr().add(index) }
+    }
+
+    /// The number of values in the arena.
+    pub fn len(&self) -> usize {
+        let chunks = self.chunks.borrow();
+        chunks.current.len() + chunks.rest.iter().map(Vec::len).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of chunks the arena has allocated.
+    pub fn chunks(&self) -> usize {
+        self.chunks.borrow().rest.len() + 1
+    }
+
+    /// The number of bytes allocated for the nodes of parsed trees.
+    pub fn node_bytes(&self) -> usize {
+        self.nodes.allocated_bytes()
+    }
+
+    // Run a function with the `NodeBox`es that it creates allocated in the
+    // arena.
+    fn allocating<R, F: FnOnce() -> R>(&self, f: F) -> R {
+        let previous = NODES.with(|nodes| nodes.replace(Some(NonNull::from(&self.nodes))));
+        let _restore = Restore(previous);
+        f()
+    }
+}
+
+impl<'src> Arena<root::Script<'src>> {
+    /// Parse a script into the arena, whatever the source type in the
+    /// options.
+    pub fn parse_script(&self, code: &'src str, options: &ParserOptions) -> Result<&root::Script<'src>, Error> {
+        let script = self.allocating(|| parser::parse_script(code, options))?;
+        Ok(self.alloc(script))
+    }
+}
+
+impl<'src> Arena<root::Module<'src>> {
+    /// Parse a module into the arena, whatever the source type in the
+    /// options.
+    pub fn parse_module(&self, code: &'src str, options: &ParserOptions) -> Result<&root::Module<'src>, Error> {
+        let module = self.allocating(|| parser::parse_module(code, options))?;
+        Ok(self.alloc(module))
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Arena<T> {
+        Arena::new()
+    }
+}
+
+impl<T> fmt::Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("len", &self.len())
+            .field("chunks", &self.chunks())
+            .field("node_bytes", &self.node_bytes())
+            .finish()
+    }
+}
+
+thread_local! {
+    // The allocator for the nodes of the tree being parsed into an arena, if
+    // any. Trees only escape an arena by reference, so it outlives them.
+    static NODES: Cell<Option<NonNull<Bump>>> = const { Cell::new(None) };
+}
+
+// Puts back the allocator that was in use before parsing into an arena, even
+// if parsing panics.
+struct Restore(Option<NonNull<Bump>>);
+impl Drop for Restore {
+    fn drop(&mut self) {
+        NODES.with(|nodes| nodes.set(self.0));
+    }
+}
+
+/// An owned pointer to part of a tree, like a `Box`, allocated in the arena
+/// that the tree is being parsed into if there is one, and on the heap
+/// otherwise.
+pub struct NodeBox<T> {
+    // The low bit is set for values in an arena, whose memory belongs to the
+    // arena, so they are dropped in place but not freed.
+    ptr: NonNull<T>,
+    marker: PhantomData<T>,
+}
+
+// Like a `Box`, a `NodeBox` is only as thread-safe as its value. Values in an
+// arena are only ever reached through references to the arena's trees.
+unsafe impl<T: Send> Send for NodeBox<T> {}
+unsafe impl<T: Sync> Sync for NodeBox<T> {}
+
+impl<T> NodeBox<T> {
+    pub fn new(value: T) -> NodeBox<T> {
+        // The pointer's low bit needs to be free for the tag.
+        assert!(mem::align_of::<T>() > 1, "NodeBox values must be aligned to more than one byte");
+
+        let ptr = match NODES.with(Cell::get) {
+            Some(nodes) => {
+                let value: *mut T = unsafe { nodes.as_ref() }.alloc(value);
+                (value as usize | 1) as *mut T
+            }
+            None => Box::into_raw(Box::new(value)),
+        };
+        NodeBox {
+            ptr: unsafe { NonNull::new_unchecked(ptr) },
+            marker: PhantomData,
+        }
+    }
+
+    /// Move the value out of the box.
+    pub fn into_inner(b: NodeBox<T>) -> T {
+        let in_arena = b.is_in_arena();
+        let ptr = b.as_ptr();
+        mem::forget(b);
+        if in_arena {
+            unsafe { ptr::read(ptr) }
+        } else {
+            *unsafe { Box::from_raw(ptr) }
+        }
+    }
+
+    /// Whether the value was allocated in an arena rather than on the heap.
+    pub fn is_in_arena(&self) -> bool {
+        self.ptr.as_ptr() as usize & 1 == 1
+    }
+
+    fn as_ptr(&self) -> *mut T {
+        (self.ptr.as_ptr() as usize & !1) as *mut T
+    }
+}
+
+impl<T> Drop for NodeBox<T> {
+    fn drop(&mut self) {
+        if self.is_in_arena() {
+            unsafe { ptr::drop_in_place(self.as_ptr()) }
+        } else {
+            drop(unsafe { Box::from_raw(self.as_ptr()) })
+        }
+    }
+}
+
+impl<T> Deref for NodeBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.as_ptr() }
+    }
+}
+impl<T> DerefMut for NodeBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.as_ptr() }
+    }
+}
+impl<T> AsRef<T> for NodeBox<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+impl<T> From<T> for NodeBox<T> {
+    fn from(value: T) -> NodeBox<T> {
+        NodeBox::new(value)
+    }
+}
+impl<T: Default> Default for NodeBox<T> {
+    fn default() -> NodeBox<T> {
+        NodeBox::new(T::default())
+    }
+}
+impl<T: Clone> Clone for NodeBox<T> {
+    fn clone(&self) -> NodeBox<T> {
+        NodeBox::new((**self).clone())
+    }
+}
+impl<T: PartialEq> PartialEq for NodeBox<T> {
+    fn eq(&self, other: &NodeBox<T>) -> bool {
+        **self == **other
+    }
+}
+impl<T: Eq> Eq for NodeBox<T> {}
+impl<T: fmt::Debug> fmt::Debug for NodeBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+// Each chunk holds twice as many values as the one before it, within bounds
+// on its size in bytes.
+fn chunk_len<T>(previous: usize) -> usize {
+    let size = cmp::max(mem::size_of::<T>(), 1);
+    let min = cmp::max(MIN_CHUNK_BYTES / size, 1);
+    let max = cmp::max(MAX_CHUNK_BYTES / size, 1);
+    cmp::max(cmp::min(previous * 2, max), min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use ast::{self, alias};
+
+    #[test]
+    fn it_parses_into_the_arena() {
+        let arena = Arena::new();
+        let code = "var a = [1, 2, 3].map(function (x) { return x * 2; });";
+        let script = arena.parse_script(code, &ParserOptions::script()).unwrap();
+
+        assert_eq!(arena.len(), 1);
+        assert!(arena.node_bytes() > 0);
+        assert_eq!(
+            ast::format(script, &Default::default()).unwrap(),
+            "var a=[1,2,3].map(function(x){return x*2;});"
+        );
+    }
+
+    #[test]
+    fn it_allocates_nodes_in_the_arena() {
+        let arena = Arena::new();
+        let code = "var a = b(1);";
+        let script = arena.parse_script(code, &ParserOptions::script()).unwrap();
+
+        assert!(script.position.as_ref().unwrap().is_in_arena());
+        match script.body[0] {
+            alias::StatementItem::Variable(ref n) => assert!(n.is_in_arena()),
+            ref item => panic!("{:?}", item),
+        }
+
+        // Boxes created outside of the arena, like in trees parsed on their
+        // own, are on the heap.
+        let script = parser::parse_script(code, &ParserOptions::script()).unwrap();
+        assert!(!script.position.as_ref().unwrap().is_in_arena());
+        assert!(!NodeBox::new(1).is_in_arena());
+    }
+
+    #[test]
+    fn it_drops_boxes_in_the_arena() {
+        let counter = Rc::new(());
+        {
+            let arena: Arena<()> = Arena::new();
+            let boxes: Vec<_> = arena.allocating(|| (0..1000).map(|_| NodeBox::new(counter.clone())).collect());
+            assert!(boxes.iter().all(NodeBox::is_in_arena));
+            assert_eq!(Rc::strong_count(&counter), 1001);
+
+            // Moving a value out leaves its memory to the arena.
+            let values: Vec<_> = boxes.into_iter().take(10).map(NodeBox::into_inner).collect();
+            assert_eq!(Rc::strong_count(&counter), 11);
+            drop(values);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn it_holds_several_trees() {
+        let arena = Arena::new();
+        let first = arena.parse_module("import a from 'a';", &ParserOptions::module()).unwrap();
+        let second = arena.parse_module("export default b;", &ParserOptions::script()).unwrap();
+
+        assert_eq!(arena.len(), 2);
+        assert_eq!(first.body.len(), 1);
+        assert_eq!(second.body.len(), 1);
+    }
+
+    #[test]
+    fn it_keeps_references_when_growing() {
+        let arena = Arena::new();
+        let values: Vec<&usize> = (0..100000).map(|i| arena.alloc(i)).collect();
+
+        assert!(arena.chunks() > 1);
+        assert_eq!(arena.len(), 100000);
+        assert!(values.iter().enumerate().all(|(i, value)| **value == i));
+    }
+
+    #[test]
+    fn it_drops_values_with_the_arena() {
+        let counter = Rc::new(());
+        {
+            let arena = Arena::new();
+            for _ in 0..10000 {
+                arena.alloc(counter.clone());
+            }
+            assert_eq!(Rc::strong_count(&counter), 10001);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn it_returns_errors() {
+        let arena = Arena::new();
+        let error = arena.parse_script("var 1;", &ParserOptions::script()).unwrap_err();
+
+        assert_eq!(error.to_string(), "UnexpectedToken (1:4)");
+        assert!(arena.is_empty());
+    }
+
+    #[test]
+    fn it_parses_with_options() {
+        let arena = Arena::new();
+        let options = ParserOptions {
+            allow_return_outside_function: true,
+            preserve_parens: false,
+            ..ParserOptions::script()
+        };
+        let script = arena.parse_script("return (a);", &options).unwrap();
+
+        assert_eq!(ast::format(script, &Default::default()).unwrap(), "return a;");
+        assert!(arena.parse_script("return (a);", &ParserOptions::script()).is_err());
+    }
+}
@@ -7,86 +7,96 @@ use ast::objects;
 use ast::classes;
 use ast::functions;
 use ast::general;
+use ast::typescript;
+use ast::flow;
+use ast::NodeBox;
 
 
 node_enum!(@node_display pub enum Function {
     // TODO: Should the method types be in here? What is the goal of this node type?
-    ClassMethod(classes::ClassMethod),
-    ObjectMethod(objects::ObjectMethod),
-    DefaultDeclaration(functions::ExportDefaultFunctionDeclaration),
-    Declaration(functions::FunctionDeclaration),
-    Expression(functions::FunctionExpression),
-    Arrow(functions::ArrowFunctionExpression),
+    ClassMethod(classes::ClassMethod<'src>),
+    ObjectMethod(objects::ObjectMethod<'src>),
+    DefaultDeclaration(functions::ExportDefaultFunctionDeclaration<'src>),
+    Declaration(functions::FunctionDeclaration<'src>),
+    Expression(functions::FunctionExpression<'src>),
+    Arrow(functions::ArrowFunctionExpression<'src>),
 });
 
 
 node_enum!(@node_display pub enum Method {
-    ClassMethod(classes::ClassMethod),
-    ObjectMethod(objects::ObjectMethod),
+    ClassMethod(classes::ClassMethod<'src>),
+    ObjectMethod(objects::ObjectMethod<'src>),
 });
 
 
-node_enum!(@node_display pub enum ModuleStatementItem {
+node_enum!(@boxed @node_display @list pub enum ModuleStatementItem {
     // Statements
-    Block(statement::BlockStatement),
-    Variable(statement::VariableStatement),
-    Empty(statement::EmptyStatement),
-    Expression(statement::ExpressionStatement),
-    If(statement::IfStatement),
-    For(statement::ForStatement),
-    ForIn(statement::ForInStatement),
-    ForOf(statement::ForOfStatement),
-    ForAwait(statement::ForAwaitStatement),
-    While(statement::WhileStatement),
-    DoWhile(statement::DoWhileStatement),
-    Switch(statement::SwitchStatement),
-    Continue(statement::ContinueStatement),
-    Break(statement::BreakStatement),
-    Return(statement::ReturnStatement),
-    With(statement::WithStatement),
-    Labelled(statement::LabelledStatement),
-    Throw(statement::ThrowStatement),
-    TryCatch(statement::TryCatchStatement),
-    TryCatchFinally(statement::TryCatchFinallyStatement),
-    TryFinally(statement::TryFinallyStatement),
-    Debugger(statement::DebuggerStatement),
+    Block(statement::BlockStatement<'src>),
+    Variable(statement::VariableStatement<'src>),
+    Empty(statement::EmptyStatement<'src>),
+    Expression(statement::ExpressionStatement<'src>),
+    If(statement::IfStatement<'src>),
+    IfElse(statement::IfElseStatement<'src>),
+    For(statement::ForStatement<'src>),
+    ForIn(statement::ForInStatement<'src>),
+    ForOf(statement::ForOfStatement<'src>),
+    ForAwait(statement::ForAwaitStatement<'src>),
+    While(statement::WhileStatement<'src>),
+    DoWhile(statement::DoWhileStatement<'src>),
+    Switch(statement::SwitchStatement<'src>),
+    Continue(statement::ContinueStatement<'src>),
+    Break(statement::BreakStatement<'src>),
+    Return(statement::ReturnStatement<'src>),
+    With(statement::WithStatement<'src>),
+    Labelled(statement::LabelledStatement<'src>),
+    Throw(statement::ThrowStatement<'src>),
+    TryCatch(statement::TryCatchStatement<'src>),
+    TryCatchFinally(statement::TryCatchFinallyStatement<'src>),
+    TryFinally(statement::TryFinallyStatement<'src>),
+    Debugger(statement::DebuggerStatement<'src>),
 
     // Declarations
-    Function(functions::FunctionDeclaration),
-    Class(classes::ClassDeclaration),
-    Let(statement::LetDeclaration),
-    Const(statement::ConstDeclaration),
+    Function(functions::FunctionDeclaration<'src>),
+    Class(classes::ClassDeclaration<'src>),
+    Let(statement::LetDeclaration<'src>),
+    Const(statement::ConstDeclaration<'src>),
+    TypeScript(typescript::Declaration<'src>), // experimental
 
     // ExportDeclaration
-    ExportDefaultClass(classes::ExportDefaultClassDeclaration),
-    ExportDefaultFunction(functions::ExportDefaultFunctionDeclaration),
-    ExportDefaultExpression(modules::ExportDefaultExpression),
-    ExportClass(modules::ExportClassDeclaration),
-    ExportFunction(modules::ExportFunctionDeclaration),
-    ExportVariable(modules::ExportVarStatement),
-    ExportLet(modules::ExportLetDeclaration),
-    ExportConst(modules::ExportConstDeclaration),
-    ExportLocalBindings(modules::ExportLocalBindings),
-    ExportSourceSpecifiers(modules::ExportSourceSpecifiers),
-    ExportAll(modules::ExportAllSpecifiers),
-    ExportNamed(modules::ExportNamedSpecifier),
-    ExportNamedAndNamespace(modules::ExportNamedAndNamespace),
-    ExportNamespace(modules::ExportNamespace),
-    ExportNamedAndSpecifiers(modules::ExportNamedAndSpecifiers),
+    ExportDefaultClass(classes::ExportDefaultClassDeclaration<'src>),
+    ExportDefaultFunction(functions::ExportDefaultFunctionDeclaration<'src>),
+    ExportDefaultExpression(modules::ExportDefaultExpression<'src>),
+    ExportClass(modules::ExportClassDeclaration<'src>),
+    ExportFunction(modules::ExportFunctionDeclaration<'src>),
+    ExportVariable(modules::ExportVarStatement<'src>),
+    ExportLet(modules::ExportLetDeclaration<'src>),
+    ExportConst(modules::ExportConstDeclaration<'src>),
+    ExportLocalBindings(modules::ExportLocalBindings<'src>),
+    ExportSourceSpecifiers(modules::ExportSourceSpecifiers<'src>),
+    ExportAll(modules::ExportAllSpecifiers<'src>),
+    ExportNamed(modules::ExportNamedSpecifier<'src>),
+    ExportNamedAndNamespace(modules::ExportNamedAndNamespace<'src>),
+    ExportNamespace(modules::ExportNamespace<'src>),
+    ExportNamedAndSpecifiers(modules::ExportNamedAndSpecifiers<'src>),
+    ExportTypeScript(typescript::ExportDeclaration<'src>), // experimental
+    ExportAssignment(typescript::ExportAssignment<'src>), // experimental
+    ExportTypes(typescript::ExportTypeSpecifiers<'src>), // experimental
 
     // ImportDeclaration
-    ImportNamed(modules::ImportNamedDeclaration),
-    ImportNamedAndNamespace(modules::ImportNamedAndNamespaceDeclaration),
-    ImportNamespace(modules::ImportNamespaceDeclaration),
-    ImportNamedAndSpecifiers(modules::ImportNamedAndSpecifiersDeclaration),
-    ImportSpecifiers(modules::ImportSpecifiersDeclaration),
+    ImportSource(modules::ImportSourceDeclaration<'src>),
+    ImportNamed(modules::ImportNamedDeclaration<'src>),
+    ImportNamedAndNamespace(modules::ImportNamedAndNamespaceDeclaration<'src>),
+    ImportNamespace(modules::ImportNamespaceDeclaration<'src>),
+    ImportNamedAndSpecifiers(modules::ImportNamedAndSpecifiersDeclaration<'src>),
+    ImportSpecifiers(modules::ImportSpecifiersDeclaration<'src>),
+    ImportType(flow::ImportTypeDeclaration<'src>), // experimental
 });
-impl<T: Into<Expression>> From<T> for ModuleStatementItem {
-    fn from(v: T) -> ModuleStatementItem {
-        ModuleStatementItem::Expression(statement::ExpressionStatement::from(v))
+impl<'src, T: Into<Expression<'src>>> From<T> for ModuleStatementItem<'src> {
+    fn from(v: T) -> ModuleStatementItem<'src> {
+        ModuleStatementItem::Expression(NodeBox::new(statement::ExpressionStatement::new(v)))
     }
 }
-impl From<StatementItem> for ModuleStatementItem {
+impl<'src> From<StatementItem<'src>> for ModuleStatementItem<'src> {
     fn from(v: StatementItem) -> ModuleStatementItem {
         match v {
             StatementItem::Block(n) => n.into(),
@@ -94,6 +104,7 @@ impl From<StatementItem> for ModuleStatementItem {
             StatementItem::Empty(n) => n.into(),
             StatementItem::Expression(n) => n.into(),
             StatementItem::If(n) => n.into(),
+            StatementItem::IfElse(n) => n.into(),
             StatementItem::For(n) => n.into(),
             StatementItem::ForIn(n) => n.into(),
             StatementItem::ForOf(n) => n.into(),
@@ -115,43 +126,46 @@ impl From<StatementItem> for ModuleStatementItem {
             StatementItem::Class(n) => n.into(),
             StatementItem::Let(n) => n.into(),
             StatementItem::Const(n) => n.into(),
+            StatementItem::TypeScript(n) => n.into(),
         }
     }
 }
 
 
-node_enum!(@node_display pub enum StatementItem {
+node_enum!(@boxed @node_display @list pub enum StatementItem {
     // Statements
-    Block(statement::BlockStatement),
-    Variable(statement::VariableStatement),
-    Empty(statement::EmptyStatement),
-    Expression(statement::ExpressionStatement),
-    If(statement::IfStatement),
-    For(statement::ForStatement),
-    ForIn(statement::ForInStatement),
-    ForOf(statement::ForOfStatement),
-    ForAwait(statement::ForAwaitStatement),
-    While(statement::WhileStatement),
-    DoWhile(statement::DoWhileStatement),
-    Switch(statement::SwitchStatement),
-    Continue(statement::ContinueStatement),
-    Break(statement::BreakStatement),
-    Return(statement::ReturnStatement),
-    With(statement::WithStatement),
-    Labelled(statement::LabelledStatement),
-    Throw(statement::ThrowStatement),
-    TryCatch(statement::TryCatchStatement),
-    TryCatchFinally(statement::TryCatchFinallyStatement),
-    TryFinally(statement::TryFinallyStatement),
-    Debugger(statement::DebuggerStatement),
+    Block(statement::BlockStatement<'src>),
+    Variable(statement::VariableStatement<'src>),
+    Empty(statement::EmptyStatement<'src>),
+    Expression(statement::ExpressionStatement<'src>),
+    If(statement::IfStatement<'src>),
+    IfElse(statement::IfElseStatement<'src>),
+    For(statement::ForStatement<'src>),
+    ForIn(statement::ForInStatement<'src>),
+    ForOf(statement::ForOfStatement<'src>),
+    ForAwait(statement::ForAwaitStatement<'src>),
+    While(statement::WhileStatement<'src>),
+    DoWhile(statement::DoWhileStatement<'src>),
+    Switch(statement::SwitchStatement<'src>),
+    Continue(statement::ContinueStatement<'src>),
+    Break(statement::BreakStatement<'src>),
+    Return(statement::ReturnStatement<'src>),
+    With(statement::WithStatement<'src>),
+    Labelled(statement::LabelledStatement<'src>),
+    Throw(statement::ThrowStatement<'src>),
+    TryCatch(statement::TryCatchStatement<'src>),
+    TryCatchFinally(statement::TryCatchFinallyStatement<'src>),
+    TryFinally(statement::TryFinallyStatement<'src>),
+    Debugger(statement::DebuggerStatement<'src>),
 
     // Declarations
-    Function(functions::FunctionDeclaration),
-    Class(classes::ClassDeclaration),
-    Let(statement::LetDeclaration),
-    Const(statement::ConstDeclaration),
+    Function(functions::FunctionDeclaration<'src>),
+    Class(classes::ClassDeclaration<'src>),
+    Let(statement::LetDeclaration<'src>),
+    Const(statement::ConstDeclaration<'src>),
+    TypeScript(typescript::Declaration<'src>), // experimental
 });
-impl From<Statement> for StatementItem {
+impl<'src> From<Statement<'src>> for StatementItem<'src> {
     fn from(stmt: Statement) -> StatementItem {
         match stmt {
             Statement::Block(n) => n.into(),
@@ -159,6 +173,7 @@ impl From<Statement> for StatementItem {
             Statement::Empty(n) => n.into(),
             Statement::Expression(n) => n.into(),
             Statement::If(n) => n.into(),
+            Statement::IfElse(n) => n.into(),
             Statement::For(n) => n.into(),
             Statement::ForIn(n) => n.into(),
             Statement::ForOf(n) => n.into(),
@@ -176,142 +191,158 @@ impl From<Statement> for StatementItem {
             Statement::TryCatchFinally(n) => n.into(),
             Statement::TryFinally(n) => n.into(),
             Statement::Debugger(n) => n.into(),
+            Statement::Function(n) => n.into(),
         }
     }
 }
-impl<T: Into<Expression>> From<T> for StatementItem {
-    fn from(v: T) -> StatementItem {
-        StatementItem::Expression(statement::ExpressionStatement::from(v.into()))
+impl<'src, T: Into<Expression<'src>>> From<T> for StatementItem<'src> {
+    fn from(v: T) -> StatementItem<'src> {
+        StatementItem::Expression(NodeBox::new(statement::ExpressionStatement::new(v)))
     }
 }
 
 
-node_enum!(@node_display pub enum Statement {
-    Block(statement::BlockStatement),
-    Variable(statement::VariableStatement),
-    Empty(statement::EmptyStatement),
-    Expression(statement::ExpressionStatement),
-    If(statement::IfStatement),
-    For(statement::ForStatement),
-    ForIn(statement::ForInStatement),
-    ForOf(statement::ForOfStatement),
-    ForAwait(statement::ForAwaitStatement),
-    While(statement::WhileStatement),
-    DoWhile(statement::DoWhileStatement),
-    Switch(statement::SwitchStatement),
-    Continue(statement::ContinueStatement),
-    Break(statement::BreakStatement),
-    Return(statement::ReturnStatement),
-    With(statement::WithStatement),
-    Labelled(statement::LabelledStatement),
-    Throw(statement::ThrowStatement),
-    TryCatch(statement::TryCatchStatement),
-    TryCatchFinally(statement::TryCatchFinallyStatement),
-    TryFinally(statement::TryFinallyStatement),
-    Debugger(statement::DebuggerStatement),
+node_enum!(@boxed @node_display pub enum Statement {
+    Block(statement::BlockStatement<'src>),
+    Variable(statement::VariableStatement<'src>),
+    Empty(statement::EmptyStatement<'src>),
+    Expression(statement::ExpressionStatement<'src>),
+    If(statement::IfStatement<'src>),
+    IfElse(statement::IfElseStatement<'src>),
+    For(statement::ForStatement<'src>),
+    ForIn(statement::ForInStatement<'src>),
+    ForOf(statement::ForOfStatement<'src>),
+    ForAwait(statement::ForAwaitStatement<'src>),
+    While(statement::WhileStatement<'src>),
+    DoWhile(statement::DoWhileStatement<'src>),
+    Switch(statement::SwitchStatement<'src>),
+    Continue(statement::ContinueStatement<'src>),
+    Break(statement::BreakStatement<'src>),
+    Return(statement::ReturnStatement<'src>),
+    With(statement::WithStatement<'src>),
+    Labelled(statement::LabelledStatement<'src>),
+    Throw(statement::ThrowStatement<'src>),
+    TryCatch(statement::TryCatchStatement<'src>),
+    TryCatchFinally(statement::TryCatchFinallyStatement<'src>),
+    TryFinally(statement::TryFinallyStatement<'src>),
+    Debugger(statement::DebuggerStatement<'src>),
+
+    // Annex B allows function declarations as the body of "if" statements
+    // and labels in sloppy mode, e.g. "if (a) function b() {}".
+    Function(functions::FunctionDeclaration<'src>),
 });
-impl Default for Statement {
-    fn default() -> Statement {
+impl<'src> Default for Statement<'src> {
+    fn default() -> Statement<'src> {
         statement::EmptyStatement::default().into()
     }
 }
 
 
-node_enum!(@node_display pub enum Expression {
-    Binding(general::ReferenceIdentifier),
-    This(expression::ThisExpression),
-    Array(objects::ArrayExpression),
-    Object(objects::ObjectExpression),
-    Null(literal::Null),
-    Boolean(literal::Boolean),
-    Numeric(literal::Numeric),
-    String(literal::String),
-    Function(functions::FunctionExpression),
-    Class(classes::ClassExpression),
-    Regex(literal::RegExp),
-    Template(expression::TemplateLiteral),
-    Member(expression::MemberExpression),
-    SuperMember(expression::SuperMemberExpression),
-    // Binary(expression::BinaryExpression),
-    // Unary(expression::UnaryExpression),
-    // Update(expression::UpdateExpression),
-    Call(expression::CallExpression),
-    New(expression::NewExpression),
-    ImportCall(expression::ImportCallExpression),
-    SuperCall(expression::SuperCallExpression),
-    Conditional(expression::ConditionalExpression),
-    // Assign(expression::AssignmentExpression),
-    // AssignUpdate(expression::AssignmentUpdateExpression),
-    Sequence(expression::SequenceExpression),
-    Arrow(functions::ArrowFunctionExpression),
-    Do(expression::DoExpression),
-    JSX(jsx::Element),
+node_enum!(@boxed @node_display pub enum Expression {
+    Binding(general::ReferenceIdentifier<'src>),
+    This(expression::ThisExpression<'src>),
+    Array(objects::ArrayExpression<'src>),
+    Object(objects::ObjectExpression<'src>),
+    Null(literal::Null<'src>),
+    Boolean(literal::Boolean<'src>),
+    Numeric(literal::Numeric<'src>),
+    String(literal::String<'src>),
+    Function(functions::FunctionExpression<'src>),
+    Class(classes::ClassExpression<'src>),
+    Regex(literal::RegExp<'src>),
+    Template(expression::TemplateLiteral<'src>),
+    TaggedTemplate(expression::TaggedTemplateLiteral<'src>),
+    Parenthesized(expression::ParenthesizedExpression<'src>),
+    Member(expression::MemberExpression<'src>),
+    OptionalMember(expression::OptionalMemberExpression<'src>),
+    SuperMember(expression::SuperMemberExpression<'src>),
+    Call(expression::CallExpression<'src>),
+    OptionalCall(expression::OptionalCallExpression<'src>),
+    New(expression::NewExpression<'src>),
+    ImportCall(expression::ImportCallExpression<'src>),
+    SuperCall(expression::SuperCallExpression<'src>),
+    Conditional(expression::ConditionalExpression<'src>),
+    Assign(expression::AssignmentExpression<'src>),
+    Sequence(expression::SequenceExpression<'src>),
+    Arrow(functions::ArrowFunctionExpression<'src>),
+    Do(expression::DoExpression<'src>),
+    JSX(jsx::Element<'src>),
+
+    // experimental: TypeScript
+    As(typescript::AsExpression<'src>),
+    Satisfies(typescript::SatisfiesExpression<'src>),
+    NonNull(typescript::NonNullExpression<'src>),
+    Instantiation(typescript::InstantiationExpression<'src>),
+    TypeAssertion(typescript::TypeAssertion<'src>),
+
+    // experimental: Flow
+    TypeCast(flow::TypeCastExpression<'src>),
 
     // BinaryExpression
-    // Add(AddExpression),
-    // Subtract(SubtractExpression),
-    // LeftShift(LeftShiftExpression),
-    // RightShift(RightShiftExpression),
-    // RightShiftSigned(RightShiftSignedExpression),
-    // Divide(DivideExpression),
-    // Multiply(MultiplyExpression),
-    // Modulus(ModulusExpression),
-    // BitAnd(BitAndExpression),
-    // BitOr(BitOrExpression),
-    // BitXor(BitXorExpression),
-    // Power(PowerExpression),
-    // Compare(EqualExpression),
-    // StrictCompare(StrictEqualExpression),
-    // NegateCompare(NegateEqualExpression),
-    // NegateStrictCompare(StrictNegateEqualExpression),
-    // LessThan(LessThanExpression),
-    // LessThanEq(LessThanEqualExpression),
-    // GreaterThan(GreaterThanExpression),
-    // GreaterThanEq(GreaterThanEqualExpression),
-    // In(InExpression),
-    // Instanceof(InstanceofExpression),
-    // And(AndExpression),
-    // Or(OrExpression),
-    // Bind(BindExpression), // experimental
+    Add(expression::AddExpression<'src>),
+    Subtract(expression::SubtractExpression<'src>),
+    LeftShift(expression::LeftShiftExpression<'src>),
+    RightShift(expression::RightShiftExpression<'src>),
+    RightShiftSigned(expression::RightShiftSignedExpression<'src>),
+    Divide(expression::DivideExpression<'src>),
+    Multiply(expression::MultiplyExpression<'src>),
+    Modulus(expression::ModulusExpression<'src>),
+    BitAnd(expression::BitAndExpression<'src>),
+    BitOr(expression::BitOrExpression<'src>),
+    BitXor(expression::BitXorExpression<'src>),
+    Power(expression::PowerExpression<'src>),
+    Compare(expression::EqualExpression<'src>),
+    StrictCompare(expression::StrictEqualExpression<'src>),
+    NegateCompare(expression::NotEqualExpression<'src>),
+    NegateStrictCompare(expression::StrictNotEqualExpression<'src>),
+    LessThan(expression::LessThanExpression<'src>),
+    LessThanEq(expression::LessThanEqualExpression<'src>),
+    GreaterThan(expression::GreaterThanExpression<'src>),
+    GreaterThanEq(expression::GreaterThanEqualExpression<'src>),
+    In(expression::InExpression<'src>),
+    Instanceof(expression::InstanceofExpression<'src>),
+    And(expression::AndExpression<'src>),
+    Or(expression::OrExpression<'src>),
+    Bind(expression::BindExpression<'src>), // experimental
 
     // AssignmentUpdateExpression
-    // AddAssign(AddAssignExpression),
-    // SubtractAssign(SubtractAssignExpression),
-    // LeftShiftAssign(LeftShiftAssignExpression),
-    // RightShiftAssign(RightShiftAssignExpression),
-    // RightShiftSignedAssign(RightShiftSignedAssignExpression),
-    // DivideAssign(DivideAssignExpression),
-    // MultiplyAssign(MultipleAssignExpression),
-    // ModulusAssign(ModulusAssignExpression),
-    // BitAndAssign(BitAndAssignExpression),
-    // BitOrAssign(BitOrAssignExpression),
-    // BitXorAssign(BitXorAssignExpression),
-    // PowerAssign(PowerAssignExpression),
+    AddAssign(expression::AddAssignExpression<'src>),
+    SubtractAssign(expression::SubtractAssignExpression<'src>),
+    LeftShiftAssign(expression::LeftShiftAssignExpression<'src>),
+    RightShiftAssign(expression::RightShiftAssignExpression<'src>),
+    RightShiftSignedAssign(expression::RightShiftSignedAssignExpression<'src>),
+    DivideAssign(expression::DivideAssignExpression<'src>),
+    MultiplyAssign(expression::MultiplyAssignExpression<'src>),
+    ModulusAssign(expression::ModulusAssignExpression<'src>),
+    BitAndAssign(expression::BitAndAssignExpression<'src>),
+    BitOrAssign(expression::BitOrAssignExpression<'src>),
+    BitXorAssign(expression::BitXorAssignExpression<'src>),
+    PowerAssign(expression::PowerAssignExpression<'src>),
 
-    // // UpdateExpression
-    // PreIncrement(PreIncrementExpression),
-    // PreDecrement(PreDecrementExpression),
-    // PostIncrement(PostIncrementExpression),
-    // PostDecrement(PostDecrementExpression),
+    // UpdateExpression
+    PreIncrement(expression::PreIncrementExpression<'src>),
+    PreDecrement(expression::PreDecrementExpression<'src>),
+    PostIncrement(expression::PostIncrementExpression<'src>),
+    PostDecrement(expression::PostDecrementExpression<'src>),
 
-    // // UnaryExpression
-    // Delete(DeleteExpression),
-    // Void(VoidExpression),
-    // Typeof(TypeofExpression),
-    // Positive(PositiveExpression),
-    // Negative(NegativeExpression),
-    // BitNegate(BitNegateExpression),
-    // Negate(NegateExpression),
-    // Await(AwaitExpression),
-    // Yield(YieldExpression),
-    // YieldDelegate(YieldDelegateExpression),
-    // Bind(BindMemberExpression),
+    // UnaryExpression
+    Delete(expression::DeleteExpression<'src>),
+    Void(expression::VoidExpression<'src>),
+    Typeof(expression::TypeofExpression<'src>),
+    Positive(expression::PositiveExpression<'src>),
+    Negative(expression::NegativeExpression<'src>),
+    BitNegate(expression::BitNegateExpression<'src>),
+    Negate(expression::NegateExpression<'src>),
+    Await(expression::AwaitExpression<'src>),
+    Yield(expression::YieldExpression<'src>),
+    DelegateYield(expression::DelegateYieldExpression<'src>),
+    BindMember(expression::BindMemberExpression<'src>), // experimental
 
-    // // MetaProperty
-    // NewTarget(NewTargetExpression),
-    // ImportMeta(ImportMetaExpression), // experimental
-    // FunctionSent(FunctionSentExpression), // experimental
-    // FunctionArguments(FunctionArgumentsExpression), // experimental
+    // MetaProperty
+    NewTarget(expression::NewTargetExpression<'src>),
+    ImportMeta(expression::ImportMetaExpression<'src>), // experimental
+    FunctionSent(expression::FunctionSentExpression<'src>), // experimental
+    FunctionArguments(expression::FunctionArgumentsExpression<'src>), // experimental
 });
 
 // impl<T: Into<general::ReferenceIdentifier>> From<T> for Expression {
@@ -319,8 +350,8 @@ node_enum!(@node_display pub enum Expression {
 //         Expression::Binding(v.into())
 //     }
 // }
-// impl<T: Into<general::ReferenceIdentifier>> From<T> for Box<Expression> {
-//     fn from(v: T) -> Box<Expression> {
+// impl<T: Into<general::ReferenceIdentifier>> From<T> for NodeBox<Expression> {
+//     fn from(v: T) -> NodeBox<Expression> {
 //         Expression::Binding(v.into()).into()
 //     }
 // }
@@ -329,38 +360,43 @@ node_enum!(@node_display pub enum Expression {
 //         Expression::This(v.into())
 //     }
 // }
-// impl<T: Into<expression::ThisExpression>> From<T> for Box<Expression> {
-//     fn from(v: T) -> Box<Expression> {
+// impl<'src, T: Into<expression::ThisExpression>> From<T> for NodeBox<Expression> {
+//     fn from(v: T) -> NodeBox<Expression> {
 //         Expression::This(v.into()).into()
 //     }
 // }
 
 
 node_enum!(@node_display pub enum ExportDeclaration {
-    DefaultClass(classes::ExportDefaultClassDeclaration),
-    DefaultFunction(functions::ExportDefaultFunctionDeclaration),
-    DefaultExpression(modules::ExportDefaultExpression),
-    Class(modules::ExportClassDeclaration),
-    Function(modules::ExportFunctionDeclaration),
-    Variable(modules::ExportVarStatement),
-    Let(modules::ExportLetDeclaration),
-    Const(modules::ExportConstDeclaration),
-    LocalBindings(modules::ExportLocalBindings),
-    SourceSpecifiers(modules::ExportSourceSpecifiers),
-    All(modules::ExportAllSpecifiers),
+    DefaultClass(classes::ExportDefaultClassDeclaration<'src>),
+    DefaultFunction(functions::ExportDefaultFunctionDeclaration<'src>),
+    DefaultExpression(modules::ExportDefaultExpression<'src>),
+    Class(modules::ExportClassDeclaration<'src>),
+    Function(modules::ExportFunctionDeclaration<'src>),
+    Variable(modules::ExportVarStatement<'src>),
+    Let(modules::ExportLetDeclaration<'src>),
+    Const(modules::ExportConstDeclaration<'src>),
+    LocalBindings(modules::ExportLocalBindings<'src>),
+    SourceSpecifiers(modules::ExportSourceSpecifiers<'src>),
+    All(modules::ExportAllSpecifiers<'src>),
 
     // experimental
-    Named(modules::ExportNamedSpecifier),
-    NamedAndNamespace(modules::ExportNamedAndNamespace),
-    Namespace(modules::ExportNamespace),
-    NamedAndSpecifiers(modules::ExportNamedAndSpecifiers),
+    Named(modules::ExportNamedSpecifier<'src>),
+    NamedAndNamespace(modules::ExportNamedAndNamespace<'src>),
+    Namespace(modules::ExportNamespace<'src>),
+    NamedAndSpecifiers(modules::ExportNamedAndSpecifiers<'src>),
+    TypeScript(typescript::ExportDeclaration<'src>),
+    Assignment(typescript::ExportAssignment<'src>),
+    Types(typescript::ExportTypeSpecifiers<'src>),
 });
 
 
 node_enum!(@node_display pub enum ImportDeclaration {
-    Named(modules::ImportNamedDeclaration),
-    NamedAndNamespace(modules::ImportNamedAndNamespaceDeclaration),
-    Namespace(modules::ImportNamespaceDeclaration),
-    NamedAndSpecifiers(modules::ImportNamedAndSpecifiersDeclaration),
-    Specifiers(modules::ImportSpecifiersDeclaration),
+    Source(modules::ImportSourceDeclaration<'src>),
+    Named(modules::ImportNamedDeclaration<'src>),
+    NamedAndNamespace(modules::ImportNamedAndNamespaceDeclaration<'src>),
+    Namespace(modules::ImportNamespaceDeclaration<'src>),
+    NamedAndSpecifiers(modules::ImportNamedAndSpecifiersDeclaration<'src>),
+    Specifiers(modules::ImportSpecifiersDeclaration<'src>),
+    Type(flow::ImportTypeDeclaration<'src>), // experimental
 });
@@ -1,4 +1,4 @@
-use ast::{SeparatorTokens, KeywordData, CommentIterator, LeadingComments};
+use ast::{SeparatorTokens, KeywordData, NodeBox};
 
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punctuator, Precedence,
                    LookaheadSequence};
@@ -10,53 +10,93 @@ use ast::objects::MethodKind;
 use ast::general::{BindingIdentifier, PropertyIdentifier, PropertyName};
 
 use ast::alias;
+use ast::general;
+use ast::typescript::{self, Modifier, ParameterPropertyAssignment, TypeAnnotation, TypeArguments, TypeParameters,
+                      TypeReference};
 
 node!(pub struct ClassHeritage {
     pub token_extends: KeywordData,
-    pub tokens_extends_post: SeparatorTokens,
-    pub expression: Option<Box<alias::Expression>>,
+    pub tokens_extends_post: SeparatorTokens<'src>,
+    pub expression: NodeBox<alias::Expression<'src>>,
+    pub type_arguments: Option<TypeArguments<'src>>, // experimental: TypeScript
 });
-impl NodeDisplay for ClassHeritage {
+impl<'src> NodeDisplay for ClassHeritage<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Extends, &self.token_extends);
         // f.separator(&self.tokens_extends_post);
         f.require_precedence(Precedence::LeftHand).node(&self.expression)?;
+        if let Some(ref type_arguments) = self.type_arguments {
+            f.node(type_arguments)?;
+        }
 
         Ok(())
     }
 }
 
+impl<'src> From<alias::Expression<'src>> for ClassHeritage<'src> {
+    fn from(expression: alias::Expression) -> ClassHeritage {
+        ClassHeritage {
+            token_extends: Default::default(),
+            tokens_extends_post: Default::default(),
+            expression: NodeBox::new(expression),
+            type_arguments: None,
+            position: None,
+            comments: None,
+        }
+    }
+}
+
 node!(pub struct ClassName {
-    pub tokens_prefix: SeparatorTokens,
-    pub id: BindingIdentifier,
+    pub tokens_prefix: SeparatorTokens<'src>,
+    pub id: BindingIdentifier<'src>,
 });
-impl NodeDisplay for ClassName {
+impl<'src> NodeDisplay for ClassName<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // f.separator(&self.tokens_prefix);
         f.node(&self.id)
     }
 }
+impl<'src> From<BindingIdentifier<'src>> for ClassName<'src> {
+    fn from(id: BindingIdentifier) -> ClassName {
+        ClassName {
+            tokens_prefix: Default::default(),
+            id,
+            position: None,
+            comments: None,
+        }
+    }
+}
 
 // export default class name {}
 node!(#[derive(Default)] pub struct ExportDefaultClassDeclaration {
-    pub decorators: Vec<ClassDecorator>, // experimental
+    pub decorators: Vec<ClassDecorator<'src>>, // experimental
     pub token_export: KeywordData,
     pub token_default: KeywordData,
+    pub modifiers: Vec<Modifier<'src>>, // experimental: TypeScript
     pub token_class: KeywordData,
-    pub id: Option<ClassName>,
-    pub heritage: Option<ClassHeritage>,
-    pub body: ClassBody,
+    pub id: Option<ClassName<'src>>,
+    pub type_parameters: Option<TypeParameters<'src>>, // experimental: TypeScript
+    pub heritage: Option<ClassHeritage<'src>>,
+    pub implements: Vec<TypeReference<'src>>, // experimental: TypeScript
+    pub body: ClassBody<'src>,
 });
-impl NodeDisplay for ExportDefaultClassDeclaration {
+impl<'src> NodeDisplay for ExportDefaultClassDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Export, &self.token_export);
         f.keyword(Keyword::Default, &self.token_default);
 
-        f.node_list(&self.decorators)?;
+        f.decorators(&self.decorators)?;
+        for modifier in self.modifiers.iter() {
+            f.node(modifier)?;
+        }
         f.keyword(Keyword::Class, &self.token_class);
 
         f.node(&self.id)?;
+        if let Some(ref type_parameters) = self.type_parameters {
+            f.node(type_parameters)?;
+        }
         f.node(&self.heritage)?;
+        typescript::class_implements(f, &self.implements)?;
         f.node(&self.body)
     }
 }
@@ -78,10 +118,17 @@ mod tests_class_export_default {
         assert_serialize!(
             ExportDefaultClassDeclaration {
                 decorators: Default::default(),
-                id: BindingIdentifier::from("someName").into(),
-                extends: Default::default(),
+                token_export: Default::default(),
+                token_default: Default::default(),
+                modifiers: Default::default(),
+                token_class: Default::default(),
+                id: ClassName::from(BindingIdentifier::from("someName")).into(),
+                type_parameters: None,
+                heritage: None,
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "export default class someName{}"
         );
@@ -92,10 +139,17 @@ mod tests_class_export_default {
         assert_serialize!(
             ExportDefaultClassDeclaration {
                 decorators: Default::default(),
+                token_export: Default::default(),
+                token_default: Default::default(),
+                modifiers: Default::default(),
+                token_class: Default::default(),
                 id: Default::default(),
-                extends: ReferenceIdentifier::from("baseClass").into(),
+                type_parameters: None,
+                heritage: Some(alias::Expression::from(ReferenceIdentifier::from("baseClass")).into()),
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "export default class extends baseClass{}"
         );
@@ -106,10 +160,17 @@ mod tests_class_export_default {
         assert_serialize!(
             ExportDefaultClassDeclaration {
                 decorators: Default::default(),
-                id: BindingIdentifier::from("someName").into(),
-                extends: ReferenceIdentifier::from("baseClass").into(),
+                token_export: Default::default(),
+                token_default: Default::default(),
+                modifiers: Default::default(),
+                token_class: Default::default(),
+                id: ClassName::from(BindingIdentifier::from("someName")).into(),
+                type_parameters: None,
+                heritage: Some(alias::Expression::from(ReferenceIdentifier::from("baseClass")).into()),
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "export default class someName extends baseClass{}"
         );
@@ -119,20 +180,30 @@ mod tests_class_export_default {
 
 // class name {}
 node!(pub struct ClassDeclaration {
-    pub decorators: Vec<ClassDecorator>, // experimental
+    pub decorators: Vec<ClassDecorator<'src>>, // experimental
+    pub modifiers: Vec<Modifier<'src>>, // experimental: TypeScript
     pub token_class: KeywordData,
-    pub id: ClassName,
-    pub heritage: Option<ClassHeritage>,
-    pub body: ClassBody,
+    pub id: ClassName<'src>,
+    pub type_parameters: Option<TypeParameters<'src>>, // experimental: TypeScript
+    pub heritage: Option<ClassHeritage<'src>>,
+    pub implements: Vec<TypeReference<'src>>, // experimental: TypeScript
+    pub body: ClassBody<'src>,
 });
-impl NodeDisplay for ClassDeclaration {
+impl<'src> NodeDisplay for ClassDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.node_list(&self.decorators)?;
+        f.decorators(&self.decorators)?;
+        for modifier in self.modifiers.iter() {
+            f.node(modifier)?;
+        }
 
         f.keyword(Keyword::Class, &self.token_class);
 
         f.node(&self.id)?;
+        if let Some(ref type_parameters) = self.type_parameters {
+            f.node(type_parameters)?;
+        }
         f.node(&self.heritage)?;
+        typescript::class_implements(f, &self.implements)?;
         f.node(&self.body)
     }
 }
@@ -146,10 +217,15 @@ mod tests_class_declaration {
         assert_serialize!(
             ClassDeclaration {
                 decorators: Default::default(),
-                id: BindingIdentifier::from("someName").into(),
-                extends: Default::default(),
+                modifiers: Default::default(),
+                token_class: Default::default(),
+                id: ClassName::from(BindingIdentifier::from("someName")),
+                type_parameters: None,
+                heritage: None,
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "class someName{}"
         );
@@ -160,10 +236,15 @@ mod tests_class_declaration {
         assert_serialize!(
             ClassDeclaration {
                 decorators: Default::default(),
-                id: BindingIdentifier::from("someName").into(),
-                extends: ReferenceIdentifier::from("baseClass").into(),
+                modifiers: Default::default(),
+                token_class: Default::default(),
+                id: ClassName::from(BindingIdentifier::from("someName")),
+                type_parameters: None,
+                heritage: Some(alias::Expression::from(ReferenceIdentifier::from("baseClass")).into()),
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "class someName extends baseClass{}"
         );
@@ -173,22 +254,28 @@ mod tests_class_declaration {
 
 // (class {})
 node!(#[derive(Default)] pub struct ClassExpression {
-    pub decorators: Vec<ClassDecorator>, // experimental
+    pub decorators: Vec<ClassDecorator<'src>>, // experimental
     pub token_class: KeywordData,
-    pub id: Option<ClassName>,
-    pub heritage: Option<ClassHeritage>,
-    pub body: ClassBody,
+    pub id: Option<ClassName<'src>>,
+    pub type_parameters: Option<TypeParameters<'src>>, // experimental: TypeScript
+    pub heritage: Option<ClassHeritage<'src>>,
+    pub implements: Vec<TypeReference<'src>>, // experimental: TypeScript
+    pub body: ClassBody<'src>,
 });
-impl NodeDisplay for ClassExpression {
+impl<'src> NodeDisplay for ClassExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.lookahead_wrap_parens(LookaheadSequence::Declaration);
 
-        f.node_list(&self.decorators)?;
+        f.decorators(&self.decorators)?;
 
         f.keyword(Keyword::Class, &self.token_class);
 
         f.node(&self.id)?;
+        if let Some(ref type_parameters) = self.type_parameters {
+            f.node(type_parameters)?;
+        }
         f.node(&self.heritage)?;
+        typescript::class_implements(&mut f, &self.implements)?;
         f.node(&self.body)
     }
 }
@@ -207,10 +294,14 @@ mod tests_class_expression {
         assert_serialize!(
             ClassExpression {
                 decorators: Default::default(),
-                id: BindingIdentifier::from("someName").into(),
-                extends: Default::default(),
+                token_class: Default::default(),
+                id: ClassName::from(BindingIdentifier::from("someName")).into(),
+                type_parameters: None,
+                heritage: None,
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "class someName{}"
         );
@@ -221,10 +312,14 @@ mod tests_class_expression {
         assert_serialize!(
             ClassExpression {
                 decorators: Default::default(),
+                token_class: Default::default(),
                 id: Default::default(),
-                extends: ReferenceIdentifier::from("baseClass").into(),
+                type_parameters: None,
+                heritage: Some(alias::Expression::from(ReferenceIdentifier::from("baseClass")).into()),
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "class extends baseClass{}"
         );
@@ -235,10 +330,14 @@ mod tests_class_expression {
         assert_serialize!(
             ClassExpression {
                 decorators: Default::default(),
-                id: BindingIdentifier::from("someName").into(),
-                extends: ReferenceIdentifier::from("baseClass").into(),
+                token_class: Default::default(),
+                id: ClassName::from(BindingIdentifier::from("someName")).into(),
+                type_parameters: None,
+                heritage: Some(alias::Expression::from(ReferenceIdentifier::from("baseClass")).into()),
+                implements: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "class someName extends baseClass{}"
         );
@@ -248,10 +347,10 @@ mod tests_class_expression {
 
 node!(#[derive(Default)] pub struct ClassBody {
     pub token_curly_l: KeywordData,
-    pub items: Vec<ClassItem>,
+    pub items: Vec<ClassItem<'src>>,
     pub token_curly_r: KeywordData,
 });
-impl NodeDisplay for ClassBody {
+impl<'src> NodeDisplay for ClassBody<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.wrap_curly().node_list(&self.items)?;
 
@@ -262,43 +361,64 @@ impl NodeDisplay for ClassBody {
 node!(#[derive(Default)] pub struct ClassEmpty {
     pub token: KeywordData,
 });
-impl NodeDisplay for ClassEmpty {
+impl<'src> NodeDisplay for ClassEmpty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Semicolon);
+        f.punctuator(Punctuator::Semicolon, &self.token);
         Ok(())
     }
 }
 
 
 // TODO: Should the class constructor be it's own item type to make "super()" checks easier?
-node_enum!(@node_display pub enum ClassItem {
-    Method(ClassMethod),
-    Field(ClassField),
-    Empty(ClassEmpty),
+node_enum!(@boxed @node_display pub enum ClassItem {
+    Method(ClassMethod<'src>),
+    Field(ClassField<'src>),
+    Empty(ClassEmpty<'src>),
+
+    // experimental: TypeScript
+    MethodSignature(typescript::ClassMethodSignature<'src>),
+    IndexSignature(typescript::IndexSignature<'src>),
 });
 
 // experimental
-node_enum!(@node_display @leading_comments pub enum ClassFieldId {
-    Public(PropertyName),
-    Private(PropertyIdentifier),
+node_enum!(@node_display pub enum ClassFieldId {
+    Public(PropertyName<'src>),
+    Private(PropertyIdentifier<'src>),
 });
 
 // experimental
 node!(pub struct ClassField {
-    pub decorators: Vec<ClassItemDecorator>,
-    pub pos: Option<StaticPosition>,
-    pub id: ClassFieldId,
-    pub init: Option<general::Initializer>,
+    pub decorators: Vec<ClassItemDecorator<'src>>,
+    pub modifiers: Vec<Modifier<'src>>, // experimental: TypeScript
+    pub pos: Option<StaticPosition<'src>>,
+    pub id: ClassFieldId<'src>,
+    pub optional: bool, // experimental: TypeScript
+    pub definite: bool, // experimental: TypeScript
+    pub type_annotation: Option<TypeAnnotation<'src>>, // experimental: TypeScript
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ClassField {
+impl<'src> NodeDisplay for ClassField<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.node_list(&self.decorators)?;
+        if f.strip_types() && typescript::is_ambient(&self.modifiers) {
+            return Ok(());
+        }
+
+        f.decorators(&self.decorators)?;
 
-        f.node(&self.pos)?;
+        typescript::class_member_modifiers(f, &self.modifiers, &self.pos)?;
         f.node(&self.id)?;
+        if self.optional && !f.strip_types() {
+            f.punctuator(Punctuator::Question, &());
+        }
+        if self.definite && !f.strip_types() {
+            f.punctuator(Punctuator::Exclam, &());
+        }
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
         f.node(&self.init)?;
 
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&());
 
         Ok(())
     }
@@ -326,10 +446,15 @@ mod tests_class_field {
         assert_serialize!(
             ClassField {
                 decorators: Default::default(),
+                modifiers: Default::default(),
                 pos: Default::default(),
                 id: PropertyIdentifier::from("someName").into(),
-                value: Default::default(),
+                optional: false,
+                definite: false,
+                type_annotation: None,
+                init: None,
                 position: None,
+                comments: None,
             },
             "someName;"
         );
@@ -340,10 +465,15 @@ mod tests_class_field {
         assert_serialize!(
             ClassField {
                 decorators: Default::default(),
-                pos: FieldPosition::Static,
+                modifiers: Default::default(),
+                pos: Some(StaticPosition::default()),
                 id: PropertyIdentifier::from("someName").into(),
-                value: Default::default(),
+                optional: false,
+                definite: false,
+                type_annotation: None,
+                init: None,
                 position: None,
+                comments: None,
             },
             "static someName;"
         );
@@ -354,10 +484,15 @@ mod tests_class_field {
         assert_serialize!(
             ClassField {
                 decorators: Default::default(),
+                modifiers: Default::default(),
                 pos: Default::default(),
                 id: PropertyIdentifier::from("someName").into(),
-                value: literal::Boolean::from(true).into(),
+                optional: false,
+                definite: false,
+                type_annotation: None,
+                init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
                 position: None,
+                comments: None,
             },
             "someName=true;"
         );
@@ -368,10 +503,15 @@ mod tests_class_field {
         assert_serialize!(
             ClassField {
                 decorators: Default::default(),
-                pos: FieldPosition::Static,
+                modifiers: Default::default(),
+                pos: Some(StaticPosition::default()),
                 id: PropertyIdentifier::from("someName").into(),
-                value: literal::Boolean::from(true).into(),
+                optional: false,
+                definite: false,
+                type_annotation: None,
+                init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
                 position: None,
+                comments: None,
             },
             "static someName=true;"
         );
@@ -379,27 +519,51 @@ mod tests_class_field {
 }
 
 node!(pub struct ClassMethod {
-    pub decorators: Vec<ClassItemDecorator>,
-    pub pos: Option<StaticPosition>,
+    pub decorators: Vec<ClassItemDecorator<'src>>,
+    pub modifiers: Vec<Modifier<'src>>, // experimental: TypeScript
+    pub pos: Option<StaticPosition<'src>>,
     pub kind: MethodKind,
-    pub id: ClassFieldId,
-    pub params: FunctionParams,
-    pub body: FunctionBody,
+    pub id: ClassFieldId<'src>,
+    pub params: FunctionParams<'src>,
+    pub body: FunctionBody<'src>,
 });
-impl NodeDisplay for ClassMethod {
+impl<'src> NodeDisplay for ClassMethod<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.node_list(&self.decorators)?;
+        f.decorators(&self.decorators)?;
 
-        f.node(&self.pos)?;
+        typescript::class_member_modifiers(f, &self.modifiers, &self.pos)?;
         f.node(&self.kind)?;
 
         f.node(&self.id)?;
         f.node(&self.params)?;
-        f.node(&self.body)?;
+
+        let properties = typescript::parameter_properties(&self.params);
+        if !f.strip_types() || properties.is_empty() {
+            return f.node(&self.body);
+        }
+
+        // Without types, constructor parameter properties become assignments,
+        // which have to come after any "super()" call.
+        let body = &self.body.body;
+        let split = body.iter().position(is_super_call).map_or(0, |i| i + 1);
+        let assignments: Vec<_> = properties.into_iter().map(ParameterPropertyAssignment).collect();
+
+        let mut f = f.wrap_curly();
+        f.node_list(&self.body.directives)?;
+        f.node_list(&body[..split])?;
+        f.node_list(&assignments)?;
+        f.node_list(&body[split..])?;
 
         Ok(())
     }
 }
+
+fn is_super_call(item: &alias::StatementItem) -> bool {
+    match *item {
+        alias::StatementItem::Expression(ref stmt) => matches!(stmt.expression, alias::Expression::SuperCall(_)),
+        _ => false,
+    }
+}
 #[cfg(test)]
 mod tests_class_method {
     use super::*;
@@ -409,12 +573,14 @@ mod tests_class_method {
         assert_serialize!(
             ClassMethod {
                 decorators: Default::default(),
+                modifiers: Default::default(),
                 pos: Default::default(),
                 kind: Default::default(),
                 id: PropertyIdentifier::from("someName").into(),
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "someName(){}"
         );
@@ -425,12 +591,14 @@ mod tests_class_method {
         assert_serialize!(
             ClassMethod {
                 decorators: Default::default(),
-                pos: FieldPosition::Static,
+                modifiers: Default::default(),
+                pos: Some(StaticPosition::default()),
                 kind: Default::default(),
                 id: PropertyIdentifier::from("someName").into(),
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "static someName(){}"
         );
@@ -441,12 +609,14 @@ mod tests_class_method {
         assert_serialize!(
             ClassMethod {
                 decorators: Default::default(),
+                modifiers: Default::default(),
                 pos: Default::default(),
                 kind: MethodKind::Async,
                 id: PropertyIdentifier::from("someName").into(),
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "async someName(){}"
         );
@@ -458,9 +628,9 @@ mod tests_class_method {
 node!(#[derive(Default)] pub struct StaticPosition {
     pub token_static: KeywordData,
 });
-impl NodeDisplay for StaticPosition {
+impl<'src> NodeDisplay for StaticPosition<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Static);
+        f.keyword(Keyword::Static, &self.token_static);
         Ok(())
     }
 }
@@ -471,32 +641,33 @@ impl NodeDisplay for StaticPosition {
 // }
 
 node!(pub struct ClassDecorator {
-    pub tokens_prefix: SeparatorTokens,
-    pub value: DecoratorValue,
+    pub tokens_prefix: SeparatorTokens<'src>,
+    pub value: DecoratorValue<'src>,
 });
-impl NodeDisplay for ClassDecorator {
+impl<'src> NodeDisplay for ClassDecorator<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::At);
+        f.punctuator(Punctuator::At, &());
         f.node(&self.value)
     }
 }
-impl<T: Into<DecoratorValue>> From<T> for ClassDecorator {
-    fn from(obj: T) -> ClassDecorator {
+impl<'src, T: Into<DecoratorValue<'src>>> From<T> for ClassDecorator<'src> {
+    fn from(obj: T) -> ClassDecorator<'src> {
         ClassDecorator {
             tokens_prefix: Default::default(),
             value: obj.into(),
             position: None,
+            comments: None,
         }
     }
 }
 
 node!(pub struct ClassItemDecorator {
-    pub tokens_prefix: SeparatorTokens,
-    pub value: DecoratorValue,
+    pub tokens_prefix: SeparatorTokens<'src>,
+    pub value: DecoratorValue<'src>,
 });
-impl NodeDisplay for ClassItemDecorator {
+impl<'src> NodeDisplay for ClassItemDecorator<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::At);
+        f.punctuator(Punctuator::At, &());
         f.node(&self.value)
     }
 }
@@ -505,12 +676,13 @@ impl NodeDisplay for ClassItemDecorator {
 //         CommentIterator::new(&self.tokens_prefix)
 //     }
 // }
-impl<T: Into<DecoratorValue>> From<T> for ClassItemDecorator {
-    fn from(obj: T) -> ClassItemDecorator {
+impl<'src, T: Into<DecoratorValue<'src>>> From<T> for ClassItemDecorator<'src> {
+    fn from(obj: T) -> ClassItemDecorator<'src> {
         ClassItemDecorator {
             tokens_prefix: Default::default(),
             value: obj.into(),
             position: None,
+            comments: None,
         }
     }
 }
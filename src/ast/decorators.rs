@@ -1,55 +1,54 @@
-use ast::MaybeTokenPosition;
-
-use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Punctuator, Precedence};
+use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Punctuator};
 
 use ast::general::{ReferenceIdentifier, PropertyIdentifier};
 use ast::alias;
 
 use ast::expression::CallArguments;
+use ast::NodeBox;
 
 // experimental
 // TODO: Enum fix
 node_enum!(@node_display pub enum DecoratorValue {
-    Property(DecoratorValueExpression),
-    Call(DecoratorCallExpression),
-    Expression(DecoratorExpression), // Backward-compat
+    Property(DecoratorValueExpression<'src>),
+    Call(DecoratorCallExpression<'src>),
+    Expression(DecoratorExpression<'src>), // Backward-compat
 });
 
 
-// Backward-compat for older decorator spec
+// Backward-compat for older decorator spec, written in parens
 node!(pub struct DecoratorExpression {
-    pub expression: alias::Expression,
+    pub expression: alias::Expression<'src>,
 });
-impl NodeDisplay for DecoratorExpression {
+impl<'src> NodeDisplay for DecoratorExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.require_precedence(Precedence::Normal).node(&self.expression)
+        f.wrap_parens().node(&self.expression)
     }
 }
 
 node!(pub struct DecoratorMemberAccess {
-    pub object: Box<DecoratorValueExpression>,
-    pub property: PropertyIdentifier,
+    pub object: NodeBox<DecoratorValueExpression<'src>>,
+    pub property: PropertyIdentifier<'src>,
 });
-impl NodeDisplay for DecoratorMemberAccess {
+impl<'src> NodeDisplay for DecoratorMemberAccess<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.object)?;
-        f.punctuator(Punctuator::Period);
+        f.punctuator(Punctuator::Period, &());
         f.node(&self.property)
     }
 }
 
 // experimental
 node_enum!(@node_display pub enum DecoratorValueExpression {
-    Identifier(ReferenceIdentifier),
-    Member(DecoratorMemberAccess),
+    Identifier(ReferenceIdentifier<'src>),
+    Member(DecoratorMemberAccess<'src>),
 });
 
 // experimental
 node!(pub struct DecoratorCallExpression {
-    pub callee: DecoratorValueExpression,
-    pub arguments: CallArguments,
+    pub callee: DecoratorValueExpression<'src>,
+    pub arguments: CallArguments<'src>,
 });
-impl NodeDisplay for DecoratorCallExpression {
+impl<'src> NodeDisplay for DecoratorCallExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.callee)?;
         f.node(&self.arguments)
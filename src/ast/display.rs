@@ -1,8 +1,11 @@
 use std::fmt;
 use std::fmt::Write;
+use std::mem;
+use std::ops::Range;
 
-use ast::{MaybeTokenPosition, KeywordData};
-use ast;
+use ast::{MaybeTokenPosition, NodePosition, PositionRange, KeywordData, KeywordSuffixData, KeywordWrappedData, NodeBox};
+use ast::source_map::{Mapping, SourceMap};
+use stack;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Punctuator {
@@ -16,6 +19,10 @@ pub enum Punctuator {
     CurlyR,
     CurlyL,
 
+    // Flow's exact object types
+    CurlyBarR,
+    CurlyBarL,
+
     ParenR,
     ParenL,
 
@@ -27,6 +34,7 @@ pub enum Punctuator {
 
     Semicolon,
     SQuote,
+    DQuote,
 
     Ellipsis,
     Period,
@@ -39,14 +47,19 @@ pub enum Punctuator {
     ColonColon,
 
     Slash,
+    SlashEq,
 
     Star,
+    StarEq,
     StarStar,
+    StarStarEq,
 
     Plus,
     PlusPlus,
+    PlusEq,
 
     Subtract,
+    SubtractEq,
     Minus,
     MinusMinus,
 
@@ -54,22 +67,29 @@ pub enum Punctuator {
     ArrowStar,
 
     Caret,
+    CaretEq,
     LAngle,
     LAngleEq,
     LAngleAngle,
+    LAngleAngleEq,
 
     RAngle,
     RAngleEq,
     RAngleAngle,
+    RAngleAngleEq,
     RAngleAngleAngle,
+    RAngleAngleAngleEq,
 
     Mod,
+    ModEq,
 
     Amp,
     AmpAmp,
+    AmpEq,
 
     Bar,
     BarBar,
+    BarEq,
     Bind,
 
     Exclam,
@@ -138,6 +158,33 @@ pub enum Keyword {
     Set,
     Static,
     As,
+
+    // TypeScript
+    Interface,
+    Type,
+    Enum,
+    Declare,
+    Abstract,
+    Implements,
+    Public,
+    Private,
+    Protected,
+    Readonly,
+    Override,
+    Out,
+    Keyof,
+    Unique,
+    Is,
+    Satisfies,
+    Infer,
+    Asserts,
+    Namespace,
+    Module,
+    Global,
+
+    // Flow
+    Opaque,
+    Checks,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -190,8 +237,176 @@ pub enum LookaheadSequence {
     Let,
 }
 
+/// The whitespace used for one level of indentation in pretty mode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tabs,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Semicolons {
+    // Terminate every statement with a semicolon.
+    Always,
+
+    // Only print the semicolons that automatic semicolon insertion
+    // would not be able to recover.
+    AsNeeded,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QuoteStyle {
+    Single,
+    Double,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TrailingCommas {
+    // Keep trailing commas wherever the AST has them.
+    Preserve,
+
+    // Remove trailing commas, except where they mark an array hole.
+    Never,
+
+    // Add trailing commas to lists that are broken across lines,
+    // and remove them from single-line lists.
+    Multiline,
+}
+
+/// Options controlling the output of the `NodeFormatter`.
+///
+/// The default options produce minified output. The layout options,
+/// `indent` and `brace_spacing`, only apply when `pretty` is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub pretty: bool,
+    pub indent: Indent,
+    pub semicolons: Semicolons,
+    pub quotes: QuoteStyle,
+    pub trailing_commas: TrailingCommas,
+    pub brace_spacing: bool,
+
+    /// Record the original positions of printed tokens, for `source_map`.
+    pub source_map: bool,
+
+    /// Leave out TypeScript and Flow annotations and declarations, printing
+    /// the plain JavaScript that they describe.
+    pub strip_types: bool,
+
+    /// Print numbers in their shortest form, like `1e6` or `.5`, and quote
+    /// strings with whichever quote needs fewer escapes.
+    pub shortest_literals: bool,
+}
+impl FormatOptions {
+    /// Readable output with two-space indentation.
+    pub fn pretty() -> FormatOptions {
+        FormatOptions {
+            pretty: true,
+            indent: Indent::Spaces(2),
+            semicolons: Semicolons::Always,
+            quotes: QuoteStyle::Single,
+            trailing_commas: TrailingCommas::Multiline,
+            brace_spacing: true,
+            source_map: false,
+            strip_types: false,
+            shortest_literals: false,
+        }
+    }
+}
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions {
+            pretty: false,
+            indent: Indent::Spaces(2),
+            semicolons: Semicolons::Always,
+            quotes: QuoteStyle::Single,
+            trailing_commas: TrailingCommas::Preserve,
+            brace_spacing: false,
+            source_map: false,
+            strip_types: false,
+            shortest_literals: false,
+        }
+    }
+}
+
+/// Serializes a node using the given options.
+pub fn format<T: NodeDisplay + ?Sized>(node: &T, options: &FormatOptions) -> Result<String, NodeDisplayError> {
+    let mut f = NodeFormatter::with_options(options.clone());
+    node.fmt(&mut f)?;
+    Ok(f.output)
+}
+
+/// Serializes a node along with a source map pointing back to the source
+/// file it was parsed from. `code` is the text of that file, which is
+/// embedded in the map if `include_content` is set.
+pub fn format_with_source_map<T: NodeDisplay + SourcePosition + ?Sized>(
+    node: &T,
+    options: &FormatOptions,
+    source: &str,
+    code: &str,
+    include_content: bool,
+) -> Result<(String, SourceMap), NodeDisplayError> {
+    let mut f = NodeFormatter::with_options(FormatOptions {
+        source_map: true,
+        ..options.clone()
+    });
+    f.node(node)?;
+
+    let map = f.source_map(source, code, include_content);
+    Ok((f.output, map))
+}
+
+/// A node printed while `record_nodes` was enabled, in the order the nodes
+/// were started.
+#[derive(Clone, Debug)]
+pub struct PrintedNode {
+    /// The range of the source the node was parsed from, if any.
+    pub position: Option<Range<usize>>,
+    pub output: Range<usize>,
+    pub parent: Option<usize>,
+}
+
+// A mapping from an offset in the output to an original line and column.
+#[derive(Debug)]
+struct OutputMapping {
+    offset: usize,
+    original: (usize, usize),
+    name: Option<String>,
+}
+
 pub type NodeDisplayResult = Result<(), NodeDisplayError>;
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Keyword(Keyword),
+    Punctuator(Punctuator),
+    Word,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum FrameKind {
+    Paren,
+    Curly,
+    Square,
+}
+
+// An open bracket pair, tracked so that its contents can be laid out
+// once they are known.
+#[derive(Debug)]
+struct Frame {
+    kind: FrameKind,
+
+    // Offset of the output just after the opening bracket.
+    start: usize,
+
+    // Set when the contents are a statement list printed by node_list.
+    block: bool,
+
+    // Set when the contents are a comma_list, with the offsets of its commas.
+    list: bool,
+    commas: Vec<usize>,
+}
+
 pub struct NodeFormatter {
     prec: Precedence,
     in_operator: bool,
@@ -201,10 +416,32 @@ pub struct NodeFormatter {
     ends_with_integer: bool,
     ends_with_keyword: bool,
 
+    options: FormatOptions,
+    in_jsx: bool,
+    // The name of a stripped enum and its members that references read from
+    // the enum's object.
+    enum_members: Option<(String, Vec<String>)>,
+    indent: usize,
+    line_start: usize,
+    space_next: bool,
+    last: Option<Token>,
+    pending_semicolon: Option<usize>,
+    frames: Vec<Frame>,
+
+    pending_mapping: Option<(usize, usize)>,
+    mappings: Vec<OutputMapping>,
+
+    printed: Option<Vec<PrintedNode>>,
+    printing: Option<usize>,
+
     pub output: String,
 }
 impl NodeFormatter {
     pub fn new() -> NodeFormatter {
+        NodeFormatter::with_options(Default::default())
+    }
+
+    pub fn with_options(options: FormatOptions) -> NodeFormatter {
         NodeFormatter {
             prec: Precedence::Normal,
             in_operator: true,
@@ -213,19 +450,139 @@ impl NodeFormatter {
             lookahead_restriction: None,
             ends_with_integer: false,
             ends_with_keyword: false,
+
+            options,
+            in_jsx: false,
+            enum_members: None,
+            indent: 0,
+            line_start: 0,
+            space_next: false,
+            last: None,
+            pending_semicolon: None,
+            frames: vec![],
+            pending_mapping: None,
+            mappings: vec![],
+            printed: None,
+            printing: None,
             output: String::with_capacity(512 * 1024),
         }
     }
 
+    fn pretty(&self) -> bool {
+        self.options.pretty && !self.in_jsx
+    }
+
+    /// Whether TypeScript and Flow syntax should be left out of the output.
+    pub fn strip_types(&self) -> bool {
+        self.options.strip_types
+    }
+
+    /// Builds a source map from the mappings recorded while printing, if the
+    /// `source_map` option was enabled. `code` is the source the printed nodes
+    /// were parsed from, needed to count original columns in UTF-16 code
+    /// units like source map consumers do.
+    pub fn source_map(&self, source: &str, code: &str, include_content: bool) -> SourceMap {
+        let mut map = SourceMap::new();
+        let source = map.add_source(source, if include_content { Some(code) } else { None });
+
+        let mut line_starts = vec![];
+        if !code.is_ascii() {
+            line_starts.push(0);
+            let mut chars = code.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\r' if chars.peek().map(|&(_, c)| c) == Some('\n') => {}
+                    '\n' | '\r' | '\u{2028}' | '\u{2029}' => line_starts.push(i + c.len_utf8()),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut line = 0;
+        let mut column = 0;
+        let mut offset = 0;
+        for mapping in self.mappings.iter() {
+            for c in self.output[offset..mapping.offset].chars() {
+                if c == '\n' {
+                    line += 1;
+                    column = 0;
+                } else {
+                    column += c.len_utf16();
+                }
+            }
+            offset = mapping.offset;
+
+            let (original_line, original_column) = mapping.original;
+            let original_column = match line_starts.get(original_line - 1) {
+                Some(&start) => code[start..].chars().take(original_column).map(char::len_utf16).sum(),
+                None => original_column,
+            };
+
+            let name = mapping.name.as_ref().map(|name| map.add_name(name));
+            map.add_mapping(Mapping {
+                generated_line: line,
+                generated_column: column,
+                source,
+                original_line: original_line - 1,
+                original_column,
+                name,
+            });
+        }
+
+        map
+    }
+
+    /// Start recording the output range of every node printed with `node`.
+    pub fn record_nodes(&mut self) {
+        self.printed = Some(vec![]);
+    }
+
+    /// Take the nodes recorded since `record_nodes` was called.
+    pub fn take_printed_nodes(&mut self) -> Vec<PrintedNode> {
+        self.printed.take().unwrap_or_default()
+    }
+
+    // Records that the token about to be written came from the given position,
+    // falling back to the start of the node currently being printed.
+    fn map_token(&mut self, position: Option<&PositionRange>, name: Option<&str>) {
+        if !self.options.source_map {
+            return;
+        }
+
+        let original = match (position, self.pending_mapping.take()) {
+            (Some(position), _) => position.start,
+            (None, Some(start)) => start,
+            (None, None) => return,
+        };
+
+        let offset = self.output.len();
+        if let Some(last) = self.mappings.last_mut() {
+            if last.offset == offset {
+                last.original = original;
+                last.name = name.map(Into::into);
+                return;
+            }
+        }
+        self.mappings.push(OutputMapping {
+            offset,
+            original,
+            name: name.map(Into::into),
+        });
+    }
+
     /// Set the active precedence
     pub fn precedence<'a>(&'a mut self, p: Precedence) -> FormatterLock<'a> {
         let wrap = (p as u32) < (self.prec as u32);
+        if wrap {
+            return self.wrap_parens_inner(true);
+        }
 
-        // TODO: does this need to set and restore the precedence too?
-
-        // println!("{:?} to {:?}: {}", self.prec, p, wrap);
+        // Operands printed without their own required precedence, like the
+        // left side of a binary operator, need at least this one.
+        let prec = self.prec;
+        self.prec = p;
 
-        self.wrap_parens_inner(wrap)
+        FormatterLock::new(self, Box::new(move |fmt| { fmt.prec = prec; }))
     }
 
     pub fn require_precedence<'a>(&'a mut self, p: Precedence) -> FormatterLock<'a> {
@@ -311,7 +668,7 @@ impl NodeFormatter {
     /// is currently disallowed.
     pub fn in_wrap_parens<'a>(&'a mut self) -> FormatterLock<'a> {
         let in_operator = self.in_operator;
-        self.wrap_parens_inner(in_operator)
+        self.wrap_parens_inner(!in_operator)
     }
 
     fn wrap_parens_inner<'a>(&'a mut self, wrap: bool) -> FormatterLock<'a> {
@@ -326,7 +683,8 @@ impl NodeFormatter {
         self.prec = Precedence::Normal;
         self.in_operator = true;
         self.wrap_standalone_if = false;
-        self.punctuator(Punctuator::ParenL);
+        self.punctuator(Punctuator::ParenL, &());
+        self.open_frame(FrameKind::Paren);
 
         FormatterLock::new(
             self,
@@ -334,7 +692,8 @@ impl NodeFormatter {
                 fmt.prec = prec;
                 fmt.in_operator = in_operator;
                 fmt.wrap_standalone_if = wrap_standalone_if;
-                fmt.punctuator(Punctuator::ParenR);
+                fmt.close_frame();
+                fmt.punctuator(Punctuator::ParenR, &());
             }),
         )
     }
@@ -346,19 +705,41 @@ impl NodeFormatter {
 
     /// Creates a formatter lock that wraps the output in curly brackets.
     pub fn wrap_curly<'a>(&'a mut self) -> FormatterLock<'a> {
+        self.wrap_curly_inner(Punctuator::CurlyL, Punctuator::CurlyR)
+    }
+
+    /// Creates a formatter lock that wraps the output in the "{|" and "|}"
+    /// of Flow's exact object types.
+    pub fn wrap_curly_bar<'a>(&'a mut self) -> FormatterLock<'a> {
+        self.wrap_curly_inner(Punctuator::CurlyBarL, Punctuator::CurlyBarR)
+    }
+
+    fn wrap_curly_inner<'a>(&'a mut self, open: Punctuator, close: Punctuator) -> FormatterLock<'a> {
         let wrap_standalone_if = self.wrap_standalone_if;
         let in_operator = self.in_operator;
 
         self.wrap_standalone_if = false;
         self.in_operator = true;
-        self.punctuator(Punctuator::CurlyL);
+        if self.pretty() && !self.space_next {
+            // Separate the brace from whatever precedes it, unless it opens
+            // a nested expression. The ">" closing type arguments isn't one.
+            let type_angle = matches!(self.last, Some(Token::Punctuator(Punctuator::RAngle)));
+            let open = self.output.ends_with("...") ||
+                self.output.ends_with(|c: char| "([{=>}!~+-".contains(c)) && !type_angle;
+            if !open {
+                self.space();
+            }
+        }
+        self.punctuator(open, &());
+        self.open_frame(FrameKind::Curly);
 
         FormatterLock::new(
             self,
             Box::new(move |fmt| {
                 fmt.wrap_standalone_if = wrap_standalone_if;
                 fmt.in_operator = in_operator;
-                fmt.punctuator(Punctuator::CurlyR);
+                fmt.close_frame();
+                fmt.punctuator(close, &());
             }),
         )
     }
@@ -370,18 +751,289 @@ impl NodeFormatter {
 
         self.wrap_standalone_if = false;
         self.in_operator = true;
-        self.punctuator(Punctuator::SquareL);
+        self.punctuator(Punctuator::SquareL, &());
+        self.open_frame(FrameKind::Square);
 
         FormatterLock::new(
             self,
             Box::new(move |fmt| {
                 fmt.wrap_standalone_if = wrap_standalone_if;
                 fmt.in_operator = in_operator;
-                fmt.punctuator(Punctuator::SquareR);
+                fmt.close_frame();
+                fmt.punctuator(Punctuator::SquareR, &());
             }),
         )
     }
 
+    /// Creates a formatter lock that indents any lines started within it.
+    pub fn indent<'a>(&'a mut self) -> FormatterLock<'a> {
+        let indent = self.indent;
+        if self.pretty() {
+            self.indent += 1;
+        }
+
+        FormatterLock::new(
+            self,
+            Box::new(move |fmt| { fmt.indent = indent; }),
+        )
+    }
+
+    /// Creates a formatter lock for printing JSX, which is always printed
+    /// without any added whitespace.
+    pub fn jsx<'a>(&'a mut self) -> FormatterLock<'a> {
+        let in_jsx = self.in_jsx;
+        self.in_jsx = true;
+
+        FormatterLock::new(
+            self,
+            Box::new(move |fmt| { fmt.in_jsx = in_jsx; }),
+        )
+    }
+
+    /// Creates a formatter lock within which references to the given members
+    /// of a stripped enum are read from the enum's object, as in "E['A']".
+    pub fn enum_members<'a>(&'a mut self, id: String, names: &[&str]) -> FormatterLock<'a> {
+        let enum_members = self.enum_members.take();
+        self.enum_members = Some((id, names.iter().map(|name| name.to_string()).collect()));
+
+        FormatterLock::new(
+            self,
+            Box::new(move |fmt| { fmt.enum_members = enum_members.clone(); }),
+        )
+    }
+
+    /// The enum whose object a reference to the given name reads from.
+    pub fn enum_member(&self, name: &str) -> Option<&str> {
+        match self.enum_members {
+            Some((ref id, ref names)) if names.iter().any(|n| n == name) => Some(id),
+            _ => None,
+        }
+    }
+
+    fn open_frame(&mut self, kind: FrameKind) {
+        if self.pretty() && kind != FrameKind::Paren {
+            self.indent += 1;
+        }
+        self.space_next = false;
+
+        self.frames.push(Frame {
+            kind,
+            start: self.output.len(),
+            block: false,
+            list: false,
+            commas: vec![],
+        });
+    }
+
+    fn close_frame(&mut self) {
+        let frame = self.frames.pop().expect("unbalanced formatter frames");
+        let pretty = self.pretty();
+        if pretty && frame.kind != FrameKind::Paren {
+            self.indent -= 1;
+        }
+        self.space_next = false;
+
+        if frame.block {
+            if pretty && self.output.len() > frame.start {
+                self.newline();
+            }
+        } else if frame.list {
+            self.layout_list(frame, pretty);
+        }
+    }
+
+    // Reformats the contents of a comma-separated list, now that it is known
+    // whether the list fits on a single line.
+    fn layout_list(&mut self, frame: Frame, pretty: bool) {
+        let content = self.output.split_off(frame.start);
+
+        // Each item is kept with its offset in the content, so that recorded
+        // mappings can follow it to its new location.
+        let mut items = vec![];
+        let mut offset = 0;
+        for &comma in frame.commas.iter() {
+            items.push(trim_item(&content, offset, comma - frame.start));
+            offset = comma - frame.start + 1;
+        }
+        items.push(trim_item(&content, offset, content.len()));
+
+        // An empty item after the last comma means the list ends in a trailing
+        // comma. The comma has to stay if it follows an array hole.
+        let trailing = items.len() > 1 && items[items.len() - 1].1.is_empty();
+        if trailing {
+            items.pop();
+        }
+        let last = items[items.len() - 1].1;
+        let hole = trailing && last.is_empty();
+
+        let multiline = pretty && frame.kind != FrameKind::Paren && content.contains('\n');
+        let trailing = hole || match self.options.trailing_commas {
+            TrailingCommas::Preserve => trailing,
+            TrailingCommas::Never => false,
+            TrailingCommas::Multiline => multiline && !last.starts_with("...") && !last.is_empty(),
+        };
+
+        let spacing = !multiline && pretty && self.options.brace_spacing &&
+            frame.kind == FrameKind::Curly && !content.trim().is_empty();
+        if spacing {
+            self.output += " ";
+        }
+
+        let mut moved = vec![];
+        let count = items.len();
+        for (i, (offset, item)) in items.into_iter().enumerate() {
+            if multiline {
+                self.indent += 1;
+                self.newline();
+                self.indent -= 1;
+            } else if pretty && i > 0 {
+                self.output += " ";
+            }
+
+            moved.push((frame.start + offset, item.len(), self.output.len()));
+            self.output += item;
+            if i + 1 != count || trailing {
+                self.output += ",";
+            }
+        }
+
+        if multiline {
+            self.newline();
+        } else if spacing {
+            self.output += " ";
+        }
+
+        // Mappings that pointed at separators are dropped along with them.
+        self.mappings.retain_mut(|mapping| {
+            if mapping.offset < frame.start {
+                return true;
+            }
+            let item = moved.iter().find(|&&(start, len, _)| {
+                start <= mapping.offset && mapping.offset < start + len
+            });
+            match item {
+                Some(&(start, _, new_start)) => {
+                    mapping.offset = mapping.offset - start + new_start;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    // Writes a single space unless the output already ends in whitespace.
+    fn space(&mut self) {
+        self.space_next = false;
+        if !self.output.is_empty() && !self.output.ends_with(char::is_whitespace) {
+            self.output += " ";
+        }
+        self.ends_with_keyword = false;
+        self.ends_with_integer = false;
+    }
+
+    // Starts a new line at the current indentation level.
+    fn newline(&mut self) {
+        self.space_next = false;
+        self.ends_with_keyword = false;
+        self.ends_with_integer = false;
+        if self.output.is_empty() || self.output.len() == self.line_start {
+            return;
+        }
+
+        let indent = match self.options.indent {
+            Indent::Spaces(n) => " ".repeat(n * self.indent),
+            Indent::Tabs => "\t".repeat(self.indent),
+        };
+        write!(self, "\n{}", indent).unwrap();
+        self.line_start = self.output.len();
+    }
+
+    // Inserts any whitespace that pretty mode needs before the given token.
+    fn before_token(&mut self, next: Token) {
+        if !self.pretty() {
+            return;
+        }
+
+        let last = self.last;
+        self.last = Some(next);
+
+        match (last, next) {
+            (Some(Token::Keyword(Keyword::Function)), Token::Punctuator(Punctuator::Star)) |
+            (Some(Token::Keyword(Keyword::Yield)), Token::Punctuator(Punctuator::Star)) |
+            (Some(Token::Keyword(Keyword::Import)), Token::Punctuator(Punctuator::ParenL)) |
+            (Some(Token::Keyword(Keyword::Checks)), Token::Punctuator(Punctuator::ParenL)) |
+            (Some(Token::Keyword(Keyword::Class)), Token::Punctuator(Punctuator::AngleL)) |
+            (Some(Token::Punctuator(Punctuator::AngleL)), Token::Keyword(Keyword::In)) => {
+                self.space_next = false;
+            }
+            (_, Token::Punctuator(Punctuator::ParenR)) |
+            (_, Token::Punctuator(Punctuator::SquareR)) |
+            (_, Token::Punctuator(Punctuator::CurlyR)) |
+            (_, Token::Punctuator(Punctuator::CurlyBarR)) |
+            (_, Token::Punctuator(Punctuator::TemplateClose)) |
+            (_, Token::Punctuator(Punctuator::Comma)) |
+            (_, Token::Punctuator(Punctuator::Semicolon)) |
+            (_, Token::Punctuator(Punctuator::Colon)) |
+            (_, Token::Punctuator(Punctuator::Period)) |
+            (_, Token::Punctuator(Punctuator::QuestionPeriod)) => {
+                self.space_next = false;
+            }
+            (_, Token::Keyword(Keyword::As)) |
+            (_, Token::Keyword(Keyword::Satisfies)) |
+            (_, Token::Keyword(Keyword::Is)) |
+            (_, Token::Keyword(Keyword::Extends)) |
+            (_, Token::Keyword(Keyword::Implements)) |
+            (_, Token::Keyword(Keyword::From)) |
+            (_, Token::Keyword(Keyword::Of)) |
+            (_, Token::Keyword(Keyword::In)) |
+            (_, Token::Keyword(Keyword::Instanceof)) |
+            (_, Token::Keyword(Keyword::Else)) |
+            (_, Token::Keyword(Keyword::Catch)) |
+            (_, Token::Keyword(Keyword::Finally)) |
+            (_, Token::Keyword(Keyword::While)) |
+            (_, Token::Punctuator(Punctuator::Mod)) => {
+                self.space();
+            }
+            (Some(Token::Punctuator(Punctuator::ParenR)), Token::Keyword(_)) |
+            (Some(Token::Punctuator(Punctuator::ParenR)), Token::Word) => {
+                self.space();
+            }
+            _ => {}
+        }
+
+        if self.space_next {
+            self.space();
+        }
+    }
+
+    // Decides whether pretty mode separates the given token from the next one.
+    fn after_token(&mut self, token: Token) {
+        if !self.pretty() {
+            return;
+        }
+
+        self.space_next = match token {
+            Token::Keyword(Keyword::This) |
+            Token::Keyword(Keyword::Super) |
+            Token::Keyword(Keyword::Null) |
+            Token::Keyword(Keyword::True) |
+            Token::Keyword(Keyword::False) |
+            Token::Keyword(Keyword::Target) |
+            Token::Keyword(Keyword::Meta) |
+            Token::Keyword(Keyword::Sent) |
+            Token::Keyword(Keyword::Arguments) => false,
+            Token::Keyword(_) => true,
+
+            Token::Punctuator(Punctuator::Comma) |
+            Token::Punctuator(Punctuator::Semicolon) |
+            Token::Punctuator(Punctuator::Colon) => true,
+            Token::Punctuator(Punctuator::Star) => {
+                self.output.ends_with("function*") || self.output.ends_with("yield*")
+            }
+            _ => false,
+        };
+    }
+
     /// Creates a formatter lock that disallows orphan "if" blocks
     /// (without else blocks) in the current scope.
     pub fn disallow_orphan_if<'a>(&'a mut self) -> FormatterLock<'a> {
@@ -405,28 +1057,123 @@ impl NodeFormatter {
     }
 
     /// Prints a list of items with commas between them.
-    pub fn comma_list<T: NodeDisplay>(&mut self, list: &[(T, KeywordData)]) -> NodeDisplayResult {
+    ///
+    /// When the list is the first thing inside of a bracket pair, the
+    /// commas are recorded so the list can be laid out when the brackets close.
+    pub fn comma_list<T: NodeDisplay + SourcePosition>(&mut self, list: &[(T, KeywordData)]) -> NodeDisplayResult {
+        let len = self.output.len();
+        let owned = match self.frames.last_mut() {
+            Some(frame) if frame.start == len && !frame.block => {
+                frame.list = true;
+                true
+            }
+            _ => false,
+        };
+
         let mut f = self.require_precedence(Precedence::Assignment);
 
-        for &(ref item, ref dat) in list {
+        for (item, dat) in list {
+            if f.is_flow_comment(item) {
+                continue;
+            }
             f.node(item)?;
+            if owned {
+                let offset = f.output.len();
+                f.frames.last_mut().unwrap().commas.push(offset);
+            }
             f.punctuator(Punctuator::Comma, dat);
         }
 
         Ok(())
     }
 
-    pub fn node_list<T: NodeDisplay>(&mut self, list: &[T]) -> NodeDisplayResult {
+    /// Prints a list of items, each on its own line in pretty mode.
+    pub fn node_list<T: NodeDisplay + SourcePosition>(&mut self, list: &[T]) -> NodeDisplayResult {
+        if !self.pretty() {
+            for item in list.iter() {
+                self.node(item)?;
+            }
+            return Ok(());
+        }
+
+        for item in list.iter() {
+            let (len, line_start) = (self.output.len(), self.line_start);
+            self.newline();
+            let start = self.output.len();
+            self.node(item)?;
+
+            // Items that print nothing, like types when they are stripped,
+            // don't get a line of their own.
+            if self.output.len() == start {
+                self.unwrite(len);
+                self.line_start = line_start;
+            } else if let Some(frame) = self.frames.last_mut() {
+                frame.block = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Removes the whitespace written since the output had the given length.
+    fn unwrite(&mut self, len: usize) {
+        self.output.truncate(len);
+        for node in self.printed.iter_mut().flatten() {
+            node.output.start = node.output.start.min(len);
+            node.output.end = node.output.end.min(len);
+        }
+    }
+
+    /// Prints decorators, on the same line as what they decorate.
+    pub fn decorators<T: NodeDisplay + SourcePosition>(&mut self, list: &[T]) -> NodeDisplayResult {
         for item in list.iter() {
             self.node(item)?;
+            if self.pretty() {
+                write!(self, " ")?;
+                self.ends_with_keyword = false;
+            }
         }
 
         Ok(())
     }
 
+    // Whether the node is in Flow's comment syntax, like "/*:: a: number */",
+    // whose code is only there for Flow and is left out with the types.
+    fn is_flow_comment<T: SourcePosition + ?Sized>(&self, s: &T) -> bool {
+        self.strip_types() && s.source_position().is_some_and(|p| p.in_flow_comment)
+    }
+
     /// Prints a given node.
-    pub fn node<T: NodeDisplay>(&mut self, s: &T) -> NodeDisplayResult {
-        s.fmt(self)
+    pub fn node<T: NodeDisplay + SourcePosition + ?Sized>(&mut self, s: &T) -> NodeDisplayResult {
+        if self.is_flow_comment(s) {
+            return Ok(());
+        }
+        if self.options.source_map && self.pending_mapping.is_none() {
+            self.pending_mapping = s.source_position().map(|p| p.range.start);
+        }
+
+        let start = self.output.len();
+        let index = match self.printed {
+            Some(ref mut printed) => {
+                printed.push(PrintedNode {
+                    position: s.source_position().map(|p| p.start..p.end),
+                    output: start..start,
+                    parent: self.printing,
+                });
+                Some(printed.len() - 1)
+            }
+            None => return s.fmt(self),
+        };
+
+        let parent = mem::replace(&mut self.printing, index);
+        let result = s.fmt(self);
+        self.printing = parent;
+
+        let end = self.output.len();
+        if let (Some(printed), Some(index)) = (self.printed.as_mut(), index) {
+            printed[index].output.end = end;
+        }
+        result
     }
 
     // pub fn separators<T: Separators>(&mut self, s: &T) {
@@ -434,12 +1181,14 @@ impl NodeFormatter {
     // }
 
     /// Prints a given keyword.
-    pub fn keyword<T: KeywordData>(&mut self, t: Keyword, _pos: &T) {
+    pub fn keyword<T: TokenData>(&mut self, t: Keyword, pos: &T) {
         // println!("{:?}", t);
 
+        self.before_token(Token::Keyword(t));
         if self.ends_with_keyword {
             write!(self, " ").unwrap();
         }
+        self.map_token(pos.position(), None);
         self.ends_with_keyword = true;
         self.ends_with_integer = false;
         self.lookahead_restriction = None;
@@ -494,11 +1243,52 @@ impl NodeFormatter {
             Keyword::Set => write!(self, "set"),
             Keyword::Static => write!(self, "static"),
             Keyword::As => write!(self, "as"),
-        }.unwrap()
+            Keyword::Interface => write!(self, "interface"),
+            Keyword::Type => write!(self, "type"),
+            Keyword::Enum => write!(self, "enum"),
+            Keyword::Declare => write!(self, "declare"),
+            Keyword::Abstract => write!(self, "abstract"),
+            Keyword::Implements => write!(self, "implements"),
+            Keyword::Public => write!(self, "public"),
+            Keyword::Private => write!(self, "private"),
+            Keyword::Protected => write!(self, "protected"),
+            Keyword::Readonly => write!(self, "readonly"),
+            Keyword::Override => write!(self, "override"),
+            Keyword::Out => write!(self, "out"),
+            Keyword::Keyof => write!(self, "keyof"),
+            Keyword::Unique => write!(self, "unique"),
+            Keyword::Is => write!(self, "is"),
+            Keyword::Satisfies => write!(self, "satisfies"),
+            Keyword::Infer => write!(self, "infer"),
+            Keyword::Asserts => write!(self, "asserts"),
+            Keyword::Namespace => write!(self, "namespace"),
+            Keyword::Module => write!(self, "module"),
+            Keyword::Global => write!(self, "global"),
+            Keyword::Opaque => write!(self, "opaque"),
+            Keyword::Checks => write!(self, "checks"),
+        }.unwrap();
+
+        self.after_token(Token::Keyword(t));
     }
 
     /// Prints a given punctuator.
-    pub fn punctuator(&mut self, p: Punctuator, _pos: &KeywordData) {
+    pub fn punctuator<T: TokenData>(&mut self, p: Punctuator, pos: &T) {
+        self.before_token(Token::Punctuator(p));
+        if p == Punctuator::Period && self.ends_with_integer {
+            // Otherwise the period would be read as a decimal point.
+            write!(self, " ").unwrap();
+        }
+        self.separate_from(match p {
+            Punctuator::Plus | Punctuator::PlusPlus | Punctuator::PlusEq => '+',
+            Punctuator::Minus | Punctuator::Subtract | Punctuator::MinusMinus | Punctuator::SubtractEq => '-',
+            Punctuator::Slash | Punctuator::SlashEq | Punctuator::SlashAngle => '/',
+            Punctuator::Star | Punctuator::StarEq | Punctuator::StarStar | Punctuator::StarStarEq => '*',
+            Punctuator::AngleL => '<',
+            Punctuator::Exclam => '!',
+            Punctuator::Eq | Punctuator::EqEq | Punctuator::EqEqEq | Punctuator::Arrow | Punctuator::ArrowStar => '=',
+            _ => ' ',
+        });
+        self.map_token(pos.position(), None);
         self.ends_with_keyword = false;
         self.ends_with_integer = false;
         self.lookahead_restriction = None;
@@ -511,6 +1301,8 @@ impl NodeFormatter {
             Punctuator::NeqEq => write!(self, "!=="),
             Punctuator::CurlyR => write!(self, "}}"),
             Punctuator::CurlyL => write!(self, "{{"),
+            Punctuator::CurlyBarR => write!(self, "|}}"),
+            Punctuator::CurlyBarL => write!(self, "{{|"),
             Punctuator::ParenR => write!(self, ")"),
             Punctuator::ParenL => write!(self, "("),
             Punctuator::SquareR => write!(self, "]"),
@@ -519,6 +1311,7 @@ impl NodeFormatter {
             Punctuator::AngleL => write!(self, "<"),
             Punctuator::Semicolon => write!(self, ";"),
             Punctuator::SQuote => write!(self, "'"),
+            Punctuator::DQuote => write!(self, "\""),
             Punctuator::Ellipsis => write!(self, "..."),
             Punctuator::Period => write!(self, "."),
             Punctuator::At => write!(self, "@"),
@@ -527,28 +1320,40 @@ impl NodeFormatter {
             Punctuator::Colon => write!(self, ":"),
             Punctuator::ColonColon => write!(self, "::"),
             Punctuator::Slash => write!(self, "/"),
+            Punctuator::SlashEq => write!(self, "/="),
             Punctuator::Star => write!(self, "*"),
+            Punctuator::StarEq => write!(self, "*="),
             Punctuator::StarStar => write!(self, "**"),
+            Punctuator::StarStarEq => write!(self, "**="),
             Punctuator::Plus => write!(self, "+"),
             Punctuator::PlusPlus => write!(self, "++"),
+            Punctuator::PlusEq => write!(self, "+="),
             Punctuator::Subtract => write!(self, "-"),
+            Punctuator::SubtractEq => write!(self, "-="),
             Punctuator::Minus => write!(self, "-"),
             Punctuator::MinusMinus => write!(self, "--"),
             Punctuator::Arrow => write!(self, "=>"),
             Punctuator::ArrowStar => write!(self, "=*>"),
             Punctuator::Caret => write!(self, "^"),
+            Punctuator::CaretEq => write!(self, "^="),
             Punctuator::LAngle => write!(self, "<"),
             Punctuator::LAngleEq => write!(self, "<="),
             Punctuator::LAngleAngle => write!(self, "<<"),
+            Punctuator::LAngleAngleEq => write!(self, "<<="),
             Punctuator::RAngle => write!(self, ">"),
             Punctuator::RAngleEq => write!(self, ">="),
             Punctuator::RAngleAngle => write!(self, ">>"),
+            Punctuator::RAngleAngleEq => write!(self, ">>="),
             Punctuator::RAngleAngleAngle => write!(self, ">>>"),
+            Punctuator::RAngleAngleAngleEq => write!(self, ">>>="),
             Punctuator::Mod => write!(self, "%"),
+            Punctuator::ModEq => write!(self, "%="),
             Punctuator::Amp => write!(self, "&"),
             Punctuator::AmpAmp => write!(self, "&&"),
+            Punctuator::AmpEq => write!(self, "&="),
             Punctuator::Bar => write!(self, "|"),
             Punctuator::BarBar => write!(self, "||"),
+            Punctuator::BarEq => write!(self, "|="),
             Punctuator::Bind => write!(self, "::"),
             Punctuator::Exclam => write!(self, "!"),
             Punctuator::Tilde => write!(self, "~"),
@@ -559,14 +1364,56 @@ impl NodeFormatter {
             Punctuator::SlashAngle => write!(self, "/>"),
             Punctuator::AngleSlash => write!(self, "</"),
             Punctuator::QuestionPeriod => write!(self, "?."),
-        }.unwrap()
+        }.unwrap();
+
+        self.after_token(Token::Punctuator(p));
+    }
+
+    // Print a space if a token starting with the given character would
+    // otherwise run into the end of the output as a different token, like
+    // "a - -b" printed as "a--b", "a / /b/" as a comment, "a < !--b" as an
+    // HTML comment or TypeScript's "a! == b" as "a!==b".
+    fn separate_from(&mut self, first: char) {
+        let last = self.output.chars().last();
+        if matches!((last, first), (Some('+'), '+') | (Some('-'), '-') | (Some('/'), '/') | (Some('/'), '*') | (Some('<'), '<') | (Some('<'), '!') | (Some('!'), '=')) {
+            write!(self, " ").unwrap();
+        }
+    }
+
+    /// Prints a binary operator, which pretty mode surrounds with spaces.
+    pub fn operator<T: TokenData>(&mut self, p: Punctuator, pos: &T) {
+        if self.pretty() {
+            self.space();
+        }
+        self.punctuator(p, pos);
+        if self.pretty() {
+            self.space_next = true;
+        }
+    }
+
+    /// Prints the semicolon terminating a statement, if the options call for it.
+    pub fn semicolon<T: TokenData>(&mut self, pos: &T) {
+        if self.options.semicolons == Semicolons::Always {
+            return self.punctuator(Punctuator::Semicolon, pos);
+        }
+
+        // The decision is deferred until the next token shows whether
+        // the statement would otherwise run on into it.
+        self.before_token(Token::Punctuator(Punctuator::Semicolon));
+        self.ends_with_keyword = false;
+        self.ends_with_integer = false;
+        self.lookahead_restriction = None;
+        self.pending_semicolon = Some(self.output.len());
+        self.after_token(Token::Punctuator(Punctuator::Semicolon));
     }
 
     /// Prints a given identifier.
     pub fn identifier(&mut self, name: &str, raw: Option<&str>) -> NodeDisplayResult {
+        self.before_token(Token::Word);
         if self.ends_with_keyword {
             write!(self, " ").unwrap();
         }
+        self.map_token(None, Some(name));
         self.ends_with_keyword = true;
         self.ends_with_integer = false;
         self.lookahead_restriction = None;
@@ -581,39 +1428,112 @@ impl NodeFormatter {
         Ok(())
     }
     pub fn string(&mut self, value: &str, raw: Option<&str>) -> NodeDisplayResult {
+        self.before_token(Token::Word);
+        self.ends_with_keyword = false;
+        self.ends_with_integer = false;
         self.lookahead_restriction = None;
 
-        self.punctuator(Punctuator::SQuote);
-        if let Some(ref raw) = raw {
-            // Ensure that single-quotes are escaped
-            write!(self, "{}", raw)?;
+        let text = raw.unwrap_or(value);
+        let (quote, other) = match self.options.quotes {
+            QuoteStyle::Single => ('\'', '"'),
+            QuoteStyle::Double => ('"', '\''),
+        };
+        let shortest = self.options.shortest_literals;
+        let (quote, other) = if shortest && text.matches(other).count() < text.matches(quote).count() {
+            (other, quote)
         } else {
-            write!(self, "{}", value)?;
-            // Serialize "value", escaping anything that _must_ be escaped,
-            // like newlines and slashes
+            (quote, other)
+        };
+
+        self.map_token(None, None);
+        self.write_char(quote)?;
+
+        // String values are kept in their escaped source form, so only quotes
+        // and line terminators that would end the literal need escaping.
+        let mut escaped = false;
+        for c in text.chars() {
+            if escaped {
+                escaped = false;
+                // The other quote doesn't need the escape it had in the source.
+                if !(shortest && c == other) {
+                    self.write_char('\\')?;
+                }
+                self.write_char(c)?;
+                continue;
+            }
+            match c {
+                '\\' => escaped = true,
+                '\n' => write!(self, "\\n")?,
+                '\r' => write!(self, "\\r")?,
+                '\u{2028}' => write!(self, "\\u2028")?,
+                '\u{2029}' => write!(self, "\\u2029")?,
+                c if c == quote => write!(self, "\\{}", c)?,
+                c => self.write_char(c)?,
+            }
         }
-        self.punctuator(Punctuator::SQuote);
+        self.write_char(quote)?;
 
         Ok(())
     }
-    pub fn number(&mut self, value: &f64, _raw: Option<&str>) -> NodeDisplayResult {
+    /// Prints a directive's value exactly as it was written, since changing
+    /// how it is escaped would change the directive.
+    pub fn directive(&mut self, value: &str) -> NodeDisplayResult {
+        self.before_token(Token::Word);
+        self.ends_with_keyword = false;
+        self.ends_with_integer = false;
+        self.lookahead_restriction = None;
+
+        let (preferred, other) = match self.options.quotes {
+            QuoteStyle::Single => ('\'', '"'),
+            QuoteStyle::Double => ('"', '\''),
+        };
+
+        // The value can only contain one kind of unescaped quote, the one
+        // that it was not originally quoted with.
+        let mut escaped = false;
+        let contains_preferred = value.chars().any(|c| {
+            let unescaped_quote = !escaped && c == preferred;
+            escaped = !escaped && c == '\\';
+            unescaped_quote
+        });
+        let quote = if contains_preferred { other } else { preferred };
+
+        self.map_token(None, None);
+        write!(self, "{}{}{}", quote, value, quote)?;
+        Ok(())
+    }
+
+    pub fn number(&mut self, value: &f64, raw: Option<&str>) -> NodeDisplayResult {
+        self.before_token(Token::Word);
         if self.ends_with_keyword {
             write!(self, " ").unwrap();
             self.ends_with_integer = false;
         }
+        self.map_token(None, None);
         self.lookahead_restriction = None;
 
-        let s = format!("{}", value);
+        // Only a literal too large to represent is infinite, so print one.
+        let s = if value.is_infinite() {
+            "1e999".to_string()
+        } else {
+            match raw {
+                _ if !self.options.shortest_literals => format!("{}", value),
+                Some(raw) if raw.len() < shortest_number(*value).len() => raw.to_string(),
+                _ => shortest_number(*value),
+            }
+        };
         write!(self, "{}", s)?;
 
-        // Serialize number
-        self.ends_with_integer = !s.chars().any(|c| c == '.');
-        self.ends_with_keyword = false;
+        // A number can't be directly followed by an identifier or keyword.
+        self.ends_with_integer = s.bytes().all(|b| b.is_ascii_digit());
+        self.ends_with_keyword = true;
 
         Ok(())
     }
 
     pub fn template_part(&mut self, value: &str, raw: Option<&str>) -> NodeDisplayResult {
+        self.space_next = false;
+        self.map_token(None, None);
         if let Some(ref raw) = raw {
             // Write raw value as-is
             write!(self, "{}", raw)?;
@@ -625,12 +1545,19 @@ impl NodeFormatter {
     }
 
     pub fn regexp(&mut self, value: &str, flags: &[char]) -> NodeDisplayResult {
-        self.punctuator(Punctuator::Slash);
+        self.before_token(Token::Word);
+        self.map_token(None, None);
+        self.punctuator(Punctuator::Slash, &());
         write!(self, "{}", value)?;
-        self.punctuator(Punctuator::Slash);
+        write!(self, "/")?;
         for f in flags.iter() {
             write!(self, "{}", f)?;
         }
+
+        // Any word following the closing slash would be read as more flags.
+        self.ends_with_keyword = true;
+        self.ends_with_integer = false;
+        self.lookahead_restriction = None;
         Ok(())
     }
 
@@ -644,8 +1571,19 @@ impl NodeFormatter {
         }
         Ok(())
     }
+    pub fn jsx_space(&mut self) -> NodeDisplayResult {
+        write!(self, " ")?;
+        Ok(())
+    }
     pub fn jsx_string(&mut self, value: &str, raw: Option<&str>) -> NodeDisplayResult {
-        self.punctuator(Punctuator::SQuote);
+        // Strings in JSX have no escapes, so the quotes must not appear in
+        // the string.
+        let quote = if raw.unwrap_or(value).contains('\'') {
+            Punctuator::DQuote
+        } else {
+            Punctuator::SQuote
+        };
+        self.punctuator(quote, &());
         if let Some(ref raw) = raw {
             // Write raw value as-is
             write!(self, "{}", raw)?;
@@ -653,7 +1591,7 @@ impl NodeFormatter {
             // Serialize "value", encoding all entities like {}<>
             write!(self, "{}", value)?;
         }
-        self.punctuator(Punctuator::SQuote);
+        self.punctuator(quote, &());
         Ok(())
     }
     pub fn jsx_text(&mut self, value: &str, raw: Option<&str>) -> NodeDisplayResult {
@@ -667,8 +1605,49 @@ impl NodeFormatter {
         Ok(())
     }
 }
+impl Default for NodeFormatter {
+    fn default() -> NodeFormatter {
+        NodeFormatter::new()
+    }
+}
 impl fmt::Write for NodeFormatter {
     fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(offset) = self.pending_semicolon {
+            if let Some(i) = s.find(|c: char| !c.is_whitespace()) {
+                self.pending_semicolon = None;
+
+                // Across a line break, ASI only fails when the next line could
+                // continue the statement. On the same line, only a closing
+                // curly can end it.
+                let c = s[i..].chars().next().unwrap();
+                let needed = if self.output[offset..].contains('\n') || s[..i].contains('\n') {
+                    "([`+-/".contains(c)
+                } else {
+                    c != '}'
+                };
+                if needed {
+                    self.output.insert(offset, ';');
+                    if self.line_start > offset {
+                        self.line_start += 1;
+                    }
+                    for frame in self.frames.iter_mut().filter(|frame| frame.start > offset) {
+                        frame.start += 1;
+                    }
+                    for mapping in self.mappings.iter_mut().filter(|mapping| mapping.offset >= offset) {
+                        mapping.offset += 1;
+                    }
+                    for node in self.printed.iter_mut().flatten() {
+                        if node.output.start >= offset {
+                            node.output.start += 1;
+                        }
+                        if node.output.end > offset {
+                            node.output.end += 1;
+                        }
+                    }
+                }
+            }
+        }
+
         self.output += s;
 
         Ok(())
@@ -678,12 +1657,12 @@ impl fmt::Write for NodeFormatter {
 
 pub struct FormatterLock<'a> {
     fmt: &'a mut NodeFormatter,
-    drop: Box<Fn(&mut NodeFormatter) + 'static>,
+    drop: Box<dyn Fn(&mut NodeFormatter) + 'static>,
 }
 impl<'a> FormatterLock<'a> {
     fn new(
         fmt: &'a mut NodeFormatter,
-        drop: Box<Fn(&mut NodeFormatter) + 'static>,
+        drop: Box<dyn Fn(&mut NodeFormatter) + 'static>,
     ) -> FormatterLock<'a> {
         FormatterLock { fmt, drop }
     }
@@ -723,10 +1702,9 @@ pub trait NodeDisplay {
 }
 
 
-impl<T: NodeDisplay> NodeDisplay for Box<T> {
+impl<T: NodeDisplay> NodeDisplay for NodeBox<T> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        let ref n = **self;
-        n.fmt(f)
+        stack::grow(|| (**self).fmt(f))
     }
 }
 
@@ -739,20 +1717,303 @@ impl<T: NodeDisplay> NodeDisplay for Option<T> {
     }
 }
 
-trait KeywordData {
-  fn leading(&self) {
-
-  }
+// Formats a finite number as the shorter of its decimal and exponent forms,
+// e.g. "1e21" rather than 22 digits, or "15e-8" rather than "0.00000015",
+// leaving out the zero before the point of fractions like ".5".
+fn shortest_number(value: f64) -> String {
+    let decimal = format!("{}", value);
+    let decimal = match decimal.strip_prefix("0.") {
+        Some(fraction) => format!(".{}", fraction),
+        None => decimal.replacen("-0.", "-.", 1),
+    };
+
+    let exponent = format!("{:e}", value);
+    let (mantissa, exp) = match exponent.find('e') {
+        Some(i) => (&exponent[..i], exponent[i + 1..].parse::<i32>().unwrap_or(0)),
+        None => return decimal,
+    };
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let exp = exp - (digits.len() as i32 - 1);
+
+    let compact = if exp == 0 {
+        format!("{}{}", sign, digits)
+    } else {
+        format!("{}{}e{}", sign, digits, exp)
+    };
+    if compact.len() < decimal.len() {
+        compact
+    } else {
+        decimal
+    }
+}
 
-  fn keyword(&self) {
+// Returns a list item's offset and text without surrounding whitespace.
+fn trim_item(content: &str, start: usize, end: usize) -> (usize, &str) {
+    let item = &content[start..end];
+    let trimmed = item.trim_start();
+    (start + item.len() - trimmed.len(), trimmed.trim_end())
+}
 
-  }
+/// Provides the source position of a node being printed, if it has one.
+pub trait SourcePosition {
+    fn source_position(&self) -> Option<&NodePosition>;
+}
+impl<T: SourcePosition> SourcePosition for NodeBox<T> {
+    fn source_position(&self) -> Option<&NodePosition> {
+        (**self).source_position()
+    }
+}
+impl<T: SourcePosition> SourcePosition for Option<T> {
+    fn source_position(&self) -> Option<&NodePosition> {
+        self.as_ref().and_then(SourcePosition::source_position)
+    }
+}
 
-  fn trailing(&self) {
+/// Source information stored alongside a keyword or punctuator token in the AST.
+pub trait TokenData {
+    fn position(&self) -> Option<&PositionRange>;
+}
 
-  }
+// Used for tokens that the AST does not store any information for.
+impl TokenData for () {
+    fn position(&self) -> Option<&PositionRange> {
+        None
+    }
+}
+impl TokenData for MaybeTokenPosition {
+    fn position(&self) -> Option<&PositionRange> {
+        self.as_ref().map(|p| &**p)
+    }
 }
+impl TokenData for KeywordData {
+    fn position(&self) -> Option<&PositionRange> {
+        self.position.position()
+    }
+}
+impl TokenData for KeywordSuffixData {
+    fn position(&self) -> Option<&PositionRange> {
+        self.position.position()
+    }
+}
+impl TokenData for KeywordWrappedData {
+    fn position(&self) -> Option<&PositionRange> {
+        self.position.position()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
 
-impl KeywordData for ast::KeywordData {
+    fn assert_format(code: &str, options: &FormatOptions, expected: &str) {
+        let module = Parser::new(code.into_tokenizer()).parse_module().unwrap();
+
+        assert_eq!(format(&module, options).unwrap(), expected);
+    }
 
+    #[test]
+    fn it_prints_minified_by_default() {
+        assert_format(
+            "if (a) { b(1, 2); } else c = d + 1;",
+            &Default::default(),
+            "if(a){b(1,2);}else c=d+1;",
+        );
+    }
+
+    #[test]
+    fn it_pretty_prints() {
+        assert_format(
+            "import {a, b as c} from \"x\"; function f(a, ...b) { if (a) { return b; } else x += -1; } do f(); while (a) for (let i = 0; i < 2; i++);",
+            &FormatOptions::pretty(),
+            "import { a, b as c } from 'x';\nfunction f(a, ...b) {\n  if (a) {\n    return b;\n  } else x += -1;\n}\ndo f(); while (a);\nfor (let i = 0; i < 2; i++);",
+        );
+    }
+
+    #[test]
+    fn it_indents_switch_cases() {
+        assert_format(
+            "switch (a) { case 1: b(); break; default: c(); }",
+            &FormatOptions::pretty(),
+            "switch (a) {\n  case 1:\n    b();\n    break;\n  default:\n    c();\n}",
+        );
+    }
+
+    #[test]
+    fn it_indents_with_tabs() {
+        assert_format(
+            "function f() { return function* () { yield* a; }; }",
+            &FormatOptions {
+                indent: Indent::Tabs,
+                ..FormatOptions::pretty()
+            },
+            "function f() {\n\treturn function* () {\n\t\tyield* a;\n\t};\n}",
+        );
+    }
+
+    #[test]
+    fn it_breaks_multiline_lists() {
+        let code = "x = {a: 1, b() { return [1, 2]; }, ...c}; y = [function () {}, {d: 2}];";
+
+        assert_format(code, &FormatOptions::pretty(), "x = {\n  a: 1,\n  b() {\n    return [1, 2];\n  },\n  ...c\n};\ny = [function () {}, { d: 2 }];");
+        assert_format(
+            "x = {a: 1, b() {}, c};",
+            &FormatOptions::pretty(),
+            "x = { a: 1, b() {}, c };",
+        );
+        assert_format(
+            "x = {a: 1, b() { c(); }};",
+            &FormatOptions::pretty(),
+            "x = {\n  a: 1,\n  b() {\n    c();\n  },\n};",
+        );
+    }
+
+    #[test]
+    fn it_applies_trailing_commas() {
+        let code = "x = {a: 1, b: 2,}; y = [1,,]; f(a, b,);";
+
+        assert_format(code, &Default::default(), "x={a:1,b:2,};y=[1,,];f(a,b,);");
+        assert_format(code, &FormatOptions {
+            trailing_commas: TrailingCommas::Never,
+            ..Default::default()
+        }, "x={a:1,b:2};y=[1,,];f(a,b);");
+        assert_format(code, &FormatOptions::pretty(), "x = { a: 1, b: 2 };\ny = [1, ,];\nf(a, b);");
+    }
+
+    #[test]
+    fn it_applies_brace_spacing() {
+        assert_format(
+            "let {a, b: {c}} = {a: 1}; export {a};",
+            &FormatOptions {
+                brace_spacing: false,
+                ..FormatOptions::pretty()
+            },
+            "let {a, b: {c}} = {a: 1};\nexport {a};",
+        );
+    }
+
+    #[test]
+    fn it_applies_quote_style() {
+        let code = "a = 'it\\'s'; b = \"say \\\"hi\\\"\"; c = \"it's\";";
+
+        assert_format(code, &Default::default(), "a='it\\'s';b='say \\\"hi\\\"';c='it\\'s';");
+        assert_format(code, &FormatOptions {
+            quotes: QuoteStyle::Double,
+            ..Default::default()
+        }, "a=\"it\\'s\";b=\"say \\\"hi\\\"\";c=\"it's\";");
+    }
+
+    #[test]
+    fn it_omits_semicolons_as_needed() {
+        let options = FormatOptions {
+            semicolons: Semicolons::AsNeeded,
+            ..FormatOptions::pretty()
+        };
+
+        assert_format(
+            "let a = 1; (function () {})(); [a] = b; a++; function f() { return; }",
+            &options,
+            "let a = 1;\n(function () {})();\n[a] = b\na++\nfunction f() {\n  return\n}",
+        );
+        assert_format("if (a) b(); else c();", &options, "if (a) b(); else c()");
+        assert_format("a(); b();", &FormatOptions {
+            semicolons: Semicolons::AsNeeded,
+            ..Default::default()
+        }, "a();b()");
+    }
+
+    #[test]
+    fn it_keeps_tokens_apart() {
+        assert_format(
+            "a = b - -c + +d; e = f / /g/ / h; i = 1..toString() + 2.5.toFixed(); j = 1e999;",
+            &Default::default(),
+            "a=b- -c+ +d;e=f/ /g/ /h;i=1 .toString()+2.5.toFixed();j=1e999;",
+        );
+    }
+
+    #[test]
+    fn it_keeps_words_apart_from_regexps_and_numbers() {
+        assert_format(
+            "x = /re/ instanceof RegExp; y = /a/g in o; z = 1 in o;",
+            &Default::default(),
+            "x=/re/ instanceof RegExp;y=/a/g in o;z=1 in o;",
+        );
+    }
+
+    #[test]
+    fn it_prints_the_shortest_literals() {
+        let options = FormatOptions {
+            shortest_literals: true,
+            ..Default::default()
+        };
+        assert_format(
+            "a = [1e21, 1e300, 1.5e-7, 1230000, 0.001, 100, 1.5, 0.5, 0x10];",
+            &options,
+            "a=[1e21,1e300,15e-8,123e4,.001,100,1.5,.5,16];",
+        );
+        assert_format(
+            "a = [\"it's\", 'it\\'s', 'say \"hi\"', \"'\\\"\"];",
+            &options,
+            "a=[\"it's\",\"it's\",'say \"hi\"','\\'\"'];",
+        );
+    }
+
+    #[test]
+    fn it_prints_numbers_in_decimal_by_default() {
+        assert_format("a = [1.5e-7, 0.5, 0x10];", &Default::default(), "a=[0.00000015,0.5,16];");
+    }
+
+    #[test]
+    fn it_prints_directives_unchanged() {
+        assert_format(
+            "function f() { \"it's\"; 'say \"hi\"'; 'a\\'b'; }",
+            &Default::default(),
+            "function f(){\"it's\";'say \"hi\"';'a\\'b';}",
+        );
+    }
+
+    #[test]
+    fn it_prints_template_literals() {
+        assert_format("t = `a${b}c${d}e`;", &Default::default(), "t=`a${b}c${d}e`;");
+    }
+
+    #[test]
+    fn it_generates_source_maps() {
+        let code = "let a = 1;\nfoo(a, {b: 2});";
+        let module = Parser::new(code.into_tokenizer()).parse_module().unwrap();
+
+        let (output, map) = format_with_source_map(&module, &Default::default(), "in.js", code, true).unwrap();
+        assert_eq!(output, "let a=1;foo(a,{b:2});");
+        assert_eq!(map.names, vec!["a", "foo", "b"]);
+        assert_eq!(map.sources_content, vec![Some(code.to_string())]);
+        assert_eq!(map.encode_mappings(), "AAAA,IAAIA,CAAE,CAAE,CAAC,CACTC,GAAG,CAACD,EAAG,CAACE,EAAG,GAAG");
+
+        // Re-laid out lists keep the mappings of their items.
+        let (output, map) = format_with_source_map(&module, &FormatOptions::pretty(), "in.js", code, false).unwrap();
+        assert_eq!(output, "let a = 1;\nfoo(a, { b: 2 });");
+        assert_eq!(map.encode_mappings(), "AAAA,IAAIA,EAAE,EAAE,CAAC;AACTC,GAAG,CAACD,GAAG,EAACE,GAAG,IAAG");
+        assert_eq!(
+            map.to_json(),
+            "{\"version\":3,\"sources\":[\"in.js\"],\"names\":[\"a\",\"foo\",\"b\"],\
+             \"mappings\":\"AAAA,IAAIA,EAAE,EAAE,CAAC;AACTC,GAAG,CAACD,GAAG,EAACE,GAAG,IAAG\"}"
+        );
+    }
+
+    #[test]
+    fn it_counts_source_map_columns_in_utf16() {
+        let code = "'\u{1F600}'; foo;";
+        let module = Parser::new(code.into_tokenizer()).parse_module().unwrap();
+
+        let (output, map) = format_with_source_map(&module, &Default::default(), "in.js", code, false).unwrap();
+        assert_eq!(output, "'\u{1F600}';foo;");
+
+        let foo = map.mappings.iter().find(|m| m.name == Some(0)).unwrap();
+        assert_eq!(map.names, vec!["foo"]);
+        assert_eq!((foo.generated_column, foo.original_column), (5, 6));
+    }
 }
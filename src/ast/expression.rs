@@ -1,6 +1,4 @@
-use std::string;
-
-use ast::{MaybeTokenPosition, KeywordData, KeywordSuffixData, KeywordWrappedData, SeparatorTokens, SeparatorTokensSingleLine};
+use ast::{MaybeTokenPosition, KeywordData, KeywordSuffixData, KeywordWrappedData, SeparatorTokens, SeparatorTokensSingleLine, Str, NodeBox};
 
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punctuator, Precedence,
                    LookaheadSequence};
@@ -11,14 +9,14 @@ use ast::alias;
 
 use ast::patterns::{LeftHandSimpleAssign, LeftHandComplexAssign};
 use ast::statement::BlockStatement;
-use ast::general::{ReferenceIdentifier, PropertyIdentifier};
+use ast::general::PropertyIdentifier;
 
 
 // this
 node!(#[derive(Default)] pub struct ThisExpression {
     pub token_this: MaybeTokenPosition,
 });
-impl NodeDisplay for ThisExpression {
+impl<'src> NodeDisplay for ThisExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::This, &self.token_this);
         Ok(())
@@ -37,21 +35,22 @@ mod tests_this {
 
 node!(pub struct ParenthesizedExpression {
     pub token_paren_l: KeywordSuffixData,
-    pub expr: Box<alias::Expression>,
+    pub expr: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
 });
-impl NodeDisplay for ParenthesizedExpression {
+impl<'src> NodeDisplay for ParenthesizedExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.wrap_parens().node(&self.expr)
     }
 }
-impl<T: Into<alias::Expression>> From<T> for ParenthesizedExpression {
-    fn from(expr: T) -> ParenthesizedExpression {
+impl<'src> ParenthesizedExpression<'src> {
+    pub fn new<T: Into<alias::Expression<'src>>>(expr: T) -> ParenthesizedExpression<'src> {
         ParenthesizedExpression {
             token_paren_l: Default::default(),
-            expr: Box::new(expr.into()),
+            expr: NodeBox::new(expr.into()),
             token_paren_r: Default::default(),
             position: None,
+            comments: None,
         }
     }
 }
@@ -62,7 +61,7 @@ mod tests_paren_expr {
     #[test]
     fn it_prints() {
         assert_serialize!(
-            ParenthesizedExpression::from(ThisExpression::default()),
+            ParenthesizedExpression::new(ThisExpression::default()),
             "(this)"
         );
     }
@@ -70,11 +69,11 @@ mod tests_paren_expr {
 
 // fn`content`
 node!(pub struct TaggedTemplateLiteral {
-    pub tag: Box<alias::Expression>,
-    pub tokens_sep: SeparatorTokens,
-    pub template: TemplateLiteral,
+    pub tag: NodeBox<alias::Expression<'src>>,
+    pub tokens_sep: SeparatorTokens<'src>,
+    pub template: TemplateLiteral<'src>,
 });
-impl NodeDisplay for TaggedTemplateLiteral {
+impl<'src> NodeDisplay for TaggedTemplateLiteral<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.require_precedence(Precedence::Member).node(&self.tag)?;
         f.node(&self.template)
@@ -85,18 +84,18 @@ impl NodeDisplay for TaggedTemplateLiteral {
 // `content`
 node!(pub struct TemplateLiteral {
     pub token_tick_open: MaybeTokenPosition,
-    pub parts: Vec<(TemplatePart, KeywordSuffixData, alias::Expression, KeywordData)>,
-    pub last_part: TemplatePart,
+    pub parts: Vec<(TemplatePart<'src>, KeywordSuffixData, alias::Expression<'src>, KeywordData)>,
+    pub last_part: TemplatePart<'src>,
     pub token_tick_close: MaybeTokenPosition,
 });
-impl NodeDisplay for TemplateLiteral {
+impl<'src> NodeDisplay for TemplateLiteral<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.punctuator(Punctuator::TemplateTick, &self.token_tick_open);
-        for &(ref part, ref close, ref expr, ref open) in self.parts.iter() {
+        for (part, open, expr, close) in self.parts.iter() {
             f.node(part)?;
-            f.punctuator(Punctuator::TemplateClose, close);
-            f.allow_in().node(expr)?;
             f.punctuator(Punctuator::TemplateOpen, open);
+            f.allow_in().node(expr)?;
+            f.punctuator(Punctuator::TemplateClose, close);
         }
         f.node(&self.last_part)?;
         f.punctuator(Punctuator::TemplateTick, &self.token_tick_close);
@@ -106,35 +105,36 @@ impl NodeDisplay for TemplateLiteral {
 
 
 node!(pub struct TemplatePart {
-    pub value: string::String,
-    pub raw_value: Option<string::String>,
+    pub value: Str<'src>,
+    pub raw_value: Option<Str<'src>>,
 });
-impl NodeDisplay for TemplatePart {
+impl<'src> NodeDisplay for TemplatePart<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.template_part(&self.value, self.raw_value.as_ref().map(String::as_str))
+        f.template_part(&self.value, self.raw_value.as_deref())
     }
 }
 
 
 node_enum!(@node_display pub enum CallArgument {
-    Expression(CallArgumentExpression),
-    Spread(CallArgumentSpread),
+    Expression(CallArgumentExpression<'src>),
+    Spread(CallArgumentSpread<'src>),
 });
-impl From<alias::Expression> for CallArgument {
+impl<'src> From<alias::Expression<'src>> for CallArgument<'src> {
     fn from(e: alias::Expression) -> CallArgument {
         CallArgumentExpression {
             tokens_prefix: Default::default(),
             expression: e.into(),
             position: None,
+            comments: None,
         }.into()
     }
 }
 
 node!(pub struct CallArgumentExpression {
-    pub tokens_prefix: SeparatorTokens,
-    pub expression: Box<alias::Expression>,
+    pub tokens_prefix: SeparatorTokens<'src>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for CallArgumentExpression {
+impl<'src> NodeDisplay for CallArgumentExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.expression)
     }
@@ -142,11 +142,11 @@ impl NodeDisplay for CallArgumentExpression {
 
 node!(pub struct CallArgumentSpread {
     pub token_ellipsis: KeywordData,
-    pub expression: Box<alias::Expression>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for CallArgumentSpread {
+impl<'src> NodeDisplay for CallArgumentSpread<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &self.token_ellipsis);
         f.node(&self.expression)
     }
 }
@@ -154,32 +154,35 @@ impl NodeDisplay for CallArgumentSpread {
 node!(#[derive(Default)] pub struct CallArguments {
     pub token_paren_l: KeywordSuffixData,
 
-    pub args: Vec<(CallArgument, KeywordData)>,
-    pub last_arg: Option<CallArgument>,
+    pub args: Vec<(CallArgument<'src>, KeywordData)>,
+    pub last_arg: Option<CallArgument<'src>>,
 
     pub token_paren_r: KeywordData,
 });
-impl CallArguments {
+impl<'src> CallArguments<'src> {
     pub fn is_empty(&self) -> bool {
         self.args.is_empty() && self.last_arg.is_none()
     }
 }
-impl NodeDisplay for CallArguments {
+impl<'src> NodeDisplay for CallArguments<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_parens();
 
         f.comma_list(&self.args)?;
-        f.node(&self.last_arg)
+        f.require_precedence(Precedence::Assignment).node(&self.last_arg)?;
+        Ok(())
     }
 }
-impl From<Vec<alias::Expression>> for CallArguments {
-    fn from(v: Vec<alias::Expression>) -> CallArguments {
+impl<'src> From<Vec<alias::Expression<'src>>> for CallArguments<'src> {
+    fn from(mut v: Vec<alias::Expression>) -> CallArguments {
+        let last_arg = v.pop().map(Into::into);
         CallArguments {
             token_paren_l: Default::default(),
             args: v.into_iter().map(|e| (e.into(), Default::default())).collect(),
-            last_arg: Default::default(),
+            last_arg,
             token_paren_r: Default::default(),
             position: None,
+            comments: None,
         }
     }
 }
@@ -213,9 +216,11 @@ mod tests_call_args {
             CallArguments::from(vec![
                 ReferenceIdentifier::from("arg1").into(),
                 SequenceExpression {
+                    token_comma: Default::default(),
                     left: ThisExpression::default().into(),
                     right: literal::Boolean::from(true).into(),
                     position: None,
+                    comments: None,
                 }.into(),
                 ReferenceIdentifier::from("arg3").into(),
             ]),
@@ -225,44 +230,52 @@ mod tests_call_args {
 
     #[test]
     fn it_prints_args_with_spread() {
-        assert_serialize!(
-            CallArguments {
-                args: vec![
-                    ThisExpression::default().into(),
-                    ReferenceIdentifier::from("arg").into(),
-                    literal::Boolean::from(true).into(),
-                ],
-                spread: literal::Numeric::from(3.6).into(),
-                position: None,
-            },
-            "(this,arg,true,...3.6)"
-        );
+        let mut args = CallArguments::from(vec![
+            ThisExpression::default().into(),
+            ReferenceIdentifier::from("arg").into(),
+            literal::Boolean::from(true).into(),
+        ]);
+        let last = args.last_arg.take().unwrap();
+        args.args.push((last, Default::default()));
+        args.last_arg = Some(CallArgumentSpread {
+            token_ellipsis: Default::default(),
+            expression: alias::Expression::from(literal::Numeric::from(3.6)).into(),
+            position: None,
+            comments: None,
+        }.into());
+
+        assert_serialize!(args, "(this,arg,true,...3.6)");
     }
 
     #[test]
     fn it_prints_args_with_spread_and_precedence() {
-        assert_serialize!(
-            CallArguments {
-                args: vec![ReferenceIdentifier::from("arg").into()],
-                spread: SequenceExpression {
-                    left: ThisExpression::default().into(),
-                    right: literal::Boolean::from(true).into(),
-                    position: None,
-                }.into(),
+        let mut args = CallArguments::from(vec![ReferenceIdentifier::from("arg").into()]);
+        let last = args.last_arg.take().unwrap();
+        args.args.push((last, Default::default()));
+        args.last_arg = Some(CallArgumentSpread {
+            token_ellipsis: Default::default(),
+            expression: alias::Expression::from(SequenceExpression {
+                token_comma: Default::default(),
+                left: ThisExpression::default().into(),
+                right: literal::Boolean::from(true).into(),
                 position: None,
-            },
-            "(arg,...(this,true))"
-        );
+                comments: None,
+            }).into(),
+            position: None,
+            comments: None,
+        }.into());
+
+        assert_serialize!(args, "(arg,...(this,true))");
     }
 }
 
 
 // foo()
 node!(pub struct CallExpression {
-    pub callee: Box<alias::Expression>,
-    pub arguments: CallArguments,
+    pub callee: NodeBox<alias::Expression<'src>>,
+    pub arguments: CallArguments<'src>,
 });
-impl NodeDisplay for CallExpression {
+impl<'src> NodeDisplay for CallExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // TODO: I think this sometimes adds parens when not needed?
         f.require_precedence(Precedence::New).node(&self.callee)?;
@@ -272,35 +285,39 @@ impl NodeDisplay for CallExpression {
 
 // foo?.()
 node!(pub struct OptionalCallExpression {
-    pub callee: Box<alias::Expression>,
+    pub callee: NodeBox<alias::Expression<'src>>,
     pub token_question: KeywordData,
-    pub arguments: CallArguments,
+    pub arguments: CallArguments<'src>,
 });
-impl NodeDisplay for OptionalCallExpression {
+impl<'src> NodeDisplay for OptionalCallExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // TODO: I think this sometimes adds parens when not needed?
         f.require_precedence(Precedence::New).node(&self.callee)?;
-        f.punctuator(Punctuator::QuestionPeriod);
+        f.punctuator(Punctuator::QuestionPeriod, &self.token_question);
         f.node(&self.arguments)
     }
 }
 
-node_enum!(pub enum NewExpression {
-    Normal(NormalNewExpression),
-    Empty(EmptyNewExpression),
+node_enum!(@node_display pub enum NewExpression {
+    Normal(NormalNewExpression<'src>),
+    Empty(EmptyNewExpression<'src>),
 });
 
 // new foo()
 node!(pub struct NormalNewExpression {
     pub token_new: KeywordSuffixData,
-    pub callee: Box<alias::Expression>,
-    pub arguments: CallArguments,
+    pub callee: NodeBox<alias::Expression<'src>>,
+    pub arguments: CallArguments<'src>,
 });
-impl NodeDisplay for NormalNewExpression {
+impl<'src> NodeDisplay for NormalNewExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Member);
-        f.keyword(Keyword::New);
-        f.require_precedence(Precedence::Member).node(&self.callee)?;
+        f.keyword(Keyword::New, &self.token_new);
+        if has_call(&self.callee) {
+            f.wrap_parens().node(&self.callee)?;
+        } else {
+            f.require_precedence(Precedence::Member).node(&self.callee)?;
+        }
         f.node(&self.arguments)?;
         Ok(())
     }
@@ -309,27 +326,46 @@ impl NodeDisplay for NormalNewExpression {
 // new foo
 node!(pub struct EmptyNewExpression {
     pub token_new: KeywordSuffixData,
-    pub callee: Box<alias::Expression>,
+    pub callee: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for EmptyNewExpression {
+impl<'src> NodeDisplay for EmptyNewExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::New);
-        f.keyword(Keyword::New);
-        f.require_precedence(Precedence::New).node(&self.callee)?;
+        f.keyword(Keyword::New, &self.token_new);
+        if has_call(&self.callee) {
+            f.wrap_parens().node(&self.callee)?;
+        } else {
+            f.require_precedence(Precedence::New).node(&self.callee)?;
+        }
         Ok(())
     }
 }
 
+// Whether an expression is a call or optional chain, or a member of one,
+// which as the callee of "new" would take its arguments from it.
+fn has_call(expr: &alias::Expression) -> bool {
+    match *expr {
+        alias::Expression::Call(_) |
+        alias::Expression::OptionalCall(_) |
+        alias::Expression::OptionalMember(_) |
+        alias::Expression::ImportCall(_) |
+        alias::Expression::SuperCall(_) => true,
+        alias::Expression::Member(ref member) => has_call(&member.object),
+        alias::Expression::TaggedTemplate(ref template) => has_call(&template.tag),
+        _ => false,
+    }
+}
+
 
 // experimental
 // import(foo)
 node!(pub struct ImportCallExpression {
     pub token_import: MaybeTokenPosition,
-    pub argument: Box<alias::Expression>,
+    pub argument: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ImportCallExpression {
+impl<'src> NodeDisplay for ImportCallExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Import);
+        f.keyword(Keyword::Import, &self.token_import);
 
         let mut f = f.wrap_parens();
         f.require_precedence(Precedence::Assignment).node(
@@ -343,11 +379,11 @@ impl NodeDisplay for ImportCallExpression {
 
 node!(pub struct SuperCallExpression {
     pub token_super: MaybeTokenPosition,
-    pub arguments: CallArguments,
+    pub arguments: CallArguments<'src>,
 });
-impl NodeDisplay for SuperCallExpression {
+impl<'src> NodeDisplay for SuperCallExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Super);
+        f.keyword(Keyword::Super, &self.token_super);
         f.node(&self.arguments)
     }
 }
@@ -357,10 +393,10 @@ impl NodeDisplay for SuperCallExpression {
 // foo[bar]
 // foo.#bar
 node!(pub struct MemberExpression {
-    pub object: Box<alias::Expression>,
-    pub property: PropertyAccess,
+    pub object: NodeBox<alias::Expression<'src>>,
+    pub property: PropertyAccess<'src>,
 });
-impl NodeDisplay for MemberExpression {
+impl<'src> NodeDisplay for MemberExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // TODO: I think this sometimes adds parens when not needed?
         // Member expressions can either be part of the MemberExpression grammar or
@@ -378,7 +414,7 @@ fn get_sequence(expr: &MemberExpression) -> LookaheadSequence {
     use ast::alias::Expression::Binding;
 
     match *expr.object {
-        Binding(ReferenceIdentifier { ref value, .. }) if value == "let" => {
+        Binding(ref id) if id.value == "let" => {
             match expr.property {
                 PropertyAccess::Computed(_) => LookaheadSequence::LetSquare,
                 _ => LookaheadSequence::Let,
@@ -393,13 +429,13 @@ fn get_sequence(expr: &MemberExpression) -> LookaheadSequence {
 // foo?.[bar]
 // foo?.#bar
 node!(pub struct OptionalMemberExpression {
-    pub object: Box<alias::Expression>,
-    pub property: OptionalPropertyAccess,
+    pub object: NodeBox<alias::Expression<'src>>,
+    pub property: OptionalPropertyAccess<'src>,
 });
-impl NodeDisplay for OptionalMemberExpression {
+impl<'src> NodeDisplay for OptionalMemberExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.require_precedence(Precedence::Member).node(&self.object)?;
-        f.punctuator(Punctuator::Question);
+        f.punctuator(Punctuator::Question, &());
         f.node(&self.property)?;
         Ok(())
     }
@@ -407,32 +443,32 @@ impl NodeDisplay for OptionalMemberExpression {
 
 
 node_enum!(@node_display pub enum PropertyAccess {
-    Identifier(IdentifierPropertyAccess),
-    Computed(ComputedPropertyAccess),
-    Private(PrivatePropertyAccess),
+    Identifier(IdentifierPropertyAccess<'src>),
+    Computed(ComputedPropertyAccess<'src>),
+    Private(PrivatePropertyAccess<'src>),
 });
 
 // .foo
 node!(pub struct IdentifierPropertyAccess {
     pub token_period: KeywordData,
-    pub tokens_separator: SeparatorTokens,
-    pub id: PropertyIdentifier,
+    pub tokens_separator: SeparatorTokens<'src>,
+    pub id: PropertyIdentifier<'src>,
 });
-impl NodeDisplay for IdentifierPropertyAccess {
+impl<'src> NodeDisplay for IdentifierPropertyAccess<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Period);
+        f.punctuator(Punctuator::Period, &self.token_period);
         f.node(&self.id)
     }
 }
 
 // [foo]
 node!(pub struct ComputedPropertyAccess {
-    pub tokens_prefix: SeparatorTokens,
+    pub tokens_prefix: SeparatorTokens<'src>,
     pub token_square_l: KeywordSuffixData,
-    pub expression: Box<alias::Expression>,
+    pub expression: NodeBox<alias::Expression<'src>>,
     pub token_square_r: KeywordData,
 });
-impl NodeDisplay for ComputedPropertyAccess {
+impl<'src> NodeDisplay for ComputedPropertyAccess<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_square();
         f.require_precedence(Precedence::Assignment).node(
@@ -446,13 +482,13 @@ impl NodeDisplay for ComputedPropertyAccess {
 node!(pub struct PrivatePropertyAccess {
     pub token_period: KeywordData,
     pub token_hash: KeywordData,
-    pub tokens_separator: SeparatorTokens,
-    pub property: PropertyIdentifier,
+    pub tokens_separator: SeparatorTokens<'src>,
+    pub property: PropertyIdentifier<'src>,
 });
-impl NodeDisplay for PrivatePropertyAccess {
+impl<'src> NodeDisplay for PrivatePropertyAccess<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Period);
-        f.punctuator(Punctuator::Hash);
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.punctuator(Punctuator::Hash, &self.token_hash);
         f.node(&self.property)
     }
 }
@@ -461,22 +497,22 @@ impl NodeDisplay for PrivatePropertyAccess {
 
 
 node_enum!(@node_display pub enum OptionalPropertyAccess {
-    Identifier(OptionalIdentifierPropertyAccess),
-    Computed(OptionalComputedPropertyAccess),
-    Private(OptionalPrivatePropertyAccess),
+    Identifier(OptionalIdentifierPropertyAccess<'src>),
+    Computed(OptionalComputedPropertyAccess<'src>),
+    Private(OptionalPrivatePropertyAccess<'src>),
 });
 
 // ?.[foo]
 node!(pub struct OptionalComputedPropertyAccess {
     pub token_question_period: KeywordData,
-    pub tokens_separator: SeparatorTokens,
+    pub tokens_separator: SeparatorTokens<'src>,
     pub token_square_l: KeywordSuffixData,
-    pub expression: Box<alias::Expression>,
+    pub expression: NodeBox<alias::Expression<'src>>,
     pub token_square_r: KeywordSuffixData,
 });
-impl NodeDisplay for OptionalComputedPropertyAccess {
+impl<'src> NodeDisplay for OptionalComputedPropertyAccess<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::QuestionPeriod);
+        f.punctuator(Punctuator::QuestionPeriod, &self.token_question_period);
 
         let mut f = f.wrap_square();
         f.require_precedence(Precedence::Assignment).node(
@@ -489,12 +525,12 @@ impl NodeDisplay for OptionalComputedPropertyAccess {
 // ?.foo
 node!(pub struct OptionalIdentifierPropertyAccess {
     pub token_question_period: KeywordData,
-    pub tokens_separator: SeparatorTokens,
-    pub id: PropertyIdentifier,
+    pub tokens_separator: SeparatorTokens<'src>,
+    pub id: PropertyIdentifier<'src>,
 });
-impl NodeDisplay for OptionalIdentifierPropertyAccess {
+impl<'src> NodeDisplay for OptionalIdentifierPropertyAccess<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::QuestionPeriod);
+        f.punctuator(Punctuator::QuestionPeriod, &self.token_question_period);
         f.node(&self.id)
     }
 }
@@ -503,41 +539,41 @@ impl NodeDisplay for OptionalIdentifierPropertyAccess {
 node!(pub struct OptionalPrivatePropertyAccess {
     pub token_question_period: KeywordData,
     pub token_hash: KeywordData,
-    pub tokens_separator: SeparatorTokens,
-    pub property: PropertyIdentifier,
+    pub tokens_separator: SeparatorTokens<'src>,
+    pub property: PropertyIdentifier<'src>,
 });
-impl NodeDisplay for OptionalPrivatePropertyAccess {
+impl<'src> NodeDisplay for OptionalPrivatePropertyAccess<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::QuestionPeriod);
-        f.punctuator(Punctuator::Hash);
+        f.punctuator(Punctuator::QuestionPeriod, &self.token_question_period);
+        f.punctuator(Punctuator::Hash, &self.token_hash);
         f.node(&self.property)
     }
 }
 
 // i++
 node!(pub struct PostIncrementExpression {
-    pub value: LeftHandSimpleAssign,
-    pub tokens_separator: SeparatorTokensSingleLine,
+    pub value: LeftHandSimpleAssign<'src>,
+    pub tokens_separator: SeparatorTokensSingleLine<'src>,
     pub token_plusplus: MaybeTokenPosition,
 });
-impl NodeDisplay for PostIncrementExpression {
+impl<'src> NodeDisplay for PostIncrementExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.require_precedence(Precedence::LeftHand).node(&self.value)?;
-        f.punctuator(Punctuator::PlusPlus);
+        f.punctuator(Punctuator::PlusPlus, &self.token_plusplus);
         Ok(())
     }
 }
 
 // i--
 node!(pub struct PostDecrementExpression {
-    pub value: LeftHandSimpleAssign,
-    pub tokens_separator: SeparatorTokensSingleLine,
+    pub value: LeftHandSimpleAssign<'src>,
+    pub tokens_separator: SeparatorTokensSingleLine<'src>,
     pub token_minusminus: MaybeTokenPosition,
 });
-impl NodeDisplay for PostDecrementExpression {
+impl<'src> NodeDisplay for PostDecrementExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.require_precedence(Precedence::LeftHand).node(&self.value)?;
-        f.punctuator(Punctuator::MinusMinus);
+        f.punctuator(Punctuator::MinusMinus, &self.token_minusminus);
         Ok(())
     }
 }
@@ -545,11 +581,11 @@ impl NodeDisplay for PostDecrementExpression {
 // ++i
 node!(pub struct PreIncrementExpression {
     pub token_plusplus: KeywordSuffixData,
-    pub value: LeftHandSimpleAssign,
+    pub value: LeftHandSimpleAssign<'src>,
 });
-impl NodeDisplay for PreIncrementExpression {
+impl<'src> NodeDisplay for PreIncrementExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::PlusPlus);
+        f.punctuator(Punctuator::PlusPlus, &self.token_plusplus);
         f.require_precedence(Precedence::Unary).node(&self.value)
     }
 }
@@ -557,11 +593,11 @@ impl NodeDisplay for PreIncrementExpression {
 // --i
 node!(pub struct PreDecrementExpression {
     pub token_minusminus: KeywordSuffixData,
-    pub value: LeftHandSimpleAssign,
+    pub value: LeftHandSimpleAssign<'src>,
 });
-impl NodeDisplay for PreDecrementExpression {
+impl<'src> NodeDisplay for PreDecrementExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::MinusMinus);
+        f.punctuator(Punctuator::MinusMinus, &self.token_minusminus);
         f.require_precedence(Precedence::Unary).node(&self.value)
     }
 }
@@ -572,96 +608,96 @@ impl NodeDisplay for PreDecrementExpression {
 
 node!(pub struct DeleteExpression {
     pub token_delete: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for DeleteExpression {
+impl<'src> NodeDisplay for DeleteExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.keyword(Keyword::Delete);
+        f.keyword(Keyword::Delete, &self.token_delete);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
 }
 node!(pub struct VoidExpression {
     pub token_void: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for VoidExpression {
+impl<'src> NodeDisplay for VoidExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.keyword(Keyword::Void);
+        f.keyword(Keyword::Void, &self.token_void);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
 }
 node!(pub struct TypeofExpression {
     pub token_typeof: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for TypeofExpression {
+impl<'src> NodeDisplay for TypeofExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.keyword(Keyword::Typeof);
+        f.keyword(Keyword::Typeof, &self.token_typeof);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
 }
 node!(pub struct PositiveExpression {
     pub token_plus: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for PositiveExpression {
+impl<'src> NodeDisplay for PositiveExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.punctuator(Punctuator::Plus);
+        f.punctuator(Punctuator::Plus, &self.token_plus);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
 }
 node!(pub struct NegativeExpression {
     pub token_minus: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for NegativeExpression {
+impl<'src> NodeDisplay for NegativeExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.punctuator(Punctuator::Exclam);
+        f.punctuator(Punctuator::Minus, &self.token_minus);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
 }
 node!(pub struct BitNegateExpression {
-    pub token_caret: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub token_tilde: KeywordSuffixData,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BitNegateExpression {
+impl<'src> NodeDisplay for BitNegateExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.punctuator(Punctuator::Tilde);
+        f.punctuator(Punctuator::Tilde, &self.token_tilde);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
 }
 node!(pub struct NegateExpression {
     pub token_exclam: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for NegateExpression {
+impl<'src> NodeDisplay for NegateExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.punctuator(Punctuator::Exclam);
+        f.punctuator(Punctuator::Exclam, &self.token_exclam);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
 }
 node!(pub struct AwaitExpression {
     pub token_await: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for AwaitExpression {
+impl<'src> NodeDisplay for AwaitExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.keyword(Keyword::Await);
+        f.keyword(Keyword::Await, &self.token_await);
         f.require_precedence(Precedence::Unary).node(&self.value)?;
         Ok(())
     }
@@ -670,12 +706,12 @@ impl NodeDisplay for AwaitExpression {
 
 node!(pub struct YieldExpression {
     pub token_yield: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: Option<NodeBox<alias::Expression<'src>>>,
 });
-impl NodeDisplay for YieldExpression {
+impl<'src> NodeDisplay for YieldExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        let mut f = f.precedence(Precedence::Unary);
-        f.keyword(Keyword::Yield);
+        let mut f = f.precedence(Precedence::Assignment);
+        f.keyword(Keyword::Yield, &self.token_yield);
         f.require_precedence(Precedence::Assignment).node(&self.value)?;
         Ok(())
     }
@@ -684,13 +720,13 @@ node!(pub struct DelegateYieldExpression {
     pub token_yield: KeywordSuffixData,
     // TODO: No newlines allowed
     pub token_star: KeywordSuffixData,
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for DelegateYieldExpression {
+impl<'src> NodeDisplay for DelegateYieldExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        let mut f = f.precedence(Precedence::Unary);
-        f.keyword(Keyword::Yield);
-        f.punctuator(Punctuator::Star);
+        let mut f = f.precedence(Precedence::Assignment);
+        f.keyword(Keyword::Yield, &self.token_yield);
+        f.punctuator(Punctuator::Star, &self.token_star);
         f.require_precedence(Precedence::Assignment).node(&self.value)?;
         Ok(())
     }
@@ -698,12 +734,12 @@ impl NodeDisplay for DelegateYieldExpression {
 node!(pub struct BindMemberExpression {
     pub token_coloncolon: KeywordSuffixData,
     // TODO: No newlines allowed
-    pub value: Box<alias::Expression>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BindMemberExpression {
+impl<'src> NodeDisplay for BindMemberExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Unary);
-        f.punctuator(Punctuator::Bind);
+        f.punctuator(Punctuator::Bind, &self.token_coloncolon);
         f.require_precedence(Precedence::Member).node(&self.value)?;
         Ok(())
     }
@@ -712,33 +748,33 @@ impl NodeDisplay for BindMemberExpression {
 
 // foo + bar
 node!(pub struct AddExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_plus: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for AddExpression {
+impl<'src> NodeDisplay for AddExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Additive);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Plus);
+        f.operator(Punctuator::Plus, &self.token_plus);
         f.require_precedence(Precedence::Multiplicative).node(
             &self.right,
-        );
+        )?;
         Ok(())
     }
 }
 
 // foo - bar
 node!(pub struct SubtractExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_minus: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for SubtractExpression {
+impl<'src> NodeDisplay for SubtractExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Additive);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Minus);
+        f.operator(Punctuator::Minus, &self.token_minus);
         f.require_precedence(Precedence::Multiplicative).node(
             &self.right,
         )?;
@@ -748,15 +784,15 @@ impl NodeDisplay for SubtractExpression {
 
 // foo << bar
 node!(pub struct LeftShiftExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_langleangle: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for LeftShiftExpression {
+impl<'src> NodeDisplay for LeftShiftExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Shift);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::LAngleAngle);
+        f.operator(Punctuator::LAngleAngle, &self.token_langleangle);
         f.require_precedence(Precedence::Additive).node(&self.right)?;
         Ok(())
     }
@@ -764,15 +800,15 @@ impl NodeDisplay for LeftShiftExpression {
 
 // foo >> bar
 node!(pub struct RightShiftExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_rangleangle: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for RightShiftExpression {
+impl<'src> NodeDisplay for RightShiftExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Shift);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::RAngleAngle);
+        f.operator(Punctuator::RAngleAngle, &self.token_rangleangle);
         f.require_precedence(Precedence::Additive).node(&self.right)?;
         Ok(())
     }
@@ -780,30 +816,30 @@ impl NodeDisplay for RightShiftExpression {
 
 // foo >>> bar
 node!(pub struct RightShiftSignedExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_rangleangleangle: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for RightShiftSignedExpression {
+impl<'src> NodeDisplay for RightShiftSignedExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Shift);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::RAngleAngleAngle);
+        f.operator(Punctuator::RAngleAngleAngle, &self.token_rangleangleangle);
         f.require_precedence(Precedence::Additive).node(&self.right)?;
         Ok(())
     }
 }
 // foo / bar
 node!(pub struct DivideExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_slash: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for DivideExpression {
+impl<'src> NodeDisplay for DivideExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Multiplicative);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Slash);
+        f.operator(Punctuator::Slash, &self.token_slash);
         f.require_precedence(Precedence::Exponential).node(
             &self.right,
         )?;
@@ -813,15 +849,15 @@ impl NodeDisplay for DivideExpression {
 
 // foo * bar
 node!(pub struct MultiplyExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_star: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for MultiplyExpression {
+impl<'src> NodeDisplay for MultiplyExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Multiplicative);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Star);
+        f.operator(Punctuator::Star, &self.token_star);
         f.require_precedence(Precedence::Exponential).node(
             &self.right,
         )?;
@@ -831,15 +867,15 @@ impl NodeDisplay for MultiplyExpression {
 
 // foo % bar
 node!(pub struct ModulusExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_percent: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ModulusExpression {
+impl<'src> NodeDisplay for ModulusExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Multiplicative);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Mod);
+        f.operator(Punctuator::Mod, &self.token_percent);
         f.require_precedence(Precedence::Exponential).node(
             &self.right,
         )?;
@@ -849,15 +885,15 @@ impl NodeDisplay for ModulusExpression {
 
 // foo & bar
 node!(pub struct BitAndExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_amp: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BitAndExpression {
+impl<'src> NodeDisplay for BitAndExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::BitwiseAnd);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Amp);
+        f.operator(Punctuator::Amp, &self.token_amp);
         f.require_precedence(Precedence::Equality).node(&self.right)?;
         Ok(())
     }
@@ -865,15 +901,15 @@ impl NodeDisplay for BitAndExpression {
 
 // foo | bar
 node!(pub struct BitOrExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_bar: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BitOrExpression {
+impl<'src> NodeDisplay for BitOrExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::BitwiseOr);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Bar);
+        f.operator(Punctuator::Bar, &self.token_bar);
         f.require_precedence(Precedence::BitwiseXOr).node(
             &self.right,
         )?;
@@ -883,15 +919,15 @@ impl NodeDisplay for BitOrExpression {
 
 // foo ^ bar
 node!(pub struct BitXorExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_caret: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BitXorExpression {
+impl<'src> NodeDisplay for BitXorExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::BitwiseXOr);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Caret);
+        f.operator(Punctuator::Caret, &self.token_caret);
         f.require_precedence(Precedence::BitwiseAnd).node(
             &self.right,
         )?;
@@ -901,15 +937,15 @@ impl NodeDisplay for BitXorExpression {
 
 // foo ** bar
 node!(pub struct PowerExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_starstar: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for PowerExpression {
+impl<'src> NodeDisplay for PowerExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        let mut f = f.precedence(Precedence::Update);
-        f.node(&self.left)?;
-        f.punctuator(Punctuator::StarStar);
+        let mut f = f.precedence(Precedence::Exponential);
+        f.require_precedence(Precedence::Update).node(&self.left)?;
+        f.operator(Punctuator::StarStar, &self.token_starstar);
         f.require_precedence(Precedence::Exponential).node(
             &self.right,
         )?;
@@ -920,15 +956,15 @@ impl NodeDisplay for PowerExpression {
 
 // foo == bar
 node!(pub struct EqualExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_eqeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for EqualExpression {
+impl<'src> NodeDisplay for EqualExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Equality);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::EqEq);
+        f.operator(Punctuator::EqEq, &self.token_eqeq);
         f.require_precedence(Precedence::Relational).node(
             &self.right,
         )?;
@@ -938,15 +974,15 @@ impl NodeDisplay for EqualExpression {
 
 // foo === bar
 node!(pub struct StrictEqualExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_eqeqeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for StrictEqualExpression {
+impl<'src> NodeDisplay for StrictEqualExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Equality);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::EqEqEq);
+        f.operator(Punctuator::EqEqEq, &self.token_eqeqeq);
         f.require_precedence(Precedence::Relational).node(
             &self.right,
         )?;
@@ -956,31 +992,32 @@ impl NodeDisplay for StrictEqualExpression {
 
 // foo != bar
 node!(pub struct NotEqualExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_neeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for NotEqualExpression {
+impl<'src> NodeDisplay for NotEqualExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Equality);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Neq);
+        f.operator(Punctuator::Neq, &self.token_neeq);
         f.require_precedence(Precedence::Relational).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo !== bar
 node!(pub struct StrictNotEqualExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_neeqeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for StrictNotEqualExpression {
+impl<'src> NodeDisplay for StrictNotEqualExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Equality);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::NeqEq);
+        f.operator(Punctuator::NeqEq, &self.token_neeqeq);
         f.require_precedence(Precedence::Relational).node(
             &self.right,
         )?;
@@ -989,15 +1026,15 @@ impl NodeDisplay for StrictNotEqualExpression {
 }
 // foo < bar
 node!(pub struct LessThanExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_langle: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for LessThanExpression {
+impl<'src> NodeDisplay for LessThanExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Relational);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::LAngle);
+        f.operator(Punctuator::LAngle, &self.token_langle);
         f.require_precedence(Precedence::Shift).node(&self.right)?;
         Ok(())
     }
@@ -1005,31 +1042,31 @@ impl NodeDisplay for LessThanExpression {
 
 // foo <= bar
 node!(pub struct LessThanEqualExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_langleeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for LessThanEqualExpression {
+impl<'src> NodeDisplay for LessThanEqualExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Relational);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::LAngleEq);
+        f.operator(Punctuator::LAngleEq, &self.token_langleeq);
         f.require_precedence(Precedence::Shift).node(&self.right)?;
         Ok(())
     }
 }
 
 // foo > bar
-node!(pub struct GreatherThanExpression {
-    pub left: Box<alias::Expression>,
+node!(pub struct GreaterThanExpression {
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_rangle: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for GreatherThanExpression {
+impl<'src> NodeDisplay for GreaterThanExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Relational);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::RAngle);
+        f.operator(Punctuator::RAngle, &self.token_rangle);
         f.require_precedence(Precedence::Shift).node(&self.right)?;
         Ok(())
     }
@@ -1037,15 +1074,15 @@ impl NodeDisplay for GreatherThanExpression {
 
 // foo >= bar
 node!(pub struct GreaterThanEqualExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_rangleeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for GreaterThanEqualExpression {
+impl<'src> NodeDisplay for GreaterThanEqualExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Relational);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::RAngleEq);
+        f.operator(Punctuator::RAngleEq, &self.token_rangleeq);
         f.require_precedence(Precedence::Shift).node(&self.right)?;
         Ok(())
     }
@@ -1054,16 +1091,16 @@ impl NodeDisplay for GreaterThanEqualExpression {
 
 // foo in bar
 node!(pub struct InExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_in: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for InExpression {
+impl<'src> NodeDisplay for InExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Relational);
         let mut f = f.in_wrap_parens();
         f.node(&self.left)?;
-        f.keyword(Keyword::In);
+        f.keyword(Keyword::In, &self.token_in);
         f.require_precedence(Precedence::Shift).node(&self.right)?;
         Ok(())
     }
@@ -1071,15 +1108,15 @@ impl NodeDisplay for InExpression {
 
 // foo instanceof bar
 node!(pub struct InstanceofExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_instanceof: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for InstanceofExpression {
+impl<'src> NodeDisplay for InstanceofExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Relational);
         f.node(&self.left)?;
-        f.keyword(Keyword::Instanceof);
+        f.keyword(Keyword::Instanceof, &self.token_instanceof);
         f.require_precedence(Precedence::Shift).node(&self.right)?;
         Ok(())
     }
@@ -1087,15 +1124,15 @@ impl NodeDisplay for InstanceofExpression {
 
 // foo && bar
 node!(pub struct AndExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_ampamp: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for AndExpression {
+impl<'src> NodeDisplay for AndExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::LogicalAnd);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::AmpAmp);
+        f.operator(Punctuator::AmpAmp, &self.token_ampamp);
         f.require_precedence(Precedence::BitwiseOr).node(
             &self.right,
         )?;
@@ -1105,15 +1142,15 @@ impl NodeDisplay for AndExpression {
 
 // foo || bar
 node!(pub struct OrExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_barbar: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for OrExpression {
+impl<'src> NodeDisplay for OrExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::LogicalOr);
         f.node(&self.left)?;
-        f.punctuator(Punctuator::BarBar);
+        f.operator(Punctuator::BarBar, &self.token_barbar);
         f.require_precedence(Precedence::LogicalAnd).node(
             &self.right,
         )?;
@@ -1123,18 +1160,18 @@ impl NodeDisplay for OrExpression {
 
 // foo :: bar
 node!(pub struct BindExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_coloncolon: KeywordWrappedData,
     // TODO: For bind, 'right' can be restructed to MemberExpression or SuperProperty,
     // so it should probably be its own node type.
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BindExpression {
+impl<'src> NodeDisplay for BindExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // TODO: Parens might not be right when inside a callexpr?
         let mut f = f.precedence(Precedence::LeftHand);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::ColonColon);
+        f.punctuator(Punctuator::ColonColon, &self.token_coloncolon);
         f.require_precedence(Precedence::Member).node(&self.right)?;
         Ok(())
     }
@@ -1145,23 +1182,23 @@ impl NodeDisplay for BindExpression {
 
 // foo ? bar : baz
 node!(pub struct ConditionalExpression {
-    pub test: Box<alias::Expression>,
+    pub test: NodeBox<alias::Expression<'src>>,
     pub token_question: KeywordWrappedData,
-    pub consequent: Box<alias::Expression>,
+    pub consequent: NodeBox<alias::Expression<'src>>,
     pub token_colon: KeywordWrappedData,
-    pub alternate: Box<alias::Expression>,
+    pub alternate: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ConditionalExpression {
+impl<'src> NodeDisplay for ConditionalExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Conditional);
 
         f.require_precedence(Precedence::LogicalOr).node(&self.test)?;
 
-        f.punctuator(Punctuator::Question);
+        f.operator(Punctuator::Question, &self.token_question);
 
         let mut f = f.require_precedence(Precedence::Assignment);
         f.allow_in().node(&self.consequent)?;
-        f.punctuator(Punctuator::Colon);
+        f.operator(Punctuator::Colon, &self.token_colon);
         f.node(&self.alternate)?;
 
         Ok(())
@@ -1171,12 +1208,13 @@ impl NodeDisplay for ConditionalExpression {
 
 // foo = bar
 node!(pub struct AssignmentExpression {
-    pub left: Box<LeftHandComplexAssign>,
+    pub left: NodeBox<LeftHandComplexAssign<'src>>,
     pub token_eq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for AssignmentExpression {
+impl<'src> NodeDisplay for AssignmentExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         let sequence = if let LeftHandComplexAssign::Object(_) = *self.left {
             LookaheadSequence::Curly
         } else {
@@ -1185,7 +1223,7 @@ impl NodeDisplay for AssignmentExpression {
 
         let mut f = f.lookahead_wrap_parens(sequence);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::Eq, &self.token_eq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
         )?;
@@ -1196,194 +1234,206 @@ impl NodeDisplay for AssignmentExpression {
 
 // foo += bar
 node!(pub struct AddAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_pluseq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for AddAssignExpression {
+impl<'src> NodeDisplay for AddAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Plus);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::PlusEq, &self.token_pluseq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo -= bar
 node!(pub struct SubtractAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_minuseq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for SubtractAssignExpression {
+impl<'src> NodeDisplay for SubtractAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Subtract);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::SubtractEq, &self.token_minuseq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo <<= bar
 node!(pub struct LeftShiftAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_langleangleeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for LeftShiftAssignExpression {
+impl<'src> NodeDisplay for LeftShiftAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::LAngleAngle);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::LAngleAngleEq, &self.token_langleangleeq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo >>= bar
 node!(pub struct RightShiftAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_rangleangleeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for RightShiftAssignExpression {
+impl<'src> NodeDisplay for RightShiftAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::RAngleAngle);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::RAngleAngleEq, &self.token_rangleangleeq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo >>>= bar
 node!(pub struct RightShiftSignedAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_rangleangleangleeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for RightShiftSignedAssignExpression {
+impl<'src> NodeDisplay for RightShiftSignedAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::RAngleAngleAngle);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::RAngleAngleAngleEq, &self.token_rangleangleangleeq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo /= bar
 node!(pub struct DivideAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_slasheq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for DivideAssignExpression {
+impl<'src> NodeDisplay for DivideAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Slash);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::SlashEq, &self.token_slasheq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo *= bar
-node!(pub struct MultipleAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+node!(pub struct MultiplyAssignExpression {
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_stareq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for MultipleAssignExpression {
+impl<'src> NodeDisplay for MultiplyAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Star);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::StarEq, &self.token_stareq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo %= bar
 node!(pub struct ModulusAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_percenteq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ModulusAssignExpression {
+impl<'src> NodeDisplay for ModulusAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Mod);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::ModEq, &self.token_percenteq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo &= bar
 node!(pub struct BitAndAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_ampeq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BitAndAssignExpression {
+impl<'src> NodeDisplay for BitAndAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Amp);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::AmpEq, &self.token_ampeq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo |= bar
 node!(pub struct BitOrAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_bareq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BitOrAssignExpression {
+impl<'src> NodeDisplay for BitOrAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Bar);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::BarEq, &self.token_bareq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo ^= bar
 node!(pub struct BitXorAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_careteq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for BitXorAssignExpression {
+impl<'src> NodeDisplay for BitXorAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::Caret);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::CaretEq, &self.token_careteq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 // foo **= bar
 node!(pub struct PowerAssignExpression {
-    pub left: Box<LeftHandSimpleAssign>,
+    pub left: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_starstareq: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for PowerAssignExpression {
+impl<'src> NodeDisplay for PowerAssignExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         f.require_precedence(Precedence::LeftHand).node(&self.left)?;
-        f.punctuator(Punctuator::StarStar);
-        f.punctuator(Punctuator::Eq);
+        f.operator(Punctuator::StarStarEq, &self.token_starstareq);
         f.require_precedence(Precedence::Assignment).node(
             &self.right,
-        )
+        )?;
+        Ok(())
     }
 }
 
@@ -1391,16 +1441,16 @@ impl NodeDisplay for PowerAssignExpression {
 
 // foo, bar
 node!(pub struct SequenceExpression {
-    pub left: Box<alias::Expression>,
+    pub left: NodeBox<alias::Expression<'src>>,
     pub token_comma: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for SequenceExpression {
+impl<'src> NodeDisplay for SequenceExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Normal);
 
         f.node(&self.left)?;
-        f.punctuator(Punctuator::Comma);
+        f.punctuator(Punctuator::Comma, &self.token_comma);
 
         // Note: This precedence isn't needed to reproduce functionality, but it is to make the
         // AST reproduce properly from the serialized code. Parens can be avoided by reordering AST.
@@ -1416,11 +1466,11 @@ impl NodeDisplay for SequenceExpression {
 // do { foo; }
 node!(#[derive(Default)] pub struct DoExpression {
     pub token_do: MaybeTokenPosition,
-    pub body: BlockStatement,
+    pub body: BlockStatement<'src>,
 });
-impl NodeDisplay for DoExpression {
+impl<'src> NodeDisplay for DoExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Do);
+        f.keyword(Keyword::Do, &self.token_do);
         f.node(&self.body)
     }
 }
@@ -1432,11 +1482,11 @@ node!(pub struct NewTargetExpression {
     pub token_period: KeywordWrappedData,
     pub token_target: MaybeTokenPosition,
 });
-impl NodeDisplay for NewTargetExpression {
+impl<'src> NodeDisplay for NewTargetExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::New);
-        f.punctuator(Punctuator::Period);
-        f.keyword(Keyword::Target);
+        f.keyword(Keyword::New, &self.token_new);
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.keyword(Keyword::Target, &self.token_target);
         Ok(())
     }
 }
@@ -1447,11 +1497,11 @@ node!(pub struct ImportMetaExpression {
     pub token_period: KeywordWrappedData,
     pub token_meta: MaybeTokenPosition,
 });
-impl NodeDisplay for ImportMetaExpression {
+impl<'src> NodeDisplay for ImportMetaExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Import);
-        f.punctuator(Punctuator::Period);
-        f.keyword(Keyword::Meta);
+        f.keyword(Keyword::Import, &self.token_import);
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.keyword(Keyword::Meta, &self.token_meta);
         Ok(())
     }
 }
@@ -1462,11 +1512,11 @@ node!(pub struct FunctionSentExpression {
     pub token_period: KeywordWrappedData,
     pub token_send: MaybeTokenPosition,
 });
-impl NodeDisplay for FunctionSentExpression {
+impl<'src> NodeDisplay for FunctionSentExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Function);
-        f.punctuator(Punctuator::Period);
-        f.keyword(Keyword::Sent);
+        f.keyword(Keyword::Function, &self.token_function);
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.keyword(Keyword::Sent, &());
         Ok(())
     }
 }
@@ -1477,11 +1527,11 @@ node!(pub struct FunctionArgumentsExpression {
     pub token_period: KeywordWrappedData,
     pub token_arguments: MaybeTokenPosition,
 });
-impl NodeDisplay for FunctionArgumentsExpression {
+impl<'src> NodeDisplay for FunctionArgumentsExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Function);
-        f.punctuator(Punctuator::Period);
-        f.keyword(Keyword::Arguments);
+        f.keyword(Keyword::Function, &self.token_function);
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.keyword(Keyword::Arguments, &self.token_arguments);
         Ok(())
     }
 }
@@ -1491,17 +1541,17 @@ impl NodeDisplay for FunctionArgumentsExpression {
 // super[foo]
 node!(pub struct SuperMemberExpression {
     pub token_super: MaybeTokenPosition,
-    pub property: SuperMemberAccess,
+    pub property: SuperMemberAccess<'src>,
 });
-impl NodeDisplay for SuperMemberExpression {
+impl<'src> NodeDisplay for SuperMemberExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Super);
+        f.keyword(Keyword::Super, &self.token_super);
         f.node(&self.property)
     }
 }
 
 
 node_enum!(@node_display pub enum SuperMemberAccess {
-    Identifier(IdentifierPropertyAccess),
-    Computed(ComputedPropertyAccess),
+    Identifier(IdentifierPropertyAccess<'src>),
+    Computed(ComputedPropertyAccess<'src>),
 });
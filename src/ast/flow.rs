@@ -0,0 +1,490 @@
+//! Nodes for the parts of Flow's syntax that TypeScript doesn't share, and
+//! for type imports, which TypeScript shares with Flow.
+//!
+//! Flow's annotations are otherwise parsed into the nodes of
+//! `ast::typescript`. Like those, these only come out of the parser when the
+//! `flow` extension is enabled, and a formatter with `strip_types` set leaves
+//! them out.
+
+use ast::{KeywordData, KeywordSuffixData, NodeBox};
+
+use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punctuator, Precedence};
+
+use ast::alias;
+use ast::general::BindingIdentifier;
+use ast::literal::String;
+use ast::modules::ImportSpecifier;
+use ast::typescript::{DeclaredItem, Type, TypeAnnotation, TypeIdentifier, TypeMember, TypeParameters};
+
+
+// ?string
+node!(pub struct MaybeType {
+    pub token_question: KeywordData,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for MaybeType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.punctuator(Punctuator::Question, &self.token_question);
+        f.node(&self.value)
+    }
+}
+
+// {| a: A |}
+node!(#[derive(Default)] pub struct ExactObjectType {
+    pub members: Vec<TypeMember<'src>>,
+});
+impl<'src> NodeDisplay for ExactObjectType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.wrap_curly_bar().node_list(&self.members)
+    }
+}
+
+// ...A, as a member of an object type
+//
+// Without a type, the "..." marks an object type as inexact, like
+// "{ a: A, ... }".
+node!(pub struct SpreadTypeMember {
+    pub token_ellipsis: KeywordData,
+    pub value: Option<NodeBox<Type<'src>>>,
+});
+impl<'src> SpreadTypeMember<'src> {
+    pub fn is_inexact(&self) -> bool {
+        self.value.is_none()
+    }
+}
+impl<'src> NodeDisplay for SpreadTypeMember<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.punctuator(Punctuator::Ellipsis, &self.token_ellipsis);
+        if let Some(ref value) = self.value {
+            f.node(value)?;
+        }
+        f.punctuator(Punctuator::Semicolon, &());
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_types {
+    use super::*;
+    use ast::general::PropertyIdentifier;
+    use ast::typescript::{KeywordType, PropertySignature, TypeKeyword, TypeReference};
+
+    #[test]
+    fn it_prints_maybe_types() {
+        assert_serialize!(
+            MaybeType {
+                token_question: Default::default(),
+                value: KeywordType::from(TypeKeyword::String).into(),
+                position: None,
+                comments: None,
+            },
+            "?string"
+        );
+    }
+
+    #[test]
+    fn it_prints_exact_objects() {
+        assert_serialize!(
+            ExactObjectType {
+                members: vec![
+                    SpreadTypeMember {
+                        token_ellipsis: Default::default(),
+                        value: Some(TypeReference::from(TypeIdentifier::from("A")).into()),
+                        position: None,
+                        comments: None,
+                    }.into(),
+                    PropertySignature {
+                        modifiers: vec![],
+                        name: PropertyIdentifier::from("b").into(),
+                        optional: false,
+                        type_annotation: Some(KeywordType::from(TypeKeyword::Number).into()),
+                        position: None,
+                        comments: None,
+                    }.into(),
+                ],
+                position: None,
+                comments: None,
+            },
+            "{|...A;b:number;|}"
+        );
+    }
+}
+
+
+// %checks
+// %checks(typeof x === "string")
+//
+// The predicate of a function that refines the types of its arguments,
+// after its return type. Only declared functions give the expression.
+node!(pub struct Predicate {
+    // The colon, if there is no return type, like "(x): %checks".
+    pub token_colon: Option<KeywordData>,
+    pub token_percent: KeywordData,
+    pub token_checks: KeywordData,
+    pub value: Option<NodeBox<alias::Expression<'src>>>,
+});
+impl<'src> NodeDisplay for Predicate<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        if let Some(ref token_colon) = self.token_colon {
+            f.punctuator(Punctuator::Colon, token_colon);
+        }
+        f.punctuator(Punctuator::Mod, &self.token_percent);
+        f.keyword(Keyword::Checks, &self.token_checks);
+        if let Some(ref value) = self.value {
+            f.wrap_parens().node(value)?;
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_predicate {
+    use super::*;
+    use ast::general::ReferenceIdentifier;
+
+    #[test]
+    fn it_prints_predicates() {
+        assert_serialize!(
+            Predicate {
+                token_colon: None,
+                token_percent: Default::default(),
+                token_checks: Default::default(),
+                value: Some(NodeBox::new(ReferenceIdentifier::from("x").into())),
+                position: None,
+                comments: None,
+            },
+            "%checks(x)"
+        );
+    }
+}
+
+
+// opaque type A: B = C;
+node!(pub struct OpaqueTypeDeclaration {
+    pub token_opaque: KeywordData,
+    pub token_type: KeywordData,
+    pub id: TypeIdentifier<'src>,
+    pub type_parameters: Option<TypeParameters<'src>>,
+    pub supertype: Option<TypeAnnotation<'src>>,
+
+    // Only declared opaque types, like "declare opaque type A;", leave out
+    // the underlying type.
+    pub value: Option<NodeBox<Type<'src>>>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for OpaqueTypeDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.keyword(Keyword::Opaque, &self.token_opaque);
+        f.keyword(Keyword::Type, &self.token_type);
+        f.node(&self.id)?;
+        if let Some(ref type_parameters) = self.type_parameters {
+            f.node(type_parameters)?;
+        }
+        if let Some(ref supertype) = self.supertype {
+            f.node(supertype)?;
+        }
+        if let Some(ref value) = self.value {
+            f.operator(Punctuator::Eq, &());
+            f.node(value)?;
+        }
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_opaque_type_declaration {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+    use ast::typescript::{KeywordType, TypeKeyword};
+
+    fn opaque_type() -> OpaqueTypeDeclaration<'static> {
+        OpaqueTypeDeclaration {
+            token_opaque: Default::default(),
+            token_type: Default::default(),
+            id: "Id".into(),
+            type_parameters: None,
+            supertype: Some(KeywordType::from(TypeKeyword::String).into()),
+            value: Some(KeywordType::from(TypeKeyword::String).into()),
+            token_semi: Default::default(),
+            position: None,
+            comments: None,
+        }
+    }
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(opaque_type(), "opaque type Id:string=string;");
+    }
+
+    #[test]
+    fn it_strips() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&opaque_type(), &options).unwrap(), "");
+    }
+}
+
+
+
+// The "export function f(): void;" of "declare export function f(): void;"
+node!(pub struct DeclareExportDeclaration {
+    pub token_export: KeywordData,
+    pub token_default: Option<KeywordData>,
+    pub exported: DeclareExport<'src>,
+});
+impl<'src> NodeDisplay for DeclareExportDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::Export, &self.token_export);
+        if let Some(ref token_default) = self.token_default {
+            f.keyword(Keyword::Default, token_default);
+        }
+        f.node(&self.exported)
+    }
+}
+
+node_enum!(@node_display pub enum DeclareExport {
+    Item(NodeBox<DeclaredItem<'src>>),
+
+    // The "A;" of "declare export default A;"
+    Type(DeclareExportType<'src>),
+});
+
+node!(pub struct DeclareExportType {
+    pub value: NodeBox<Type<'src>>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for DeclareExportType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.value)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+
+// The "module.exports: A;" of "declare module.exports: A;"
+node!(pub struct DeclareModuleExports {
+    pub token_module: KeywordData,
+    pub token_period: KeywordData,
+    pub type_annotation: TypeAnnotation<'src>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for DeclareModuleExports<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::Module, &self.token_module);
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.identifier("exports", None)?;
+        f.node(&self.type_annotation)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_declare_export_declaration {
+    use super::*;
+    use ast::typescript::{DeclareDeclaration, TypeReference};
+
+    #[test]
+    fn it_prints_default_types() {
+        assert_serialize!(
+            DeclareDeclaration {
+                token_declare: Default::default(),
+                declaration: DeclareExportDeclaration {
+                    token_export: Default::default(),
+                    token_default: Some(Default::default()),
+                    exported: DeclareExportType {
+                        value: TypeReference::from(TypeIdentifier::from("A")).into(),
+                        token_semi: Default::default(),
+                        position: None,
+                        comments: None,
+                    }.into(),
+                    position: None,
+                    comments: None,
+                }.into(),
+                position: None,
+                comments: None,
+            },
+            "declare export default A;"
+        );
+    }
+
+    #[test]
+    fn it_prints_module_exports() {
+        assert_serialize!(
+            DeclareModuleExports {
+                token_module: Default::default(),
+                token_period: Default::default(),
+                type_annotation: TypeReference::from(TypeIdentifier::from("A")).into(),
+                token_semi: Default::default(),
+                position: None,
+                comments: None,
+            },
+            "module.exports:A;"
+        );
+    }
+}
+
+// (a: A)
+node!(pub struct TypeCastExpression {
+    pub token_paren_l: KeywordSuffixData,
+    pub expression: NodeBox<alias::Expression<'src>>,
+    pub type_annotation: TypeAnnotation<'src>,
+    pub token_paren_r: KeywordData,
+});
+impl<'src> NodeDisplay for TypeCastExpression<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return f.node(&self.expression);
+        }
+
+        let mut f = f.wrap_parens();
+        f.require_precedence(Precedence::Assignment).node(&self.expression)?;
+        f.node(&self.type_annotation)
+    }
+}
+#[cfg(test)]
+mod tests_type_cast_expression {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+    use ast::general::ReferenceIdentifier;
+    use ast::typescript::{KeywordType, TypeKeyword};
+
+    fn type_cast() -> TypeCastExpression<'static> {
+        TypeCastExpression {
+            token_paren_l: Default::default(),
+            expression: alias::Expression::from(ReferenceIdentifier::from("x")).into(),
+            type_annotation: KeywordType::from(TypeKeyword::Any).into(),
+            token_paren_r: Default::default(),
+            position: None,
+            comments: None,
+        }
+    }
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(type_cast(), "(x:any)");
+    }
+
+    #[test]
+    fn it_strips() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&type_cast(), &options).unwrap(), "x");
+    }
+}
+
+
+node_kind!(pub enum ImportKind {
+    Type,
+    Typeof,
+});
+
+// import type A, { B } from "";
+// import typeof * as C from "";
+node!(pub struct ImportTypeDeclaration {
+    pub token_import: KeywordData,
+    pub kind: ImportKind,
+    pub token_kind: KeywordData,
+    pub default: Option<BindingIdentifier<'src>>,
+
+    // The "*" and the name of a namespace import.
+    pub namespace: Option<(KeywordData, BindingIdentifier<'src>)>,
+
+    // The specifiers in curly brackets, if there are any brackets.
+    pub specifiers: Option<Vec<(ImportSpecifier<'src>, KeywordData)>>,
+    pub last_specifier: Option<ImportSpecifier<'src>>,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for ImportTypeDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.keyword(Keyword::Import, &self.token_import);
+        f.keyword(match self.kind {
+            ImportKind::Type => Keyword::Type,
+            ImportKind::Typeof => Keyword::Typeof,
+        }, &self.token_kind);
+        if let Some(ref default) = self.default {
+            f.node(default)?;
+            if self.namespace.is_some() || self.specifiers.is_some() {
+                f.punctuator(Punctuator::Comma, &());
+            }
+        }
+        if let Some((ref token_star, ref namespace)) = self.namespace {
+            f.punctuator(Punctuator::Star, token_star);
+            f.keyword(Keyword::As, &());
+            f.node(namespace)?;
+        }
+        if let Some(ref specifiers) = self.specifiers {
+            let mut f = f.wrap_curly();
+            f.comma_list(specifiers)?;
+            f.node(&self.last_specifier)?;
+        }
+        f.keyword(Keyword::From, &self.token_from);
+        f.node(&self.source)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_import_type_declaration {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+    use ast::modules::NormalImportSpecifier;
+
+    fn import_type() -> ImportTypeDeclaration<'static> {
+        ImportTypeDeclaration {
+            token_import: Default::default(),
+            kind: ImportKind::Type,
+            token_kind: Default::default(),
+            default: Some("A".into()),
+            namespace: None,
+            specifiers: Some(vec![]),
+            last_specifier: Some(NormalImportSpecifier::from(BindingIdentifier::from("B")).into()),
+            token_from: Default::default(),
+            source: "file.js".into(),
+            token_semi: Default::default(),
+            position: None,
+            comments: None,
+        }
+    }
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(import_type(), "import type A,{B}from'file.js';");
+    }
+
+    #[test]
+    fn it_strips() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&import_type(), &options).unwrap(), "");
+    }
+}
+
+// type A
+// typeof B as C
+node!(pub struct TypeImportSpecifier {
+    pub kind: ImportKind,
+    pub token_kind: KeywordData,
+    pub specifier: NodeBox<ImportSpecifier<'src>>,
+});
+impl<'src> NodeDisplay for TypeImportSpecifier<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.keyword(match self.kind {
+            ImportKind::Type => Keyword::Type,
+            ImportKind::Typeof => Keyword::Typeof,
+        }, &self.token_kind);
+        f.node(&self.specifier)
+    }
+}
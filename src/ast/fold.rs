@@ -0,0 +1,202 @@
+//! Rewriting of the AST by value.
+//!
+//! `Fold` has a `fold_*` method for every enum of nodes, like those in
+//! `alias`, each taking ownership of a node and returning its replacement.
+//! The defaults fold the node's children and return it otherwise unchanged.
+//! Statements in statement lists may be replaced by any number of statements.
+//!
+//! The methods are generated for every enum declared with `node_enum!`, and
+//! named after it, like `fold_statement` for `Statement`.
+
+use std::string;
+
+use ast::{
+    alias, classes, decorators, expression, flow, functions, general, jsx, modules, objects, patterns,
+    root, statement, typescript,
+};
+use ast::{KeywordData, KeywordSuffixData, KeywordWrappedData, NodeBox, PositionRange, Separators, Str};
+use stack;
+
+/// Folds the children of a node, rebuilding it from the results.
+/// Implemented for every node by the `node!` and `node_enum!` macros.
+pub trait FoldChildren<'src>: Sized {
+    fn fold_children<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self;
+}
+
+/// Anything that can appear in a node field, passing any nodes it contains
+/// to the matching `Fold` method.
+pub trait Foldable<'src>: Sized {
+    fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self;
+
+    /// Folds an item of a list, which may replace it with any number of items.
+    fn fold_into<F: Fold<'src> + ?Sized>(self, folder: &mut F, out: &mut Vec<Self>) {
+        out.push(self.fold(folder));
+    }
+}
+impl<'src, T: Foldable<'src>> Foldable<'src> for NodeBox<T> {
+    fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+        NodeBox::new(stack::grow(|| NodeBox::into_inner(self).fold(folder)))
+    }
+}
+impl<'src, T: Foldable<'src>> Foldable<'src> for Option<T> {
+    fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+        self.map(|n| n.fold(folder))
+    }
+}
+impl<'src, T: Foldable<'src>> Foldable<'src> for Vec<T> {
+    fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+        let mut out = Vec::with_capacity(self.len());
+        for n in self {
+            n.fold_into(folder, &mut out);
+        }
+        out
+    }
+}
+impl<'src, A: Foldable<'src>, B: Foldable<'src>> Foldable<'src> for (A, B) {
+    fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+        (self.0.fold(folder), self.1.fold(folder))
+    }
+}
+impl<'src, A, B, C, D> Foldable<'src> for (A, B, C, D)
+where
+    A: Foldable<'src>,
+    B: Foldable<'src>,
+    C: Foldable<'src>,
+    D: Foldable<'src>,
+{
+    fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+        (self.0.fold(folder), self.1.fold(folder), self.2.fold(folder), self.3.fold(folder))
+    }
+}
+macro_rules! leaf_foldable {
+    ($($t:ty),*) => {
+        $(
+            impl<'src> Foldable<'src> for $t {
+                fn fold<F: Fold<'src> + ?Sized>(self, _folder: &mut F) -> Self {
+                    self
+                }
+            }
+        )*
+    };
+}
+leaf_foldable!(
+    bool, f64, char, string::String, Str<'src>,
+    KeywordData, KeywordSuffixData, KeywordWrappedData, PositionRange, Separators<'src>
+);
+
+// Generates the `Fold` trait, and dispatches each enum to its method. Enums
+// listed after the semicolon only appear in lists, and fold into a `Vec`.
+macro_rules! folders {
+    (
+        $($module:ident::$node:ident: $method:ident,)*
+        ;
+        $($list_module:ident::$list_node:ident: $list_method:ident,)*
+    ) => {
+        pub trait Fold<'src> {
+            $(
+                fn $method(&mut self, node: $module::$node<'src>) -> $module::$node<'src> {
+                    node.fold_children(self)
+                }
+            )*
+            $(
+                fn $list_method(&mut self, node: $list_module::$list_node<'src>) -> Vec<$list_module::$list_node<'src>> {
+                    vec![node.fold_children(self)]
+                }
+            )*
+        }
+
+        $(
+            impl<'src> Foldable<'src> for $module::$node<'src> {
+                fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+                    folder.$method(self)
+                }
+            }
+        )*
+        $(
+            impl<'src> Foldable<'src> for $list_module::$list_node<'src> {
+                fn fold<F: Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+                    let mut nodes = folder.$list_method(self);
+                    assert_eq!(nodes.len(), 1, "{} must fold to a single node outside of a list", stringify!($list_node));
+                    nodes.pop().unwrap()
+                }
+                fn fold_into<F: Fold<'src> + ?Sized>(self, folder: &mut F, out: &mut Vec<Self>) {
+                    out.extend(folder.$list_method(self));
+                }
+            }
+        )*
+    };
+}
+
+// The list of enums is collected from the `node_enum!` declarations by
+// build.rs.
+include!(concat!(env!("OUT_DIR"), "/folders.rs"));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::literal;
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
+
+    // Folds additions of two numbers into a single number.
+    struct ConstantAdd;
+    impl<'src> Fold<'src> for ConstantAdd {
+        fn fold_expression(&mut self, node: alias::Expression<'src>) -> alias::Expression<'src> {
+            match node.fold_children(self) {
+                alias::Expression::Add(add) => {
+                    let add = NodeBox::into_inner(add);
+                    match (NodeBox::into_inner(add.left), NodeBox::into_inner(add.right)) {
+                        (alias::Expression::Numeric(left), alias::Expression::Numeric(right)) => {
+                            literal::Numeric::from(left.value + right.value).into()
+                        }
+                        (left, right) => expression::AddExpression {
+                            left: left.into(),
+                            right: right.into(),
+                            ..add
+                        }.into(),
+                    }
+                }
+                node => node,
+            }
+        }
+    }
+
+    // Drops debugger statements and repeats other expression statements.
+    struct Statements;
+    impl<'src> Fold<'src> for Statements {
+        fn fold_statement_item(&mut self, node: alias::StatementItem<'src>) -> Vec<alias::StatementItem<'src>> {
+            match node {
+                alias::StatementItem::Debugger(_) => vec![],
+                alias::StatementItem::Expression(stmt) => {
+                    let copy = statement::ExpressionStatement::new(
+                        ConstantAdd.fold_expression(literal::Numeric::from(0.0).into()),
+                    );
+                    vec![NodeBox::into_inner(stmt).fold_children(self).into(), copy.into()]
+                }
+                node => vec![node.fold_children(self)],
+            }
+        }
+    }
+
+    fn parse(code: &str) -> root::Script<'_> {
+        Parser::new(code.into_tokenizer()).parse_script().unwrap()
+    }
+
+    #[test]
+    fn it_replaces_expressions() {
+        let script = parse("a = 1 + 2 + b; f(3 + 4, [5 + 6 + 7, (8 + 9)]);");
+
+        let script = script.fold(&mut ConstantAdd);
+
+        assert_eq!(format!("{}", script), "a=3+b;f(7,[18,(17)]);");
+    }
+
+    #[test]
+    fn it_replaces_statements_with_lists() {
+        let script = parse("debugger; a(); if (b) { debugger; c(); }");
+
+        let script = script.fold(&mut Statements);
+
+        assert_eq!(format!("{}", script), "a();0;if(b){c();0;}");
+    }
+}
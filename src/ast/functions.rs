@@ -1,7 +1,6 @@
-use std::string;
 use std::default;
 
-use ast::{MaybeTokenPosition, KeywordData, SeparatorTokens};
+use ast::{KeywordData, SeparatorTokens, Str, NodeBox};
 
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punctuator, Precedence,
                    LookaheadRestriction, LookaheadSequence};
@@ -12,25 +11,28 @@ use ast::alias;
 use ast::patterns::BindingPattern;
 
 use ast::decorators::DecoratorValue;
+use ast::typescript::{Modifier, TypeAnnotation, TypeParameters};
+use ast::flow;
 
 
 node!(pub struct Directive {
-    pub value: DirectiveLiteral,
+    pub value: DirectiveLiteral<'src>,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for Directive {
+impl<'src> NodeDisplay for Directive<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.value)?;
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
-impl<T: Into<DirectiveLiteral>> From<T> for Directive {
-    fn from(v: T) -> Directive {
+impl<'src, T: Into<DirectiveLiteral<'src>>> From<T> for Directive<'src> {
+    fn from(v: T) -> Directive<'src> {
         Directive {
             value: v.into(),
             token_semi: Default::default(),
             position: None,
+            comments: None,
         }
     }
 }
@@ -45,22 +47,21 @@ mod tests_directive {
 }
 
 node!(pub struct DirectiveLiteral {
-    pub tokens_prefix: SeparatorTokens,
-    pub value: string::String,
+    pub tokens_prefix: SeparatorTokens<'src>,
+    pub value: Str<'src>,
 });
-impl NodeDisplay for DirectiveLiteral {
+impl<'src> NodeDisplay for DirectiveLiteral<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        // TODO: This needs its own primitive rendering to preserve
-        // the codepoint sequence exactly.
-        f.string(&self.value, Some(&self.value))
+        f.directive(&self.value)
     }
 }
-impl<T: Into<string::String>> From<T> for DirectiveLiteral {
-    fn from(v: T) -> DirectiveLiteral {
+impl<'src, T: Into<Str<'src>>> From<T> for DirectiveLiteral<'src> {
+    fn from(v: T) -> DirectiveLiteral<'src> {
         DirectiveLiteral {
             tokens_prefix: Default::default(),
             value: v.into(),
             position: None,
+            comments: None,
         }
     }
 }
@@ -75,35 +76,31 @@ mod tests_directive_literal {
 }
 
 
-node_kind!(pub enum FunctionKind {
+node_kind!(#[derive(Default)] pub enum FunctionKind {
+    #[default]
     Normal,
     Generator,
     Async,
     AsyncGenerator, // experimental
 });
-impl default::Default for FunctionKind {
-    fn default() -> FunctionKind {
-        FunctionKind::Normal
-    }
-}
 impl NodeDisplay for FunctionKind {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         match *self {
             FunctionKind::Normal => {
-                f.keyword(Keyword::Function);
+                f.keyword(Keyword::Function, &());
             }
             FunctionKind::Generator => {
-                f.keyword(Keyword::Function);
-                f.punctuator(Punctuator::Star);
+                f.keyword(Keyword::Function, &());
+                f.punctuator(Punctuator::Star, &());
             }
             FunctionKind::Async => {
-                f.keyword(Keyword::Async);
-                f.keyword(Keyword::Function);
+                f.keyword(Keyword::Async, &());
+                f.keyword(Keyword::Function, &());
             }
             FunctionKind::AsyncGenerator => {
-                f.keyword(Keyword::Async);
-                f.keyword(Keyword::Function);
-                f.punctuator(Punctuator::Star);
+                f.keyword(Keyword::Async, &());
+                f.keyword(Keyword::Function, &());
+                f.punctuator(Punctuator::Star, &());
             }
         }
         Ok(())
@@ -112,15 +109,57 @@ impl NodeDisplay for FunctionKind {
 
 
 node!(#[derive(Default)] pub struct FunctionParams {
-    pub params: Vec<(FunctionParam, KeywordData)>,
-    pub last_param: Option<FunctionLastParam>,
+    pub type_parameters: Option<NodeBox<TypeParameters<'src>>>, // experimental: TypeScript
+    pub params: Vec<(FunctionParam<'src>, KeywordData)>,
+    pub last_param: Option<FunctionLastParam<'src>>,
+    pub return_type: Option<NodeBox<TypeAnnotation<'src>>>, // experimental: TypeScript
+    pub predicate: Option<NodeBox<flow::Predicate<'src>>>, // Flow
 });
-impl NodeDisplay for FunctionParams {
+impl<'src> NodeDisplay for FunctionParams<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        let mut f = f.wrap_parens();
+        if let Some(ref type_parameters) = self.type_parameters {
+            f.node(type_parameters)?;
+        }
+        {
+            let mut f = f.wrap_parens();
+
+            // A TypeScript "this" parameter only gives the type of "this".
+            let mut params = &self.params[..];
+            let mut last_param = self.last_param.as_ref();
+            if f.strip_types() {
+                match (params.first(), last_param) {
+                    (Some((param, _)), _) if param.is_this() => params = &params[1..],
+                    (None, Some(FunctionLastParam::Param(param))) if param.is_this() => last_param = None,
+                    _ => {}
+                }
+            }
 
-        f.comma_list(&self.params)?;
-        f.node(&self.last_param)
+            f.comma_list(params)?;
+            if let Some(last_param) = last_param {
+                f.node(last_param)?;
+            }
+        }
+        if let Some(ref return_type) = self.return_type {
+            f.node(return_type)?;
+        }
+        if let Some(ref predicate) = self.predicate {
+            f.node(predicate)?;
+        }
+        Ok(())
+    }
+}
+impl<'src> From<Vec<FunctionParam<'src>>> for FunctionParams<'src> {
+    fn from(mut v: Vec<FunctionParam>) -> FunctionParams {
+        let last_param = v.pop().map(Into::into);
+        FunctionParams {
+            type_parameters: None,
+            params: v.into_iter().map(|p| (p, Default::default())).collect(),
+            last_param,
+            return_type: None,
+            predicate: None,
+            position: None,
+            comments: None,
+        }
     }
 }
 #[cfg(test)]
@@ -137,24 +176,19 @@ mod tests_function_params {
     #[test]
     fn it_prints_params() {
         assert_serialize!(
-            FunctionParams {
-                params: vec![
-                    FunctionParam {
-                        decorators: Default::default(),
-                        id: BindingIdentifier::from("arg").into(),
-                        init: Default::default(),
-                        position: None,
-                    },
-                    FunctionParam {
-                        decorators: Default::default(),
-                        id: BindingIdentifier::from("arg2").into(),
-                        init: literal::Boolean::from(true).into(),
-                        position: None,
-                    },
-                ],
-                rest: Default::default(),
-                position: None,
-            },
+            FunctionParams::from(vec![
+                BindingIdentifier::from("arg").into(),
+                FunctionParam {
+                    decorators: Default::default(),
+                    modifiers: Default::default(),
+                    id: BindingIdentifier::from("arg2").into(),
+                    optional: false,
+                    type_annotation: None,
+                    init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
+                    position: None,
+                    comments: None,
+                },
+            ]),
             "(arg,arg2=true)"
         );
     }
@@ -163,12 +197,18 @@ mod tests_function_params {
     fn it_prints_rest() {
         assert_serialize!(
             FunctionParams {
+                type_parameters: None,
                 params: Default::default(),
-                rest: FunctionRestParam {
+                last_param: Some(FunctionRestParam {
                     id: BindingIdentifier::from("arg").into(),
+                    type_annotation: None,
                     position: None,
-                }.into(),
+                    comments: None,
+                }.into()),
+                return_type: None,
+                predicate: None,
                 position: None,
+                comments: None,
             },
             "(...arg)"
         );
@@ -178,25 +218,33 @@ mod tests_function_params {
     fn it_prints_params_and_rest() {
         assert_serialize!(
             FunctionParams {
+                type_parameters: None,
                 params: vec![
-                    FunctionParam {
-                        decorators: Default::default(),
-                        id: BindingIdentifier::from("arg").into(),
-                        init: Default::default(),
-                        position: None,
-                    },
-                    FunctionParam {
-                        decorators: Default::default(),
-                        id: BindingIdentifier::from("arg2").into(),
-                        init: literal::Boolean::from(true).into(),
-                        position: None,
-                    },
+                    (BindingIdentifier::from("arg").into(), Default::default()),
+                    (
+                        FunctionParam {
+                            decorators: Default::default(),
+                            modifiers: Default::default(),
+                            id: BindingIdentifier::from("arg2").into(),
+                            optional: false,
+                            type_annotation: None,
+                            init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
+                            position: None,
+                            comments: None,
+                        },
+                        Default::default(),
+                    ),
                 ],
-                rest: FunctionRestParam {
+                last_param: Some(FunctionRestParam {
                     id: BindingIdentifier::from("arg3").into(),
+                    type_annotation: None,
                     position: None,
-                }.into(),
+                    comments: None,
+                }.into()),
+                return_type: None,
+                predicate: None,
                 position: None,
+                comments: None,
             },
             "(arg,arg2=true,...arg3)"
         );
@@ -204,45 +252,74 @@ mod tests_function_params {
 }
 
 node_enum!(@node_display pub enum FunctionLastParam {
-    Param(FunctionParam),
-    Rest(FunctionRestParam),
+    Param(FunctionParam<'src>),
+    Rest(FunctionRestParam<'src>),
 });
 
 
 node!(pub struct FunctionParam {
-    pub decorators: Vec<FunctionParamDecorator>, // experimental
-    pub id: BindingPattern,
-    pub init: Option<general::Initializer>,
+    pub decorators: Vec<FunctionParamDecorator<'src>>, // experimental
+    pub modifiers: Vec<Modifier<'src>>, // experimental: TypeScript
+    pub id: BindingPattern<'src>,
+    pub optional: bool, // experimental: TypeScript
+    pub type_annotation: Option<TypeAnnotation<'src>>, // experimental: TypeScript
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for FunctionParam {
+impl<'src> FunctionParam<'src> {
+    /// Whether this is TypeScript's "this" parameter, like "f(this: A)".
+    pub fn is_this(&self) -> bool {
+        match self.id {
+            BindingPattern::Identifier(ref id) => id.value == "this",
+            _ => false,
+        }
+    }
+}
+impl<'src> NodeDisplay for FunctionParam<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.node_list(&self.decorators)?;
+        f.decorators(&self.decorators)?;
+        for modifier in self.modifiers.iter() {
+            f.node(modifier)?;
+        }
 
         f.node(&self.id)?;
+        if self.optional && !f.strip_types() {
+            f.punctuator(Punctuator::Question, &());
+        }
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
         f.node(&self.init)?;
 
         Ok(())
     }
 }
-impl<T: Into<BindingPattern>> From<T> for FunctionParam {
-    fn from(v: T) -> FunctionParam {
+impl<'src, T: Into<BindingPattern<'src>>> From<T> for FunctionParam<'src> {
+    fn from(v: T) -> FunctionParam<'src> {
         FunctionParam {
             decorators: Default::default(),
+            modifiers: Default::default(),
             id: v.into(),
+            optional: false,
+            type_annotation: None,
             init: Default::default(),
             position: None,
+            comments: None,
         }
     }
 }
 
 
 node!(pub struct FunctionRestParam {
-    pub id: BindingPattern,
+    pub id: BindingPattern<'src>,
+    pub type_annotation: Option<TypeAnnotation<'src>>, // experimental: TypeScript
 });
-impl NodeDisplay for FunctionRestParam {
+impl<'src> NodeDisplay for FunctionRestParam<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &());
         f.node(&self.id)?;
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
 
         Ok(())
     }
@@ -250,10 +327,10 @@ impl NodeDisplay for FunctionRestParam {
 
 
 node!(#[derive(Default)] pub struct FunctionBody {
-    pub directives: Vec<Directive>,
-    pub body: Vec<alias::StatementItem>,
+    pub directives: Vec<Directive<'src>>,
+    pub body: Vec<alias::StatementItem<'src>>,
 });
-impl NodeDisplay for FunctionBody {
+impl<'src> NodeDisplay for FunctionBody<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_curly();
 
@@ -265,11 +342,11 @@ impl NodeDisplay for FunctionBody {
 }
 
 node!(pub struct FunctionParamDecorator {
-    pub value: DecoratorValue,
+    pub value: DecoratorValue<'src>,
 });
-impl NodeDisplay for FunctionParamDecorator {
+impl<'src> NodeDisplay for FunctionParamDecorator<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::At);
+        f.punctuator(Punctuator::At, &());
         f.node(&self.value)
     }
 }
@@ -278,14 +355,14 @@ impl NodeDisplay for FunctionParamDecorator {
 // export default function name() {}
 node!(#[derive(Default)] pub struct ExportDefaultFunctionDeclaration {
     pub kind: FunctionKind,
-    pub id: Option<BindingIdentifier>,
-    pub params: FunctionParams,
-    pub body: FunctionBody,
+    pub id: Option<BindingIdentifier<'src>>,
+    pub params: FunctionParams<'src>,
+    pub body: FunctionBody<'src>,
 });
-impl NodeDisplay for ExportDefaultFunctionDeclaration {
+impl<'src> NodeDisplay for ExportDefaultFunctionDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
-        f.keyword(Keyword::Default);
+        f.keyword(Keyword::Export, &());
+        f.keyword(Keyword::Default, &());
         f.node(&self.kind)?;
         f.node(&self.id)?;
         f.node(&self.params)?;
@@ -314,6 +391,7 @@ mod tests_function_export_default {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "export default function someName(){}"
         );
@@ -323,11 +401,11 @@ mod tests_function_export_default {
 // function name() {}
 node!(pub struct FunctionDeclaration {
     pub kind: FunctionKind,
-    pub id: BindingIdentifier,
-    pub params: FunctionParams,
-    pub body: FunctionBody,
+    pub id: BindingIdentifier<'src>,
+    pub params: FunctionParams<'src>,
+    pub body: FunctionBody<'src>,
 });
-impl NodeDisplay for FunctionDeclaration {
+impl<'src> NodeDisplay for FunctionDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.kind)?;
         f.node(&self.id)?;
@@ -348,6 +426,7 @@ mod tests_function_declaration {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "function someName(){}"
         );
@@ -357,11 +436,11 @@ mod tests_function_declaration {
 // (function(){})
 node!(#[derive(Default)] pub struct FunctionExpression {
     pub kind: FunctionKind,
-    pub id: Option<BindingIdentifier>,
-    pub params: FunctionParams,
-    pub body: FunctionBody,
+    pub id: Option<BindingIdentifier<'src>>,
+    pub params: FunctionParams<'src>,
+    pub body: FunctionBody<'src>,
 });
-impl NodeDisplay for FunctionExpression {
+impl<'src> NodeDisplay for FunctionExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.lookahead_wrap_parens(LookaheadSequence::Declaration);
 
@@ -389,6 +468,7 @@ mod tests_function_expression {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "function someName(){}"
         );
@@ -400,40 +480,37 @@ mod tests_function_expression {
 node!(#[derive(Default)] pub struct ArrowFunctionExpression {
     // TODO: Needs to handle single-param Ident output as type of params
     pub kind: ArrowFunctionKind,
-    pub params: ArrowFunctionParams,
-    pub body: ArrowFunctionBody,
+    pub params: ArrowFunctionParams<'src>,
+    pub body: ArrowFunctionBody<'src>,
 });
-node_kind!(pub enum ArrowFunctionKind {
+node_kind!(#[derive(Default)] pub enum ArrowFunctionKind {
+    #[default]
     Normal,
     Async,
     Generator, // experimental
     AsyncGenerator, // experimental
 });
-impl default::Default for ArrowFunctionKind {
-    fn default() -> ArrowFunctionKind {
-        ArrowFunctionKind::Normal
-    }
-}
-impl NodeDisplay for ArrowFunctionExpression {
+impl<'src> NodeDisplay for ArrowFunctionExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.precedence(Precedence::Assignment);
         match self.kind {
             ArrowFunctionKind::Normal => {
                 f.node(&self.params)?;
-                f.punctuator(Punctuator::Arrow);
+                f.operator(Punctuator::Arrow, &());
             }
             ArrowFunctionKind::Async => {
-                f.keyword(Keyword::Async);
+                f.keyword(Keyword::Async, &());
                 f.node(&self.params)?;
-                f.punctuator(Punctuator::Arrow);
+                f.operator(Punctuator::Arrow, &());
             }
             ArrowFunctionKind::Generator => {
                 f.node(&self.params)?;
-                f.punctuator(Punctuator::ArrowStar);
+                f.operator(Punctuator::ArrowStar, &());
             }
             ArrowFunctionKind::AsyncGenerator => {
-                f.keyword(Keyword::Async);
+                f.keyword(Keyword::Async, &());
                 f.node(&self.params)?;
-                f.punctuator(Punctuator::ArrowStar);
+                f.operator(Punctuator::ArrowStar, &());
             }
         }
 
@@ -458,6 +535,7 @@ mod tests_arrow_function_expression {
                 params: BindingIdentifier::from("arg").into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "arg=>{}"
         );
@@ -471,6 +549,7 @@ mod tests_arrow_function_expression {
                 params: BindingIdentifier::from("arg").into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "async arg=>{}"
         );
@@ -484,6 +563,7 @@ mod tests_arrow_function_expression {
                 params: BindingIdentifier::from("arg").into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "arg=*>{}"
         );
@@ -497,6 +577,7 @@ mod tests_arrow_function_expression {
                 params: BindingIdentifier::from("arg").into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "async arg=*>{}"
         );
@@ -507,16 +588,13 @@ mod tests_arrow_function_expression {
         assert_serialize!(
             ArrowFunctionExpression {
                 kind: Default::default(),
-                params: FunctionParams {
-                    params: vec![
-                        BindingIdentifier::from("arg1").into(),
-                        BindingIdentifier::from("arg2").into(),
-                    ],
-                    rest: Default::default(),
-                    position: None,
-                }.into(),
+                params: FunctionParams::from(vec![
+                    BindingIdentifier::from("arg1").into(),
+                    BindingIdentifier::from("arg2").into(),
+                ]).into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "(arg1,arg2)=>{}"
         );
@@ -527,16 +605,13 @@ mod tests_arrow_function_expression {
         assert_serialize!(
             ArrowFunctionExpression {
                 kind: ArrowFunctionKind::Async,
-                params: FunctionParams {
-                    params: vec![
-                        BindingIdentifier::from("arg1").into(),
-                        BindingIdentifier::from("arg2").into(),
-                    ],
-                    rest: Default::default(),
-                    position: None,
-                }.into(),
+                params: FunctionParams::from(vec![
+                    BindingIdentifier::from("arg1").into(),
+                    BindingIdentifier::from("arg2").into(),
+                ]).into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "async(arg1,arg2)=>{}"
         );
@@ -547,16 +622,13 @@ mod tests_arrow_function_expression {
         assert_serialize!(
             ArrowFunctionExpression {
                 kind: ArrowFunctionKind::Generator,
-                params: FunctionParams {
-                    params: vec![
-                        BindingIdentifier::from("arg1").into(),
-                        BindingIdentifier::from("arg2").into(),
-                    ],
-                    rest: Default::default(),
-                    position: None,
-                }.into(),
+                params: FunctionParams::from(vec![
+                    BindingIdentifier::from("arg1").into(),
+                    BindingIdentifier::from("arg2").into(),
+                ]).into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "(arg1,arg2)=*>{}"
         );
@@ -567,49 +639,46 @@ mod tests_arrow_function_expression {
         assert_serialize!(
             ArrowFunctionExpression {
                 kind: ArrowFunctionKind::AsyncGenerator,
-                params: FunctionParams {
-                    params: vec![
-                        BindingIdentifier::from("arg1").into(),
-                        BindingIdentifier::from("arg2").into(),
-                    ],
-                    rest: Default::default(),
-                    position: None,
-                }.into(),
+                params: FunctionParams::from(vec![
+                    BindingIdentifier::from("arg1").into(),
+                    BindingIdentifier::from("arg2").into(),
+                ]).into(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "async(arg1,arg2)=*>{}"
         );
     }
 }
 
-node_enum!(@node_display pub enum ArrowFunctionParams {
-    Singular(BindingIdentifier),
-    Normal(FunctionParams),
+node_enum!(@boxed @node_display pub enum ArrowFunctionParams {
+    Singular(BindingIdentifier<'src>),
+    Normal(FunctionParams<'src>),
 });
-impl Default for ArrowFunctionParams {
-    fn default() -> ArrowFunctionParams {
+impl<'src> Default for ArrowFunctionParams<'src> {
+    fn default() -> ArrowFunctionParams<'src> {
         ArrowFunctionParams::Normal(Default::default())
     }
 }
 
 
 node_enum!(@node_display pub enum ArrowFunctionBody {
-    Expression(ArrowFunctionExpressionBody),
+    Expression(ArrowFunctionExpressionBody<'src>),
     // TODO: Do we need an async arrow body for fn return val
-    Block(FunctionBody),
+    Block(FunctionBody<'src>),
 });
-impl default::Default for ArrowFunctionBody {
-    fn default() -> ArrowFunctionBody {
+impl<'src> default::Default for ArrowFunctionBody<'src> {
+    fn default() -> ArrowFunctionBody<'src> {
         ArrowFunctionBody::Block(Default::default())
     }
 }
 
 
 node!(pub struct ArrowFunctionExpressionBody {
-    pub expression: Box<alias::Expression>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ArrowFunctionExpressionBody {
+impl<'src> NodeDisplay for ArrowFunctionExpressionBody<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.restrict_lookahead(LookaheadRestriction::ConciseBody);
         f.require_precedence(Precedence::Assignment).node(
@@ -618,11 +687,12 @@ impl NodeDisplay for ArrowFunctionExpressionBody {
         Ok(())
     }
 }
-impl<T: Into<alias::Expression>> From<T> for ArrowFunctionExpressionBody {
-    fn from(v: T) -> ArrowFunctionExpressionBody {
+impl<'src, T: Into<alias::Expression<'src>>> From<T> for ArrowFunctionExpressionBody<'src> {
+    fn from(v: T) -> ArrowFunctionExpressionBody<'src> {
         ArrowFunctionExpressionBody {
-            expression: Box::new(v.into()),
+            expression: NodeBox::new(v.into()),
             position: None,
+            comments: None,
         }
     }
 }
@@ -638,6 +708,7 @@ mod tests_arrow_function_expression_body {
             ArrowFunctionExpressionBody {
                 expression: literal::Boolean::from(false).into(),
                 position: None,
+                comments: None,
             },
             "false"
         );
@@ -649,6 +720,7 @@ mod tests_arrow_function_expression_body {
             ArrowFunctionExpressionBody {
                 expression: objects::ObjectExpression::default().into(),
                 position: None,
+                comments: None,
             },
             "({})"
         );
@@ -1,6 +1,4 @@
-use std::string;
-
-use ast::{MaybeTokenPosition, KeywordWrappedData};
+use ast::{KeywordWrappedData, Str, NodeBox};
 
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Precedence, Punctuator};
 use ast::alias;
@@ -8,30 +6,32 @@ use ast::literal;
 
 // identifiers used as binding names
 node!(pub struct BindingIdentifier {
-    pub value: string::String,
-    pub raw: Option<string::String>,
+    pub value: Str<'src>,
+    pub raw: Option<Str<'src>>,
 });
-impl BindingIdentifier {
-    pub fn new<T: Into<String>>(s: T) -> BindingIdentifier {
+impl<'src> BindingIdentifier<'src> {
+    pub fn new<T: Into<Str<'src>>>(s: T) -> BindingIdentifier<'src> {
         BindingIdentifier {
             value: s.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
-impl NodeDisplay for BindingIdentifier {
+impl<'src> NodeDisplay for BindingIdentifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.identifier(&self.value, self.raw.as_ref().map(string::String::as_str))
+        f.identifier(&self.value, self.raw.as_deref())
     }
 }
 
-impl<T: Into<string::String>> From<T> for BindingIdentifier {
-    fn from(value: T) -> BindingIdentifier {
+impl<'src, T: Into<Str<'src>>> From<T> for BindingIdentifier<'src> {
+    fn from(value: T) -> BindingIdentifier<'src> {
         BindingIdentifier {
             value: value.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
@@ -39,29 +39,35 @@ impl<T: Into<string::String>> From<T> for BindingIdentifier {
 
 // identifiers used as references to bindings
 node!(pub struct ReferenceIdentifier {
-    pub value: string::String,
-    pub raw: Option<string::String>,
+    pub value: Str<'src>,
+    pub raw: Option<Str<'src>>,
 });
-impl ReferenceIdentifier {
-    pub fn new<T: Into<String>>(s: T) -> ReferenceIdentifier {
+impl<'src> ReferenceIdentifier<'src> {
+    pub fn new<T: Into<Str<'src>>>(s: T) -> ReferenceIdentifier<'src> {
         ReferenceIdentifier {
             value: s.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
-impl NodeDisplay for ReferenceIdentifier {
+impl<'src> NodeDisplay for ReferenceIdentifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.identifier(&self.value, self.raw.as_ref().map(string::String::as_str))
+        if let Some(id) = f.enum_member(&self.value).map(String::from) {
+            f.identifier(&id, None)?;
+            return f.wrap_square().string(&self.value, None);
+        }
+        f.identifier(&self.value, self.raw.as_deref())
     }
 }
-impl<T: Into<string::String>> From<T> for ReferenceIdentifier {
-    fn from(value: T) -> ReferenceIdentifier {
+impl<'src, T: Into<Str<'src>>> From<T> for ReferenceIdentifier<'src> {
+    fn from(value: T) -> ReferenceIdentifier<'src> {
         ReferenceIdentifier {
             value: value.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
@@ -70,45 +76,47 @@ impl<T: Into<string::String>> From<T> for ReferenceIdentifier {
 
 // identifiers used as properties
 node!(pub struct PropertyIdentifier {
-    pub value: string::String,
-    pub raw: Option<string::String>,
+    pub value: Str<'src>,
+    pub raw: Option<Str<'src>>,
 });
-impl PropertyIdentifier {
-    pub fn new<T: Into<String>>(s: T) -> PropertyIdentifier {
+impl<'src> PropertyIdentifier<'src> {
+    pub fn new<T: Into<Str<'src>>>(s: T) -> PropertyIdentifier<'src> {
         PropertyIdentifier {
             value: s.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
-impl NodeDisplay for PropertyIdentifier {
+impl<'src> NodeDisplay for PropertyIdentifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.identifier(&self.value, self.raw.as_ref().map(String::as_str))
+        f.identifier(&self.value, self.raw.as_deref())
     }
 }
-impl<T: Into<string::String>> From<T> for PropertyIdentifier {
-    fn from(value: T) -> PropertyIdentifier {
+impl<'src, T: Into<Str<'src>>> From<T> for PropertyIdentifier<'src> {
+    fn from(value: T) -> PropertyIdentifier<'src> {
         PropertyIdentifier {
             value: value.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
 
 
 node_enum!(@node_display pub enum PropertyName {
-    Identifier(PropertyIdentifier),
-    String(literal::String),
-    Number(literal::Numeric),
-    Computed(ComputedPropertyName),
+    Identifier(PropertyIdentifier<'src>),
+    String(literal::String<'src>),
+    Number(literal::Numeric<'src>),
+    Computed(ComputedPropertyName<'src>),
 });
 
 node!(pub struct ComputedPropertyName {
-    pub expression: Box<alias::Expression>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ComputedPropertyName {
+impl<'src> NodeDisplay for ComputedPropertyName<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_square();
 
@@ -123,19 +131,21 @@ impl NodeDisplay for ComputedPropertyName {
 
 node!(pub struct Initializer {
     pub token_eq: KeywordWrappedData,
-    pub expression: Box<alias::Expression>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for Initializer {
+impl<'src> NodeDisplay for Initializer<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Eq, &self.token_eq);
+        f.operator(Punctuator::Eq, &self.token_eq);
         f.require_precedence(Precedence::Assignment).node(&self.expression)
     }
 }
-impl From<alias::Expression> for Initializer {
+impl<'src> From<alias::Expression<'src>> for Initializer<'src> {
     fn from(expression: alias::Expression) -> Initializer {
         Initializer {
-            expression,
+            token_eq: Default::default(),
+            expression: NodeBox::new(expression),
             position: None,
+            comments: None,
         }
     }
 }
@@ -1,44 +1,51 @@
-use std::string;
-
-use ast::MaybeTokenPosition;
-
+use ast::Str;
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Punctuator, Precedence};
 
 use ast::alias;
+use ast::NodeBox;
 
 node!(pub struct Element {
-    pub opening: ElementName,
-    pub attributes: Vec<Attribute>,
-    pub children: Vec<Child>,
-    pub closing: Option<ElementName>,
+    pub opening: ElementName<'src>,
+    pub attributes: Vec<Attribute<'src>>,
+    pub children: Vec<Child<'src>>,
+    pub closing: Option<ElementName<'src>>,
 });
-impl NodeDisplay for Element {
+impl<'src> NodeDisplay for Element<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::AngleL);
+        // The "<" is spaced like any other token, and the rest of the
+        // element is printed as it would be written.
+        f.punctuator(Punctuator::AngleL, &());
+        let mut f = f.jsx();
         f.node(&self.opening)?;
 
-        f.node_list(&self.attributes)?;
+        for attribute in &self.attributes {
+            // Names would run into the name or attribute before them.
+            if let Attribute::Pair(_) = *attribute {
+                f.jsx_space()?;
+            }
+            f.node(attribute)?;
+        }
 
-        if self.children.len() > 0 {
-            f.punctuator(Punctuator::AngleR);
+        if !self.children.is_empty() {
+            f.punctuator(Punctuator::AngleR, &());
 
             f.node_list(&self.children)?;
 
-            f.punctuator(Punctuator::AngleSlash);
+            f.punctuator(Punctuator::AngleSlash, &());
             if let Some(ref close) = self.closing {
                 f.node(close)?;
             } else {
                 f.node(&self.opening)?;
             }
-            f.punctuator(Punctuator::AngleR);
+            f.punctuator(Punctuator::AngleR, &());
         } else {
             if let Some(ref close) = self.closing {
-                f.punctuator(Punctuator::AngleR);
-                f.punctuator(Punctuator::AngleSlash);
+                f.punctuator(Punctuator::AngleR, &());
+                f.punctuator(Punctuator::AngleSlash, &());
                 f.node(close)?;
-                f.punctuator(Punctuator::AngleR);
+                f.punctuator(Punctuator::AngleR, &());
             } else {
-                f.punctuator(Punctuator::SlashAngle);
+                f.punctuator(Punctuator::SlashAngle, &());
             }
         }
 
@@ -59,6 +66,7 @@ mod tests_element {
                 children: Default::default(),
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<foo-bar/>"
         );
@@ -70,13 +78,15 @@ mod tests_element {
             Element {
                 opening: MemberExpression {
                     object: Identifier::from("foo-bar").into(),
-                    property: Identifier::from("baz-bat").into(),
+                    property: Identifier::from("baz-bat"),
                     position: None,
+                    comments: None,
                 }.into(),
                 attributes: Default::default(),
                 children: Default::default(),
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<foo-bar.baz-bat/>"
         );
@@ -87,14 +97,16 @@ mod tests_element {
         assert_serialize!(
             Element {
                 opening: NamespacedName {
-                    namespace: Identifier::from("foo-bar").into(),
-                    name: Identifier::from("baz-bat").into(),
+                    namespace: Identifier::from("foo-bar"),
+                    name: Identifier::from("baz-bat"),
                     position: None,
+                    comments: None,
                 }.into(),
                 attributes: Default::default(),
                 children: Default::default(),
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<foo-bar:baz-bat/>"
         );
@@ -109,6 +121,7 @@ mod tests_element {
                 children: Default::default(),
                 closing: Identifier::from("foo-bar").into(),
                 position: None,
+                comments: None,
             },
             "<foo-bar></foo-bar>"
         );
@@ -123,11 +136,13 @@ mod tests_element {
                     SpreadAttribute {
                         expression: ReferenceIdentifier::from("someVar").into(),
                         position: None,
+                        comments: None,
                     }.into(),
                 ],
                 children: Default::default(),
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<foo-bar{...someVar}/>"
         );
@@ -142,6 +157,7 @@ mod tests_element {
                 children: vec![Text::from("some text").into()],
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<foo-bar>some text</foo-bar>"
         );
@@ -156,6 +172,7 @@ mod tests_element {
                 children: vec![Text::from("some text").into()],
                 closing: Identifier::from("foo-bar").into(),
                 position: None,
+                comments: None,
             },
             "<foo-bar>some text</foo-bar>"
         );
@@ -165,66 +182,67 @@ mod tests_element {
 
 node!(pub struct Identifier {
     // Same as a JS identifier, but allows "-"
-    pub raw: Option<string::String>,
-    pub value: string::String,
+    pub raw: Option<Str<'src>>,
+    pub value: Str<'src>,
 });
-impl NodeDisplay for Identifier {
+impl<'src> NodeDisplay for Identifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.jsx_identifier(&self.value, self.raw.as_ref().map(String::as_str))
+        f.jsx_identifier(&self.value, self.raw.as_deref())
     }
 }
-impl<T: Into<string::String>> From<T> for Identifier {
-    fn from(v: T) -> Identifier {
+impl<'src, T: Into<Str<'src>>> From<T> for Identifier<'src> {
+    fn from(v: T) -> Identifier<'src> {
         Identifier {
             raw: None,
             value: v.into(),
             position: None,
+            comments: None,
         }
     }
 }
 
 
 node_enum!(@node_display pub enum ElementName {
-    Identifier(Identifier),
-    Member(MemberExpression),
-    Namespaced(NamespacedName),
+    Identifier(Identifier<'src>),
+    Member(MemberExpression<'src>),
+    Namespaced(NamespacedName<'src>),
 });
 
 
 node!(pub struct MemberExpression {
-    pub object: Box<MemberObject>,
-    pub property: Identifier,
+    pub object: NodeBox<MemberObject<'src>>,
+    pub property: Identifier<'src>,
 });
-impl NodeDisplay for MemberExpression {
+impl<'src> NodeDisplay for MemberExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.object)?;
-        f.punctuator(Punctuator::Period);
+        f.punctuator(Punctuator::Period, &());
         f.node(&self.property)
     }
 }
 
 node_enum!(@node_display pub enum MemberObject {
-    Identifier(Identifier),
-    Member(MemberExpression),
+    Identifier(Identifier<'src>),
+    Member(MemberExpression<'src>),
 });
 
 
 node!(pub struct NamespacedName {
-    pub namespace: Identifier,
-    pub name: Identifier,
+    pub namespace: Identifier<'src>,
+    pub name: Identifier<'src>,
 });
-impl NodeDisplay for NamespacedName {
+impl<'src> NodeDisplay for NamespacedName<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.namespace)?;
-        f.punctuator(Punctuator::Colon);
+        f.punctuator(Punctuator::Colon, &());
         f.node(&self.name)
     }
 }
 
 
-node_enum!(@node_display pub enum Attribute {
-    Spread(SpreadAttribute),
-    Pair(PairAttribute),
+node_enum!(@boxed @node_display pub enum Attribute {
+    Spread(SpreadAttribute<'src>),
+    Pair(PairAttribute<'src>),
 });
 #[cfg(test)]
 mod tests_attribute {
@@ -237,6 +255,7 @@ mod tests_attribute {
             SpreadAttribute {
                 expression: ReferenceIdentifier::from("attrName").into(),
                 position: None,
+                comments: None,
             },
             "{...attrName}"
         );
@@ -249,6 +268,7 @@ mod tests_attribute {
                 name: Identifier::from("attrName").into(),
                 value: StringAttribute::from("omg").into(),
                 position: None,
+                comments: None,
             },
             "attrName='omg'"
         );
@@ -259,12 +279,14 @@ mod tests_attribute {
         assert_serialize!(
             PairAttribute {
                 name: NamespacedName {
-                    namespace: Identifier::from("attrName").into(),
-                    name: Identifier::from("prop").into(),
+                    namespace: Identifier::from("attrName"),
+                    name: Identifier::from("prop"),
                     position: None,
+                    comments: None,
                 }.into(),
                 value: StringAttribute::from("omg").into(),
                 position: None,
+                comments: None,
             },
             "attrName:prop='omg'"
         );
@@ -278,8 +300,10 @@ mod tests_attribute {
                 value: ExpressionAttribute {
                     expression: ReferenceIdentifier::from("omg").into(),
                     position: None,
+                    comments: None,
                 }.into(),
                 position: None,
+                comments: None,
             },
             "attrName={omg}"
         );
@@ -296,8 +320,10 @@ mod tests_attribute {
                     children: Default::default(),
                     closing: Default::default(),
                     position: None,
+                    comments: None,
                 }.into(),
                 position: None,
+                comments: None,
             },
             "attrName=<div/>"
         );
@@ -310,6 +336,7 @@ mod tests_attribute {
                 name: Identifier::from("attrName").into(),
                 value: None,
                 position: None,
+                comments: None,
             },
             "attrName"
         );
@@ -318,19 +345,19 @@ mod tests_attribute {
 
 
 node_enum!(@node_display pub enum AttributeName {
-    Identifier(Identifier),
-    Namespaced(NamespacedName),
+    Identifier(Identifier<'src>),
+    Namespaced(NamespacedName<'src>),
 });
 
 
 node!(pub struct SpreadAttribute {
-    pub expression: alias::Expression,
+    pub expression: alias::Expression<'src>,
 });
-impl NodeDisplay for SpreadAttribute {
+impl<'src> NodeDisplay for SpreadAttribute<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_curly();
 
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &());
         f.require_precedence(Precedence::Assignment).node(
             &self.expression,
         )?;
@@ -340,14 +367,14 @@ impl NodeDisplay for SpreadAttribute {
 
 
 node!(pub struct PairAttribute {
-    pub name: AttributeName,
-    pub value: Option<AttributeValue>,
+    pub name: AttributeName<'src>,
+    pub value: Option<AttributeValue<'src>>,
 });
-impl NodeDisplay for PairAttribute {
+impl<'src> NodeDisplay for PairAttribute<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.name)?;
         if let Some(ref value) = self.value {
-            f.punctuator(Punctuator::Eq);
+            f.punctuator(Punctuator::Eq, &());
             f.node(value)?;
         }
         Ok(())
@@ -355,18 +382,18 @@ impl NodeDisplay for PairAttribute {
 }
 
 
-node_enum!(@node_display pub enum AttributeValue {
-    String(StringAttribute),
-    Expression(ExpressionAttribute),
-    Element(Element),
+node_enum!(@boxed @node_display pub enum AttributeValue {
+    String(StringAttribute<'src>),
+    Expression(ExpressionAttribute<'src>),
+    Element(Element<'src>),
 });
 
 
 node!(pub struct ExpressionAttribute {
     // String literal that allows _all_ chars, except closing quote
-    pub expression: alias::Expression,
+    pub expression: alias::Expression<'src>,
 });
-impl NodeDisplay for ExpressionAttribute {
+impl<'src> NodeDisplay for ExpressionAttribute<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.wrap_curly().node(&self.expression)
     }
@@ -375,31 +402,32 @@ impl NodeDisplay for ExpressionAttribute {
 
 node!(#[derive(Default)] pub struct StringAttribute {
     // String literal that allows _all_ chars, except closing quote
-    pub raw: Option<string::String>,
-    pub value: string::String,
+    pub raw: Option<Str<'src>>,
+    pub value: Str<'src>,
 });
-impl NodeDisplay for StringAttribute {
+impl<'src> NodeDisplay for StringAttribute<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.jsx_string(&self.value, self.raw.as_ref().map(String::as_str))
+        f.jsx_string(&self.value, self.raw.as_deref())
     }
 }
-impl<T: Into<string::String>> From<T> for StringAttribute {
-    fn from(v: T) -> StringAttribute {
+impl<'src, T: Into<Str<'src>>> From<T> for StringAttribute<'src> {
+    fn from(v: T) -> StringAttribute<'src> {
         StringAttribute {
             raw: None,
             value: v.into(),
             position: None,
+            comments: None,
         }
     }
 }
 
 
-node_enum!(@node_display pub enum Child {
-    Empty(Empty),
-    Text(Text),
-    Element(Element),
-    Expression(Expression),
-    Spread(ExpressionSpread),
+node_enum!(@boxed @node_display pub enum Child {
+    Empty(Empty<'src>),
+    Text(Text<'src>),
+    Element(Element<'src>),
+    Expression(Expression<'src>),
+    Spread(ExpressionSpread<'src>),
 });
 #[cfg(test)]
 mod tests_element_children {
@@ -415,6 +443,7 @@ mod tests_element_children {
                 children: vec![Empty::default().into()],
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<div>{}</div>"
         );
@@ -429,6 +458,7 @@ mod tests_element_children {
                 children: vec![Text::from("content").into()],
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<div>content</div>"
         );
@@ -448,11 +478,13 @@ mod tests_element_children {
                         children: Default::default(),
                         closing: Default::default(),
                         position: None,
+                        comments: None,
                     }.into(),
                     Text::from("after").into(),
                 ],
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<div>before<div/>after</div>"
         );
@@ -468,10 +500,12 @@ mod tests_element_children {
                     Expression {
                         expression: ReferenceIdentifier::from("someVar").into(),
                         position: None,
+                        comments: None,
                     }.into(),
                 ],
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<div>{someVar}</div>"
         );
@@ -487,10 +521,12 @@ mod tests_element_children {
                     ExpressionSpread {
                         expression: ReferenceIdentifier::from("someVar").into(),
                         position: None,
+                        comments: None,
                     }.into(),
                 ],
                 closing: Default::default(),
                 position: None,
+                comments: None,
             },
             "<div>{...someVar}</div>"
         );
@@ -498,9 +534,9 @@ mod tests_element_children {
 }
 
 node!(pub struct Expression {
-    pub expression: alias::Expression,
+    pub expression: alias::Expression<'src>,
 });
-impl NodeDisplay for Expression {
+impl<'src> NodeDisplay for Expression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_curly();
 
@@ -513,12 +549,12 @@ impl NodeDisplay for Expression {
 
 // experimental?
 node!(pub struct ExpressionSpread {
-    pub expression: alias::Expression,
+    pub expression: alias::Expression<'src>,
 });
-impl NodeDisplay for ExpressionSpread {
+impl<'src> NodeDisplay for ExpressionSpread<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_curly();
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &());
         f.require_precedence(Precedence::Assignment).node(
             &self.expression,
         )?;
@@ -528,7 +564,7 @@ impl NodeDisplay for ExpressionSpread {
 }
 
 node!(#[derive(Default)] pub struct Empty {});
-impl NodeDisplay for Empty {
+impl<'src> NodeDisplay for Empty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.wrap_curly();
         Ok(())
@@ -538,20 +574,21 @@ impl NodeDisplay for Empty {
 node!(#[derive(Default)] pub struct Text {
     // Serialized string should contain HTML entities since it,
     // allows all chars except {, }, <, and >
-    pub value: string::String,
-    pub raw: Option<string::String>,
+    pub value: Str<'src>,
+    pub raw: Option<Str<'src>>,
 });
-impl NodeDisplay for Text {
+impl<'src> NodeDisplay for Text<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.jsx_text(&self.value, None)
     }
 }
-impl<T: Into<string::String>> From<T> for Text {
-    fn from(v: T) -> Text {
+impl<'src, T: Into<Str<'src>>> From<T> for Text<'src> {
+    fn from(v: T) -> Text<'src> {
         Text {
             raw: None,
             value: v.into(),
             position: None,
+            comments: None,
         }
     }
 }
@@ -1,15 +1,12 @@
-use std::string;
-
-use ast::{MaybeTokenPosition, KeywordData};
-
+use ast::Str;
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword};
 
 
 // null
 node!(#[derive(Default)] pub struct Null {});
-impl NodeDisplay for Null {
+impl<'src> NodeDisplay for Null<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Null);
+        f.keyword(Keyword::Null, &());
         Ok(())
     }
 }
@@ -28,21 +25,22 @@ mod tests_null {
 node!(pub struct Boolean {
     pub value: bool,
 });
-impl NodeDisplay for Boolean {
+impl<'src> NodeDisplay for Boolean<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         if self.value {
-            f.keyword(Keyword::True);
+            f.keyword(Keyword::True, &());
         } else {
-            f.keyword(Keyword::False);
+            f.keyword(Keyword::False, &());
         }
         Ok(())
     }
 }
-impl From<bool> for Boolean {
-    fn from(value: bool) -> Boolean {
+impl<'src> From<bool> for Boolean<'src> {
+    fn from(value: bool) -> Boolean<'src> {
         Boolean {
             value,
             position: None,
+            comments: None,
         }
     }
 }
@@ -60,20 +58,21 @@ mod tests_boolean {
 
 // 12
 node!(pub struct Numeric {
-    pub raw: Option<string::String>,
+    pub raw: Option<Str<'src>>,
     pub value: f64,
 });
-impl NodeDisplay for Numeric {
+impl<'src> NodeDisplay for Numeric<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.number(&self.value, self.raw.as_ref().map(string::String::as_str))
+        f.number(&self.value, self.raw.as_deref())
     }
 }
-impl From<f64> for Numeric {
-    fn from(value: f64) -> Numeric {
+impl<'src> From<f64> for Numeric<'src> {
+    fn from(value: f64) -> Numeric<'src> {
         Numeric {
             value,
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
@@ -94,20 +93,21 @@ mod tests_numeric {
 
 // "foo"
 node!(pub struct String {
-    pub raw: Option<string::String>,
-    pub value: string::String,
+    pub raw: Option<Str<'src>>,
+    pub value: Str<'src>,
 });
-impl NodeDisplay for String {
+impl<'src> NodeDisplay for String<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.string(&self.value, self.raw.as_ref().map(string::String::as_str))
+        f.string(&self.value, self.raw.as_deref())
     }
 }
-impl<T: Into<string::String>> From<T> for String {
-    fn from(value: T) -> String {
+impl<'src, T: Into<Str<'src>>> From<T> for String<'src> {
+    fn from(value: T) -> String<'src> {
         String {
             value: value.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
@@ -124,10 +124,10 @@ mod tests_string {
 
 // /foo/g
 node!(pub struct RegExp {
-    pub value: string::String,
+    pub value: Str<'src>,
     pub flags: Vec<char>,
 });
-impl NodeDisplay for RegExp {
+impl<'src> NodeDisplay for RegExp<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.regexp(&self.value, &self.flags)
     }
@@ -144,6 +144,7 @@ mod tests_regexp {
                 value: "hello".into(),
                 flags: vec!['g', 'u'],
                 position: None,
+                comments: None,
             },
             "/hello/gu"
         );
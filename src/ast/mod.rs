@@ -1,50 +1,133 @@
 // Enforce the structure of enums that store flags.
 macro_rules! node_kind {
-    (pub enum $name:ident { $($key:ident ,)* }) => {
+    ($(#[$attr:meta])* pub enum $name:ident { $($(#[$key_attr:meta])* $key:ident ,)* }) => {
         #[derive(Debug)]
+        $(#[$attr])*
         pub enum $name {
-            $($key ,)*
+            $($(#[$key_attr])* $key ,)*
+        }
+
+        impl<'src> $crate::ast::NodeChildren<'src> for $name {
+            fn collect_mut<'a>(&'a mut self, _out: &mut Vec<&'a mut dyn $crate::ast::Node<'src>>) {}
+        }
+        impl $crate::ast::display::SourcePosition for $name {
+            fn source_position(&self) -> Option<&$crate::ast::NodePosition> {
+                None
+            }
+        }
+        impl<'src> $crate::ast::visit::Visitable<'src> for $name {
+            fn accept<V: $crate::ast::visit::Visit<'src> + ?Sized>(&self, _visitor: &mut V) {}
+            fn accept_mut<V: $crate::ast::visit::VisitMut<'src> + ?Sized>(&mut self, _visitor: &mut V) {}
+        }
+        impl<'src> $crate::ast::fold::Foldable<'src> for $name {
+            fn fold<F: $crate::ast::fold::Fold<'src> + ?Sized>(self, _folder: &mut F) -> Self {
+                self
+            }
+        }
+        impl $crate::ast::IntoOwned for $name {
+            type Owned = $name;
+
+            fn into_owned(self) -> $name {
+                self
+            }
         }
     };
 }
 
 // Enforce the structure of enums that wrap multiple node types into a single item.
+//
+// With "@boxed", every variant is stored in a NodeBox, for enums like expressions
+// and statements that are passed by value through the parser's recursion,
+// where the largest variant would otherwise set the size of every frame.
+//
+// With "@list", the enum only appears in lists, and its `Fold` method may
+// replace an item with any number of items.
 macro_rules! node_enum {
-    ( ( $(@$label:tt)* ) pub enum $id:ident $body:tt ) => {
+    ( ( @boxed $(@$label:tt)* ) pub enum $id:ident $body:tt ) => {
+        node_enum!(@boxed_enum $id $body);
+
+        node_enum!(@impl @from_boxed $id $body);
+        node_enum!(@impl @comments $id $body);
+        $(
+            node_enum!(@impl @$label $id $body);
+        )*
+    };
+    (@boxed_enum $id:ident { $( $key:ident($type:ty) ,)* }) => {
         #[derive(Debug)]
-        pub enum $id $body
+        pub enum $id<'src> {
+            $( $key($crate::ast::NodeBox<$type>) ,)*
+        }
+    };
+    ( ( $(@$label:tt)* ) pub enum $id:ident $body:tt ) => {
+        node_enum!(@enum $id $body);
 
         node_enum!(@impl @from $id $body);
+        node_enum!(@impl @comments $id $body);
         $(
             node_enum!(@impl @$label $id $body);
         )*
     };
+    (@enum $id:ident { $( $key:ident($type:ty) ,)* }) => {
+        #[derive(Debug)]
+        pub enum $id<'src> {
+            $( $key($type) ,)*
+        }
+    };
     (@impl @from $name:ident { $( $key:ident($type:ty) ,)* }) => {
         $(
-            impl From<$type> for $name {
-                fn from(val: $type) -> $name {
+            impl<'src> From<$type> for $name<'src> {
+                fn from(val: $type) -> $name<'src> {
                     $name::$key(val)
                 }
             }
-            impl From<$type> for Box<$name> {
-                fn from(val: $type) -> Box<$name> {
-                    Box::new($name::$key(val))
+            impl<'src> From<$type> for $crate::ast::NodeBox<$name<'src>> {
+                fn from(val: $type) -> $crate::ast::NodeBox<$name<'src>> {
+                    $crate::ast::NodeBox::new($name::$key(val))
                 }
             }
-            impl From<$type> for Option<$name> {
-                fn from(val: $type) -> Option<$name> {
+            impl<'src> From<$type> for Option<$name<'src>> {
+                fn from(val: $type) -> Option<$name<'src>> {
                     Some($name::$key(val))
                 }
             }
-            impl From<$type> for Option<Box<$name>> {
-                fn from(val: $type) -> Option<Box<$name>> {
-                    Some(Box::new($name::$key(val)))
+            impl<'src> From<$type> for Option<$crate::ast::NodeBox<$name<'src>>> {
+                fn from(val: $type) -> Option<$crate::ast::NodeBox<$name<'src>>> {
+                    Some($crate::ast::NodeBox::new($name::$key(val)))
+                }
+            }
+        )*
+    };
+    (@impl @from_boxed $name:ident { $( $key:ident($type:ty) ,)* }) => {
+        $(
+            impl<'src> From<$type> for $name<'src> {
+                fn from(val: $type) -> $name<'src> {
+                    $name::$key($crate::ast::NodeBox::new(val))
+                }
+            }
+            impl<'src> From<$crate::ast::NodeBox<$type>> for $name<'src> {
+                fn from(val: $crate::ast::NodeBox<$type>) -> $name<'src> {
+                    $name::$key(val)
+                }
+            }
+            impl<'src> From<$type> for $crate::ast::NodeBox<$name<'src>> {
+                fn from(val: $type) -> $crate::ast::NodeBox<$name<'src>> {
+                    $crate::ast::NodeBox::new($name::$key($crate::ast::NodeBox::new(val)))
+                }
+            }
+            impl<'src> From<$type> for Option<$name<'src>> {
+                fn from(val: $type) -> Option<$name<'src>> {
+                    Some($name::$key($crate::ast::NodeBox::new(val)))
+                }
+            }
+            impl<'src> From<$type> for Option<$crate::ast::NodeBox<$name<'src>>> {
+                fn from(val: $type) -> Option<$crate::ast::NodeBox<$name<'src>>> {
+                    Some($crate::ast::NodeBox::new($name::$key($crate::ast::NodeBox::new(val))))
                 }
             }
         )*
     };
     (@impl @node_display $name:ident { $( $key:ident($type:ty) ,)* }) => {
-        impl $crate::ast::display::NodeDisplay for $name {
+        impl<'src> $crate::ast::display::NodeDisplay for $name<'src> {
             fn fmt(&self, f: &mut $crate::ast::display::NodeFormatter)
                 -> $crate::ast::display::NodeDisplayResult
             {
@@ -56,12 +139,96 @@ macro_rules! node_enum {
             }
         }
     };
-    (@impl @leading_comments $name:ident { $( $key:ident($type:ty) ,)* }) => {
-        impl $crate::ast::LeadingComments for $name {
-            fn leading_comments(&self) -> $crate::ast::CommentIterator {
+    (@impl @list $name:ident $body:tt) => {
+        // Read by build.rs when it lists the enums for `Fold`.
+    };
+    (@impl @comments $name:ident { $( $key:ident($type:ty) ,)* }) => {
+        impl<'src> $name<'src> {
+            /// The position of the node wrapped by this item.
+            pub fn position(&self) -> Option<&$crate::ast::NodePosition> {
+                #[allow(unused_imports)]
+                use $crate::ast::Node;
+
+                match *self {
+                    $(
+                        $name::$key(ref n) => n.position(),
+                    )*
+                }
+            }
+        }
+        impl<'src> $crate::ast::display::SourcePosition for $name<'src> {
+            fn source_position(&self) -> Option<&$crate::ast::NodePosition> {
+                self.position()
+            }
+        }
+        impl<'src> $crate::ast::LeadingComments for $name<'src> {
+            fn leading_comments(&self) -> $crate::ast::CommentIterator<'_> {
+                match *self {
+                    $(
+                        $name::$key(ref n) => $crate::ast::LeadingComments::leading_comments(n),
+                    )*
+                }
+            }
+        }
+        impl<'src> $crate::ast::TrailingComments for $name<'src> {
+            fn trailing_comments(&self) -> $crate::ast::CommentIterator<'_> {
+                match *self {
+                    $(
+                        $name::$key(ref n) => $crate::ast::TrailingComments::trailing_comments(n),
+                    )*
+                }
+            }
+        }
+        impl<'src> $crate::ast::InnerComments for $name<'src> {
+            fn inner_comments(&self) -> $crate::ast::CommentIterator<'_> {
                 match *self {
                     $(
-                        $name::$key(ref n) => n.leading_comments(),
+                        $name::$key(ref n) => $crate::ast::InnerComments::inner_comments(n),
+                    )*
+                }
+            }
+        }
+        impl<'src> $crate::ast::NodeChildren<'src> for $name<'src> {
+            fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn $crate::ast::Node<'src>>) {
+                match *self {
+                    $(
+                        $name::$key(ref mut n) => n.collect_mut(out),
+                    )*
+                }
+            }
+        }
+        impl<'src> $crate::ast::visit::Visitable<'src> for $name<'src> {
+            fn accept<V: $crate::ast::visit::Visit<'src> + ?Sized>(&self, visitor: &mut V) {
+                match *self {
+                    $(
+                        $name::$key(ref n) => $crate::ast::visit::Visitable::accept(n, visitor),
+                    )*
+                }
+            }
+            fn accept_mut<V: $crate::ast::visit::VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V) {
+                match *self {
+                    $(
+                        $name::$key(ref mut n) => $crate::ast::visit::Visitable::accept_mut(n, visitor),
+                    )*
+                }
+            }
+        }
+        impl<'src> $crate::ast::fold::FoldChildren<'src> for $name<'src> {
+            fn fold_children<F: $crate::ast::fold::Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+                match self {
+                    $(
+                        $name::$key(n) => $name::$key($crate::ast::fold::Foldable::fold(n, folder)),
+                    )*
+                }
+            }
+        }
+        impl<'src> $crate::ast::IntoOwned for $name<'src> {
+            type Owned = $name<'static>;
+
+            fn into_owned(self) -> $name<'static> {
+                match self {
+                    $(
+                        $name::$key(n) => $name::$key($crate::ast::IntoOwned::into_owned(n)),
                     )*
                 }
             }
@@ -88,9 +255,16 @@ macro_rules! node_enum {
 // Enforce structure for AST node structs.
 macro_rules! node {
     (@node_display $id:ident) => {
-        impl ::std::fmt::Display for $id {
+        impl<'src> ::std::fmt::Display for $id<'src> {
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-                let mut node_fmt = $crate::ast::display::NodeFormatter::new();
+                // The alternate flag, as in "{:#}", selects pretty output.
+                let mut node_fmt = if f.alternate() {
+                    $crate::ast::display::NodeFormatter::with_options(
+                        $crate::ast::display::FormatOptions::pretty(),
+                    )
+                } else {
+                    $crate::ast::display::NodeFormatter::new()
+                };
 
                 $crate::ast::display::NodeDisplay::fmt(self, &mut node_fmt).unwrap();
 
@@ -101,22 +275,121 @@ macro_rules! node {
     (@ensure_debug [ derive( $($t:ident),* )] $item:item) => {
         #[derive(Debug, $($t),*  )] $item
     };
+    (@node $id:ident { $($field_id:ident)* }) => {
+        impl<'src> $crate::ast::Node<'src> for $id<'src> {
+            fn position(&self) -> Option<&$crate::ast::NodePosition> {
+                self.position.as_ref().map(|p| &**p)
+            }
+            fn set_position(&mut self, position: Option<$crate::ast::NodePosition>) {
+                self.position = position.map($crate::ast::NodeBox::new);
+            }
+            fn comments(&self) -> Option<&$crate::ast::NodeComments<'src>> {
+                self.comments.as_ref().map(|c| &**c)
+            }
+            fn comments_mut(&mut self) -> &mut $crate::ast::NodeComments<'src> {
+                self.comments.get_or_insert_with(Default::default)
+            }
+            fn set_comments(&mut self, comments: Option<$crate::ast::NodeComments<'src>>) {
+                self.comments = comments.map($crate::ast::NodeBox::new);
+            }
+            fn children_mut<'a>(&'a mut self, _out: &mut Vec<&'a mut dyn $crate::ast::Node<'src>>) {
+                $(
+                    $crate::ast::NodeChildren::collect_mut(&mut self.$field_id, _out);
+                )*
+            }
+            fn tokens_mut<'a>(&'a mut self, _out: &mut Vec<&'a mut $crate::ast::PositionRange>) {
+                $(
+                    $crate::ast::NodeChildren::collect_tokens_mut(&mut self.$field_id, _out);
+                )*
+            }
+        }
+        impl<'src> $crate::ast::NodeChildren<'src> for $id<'src> {
+            fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn $crate::ast::Node<'src>>) {
+                out.push(self);
+            }
+        }
+        impl<'src> $crate::ast::display::SourcePosition for $id<'src> {
+            fn source_position(&self) -> Option<&$crate::ast::NodePosition> {
+                self.position.as_ref().map(|p| &**p)
+            }
+        }
+        impl<'src> $crate::ast::visit::Walk<'src> for $id<'src> {
+            fn walk<V: $crate::ast::visit::Visit<'src> + ?Sized>(&self, _visitor: &mut V) {
+                $(
+                    $crate::ast::visit::Visitable::accept(&self.$field_id, _visitor);
+                )*
+            }
+            fn walk_mut<V: $crate::ast::visit::VisitMut<'src> + ?Sized>(&mut self, _visitor: &mut V) {
+                $(
+                    $crate::ast::visit::Visitable::accept_mut(&mut self.$field_id, _visitor);
+                )*
+            }
+        }
+        impl<'src> $crate::ast::fold::FoldChildren<'src> for $id<'src> {
+            fn fold_children<F: $crate::ast::fold::Fold<'src> + ?Sized>(self, _folder: &mut F) -> Self {
+                $id {
+                    $(
+                        $field_id: $crate::ast::fold::Foldable::fold(self.$field_id, _folder),
+                    )*
+                    position: self.position,
+                    comments: self.comments,
+                }
+            }
+        }
+        impl<'src> $crate::ast::fold::Foldable<'src> for $id<'src> {
+            fn fold<F: $crate::ast::fold::Fold<'src> + ?Sized>(self, folder: &mut F) -> Self {
+                $crate::ast::fold::FoldChildren::fold_children(self, folder)
+            }
+        }
+        impl<'src> $crate::ast::IntoOwned for $id<'src> {
+            type Owned = $id<'static>;
+
+            fn into_owned(self) -> $id<'static> {
+                $id {
+                    $(
+                        $field_id: $crate::ast::IntoOwned::into_owned(self.$field_id),
+                    )*
+                    position: self.position,
+                    comments: $crate::ast::IntoOwned::into_owned(self.comments),
+                }
+            }
+        }
+        impl<'src> $crate::ast::LeadingComments for $id<'src> {
+            fn leading_comments(&self) -> $crate::ast::CommentIterator<'_> {
+                $crate::ast::CommentIterator::new(self.comments.as_ref().map(|c| &c.leading[..]))
+            }
+        }
+        impl<'src> $crate::ast::TrailingComments for $id<'src> {
+            fn trailing_comments(&self) -> $crate::ast::CommentIterator<'_> {
+                $crate::ast::CommentIterator::new(self.comments.as_ref().map(|c| &c.trailing[..]))
+            }
+        }
+        impl<'src> $crate::ast::InnerComments for $id<'src> {
+            fn inner_comments(&self) -> $crate::ast::CommentIterator<'_> {
+                $crate::ast::CommentIterator::new(self.comments.as_ref().map(|c| &c.inner[..]))
+            }
+        }
+    };
     (pub struct $id:ident { $(pub $field_id:ident: $field_type:ty ,)* }) => {
         #[derive(Debug)]
-        pub struct $id {
+        pub struct $id<'src> {
             // TODO: This 'pub' should be in the declarations themselves.
             $(pub $field_id: $field_type,)*
-            pub position: Option<Box<$crate::ast::NodePosition>>,
+            pub position: Option<$crate::ast::NodeBox<$crate::ast::NodePosition>>,
+            pub comments: Option<$crate::ast::NodeBox<$crate::ast::NodeComments<'src>>>,
         }
         node!(@node_display $id);
+        node!(@node $id { $($field_id)* });
     };
     (#$meta:tt pub struct $id:ident { $(pub $field_id:ident: $field_type:ty ,)* }) => {
-        node!(@ensure_debug $meta pub struct $id {
+        node!(@ensure_debug $meta pub struct $id<'src> {
             // TODO: This 'pub' should be in the declarations themselves.
             $(pub $field_id: $field_type,)*
-            pub position: Option<Box<$crate::ast::NodePosition>>,
+            pub position: Option<$crate::ast::NodeBox<$crate::ast::NodePosition>>,
+            pub comments: Option<$crate::ast::NodeBox<$crate::ast::NodeComments<'src>>>,
         });
         node!(@node_display $id);
+        node!(@node $id { $($field_id)* });
     };
 }
 
@@ -128,11 +401,16 @@ macro_rules! assert_serialize {
 }
 
 
+use std::fmt;
+
 pub mod alias;
 pub mod classes;
 pub mod decorators;
 mod display;
+pub use self::display::{format, format_with_source_map, FormatOptions, Indent, NodeDisplay, NodeFormatter, PrintedNode, QuoteStyle, Semicolons, SourcePosition, TokenData, TrailingCommas};
 pub mod expression;
+pub mod flow;
+pub mod fold;
 pub mod functions;
 pub mod general;
 pub mod jsx;
@@ -141,47 +419,322 @@ pub mod modules;
 pub mod objects;
 pub mod patterns;
 pub mod root;
+pub mod source_map;
+mod strings;
+pub use self::strings::Str;
+pub use arena::NodeBox;
 pub mod statement;
+pub mod typescript;
+pub mod visit;
 
 use std::iter::Iterator;
+use std::slice;
+use std::string;
 
-#[derive(Debug)]
+use stack;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NodePosition {
     pub start: usize,
     pub end: usize,
     pub range: PositionRange,
+
+    // Whether the node is inside of Flow's comment syntax, like the
+    // parameter of "function f(/*:: a: number */) {}".
+    pub in_flow_comment: bool,
 }
 
-#[derive(Debug)]
+/// Line/column pairs for the start and end of a node or token. Lines are
+/// 1-indexed and columns are 0-indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PositionRange {
     pub start: (usize, usize),
     pub end: (usize, usize),
 }
 
-pub type MaybeTokenPosition = Option<Box<PositionRange>>;
-pub type SeparatorTokens = Vec<Separators>;
+pub type MaybeTokenPosition = Option<NodeBox<PositionRange>>;
+pub type SeparatorTokens<'src> = Vec<Separators<'src>>;
 
-pub type SeparatorTokensSingleLine = Vec<Separators>;
+pub type SeparatorTokensSingleLine<'src> = Vec<Separators<'src>>;
 
 #[derive(Default, Debug)]
 pub struct KeywordData {
-    prefix: SeparatorTokens,
     position: MaybeTokenPosition,
 }
 #[derive(Default, Debug)]
 pub struct KeywordSuffixData {
     position: MaybeTokenPosition,
-    suffix: SeparatorTokens,
 }
 #[derive(Default, Debug)]
 pub struct KeywordWrappedData {
     position: MaybeTokenPosition,
-    suffix: SeparatorTokens,
 }
 
+macro_rules! token_data {
+    ($($t:ident),*) => {
+        $(
+            impl From<PositionRange> for $t {
+                fn from(position: PositionRange) -> $t {
+                    $t {
+                        position: Some(NodeBox::new(position)),
+                    }
+                }
+            }
+        )*
+    };
+}
+token_data!(KeywordData, KeywordSuffixData, KeywordWrappedData);
+
+impl From<PositionRange> for MaybeTokenPosition {
+    fn from(position: PositionRange) -> MaybeTokenPosition {
+        Some(NodeBox::new(position))
+    }
+}
+
+
+/// Implemented by every struct created with `node!`, allowing code to treat
+/// nodes generically, e.g. to attach comments after parsing.
+pub trait Node<'src>: fmt::Debug {
+    fn position(&self) -> Option<&NodePosition>;
+    fn set_position(&mut self, position: Option<NodePosition>);
+
+    fn comments(&self) -> Option<&NodeComments<'src>>;
+    fn comments_mut(&mut self) -> &mut NodeComments<'src>;
+    fn set_comments(&mut self, comments: Option<NodeComments<'src>>);
+
+    /// Collect the direct child nodes of this node, in source order.
+    fn children_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn Node<'src>>);
+
+    /// Collect the positions of the keywords and punctuators stored on this
+    /// node, not including those of its children.
+    fn tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>);
+}
+
+/// Collects the nodes stored in a node field, looking through enums and
+/// containers.
+pub trait NodeChildren<'src> {
+    fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn Node<'src>>);
+
+    /// Collect the token positions stored in the field itself, rather than
+    /// in the nodes it contains.
+    fn collect_tokens_mut<'a>(&'a mut self, _out: &mut Vec<&'a mut PositionRange>) {}
+}
+impl<'src, T: NodeChildren<'src>> NodeChildren<'src> for NodeBox<T> {
+    fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn Node<'src>>) {
+        (**self).collect_mut(out)
+    }
+    fn collect_tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>) {
+        (**self).collect_tokens_mut(out)
+    }
+}
+impl<'src, T: NodeChildren<'src>> NodeChildren<'src> for Option<T> {
+    fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn Node<'src>>) {
+        if let Some(ref mut n) = *self {
+            n.collect_mut(out);
+        }
+    }
+    fn collect_tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>) {
+        if let Some(ref mut n) = *self {
+            n.collect_tokens_mut(out);
+        }
+    }
+}
+impl<'src, T: NodeChildren<'src>> NodeChildren<'src> for Vec<T> {
+    fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn Node<'src>>) {
+        for n in self.iter_mut() {
+            n.collect_mut(out);
+        }
+    }
+    fn collect_tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>) {
+        for n in self.iter_mut() {
+            n.collect_tokens_mut(out);
+        }
+    }
+}
+impl<'src, A: NodeChildren<'src>, B: NodeChildren<'src>> NodeChildren<'src> for (A, B) {
+    fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn Node<'src>>) {
+        self.0.collect_mut(out);
+        self.1.collect_mut(out);
+    }
+    fn collect_tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>) {
+        self.0.collect_tokens_mut(out);
+        self.1.collect_tokens_mut(out);
+    }
+}
+impl<'src, A, B, C, D> NodeChildren<'src> for (A, B, C, D)
+where
+    A: NodeChildren<'src>,
+    B: NodeChildren<'src>,
+    C: NodeChildren<'src>,
+    D: NodeChildren<'src>,
+{
+    fn collect_mut<'a>(&'a mut self, out: &mut Vec<&'a mut dyn Node<'src>>) {
+        self.0.collect_mut(out);
+        self.1.collect_mut(out);
+        self.2.collect_mut(out);
+        self.3.collect_mut(out);
+    }
+    fn collect_tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>) {
+        self.0.collect_tokens_mut(out);
+        self.1.collect_tokens_mut(out);
+        self.2.collect_tokens_mut(out);
+        self.3.collect_tokens_mut(out);
+    }
+}
+macro_rules! leaf_children {
+    ($($t:ty),*) => {
+        $(
+            impl<'src> NodeChildren<'src> for $t {
+                fn collect_mut<'a>(&'a mut self, _out: &mut Vec<&'a mut dyn Node<'src>>) {}
+            }
+        )*
+    };
+}
+leaf_children!(bool, f64, char, string::String, Str<'src>, Separators<'src>);
+macro_rules! token_children {
+    ($($t:ty),*) => {
+        $(
+            impl<'src> NodeChildren<'src> for $t {
+                fn collect_mut<'a>(&'a mut self, _out: &mut Vec<&'a mut dyn Node<'src>>) {}
+                fn collect_tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>) {
+                    self.position.collect_tokens_mut(out);
+                }
+            }
+        )*
+    };
+}
+token_children!(KeywordData, KeywordSuffixData, KeywordWrappedData);
+impl<'src> NodeChildren<'src> for PositionRange {
+    fn collect_mut<'a>(&'a mut self, _out: &mut Vec<&'a mut dyn Node<'src>>) {}
+    fn collect_tokens_mut<'a>(&'a mut self, out: &mut Vec<&'a mut PositionRange>) {
+        out.push(self);
+    }
+}
+
+/// Converts a tree, or anything that can appear in a node field, into one
+/// that owns all of its strings rather than borrowing them from the source.
+/// Implemented for every node by the `node!` and `node_enum!` macros.
+pub trait IntoOwned {
+    type Owned: 'static;
+
+    fn into_owned(self) -> Self::Owned;
+}
+impl<T: IntoOwned> IntoOwned for NodeBox<T> {
+    type Owned = NodeBox<T::Owned>;
+
+    fn into_owned(self) -> NodeBox<T::Owned> {
+        NodeBox::new(stack::grow(|| NodeBox::into_inner(self).into_owned()))
+    }
+}
+impl<T: IntoOwned> IntoOwned for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Option<T::Owned> {
+        self.map(IntoOwned::into_owned)
+    }
+}
+impl<T: IntoOwned> IntoOwned for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Vec<T::Owned> {
+        self.into_iter().map(IntoOwned::into_owned).collect()
+    }
+}
+impl<A: IntoOwned, B: IntoOwned> IntoOwned for (A, B) {
+    type Owned = (A::Owned, B::Owned);
+
+    fn into_owned(self) -> (A::Owned, B::Owned) {
+        (self.0.into_owned(), self.1.into_owned())
+    }
+}
+impl<A: IntoOwned, B: IntoOwned, C: IntoOwned, D: IntoOwned> IntoOwned for (A, B, C, D) {
+    type Owned = (A::Owned, B::Owned, C::Owned, D::Owned);
+
+    fn into_owned(self) -> (A::Owned, B::Owned, C::Owned, D::Owned) {
+        (self.0.into_owned(), self.1.into_owned(), self.2.into_owned(), self.3.into_owned())
+    }
+}
+impl<'src> IntoOwned for Str<'src> {
+    type Owned = Str<'static>;
+
+    fn into_owned(self) -> Str<'static> {
+        string::String::from(self).into()
+    }
+}
+impl<'src> IntoOwned for NodeComments<'src> {
+    type Owned = NodeComments<'static>;
+
+    fn into_owned(self) -> NodeComments<'static> {
+        NodeComments {
+            leading: self.leading.into_owned(),
+            trailing: self.trailing.into_owned(),
+            inner: self.inner.into_owned(),
+        }
+    }
+}
+impl<'src> IntoOwned for Separators<'src> {
+    type Owned = Separators<'static>;
+
+    fn into_owned(self) -> Separators<'static> {
+        match self {
+            Separators::Comment(comment) => Separators::Comment(comment.into_owned()),
+            Separators::Whitespace => Separators::Whitespace,
+        }
+    }
+}
+impl<'src> IntoOwned for CommentNode<'src> {
+    type Owned = CommentNode<'static>;
+
+    fn into_owned(self) -> CommentNode<'static> {
+        CommentNode {
+            format: self.format,
+            value: self.value.into_owned(),
+            position: self.position,
+        }
+    }
+}
+macro_rules! leaf_into_owned {
+    ($($t:ty),*) => {
+        $(
+            impl IntoOwned for $t {
+                type Owned = $t;
+
+                fn into_owned(self) -> $t {
+                    self
+                }
+            }
+        )*
+    };
+}
+leaf_into_owned!(
+    bool, f64, char, string::String,
+    KeywordData, KeywordSuffixData, KeywordWrappedData, NodePosition, PositionRange
+);
+
 
 pub trait LeadingComments {
-    fn leading_comments(&self) -> CommentIterator;
+    fn leading_comments(&self) -> CommentIterator<'_>;
+}
+pub trait TrailingComments {
+    fn trailing_comments(&self) -> CommentIterator<'_>;
+}
+pub trait InnerComments {
+    fn inner_comments(&self) -> CommentIterator<'_>;
+}
+impl<T: LeadingComments> LeadingComments for NodeBox<T> {
+    fn leading_comments(&self) -> CommentIterator<'_> {
+        (**self).leading_comments()
+    }
+}
+impl<T: TrailingComments> TrailingComments for NodeBox<T> {
+    fn trailing_comments(&self) -> CommentIterator<'_> {
+        (**self).trailing_comments()
+    }
+}
+impl<T: InnerComments> InnerComments for NodeBox<T> {
+    fn inner_comments(&self) -> CommentIterator<'_> {
+        (**self).inner_comments()
+    }
 }
 
 pub trait SeparatorList {
@@ -189,34 +742,60 @@ pub trait SeparatorList {
 
 }
 
+/// Comments attached to a node. Leading comments come before the node,
+/// trailing comments follow it, and inner comments are inside of a node
+/// that has no child nodes for them to attach to, like an empty block.
+#[derive(Debug, Default)]
+pub struct NodeComments<'src> {
+    pub leading: SeparatorTokens<'src>,
+    pub trailing: SeparatorTokens<'src>,
+    pub inner: SeparatorTokens<'src>,
+}
+
 #[derive(Debug)]
-pub enum Separators {
-    Comment(CommentNode),
+pub enum Separators<'src> {
+    Comment(CommentNode<'src>),
     Whitespace,
 }
 
-pub struct CommentNode;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentFormat {
+    // foo
+    Line,
+    /* foo */
+    Block,
+    // <!-- foo
+    HTMLOpen,
+    // --> foo
+    HTMLClose,
+    // #! foo
+    Hashbang,
+}
+
+#[derive(Debug)]
+pub struct CommentNode<'src> {
+    pub format: CommentFormat,
+    pub value: Str<'src>,
+    pub position: Option<NodeBox<NodePosition>>,
+}
 
 pub struct CommentIterator<'a> {
-    it: ::std::slice::Iter<'a, Separators>,
+    it: slice::Iter<'a, Separators<'a>>,
 }
 impl<'a> CommentIterator<'a> {
-    fn new(v: &'a Vec<Separators>) -> CommentIterator<'a> {
+    fn new(v: Option<&'a [Separators<'a>]>) -> CommentIterator<'a> {
         CommentIterator {
-            it: v.iter(),
+            it: v.unwrap_or(&[]).iter(),
         }
     }
 }
 impl<'a> Iterator for CommentIterator<'a> {
-    type Item = &'a CommentNode;
+    type Item = &'a CommentNode<'a>;
 
-    fn next(&mut self) -> Option<&'a CommentNode> {
-        while let Some(sep) = self.it.next() {
-            match *sep {
-                Separators::Comment(ref comment) => {
-                    return Some(comment);
-                }
-                _ => {},
+    fn next(&mut self) -> Option<&'a CommentNode<'a>> {
+        for sep in self.it.by_ref() {
+            if let Separators::Comment(ref comment) = *sep {
+                return Some(comment);
             }
         }
         None
@@ -1,6 +1,4 @@
-use std::string;
-
-use ast::{MaybeTokenPosition, KeywordData, KeywordWrappedData};
+use ast::{KeywordData, KeywordWrappedData, Str};
 
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punctuator, Precedence,
                    LookaheadRestriction};
@@ -10,6 +8,7 @@ use ast::classes::ClassDeclaration;
 use ast::functions::FunctionDeclaration;
 use ast::literal::String;
 use ast::alias;
+use ast::flow;
 
 use ast::general::{BindingIdentifier, ReferenceIdentifier};
 
@@ -17,63 +16,102 @@ use ast::general::{BindingIdentifier, ReferenceIdentifier};
 // identifiers used as names of imports and exports
 node!(pub struct ModuleIdentifier {
     // Identifier with "default"
-    pub value: string::String,
-    pub raw: Option<string::String>,
+    pub value: Str<'src>,
+    pub raw: Option<Str<'src>>,
 });
-impl NodeDisplay for ModuleIdentifier {
+impl<'src> NodeDisplay for ModuleIdentifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.identifier(&self.value, self.raw.as_ref().map(string::String::as_str))
+        f.identifier(&self.value, self.raw.as_deref())
     }
 }
-impl ModuleIdentifier {
-    pub fn new<T: Into<string::String>>(s: T) -> ModuleIdentifier {
+impl<'src> ModuleIdentifier<'src> {
+    pub fn new<T: Into<Str<'src>>>(s: T) -> ModuleIdentifier<'src> {
         ModuleIdentifier {
             value: s.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
-impl<T: Into<string::String>> From<T> for ModuleIdentifier {
-    fn from(value: T) -> ModuleIdentifier {
+impl<'src, T: Into<Str<'src>>> From<T> for ModuleIdentifier<'src> {
+    fn from(value: T) -> ModuleIdentifier<'src> {
         ModuleIdentifier {
             value: value.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
 
 node_enum!(@node_display pub enum ImportSpecifier {
-    Normal(NormalImportSpecifier),
-    Aliased(AliasedImportSpecifier),
+    Normal(NormalImportSpecifier<'src>),
+    Aliased(AliasedImportSpecifier<'src>),
+    Type(flow::TypeImportSpecifier<'src>), // experimental
 });
+impl<'src> ImportSpecifier<'src> {
+    fn is_type(&self) -> bool {
+        matches!(*self, ImportSpecifier::Type(_))
+    }
+}
+
+// Whether every one of a non-empty list of specifiers only imports types, so
+// stripping types leaves nothing to import.
+fn only_types(specifiers: &[(ImportSpecifier, KeywordData)], last: &Option<ImportSpecifier>) -> bool {
+    let mut all = specifiers.iter().map(|(spec, _)| spec).chain(last).peekable();
+    all.peek().is_some() && all.all(ImportSpecifier::is_type)
+}
+
+// Prints specifiers in curly brackets, leaving out the commas of type-only
+// specifiers when they're stripped.
+fn import_specifiers(
+    f: &mut NodeFormatter,
+    specifiers: &[(ImportSpecifier, KeywordData)],
+    last: &Option<ImportSpecifier>,
+) -> NodeDisplayResult {
+    let mut f = f.wrap_curly();
+    if !f.strip_types() {
+        f.comma_list(specifiers)?;
+        return f.node(last);
+    }
+
+    let values = specifiers.iter().map(|(spec, _)| spec).chain(last).filter(|spec| !spec.is_type());
+    for (i, spec) in values.enumerate() {
+        if i > 0 {
+            f.punctuator(Punctuator::Comma, &());
+        }
+        f.node(spec)?;
+    }
+    Ok(())
+}
 
 
 node!(pub struct NormalImportSpecifier {
-    pub local: BindingIdentifier,
+    pub local: BindingIdentifier<'src>,
 });
-impl NodeDisplay for NormalImportSpecifier {
+impl<'src> NodeDisplay for NormalImportSpecifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.local)
     }
 }
-impl From<BindingIdentifier> for NormalImportSpecifier {
+impl<'src> From<BindingIdentifier<'src>> for NormalImportSpecifier<'src> {
     fn from(b: BindingIdentifier) -> NormalImportSpecifier {
         NormalImportSpecifier {
             local: b,
             position: None,
+            comments: None,
         }
     }
 }
 
 
 node!(pub struct AliasedImportSpecifier {
-    pub imported: ModuleIdentifier,
+    pub imported: ModuleIdentifier<'src>,
     pub token_as: KeywordWrappedData,
-    pub local: BindingIdentifier,
+    pub local: BindingIdentifier<'src>,
 });
-impl NodeDisplay for AliasedImportSpecifier {
+impl<'src> NodeDisplay for AliasedImportSpecifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.imported)?;
         f.keyword(Keyword::As, &self.token_as);
@@ -81,20 +119,56 @@ impl NodeDisplay for AliasedImportSpecifier {
     }
 }
 
+// import "";
+node!(pub struct ImportSourceDeclaration {
+    pub token_import: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for ImportSourceDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::Import, &self.token_import);
+        f.node(&self.source)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_import_source {
+    use super::*;
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(
+            ImportSourceDeclaration {
+                token_import: Default::default(),
+                source: "file.js".into(),
+                token_semi: Default::default(),
+                position: None,
+                comments: None,
+            },
+            "import'file.js';"
+        );
+    }
+}
+
+
 // import foo from "";
 node!(pub struct ImportNamedDeclaration {
     pub token_import: KeywordData,
-    pub default: BindingIdentifier,
+    pub default: BindingIdentifier<'src>,
     pub token_from: KeywordData,
-    pub source: String,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ImportNamedDeclaration {
+impl<'src> NodeDisplay for ImportNamedDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Import, &self.token_import);
         f.node(&self.default)?;
         f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -107,9 +181,13 @@ mod tests_import_named {
     fn it_prints() {
         assert_serialize!(
             ImportNamedDeclaration {
+                token_import: Default::default(),
                 default: "foo".into(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "import foo from'file.js';"
         );
@@ -120,21 +198,26 @@ mod tests_import_named {
 // import foo, * as bar from "";
 node!(pub struct ImportNamedAndNamespaceDeclaration {
     pub token_import: KeywordData,
-    pub default: BindingIdentifier,
-    pub namespace: BindingIdentifier,
-    pub source: String,
+    pub default: BindingIdentifier<'src>,
+    pub token_comma: KeywordData,
+    pub token_star: KeywordData,
+    pub token_as: KeywordWrappedData,
+    pub namespace: BindingIdentifier<'src>,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ImportNamedAndNamespaceDeclaration {
+impl<'src> NodeDisplay for ImportNamedAndNamespaceDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Import);
+        f.keyword(Keyword::Import, &self.token_import);
         f.node(&self.default)?;
-        f.punctuator(Punctuator::Comma);
-        f.punctuator(Punctuator::Star);
-        f.keyword(Keyword::As);
+        f.punctuator(Punctuator::Comma, &self.token_comma);
+        f.punctuator(Punctuator::Star, &self.token_star);
+        f.keyword(Keyword::As, &self.token_as);
         f.node(&self.namespace)?;
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -147,10 +230,17 @@ mod tests_import_named_and_namespace {
     fn it_prints() {
         assert_serialize!(
             ImportNamedAndNamespaceDeclaration {
+                token_import: Default::default(),
                 default: "foo".into(),
+                token_comma: Default::default(),
+                token_star: Default::default(),
+                token_as: Default::default(),
                 namespace: "namespaceObj".into(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "import foo,*as namespaceObj from'file.js';"
         );
@@ -161,18 +251,22 @@ mod tests_import_named_and_namespace {
 // import * as bar from "";
 node!(pub struct ImportNamespaceDeclaration {
     pub token_import: KeywordData,
-    pub namespace: BindingIdentifier,
-    pub source: String,
+    pub token_star: KeywordData,
+    pub token_as: KeywordWrappedData,
+    pub namespace: BindingIdentifier<'src>,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ImportNamespaceDeclaration {
+impl<'src> NodeDisplay for ImportNamespaceDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Import);
-        f.punctuator(Punctuator::Star);
-        f.keyword(Keyword::As);
+        f.keyword(Keyword::Import, &self.token_import);
+        f.punctuator(Punctuator::Star, &self.token_star);
+        f.keyword(Keyword::As, &self.token_as);
         f.node(&self.namespace)?;
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -185,9 +279,15 @@ mod tests_import_namespace {
     fn it_prints() {
         assert_serialize!(
             ImportNamespaceDeclaration {
+                token_import: Default::default(),
+                token_star: Default::default(),
+                token_as: Default::default(),
                 namespace: "namespaceObj".into(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "import*as namespaceObj from'file.js';"
         );
@@ -199,24 +299,27 @@ mod tests_import_namespace {
 // import foo, {bar as bar} from "";
 node!(pub struct ImportNamedAndSpecifiersDeclaration {
     pub token_import: KeywordData,
-    pub default: BindingIdentifier,
-    pub specifiers: Vec<(ImportSpecifier, KeywordData)>,
-    pub last_specifier: Option<ImportSpecifier>,
-    pub source: String,
+    pub default: BindingIdentifier<'src>,
+    pub token_comma: KeywordData,
+    pub token_curly_l: KeywordData,
+    pub specifiers: Vec<(ImportSpecifier<'src>, KeywordData)>,
+    pub last_specifier: Option<ImportSpecifier<'src>>,
+    pub token_curly_r: KeywordData,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ImportNamedAndSpecifiersDeclaration {
+impl<'src> NodeDisplay for ImportNamedAndSpecifiersDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Import);
+        f.keyword(Keyword::Import, &self.token_import);
         f.node(&self.default)?;
-        f.punctuator(Punctuator::Comma);
-        {
-            let mut f = f.wrap_curly();
-            f.comma_list(&self.specifiers)?;
-            f.node(&self.last_specifier)?;
+        if !f.strip_types() || !only_types(&self.specifiers, &self.last_specifier) {
+            f.punctuator(Punctuator::Comma, &self.token_comma);
+            import_specifiers(f, &self.specifiers, &self.last_specifier)?;
         }
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -230,10 +333,18 @@ mod tests_import_named_and_specifiers {
     fn it_prints() {
         assert_serialize!(
             ImportNamedAndSpecifiersDeclaration {
+                token_import: Default::default(),
                 default: "foo".into(),
+                token_comma: Default::default(),
+                token_curly_l: Default::default(),
                 specifiers: vec![],
+                last_specifier: None,
+                token_curly_r: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "import foo,{}from'file.js';"
         );
@@ -243,20 +354,35 @@ mod tests_import_named_and_specifiers {
     fn it_prints_with_specifiers() {
         assert_serialize!(
             ImportNamedAndSpecifiersDeclaration {
+                token_import: Default::default(),
                 default: "foo".into(),
+                token_comma: Default::default(),
+                token_curly_l: Default::default(),
                 specifiers: vec![
-                    BindingIdentifier::from("spec1").into(),
-                    BindingIdentifier::from("spec2").into(),
-                    ImportSpecifier {
-                        local: BindingIdentifier::from("spec3"),
-                        imported: ModuleIdentifier::from("fooImport").into(),
-                        position: None,
-                    },
+                    (
+                        NormalImportSpecifier::from(BindingIdentifier::from("spec1")).into(),
+                        Default::default(),
+                    ),
+                    (
+                        NormalImportSpecifier::from(BindingIdentifier::from("spec2")).into(),
+                        Default::default(),
+                    ),
                 ],
+                last_specifier: Some(AliasedImportSpecifier {
+                    imported: ModuleIdentifier::from("fooImport"),
+                    token_as: Default::default(),
+                    local: BindingIdentifier::from("spec3"),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_r: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
-            "import foo,{spec1,spec2,spec3 as fooImport}from'file.js';"
+            "import foo,{spec1,spec2,fooImport as spec3}from'file.js';"
         );
     }
 }
@@ -265,21 +391,25 @@ mod tests_import_named_and_specifiers {
 // import {bar as bar} from "";
 node!(pub struct ImportSpecifiersDeclaration {
     pub token_import: KeywordData,
-    pub specifiers: Vec<(ImportSpecifier, KeywordData)>,
-    pub last_specifier: ImportSpecifier,
-    pub source: String,
+    pub token_curly_l: KeywordData,
+    pub specifiers: Vec<(ImportSpecifier<'src>, KeywordData)>,
+    pub last_specifier: Option<ImportSpecifier<'src>>,
+    pub token_curly_r: KeywordData,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ImportSpecifiersDeclaration {
+impl<'src> NodeDisplay for ImportSpecifiersDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Import);
-        {
-            let mut f = f.wrap_curly();
-            f.comma_list(&self.specifiers)?;
-            f.node(&self.last_specifier)?;
+        if f.strip_types() && only_types(&self.specifiers, &self.last_specifier) {
+            return Ok(());
         }
-        f.keyword(Keyword::From);
+
+        f.keyword(Keyword::Import, &self.token_import);
+        import_specifiers(f, &self.specifiers, &self.last_specifier)?;
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -293,9 +423,16 @@ mod tests_import_specifiers {
     fn it_prints() {
         assert_serialize!(
             ImportSpecifiersDeclaration {
+                token_import: Default::default(),
+                token_curly_l: Default::default(),
                 specifiers: vec![],
+                last_specifier: None,
+                token_curly_r: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "import{}from'file.js';"
         );
@@ -305,19 +442,33 @@ mod tests_import_specifiers {
     fn it_prints_with_specifiers() {
         assert_serialize!(
             ImportSpecifiersDeclaration {
+                token_import: Default::default(),
+                token_curly_l: Default::default(),
                 specifiers: vec![
-                    BindingIdentifier::from("spec1").into(),
-                    BindingIdentifier::from("spec2").into(),
-                    ImportSpecifier {
-                        local: BindingIdentifier::from("spec3"),
-                        imported: ModuleIdentifier::from("fooImport").into(),
-                        position: None,
-                    },
+                    (
+                        NormalImportSpecifier::from(BindingIdentifier::from("spec1")).into(),
+                        Default::default(),
+                    ),
+                    (
+                        NormalImportSpecifier::from(BindingIdentifier::from("spec2")).into(),
+                        Default::default(),
+                    ),
                 ],
+                last_specifier: Some(AliasedImportSpecifier {
+                    imported: ModuleIdentifier::from("fooImport"),
+                    token_as: Default::default(),
+                    local: BindingIdentifier::from("spec3"),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_r: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
-            "import{spec1,spec2,spec3 as fooImport}from'file.js';"
+            "import{spec1,spec2,fooImport as spec3}from'file.js';"
         );
     }
 }
@@ -328,12 +479,13 @@ mod tests_import_specifiers {
 node!(pub struct ExportDefaultExpression {
     pub token_export: KeywordData,
     pub token_default: KeywordData,
-    pub expression: alias::Expression,
+    pub expression: alias::Expression<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportDefaultExpression {
+impl<'src> NodeDisplay for ExportDefaultExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
-        f.keyword(Keyword::Default);
+        f.keyword(Keyword::Export, &self.token_export);
+        f.keyword(Keyword::Default, &self.token_default);
 
         {
             let mut f = f.restrict_lookahead(LookaheadRestriction::ExportDefault);
@@ -342,17 +494,19 @@ impl NodeDisplay for ExportDefaultExpression {
             )?;
         }
 
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
-impl<T: Into<alias::Expression>> From<T> for ExportDefaultExpression {
-    fn from(val: T) -> ExportDefaultExpression {
+impl<'src, T: Into<alias::Expression<'src>>> From<T> for ExportDefaultExpression<'src> {
+    fn from(val: T) -> ExportDefaultExpression<'src> {
         ExportDefaultExpression {
             token_export: Default::default(),
             token_default: Default::default(),
             expression: val.into(),
+            token_semi: Default::default(),
             position: None,
+            comments: None,
         }
     }
 }
@@ -393,11 +547,11 @@ mod tests_export_default_expression {
 // export class foo {}
 node!(pub struct ExportClassDeclaration {
     pub token_export: KeywordData,
-    pub exported: ClassDeclaration,
+    pub exported: ClassDeclaration<'src>,
 });
-impl NodeDisplay for ExportClassDeclaration {
+impl<'src> NodeDisplay for ExportClassDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
 
         f.node(&self.exported)
     }
@@ -407,11 +561,11 @@ impl NodeDisplay for ExportClassDeclaration {
 // export function foo() {}
 node!(pub struct ExportFunctionDeclaration {
     pub token_export: KeywordData,
-    pub exported: FunctionDeclaration,
+    pub exported: FunctionDeclaration<'src>,
 });
-impl NodeDisplay for ExportFunctionDeclaration {
+impl<'src> NodeDisplay for ExportFunctionDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
 
         f.node(&self.exported)
     }
@@ -421,11 +575,11 @@ impl NodeDisplay for ExportFunctionDeclaration {
 // export var foo;
 node!(pub struct ExportVarStatement {
     pub token_export: KeywordData,
-    pub exported: VariableStatement,
+    pub exported: VariableStatement<'src>,
 });
-impl NodeDisplay for ExportVarStatement {
+impl<'src> NodeDisplay for ExportVarStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
 
         f.node(&self.exported)
     }
@@ -435,11 +589,11 @@ impl NodeDisplay for ExportVarStatement {
 // export let foo;
 node!(pub struct ExportLetDeclaration {
     pub token_export: KeywordData,
-    pub exported: LetDeclaration,
+    pub exported: LetDeclaration<'src>,
 });
-impl NodeDisplay for ExportLetDeclaration {
+impl<'src> NodeDisplay for ExportLetDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
 
         f.node(&self.exported)
     }
@@ -449,11 +603,11 @@ impl NodeDisplay for ExportLetDeclaration {
 // export const foo;
 node!(pub struct ExportConstDeclaration {
     pub token_export: KeywordData,
-    pub exported: ConstDeclaration,
+    pub exported: ConstDeclaration<'src>,
 });
-impl NodeDisplay for ExportConstDeclaration {
+impl<'src> NodeDisplay for ExportConstDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
 
         f.node(&self.exported)
     }
@@ -464,32 +618,31 @@ impl NodeDisplay for ExportConstDeclaration {
 // export {foo as bar};
 node!(#[derive(Default)] pub struct ExportLocalBindings {
     pub token_export: KeywordData,
-    pub token_curly_left: MaybeTokenPosition,
-    pub specifiers: Vec<(LocalExportSpecifier, KeywordData)>,
-    pub last_specifier: Option<LocalExportSpecifier>,
-    pub token_curly_right: MaybeTokenPosition,
+    pub token_curly_l: KeywordData,
+    pub specifiers: Vec<(LocalExportSpecifier<'src>, KeywordData)>,
+    pub last_specifier: Option<LocalExportSpecifier<'src>>,
+    pub token_curly_r: KeywordData,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportLocalBindings {
+impl<'src> NodeDisplay for ExportLocalBindings<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
         {
             let mut f = f.wrap_curly();
             f.comma_list(&self.specifiers)?;
-            f.node(&self.last_specifier);
+            f.node(&self.last_specifier)?;
         }
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
-impl From<Vec<LocalExportSpecifier>> for ExportLocalBindings {
-    fn from(v: Vec<LocalExportSpecifier>) -> ExportLocalBindings {
+impl<'src> From<Vec<LocalExportSpecifier<'src>>> for ExportLocalBindings<'src> {
+    fn from(mut v: Vec<LocalExportSpecifier>) -> ExportLocalBindings {
+        let last_specifier = v.pop();
         ExportLocalBindings {
-            token_export: Default::default(),
-            token_curly_left: Default::default(),
             specifiers: v.into_iter().map(|s| (s, Default::default())).collect(),
-            last_specifier: Default::default(),
-            token_curly_right: Default::default(),
-            position: None,
+            last_specifier,
+            ..Default::default()
         }
     }
 }
@@ -503,13 +656,15 @@ mod tests_export_specifiers {
     fn it_prints_with_specifiers() {
         assert_serialize!(
             ExportLocalBindings::from(vec![
-                ReferenceIdentifier::from("someName").into(),
-                ReferenceIdentifier::from("someOtherName").into(),
-                LocalExportSpecifier {
+                NormalLocalExportSpecifier::from(ReferenceIdentifier::from("someName")).into(),
+                NormalLocalExportSpecifier::from(ReferenceIdentifier::from("someOtherName")).into(),
+                AliasedLocalExportSpecifier {
                     local: "local".into(),
-                    exported: Some("exp".into()),
+                    token_as: Default::default(),
+                    exported: "exp".into(),
                     position: None,
-                },
+                    comments: None,
+                }.into(),
             ]),
             "export{someName,someOtherName,local as exp};"
         );
@@ -517,35 +672,37 @@ mod tests_export_specifiers {
 }
 
 node_enum!(@node_display pub enum LocalExportSpecifier {
-    Normal(NormalLocalExportSpecifier),
-    Aliased(AliasedLocalExportSpecifier),
+    Normal(NormalLocalExportSpecifier<'src>),
+    Aliased(AliasedLocalExportSpecifier<'src>),
 });
 
 node!(pub struct NormalLocalExportSpecifier {
-    pub local: ReferenceIdentifier,
+    pub local: ReferenceIdentifier<'src>,
 });
-impl NodeDisplay for NormalLocalExportSpecifier {
+impl<'src> NodeDisplay for NormalLocalExportSpecifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.local)
     }
 }
-impl From<ReferenceIdentifier> for NormalLocalExportSpecifier {
+impl<'src> From<ReferenceIdentifier<'src>> for NormalLocalExportSpecifier<'src> {
     fn from(b: ReferenceIdentifier) -> NormalLocalExportSpecifier {
         NormalLocalExportSpecifier {
             local: b,
             position: None,
+            comments: None,
         }
     }
 }
 
 node!(pub struct AliasedLocalExportSpecifier {
-    pub local: ReferenceIdentifier,
-    pub exported: ModuleIdentifier,
+    pub local: ReferenceIdentifier<'src>,
+    pub token_as: KeywordWrappedData,
+    pub exported: ModuleIdentifier<'src>,
 });
-impl NodeDisplay for AliasedLocalExportSpecifier {
+impl<'src> NodeDisplay for AliasedLocalExportSpecifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.local)?;
-        f.keyword(Keyword::As);
+        f.keyword(Keyword::As, &self.token_as);
         f.node(&self.exported)
     }
 }
@@ -554,17 +711,18 @@ impl NodeDisplay for AliasedLocalExportSpecifier {
 // export {foo} from "";
 // export {foo as bar} from "";
 node!(pub struct ExportSourceSpecifiers {
-    pub token_export: MaybeTokenPosition,
-    pub token_curly_left: MaybeTokenPosition,
-    pub specifiers: Vec<(SourceExportSpecifier, KeywordData)>,
-    pub last_specifier: Option<SourceExportSpecifier>,
-    pub token_curly_right: MaybeTokenPosition,
-    pub token_from: MaybeTokenPosition,
-    pub source: String,
+    pub token_export: KeywordData,
+    pub token_curly_l: KeywordData,
+    pub specifiers: Vec<(SourceExportSpecifier<'src>, KeywordData)>,
+    pub last_specifier: Option<SourceExportSpecifier<'src>>,
+    pub token_curly_r: KeywordData,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportSourceSpecifiers {
+impl<'src> NodeDisplay for ExportSourceSpecifiers<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
 
         {
             let mut f = f.wrap_curly();
@@ -572,9 +730,9 @@ impl NodeDisplay for ExportSourceSpecifiers {
             f.node(&self.last_specifier)?;
         }
 
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -586,17 +744,31 @@ mod tests_export_source_specifiers {
     fn it_prints_with_specifiers() {
         assert_serialize!(
             ExportSourceSpecifiers {
+                token_export: Default::default(),
+                token_curly_l: Default::default(),
                 specifiers: vec![
-                    ModuleIdentifier::from("someName").into(),
-                    ModuleIdentifier::from("someOtherName").into(),
-                    SourceExportSpecifier {
-                        imported: "local".into(),
-                        exported: Some("exp".into()),
-                        position: None,
-                    },
+                    (
+                        NormalSourceExportSpecifier::from(ModuleIdentifier::from("someName")).into(),
+                        Default::default(),
+                    ),
+                    (
+                        NormalSourceExportSpecifier::from(ModuleIdentifier::from("someOtherName")).into(),
+                        Default::default(),
+                    ),
                 ],
+                last_specifier: Some(AliasedSourceExportSpecifier {
+                    imported: "local".into(),
+                    token_as: Default::default(),
+                    exported: "exp".into(),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_r: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "export{someName,someOtherName,local as exp}from'file.js';"
         );
@@ -604,36 +776,38 @@ mod tests_export_source_specifiers {
 }
 
 node_enum!(@node_display pub enum SourceExportSpecifier {
-    Normal(NormalSourceExportSpecifier),
-    Aliased(AliasedSourceExportSpecifier),
+    Normal(NormalSourceExportSpecifier<'src>),
+    Aliased(AliasedSourceExportSpecifier<'src>),
 });
 
 
 node!(pub struct NormalSourceExportSpecifier {
-    pub imported: ModuleIdentifier,
+    pub imported: ModuleIdentifier<'src>,
 });
-impl NodeDisplay for NormalSourceExportSpecifier {
+impl<'src> NodeDisplay for NormalSourceExportSpecifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.imported)
     }
 }
-impl From<ModuleIdentifier> for NormalSourceExportSpecifier {
+impl<'src> From<ModuleIdentifier<'src>> for NormalSourceExportSpecifier<'src> {
     fn from(b: ModuleIdentifier) -> NormalSourceExportSpecifier {
         NormalSourceExportSpecifier {
             imported: b,
             position: None,
+            comments: None,
         }
     }
 }
 
 node!(pub struct AliasedSourceExportSpecifier {
-    pub imported: ModuleIdentifier,
-    pub exported: ModuleIdentifier,
+    pub imported: ModuleIdentifier<'src>,
+    pub token_as: KeywordWrappedData,
+    pub exported: ModuleIdentifier<'src>,
 });
-impl NodeDisplay for AliasedSourceExportSpecifier {
+impl<'src> NodeDisplay for AliasedSourceExportSpecifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.imported)?;
-        f.keyword(Keyword::As);
+        f.keyword(Keyword::As, &self.token_as);
         f.node(&self.exported)
     }
 }
@@ -641,18 +815,19 @@ impl NodeDisplay for AliasedSourceExportSpecifier {
 
 // export * from "";
 node!(pub struct ExportAllSpecifiers {
-    pub token_export: MaybeTokenPosition,
-    pub token_star: MaybeTokenPosition,
-    pub token_from: MaybeTokenPosition,
-    pub source: String,
+    pub token_export: KeywordData,
+    pub token_star: KeywordData,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportAllSpecifiers {
+impl<'src> NodeDisplay for ExportAllSpecifiers<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
-        f.punctuator(Punctuator::Star);
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::Export, &self.token_export);
+        f.punctuator(Punctuator::Star, &self.token_star);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -664,8 +839,13 @@ mod tests_export_source_all {
     fn it_prints_with_specifiers() {
         assert_serialize!(
             ExportAllSpecifiers {
+                token_export: Default::default(),
+                token_star: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "export*from'file.js';"
         );
@@ -675,18 +855,19 @@ mod tests_export_source_all {
 
 // export foo from "";
 node!(pub struct ExportNamedSpecifier {
-    pub token_export: MaybeTokenPosition,
-    pub default: ModuleIdentifier,
-    pub token_from: MaybeTokenPosition,
-    pub source: String,
+    pub token_export: KeywordData,
+    pub default: ModuleIdentifier<'src>,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportNamedSpecifier {
+impl<'src> NodeDisplay for ExportNamedSpecifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
         f.node(&self.default)?;
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -698,9 +879,13 @@ mod tests_export_named_default {
     fn it_prints_with_specifiers() {
         assert_serialize!(
             ExportNamedSpecifier {
+                token_export: Default::default(),
                 default: "fooExp".into(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "export fooExp from'file.js';"
         );
@@ -710,24 +895,27 @@ mod tests_export_named_default {
 
 // export foo, * as foo from "";
 node!(pub struct ExportNamedAndNamespace {
-    pub token_export: MaybeTokenPosition,
-    pub default: ModuleIdentifier,
-    pub token_comma: MaybeTokenPosition,
-    pub namespace: ModuleIdentifier,
-    pub token_from: MaybeTokenPosition,
-    pub source: String,
+    pub token_export: KeywordData,
+    pub default: ModuleIdentifier<'src>,
+    pub token_comma: KeywordData,
+    pub token_star: KeywordData,
+    pub token_as: KeywordWrappedData,
+    pub namespace: ModuleIdentifier<'src>,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportNamedAndNamespace {
+impl<'src> NodeDisplay for ExportNamedAndNamespace<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
         f.node(&self.default)?;
-        f.punctuator(Punctuator::Comma);
-        f.punctuator(Punctuator::Star);
-        f.keyword(Keyword::As);
+        f.punctuator(Punctuator::Comma, &self.token_comma);
+        f.punctuator(Punctuator::Star, &self.token_star);
+        f.keyword(Keyword::As, &self.token_as);
         f.node(&self.namespace)?;
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -735,21 +923,23 @@ impl NodeDisplay for ExportNamedAndNamespace {
 
 // export * as foo from "";
 node!(pub struct ExportNamespace {
-    pub token_export: MaybeTokenPosition,
-    pub token_star: MaybeTokenPosition,
-    pub token_as: MaybeTokenPosition,
-    pub namespace: ModuleIdentifier,
-    pub source: String,
+    pub token_export: KeywordData,
+    pub token_star: KeywordData,
+    pub token_as: KeywordWrappedData,
+    pub namespace: ModuleIdentifier<'src>,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportNamespace {
+impl<'src> NodeDisplay for ExportNamespace<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
-        f.punctuator(Punctuator::Star);
-        f.keyword(Keyword::As);
+        f.keyword(Keyword::Export, &self.token_export);
+        f.punctuator(Punctuator::Star, &self.token_star);
+        f.keyword(Keyword::As, &self.token_as);
         f.node(&self.namespace)?;
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -762,9 +952,15 @@ mod tests_export_namespace {
     fn it_prints_without_specifiers() {
         assert_serialize!(
             ExportNamespace {
+                token_export: Default::default(),
+                token_star: Default::default(),
+                token_as: Default::default(),
                 namespace: "foo".into(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "export*as foo from'file.js';"
         );
@@ -775,29 +971,30 @@ mod tests_export_namespace {
 // export foo, {foo} from "";
 // export foo, {foo as bar} from "";
 node!(pub struct ExportNamedAndSpecifiers {
-    pub token_export: MaybeTokenPosition,
-    pub default: ModuleIdentifier,
-    pub token_comma: MaybeTokenPosition,
-    pub token_curly_left: MaybeTokenPosition,
-    pub specifiers: Vec<(SourceExportSpecifier, KeywordData)>,
-    pub last_specifier: Option<SourceExportSpecifier>,
-    pub token_curly_right: MaybeTokenPosition,
-    pub token_from: MaybeTokenPosition,
-    pub source: String,
+    pub token_export: KeywordData,
+    pub default: ModuleIdentifier<'src>,
+    pub token_comma: KeywordData,
+    pub token_curly_l: KeywordData,
+    pub specifiers: Vec<(SourceExportSpecifier<'src>, KeywordData)>,
+    pub last_specifier: Option<SourceExportSpecifier<'src>>,
+    pub token_curly_r: KeywordData,
+    pub token_from: KeywordData,
+    pub source: String<'src>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ExportNamedAndSpecifiers {
+impl<'src> NodeDisplay for ExportNamedAndSpecifiers<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.keyword(Keyword::Export);
+        f.keyword(Keyword::Export, &self.token_export);
         f.node(&self.default)?;
-        f.punctuator(Punctuator::Comma);
+        f.punctuator(Punctuator::Comma, &self.token_comma);
         {
             let mut f = f.wrap_curly();
             f.comma_list(&self.specifiers)?;
             f.node(&self.last_specifier)?;
         }
-        f.keyword(Keyword::From);
+        f.keyword(Keyword::From, &self.token_from);
         f.node(&self.source)?;
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -810,10 +1007,18 @@ mod tests_export_named_and_specifiers {
     fn it_prints_without_specifiers() {
         assert_serialize!(
             ExportNamedAndSpecifiers {
+                token_export: Default::default(),
                 default: "foo".into(),
+                token_comma: Default::default(),
+                token_curly_l: Default::default(),
                 specifiers: vec![],
+                last_specifier: None,
+                token_curly_r: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "export foo,{}from'file.js';"
         );
@@ -823,18 +1028,33 @@ mod tests_export_named_and_specifiers {
     fn it_prints_with_specifiers() {
         assert_serialize!(
             ExportNamedAndSpecifiers {
+                token_export: Default::default(),
                 default: "foo".into(),
+                token_comma: Default::default(),
+                token_curly_l: Default::default(),
                 specifiers: vec![
-                    ModuleIdentifier::from("someName").into(),
-                    ModuleIdentifier::from("someOtherName").into(),
-                    SourceExportSpecifier {
-                        imported: "local".into(),
-                        exported: Some("exp".into()),
-                        position: None,
-                    },
+                    (
+                        NormalSourceExportSpecifier::from(ModuleIdentifier::from("someName")).into(),
+                        Default::default(),
+                    ),
+                    (
+                        NormalSourceExportSpecifier::from(ModuleIdentifier::from("someOtherName")).into(),
+                        Default::default(),
+                    ),
                 ],
+                last_specifier: Some(AliasedSourceExportSpecifier {
+                    imported: "local".into(),
+                    token_as: Default::default(),
+                    exported: "exp".into(),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_r: Default::default(),
+                token_from: Default::default(),
                 source: "file.js".into(),
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "export foo,{someName,someOtherName,local as exp}from'file.js';"
         );
@@ -1,22 +1,20 @@
-use std::default;
-
-use ast::{MaybeTokenPosition, KeywordData, SeparatorTokens};
+use ast::{MaybeTokenPosition, KeywordData, SeparatorTokens, NodeBox};
 
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punctuator, Precedence,
                    LookaheadSequence};
 
 use ast::alias;
 
-use ast::general::PropertyName;
+use ast::general::{PropertyName, ReferenceIdentifier};
 use ast::functions::{FunctionParams, FunctionBody};
 
 // experimental
 node!(pub struct ObjectSpreadElement {
-    pub expression: Box<alias::Expression>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ObjectSpreadElement {
+impl<'src> NodeDisplay for ObjectSpreadElement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &());
         f.require_precedence(Precedence::Assignment).node(&self.expression)?;
         Ok(())
     }
@@ -25,11 +23,11 @@ impl NodeDisplay for ObjectSpreadElement {
 // {a: 1, ...b}
 node!(#[derive(Default)] pub struct ObjectExpression {
     pub token_curly_left: MaybeTokenPosition,
-    pub properties: Vec<(ObjectItem, KeywordData)>,
-    pub last_property: Option<ObjectItem>,
+    pub properties: Vec<(ObjectItem<'src>, KeywordData)>,
+    pub last_property: Option<ObjectItem<'src>>,
     pub token_curly_right: MaybeTokenPosition,
 });
-impl NodeDisplay for ObjectExpression {
+impl<'src> NodeDisplay for ObjectExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.lookahead_wrap_parens(LookaheadSequence::Curly);
         let mut f = f.wrap_curly();
@@ -56,22 +54,29 @@ mod tests_object_expression {
     fn it_prints() {
         assert_serialize!(
             ObjectExpression {
+                token_curly_left: Default::default(),
                 properties: vec![
-                    ObjectProperty {
-                        name: PropertyIdentifier::from("fooProp").into(),
-                        value: literal::Boolean::from(false).into(),
-                        position: None,
-                    }.into(),
-                    ObjectMethod {
-                        kind: Default::default(),
-                        id: PropertyIdentifier::from("fooMethod").into(),
-                        params: Default::default(),
-                        body: Default::default(),
-                        position: None,
-                    }.into(),
+                    (
+                        ObjectProperty {
+                            name: PropertyIdentifier::from("fooProp").into(),
+                            value: literal::Boolean::from(false).into(),
+                            position: None,
+                            comments: None,
+                        }.into(),
+                        Default::default(),
+                    ),
                 ],
-                spread: Default::default(),
+                last_property: Some(ObjectMethod {
+                    kind: Default::default(),
+                    id: PropertyIdentifier::from("fooMethod").into(),
+                    params: Default::default(),
+                    body: Default::default(),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_right: Default::default(),
                 position: None,
+                comments: None,
             },
             "{fooProp:false,fooMethod(){}}"
         );
@@ -81,15 +86,26 @@ mod tests_object_expression {
     fn it_prints_prop_and_spread() {
         assert_serialize!(
             ObjectExpression {
+                token_curly_left: Default::default(),
                 properties: vec![
-                    ObjectProperty {
-                        name: PropertyIdentifier::from("fooProp").into(),
-                        value: literal::Boolean::from(false).into(),
-                        position: None,
-                    }.into(),
+                    (
+                        ObjectProperty {
+                            name: PropertyIdentifier::from("fooProp").into(),
+                            value: literal::Boolean::from(false).into(),
+                            position: None,
+                            comments: None,
+                        }.into(),
+                        Default::default(),
+                    ),
                 ],
-                spread: literal::Boolean::from(true).into(),
+                last_property: Some(ObjectSpreadElement {
+                    expression: literal::Boolean::from(true).into(),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_right: Default::default(),
                 position: None,
+                comments: None,
             },
             "{fooProp:false,...true}"
         );
@@ -99,9 +115,16 @@ mod tests_object_expression {
     fn it_prints_spread() {
         assert_serialize!(
             ObjectExpression {
+                token_curly_left: Default::default(),
                 properties: vec![],
-                spread: literal::Boolean::from(true).into(),
+                last_property: Some(ObjectSpreadElement {
+                    expression: literal::Boolean::from(true).into(),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_right: Default::default(),
                 position: None,
+                comments: None,
             },
             "{...true}"
         );
@@ -109,14 +132,16 @@ mod tests_object_expression {
 }
 
 
-node_enum!(@node_display pub enum ObjectItem {
-    Method(ObjectMethod),
-    Property(ObjectProperty),
-    Spread(ObjectSpreadElement),
+node_enum!(@boxed @node_display pub enum ObjectItem {
+    Method(ObjectMethod<'src>),
+    Property(ObjectProperty<'src>),
+    Shorthand(ObjectShorthandProperty<'src>),
+    Spread(ObjectSpreadElement<'src>),
 });
 
 
-node_kind!(pub enum MethodKind {
+node_kind!(#[derive(Default)] pub enum MethodKind {
+    #[default]
     Normal,
     Generator,
     Async,
@@ -124,23 +149,18 @@ node_kind!(pub enum MethodKind {
     Get,
     Set,
 });
-impl default::Default for MethodKind {
-    fn default() -> MethodKind {
-        MethodKind::Normal
-    }
-}
 impl NodeDisplay for MethodKind {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         match *self {
             MethodKind::Normal => {}
-            MethodKind::Generator => f.punctuator(Punctuator::Star),
-            MethodKind::Async => f.keyword(Keyword::Async),
+            MethodKind::Generator => f.punctuator(Punctuator::Star, &()),
+            MethodKind::Async => f.keyword(Keyword::Async, &()),
             MethodKind::AsyncGenerator => {
-                f.keyword(Keyword::Async);
-                f.punctuator(Punctuator::Star);
+                f.keyword(Keyword::Async, &());
+                f.punctuator(Punctuator::Star, &());
             }
-            MethodKind::Get => f.keyword(Keyword::Get),
-            MethodKind::Set => f.keyword(Keyword::Set),
+            MethodKind::Get => f.keyword(Keyword::Get, &()),
+            MethodKind::Set => f.keyword(Keyword::Set, &()),
         }
 
         Ok(())
@@ -150,11 +170,11 @@ impl NodeDisplay for MethodKind {
 
 node!(pub struct ObjectMethod {
     pub kind: MethodKind,
-    pub id: PropertyName, // TODO: Rename to "name" to match property?
-    pub params: FunctionParams,
-    pub body: FunctionBody,
+    pub id: PropertyName<'src>, // TODO: Rename to "name" to match property?
+    pub params: FunctionParams<'src>,
+    pub body: FunctionBody<'src>,
 });
-impl NodeDisplay for ObjectMethod {
+impl<'src> NodeDisplay for ObjectMethod<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.kind)?;
         f.node(&self.id)?;
@@ -176,6 +196,7 @@ mod tests_object_method {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "fooMethod(){}"
         );
@@ -190,6 +211,7 @@ mod tests_object_method {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "async fooMethod(){}"
         );
@@ -204,6 +226,7 @@ mod tests_object_method {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "*fooMethod(){}"
         );
@@ -218,6 +241,7 @@ mod tests_object_method {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "async*fooMethod(){}"
         );
@@ -232,6 +256,7 @@ mod tests_object_method {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "get fooMethod(){}"
         );
@@ -246,6 +271,7 @@ mod tests_object_method {
                 params: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "set fooMethod(){}"
         );
@@ -254,14 +280,14 @@ mod tests_object_method {
 
 
 node!(pub struct ObjectProperty {
-    pub name: PropertyName,
-    pub value: Box<alias::Expression>,
+    pub name: PropertyName<'src>,
+    pub value: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ObjectProperty {
+impl<'src> NodeDisplay for ObjectProperty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
 
         f.node(&self.name)?;
-        f.punctuator(Punctuator::Colon);
+        f.punctuator(Punctuator::Colon, &());
 
         f.require_precedence(Precedence::Assignment).node(
             &self.value,
@@ -284,6 +310,7 @@ mod tests_object_property {
                 name: PropertyIdentifier::from("fooProp").into(),
                 value: literal::Boolean::from(false).into(),
                 position: None,
+                comments: None,
             },
             "fooProp:false"
         );
@@ -295,11 +322,14 @@ mod tests_object_property {
             ObjectProperty {
                 name: PropertyIdentifier::from("fooProp").into(),
                 value: SequenceExpression {
+                    token_comma: Default::default(),
                     left: literal::Boolean::from(false).into(),
                     right: literal::Boolean::from(true).into(),
                     position: None,
+                    comments: None,
                 }.into(),
                 position: None,
+                comments: None,
             },
             "fooProp:(false,true)"
         );
@@ -307,41 +337,70 @@ mod tests_object_property {
 }
 
 
+// {foo}
+node!(pub struct ObjectShorthandProperty {
+    pub id: ReferenceIdentifier<'src>,
+});
+impl<'src> NodeDisplay for ObjectShorthandProperty<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.id)
+    }
+}
+impl<'src, T: Into<ReferenceIdentifier<'src>>> From<T> for ObjectShorthandProperty<'src> {
+    fn from(id: T) -> ObjectShorthandProperty<'src> {
+        ObjectShorthandProperty {
+            id: id.into(),
+            position: None,
+            comments: None,
+        }
+    }
+}
+#[cfg(test)]
+mod tests_object_shorthand_property {
+    use super::*;
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(ObjectShorthandProperty::from("fooProp"), "fooProp");
+    }
+}
+
+
 node_enum!(@node_display pub enum ArrayItem {
-    Expression(ArrayExpressionItem),
-    Spread(ArraySpreadItem),
+    Expression(ArrayExpressionItem<'src>),
+    Spread(ArraySpreadItem<'src>),
 });
 
 node!(pub struct ArrayExpressionItem {
-    pub token_prefix: SeparatorTokens,
-    pub expression: Box<alias::Expression>,
+    pub token_prefix: SeparatorTokens<'src>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ArrayExpressionItem {
+impl<'src> NodeDisplay for ArrayExpressionItem<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.require_precedence(Precedence::Assignment);
         f.node(&self.expression)
     }
 }
 node!(pub struct ArraySpreadItem {
-    pub token_prefix: SeparatorTokens,
-    pub expression: Box<alias::Expression>,
+    pub token_prefix: SeparatorTokens<'src>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 
 });
-impl NodeDisplay for ArraySpreadItem {
+impl<'src> NodeDisplay for ArraySpreadItem<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.require_precedence(Precedence::Assignment);
 
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &());
         f.node(&self.expression)
     }
 }
 
 // [1, 2, 3, ...4]
 node!(#[derive(Default)] pub struct ArrayExpression {
-    pub elements: Vec<(ArrayExpressionItem, KeywordData)>,
-    pub last_element: ArrayExpressionItem,
+    pub elements: Vec<(Option<ArrayItem<'src>>, KeywordData)>,
+    pub last_element: Option<ArrayItem<'src>>,
 });
-impl NodeDisplay for ArrayExpression {
+impl<'src> NodeDisplay for ArrayExpression<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.precedence(Precedence::Primary);
         let mut f = f.wrap_square();
@@ -360,13 +419,32 @@ mod tests_array_expression {
         assert_serialize!(ArrayExpression::default(), "[]");
     }
 
+    fn item(value: bool) -> ArrayItem<'static> {
+        ArrayExpressionItem {
+            token_prefix: Default::default(),
+            expression: literal::Boolean::from(value).into(),
+            position: None,
+            comments: None,
+        }.into()
+    }
+
+    fn spread(value: bool) -> ArrayItem<'static> {
+        ArraySpreadItem {
+            token_prefix: Default::default(),
+            expression: literal::Boolean::from(value).into(),
+            position: None,
+            comments: None,
+        }.into()
+    }
+
     #[test]
     fn it_prints_with_elision() {
         assert_serialize!(
             ArrayExpression {
-                elements: vec![None, None],
-                spread: Default::default(),
+                elements: vec![(None, Default::default())],
+                last_element: None,
                 position: None,
+                comments: None,
             },
             "[,]"
         );
@@ -376,12 +454,10 @@ mod tests_array_expression {
     fn it_prints_with_items() {
         assert_serialize!(
             ArrayExpression {
-                elements: vec![
-                    literal::Boolean::from(false).into(),
-                    literal::Boolean::from(true).into(),
-                ],
-                spread: Default::default(),
+                elements: vec![(Some(item(false)), Default::default())],
+                last_element: Some(item(true)),
                 position: None,
+                comments: None,
             },
             "[false,true]"
         );
@@ -391,9 +467,10 @@ mod tests_array_expression {
     fn it_prints_with_items_and_elision() {
         assert_serialize!(
             ArrayExpression {
-                elements: vec![None, literal::Boolean::from(true).into()],
-                spread: Default::default(),
+                elements: vec![(None, Default::default())],
+                last_element: Some(item(true)),
                 position: None,
+                comments: None,
             },
             "[,true]"
         );
@@ -404,8 +481,9 @@ mod tests_array_expression {
         assert_serialize!(
             ArrayExpression {
                 elements: vec![],
-                spread: literal::Boolean::from(true).into(),
+                last_element: Some(spread(true)),
                 position: None,
+                comments: None,
             },
             "[...true]"
         );
@@ -416,11 +494,12 @@ mod tests_array_expression {
         assert_serialize!(
             ArrayExpression {
                 elements: vec![
-                    literal::Boolean::from(false).into(),
-                    literal::Boolean::from(true).into(),
+                    (Some(item(false)), Default::default()),
+                    (Some(item(true)), Default::default()),
                 ],
-                spread: literal::Boolean::from(true).into(),
+                last_element: Some(spread(true)),
                 position: None,
+                comments: None,
             },
             "[false,true,...true]"
         );
@@ -430,9 +509,13 @@ mod tests_array_expression {
     fn it_prints_with_items_elision_and_spread() {
         assert_serialize!(
             ArrayExpression {
-                elements: vec![None, literal::Boolean::from(true).into()],
-                spread: literal::Boolean::from(true).into(),
+                elements: vec![
+                    (None, Default::default()),
+                    (Some(item(true)), Default::default()),
+                ],
+                last_element: Some(spread(true)),
                 position: None,
+                comments: None,
             },
             "[,true,...true]"
         );
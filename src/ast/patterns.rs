@@ -1,8 +1,7 @@
-use ast::{MaybeTokenPosition, KeywordData, KeywordSuffixData};
+use ast::{MaybeTokenPosition, KeywordData, KeywordSuffixData, NodeBox};
 
-use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Punctuator, Precedence};
+use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Punctuator};
 
-use ast::alias;
 use ast::general;
 use ast::general::{BindingIdentifier, ReferenceIdentifier, PropertyName};
 
@@ -11,28 +10,28 @@ use ast::expression::MemberExpression;
 
 // Used for update and update-assignment
 node_enum!(@node_display pub enum LeftHandSimpleAssign {
-    Identifier(ReferenceIdentifier),
-    Member(MemberExpression),
-    Parenthesized(ParenthesizedAssignmentPattern),
+    Identifier(ReferenceIdentifier<'src>),
+    Member(MemberExpression<'src>),
+    Parenthesized(ParenthesizedAssignmentPattern<'src>),
 });
 
 // Used for standard assignment, and for..in and for..of LHS
 node_enum!(@node_display pub enum LeftHandComplexAssign {
-    Parenthesized(ParenthesizedAssignmentPattern),
-    Identifier(ReferenceIdentifier),
-    Member(MemberExpression),
-    Object(ObjectAssignmentPattern),
-    Array(ArrayAssignmentPattern),
+    Parenthesized(ParenthesizedAssignmentPattern<'src>),
+    Identifier(ReferenceIdentifier<'src>),
+    Member(MemberExpression<'src>),
+    Object(ObjectAssignmentPattern<'src>),
+    Array(ArrayAssignmentPattern<'src>),
 });
 
 
 // (i) = 4; and (obj.foo) = 4; are valid assignments in JS.
 node!(pub struct ParenthesizedAssignmentPattern {
     pub token_paren_l: KeywordData,
-    pub pattern: Box<LeftHandSimpleAssign>,
+    pub pattern: NodeBox<LeftHandSimpleAssign<'src>>,
     pub token_paren_r: KeywordData,
 });
-impl NodeDisplay for ParenthesizedAssignmentPattern {
+impl<'src> NodeDisplay for ParenthesizedAssignmentPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.wrap_parens().node(&self.pattern)
     }
@@ -42,11 +41,11 @@ impl NodeDisplay for ParenthesizedAssignmentPattern {
 // {     } =
 node!(#[derive(Default)] pub struct ObjectAssignmentPattern {
     pub token_curly_l: MaybeTokenPosition,
-    pub properties: Vec<(ObjectAssignmentPatternProperty, KeywordData)>,
-    pub last_property: Option<ObjectAssignmentPatternLastProperty>,
+    pub properties: Vec<(ObjectAssignmentPatternProperty<'src>, KeywordData)>,
+    pub last_property: Option<ObjectAssignmentPatternLastProperty<'src>>,
     pub token_curly_r: KeywordData,
 });
-impl NodeDisplay for ObjectAssignmentPattern {
+impl<'src> NodeDisplay for ObjectAssignmentPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_curly();
 
@@ -57,6 +56,7 @@ impl NodeDisplay for ObjectAssignmentPattern {
 #[cfg(test)]
 mod tests_object_assignment_pattern {
     use super::*;
+    use ast::alias;
     use ast::literal;
     use ast::general::PropertyIdentifier;
 
@@ -69,24 +69,34 @@ mod tests_object_assignment_pattern {
     fn it_prints() {
         assert_serialize!(
             ObjectAssignmentPattern {
+                token_curly_l: Default::default(),
                 properties: vec![
-                    ObjectAssignmentPatternIdentifierProperty::from(
-                        ReferenceIdentifier::from("foo")
-                    ).into(),
-                    ObjectAssignmentPatternIdentifierProperty {
-                        id: ReferenceIdentifier::from("foo2"),
-                        init: literal::Boolean::from(true).into(),
-                        position: None,
-                    }.into(),
-                    ObjectAssignmentPatternPatternProperty {
-                        name: PropertyIdentifier::from("foo3").into(),
-                        pattern: ReferenceIdentifier::from("foo4").into(),
-                        init: literal::Boolean::from(false).into(),
-                        position: None,
-                    }.into(),
+                    (
+                        ObjectAssignmentPatternIdentifierProperty::from(
+                            ReferenceIdentifier::from("foo")
+                        ).into(),
+                        Default::default(),
+                    ),
+                    (
+                        ObjectAssignmentPatternIdentifierProperty {
+                            id: ReferenceIdentifier::from("foo2"),
+                            init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
+                            position: None,
+                            comments: None,
+                        }.into(),
+                        Default::default(),
+                    ),
                 ],
-                rest: Default::default(),
+                last_property: Some(ObjectAssignmentPatternPatternProperty {
+                    name: PropertyIdentifier::from("foo3").into(),
+                    pattern: ReferenceIdentifier::from("foo4").into(),
+                    init: Some(alias::Expression::from(literal::Boolean::from(false)).into()),
+                    position: None,
+                    comments: None,
+                }.into()),
+                token_curly_r: Default::default(),
                 position: None,
+                comments: None,
             },
             "{foo,foo2=true,foo3:foo4=false}"
         );
@@ -94,32 +104,32 @@ mod tests_object_assignment_pattern {
 }
 
 node_enum!(@node_display pub enum ObjectAssignmentPatternProperty {
-    Identifier(ObjectAssignmentPatternIdentifierProperty),
-    Pattern(ObjectAssignmentPatternPatternProperty),
+    Identifier(ObjectAssignmentPatternIdentifierProperty<'src>),
+    Pattern(ObjectAssignmentPatternPatternProperty<'src>),
 });
 node_enum!(@node_display pub enum ObjectAssignmentPatternLastProperty {
-    Identifier(ObjectAssignmentPatternIdentifierProperty),
-    Pattern(ObjectAssignmentPatternPatternProperty),
-    Rest(ObjectAssignmentPatternRestProperty),
+    Identifier(ObjectAssignmentPatternIdentifierProperty<'src>),
+    Pattern(ObjectAssignmentPatternPatternProperty<'src>),
+    Rest(ObjectAssignmentPatternRestProperty<'src>),
 });
 
 node!(pub struct ObjectAssignmentPatternRestProperty {
     pub token_ellipsis: KeywordSuffixData,
     // Object rest patterns exclude object and arrays since they wouldn't really be useful.
-    pub pattern: Box<LeftHandSimpleAssign>,
+    pub pattern: NodeBox<LeftHandSimpleAssign<'src>>,
 });
-impl NodeDisplay for ObjectAssignmentPatternRestProperty {
+impl<'src> NodeDisplay for ObjectAssignmentPatternRestProperty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &self.token_ellipsis);
         f.node(&self.pattern)
     }
 }
 
 node!(pub struct ObjectAssignmentPatternIdentifierProperty {
-    pub id: ReferenceIdentifier,
-    pub init: Option<general::Initializer>,
+    pub id: ReferenceIdentifier<'src>,
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ObjectAssignmentPatternIdentifierProperty {
+impl<'src> NodeDisplay for ObjectAssignmentPatternIdentifierProperty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.id)?;
         f.node(&self.init)?;
@@ -127,26 +137,27 @@ impl NodeDisplay for ObjectAssignmentPatternIdentifierProperty {
         Ok(())
     }
 }
-impl<T: Into<ReferenceIdentifier>> From<T> for ObjectAssignmentPatternIdentifierProperty {
-    fn from(val: T) -> ObjectAssignmentPatternIdentifierProperty {
+impl<'src, T: Into<ReferenceIdentifier<'src>>> From<T> for ObjectAssignmentPatternIdentifierProperty<'src> {
+    fn from(val: T) -> ObjectAssignmentPatternIdentifierProperty<'src> {
         ObjectAssignmentPatternIdentifierProperty {
             id: val.into(),
             init: None,
             position: None,
+            comments: None,
         }
     }
 }
 
 
 node!(pub struct ObjectAssignmentPatternPatternProperty {
-    pub name: PropertyName,
-    pub pattern: Box<LeftHandComplexAssign>,
-    pub init: Option<general::Initializer>,
+    pub name: PropertyName<'src>,
+    pub pattern: NodeBox<LeftHandComplexAssign<'src>>,
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ObjectAssignmentPatternPatternProperty {
+impl<'src> NodeDisplay for ObjectAssignmentPatternPatternProperty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.name)?;
-        f.punctuator(Punctuator::Colon);
+        f.punctuator(Punctuator::Colon, &());
         f.node(&self.pattern)?;
         f.node(&self.init)?;
 
@@ -160,10 +171,10 @@ impl NodeDisplay for ObjectAssignmentPatternPatternProperty {
 
 // [     ] =
 node!(#[derive(Default)] pub struct ArrayAssignmentPattern {
-    pub items: Vec<(Option<ArrayAssignmentPatternElement>, KeywordData)>,
-    pub last_item: Option<ArrayAssignmentPatternLastElement>,
+    pub items: Vec<(Option<ArrayAssignmentPatternElement<'src>>, KeywordData)>,
+    pub last_item: Option<ArrayAssignmentPatternLastElement<'src>>,
 });
-impl NodeDisplay for ArrayAssignmentPattern {
+impl<'src> NodeDisplay for ArrayAssignmentPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_square();
 
@@ -174,6 +185,7 @@ impl NodeDisplay for ArrayAssignmentPattern {
 #[cfg(test)]
 mod tests_array_assignment_pattern {
     use super::*;
+    use ast::alias;
     use ast::literal;
 
     #[test]
@@ -186,16 +198,19 @@ mod tests_array_assignment_pattern {
         assert_serialize!(
             ArrayAssignmentPattern {
                 items: vec![
-                    ArrayAssignmentPatternElement::from(ReferenceIdentifier::from("foo"))
-                        .into(),
-                    ArrayAssignmentPatternElement {
-                        id: ReferenceIdentifier::from("foo2").into(),
-                        init: literal::Boolean::from(true).into(),
-                        position: None,
-                    }.into(),
+                    (
+                        Some(ArrayAssignmentPatternElement::from(ReferenceIdentifier::from("foo"))),
+                        Default::default(),
+                    ),
                 ],
-                rest: Default::default(),
+                last_item: Some(ArrayAssignmentPatternElement {
+                    id: NodeBox::new(ReferenceIdentifier::from("foo2").into()),
+                    init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
+                    position: None,
+                    comments: None,
+                }.into()),
                 position: None,
+                comments: None,
             },
             "[foo,foo2=true]"
         );
@@ -203,28 +218,28 @@ mod tests_array_assignment_pattern {
 }
 
 node_enum!(@node_display pub enum ArrayAssignmentPatternLastElement {
-    Pattern(ArrayAssignmentPatternElement),
-    Rest(ArrayAssignmentRestElement),
+    Pattern(ArrayAssignmentPatternElement<'src>),
+    Rest(ArrayAssignmentRestElement<'src>),
 });
 
 
 node!(pub struct ArrayAssignmentRestElement {
     pub token_ellipsis: KeywordData,
-    pub pattern: Box<LeftHandComplexAssign>,
+    pub pattern: NodeBox<LeftHandComplexAssign<'src>>,
 });
-impl NodeDisplay for ArrayAssignmentRestElement {
+impl<'src> NodeDisplay for ArrayAssignmentRestElement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &self.token_ellipsis);
         f.node(&self.pattern)
     }
 }
 
 
 node!(pub struct ArrayAssignmentPatternElement {
-    pub id: Box<LeftHandComplexAssign>,
-    pub init: Option<general::Initializer>,
+    pub id: NodeBox<LeftHandComplexAssign<'src>>,
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ArrayAssignmentPatternElement {
+impl<'src> NodeDisplay for ArrayAssignmentPatternElement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.id)?;
         f.node(&self.init)?;
@@ -232,12 +247,13 @@ impl NodeDisplay for ArrayAssignmentPatternElement {
         Ok(())
     }
 }
-impl<T: Into<LeftHandComplexAssign>> From<T> for ArrayAssignmentPatternElement {
-    fn from(val: T) -> ArrayAssignmentPatternElement {
+impl<'src, T: Into<LeftHandComplexAssign<'src>>> From<T> for ArrayAssignmentPatternElement<'src> {
+    fn from(val: T) -> ArrayAssignmentPatternElement<'src> {
         ArrayAssignmentPatternElement {
-            id: Box::new(val.into()),
+            id: NodeBox::new(val.into()),
             init: None,
             position: None,
+            comments: None,
         }
     }
 }
@@ -245,18 +261,18 @@ impl<T: Into<LeftHandComplexAssign>> From<T> for ArrayAssignmentPatternElement {
 
 // Used for binding declarations (var, let, const, and function params)
 node_enum!(@node_display pub enum BindingPattern {
-    Identifier(BindingIdentifier),
-    Object(ObjectBindingPattern),
-    Array(ArrayBindingPattern),
+    Identifier(BindingIdentifier<'src>),
+    Object(ObjectBindingPattern<'src>),
+    Array(ArrayBindingPattern<'src>),
 });
 
 
 // {     }
 node!(#[derive(Default)] pub struct ObjectBindingPattern {
-    pub properties: Vec<(ObjectBindingPatternProperty, KeywordData)>,
-    pub last_property: Option<ObjectBindingPatternLastProperty>,
+    pub properties: Vec<(ObjectBindingPatternProperty<'src>, KeywordData)>,
+    pub last_property: Option<ObjectBindingPatternLastProperty<'src>>,
 });
-impl NodeDisplay for ObjectBindingPattern {
+impl<'src> NodeDisplay for ObjectBindingPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_curly();
 
@@ -267,6 +283,7 @@ impl NodeDisplay for ObjectBindingPattern {
 #[cfg(test)]
 mod tests_object_binding_pattern {
     use super::*;
+    use ast::alias;
     use ast::literal;
     use ast::general::PropertyIdentifier;
 
@@ -280,36 +297,44 @@ mod tests_object_binding_pattern {
         assert_serialize!(
             ObjectBindingPattern {
                 properties: vec![
-                    ObjectBindingPatternIdentifierProperty::from(
-                        BindingIdentifier::from("foo")
-                    ).into(),
-                    ObjectBindingPatternIdentifierProperty {
-                        id: BindingIdentifier::from("foo2"),
-                        init: literal::Boolean::from(true).into(),
-                        position: None,
-                    }.into(),
-                    ObjectBindingPatternPatternProperty {
-                        name: PropertyIdentifier::from("foo3").into(),
-                        pattern: BindingIdentifier::from("foo4").into(),
-                        init: literal::Boolean::from(false).into(),
-                        position: None,
-                    }.into(),
+                    (
+                        ObjectBindingPatternIdentifierProperty::from(
+                            BindingIdentifier::from("foo")
+                        ).into(),
+                        Default::default(),
+                    ),
+                    (
+                        ObjectBindingPatternIdentifierProperty {
+                            id: BindingIdentifier::from("foo2"),
+                            init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
+                            position: None,
+                            comments: None,
+                        }.into(),
+                        Default::default(),
+                    ),
                 ],
-                rest: Default::default(),
+                last_property: Some(ObjectBindingPatternPatternProperty {
+                    name: PropertyIdentifier::from("foo3").into(),
+                    pattern: NodeBox::new(BindingIdentifier::from("foo4").into()),
+                    init: Some(alias::Expression::from(literal::Boolean::from(false)).into()),
+                    position: None,
+                    comments: None,
+                }.into()),
                 position: None,
+                comments: None,
             },
             "{foo,foo2=true,foo3:foo4=false}"
         );
     }
 }
 node_enum!(@node_display pub enum ObjectBindingPatternProperty {
-    Identifier(ObjectBindingPatternIdentifierProperty),
-    Pattern(ObjectBindingPatternPatternProperty),
+    Identifier(ObjectBindingPatternIdentifierProperty<'src>),
+    Pattern(ObjectBindingPatternPatternProperty<'src>),
 });
 node_enum!(@node_display pub enum ObjectBindingPatternLastProperty {
-    Identifier(ObjectBindingPatternIdentifierProperty),
-    Pattern(ObjectBindingPatternPatternProperty),
-    Rest(ObjectBindingPatternRestProperty),
+    Identifier(ObjectBindingPatternIdentifierProperty<'src>),
+    Pattern(ObjectBindingPatternPatternProperty<'src>),
+    Rest(ObjectBindingPatternRestProperty<'src>),
 });
 
 
@@ -318,21 +343,21 @@ node!(pub struct ObjectBindingPatternRestProperty {
 
     // Object rest binding patterns are explicitly identifiers only since using
     // an array pattern wouldn't make sense, and an object pattern would be useless.
-    pub pattern: BindingIdentifier,
+    pub pattern: BindingIdentifier<'src>,
 });
-impl NodeDisplay for ObjectBindingPatternRestProperty {
+impl<'src> NodeDisplay for ObjectBindingPatternRestProperty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &self.token_ellipsis);
         f.node(&self.pattern)
     }
 }
 
 
 node!(pub struct ObjectBindingPatternIdentifierProperty {
-    pub id: BindingIdentifier,
-    pub init: Option<general::Initializer>,
+    pub id: BindingIdentifier<'src>,
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ObjectBindingPatternIdentifierProperty {
+impl<'src> NodeDisplay for ObjectBindingPatternIdentifierProperty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.id)?;
         f.node(&self.init)?;
@@ -340,26 +365,27 @@ impl NodeDisplay for ObjectBindingPatternIdentifierProperty {
         Ok(())
     }
 }
-impl<T: Into<BindingIdentifier>> From<T> for ObjectBindingPatternIdentifierProperty {
-    fn from(val: T) -> ObjectBindingPatternIdentifierProperty {
+impl<'src, T: Into<BindingIdentifier<'src>>> From<T> for ObjectBindingPatternIdentifierProperty<'src> {
+    fn from(val: T) -> ObjectBindingPatternIdentifierProperty<'src> {
         ObjectBindingPatternIdentifierProperty {
             id: val.into(),
             init: None,
             position: None,
+            comments: None,
         }
     }
 }
 
 
 node!(pub struct ObjectBindingPatternPatternProperty {
-    pub name: PropertyName,
-    pub pattern: Box<BindingPattern>,
-    pub init: Option<general::Initializer>,
+    pub name: PropertyName<'src>,
+    pub pattern: NodeBox<BindingPattern<'src>>,
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ObjectBindingPatternPatternProperty {
+impl<'src> NodeDisplay for ObjectBindingPatternPatternProperty<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.name)?;
-        f.punctuator(Punctuator::Colon);
+        f.punctuator(Punctuator::Colon, &());
         f.node(&self.pattern)?;
         f.node(&self.init)?;
 
@@ -370,10 +396,10 @@ impl NodeDisplay for ObjectBindingPatternPatternProperty {
 
 // [     ]
 node!(#[derive(Default)] pub struct ArrayBindingPattern {
-    pub items: Vec<Option<(ArrayBindingPatternElement, KeywordData)>>,
-    pub last_item: Option<ArrayBindingPatternLastElement>,
+    pub items: Vec<(Option<ArrayBindingPatternElement<'src>>, KeywordData)>,
+    pub last_item: Option<ArrayBindingPatternLastElement<'src>>,
 });
-impl NodeDisplay for ArrayBindingPattern {
+impl<'src> NodeDisplay for ArrayBindingPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_square();
 
@@ -384,6 +410,7 @@ impl NodeDisplay for ArrayBindingPattern {
 #[cfg(test)]
 mod tests_array_binding_pattern {
     use super::*;
+    use ast::alias;
     use ast::literal;
 
     #[test]
@@ -396,15 +423,21 @@ mod tests_array_binding_pattern {
         assert_serialize!(
             ArrayBindingPattern {
                 items: vec![
-                    ArrayBindingPatternElement::from(BindingIdentifier::from("foo")).into(),
+                    (
+                        Some(ArrayBindingPatternElement::from(BindingIdentifier::from("foo"))),
+                        Default::default(),
+                    ),
+                ],
+                last_item: Some(ArrayBindingPatternLastElement::Pattern(NodeBox::new(
                     ArrayBindingPatternElement {
                         id: BindingIdentifier::from("foo2").into(),
-                        init: literal::Boolean::from(true).into(),
+                        init: Some(alias::Expression::from(literal::Boolean::from(true)).into()),
                         position: None,
-                    }.into(),
-                ],
-                rest: Default::default(),
+                        comments: None,
+                    },
+                ))),
                 position: None,
+                comments: None,
             },
             "[foo,foo2=true]"
         );
@@ -413,28 +446,28 @@ mod tests_array_binding_pattern {
 
 
 node_enum!(@node_display pub enum ArrayBindingPatternLastElement {
-    Pattern(Box<ArrayBindingPatternElement>),
-    Rest(ArrayBindingRestElement),
+    Pattern(NodeBox<ArrayBindingPatternElement<'src>>),
+    Rest(ArrayBindingRestElement<'src>),
 });
 
 
 node!(pub struct ArrayBindingRestElement {
     pub token_ellipsis: KeywordData,
-    pub pattern: Box<BindingPattern>,
+    pub pattern: NodeBox<BindingPattern<'src>>,
 });
-impl NodeDisplay for ArrayBindingRestElement {
+impl<'src> NodeDisplay for ArrayBindingRestElement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.punctuator(Punctuator::Ellipsis);
+        f.punctuator(Punctuator::Ellipsis, &self.token_ellipsis);
         f.node(&self.pattern)
     }
 }
 
 
 node!(pub struct ArrayBindingPatternElement {
-    pub id: BindingPattern,
-    pub init: Option<general::Initializer>,
+    pub id: BindingPattern<'src>,
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ArrayBindingPatternElement {
+impl<'src> NodeDisplay for ArrayBindingPatternElement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.id)?;
         f.node(&self.init)?;
@@ -442,12 +475,13 @@ impl NodeDisplay for ArrayBindingPatternElement {
         Ok(())
     }
 }
-impl<T: Into<BindingPattern>> From<T> for ArrayBindingPatternElement {
-    fn from(val: T) -> ArrayBindingPatternElement {
+impl<'src, T: Into<BindingPattern<'src>>> From<T> for ArrayBindingPatternElement<'src> {
+    fn from(val: T) -> ArrayBindingPatternElement<'src> {
         ArrayBindingPatternElement {
             id: val.into(),
             init: None,
             position: None,
+            comments: None,
         }
     }
 }
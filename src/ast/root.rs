@@ -1,21 +1,19 @@
-use ast::MaybeTokenPosition;
-
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult};
 
 use ast::functions::Directive;
 use ast::alias;
 
 node_enum!(@node_display pub enum Ast {
-    Script(Script),
-    Module(Module),
+    Script(Script<'src>),
+    Module(Module<'src>),
 });
 
 
 node!(#[derive(Default)] pub struct Script {
-    pub directives: Vec<Directive>,
-    pub body: Vec<alias::StatementItem>,
+    pub directives: Vec<Directive<'src>>,
+    pub body: Vec<alias::StatementItem<'src>>,
 });
-impl NodeDisplay for Script {
+impl<'src> NodeDisplay for Script<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.allow_in();
         f.node_list(&self.directives)?;
@@ -41,6 +39,7 @@ mod tests_script {
                 directives: vec!["use strict".into()],
                 body: vec![ReferenceIdentifier::from("someVar").into()],
                 position: None,
+                comments: None,
             },
             "'use strict';someVar;"
         );
@@ -49,10 +48,10 @@ mod tests_script {
 
 
 node!(#[derive(Default)] pub struct Module {
-    pub directives: Vec<Directive>,
-    pub body: Vec<alias::ModuleStatementItem>,
+    pub directives: Vec<Directive<'src>>,
+    pub body: Vec<alias::ModuleStatementItem<'src>>,
 });
-impl NodeDisplay for Module {
+impl<'src> NodeDisplay for Module<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.allow_in();
         f.node_list(&self.directives)?;
@@ -82,6 +81,7 @@ mod tests_module {
                     ExportLocalBindings::default().into(),
                 ],
                 position: None,
+                comments: None,
             },
             "'use strict';someVar;export{};"
         );
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// A single generated-to-original mapping. Lines and columns are 0-indexed,
+/// and columns count UTF-16 code units, as Source Map v3 consumers expect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated_line: usize,
+    pub generated_column: usize,
+    pub source: usize,
+    pub original_line: usize,
+    pub original_column: usize,
+    pub name: Option<usize>,
+}
+
+/// A builder for Source Map v3 documents.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    pub file: Option<String>,
+    pub sources: Vec<String>,
+    pub sources_content: Vec<Option<String>>,
+    pub names: Vec<String>,
+    pub mappings: Vec<Mapping>,
+
+    name_ids: HashMap<String, usize>,
+}
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        Default::default()
+    }
+
+    /// Adds a source file, returning its index in `sources`.
+    pub fn add_source(&mut self, name: &str, content: Option<&str>) -> usize {
+        self.sources.push(name.into());
+        self.sources_content.push(content.map(Into::into));
+        self.sources.len() - 1
+    }
+
+    /// Adds a symbol name, returning its index in `names`.
+    pub fn add_name(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.name_ids.get(name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.into());
+        self.name_ids.insert(name.into(), id);
+        id
+    }
+
+    /// Adds a mapping. Mappings must be added in generated order.
+    pub fn add_mapping(&mut self, mapping: Mapping) {
+        debug_assert!(self.mappings.last().is_none_or(|last| {
+            (last.generated_line, last.generated_column) <= (mapping.generated_line, mapping.generated_column)
+        }));
+
+        self.mappings.push(mapping);
+    }
+
+    /// Encodes the mappings into the VLQ "mappings" string.
+    pub fn encode_mappings(&self) -> String {
+        let mut out = String::new();
+
+        let mut line = 0;
+        let mut column = 0;
+        let mut source = 0;
+        let mut original_line = 0;
+        let mut original_column = 0;
+        let mut name = 0;
+
+        for (i, mapping) in self.mappings.iter().enumerate() {
+            if mapping.generated_line != line {
+                while line < mapping.generated_line {
+                    out.push(';');
+                    line += 1;
+                }
+                column = 0;
+            } else if i > 0 {
+                out.push(',');
+            }
+
+            encode_vlq(&mut out, mapping.generated_column as i64 - column as i64);
+            encode_vlq(&mut out, mapping.source as i64 - source as i64);
+            encode_vlq(&mut out, mapping.original_line as i64 - original_line as i64);
+            encode_vlq(&mut out, mapping.original_column as i64 - original_column as i64);
+            if let Some(id) = mapping.name {
+                encode_vlq(&mut out, id as i64 - name as i64);
+                name = id;
+            }
+
+            column = mapping.generated_column;
+            source = mapping.source;
+            original_line = mapping.original_line;
+            original_column = mapping.original_column;
+        }
+
+        out
+    }
+
+    /// Serializes the source map as Source Map v3 JSON. `sourcesContent` is
+    /// only included if the content of at least one source was provided.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"version\":3");
+
+        if let Some(ref file) = self.file {
+            out += ",\"file\":";
+            json_string(&mut out, file);
+        }
+
+        out += ",\"sources\":[";
+        for (i, source) in self.sources.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            json_string(&mut out, source);
+        }
+        out.push(']');
+
+        if self.sources_content.iter().any(Option::is_some) {
+            out += ",\"sourcesContent\":[";
+            for (i, content) in self.sources_content.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                match *content {
+                    Some(ref content) => json_string(&mut out, content),
+                    None => out += "null",
+                }
+            }
+            out.push(']');
+        }
+
+        out += ",\"names\":[";
+        for (i, name) in self.names.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            json_string(&mut out, name);
+        }
+        out.push(']');
+
+        out += ",\"mappings\":\"";
+        out += &self.encode_mappings();
+        out += "\"}";
+
+        out
+    }
+}
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Appends a base64 VLQ, storing the sign in the lowest bit.
+fn encode_vlq(out: &mut String, value: i64) {
+    let mut vlq = if value < 0 { ((-value) << 1) | 1 } else { value << 1 };
+
+    loop {
+        let mut digit = vlq & 0b11111;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64[digit as usize] as char);
+
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+fn json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vlq(value: i64) -> String {
+        let mut out = String::new();
+        encode_vlq(&mut out, value);
+        out
+    }
+
+    #[test]
+    fn it_encodes_vlqs() {
+        assert_eq!(vlq(0), "A");
+        assert_eq!(vlq(1), "C");
+        assert_eq!(vlq(-1), "D");
+        assert_eq!(vlq(15), "e");
+        assert_eq!(vlq(16), "gB");
+        assert_eq!(vlq(-17), "jB");
+        assert_eq!(vlq(1000), "w+B");
+    }
+
+    #[test]
+    fn it_serializes_json() {
+        let mut map = SourceMap::new();
+        map.file = Some("out.js".into());
+        let source = map.add_source("in.js", Some("let a\n\"b\""));
+        let name = map.add_name("a");
+        assert_eq!(map.add_name("a"), name);
+
+        map.add_mapping(Mapping {
+            generated_line: 0,
+            generated_column: 0,
+            source,
+            original_line: 0,
+            original_column: 0,
+            name: None,
+        });
+        map.add_mapping(Mapping {
+            generated_line: 0,
+            generated_column: 4,
+            source,
+            original_line: 0,
+            original_column: 4,
+            name: Some(name),
+        });
+        map.add_mapping(Mapping {
+            generated_line: 2,
+            generated_column: 2,
+            source,
+            original_line: 1,
+            original_column: 0,
+            name: None,
+        });
+
+        assert_eq!(
+            map.to_json(),
+            "{\"version\":3,\"file\":\"out.js\",\"sources\":[\"in.js\"],\
+             \"sourcesContent\":[\"let a\\n\\\"b\\\"\"],\"names\":[\"a\"],\
+             \"mappings\":\"AAAA,IAAIA;;EACJ\"}"
+        );
+    }
+}
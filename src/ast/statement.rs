@@ -1,6 +1,4 @@
-use std::string;
-
-use ast::{MaybeTokenPosition, KeywordData, KeywordWrappedData, SeparatorTokens};
+use ast::{KeywordData, KeywordWrappedData, SeparatorTokens, Str, NodeBox};
 
 use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punctuator, Precedence,
                    LookaheadRestriction};
@@ -8,16 +6,25 @@ use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, Keyword, Punct
 use ast::patterns::{LeftHandComplexAssign, BindingPattern};
 use ast::general;
 use ast::alias;
+use ast::typescript::TypeAnnotation;
 
 
 
 // { ... }
 node!(#[derive(Default)] pub struct BlockStatement {
     pub token_curly_l: KeywordData,
-    pub body: Vec<alias::StatementItem>,
+    pub body: Vec<alias::StatementItem<'src>>,
     pub token_curly_r: KeywordData,
 });
-impl NodeDisplay for BlockStatement {
+impl<'src> From<Vec<alias::StatementItem<'src>>> for BlockStatement<'src> {
+    fn from(body: Vec<alias::StatementItem>) -> BlockStatement {
+        BlockStatement {
+            body,
+            ..Default::default()
+        }
+    }
+}
+impl<'src> NodeDisplay for BlockStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_curly();
 
@@ -36,8 +43,11 @@ mod tests_block {
     fn it_prints() {
         assert_serialize!(
             BlockStatement {
+                token_curly_l: Default::default(),
+                token_curly_r: Default::default(),
                 body: Default::default(),
                 position: None,
+                comments: None,
             },
             "{}"
         );
@@ -47,10 +57,13 @@ mod tests_block {
     fn it_prints_with_items() {
         assert_serialize!(
             BlockStatement {
+                token_curly_l: Default::default(),
+                token_curly_r: Default::default(),
                 body: vec![
                     ExpressionStatement::new(ReferenceIdentifier::new("someWord")).into(),
                 ],
                 position: None,
+                comments: None,
             },
             "{someWord;}"
         );
@@ -61,27 +74,35 @@ mod tests_block {
 // var foo, bar;
 node!(pub struct VariableStatement {
     pub token_var: KeywordData,
-    pub declarators: Vec<(VariableDeclarator, KeywordData)>,
-    pub last_declarator: VariableDeclarator,
+    pub declarators: Vec<(VariableDeclarator<'src>, KeywordData)>,
+    pub last_declarator: VariableDeclarator<'src>,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for VariableStatement {
+impl<'src> NodeDisplay for VariableStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Var, &self.token_var);
         f.comma_list(&self.declarators)?;
         f.node(&self.last_declarator)?;
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
 
 node!(pub struct VariableDeclarator {
-    pub id: BindingPattern,
-    pub init: Option<general::Initializer>,
+    pub id: BindingPattern<'src>,
+    pub definite: bool, // experimental: TypeScript
+    pub type_annotation: Option<TypeAnnotation<'src>>, // experimental: TypeScript
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for VariableDeclarator {
+impl<'src> NodeDisplay for VariableDeclarator<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.id)?;
+        if self.definite && !f.strip_types() {
+            f.punctuator(Punctuator::Exclam, &());
+        }
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
         f.node(&self.init)
     }
 }
@@ -95,12 +116,19 @@ mod tests_var {
     fn it_prints() {
         assert_serialize!(
             VariableStatement {
-                declarations: DeclaratorList::Last(VariableDeclarator {
+                token_var: Default::default(),
+                declarators: vec![],
+                last_declarator: VariableDeclarator {
                     id: BindingIdentifier::from("myVar").into(),
+                    definite: false,
+                    type_annotation: None,
                     init: None,
                     position: None,
-                }),
+                    comments: None,
+                },
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "var myVar;"
         );
@@ -110,12 +138,19 @@ mod tests_var {
     fn it_prints_with_init() {
         assert_serialize!(
             VariableStatement {
-                declarations: DeclaratorList::Last(VariableDeclarator {
+                token_var: Default::default(),
+                declarators: vec![],
+                last_declarator: VariableDeclarator {
                     id: BindingIdentifier::from("myVar").into(),
-                    init: ReferenceIdentifier::from("initialVal").into(),
+                    definite: false,
+                    type_annotation: None,
+                    init: Some(alias::Expression::from(ReferenceIdentifier::from("initialVal")).into()),
                     position: None,
-                }),
+                    comments: None,
+                },
+                token_semi: Default::default(),
                 position: None,
+                comments: None,
             },
             "var myVar=initialVal;"
         );
@@ -145,28 +180,36 @@ mod tests_var {
 // let foo, bar;
 node!(pub struct LetDeclaration {
     pub token_let: KeywordData,
-    pub declarators: Vec<(LetDeclarator, KeywordData)>,
-    pub last_declarator: LetDeclarator,
+    pub declarators: Vec<(LetDeclarator<'src>, KeywordData)>,
+    pub last_declarator: LetDeclarator<'src>,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for LetDeclaration {
+impl<'src> NodeDisplay for LetDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Let, &self.token_let);
         f.comma_list(&self.declarators)?;
         f.node(&self.last_declarator)?;
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
 
 
 node!(pub struct LetDeclarator {
-    pub id: BindingPattern,
-    pub init: Option<general::Initializer>,
+    pub id: BindingPattern<'src>,
+    pub definite: bool, // experimental: TypeScript
+    pub type_annotation: Option<TypeAnnotation<'src>>, // experimental: TypeScript
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for LetDeclarator {
+impl<'src> NodeDisplay for LetDeclarator<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.id)?;
+        if self.definite && !f.strip_types() {
+            f.punctuator(Punctuator::Exclam, &());
+        }
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
         f.node(&self.init)
     }
 }
@@ -175,59 +218,68 @@ impl NodeDisplay for LetDeclarator {
 // const foo = 4, bar = 5;
 node!(pub struct ConstDeclaration {
     pub token_const: KeywordData,
-    pub declarators: Vec<(ConstDeclarator, KeywordData)>,
-    pub last_declarator: ConstDeclarator,
+    pub declarators: Vec<(ConstDeclarator<'src>, KeywordData)>,
+    pub last_declarator: ConstDeclarator<'src>,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for ConstDeclaration {
+impl<'src> NodeDisplay for ConstDeclaration<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Const, &self.token_const);
         f.comma_list(&self.declarators)?;
         f.node(&self.last_declarator)?;
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
 
 
 node!(pub struct ConstDeclarator {
-    pub id: BindingPattern,
-    pub init: general::Initializer,
+    pub id: BindingPattern<'src>,
+    pub type_annotation: Option<TypeAnnotation<'src>>, // experimental: TypeScript
+    pub init: general::Initializer<'src>,
 });
-impl NodeDisplay for ConstDeclarator {
+impl<'src> NodeDisplay for ConstDeclarator<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.node(&self.id)?;
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
         f.node(&self.init)
     }
 }
 
 // foo;
 node!(pub struct ExpressionStatement {
-    pub token_prefix: SeparatorTokens,
-    pub expression: alias::Expression,
+    pub token_prefix: SeparatorTokens<'src>,
+    pub expression: alias::Expression<'src>,
     pub token_semi: KeywordData,
 });
-impl ExpressionStatement {
-    pub fn new<T: Into<alias::Expression>>(expr: T) -> ExpressionStatement {
+impl<'src> ExpressionStatement<'src> {
+    pub fn new<T: Into<alias::Expression<'src>>>(expr: T) -> ExpressionStatement<'src> {
         ExpressionStatement {
             token_prefix: Default::default(),
             expression: expr.into(),
             token_semi: Default::default(),
 
             position: None,
+
+            comments: None,
         }
     }
 }
-impl NodeDisplay for ExpressionStatement {
+impl<'src> NodeDisplay for ExpressionStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        {
+        // A string on its own would be read back as a directive.
+        if let alias::Expression::String(_) = self.expression {
+            f.wrap_parens().node(&self.expression)?;
+        } else {
             let mut f = f.restrict_lookahead(LookaheadRestriction::ExpressionStatement);
             f.require_precedence(Precedence::Normal).node(
                 &self.expression,
             )?;
         }
 
-        f.punctuator(Punctuator::Semicolon);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -252,8 +304,11 @@ mod tests_expression_statement {
     fn it_prints() {
         assert_serialize!(
             ExpressionStatement {
+                token_prefix: Default::default(),
+                token_semi: Default::default(),
                 expression: ReferenceIdentifier::from("foo").into(),
                 position: None,
+                comments: None,
             },
             "foo;"
         );
@@ -263,8 +318,11 @@ mod tests_expression_statement {
     fn it_prints_with_function_parens() {
         assert_serialize!(
             ExpressionStatement {
+                token_prefix: Default::default(),
+                token_semi: Default::default(),
                 expression: functions::FunctionExpression::default().into(),
                 position: None,
+                comments: None,
             },
             "(function(){});"
         );
@@ -274,8 +332,11 @@ mod tests_expression_statement {
     fn it_prints_with_class_parens() {
         assert_serialize!(
             ExpressionStatement {
+                token_prefix: Default::default(),
+                token_semi: Default::default(),
                 expression: classes::ClassExpression::default().into(),
                 position: None,
+                comments: None,
             },
             "(class{});"
         );
@@ -285,8 +346,11 @@ mod tests_expression_statement {
     fn it_prints_with_object_expression_parens() {
         assert_serialize!(
             ExpressionStatement {
+                token_prefix: Default::default(),
+                token_semi: Default::default(),
                 expression: objects::ObjectExpression::default().into(),
                 position: None,
+                comments: None,
             },
             "({});"
         );
@@ -296,12 +360,17 @@ mod tests_expression_statement {
     fn it_prints_with_object_pattern_parens() {
         assert_serialize!(
             ExpressionStatement {
+                token_prefix: Default::default(),
+                token_semi: Default::default(),
                 expression: expression::AssignmentExpression {
+                    token_eq: Default::default(),
                     left: patterns::ObjectAssignmentPattern::default().into(),
                     right: ReferenceIdentifier::from("foo").into(),
                     position: None,
+                    comments: None,
                 }.into(),
                 position: None,
+                comments: None,
             },
             "({}=foo);"
         );
@@ -311,18 +380,25 @@ mod tests_expression_statement {
     fn it_prints_with_letsquare_parens() {
         assert_serialize!(
             ExpressionStatement {
+                token_prefix: Default::default(),
+                token_semi: Default::default(),
                 expression: expression::MemberExpression {
                     object: ReferenceIdentifier::from("let").into(),
                     property: expression::PropertyAccess::Computed(
                         expression::ComputedPropertyAccess {
-                            optional: false,
+                            tokens_prefix: Default::default(),
+                            token_square_l: Default::default(),
+                            token_square_r: Default::default(),
                             expression: literal::Boolean::from(false).into(),
                             position: None,
+                            comments: None,
                         },
                     ),
                     position: None,
+                    comments: None,
                 }.into(),
                 position: None,
+                comments: None,
             },
             "(let[false]);"
         );
@@ -334,11 +410,11 @@ mod tests_expression_statement {
 node!(pub struct IfStatement {
     pub token_if: KeywordData,
     pub token_paren_l: KeywordData,
-    pub test: alias::Expression,
+    pub test: alias::Expression<'src>,
     pub token_paren_r: KeywordData,
-    pub consequent: Box<alias::Statement>,
+    pub consequent: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for IfStatement {
+impl<'src> NodeDisplay for IfStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.wrap_orphan_if();
         f.keyword(Keyword::If, &self.token_if);
@@ -351,13 +427,13 @@ impl NodeDisplay for IfStatement {
 node!(pub struct IfElseStatement {
     pub token_if: KeywordData,
     pub token_paren_l: KeywordData,
-    pub test: alias::Expression,
+    pub test: alias::Expression<'src>,
     pub token_paren_r: KeywordData,
-    pub consequent: Box<alias::Statement>,
+    pub consequent: NodeBox<alias::Statement<'src>>,
     pub token_else: KeywordData,
-    pub alternate: Box<alias::Statement>,
+    pub alternate: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for IfElseStatement {
+impl<'src> NodeDisplay for IfElseStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::If, &self.token_if);
         f.wrap_parens().node(&self.test)?;
@@ -372,15 +448,36 @@ mod tests_if {
     use super::*;
     use ast::general::ReferenceIdentifier;
 
+    fn if_stmt<'src>(test: &str, consequent: alias::Statement<'src>) -> IfStatement<'src> {
+        IfStatement {
+            token_if: Default::default(),
+            token_paren_l: Default::default(),
+            test: ReferenceIdentifier::from(test).into(),
+            token_paren_r: Default::default(),
+            consequent: consequent.into(),
+            position: None,
+            comments: None,
+        }
+    }
+
+    fn if_else_stmt<'src>(test: &str, consequent: alias::Statement<'src>) -> IfElseStatement<'src> {
+        IfElseStatement {
+            token_if: Default::default(),
+            token_paren_l: Default::default(),
+            test: ReferenceIdentifier::from(test).into(),
+            token_paren_r: Default::default(),
+            consequent: consequent.into(),
+            token_else: Default::default(),
+            alternate: alias::Statement::from(EmptyStatement::default()).into(),
+            position: None,
+            comments: None,
+        }
+    }
+
     #[test]
     fn it_prints() {
         assert_serialize!(
-            IfStatement {
-                test: ReferenceIdentifier::from("myVar").into(),
-                consequent: EmptyStatement::default().into(),
-                alternate: None,
-                position: None,
-            },
+            if_stmt("myVar", EmptyStatement::default().into()),
             "if(myVar);"
         );
     }
@@ -388,12 +485,7 @@ mod tests_if {
     #[test]
     fn it_prints_else() {
         assert_serialize!(
-            IfStatement {
-                test: ReferenceIdentifier::from("myVar").into(),
-                consequent: EmptyStatement::default().into(),
-                alternate: EmptyStatement::default().into(),
-                position: None,
-            },
+            if_else_stmt("myVar", EmptyStatement::default().into()),
             "if(myVar);else;"
         );
     }
@@ -401,38 +493,30 @@ mod tests_if {
     #[test]
     fn it_prints_wrapped_if() {
         assert_serialize!(
-            IfStatement {
-                test: ReferenceIdentifier::from("myVar").into(),
-                consequent: IfStatement {
-                    test: ReferenceIdentifier::from("myVar2").into(),
-                    consequent: EmptyStatement::default().into(),
-                    alternate: None,
-                    position: None,
-                }.into(),
-                alternate: EmptyStatement::default().into(),
-                position: None,
-            },
+            if_else_stmt(
+                "myVar",
+                if_stmt("myVar2", EmptyStatement::default().into()).into(),
+            ),
             "if(myVar){if(myVar2);}else;"
         );
     }
     #[test]
     fn it_prints_wrapped_if_deep() {
         assert_serialize!(
-            IfStatement {
-                test: ReferenceIdentifier::from("myVar").into(),
-                consequent: WhileStatement {
+            if_else_stmt(
+                "myVar",
+                WhileStatement {
+                    token_while: Default::default(),
+                    token_paren_l: Default::default(),
+                    token_paren_r: Default::default(),
                     test: ReferenceIdentifier::from("myVar2").into(),
-                    body: IfStatement {
-                        test: ReferenceIdentifier::from("myVar3").into(),
-                        consequent: EmptyStatement::default().into(),
-                        alternate: None,
-                        position: None,
-                    }.into(),
+                    body: alias::Statement::from(
+                        if_stmt("myVar3", EmptyStatement::default().into()),
+                    ).into(),
                     position: None,
+                    comments: None,
                 }.into(),
-                alternate: EmptyStatement::default().into(),
-                position: None,
-            },
+            ),
             "if(myVar)while(myVar2){if(myVar3);}else;"
         );
     }
@@ -443,15 +527,15 @@ mod tests_if {
 node!(#[derive(Default)] pub struct ForStatement {
     pub token_for: KeywordData,
     pub token_paren_l: KeywordData,
-    pub init: Option<ForInit>,
+    pub init: Option<ForInit<'src>>,
     pub token_init_semi: KeywordWrappedData,
-    pub test: Option<alias::Expression>,
+    pub test: Option<alias::Expression<'src>>,
     pub token_test_semi: KeywordWrappedData,
-    pub update: Option<alias::Expression>,
+    pub update: Option<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for ForStatement {
+impl<'src> NodeDisplay for ForStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::For, &self.token_for);
         {
@@ -468,20 +552,34 @@ impl NodeDisplay for ForStatement {
 
 
 node_enum!(pub enum ForInit {
-    Var(VariableStatement),
-    Let(LetDeclaration),
-    Const(ConstDeclaration),
-    Expression(alias::Expression),
+    Var(VariableStatement<'src>),
+    Let(LetDeclaration<'src>),
+    Const(ConstDeclaration<'src>),
+    Expression(alias::Expression<'src>),
 });
-impl NodeDisplay for ForInit {
+impl<'src> NodeDisplay for ForInit<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         let mut f = f.restrict_lookahead(LookaheadRestriction::ForInit);
         let mut f = f.disallow_in();
 
+        // The declarations are printed without their own semicolon since
+        // the loop prints the separator itself.
         match *self {
-            ForInit::Var(ref n) => f.node(n),
-            ForInit::Let(ref n) => f.node(n),
-            ForInit::Const(ref n) => f.node(n),
+            ForInit::Var(ref n) => {
+                f.keyword(Keyword::Var, &n.token_var);
+                f.comma_list(&n.declarators)?;
+                f.node(&n.last_declarator)
+            }
+            ForInit::Let(ref n) => {
+                f.keyword(Keyword::Let, &n.token_let);
+                f.comma_list(&n.declarators)?;
+                f.node(&n.last_declarator)
+            }
+            ForInit::Const(ref n) => {
+                f.keyword(Keyword::Const, &n.token_const);
+                f.comma_list(&n.declarators)?;
+                f.node(&n.last_declarator)
+            }
             ForInit::Expression(ref n) => f.node(n),
         }
     }
@@ -502,11 +600,17 @@ mod tests_for {
     fn it_prints_test() {
         assert_serialize!(
             ForStatement {
+                token_for: Default::default(),
+                token_paren_l: Default::default(),
+                token_init_semi: Default::default(),
+                token_test_semi: Default::default(),
+                token_paren_r: Default::default(),
                 init: None,
                 test: ReferenceIdentifier::from("myVar").into(),
                 update: None,
                 body: EmptyStatement::default().into(),
                 position: None,
+                comments: None,
             },
             "for(;myVar;);"
         );
@@ -516,17 +620,26 @@ mod tests_for {
     fn it_prints_complex() {
         assert_serialize!(
             ForStatement {
+                token_for: Default::default(),
+                token_paren_l: Default::default(),
+                token_init_semi: Default::default(),
+                token_test_semi: Default::default(),
+                token_paren_r: Default::default(),
                 init: alias::Expression::from(ReferenceIdentifier::from("init")).into(),
                 test: ReferenceIdentifier::from("test").into(),
                 update: ReferenceIdentifier::from("update").into(),
                 body: BlockStatement {
+                    token_curly_l: Default::default(),
+                    token_curly_r: Default::default(),
                     body: vec![
                         literal::Boolean::from(true).into(),
                         literal::Numeric::from(4.5).into(),
                     ],
                     position: None,
+                    comments: None,
                 }.into(),
                 position: None,
+                comments: None,
             },
             "for(init;test;update){true;4.5;}"
         );
@@ -538,13 +651,13 @@ mod tests_for {
 node!(pub struct ForInStatement {
     pub token_for: KeywordData,
     pub token_paren_l: KeywordData,
-    pub left: ForInInit,
+    pub left: ForInInit<'src>,
     pub token_in: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for ForInStatement {
+impl<'src> NodeDisplay for ForInStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::For, &self.token_for);
         {
@@ -563,16 +676,16 @@ impl NodeDisplay for ForInStatement {
 
 node!(pub struct ForInVarPattern {
     pub token_var: KeywordData,
-    pub pattern: BindingPattern,
+    pub pattern: BindingPattern<'src>,
     // TODO: Technically this default init is only allowed if the pattern is an identifier,
     // Should this change to a special pattern type? Annex B feature.
-    pub init: Option<general::Initializer>,
+    pub init: Option<general::Initializer<'src>>,
 });
-impl NodeDisplay for ForInVarPattern {
+impl<'src> NodeDisplay for ForInVarPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Var, &self.token_var);
         f.node(&self.pattern)?;
-        f.node(&self.init)?;
+        f.disallow_in().node(&self.init)?;
 
         Ok(())
     }
@@ -581,9 +694,9 @@ impl NodeDisplay for ForInVarPattern {
 
 node!(pub struct ForVarPattern {
     pub token_var: KeywordData,
-    pub pattern: BindingPattern,
+    pub pattern: BindingPattern<'src>,
 });
-impl NodeDisplay for ForVarPattern {
+impl<'src> NodeDisplay for ForVarPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Var, &self.token_var);
         f.node(&self.pattern)
@@ -593,9 +706,9 @@ impl NodeDisplay for ForVarPattern {
 
 node!(pub struct ForLetPattern {
     pub token_let: KeywordData,
-    pub pattern: BindingPattern,
+    pub pattern: BindingPattern<'src>,
 });
-impl NodeDisplay for ForLetPattern {
+impl<'src> NodeDisplay for ForLetPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Let, &self.token_let);
         f.node(&self.pattern)
@@ -605,9 +718,9 @@ impl NodeDisplay for ForLetPattern {
 
 node!(pub struct ForConstPattern {
     pub token_const: KeywordData,
-    pub pattern: BindingPattern,
+    pub pattern: BindingPattern<'src>,
 });
-impl NodeDisplay for ForConstPattern {
+impl<'src> NodeDisplay for ForConstPattern<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Const, &self.token_const);
         f.node(&self.pattern)
@@ -616,10 +729,10 @@ impl NodeDisplay for ForConstPattern {
 
 
 node_enum!(@node_display pub enum ForInInit {
-    Var(ForInVarPattern),
-    Let(ForLetPattern),
-    Const(ForConstPattern),
-    Complex(LeftHandComplexAssign),
+    Var(ForInVarPattern<'src>),
+    Let(ForLetPattern<'src>),
+    Const(ForConstPattern<'src>),
+    Complex(LeftHandComplexAssign<'src>),
 });
 
 
@@ -627,13 +740,13 @@ node_enum!(@node_display pub enum ForInInit {
 node!(pub struct ForOfStatement {
     pub token_for: KeywordData,
     pub token_paren_l: KeywordData,
-    pub left: ForOfInit,
+    pub left: ForOfInit<'src>,
     pub token_of: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for ForOfStatement {
+impl<'src> NodeDisplay for ForOfStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::For, &self.token_for);
         {
@@ -655,13 +768,13 @@ node!(pub struct ForAwaitStatement {
     pub token_for: KeywordData,
     pub token_await: KeywordData,
     pub token_paren_l: KeywordData,
-    pub left: ForOfInit,
+    pub left: ForOfInit<'src>,
     pub token_of: KeywordWrappedData,
-    pub right: Box<alias::Expression>,
+    pub right: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for ForAwaitStatement {
+impl<'src> NodeDisplay for ForAwaitStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::For, &self.token_for);
         f.keyword(Keyword::Await, &self.token_await);
@@ -679,10 +792,10 @@ impl NodeDisplay for ForAwaitStatement {
 }
 
 node_enum!(@node_display pub enum ForOfInit {
-    Var(ForVarPattern),
-    Let(ForLetPattern),
-    Const(ForConstPattern),
-    Complex(LeftHandComplexAssign),
+    Var(ForVarPattern<'src>),
+    Let(ForLetPattern<'src>),
+    Const(ForConstPattern<'src>),
+    Complex(LeftHandComplexAssign<'src>),
 });
 
 
@@ -690,11 +803,11 @@ node_enum!(@node_display pub enum ForOfInit {
 node!(pub struct WhileStatement {
     pub token_while: KeywordData,
     pub token_paren_l: KeywordData,
-    pub test: Box<alias::Expression>,
+    pub test: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for WhileStatement {
+impl<'src> NodeDisplay for WhileStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::While, &self.token_while);
         f.wrap_parens().node(&self.test)?;
@@ -706,22 +819,22 @@ impl NodeDisplay for WhileStatement {
 // do ; while(...) ;
 node!(pub struct DoWhileStatement {
     pub token_do: KeywordData,
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 
     pub token_while: KeywordData,
     pub token_paren_l: KeywordData,
-    pub test: Box<alias::Expression>,
+    pub test: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for DoWhileStatement {
+impl<'src> NodeDisplay for DoWhileStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Do, &self.token_do);
 
         f.node(&self.body)?;
         f.keyword(Keyword::While, &self.token_while);
         f.wrap_parens().node(&self.test)?;
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -731,13 +844,13 @@ impl NodeDisplay for DoWhileStatement {
 node!(pub struct SwitchStatement {
     pub token_switch: KeywordData,
     pub token_paren_l: KeywordData,
-    pub discriminant: Box<alias::Expression>,
+    pub discriminant: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
     pub token_curly_l: KeywordData,
-    pub cases: Vec<SwitchClause>,
+    pub cases: Vec<SwitchClause<'src>>,
     pub token_curly_r: KeywordData,
 });
-impl NodeDisplay for SwitchStatement {
+impl<'src> NodeDisplay for SwitchStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Switch, &self.token_switch);
         f.wrap_parens().node(&self.discriminant)?;
@@ -758,9 +871,15 @@ mod tests_switch {
     fn it_prints_empty() {
         assert_serialize!(
             SwitchStatement {
+                token_switch: Default::default(),
+                token_paren_l: Default::default(),
+                token_paren_r: Default::default(),
+                token_curly_l: Default::default(),
+                token_curly_r: Default::default(),
                 discriminant: ReferenceIdentifier::from("myVar").into(),
                 cases: vec![],
                 position: None,
+                comments: None,
             },
             "switch(myVar){}"
         );
@@ -770,25 +889,39 @@ mod tests_switch {
     fn it_prints_cases() {
         assert_serialize!(
             SwitchStatement {
+                token_switch: Default::default(),
+                token_paren_l: Default::default(),
+                token_paren_r: Default::default(),
+                token_curly_l: Default::default(),
+                token_curly_r: Default::default(),
                 discriminant: ReferenceIdentifier::from("myVar").into(),
                 cases: vec![
                     SwitchCase {
+                        token_case: Default::default(),
+                        token_colon: Default::default(),
                         test: literal::Numeric::from(6.2).into(),
                         consequent: vec![literal::Boolean::from(false).into()],
                         position: None,
-                    },
-                    SwitchCase {
-                        test: None,
+                        comments: None,
+                    }.into(),
+                    SwitchDefault {
+                        token_default: Default::default(),
+                        token_colon: Default::default(),
                         consequent: vec![literal::Boolean::from(true).into()],
                         position: None,
-                    },
+                        comments: None,
+                    }.into(),
                     SwitchCase {
+                        token_case: Default::default(),
+                        token_colon: Default::default(),
                         test: literal::Numeric::from(1.32).into(),
                         consequent: vec![literal::Boolean::from(true).into()],
                         position: None,
-                    },
+                        comments: None,
+                    }.into(),
                 ],
                 position: None,
+                comments: None,
             },
             "switch(myVar){case 6.2:false;default:true;case 1.32:true;}"
         );
@@ -796,25 +929,25 @@ mod tests_switch {
 }
 
 node_enum!(@node_display pub enum SwitchClause {
-    Case(SwitchCase),
-    Default(SwitchDefault),
+    Case(SwitchCase<'src>),
+    Default(SwitchDefault<'src>),
 });
 
 // case foo:
 // default:
-node!(#[derive(Default)] pub struct SwitchCase {
+node!(pub struct SwitchCase {
     pub token_case: KeywordData,
-    pub test: Box<alias::Expression>,
+    pub test: NodeBox<alias::Expression<'src>>,
     pub token_colon: KeywordData,
-    pub consequent: Vec<alias::StatementItem>,
+    pub consequent: Vec<alias::StatementItem<'src>>,
 });
-impl NodeDisplay for SwitchCase {
+impl<'src> NodeDisplay for SwitchCase<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Case, &self.token_case);
         f.require_precedence(Precedence::Normal).node(&self.test)?;
         f.punctuator(Punctuator::Colon, &self.token_colon);
 
-        f.node_list(&self.consequent)?;
+        f.indent().node_list(&self.consequent)?;
 
         Ok(())
     }
@@ -825,14 +958,14 @@ impl NodeDisplay for SwitchCase {
 node!(#[derive(Default)] pub struct SwitchDefault {
     pub token_default: KeywordData,
     pub token_colon: KeywordData,
-    pub consequent: Vec<alias::StatementItem>,
+    pub consequent: Vec<alias::StatementItem<'src>>,
 });
-impl NodeDisplay for SwitchDefault {
+impl<'src> NodeDisplay for SwitchDefault<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Default, &self.token_default);
         f.punctuator(Punctuator::Colon, &self.token_colon);
 
-        f.node_list(&self.consequent)?;
+        f.indent().node_list(&self.consequent)?;
 
         Ok(())
     }
@@ -843,11 +976,11 @@ impl NodeDisplay for SwitchDefault {
 node!(pub struct WithStatement {
     pub token_with: KeywordData,
     pub token_paren_l: KeywordData,
-    pub object: Box<alias::Expression>,
+    pub object: NodeBox<alias::Expression<'src>>,
     pub token_paren_r: KeywordData,
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for WithStatement {
+impl<'src> NodeDisplay for WithStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::With, &self.token_with);
         f.wrap_parens().node(&self.object)?;
@@ -858,35 +991,34 @@ impl NodeDisplay for WithStatement {
 
 // identifiers used as labels
 node!(pub struct LabelIdentifier {
-    pub value: string::String,
-    pub raw: Option<string::String>,
+    pub value: Str<'src>,
+    pub raw: Option<Str<'src>>,
 });
-impl LabelIdentifier {
-    pub fn new<T: Into<string::String>>(s: T) -> LabelIdentifier {
+impl<'src> LabelIdentifier<'src> {
+    pub fn new<T: Into<Str<'src>>>(s: T) -> LabelIdentifier<'src> {
         LabelIdentifier {
             value: s.into(),
             raw: None,
             position: None,
+            comments: None,
         }
     }
 }
-impl NodeDisplay for LabelIdentifier {
+impl<'src> NodeDisplay for LabelIdentifier<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
-        f.identifier(&self.value, self.raw.as_ref().map(String::as_str))
+        f.identifier(&self.value, self.raw.as_deref())
     }
 }
 
 
 // foo: while(false) ;
 node!(pub struct LabelledStatement {
-    pub tokens_prefix: SeparatorTokens,
-    pub label: LabelIdentifier,
+    pub tokens_prefix: SeparatorTokens<'src>,
+    pub label: LabelIdentifier<'src>,
     pub token_colon: KeywordData,
-
-    // TODO: Annex B technically allows function declarations here, do we care?
-    pub body: Box<alias::Statement>,
+    pub body: NodeBox<alias::Statement<'src>>,
 });
-impl NodeDisplay for LabelledStatement {
+impl<'src> NodeDisplay for LabelledStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // f.separators(&self.tokens_prefix);
         f.node(&self.label)?;
@@ -899,12 +1031,14 @@ impl NodeDisplay for LabelledStatement {
 // throw foo;
 node!(pub struct ThrowStatement {
     pub token_throw: KeywordData,
-    pub argument: Box<alias::Expression>,
+    pub argument: NodeBox<alias::Expression<'src>>,
+    pub token_semi: KeywordData,
 });
-impl NodeDisplay for ThrowStatement {
+impl<'src> NodeDisplay for ThrowStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Throw, &self.token_throw);
         f.require_precedence(Precedence::Normal).node(&self.argument)?;
+        f.semicolon(&self.token_semi);
 
         Ok(())
     }
@@ -914,10 +1048,10 @@ impl NodeDisplay for ThrowStatement {
 // try {} catch(foo) {}
 node!(#[derive(Default)] pub struct TryCatchStatement {
     pub token_try: KeywordData,
-    pub body: BlockStatement,
-    pub catch: CatchClause,
+    pub body: BlockStatement<'src>,
+    pub catch: CatchClause<'src>,
 });
-impl NodeDisplay for TryCatchStatement {
+impl<'src> NodeDisplay for TryCatchStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Try, &self.token_try);
         f.node(&self.body)?;
@@ -940,13 +1074,24 @@ mod tests_try_catch {
     fn it_prints_with_binding() {
         assert_serialize!(
             TryCatchStatement {
+                token_try: Default::default(),
                 body: vec![literal::Boolean::from(false).into()].into(),
                 catch: CatchClause {
-                    param: BindingIdentifier::from("err").into(),
+                    token_catch: Default::default(),
+                    param: Some(CatchParam {
+                        token_paren_l: Default::default(),
+                        argument: BindingIdentifier::from("err").into(),
+                        type_annotation: None,
+                        token_paren_r: Default::default(),
+                        position: None,
+                        comments: None,
+                    }),
                     body: vec![literal::Boolean::from(true).into()].into(),
                     position: None,
+                    comments: None,
                 },
                 position: None,
+                comments: None,
             },
             "try{false;}catch(err){true;}"
         );
@@ -957,12 +1102,12 @@ mod tests_try_catch {
 // try {} catch(foo) {} finally {}
 node!(#[derive(Default)] pub struct TryCatchFinallyStatement {
     pub token_try: KeywordData,
-    pub body: BlockStatement,
-    pub catch: CatchClause,
+    pub body: BlockStatement<'src>,
+    pub catch: CatchClause<'src>,
     pub token_finally: KeywordData,
-    pub finalizer: BlockStatement,
+    pub finalizer: BlockStatement<'src>,
 });
-impl NodeDisplay for TryCatchFinallyStatement {
+impl<'src> NodeDisplay for TryCatchFinallyStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Try, &self.token_try);
         f.node(&self.body)?;
@@ -987,11 +1132,11 @@ mod tests_try_catch_finally {
 // try {} finally {}
 node!(#[derive(Default)] pub struct TryFinallyStatement {
     pub token_try: KeywordData,
-    pub body: BlockStatement,
+    pub body: BlockStatement<'src>,
     pub token_finally: KeywordData,
-    pub finalizer: BlockStatement,
+    pub finalizer: BlockStatement<'src>,
 });
-impl NodeDisplay for TryFinallyStatement {
+impl<'src> NodeDisplay for TryFinallyStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Try, &self.token_try);
         f.node(&self.body)?;
@@ -1005,10 +1150,10 @@ impl NodeDisplay for TryFinallyStatement {
 node!(#[derive(Default)] pub struct CatchClause {
     pub token_catch: KeywordData,
     // Missing param is experimental
-    pub param: Option<CatchParam>,
-    pub body: BlockStatement,
+    pub param: Option<CatchParam<'src>>,
+    pub body: BlockStatement<'src>,
 });
-impl NodeDisplay for CatchClause {
+impl<'src> NodeDisplay for CatchClause<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Catch, &self.token_catch);
         f.node(&self.param)?;
@@ -1018,13 +1163,18 @@ impl NodeDisplay for CatchClause {
 
 node!(pub struct CatchParam {
     pub token_paren_l: KeywordData,
-    pub argument: BindingPattern,
+    pub argument: BindingPattern<'src>,
+    pub type_annotation: Option<TypeAnnotation<'src>>, // experimental: TypeScript
     pub token_paren_r: KeywordData,
 });
-impl NodeDisplay for CatchParam {
+impl<'src> NodeDisplay for CatchParam<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // TODO paren tokens
-        f.wrap_parens().node(&self.argument)?;
+        let mut f = f.wrap_parens();
+        f.node(&self.argument)?;
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
         Ok(())
     }
 }
@@ -1034,14 +1184,14 @@ impl NodeDisplay for CatchParam {
 // continue foo;
 node!(#[derive(Default)] pub struct ContinueStatement {
     pub token_continue: KeywordData,
-    pub label: Option<LabelValue>,
+    pub label: Option<LabelValue<'src>>,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for ContinueStatement {
+impl<'src> NodeDisplay for ContinueStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Continue, &self.token_continue);
         f.node(&self.label)?;
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -1051,23 +1201,23 @@ impl NodeDisplay for ContinueStatement {
 // break foo;
 node!(#[derive(Default)] pub struct BreakStatement {
     pub token_break: KeywordData,
-    pub label: Option<LabelValue>,
+    pub label: Option<LabelValue<'src>>,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for BreakStatement {
+impl<'src> NodeDisplay for BreakStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Break, &self.token_break);
         f.node(&self.label)?;
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
 
 node!(pub struct LabelValue {
-    pub tokens_prefix: SeparatorTokens,
-    pub label: LabelIdentifier,
+    pub tokens_prefix: SeparatorTokens<'src>,
+    pub label: LabelIdentifier<'src>,
 });
-impl NodeDisplay for LabelValue {
+impl<'src> NodeDisplay for LabelValue<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // f.separators(&self.tokens_prefix);
         f.node(&self.label)?;
@@ -1082,24 +1232,24 @@ impl NodeDisplay for LabelValue {
 // return foo;
 node!(#[derive(Default)] pub struct ReturnStatement {
     pub token_return: KeywordData,
-    pub value: Option<ReturnValue>,
+    pub value: Option<ReturnValue<'src>>,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for ReturnStatement {
+impl<'src> NodeDisplay for ReturnStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Return, &self.token_return);
-        f.node(&self.value);
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.node(&self.value)?;
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
 
 node!(pub struct ReturnValue {
     // TODO: No newlines allowed
-    pub token_prefix: SeparatorTokens,
-    pub expression: Box<alias::Expression>,
+    pub token_prefix: SeparatorTokens<'src>,
+    pub expression: NodeBox<alias::Expression<'src>>,
 });
-impl NodeDisplay for ReturnValue {
+impl<'src> NodeDisplay for ReturnValue<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         // f.separators(&self.token_prefix);
         f.require_precedence(Precedence::Normal).node(&self.expression)?;
@@ -1113,10 +1263,10 @@ node!(#[derive(Default)] pub struct DebuggerStatement {
     pub token_debugger: KeywordData,
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for DebuggerStatement {
+impl<'src> NodeDisplay for DebuggerStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.keyword(Keyword::Debugger, &self.token_debugger);
-        f.punctuator(Punctuator::Semicolon, &self.token_semi);
+        f.semicolon(&self.token_semi);
         Ok(())
     }
 }
@@ -1125,7 +1275,7 @@ impl NodeDisplay for DebuggerStatement {
 node!(#[derive(Default)] pub struct EmptyStatement {
     pub token_semi: KeywordData,
 });
-impl NodeDisplay for EmptyStatement {
+impl<'src> NodeDisplay for EmptyStatement<'src> {
     fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
         f.punctuator(Punctuator::Semicolon, &self.token_semi);
         Ok(())
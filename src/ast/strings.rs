@@ -0,0 +1,200 @@
+//! Strings in the AST, borrowed from the source they were parsed from.
+//!
+//! Most identifiers and literals are spelled exactly as they appear in the
+//! source, so the parser hands out slices of the code rather than copying
+//! each one. Only names and values that needed escapes to be cooked, or that
+//! are created outside of the parser, own their text. Trees that need to
+//! outlive their source, like those read back from ESTree JSON or kept
+//! across edits, can be converted with `IntoOwned`.
+
+use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::{fmt, string};
+
+#[derive(Clone)]
+pub struct Str<'src>(Repr<'src>);
+
+#[derive(Clone)]
+enum Repr<'src> {
+    Borrowed(&'src str),
+    Owned(Box<str>),
+}
+
+impl<'src> Str<'src> {
+    /// A slice of the source text.
+    pub fn borrowed(s: &'src str) -> Str<'src> {
+        Str(Repr::Borrowed(s))
+    }
+
+    /// Whether the text borrows from the source rather than owning its own
+    /// allocation.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0, Repr::Borrowed(_))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self.0 {
+            Repr::Borrowed(s) => s,
+            Repr::Owned(ref s) => s,
+        }
+    }
+}
+
+impl<'src> Default for Str<'src> {
+    fn default() -> Str<'src> {
+        Str(Repr::Owned(Default::default()))
+    }
+}
+impl<'src> Deref for Str<'src> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl<'src> AsRef<str> for Str<'src> {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+impl<'src> Borrow<str> for Str<'src> {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+// Plain string slices are copied, since they may not come from the source.
+// Use `Str::borrowed` to borrow one.
+impl<'a, 'src> From<&'a str> for Str<'src> {
+    fn from(s: &'a str) -> Str<'src> {
+        Str(Repr::Owned(s.into()))
+    }
+}
+impl<'a, 'src> From<&'a string::String> for Str<'src> {
+    fn from(s: &'a string::String) -> Str<'src> {
+        s.as_str().into()
+    }
+}
+impl<'src> From<string::String> for Str<'src> {
+    fn from(s: string::String) -> Str<'src> {
+        Str(Repr::Owned(s.into_boxed_str()))
+    }
+}
+impl<'src> From<Cow<'src, str>> for Str<'src> {
+    fn from(s: Cow<'src, str>) -> Str<'src> {
+        match s {
+            Cow::Borrowed(s) => Str::borrowed(s),
+            Cow::Owned(s) => s.into(),
+        }
+    }
+}
+impl<'a, 'src> From<&'a Str<'src>> for Str<'src> {
+    fn from(s: &'a Str<'src>) -> Str<'src> {
+        s.clone()
+    }
+}
+impl<'src> From<Str<'src>> for string::String {
+    fn from(s: Str<'src>) -> string::String {
+        match s.0 {
+            Repr::Borrowed(s) => s.into(),
+            Repr::Owned(s) => s.into(),
+        }
+    }
+}
+
+impl<'src> fmt::Debug for Str<'src> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+impl<'src> fmt::Display for Str<'src> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl<'a, 'src> PartialEq<Str<'a>> for Str<'src> {
+    fn eq(&self, other: &Str<'a>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+impl<'src> Eq for Str<'src> {}
+impl<'src> PartialEq<str> for Str<'src> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+impl<'a, 'src> PartialEq<&'a str> for Str<'src> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+impl<'src> PartialEq<string::String> for Str<'src> {
+    fn eq(&self, other: &string::String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+impl<'src> PartialEq<Str<'src>> for str {
+    fn eq(&self, other: &Str<'src>) -> bool {
+        self == other.as_str()
+    }
+}
+impl<'src> PartialEq<Str<'src>> for &str {
+    fn eq(&self, other: &Str<'src>) -> bool {
+        *self == other.as_str()
+    }
+}
+impl<'src> PartialEq<Str<'src>> for string::String {
+    fn eq(&self, other: &Str<'src>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+impl<'src> PartialOrd for Str<'src> {
+    fn partial_cmp(&self, other: &Str<'src>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<'src> Ord for Str<'src> {
+    fn cmp(&self, other: &Str<'src>) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+impl<'src> Hash for Str<'src> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn it_borrows_source_text() {
+        let source = "var foo = 'bar';";
+        let name = Str::from(Cow::Borrowed(&source[4..7]));
+
+        assert!(name.is_borrowed());
+        assert_eq!(name, "foo");
+        assert_eq!(name.as_ptr(), source[4..].as_ptr());
+        assert!(!Str::from(Cow::Owned("foo".to_string())).is_borrowed());
+    }
+
+    #[test]
+    fn it_compares_by_content() {
+        let source = "foo";
+        let borrowed = Str::borrowed(source);
+        let owned = Str::from("foo");
+
+        assert!(!owned.is_borrowed());
+        assert_eq!(borrowed, owned);
+
+        let mut set = HashSet::new();
+        set.insert(borrowed);
+        assert!(set.contains("foo"));
+        assert!(set.contains(&owned));
+    }
+}
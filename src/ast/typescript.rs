@@ -0,0 +1,2284 @@
+//! Nodes for TypeScript's type annotations and declarations.
+//!
+//! These only come out of the parser when the `typescript` extension is
+//! enabled. A formatter with `strip_types` set leaves them out, printing the
+//! plain JavaScript that they annotate.
+
+use std::fmt;
+
+use ast::{KeywordData, KeywordSuffixData, Str, NodeBox};
+
+use ast::display::{NodeDisplay, NodeFormatter, NodeDisplayResult, SourcePosition, Keyword, Punctuator,
+                   Precedence};
+
+use ast::alias;
+use ast::flow;
+use ast::classes::{ClassDeclaration, ClassFieldId, StaticPosition};
+use ast::expression::TemplatePart;
+use ast::functions::{FunctionKind, FunctionParams, FunctionLastParam};
+use ast::general::{self, BindingIdentifier, PropertyIdentifier, PropertyName};
+use ast::literal;
+use ast::modules::SourceExportSpecifier;
+use ast::objects::MethodKind;
+use ast::patterns::BindingPattern;
+use ast::statement::VariableDeclarator;
+use ast::visit::{Visit, Visitable};
+use ast::NodePosition;
+
+
+// Prints items with the given separator between them.
+fn separated<T: NodeDisplay + SourcePosition>(
+    f: &mut NodeFormatter,
+    list: &[T],
+    separator: Punctuator,
+) -> NodeDisplayResult {
+    for (i, item) in list.iter().enumerate() {
+        if i > 0 {
+            if separator == Punctuator::Comma {
+                f.punctuator(separator, &());
+            } else {
+                f.operator(separator, &());
+            }
+        }
+        f.node(item)?;
+    }
+    Ok(())
+}
+
+
+// identifiers used as the names of types
+node!(pub struct TypeIdentifier {
+    pub value: Str<'src>,
+    pub raw: Option<Str<'src>>,
+});
+impl<'src> TypeIdentifier<'src> {
+    pub fn new<T: Into<Str<'src>>>(s: T) -> TypeIdentifier<'src> {
+        TypeIdentifier {
+            value: s.into(),
+            raw: None,
+            position: None,
+            comments: None,
+        }
+    }
+}
+impl<'src> NodeDisplay for TypeIdentifier<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.identifier(&self.value, self.raw.as_deref())
+    }
+}
+impl<'src, T: Into<Str<'src>>> From<T> for TypeIdentifier<'src> {
+    fn from(value: T) -> TypeIdentifier<'src> {
+        TypeIdentifier::new(value)
+    }
+}
+
+
+// : string
+node!(pub struct TypeAnnotation {
+    pub token_colon: KeywordData,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for TypeAnnotation<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.punctuator(Punctuator::Colon, &self.token_colon);
+        f.node(&self.value)
+    }
+}
+impl<'src, T: Into<Type<'src>>> From<T> for TypeAnnotation<'src> {
+    fn from(value: T) -> TypeAnnotation<'src> {
+        TypeAnnotation {
+            token_colon: Default::default(),
+            value: NodeBox::new(value.into()),
+            position: None,
+            comments: None,
+        }
+    }
+}
+#[cfg(test)]
+mod tests_type_annotation {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(TypeAnnotation::from(KeywordType::from(TypeKeyword::String)), ":string");
+    }
+
+    #[test]
+    fn it_strips() {
+        let annotation = TypeAnnotation::from(KeywordType::from(TypeKeyword::String));
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&annotation, &options).unwrap(), "");
+    }
+}
+
+
+node_enum!(@boxed @node_display pub enum Type {
+    Keyword(KeywordType<'src>),
+    Reference(TypeReference<'src>),
+    Literal(LiteralType<'src>),
+    Array(ArrayType<'src>),
+    Tuple(TupleType<'src>),
+    Union(UnionType<'src>),
+    Intersection(IntersectionType<'src>),
+    Function(FunctionType<'src>),
+    Object(ObjectType<'src>),
+    Parenthesized(ParenthesizedType<'src>),
+    Query(TypeQuery<'src>),
+    Operator(TypeOperator<'src>),
+    IndexedAccess(IndexedAccessType<'src>),
+    Predicate(TypePredicate<'src>),
+    Conditional(ConditionalType<'src>),
+    Infer(InferType<'src>),
+    Mapped(MappedType<'src>),
+    NamedTupleMember(NamedTupleMember<'src>),
+    Optional(OptionalType<'src>),
+    Rest(RestType<'src>),
+    TemplateLiteral(TemplateLiteralType<'src>),
+    Import(ImportType<'src>),
+
+    // Flow
+    Maybe(flow::MaybeType<'src>),
+    ExactObject(flow::ExactObjectType<'src>),
+});
+
+
+node_kind!(pub enum TypeKeyword {
+    Any,
+    Unknown,
+    Number,
+    String,
+    Boolean,
+    Bigint,
+    Symbol,
+    Object,
+    Never,
+    Void,
+    Undefined,
+    Null,
+    This,
+});
+impl TypeKeyword {
+    pub fn from_name(name: &str) -> Option<TypeKeyword> {
+        Some(match name {
+            "any" => TypeKeyword::Any,
+            "unknown" => TypeKeyword::Unknown,
+            "number" => TypeKeyword::Number,
+            "string" => TypeKeyword::String,
+            "boolean" => TypeKeyword::Boolean,
+            "bigint" => TypeKeyword::Bigint,
+            "symbol" => TypeKeyword::Symbol,
+            "object" => TypeKeyword::Object,
+            "never" => TypeKeyword::Never,
+            "void" => TypeKeyword::Void,
+            "undefined" => TypeKeyword::Undefined,
+            "null" => TypeKeyword::Null,
+            "this" => TypeKeyword::This,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            TypeKeyword::Any => "any",
+            TypeKeyword::Unknown => "unknown",
+            TypeKeyword::Number => "number",
+            TypeKeyword::String => "string",
+            TypeKeyword::Boolean => "boolean",
+            TypeKeyword::Bigint => "bigint",
+            TypeKeyword::Symbol => "symbol",
+            TypeKeyword::Object => "object",
+            TypeKeyword::Never => "never",
+            TypeKeyword::Void => "void",
+            TypeKeyword::Undefined => "undefined",
+            TypeKeyword::Null => "null",
+            TypeKeyword::This => "this",
+        }
+    }
+}
+
+// string, number, this
+node!(pub struct KeywordType {
+    pub kind: TypeKeyword,
+});
+impl<'src> NodeDisplay for KeywordType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        match self.kind {
+            TypeKeyword::This => f.keyword(Keyword::This, &()),
+            TypeKeyword::Null => f.keyword(Keyword::Null, &()),
+            ref kind => f.identifier(kind.name(), None)?,
+        }
+        Ok(())
+    }
+}
+impl<'src> From<TypeKeyword> for KeywordType<'src> {
+    fn from(kind: TypeKeyword) -> KeywordType<'src> {
+        KeywordType {
+            kind,
+            position: None,
+            comments: None,
+        }
+    }
+}
+
+
+// Foo<T>, a.B
+node!(pub struct TypeReference {
+    pub name: TypeName<'src>,
+    pub type_arguments: Option<TypeArguments<'src>>,
+});
+impl<'src> NodeDisplay for TypeReference<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.name)?;
+        if let Some(ref type_arguments) = self.type_arguments {
+            f.node(type_arguments)?;
+        }
+        Ok(())
+    }
+}
+impl<'src, T: Into<TypeName<'src>>> From<T> for TypeReference<'src> {
+    fn from(name: T) -> TypeReference<'src> {
+        TypeReference {
+            name: name.into(),
+            type_arguments: None,
+            position: None,
+            comments: None,
+        }
+    }
+}
+#[cfg(test)]
+mod tests_type_reference {
+    use super::*;
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(TypeReference::from(TypeIdentifier::from("Foo")), "Foo");
+    }
+
+    #[test]
+    fn it_prints_arguments() {
+        assert_serialize!(
+            TypeReference {
+                name: TypeIdentifier::from("Map").into(),
+                type_arguments: Some(TypeArguments {
+                    arguments: vec![
+                        KeywordType::from(TypeKeyword::String).into(),
+                        TypeReference::from(TypeIdentifier::from("T")).into(),
+                    ],
+                    position: None,
+                    comments: None,
+                }),
+                position: None,
+                comments: None,
+            },
+            "Map<string,T>"
+        );
+    }
+}
+
+node_enum!(@node_display pub enum TypeName {
+    Identifier(TypeIdentifier<'src>),
+    Qualified(QualifiedTypeName<'src>),
+});
+
+// a.B
+node!(pub struct QualifiedTypeName {
+    pub left: NodeBox<TypeName<'src>>,
+    pub token_period: KeywordData,
+    pub right: PropertyIdentifier<'src>,
+});
+impl<'src> NodeDisplay for QualifiedTypeName<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.left)?;
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.node(&self.right)
+    }
+}
+
+
+node_enum!(@node_display pub enum LiteralType {
+    String(literal::String<'src>),
+    Numeric(literal::Numeric<'src>),
+    Boolean(literal::Boolean<'src>),
+    Negative(NegativeLiteralType<'src>),
+});
+
+// -1
+node!(pub struct NegativeLiteralType {
+    pub token_minus: KeywordData,
+    pub value: literal::Numeric<'src>,
+});
+impl<'src> NodeDisplay for NegativeLiteralType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.punctuator(Punctuator::Minus, &self.token_minus);
+        f.node(&self.value)
+    }
+}
+
+
+// T[]
+node!(pub struct ArrayType {
+    pub element: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for ArrayType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.element)?;
+        f.punctuator(Punctuator::SquareL, &());
+        f.punctuator(Punctuator::SquareR, &());
+        Ok(())
+    }
+}
+
+// [A, B]
+node!(pub struct TupleType {
+    pub elements: Vec<Type<'src>>,
+});
+impl<'src> NodeDisplay for TupleType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.wrap_square();
+        separated(&mut f, &self.elements, Punctuator::Comma)
+    }
+}
+
+// A | B
+node!(pub struct UnionType {
+    pub types: Vec<Type<'src>>,
+});
+impl<'src> NodeDisplay for UnionType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        separated(f, &self.types, Punctuator::Bar)
+    }
+}
+
+// A & B
+node!(pub struct IntersectionType {
+    pub types: Vec<Type<'src>>,
+});
+impl<'src> NodeDisplay for IntersectionType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        separated(f, &self.types, Punctuator::Amp)
+    }
+}
+#[cfg(test)]
+mod tests_union_type {
+    use super::*;
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(
+            UnionType {
+                types: vec![
+                    KeywordType::from(TypeKeyword::String).into(),
+                    KeywordType::from(TypeKeyword::Null).into(),
+                ],
+                position: None,
+                comments: None,
+            },
+            "string|null"
+        );
+    }
+}
+
+// (a: A) => B
+node!(pub struct FunctionType {
+    pub token_abstract: Option<KeywordData>, // abstract new (a: A) => B
+    pub token_new: Option<KeywordData>, // new (a: A) => B
+    pub params: NodeBox<FunctionParams<'src>>,
+    pub token_arrow: KeywordData,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for FunctionType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if let Some(ref token_abstract) = self.token_abstract {
+            f.keyword(Keyword::Abstract, token_abstract);
+        }
+        if let Some(ref token_new) = self.token_new {
+            f.keyword(Keyword::New, token_new);
+        }
+        f.node(&self.params)?;
+        f.operator(Punctuator::Arrow, &self.token_arrow);
+        f.node(&self.value)
+    }
+}
+
+// { a: A; b(): B }
+node!(#[derive(Default)] pub struct ObjectType {
+    pub members: Vec<TypeMember<'src>>,
+});
+impl<'src> NodeDisplay for ObjectType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.wrap_curly().node_list(&self.members)
+    }
+}
+
+// (A)
+node!(pub struct ParenthesizedType {
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for ParenthesizedType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.wrap_parens().node(&self.value)
+    }
+}
+
+// typeof a.b
+node!(pub struct TypeQuery {
+    pub token_typeof: KeywordData,
+    pub name: TypeQueryName<'src>,
+});
+impl<'src> NodeDisplay for TypeQuery<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::Typeof, &self.token_typeof);
+        f.node(&self.name)
+    }
+}
+
+node_enum!(@node_display pub enum TypeQueryName {
+    Name(TypeName<'src>),
+    Import(ImportType<'src>),
+});
+
+// import("a").B<C>
+node!(pub struct ImportType {
+    pub token_import: KeywordData,
+    pub source: literal::String<'src>,
+    pub qualifier: Option<TypeName<'src>>,
+    pub type_arguments: Option<TypeArguments<'src>>,
+});
+impl<'src> NodeDisplay for ImportType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::Import, &self.token_import);
+        f.wrap_parens().node(&self.source)?;
+        if let Some(ref qualifier) = self.qualifier {
+            f.punctuator(Punctuator::Period, &());
+            f.node(qualifier)?;
+        }
+        f.node(&self.type_arguments)
+    }
+}
+
+node_kind!(pub enum TypeOperatorKind {
+    Keyof,
+    Unique,
+    Readonly,
+});
+
+// keyof T
+node!(pub struct TypeOperator {
+    pub kind: TypeOperatorKind,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for TypeOperator<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(match self.kind {
+            TypeOperatorKind::Keyof => Keyword::Keyof,
+            TypeOperatorKind::Unique => Keyword::Unique,
+            TypeOperatorKind::Readonly => Keyword::Readonly,
+        }, &());
+        f.node(&self.value)
+    }
+}
+
+// T["a"]
+node!(pub struct IndexedAccessType {
+    pub object: NodeBox<Type<'src>>,
+    pub index: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for IndexedAccessType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.object)?;
+        f.wrap_square().node(&self.index)
+    }
+}
+
+// x is T, asserts x, asserts this is T
+node!(pub struct TypePredicate {
+    pub token_asserts: Option<KeywordData>,
+    pub name: TypeIdentifier<'src>,
+
+    // The "is" and the type, which only assertions can leave out.
+    pub value: Option<(KeywordData, NodeBox<Type<'src>>)>,
+});
+impl<'src> NodeDisplay for TypePredicate<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if let Some(ref token_asserts) = self.token_asserts {
+            f.keyword(Keyword::Asserts, token_asserts);
+        }
+        f.node(&self.name)?;
+        if let Some((ref token_is, ref value)) = self.value {
+            f.keyword(Keyword::Is, token_is);
+            f.node(value)?;
+        }
+        Ok(())
+    }
+}
+
+// A extends B ? C : D
+node!(pub struct ConditionalType {
+    pub check: NodeBox<Type<'src>>,
+    pub token_extends: KeywordData,
+    pub extends: NodeBox<Type<'src>>,
+    pub token_question: KeywordData,
+    pub true_type: NodeBox<Type<'src>>,
+    pub token_colon: KeywordData,
+    pub false_type: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for ConditionalType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.check)?;
+        f.keyword(Keyword::Extends, &self.token_extends);
+        f.node(&self.extends)?;
+        f.operator(Punctuator::Question, &self.token_question);
+        f.node(&self.true_type)?;
+        f.operator(Punctuator::Colon, &self.token_colon);
+        f.node(&self.false_type)
+    }
+}
+
+// infer U, or infer U extends string
+node!(pub struct InferType {
+    pub token_infer: KeywordData,
+    pub param: TypeParameter<'src>,
+});
+impl<'src> NodeDisplay for InferType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::Infer, &self.token_infer);
+        f.node(&self.param)
+    }
+}
+
+// The "+" or "-" that adds or removes the "readonly" or "?" of the members
+// of a mapped type.
+node_kind!(pub enum MappedTypeModifier {
+    Plain,
+    Plus,
+    Minus,
+});
+impl NodeDisplay for MappedTypeModifier {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        match *self {
+            MappedTypeModifier::Plain => {}
+            MappedTypeModifier::Plus => f.punctuator(Punctuator::Plus, &()),
+            MappedTypeModifier::Minus => f.punctuator(Punctuator::Minus, &()),
+        }
+        Ok(())
+    }
+}
+
+// { readonly [K in keyof T as `get${K}`]?: T[K] }
+node!(pub struct MappedType {
+    pub readonly: Option<MappedTypeModifier>,
+    pub key: TypeIdentifier<'src>,
+    pub constraint: NodeBox<Type<'src>>,
+    pub name_type: Option<NodeBox<Type<'src>>>,
+    pub optional: Option<MappedTypeModifier>,
+    pub type_annotation: Option<TypeAnnotation<'src>>,
+});
+impl<'src> NodeDisplay for MappedType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        let mut f = f.wrap_curly();
+        if let Some(ref readonly) = self.readonly {
+            f.node(readonly)?;
+            f.keyword(Keyword::Readonly, &());
+        }
+        {
+            let mut f = f.wrap_square();
+            f.node(&self.key)?;
+            f.keyword(Keyword::In, &());
+            f.node(&self.constraint)?;
+            if let Some(ref name_type) = self.name_type {
+                f.keyword(Keyword::As, &());
+                f.node(name_type)?;
+            }
+        }
+        if let Some(ref optional) = self.optional {
+            f.node(optional)?;
+            f.punctuator(Punctuator::Question, &());
+        }
+        f.node(&self.type_annotation)?;
+        f.punctuator(Punctuator::Semicolon, &());
+        Ok(())
+    }
+}
+
+// The "a?: A" of "[a?: A]"
+node!(pub struct NamedTupleMember {
+    pub label: TypeIdentifier<'src>,
+    pub optional: bool,
+    pub token_colon: KeywordData,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for NamedTupleMember<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.label)?;
+        if self.optional {
+            f.punctuator(Punctuator::Question, &());
+        }
+        f.punctuator(Punctuator::Colon, &self.token_colon);
+        f.node(&self.value)
+    }
+}
+
+// The "A?" of "[A?]"
+node!(pub struct OptionalType {
+    pub value: NodeBox<Type<'src>>,
+    pub token_question: KeywordData,
+});
+impl<'src> NodeDisplay for OptionalType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.value)?;
+        f.punctuator(Punctuator::Question, &self.token_question);
+        Ok(())
+    }
+}
+
+// The "...A" of "[...A]"
+node!(pub struct RestType {
+    pub token_ellipsis: KeywordData,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for RestType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.punctuator(Punctuator::Ellipsis, &self.token_ellipsis);
+        f.node(&self.value)
+    }
+}
+
+// `a${B}c`
+node!(pub struct TemplateLiteralType {
+    pub parts: Vec<(TemplatePart<'src>, KeywordSuffixData, Type<'src>, KeywordData)>,
+    pub last_part: TemplatePart<'src>,
+});
+impl<'src> NodeDisplay for TemplateLiteralType<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.punctuator(Punctuator::TemplateTick, &());
+        for (part, open, value, close) in self.parts.iter() {
+            f.node(part)?;
+            f.punctuator(Punctuator::TemplateOpen, open);
+            f.node(value)?;
+            f.punctuator(Punctuator::TemplateClose, close);
+        }
+        f.node(&self.last_part)?;
+        f.punctuator(Punctuator::TemplateTick, &());
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_conditional_type {
+    use super::*;
+
+    fn reference(name: &str) -> Type<'_> {
+        TypeReference::from(TypeIdentifier::from(name)).into()
+    }
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(
+            ConditionalType {
+                check: reference("T").into(),
+                token_extends: Default::default(),
+                extends: InferType {
+                    token_infer: Default::default(),
+                    param: TypeParameter::from("U"),
+                    position: None,
+                    comments: None,
+                }.into(),
+                token_question: Default::default(),
+                true_type: reference("U").into(),
+                token_colon: Default::default(),
+                false_type: KeywordType::from(TypeKeyword::Never).into(),
+                position: None,
+                comments: None,
+            },
+            "T extends infer U?U:never"
+        );
+    }
+
+    #[test]
+    fn it_prints_mapped_types() {
+        assert_serialize!(
+            MappedType {
+                readonly: Some(MappedTypeModifier::Minus),
+                key: "K".into(),
+                constraint: reference("T").into(),
+                name_type: None,
+                optional: Some(MappedTypeModifier::Plain),
+                type_annotation: Some(reference("V").into()),
+                position: None,
+                comments: None,
+            },
+            "{-readonly[K in T]?:V;}"
+        );
+    }
+}
+
+
+node_enum!(@node_display pub enum TypeMember {
+    Property(PropertySignature<'src>),
+    Method(MethodSignature<'src>),
+    Call(CallSignature<'src>),
+    Construct(ConstructSignature<'src>),
+    Index(IndexSignature<'src>),
+    Spread(flow::SpreadTypeMember<'src>), // Flow
+});
+
+// readonly a?: A;
+node!(pub struct PropertySignature {
+    pub modifiers: Vec<Modifier<'src>>,
+    pub name: PropertyName<'src>,
+    pub optional: bool,
+    pub type_annotation: Option<TypeAnnotation<'src>>,
+});
+impl<'src> NodeDisplay for PropertySignature<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        for modifier in self.modifiers.iter() {
+            f.node(modifier)?;
+        }
+        f.node(&self.name)?;
+        if self.optional {
+            f.punctuator(Punctuator::Question, &());
+        }
+        if let Some(ref type_annotation) = self.type_annotation {
+            f.node(type_annotation)?;
+        }
+        f.punctuator(Punctuator::Semicolon, &());
+        Ok(())
+    }
+}
+
+// a?(b: B): C;
+node!(pub struct MethodSignature {
+    pub name: PropertyName<'src>,
+    pub optional: bool,
+    pub params: FunctionParams<'src>,
+});
+impl<'src> NodeDisplay for MethodSignature<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.name)?;
+        if self.optional {
+            f.punctuator(Punctuator::Question, &());
+        }
+        f.node(&self.params)?;
+        f.punctuator(Punctuator::Semicolon, &());
+        Ok(())
+    }
+}
+
+// (a: A): B;
+node!(pub struct CallSignature {
+    pub params: FunctionParams<'src>,
+});
+impl<'src> NodeDisplay for CallSignature<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.params)?;
+        f.punctuator(Punctuator::Semicolon, &());
+        Ok(())
+    }
+}
+
+// new (a: A): B;
+node!(pub struct ConstructSignature {
+    pub token_new: KeywordData,
+    pub params: FunctionParams<'src>,
+});
+impl<'src> NodeDisplay for ConstructSignature<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::New, &self.token_new);
+        f.node(&self.params)?;
+        f.punctuator(Punctuator::Semicolon, &());
+        Ok(())
+    }
+}
+
+// [key: string]: A;
+node!(pub struct IndexSignature {
+    pub modifiers: Vec<Modifier<'src>>,
+    pub key: TypeIdentifier<'src>,
+    pub key_type: TypeAnnotation<'src>,
+    pub type_annotation: TypeAnnotation<'src>,
+});
+impl<'src> NodeDisplay for IndexSignature<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        // Index signatures can also be class members, which stripping drops.
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        for modifier in self.modifiers.iter() {
+            f.node(modifier)?;
+        }
+        {
+            let mut f = f.wrap_square();
+            f.node(&self.key)?;
+            f.node(&self.key_type)?;
+        }
+        f.node(&self.type_annotation)?;
+        f.punctuator(Punctuator::Semicolon, &());
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_object_type {
+    use super::*;
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(
+            ObjectType {
+                members: vec![
+                    PropertySignature {
+                        modifiers: vec![Modifier::from(ModifierKind::Readonly)],
+                        name: PropertyIdentifier::from("a").into(),
+                        optional: true,
+                        type_annotation: Some(KeywordType::from(TypeKeyword::Number).into()),
+                        position: None,
+                        comments: None,
+                    }.into(),
+                    MethodSignature {
+                        name: PropertyIdentifier::from("b").into(),
+                        optional: false,
+                        params: Default::default(),
+                        position: None,
+                        comments: None,
+                    }.into(),
+                ],
+                position: None,
+                comments: None,
+            },
+            "{readonly a?:number;b();}"
+        );
+    }
+}
+
+
+// <T extends U = V>
+node!(pub struct TypeParameters {
+    pub params: Vec<TypeParameter<'src>>,
+});
+impl<'src> NodeDisplay for TypeParameters<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.punctuator(Punctuator::AngleL, &());
+        separated(f, &self.params, Punctuator::Comma)?;
+        f.punctuator(Punctuator::RAngle, &());
+        Ok(())
+    }
+}
+
+node!(pub struct TypeParameter {
+    // Flow's variance, like the "+" of "<+T>", or TypeScript's, like the
+    // "in out" of "<in out T>".
+    pub modifiers: Vec<Modifier<'src>>,
+    pub id: TypeIdentifier<'src>,
+
+    // Flow's bound, like the ": U" of "<T: U>", which TypeScript writes as a
+    // constraint instead.
+    pub bound: Option<TypeAnnotation<'src>>,
+    pub constraint: Option<NodeBox<Type<'src>>>,
+    pub default: Option<NodeBox<Type<'src>>>,
+});
+impl<'src> NodeDisplay for TypeParameter<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        for modifier in self.modifiers.iter() {
+            f.node(modifier)?;
+        }
+        f.node(&self.id)?;
+        f.node(&self.bound)?;
+        if let Some(ref constraint) = self.constraint {
+            f.keyword(Keyword::Extends, &());
+            f.node(constraint)?;
+        }
+        if let Some(ref default) = self.default {
+            f.operator(Punctuator::Eq, &());
+            f.node(default)?;
+        }
+        Ok(())
+    }
+}
+impl<'src, T: Into<TypeIdentifier<'src>>> From<T> for TypeParameter<'src> {
+    fn from(id: T) -> TypeParameter<'src> {
+        TypeParameter {
+            modifiers: vec![],
+            id: id.into(),
+            bound: None,
+            constraint: None,
+            default: None,
+            position: None,
+            comments: None,
+        }
+    }
+}
+
+// <string, T>
+node!(pub struct TypeArguments {
+    pub arguments: Vec<Type<'src>>,
+});
+impl<'src> NodeDisplay for TypeArguments<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.punctuator(Punctuator::AngleL, &());
+        separated(f, &self.arguments, Punctuator::Comma)?;
+        f.punctuator(Punctuator::RAngle, &());
+        Ok(())
+    }
+}
+
+
+node_kind!(pub enum ModifierKind {
+    Public,
+    Private,
+    Protected,
+    Readonly,
+    Abstract,
+    Override,
+    Declare,
+
+    // Flow's "+" and "-" variance of a property.
+    Covariant,
+    Contravariant,
+
+    // TypeScript's "in" and "out" variance of a type parameter.
+    In,
+    Out,
+});
+impl ModifierKind {
+    pub fn from_name(name: &str) -> Option<ModifierKind> {
+        Some(match name {
+            "public" => ModifierKind::Public,
+            "private" => ModifierKind::Private,
+            "protected" => ModifierKind::Protected,
+            "readonly" => ModifierKind::Readonly,
+            "abstract" => ModifierKind::Abstract,
+            "override" => ModifierKind::Override,
+            "declare" => ModifierKind::Declare,
+            _ => return None,
+        })
+    }
+
+    /// Whether this is one of the modifiers that come before "static".
+    pub fn is_accessibility(&self) -> bool {
+        matches!(*self, ModifierKind::Public | ModifierKind::Private | ModifierKind::Protected)
+    }
+}
+
+// public, readonly, abstract, or Flow's +
+node!(pub struct Modifier {
+    pub kind: ModifierKind,
+});
+impl<'src> NodeDisplay for Modifier<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        let keyword = match self.kind {
+            ModifierKind::Public => Keyword::Public,
+            ModifierKind::Private => Keyword::Private,
+            ModifierKind::Protected => Keyword::Protected,
+            ModifierKind::Readonly => Keyword::Readonly,
+            ModifierKind::Abstract => Keyword::Abstract,
+            ModifierKind::Override => Keyword::Override,
+            ModifierKind::In => Keyword::In,
+            ModifierKind::Out => Keyword::Out,
+            ModifierKind::Declare => Keyword::Declare,
+            ModifierKind::Covariant => {
+                f.punctuator(Punctuator::Plus, &());
+                return Ok(());
+            }
+            ModifierKind::Contravariant => {
+                f.punctuator(Punctuator::Minus, &());
+                return Ok(());
+            }
+        };
+        f.keyword(keyword, &());
+        Ok(())
+    }
+}
+impl<'src> From<ModifierKind> for Modifier<'src> {
+    fn from(kind: ModifierKind) -> Modifier<'src> {
+        Modifier {
+            kind,
+            position: None,
+            comments: None,
+        }
+    }
+}
+
+/// Prints the modifiers of a class member, with "static" after any
+/// accessibility modifier and before the rest.
+pub fn class_member_modifiers(
+    f: &mut NodeFormatter,
+    modifiers: &[Modifier],
+    pos: &Option<StaticPosition>,
+) -> NodeDisplayResult {
+    for modifier in modifiers.iter().filter(|m| m.kind.is_accessibility()) {
+        f.node(modifier)?;
+    }
+    f.node(pos)?;
+    for modifier in modifiers.iter().filter(|m| !m.kind.is_accessibility()) {
+        f.node(modifier)?;
+    }
+    Ok(())
+}
+
+/// Prints the "implements" clause of a class, if it has one.
+pub fn class_implements(f: &mut NodeFormatter, implements: &[TypeReference]) -> NodeDisplayResult {
+    if implements.is_empty() || f.strip_types() {
+        return Ok(());
+    }
+
+    f.keyword(Keyword::Implements, &());
+    separated(f, implements, Punctuator::Comma)
+}
+
+/// Whether the given modifiers mark a class member that only exists in the
+/// type system, like "declare x: T;" or "abstract x: T;".
+pub fn is_ambient(modifiers: &[Modifier]) -> bool {
+    modifiers.iter().any(|m| matches!(m.kind, ModifierKind::Declare | ModifierKind::Abstract))
+}
+
+
+// x as T
+node!(pub struct AsExpression {
+    pub expression: NodeBox<alias::Expression<'src>>,
+    pub token_as: KeywordData,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for AsExpression<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return f.node(&self.expression);
+        }
+
+        let mut f = f.precedence(Precedence::Relational);
+        f.require_precedence(Precedence::Relational).node(&self.expression)?;
+        f.keyword(Keyword::As, &self.token_as);
+        f.node(&self.value)
+    }
+}
+
+// x satisfies T
+node!(pub struct SatisfiesExpression {
+    pub expression: NodeBox<alias::Expression<'src>>,
+    pub token_satisfies: KeywordData,
+    pub value: NodeBox<Type<'src>>,
+});
+impl<'src> NodeDisplay for SatisfiesExpression<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return f.node(&self.expression);
+        }
+
+        let mut f = f.precedence(Precedence::Relational);
+        f.require_precedence(Precedence::Relational).node(&self.expression)?;
+        f.keyword(Keyword::Satisfies, &self.token_satisfies);
+        f.node(&self.value)
+    }
+}
+
+// x!
+node!(pub struct NonNullExpression {
+    pub expression: NodeBox<alias::Expression<'src>>,
+    pub token_exclam: KeywordData,
+});
+impl<'src> NodeDisplay for NonNullExpression<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return f.node(&self.expression);
+        }
+
+        f.require_precedence(Precedence::Member).node(&self.expression)?;
+        f.punctuator(Punctuator::Exclam, &self.token_exclam);
+        Ok(())
+    }
+}
+
+// f<T>, which is the callee of a call like f<T>()
+node!(pub struct InstantiationExpression {
+    pub expression: NodeBox<alias::Expression<'src>>,
+    pub type_arguments: TypeArguments<'src>,
+});
+impl<'src> NodeDisplay for InstantiationExpression<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return f.node(&self.expression);
+        }
+
+        f.require_precedence(Precedence::Member).node(&self.expression)?;
+        f.node(&self.type_arguments)
+    }
+}
+// <T>x
+node!(pub struct TypeAssertion {
+    pub value: NodeBox<Type<'src>>,
+    pub expression: NodeBox<alias::Expression<'src>>,
+});
+impl<'src> NodeDisplay for TypeAssertion<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return f.node(&self.expression);
+        }
+
+        let mut f = f.precedence(Precedence::Unary);
+        f.punctuator(Punctuator::AngleL, &());
+        f.node(&self.value)?;
+        f.punctuator(Punctuator::RAngle, &());
+        f.require_precedence(Precedence::Unary).node(&self.expression)?;
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_expressions {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+    use ast::general::ReferenceIdentifier;
+
+    fn as_expression() -> AsExpression<'static> {
+        AsExpression {
+            expression: alias::Expression::from(ReferenceIdentifier::from("x")).into(),
+            token_as: Default::default(),
+            value: KeywordType::from(TypeKeyword::Any).into(),
+            position: None,
+            comments: None,
+        }
+    }
+
+    #[test]
+    fn it_prints_as() {
+        assert_serialize!(as_expression(), "x as any");
+    }
+
+    #[test]
+    fn it_strips_as() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&as_expression(), &options).unwrap(), "x");
+    }
+
+    #[test]
+    fn it_prints_type_assertions() {
+        let assertion = TypeAssertion {
+            value: KeywordType::from(TypeKeyword::Any).into(),
+            expression: alias::Expression::from(ReferenceIdentifier::from("x")).into(),
+            position: None,
+            comments: None,
+        };
+        assert_serialize!(assertion, "<any>x");
+
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&assertion, &options).unwrap(), "x");
+    }
+
+    #[test]
+    fn it_prints_non_null() {
+        assert_serialize!(
+            NonNullExpression {
+                expression: alias::Expression::from(ReferenceIdentifier::from("x")).into(),
+                token_exclam: Default::default(),
+                position: None,
+                comments: None,
+            },
+            "x!"
+        );
+    }
+}
+
+
+node_enum!(@node_display pub enum Declaration {
+    Interface(InterfaceDeclaration<'src>),
+    TypeAlias(TypeAliasDeclaration<'src>),
+    Enum(EnumDeclaration<'src>),
+    Declare(DeclareDeclaration<'src>),
+    FunctionSignature(FunctionSignatureDeclaration<'src>),
+    Module(ModuleDeclaration<'src>),
+    ImportEquals(ImportEqualsDeclaration<'src>),
+    Opaque(flow::OpaqueTypeDeclaration<'src>), // Flow
+    Variable(DeclareVariableDeclaration<'src>), // Inside of a declared namespace or module
+});
+
+// interface Foo<T> extends Bar { a: T }
+node!(pub struct InterfaceDeclaration {
+    pub token_interface: KeywordData,
+    pub id: TypeIdentifier<'src>,
+    pub type_parameters: Option<TypeParameters<'src>>,
+    pub extends: Vec<TypeReference<'src>>,
+    pub body: ObjectType<'src>,
+});
+impl<'src> NodeDisplay for InterfaceDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.keyword(Keyword::Interface, &self.token_interface);
+        f.node(&self.id)?;
+        if let Some(ref type_parameters) = self.type_parameters {
+            f.node(type_parameters)?;
+        }
+        if !self.extends.is_empty() {
+            f.keyword(Keyword::Extends, &());
+            separated(f, &self.extends, Punctuator::Comma)?;
+        }
+        f.node(&self.body)
+    }
+}
+
+// type Foo<T> = T[];
+node!(pub struct TypeAliasDeclaration {
+    pub token_type: KeywordData,
+    pub id: TypeIdentifier<'src>,
+    pub type_parameters: Option<TypeParameters<'src>>,
+    pub value: NodeBox<Type<'src>>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for TypeAliasDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.keyword(Keyword::Type, &self.token_type);
+        f.node(&self.id)?;
+        if let Some(ref type_parameters) = self.type_parameters {
+            f.node(type_parameters)?;
+        }
+        f.operator(Punctuator::Eq, &());
+        f.node(&self.value)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_type_alias_declaration {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+
+    #[test]
+    fn it_prints() {
+        let alias = TypeAliasDeclaration {
+            token_type: Default::default(),
+            id: "Foo".into(),
+            type_parameters: Some(TypeParameters {
+                params: vec![TypeParameter::from("T")],
+                position: None,
+                comments: None,
+            }),
+            value: ArrayType {
+                element: TypeReference::from(TypeIdentifier::from("T")).into(),
+                position: None,
+                comments: None,
+            }.into(),
+            token_semi: Default::default(),
+            position: None,
+            comments: None,
+        };
+
+        assert_serialize!(alias, "type Foo<T>=T[];");
+
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&alias, &options).unwrap(), "");
+    }
+}
+
+// enum Foo { A, B = 2 }
+node!(pub struct EnumDeclaration {
+    pub is_const: bool,
+    pub token_enum: KeywordData,
+    pub id: BindingIdentifier<'src>,
+    pub members: Vec<EnumMember<'src>>,
+});
+impl<'src> NodeDisplay for EnumDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return self.fmt_stripped(f, None);
+        }
+
+        if self.is_const {
+            f.keyword(Keyword::Const, &());
+        }
+        f.keyword(Keyword::Enum, &self.token_enum);
+        f.node(&self.id)?;
+        f.wrap_curly().node_list(&self.members)
+    }
+}
+impl<'src> EnumDeclaration<'src> {
+    // Prints the object that TypeScript compiles the enum into:
+    //
+    //   var Foo;
+    //   (function (Foo) {
+    //     Foo[Foo["A"] = 0] = "A";
+    //   })(Foo || (Foo = {}));
+    //
+    // Members that refer to earlier members by name get their value if it is
+    // a constant, and otherwise read it from the object. Const enums are
+    // kept as objects rather than inlined.
+    fn fmt_stripped(&self, f: &mut NodeFormatter, token_export: Option<&KeywordData>) -> NodeDisplayResult {
+        f.node_list(&[
+            StrippedEnum::Declaration(self, token_export),
+            StrippedEnum::Members(self),
+        ])
+    }
+
+    fn assignments(&self) -> Vec<EnumAssignment<'_>> {
+        let mut assignments: Vec<EnumAssignment> = vec![];
+        let mut constants: Vec<Option<EnumConstant>> = vec![];
+        let mut next = Some(0.0);
+
+        for member in self.members.iter() {
+            let name = match member.name {
+                PropertyName::Identifier(ref id) => &*id.value,
+                PropertyName::String(ref s) => &*s.value,
+                _ => continue,
+            };
+            let earlier: Vec<&str> = assignments.iter().map(|a| a.name).collect();
+
+            let (value, constant) = match member.init {
+                Some(ref init) => {
+                    let constant = enum_constant(&init.expression, &earlier, &constants);
+                    let value = match (constant.clone(), &*init.expression) {
+                        (Some(EnumConstant::Number(n)), _) => EnumValue::Number(n),
+                        (Some(EnumConstant::String(s)), _) => EnumValue::String(s),
+                        (None, expression) => EnumValue::Expression(expression),
+                    };
+                    (value, constant)
+                }
+                None => match (next, assignments.last()) {
+                    (None, Some(previous)) => (EnumValue::After(previous.name), None),
+                    (value, _) => {
+                        let value = value.unwrap_or(0.0);
+                        (EnumValue::Number(value), Some(EnumConstant::Number(value)))
+                    }
+                },
+            };
+
+            next = match constant {
+                Some(EnumConstant::Number(n)) => Some(n + 1.0),
+                _ => None,
+            };
+            constants.push(constant);
+            assignments.push(EnumAssignment { id: &self.id, name, earlier, value });
+        }
+
+        assignments
+    }
+}
+
+// The value of an enum member that is known without running any code.
+#[derive(Clone)]
+enum EnumConstant {
+    Number(f64),
+    String(String),
+}
+
+// The constant value of a member's initializer, which can name the earlier
+// members, given with their constant values.
+fn enum_constant(expression: &alias::Expression, names: &[&str], constants: &[Option<EnumConstant>]) -> Option<EnumConstant> {
+    let number = |expression: &alias::Expression| match enum_constant(expression, names, constants) {
+        Some(EnumConstant::Number(n)) => Some(n),
+        _ => None,
+    };
+    let integer = |expression: &alias::Expression| number(expression).map(|n| n as i64 as i32);
+
+    Some(match *expression {
+        alias::Expression::Numeric(ref n) => EnumConstant::Number(n.value),
+        alias::Expression::String(ref s) => EnumConstant::String(s.value.to_string()),
+        alias::Expression::Parenthesized(ref e) => return enum_constant(&e.expr, names, constants),
+        alias::Expression::Negative(ref n) => EnumConstant::Number(-number(&n.value)?),
+        alias::Expression::Binding(ref id) => {
+            let index = names.iter().rposition(|name| id.value == *name)?;
+            return constants[index].clone();
+        }
+        alias::Expression::Add(ref e) => {
+            match (enum_constant(&e.left, names, constants)?, enum_constant(&e.right, names, constants)?) {
+                (EnumConstant::Number(left), EnumConstant::Number(right)) => EnumConstant::Number(left + right),
+                (left, right) => EnumConstant::String(format!("{}{}", left, right)),
+            }
+        }
+        alias::Expression::Subtract(ref e) => EnumConstant::Number(number(&e.left)? - number(&e.right)?),
+        alias::Expression::Multiply(ref e) => EnumConstant::Number(number(&e.left)? * number(&e.right)?),
+        alias::Expression::BitOr(ref e) => EnumConstant::Number(f64::from(integer(&e.left)? | integer(&e.right)?)),
+        alias::Expression::LeftShift(ref e) => {
+            EnumConstant::Number(f64::from(integer(&e.left)?.wrapping_shl(integer(&e.right)? as u32)))
+        }
+        _ => return None,
+    })
+}
+impl fmt::Display for EnumConstant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EnumConstant::Number(n) => write!(f, "{}", n),
+            EnumConstant::String(ref s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// The statements printed for an enum when types are stripped, which only
+// borrow from the enum rather than being nodes of their own.
+enum StrippedEnum<'a> {
+    Declaration(&'a EnumDeclaration<'a>, Option<&'a KeywordData>),
+    Members(&'a EnumDeclaration<'a>),
+}
+impl<'a> NodeDisplay for StrippedEnum<'a> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        match *self {
+            StrippedEnum::Declaration(decl, token_export) => {
+                if let Some(token_export) = token_export {
+                    f.keyword(Keyword::Export, token_export);
+                }
+                f.keyword(Keyword::Var, &decl.token_enum);
+                f.node(&decl.id)?;
+                f.semicolon(&());
+            }
+            StrippedEnum::Members(decl) => {
+                {
+                    let mut f = f.wrap_parens();
+                    f.keyword(Keyword::Function, &());
+                    f.wrap_parens().node(&decl.id)?;
+                    f.wrap_curly().node_list(&decl.assignments())?;
+                }
+                {
+                    let mut f = f.wrap_parens();
+                    f.node(&decl.id)?;
+                    f.operator(Punctuator::BarBar, &());
+
+                    let mut f = f.wrap_parens();
+                    f.node(&decl.id)?;
+                    f.operator(Punctuator::Eq, &());
+                    f.wrap_curly();
+                }
+                f.semicolon(&());
+            }
+        }
+        Ok(())
+    }
+}
+impl<'a> SourcePosition for StrippedEnum<'a> {
+    fn source_position(&self) -> Option<&NodePosition> {
+        None
+    }
+}
+
+enum EnumValue<'a> {
+    Number(f64),
+    // String values get no reverse mapping from the value to the name.
+    String(String),
+    Expression(&'a alias::Expression<'a>),
+    // One more than the value of the named member.
+    After(&'a str),
+}
+
+// Foo[Foo["A"] = 0] = "A";
+struct EnumAssignment<'a> {
+    id: &'a BindingIdentifier<'a>,
+    name: &'a str,
+    // The members before this one, which its initializer can refer to.
+    earlier: Vec<&'a str>,
+    value: EnumValue<'a>,
+}
+impl<'a> EnumAssignment<'a> {
+    fn fmt_member(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(self.id)?;
+        {
+            let mut f = f.wrap_square();
+            f.string(self.name, None)?;
+        }
+        f.operator(Punctuator::Eq, &());
+
+        let mut f = f.require_precedence(Precedence::Assignment);
+        match self.value {
+            EnumValue::Number(n) => {
+                if n < 0.0 {
+                    f.punctuator(Punctuator::Minus, &());
+                }
+                f.number(&n.abs(), None)?;
+            }
+            EnumValue::String(ref s) => {
+                f.string(s, None)?;
+            }
+            EnumValue::Expression(expression) => {
+                let mut f = f.enum_members(self.id.value.to_string(), &self.earlier);
+                f.node(expression)?;
+            }
+            EnumValue::After(previous) => {
+                f.node(self.id)?;
+                f.wrap_square().string(previous, None)?;
+                f.operator(Punctuator::Plus, &());
+                f.number(&1.0, None)?;
+            }
+        }
+        Ok(())
+    }
+}
+impl<'a> NodeDisplay for EnumAssignment<'a> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if let EnumValue::String(_) = self.value {
+            self.fmt_member(f)?;
+        } else {
+            f.node(self.id)?;
+            self.fmt_member(&mut f.wrap_square())?;
+            f.operator(Punctuator::Eq, &());
+            f.string(self.name, None)?;
+        }
+        f.semicolon(&());
+        Ok(())
+    }
+}
+impl<'a> SourcePosition for EnumAssignment<'a> {
+    fn source_position(&self) -> Option<&NodePosition> {
+        None
+    }
+}
+
+// A = 1,
+node!(pub struct EnumMember {
+    pub name: PropertyName<'src>,
+    pub init: Option<general::Initializer<'src>>,
+});
+impl<'src> NodeDisplay for EnumMember<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.name)?;
+        f.node(&self.init)?;
+        f.punctuator(Punctuator::Comma, &());
+        Ok(())
+    }
+}
+impl<'src, T: Into<PropertyName<'src>>> From<T> for EnumMember<'src> {
+    fn from(name: T) -> EnumMember<'src> {
+        EnumMember {
+            name: name.into(),
+            init: None,
+            position: None,
+            comments: None,
+        }
+    }
+}
+
+// function foo(a: A): B;
+node!(pub struct FunctionSignatureDeclaration {
+    pub kind: FunctionKind,
+    pub id: BindingIdentifier<'src>,
+    pub params: FunctionParams<'src>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for FunctionSignatureDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.node(&self.kind)?;
+        f.node(&self.id)?;
+        f.node(&self.params)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+
+// declare const foo: Foo;
+node!(pub struct DeclareDeclaration {
+    pub token_declare: KeywordData,
+    pub declaration: DeclaredItem<'src>,
+});
+impl<'src> NodeDisplay for DeclareDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.keyword(Keyword::Declare, &self.token_declare);
+        f.node(&self.declaration)
+    }
+}
+
+node_enum!(@node_display pub enum DeclaredItem {
+    Variable(DeclareVariableDeclaration<'src>),
+    Function(FunctionSignatureDeclaration<'src>),
+    Class(ClassDeclaration<'src>),
+    Enum(EnumDeclaration<'src>),
+    Interface(InterfaceDeclaration<'src>),
+    TypeAlias(TypeAliasDeclaration<'src>),
+    Module(ModuleDeclaration<'src>),
+    Opaque(flow::OpaqueTypeDeclaration<'src>), // Flow
+    Export(flow::DeclareExportDeclaration<'src>), // Flow
+    ModuleExports(flow::DeclareModuleExports<'src>), // Flow
+});
+
+node_kind!(pub enum DeclareVariableKind {
+    Var,
+    Let,
+    Const,
+});
+
+// The "const foo: Foo;" of "declare const foo: Foo;", which unlike an
+// ordinary const declaration needs no initializer.
+node!(pub struct DeclareVariableDeclaration {
+    pub kind: DeclareVariableKind,
+    pub declarators: Vec<VariableDeclarator<'src>>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for DeclareVariableDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(match self.kind {
+            DeclareVariableKind::Var => Keyword::Var,
+            DeclareVariableKind::Let => Keyword::Let,
+            DeclareVariableKind::Const => Keyword::Const,
+        }, &());
+        separated(f, &self.declarators, Punctuator::Comma)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+
+// export interface Foo {}
+node!(pub struct ExportDeclaration {
+    pub token_export: KeywordData,
+    pub exported: Declaration<'src>,
+});
+impl<'src> NodeDisplay for ExportDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return match self.exported {
+                Declaration::Enum(ref decl) => decl.fmt_stripped(f, Some(&self.token_export)),
+                Declaration::Module(ref decl) => decl.fmt_stripped(f, None, Some(&self.token_export)),
+                Declaration::ImportEquals(ref decl) => {
+                    f.keyword(Keyword::Export, &self.token_export);
+                    f.node(decl)
+                }
+                _ => Ok(()),
+            };
+        }
+
+        f.keyword(Keyword::Export, &self.token_export);
+        f.node(&self.exported)
+    }
+}
+#[cfg(test)]
+mod tests_enum_declaration {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+    use ast::literal;
+
+    fn enum_declaration() -> EnumDeclaration<'static> {
+        EnumDeclaration {
+            is_const: false,
+            token_enum: Default::default(),
+            id: "E".into(),
+            members: vec![
+                EnumMember::from(PropertyIdentifier::from("A")),
+                EnumMember {
+                    name: PropertyIdentifier::from("B").into(),
+                    init: Some(alias::Expression::from(literal::Numeric::from(5.0)).into()),
+                    position: None,
+                    comments: None,
+                },
+                EnumMember::from(PropertyIdentifier::from("C")),
+                EnumMember {
+                    name: literal::String::from("D").into(),
+                    init: Some(alias::Expression::from(literal::String::from("d")).into()),
+                    position: None,
+                    comments: None,
+                },
+            ],
+            position: None,
+            comments: None,
+        }
+    }
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(enum_declaration(), "enum E{A,B=5,C,'D'='d',}");
+    }
+
+    #[test]
+    fn it_strips() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(
+            format(&enum_declaration(), &options).unwrap(),
+            "var E;(function(E){E[E['A']=0]='A';E[E['B']=5]='B';E[E['C']=6]='C';E['D']='d';})(E||(E={}));"
+        );
+    }
+
+    #[test]
+    fn it_strips_pretty() {
+        let options = FormatOptions { strip_types: true, ..FormatOptions::pretty() };
+        assert_eq!(
+            format(&enum_declaration(), &options).unwrap(),
+            "var E;\n(function (E) {\n  E[E['A'] = 0] = 'A';\n  E[E['B'] = 5] = 'B';\n  E[E['C'] = 6] = 'C';\n  E['D'] = 'd';\n})(E || (E = {}));"
+        );
+    }
+}
+
+
+node_kind!(pub enum ModuleKind {
+    Namespace,
+    Module,
+    Global,
+});
+
+node_enum!(@node_display pub enum ModuleName {
+    Identifier(BindingIdentifier<'src>),
+    Qualified(QualifiedModuleName<'src>),
+    String(literal::String<'src>),
+});
+impl<'src> ModuleName<'src> {
+    // The namespaces named by a dotted name like "A.B", outermost first,
+    // which names in strings don't have.
+    fn namespaces(&self) -> Option<Vec<&BindingIdentifier<'src>>> {
+        match *self {
+            ModuleName::Identifier(ref id) => Some(vec![id]),
+            ModuleName::Qualified(ref name) => {
+                let mut names = name.left.namespaces()?;
+                names.push(&name.right);
+                Some(names)
+            }
+            ModuleName::String(_) => None,
+        }
+    }
+}
+
+// A.B
+node!(pub struct QualifiedModuleName {
+    pub left: NodeBox<ModuleName<'src>>,
+    pub token_period: KeywordData,
+    pub right: BindingIdentifier<'src>,
+});
+impl<'src> NodeDisplay for QualifiedModuleName<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.node(&self.left)?;
+        f.punctuator(Punctuator::Period, &self.token_period);
+        f.node(&self.right)
+    }
+}
+
+// namespace A.B { export const c = 1; }
+// declare module "a" {}
+node!(pub struct ModuleDeclaration {
+    pub kind: ModuleKind,
+    pub token_kind: KeywordData,
+
+    // "global" is a name of its own, like the keyword of "declare global {}".
+    pub id: Option<ModuleName<'src>>,
+
+    // Only declared modules, like "declare module 'a';", leave out the body.
+    pub body: Option<ModuleBlock<'src>>,
+});
+impl<'src> NodeDisplay for ModuleDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return self.fmt_stripped(f, None, None);
+        }
+
+        f.keyword(match self.kind {
+            ModuleKind::Namespace => Keyword::Namespace,
+            ModuleKind::Module => Keyword::Module,
+            ModuleKind::Global => Keyword::Global,
+        }, &self.token_kind);
+        f.node(&self.id)?;
+        match self.body {
+            Some(ref body) => f.node(body)?,
+            None => f.semicolon(&()),
+        }
+        Ok(())
+    }
+}
+impl<'src> ModuleDeclaration<'src> {
+    /// Whether the namespace holds any values rather than only types, and so
+    /// still exists once types are stripped.
+    pub fn is_instantiated(&self) -> bool {
+        match (&self.id, &self.body) {
+            (Some(ModuleName::String(_)), _) | (None, _) | (_, None) => false,
+            (_, Some(body)) => body.body.iter().any(is_value_item),
+        }
+    }
+
+    // Prints the function that TypeScript compiles the namespace into, with
+    // one function for each part of a dotted name:
+    //
+    //   var A;
+    //   (function (A) {
+    //     let B;
+    //     (function (B) {
+    //       const c = 1;
+    //       B.c = c;
+    //     })(B = A.B || (A.B = {}));
+    //   })(A || (A = {}));
+    //
+    // Exported declarations are kept as local bindings, and assigned to the
+    // namespace after them, rather than rewriting references to them.
+    fn fmt_stripped(
+        &self,
+        f: &mut NodeFormatter,
+        parent: Option<&BindingIdentifier>,
+        token_export: Option<&KeywordData>,
+    ) -> NodeDisplayResult {
+        if !self.is_instantiated() {
+            return Ok(());
+        }
+        let names = match self.id.as_ref().and_then(ModuleName::namespaces) {
+            Some(names) => names,
+            None => return Ok(()),
+        };
+        let body = match self.body {
+            Some(ref body) => body,
+            None => return Ok(()),
+        };
+
+        f.node_list(&[
+            StrippedModule::Declaration(names[0], &self.token_kind, token_export, parent.is_some()),
+            StrippedModule::Namespace(&names, parent, body),
+        ])
+    }
+}
+
+// Whether an item of a namespace is left behind once types are stripped.
+fn is_value_item(item: &alias::ModuleStatementItem) -> bool {
+    let decl = match *item {
+        alias::ModuleStatementItem::TypeScript(ref decl) => &**decl,
+        alias::ModuleStatementItem::ExportTypeScript(ref decl) => &decl.exported,
+        alias::ModuleStatementItem::Empty(_) |
+        alias::ModuleStatementItem::ExportTypes(_) |
+        alias::ModuleStatementItem::ImportType(_) => return false,
+        _ => return true,
+    };
+
+    match *decl {
+        Declaration::Enum(_) | Declaration::ImportEquals(_) => true,
+        Declaration::Module(ref decl) => decl.is_instantiated(),
+        _ => false,
+    }
+}
+
+// The statements printed for a namespace when types are stripped.
+enum StrippedModule<'a> {
+    // var A; or let B; in the function of an outer namespace
+    Declaration(&'a BindingIdentifier<'a>, &'a KeywordData, Option<&'a KeywordData>, bool),
+    // The function for the first of the names, in the namespace of the parent
+    // if there is one.
+    Namespace(&'a [&'a BindingIdentifier<'a>], Option<&'a BindingIdentifier<'a>>, &'a ModuleBlock<'a>),
+    // An item of the body, without the "export" of an exported declaration.
+    Item(&'a BindingIdentifier<'a>, &'a alias::ModuleStatementItem<'a>),
+    // A.b = b;
+    Export(&'a BindingIdentifier<'a>, Str<'a>),
+}
+impl<'a> NodeDisplay for StrippedModule<'a> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        match *self {
+            StrippedModule::Declaration(id, token_kind, token_export, nested) => {
+                if let Some(token_export) = token_export {
+                    f.keyword(Keyword::Export, token_export);
+                }
+                f.keyword(if nested { Keyword::Let } else { Keyword::Var }, token_kind);
+                f.node(id)?;
+                f.semicolon(&());
+            }
+            StrippedModule::Namespace(names, parent, body) => {
+                let id = names[0];
+                {
+                    let mut f = f.wrap_parens();
+                    f.keyword(Keyword::Function, &());
+                    f.wrap_parens().node(id)?;
+
+                    let mut f = f.wrap_curly();
+                    if names.len() > 1 {
+                        let token_let = KeywordData::default();
+                        f.node_list(&[
+                            StrippedModule::Declaration(names[1], &token_let, None, true),
+                            StrippedModule::Namespace(&names[1..], Some(id), body),
+                        ])?;
+                    } else {
+                        let mut items = vec![];
+                        for item in body.body.iter() {
+                            items.push(StrippedModule::Item(id, item));
+                            items.extend(exported_names(item).into_iter().map(|name| StrippedModule::Export(id, name)));
+                        }
+                        f.node_list(&items)?;
+                    }
+                }
+                {
+                    let mut f = f.wrap_parens();
+                    if let Some(parent) = parent {
+                        f.node(id)?;
+                        f.operator(Punctuator::Eq, &());
+                        f.node(parent)?;
+                        f.punctuator(Punctuator::Period, &());
+                        f.node(id)?;
+                    } else {
+                        f.node(id)?;
+                    }
+                    f.operator(Punctuator::BarBar, &());
+
+                    let mut f = f.wrap_parens();
+                    if let Some(parent) = parent {
+                        f.node(parent)?;
+                        f.punctuator(Punctuator::Period, &());
+                    }
+                    f.node(id)?;
+                    f.operator(Punctuator::Eq, &());
+                    f.wrap_curly();
+                }
+                f.semicolon(&());
+            }
+            StrippedModule::Item(id, item) => match *item {
+                alias::ModuleStatementItem::ExportVariable(ref n) => f.node(&n.exported)?,
+                alias::ModuleStatementItem::ExportLet(ref n) => f.node(&n.exported)?,
+                alias::ModuleStatementItem::ExportConst(ref n) => f.node(&n.exported)?,
+                alias::ModuleStatementItem::ExportFunction(ref n) => f.node(&n.exported)?,
+                alias::ModuleStatementItem::ExportClass(ref n) => f.node(&n.exported)?,
+                alias::ModuleStatementItem::ExportTypeScript(ref n) => match n.exported {
+                    Declaration::Module(ref decl) => decl.fmt_stripped(f, Some(id), None)?,
+                    ref decl => f.node(decl)?,
+                },
+                ref item => f.node(item)?,
+            },
+            StrippedModule::Export(id, ref name) => {
+                f.node(id)?;
+                f.punctuator(Punctuator::Period, &());
+                f.identifier(name, None)?;
+                f.operator(Punctuator::Eq, &());
+                f.identifier(name, None)?;
+                f.semicolon(&());
+            }
+        }
+        Ok(())
+    }
+}
+impl<'a> SourcePosition for StrippedModule<'a> {
+    fn source_position(&self) -> Option<&NodePosition> {
+        None
+    }
+}
+
+// The names that an exported item of a namespace adds to it, other than
+// those of nested namespaces, which assign themselves.
+fn exported_names<'src>(item: &alias::ModuleStatementItem<'src>) -> Vec<Str<'src>> {
+    let mut names = BoundNames::default();
+    match *item {
+        alias::ModuleStatementItem::ExportVariable(ref n) => {
+            for (decl, _) in n.exported.declarators.iter() {
+                decl.id.accept(&mut names);
+            }
+            n.exported.last_declarator.id.accept(&mut names);
+        }
+        alias::ModuleStatementItem::ExportLet(ref n) => {
+            for (decl, _) in n.exported.declarators.iter() {
+                decl.id.accept(&mut names);
+            }
+            n.exported.last_declarator.id.accept(&mut names);
+        }
+        alias::ModuleStatementItem::ExportConst(ref n) => {
+            for (decl, _) in n.exported.declarators.iter() {
+                decl.id.accept(&mut names);
+            }
+            n.exported.last_declarator.id.accept(&mut names);
+        }
+        alias::ModuleStatementItem::ExportFunction(ref n) => n.exported.id.accept(&mut names),
+        alias::ModuleStatementItem::ExportClass(ref n) => n.exported.id.id.accept(&mut names),
+        alias::ModuleStatementItem::ExportTypeScript(ref n) => match n.exported {
+            Declaration::Enum(ref decl) => decl.id.accept(&mut names),
+            Declaration::ImportEquals(ref decl) => decl.id.accept(&mut names),
+            _ => {}
+        },
+        _ => {}
+    }
+    names.0
+}
+
+// Collects the names bound by a pattern, skipping over its default values
+// and computed keys.
+#[derive(Default)]
+struct BoundNames<'src>(Vec<Str<'src>>);
+impl<'src> Visit<'src> for BoundNames<'src> {
+    fn visit_binding_identifier(&mut self, node: &BindingIdentifier<'src>) {
+        self.0.push(node.value.clone());
+    }
+    fn visit_initializer(&mut self, _node: &general::Initializer<'src>) {}
+    fn visit_computed_property_name(&mut self, _node: &general::ComputedPropertyName<'src>) {}
+}
+
+// { export const a = 1; }
+node!(#[derive(Default)] pub struct ModuleBlock {
+    pub body: Vec<alias::ModuleStatementItem<'src>>,
+});
+impl<'src> NodeDisplay for ModuleBlock<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.wrap_curly().node_list(&self.body)
+    }
+}
+#[cfg(test)]
+mod tests_module_declaration {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+    use ast::statement::{ConstDeclaration, ConstDeclarator};
+    use ast::modules::ExportConstDeclaration;
+
+    fn namespace(body: Vec<alias::ModuleStatementItem>) -> ModuleDeclaration {
+        ModuleDeclaration {
+            kind: ModuleKind::Namespace,
+            token_kind: Default::default(),
+            id: Some(QualifiedModuleName {
+                left: NodeBox::new(BindingIdentifier::from("A").into()),
+                token_period: Default::default(),
+                right: "B".into(),
+                position: None,
+                comments: None,
+            }.into()),
+            body: Some(ModuleBlock { body, position: None, comments: None }),
+            position: None,
+            comments: None,
+        }
+    }
+
+    fn export_const() -> alias::ModuleStatementItem<'static> {
+        ExportConstDeclaration {
+            token_export: Default::default(),
+            exported: ConstDeclaration {
+                token_const: Default::default(),
+                declarators: vec![],
+                last_declarator: ConstDeclarator {
+                    id: BindingIdentifier::from("c").into(),
+                    type_annotation: None,
+                    init: alias::Expression::from(literal::Numeric::from(1.0)).into(),
+                    position: None,
+                    comments: None,
+                },
+                token_semi: Default::default(),
+                position: None,
+                comments: None,
+            },
+            position: None,
+            comments: None,
+        }.into()
+    }
+
+    #[test]
+    fn it_prints() {
+        assert_serialize!(namespace(vec![export_const()]), "namespace A.B{export const c=1;}");
+    }
+
+    #[test]
+    fn it_strips() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(
+            format(&namespace(vec![export_const()]), &options).unwrap(),
+            "var A;(function(A){let B;(function(B){const c=1;B.c=c;})(B=A.B||(A.B={}));})(A||(A={}));"
+        );
+    }
+
+    #[test]
+    fn it_strips_types_only() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        assert_eq!(format(&namespace(vec![]), &options).unwrap(), "");
+    }
+}
+
+// import a = require("a");
+// import b = a.b;
+node!(pub struct ImportEqualsDeclaration {
+    pub token_import: KeywordData,
+    pub id: BindingIdentifier<'src>,
+    pub token_eq: KeywordData,
+    pub value: ModuleReference<'src>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for ImportEqualsDeclaration<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        // Stripping types leaves the variable that TypeScript compiles the
+        // import into.
+        f.keyword(if !f.strip_types() {
+            Keyword::Import
+        } else if let ModuleReference::External(_) = self.value {
+            Keyword::Const
+        } else {
+            Keyword::Var
+        }, &self.token_import);
+        f.node(&self.id)?;
+        f.operator(Punctuator::Eq, &self.token_eq);
+        f.node(&self.value)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+
+node_enum!(@node_display pub enum ModuleReference {
+    Name(TypeName<'src>),
+    External(ExternalModuleReference<'src>),
+});
+
+// require("a")
+node!(pub struct ExternalModuleReference {
+    pub token_require: KeywordData,
+    pub source: literal::String<'src>,
+});
+impl<'src> NodeDisplay for ExternalModuleReference<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.identifier("require", None)?;
+        f.wrap_parens().node(&self.source)
+    }
+}
+
+// export = a;
+node!(pub struct ExportAssignment {
+    pub token_export: KeywordData,
+    pub token_eq: KeywordData,
+    pub expression: alias::Expression<'src>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for ExportAssignment<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            f.identifier("module", None)?;
+            f.punctuator(Punctuator::Period, &self.token_export);
+            f.identifier("exports", None)?;
+        } else {
+            f.keyword(Keyword::Export, &self.token_export);
+        }
+        f.operator(Punctuator::Eq, &self.token_eq);
+        f.require_precedence(Precedence::Assignment).node(&self.expression)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+
+// export type { A, B as C } from "a";
+node!(pub struct ExportTypeSpecifiers {
+    pub token_export: KeywordData,
+    pub token_type: KeywordData,
+    pub specifiers: Vec<(SourceExportSpecifier<'src>, KeywordData)>,
+    pub last_specifier: Option<SourceExportSpecifier<'src>>,
+    pub source: Option<(KeywordData, literal::String<'src>)>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for ExportTypeSpecifiers<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        f.keyword(Keyword::Export, &self.token_export);
+        f.keyword(Keyword::Type, &self.token_type);
+        {
+            let mut f = f.wrap_curly();
+            f.comma_list(&self.specifiers)?;
+            f.node(&self.last_specifier)?;
+        }
+        if let Some((ref token_from, ref source)) = self.source {
+            f.keyword(Keyword::From, token_from);
+            f.node(source)?;
+        }
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests_import_equals_declaration {
+    use super::*;
+    use ast::display::{format, FormatOptions};
+    use ast::general::ReferenceIdentifier;
+
+    #[test]
+    fn it_strips() {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        let import = ImportEqualsDeclaration {
+            token_import: Default::default(),
+            id: "a".into(),
+            token_eq: Default::default(),
+            value: ExternalModuleReference {
+                token_require: Default::default(),
+                source: "a".into(),
+                position: None,
+                comments: None,
+            }.into(),
+            token_semi: Default::default(),
+            position: None,
+            comments: None,
+        };
+        assert_serialize!(import, "import a=require('a');");
+        assert_eq!(format(&import, &options).unwrap(), "const a=require('a');");
+
+        let export = ExportAssignment {
+            token_export: Default::default(),
+            token_eq: Default::default(),
+            expression: ReferenceIdentifier::from("a").into(),
+            token_semi: Default::default(),
+            position: None,
+            comments: None,
+        };
+        assert_serialize!(export, "export=a;");
+        assert_eq!(format(&export, &options).unwrap(), "module.exports=a;");
+    }
+}
+
+
+// abstract foo(): void;
+node!(pub struct ClassMethodSignature {
+    pub modifiers: Vec<Modifier<'src>>,
+    pub pos: Option<StaticPosition<'src>>,
+    pub kind: MethodKind,
+    pub id: ClassFieldId<'src>,
+    pub optional: bool,
+    pub params: FunctionParams<'src>,
+    pub token_semi: KeywordData,
+});
+impl<'src> NodeDisplay for ClassMethodSignature<'src> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        if f.strip_types() {
+            return Ok(());
+        }
+
+        class_member_modifiers(f, &self.modifiers, &self.pos)?;
+        f.node(&self.kind)?;
+        f.node(&self.id)?;
+        if self.optional {
+            f.punctuator(Punctuator::Question, &());
+        }
+        f.node(&self.params)?;
+        f.semicolon(&self.token_semi);
+        Ok(())
+    }
+}
+
+
+/// The names of constructor parameters with modifiers, like the "x" of
+/// "constructor(private x) {}", which are also assigned to "this".
+pub fn parameter_properties<'a, 'src>(params: &'a FunctionParams<'src>) -> Vec<&'a BindingIdentifier<'src>> {
+    let last = match params.last_param {
+        Some(FunctionLastParam::Param(ref param)) => Some(param),
+        _ => None,
+    };
+
+    params.params.iter().map(|(param, _)| param).chain(last)
+        .filter(|param| !param.modifiers.is_empty())
+        .filter_map(|param| match param.id {
+            BindingPattern::Identifier(ref id) => Some(id),
+            _ => None,
+        })
+        .collect()
+}
+
+// this.x = x;
+pub struct ParameterPropertyAssignment<'a>(pub &'a BindingIdentifier<'a>);
+impl<'a> NodeDisplay for ParameterPropertyAssignment<'a> {
+    fn fmt(&self, f: &mut NodeFormatter) -> NodeDisplayResult {
+        f.keyword(Keyword::This, &());
+        f.punctuator(Punctuator::Period, &());
+        f.node(self.0)?;
+        f.operator(Punctuator::Eq, &());
+        f.node(self.0)?;
+        f.semicolon(&());
+        Ok(())
+    }
+}
+impl<'a> SourcePosition for ParameterPropertyAssignment<'a> {
+    fn source_position(&self) -> Option<&NodePosition> {
+        None
+    }
+}
@@ -0,0 +1,207 @@
+//! Traversal of the AST.
+//!
+//! `Visit` and `VisitMut` have a `visit_*` method for every node struct, each
+//! of which defaults to walking into the node's children. Enums that wrap
+//! nodes, like those in `alias`, are transparent: visiting one visits the
+//! node it holds.
+//!
+//! The methods are generated for every struct declared with `node!`, and
+//! named after it, like `visit_call_expression` for `CallExpression`.
+
+use std::string;
+
+use ast::{
+    classes, decorators, expression, flow, functions, general, jsx, literal, modules, objects,
+    patterns, root, statement, typescript,
+};
+use ast::{KeywordData, KeywordSuffixData, KeywordWrappedData, NodeBox, PositionRange, Separators, Str};
+use stack;
+
+/// Walks into the child nodes of a node. Implemented for every node by the
+/// `node!` macro, and used by the default `visit_*` methods.
+pub trait Walk<'src> {
+    fn walk<V: Visit<'src> + ?Sized>(&self, visitor: &mut V);
+    fn walk_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V);
+}
+
+/// Anything that can appear in a node field, passing any nodes it contains
+/// to the matching visitor method.
+pub trait Visitable<'src> {
+    fn accept<V: Visit<'src> + ?Sized>(&self, visitor: &mut V);
+    fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V);
+}
+impl<'src, T: Visitable<'src>> Visitable<'src> for NodeBox<T> {
+    fn accept<V: Visit<'src> + ?Sized>(&self, visitor: &mut V) {
+        stack::grow(|| (**self).accept(visitor))
+    }
+    fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V) {
+        stack::grow(|| (**self).accept_mut(visitor))
+    }
+}
+impl<'src, T: Visitable<'src>> Visitable<'src> for Option<T> {
+    fn accept<V: Visit<'src> + ?Sized>(&self, visitor: &mut V) {
+        if let Some(ref n) = *self {
+            n.accept(visitor);
+        }
+    }
+    fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V) {
+        if let Some(ref mut n) = *self {
+            n.accept_mut(visitor);
+        }
+    }
+}
+impl<'src, T: Visitable<'src>> Visitable<'src> for Vec<T> {
+    fn accept<V: Visit<'src> + ?Sized>(&self, visitor: &mut V) {
+        for n in self.iter() {
+            n.accept(visitor);
+        }
+    }
+    fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V) {
+        for n in self.iter_mut() {
+            n.accept_mut(visitor);
+        }
+    }
+}
+impl<'src, A: Visitable<'src>, B: Visitable<'src>> Visitable<'src> for (A, B) {
+    fn accept<V: Visit<'src> + ?Sized>(&self, visitor: &mut V) {
+        self.0.accept(visitor);
+        self.1.accept(visitor);
+    }
+    fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V) {
+        self.0.accept_mut(visitor);
+        self.1.accept_mut(visitor);
+    }
+}
+impl<'src, A, B, C, D> Visitable<'src> for (A, B, C, D)
+where
+    A: Visitable<'src>,
+    B: Visitable<'src>,
+    C: Visitable<'src>,
+    D: Visitable<'src>,
+{
+    fn accept<V: Visit<'src> + ?Sized>(&self, visitor: &mut V) {
+        self.0.accept(visitor);
+        self.1.accept(visitor);
+        self.2.accept(visitor);
+        self.3.accept(visitor);
+    }
+    fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V) {
+        self.0.accept_mut(visitor);
+        self.1.accept_mut(visitor);
+        self.2.accept_mut(visitor);
+        self.3.accept_mut(visitor);
+    }
+}
+macro_rules! leaf_visitable {
+    ($($t:ty),*) => {
+        $(
+            impl<'src> Visitable<'src> for $t {
+                fn accept<V: Visit<'src> + ?Sized>(&self, _visitor: &mut V) {}
+                fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, _visitor: &mut V) {}
+            }
+        )*
+    };
+}
+leaf_visitable!(
+    bool, f64, char, string::String, Str<'src>,
+    KeywordData, KeywordSuffixData, KeywordWrappedData, PositionRange, Separators<'src>
+);
+
+// Generates the visitor traits, and dispatches each node struct to its method.
+macro_rules! visitors {
+    ($($module:ident::$node:ident: $method:ident,)*) => {
+        pub trait Visit<'src> {
+            $(
+                fn $method(&mut self, node: &$module::$node<'src>) {
+                    node.walk(self)
+                }
+            )*
+        }
+
+        pub trait VisitMut<'src> {
+            $(
+                fn $method(&mut self, node: &mut $module::$node<'src>) {
+                    node.walk_mut(self)
+                }
+            )*
+        }
+
+        $(
+            impl<'src> Visitable<'src> for $module::$node<'src> {
+                fn accept<V: Visit<'src> + ?Sized>(&self, visitor: &mut V) {
+                    visitor.$method(self)
+                }
+                fn accept_mut<V: VisitMut<'src> + ?Sized>(&mut self, visitor: &mut V) {
+                    visitor.$method(self)
+                }
+            }
+        )*
+    };
+}
+
+// The list of node structs is collected from the `node!` declarations by
+// build.rs.
+include!(concat!(env!("OUT_DIR"), "/visitors.rs"));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
+
+    #[derive(Default)]
+    struct Names {
+        bindings: Vec<string::String>,
+        references: Vec<string::String>,
+        functions: usize,
+    }
+    impl<'src> Visit<'src> for Names {
+        fn visit_binding_identifier(&mut self, node: &general::BindingIdentifier<'src>) {
+            self.bindings.push(node.value.to_string());
+        }
+        fn visit_reference_identifier(&mut self, node: &general::ReferenceIdentifier<'src>) {
+            self.references.push(node.value.to_string());
+        }
+        fn visit_function_declaration(&mut self, node: &functions::FunctionDeclaration<'src>) {
+            self.functions += 1;
+            node.walk(self);
+        }
+    }
+
+    struct Rename;
+    impl<'src> VisitMut<'src> for Rename {
+        fn visit_reference_identifier(&mut self, node: &mut general::ReferenceIdentifier<'src>) {
+            node.value = node.value.to_uppercase().into();
+        }
+    }
+
+    fn parse(code: &str) -> root::Module<'_> {
+        Parser::new(code.into_tokenizer()).parse_module().unwrap()
+    }
+
+    #[test]
+    fn it_visits_every_node() {
+        let module = parse("
+            import a from 'x';
+            function f(b, {c = d}) { return a + b * c; }
+            export const g = [f(1), `${e}`, ...h];
+            switch (i) { case j: k.l = m; }
+        ");
+
+        let mut names = Names::default();
+        names.visit_module(&module);
+
+        assert_eq!(names.bindings, vec!["a", "f", "b", "c", "g"]);
+        assert_eq!(names.references, vec!["d", "a", "b", "c", "f", "e", "h", "i", "j", "k", "m"]);
+        assert_eq!(names.functions, 1);
+    }
+
+    #[test]
+    fn it_visits_mutably() {
+        let mut module = parse("let a = b(c.d);");
+
+        Rename.visit_module(&mut module);
+
+        assert_eq!(format!("{}", module), "let a=B(C.d);");
+    }
+}
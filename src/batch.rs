@@ -0,0 +1,409 @@
+//! Parsing many files at once, spread over a pool of threads.
+//!
+//! Files are handed out to the threads one at a time as they finish the
+//! previous one, and the report lists them sorted by path regardless of the
+//! order they finished in.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use time;
+
+use estree::Value;
+use parser::{self, Diagnostic, ParserOptions, SourceType};
+
+/// The extensions of the files that are parsed when searching directories.
+pub const EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchOptions {
+    // The source type of every file. Otherwise ".mjs", ".ts", ".mts" and
+    // ".tsx" files are parsed as modules and everything else as scripts.
+    pub source_type: Option<SourceType>,
+
+    // The options for parsing every file, other than the source type. JSX is
+    // also parsed in ".jsx" and ".tsx" files, TypeScript in ".ts", ".mts",
+    // ".cts" and ".tsx" files, and Flow in other files marked with "@flow".
+    pub parser: ParserOptions,
+
+    pub extensions: Vec<String>,
+
+    // The number of threads to parse on, or 0 for one per CPU.
+    pub threads: usize,
+}
+impl Default for BatchOptions {
+    fn default() -> BatchOptions {
+        BatchOptions {
+            source_type: None,
+            parser: ParserOptions::default(),
+            extensions: EXTENSIONS.iter().map(|&ext| ext.into()).collect(),
+            threads: 0,
+        }
+    }
+}
+impl BatchOptions {
+    pub fn source_type(&self, path: &Path) -> SourceType {
+        self.source_type.unwrap_or(match path.extension().and_then(|ext| ext.to_str()) {
+            Some("mjs") | Some("ts") | Some("mts") | Some("tsx") => SourceType::Module,
+            _ => SourceType::Script,
+        })
+    }
+
+    pub fn parser_options(&self, path: &Path, code: &str) -> ParserOptions {
+        let mut options = self.parser;
+        options.source_type = self.source_type(path);
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("jsx") => options.extensions.jsx = true,
+            Some("ts") | Some("mts") | Some("cts") => options.extensions.typescript = true,
+            Some("tsx") => {
+                options.extensions.jsx = true;
+                options.extensions.typescript = true;
+            }
+            _ => {}
+        }
+        if !options.extensions.typescript && parser::has_flow_pragma(code) {
+            options.extensions.flow = true;
+        }
+        options
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileError {
+    Read(String),
+    Syntax(Diagnostic),
+
+    // The parser panicked, which is always a bug in the parser.
+    Panic(String),
+}
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FileError::Read(ref message) => write!(f, "{}", message),
+            FileError::Syntax(ref diagnostic) => write!(f, "{}", diagnostic),
+            FileError::Panic(ref message) => write!(f, "Parser panicked: {}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub source_type: SourceType,
+    pub bytes: usize,
+
+    // The time spent parsing the file, in nanoseconds.
+    pub time: u64,
+
+    pub error: Option<FileError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchReport {
+    pub files: Vec<FileReport>,
+    pub threads: usize,
+
+    // The time from start to finish, in nanoseconds.
+    pub time: u64,
+}
+impl BatchReport {
+    pub fn errors(&self) -> impl Iterator<Item = (&Path, &FileError)> {
+        self.files.iter().filter_map(|file| file.error.as_ref().map(|error| (&*file.path, error)))
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.files.iter().map(|file| file.bytes).sum()
+    }
+
+    /// List the errors, or every file along with its time if `timings` is
+    /// set, followed by a summary.
+    pub fn to_text(&self, timings: bool) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            match (file.error.as_ref(), timings) {
+                (Some(error), true) => out.push_str(&format!("{:>10}  {}: {}\n", format_ns(file.time), file.path.display(), error)),
+                (Some(error), false) => out.push_str(&format!("{}: {}\n", file.path.display(), error)),
+                (None, true) => out.push_str(&format!("{:>10}  {}\n", format_ns(file.time), file.path.display())),
+                (None, false) => {}
+            }
+        }
+
+        let errors = self.errors().count();
+        out.push_str(&format!(
+            "{} files, {:.2} MB in {} on {} thread{}, {} with errors\n",
+            self.files.len(),
+            self.bytes() as f64 / 1e6,
+            format_ns(self.time),
+            self.threads,
+            if self.threads == 1 { "" } else { "s" },
+            errors,
+        ));
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let files = self.files.iter().map(|file| {
+            Value::Object(vec![
+                ("path".into(), file.path.to_string_lossy().into_owned().into()),
+                ("sourceType".into(), match file.source_type {
+                    SourceType::Script => "script",
+                    SourceType::Module => "module",
+                    SourceType::Unambiguous => "unambiguous",
+                }.into()),
+                ("bytes".into(), file.bytes.into()),
+                ("time_ns".into(), (file.time as f64).into()),
+                ("error".into(), file.error.as_ref().map(error_value).into()),
+            ])
+        });
+
+        Value::Object(vec![
+            ("files".into(), Value::Array(files.collect())),
+            ("threads".into(), self.threads.into()),
+            ("time_ns".into(), (self.time as f64).into()),
+        ]).to_string()
+    }
+}
+
+fn error_value(error: &FileError) -> Value {
+    let kind = match *error {
+        FileError::Read(_) => "read",
+        FileError::Syntax(_) => "syntax",
+        FileError::Panic(_) => "panic",
+    };
+    let mut fields = vec![("kind".into(), kind.into())];
+    match *error {
+        FileError::Syntax(ref diagnostic) => {
+            fields.push(("message".into(), diagnostic.message.clone().into()));
+            fields.push(("line".into(), diagnostic.range.start.line.into()));
+            fields.push(("column".into(), diagnostic.range.start.column.into()));
+        }
+        FileError::Read(ref message) | FileError::Panic(ref message) => {
+            fields.push(("message".into(), message.clone().into()));
+        }
+    }
+    Value::Object(fields)
+}
+
+fn format_ns(ns: u64) -> String {
+    format!("{:.3}ms", ns as f64 / 1e6)
+}
+
+/// Find the files to parse. Directories are searched recursively for files
+/// with one of the extensions, while other paths are kept as they are. The
+/// result is sorted and free of duplicates.
+pub fn find_files<P: AsRef<Path>>(paths: &[P], extensions: &[String]) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths {
+        let path = path.as_ref();
+        let found = fs::metadata(path).and_then(|metadata| {
+            if metadata.is_dir() {
+                find_in_dir(path, extensions, &mut files)
+            } else {
+                files.push(path.to_path_buf());
+                Ok(())
+            }
+        });
+        found.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn find_in_dir(dir: &Path, extensions: &[String], files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        // Symlinks are not followed, so that they cannot form cycles.
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            find_in_dir(&path, extensions, files)?;
+        } else if file_type.is_file() {
+            let ext = path.extension().and_then(|ext| ext.to_str());
+            if ext.is_some_and(|ext| extensions.iter().any(|e| e == ext)) {
+                files.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse the files under the given paths, as found by `find_files`.
+pub fn parse_paths<P: AsRef<Path>>(paths: &[P], options: &BatchOptions) -> io::Result<BatchReport> {
+    let files = find_files(paths, &options.extensions)?;
+    Ok(parse_files(files, options))
+}
+
+/// Parse the given files concurrently, reporting on them in sorted order.
+pub fn parse_files(mut paths: Vec<PathBuf>, options: &BatchOptions) -> BatchReport {
+    paths.sort();
+
+    let threads = match options.threads {
+        0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        n => n,
+    }.min(paths.len()).max(1);
+
+    let start = time::precise_time_ns();
+    let next = AtomicUsize::new(0);
+    let reports = Mutex::new(vec![]);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut done = vec![];
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    match paths.get(index) {
+                        Some(path) => done.push((index, parse_file(path, options))),
+                        None => break,
+                    }
+                }
+                reports.lock().unwrap().extend(done);
+            });
+        }
+    });
+    let time = time::precise_time_ns() - start;
+
+    let mut reports = reports.into_inner().unwrap();
+    reports.sort_by_key(|&(index, _)| index);
+
+    BatchReport {
+        files: reports.into_iter().map(|(_, report)| report).collect(),
+        threads,
+        time,
+    }
+}
+
+fn parse_file(path: &Path, options: &BatchOptions) -> FileReport {
+    let mut report = FileReport {
+        path: path.to_path_buf(),
+        source_type: options.source_type(path),
+        bytes: 0,
+        time: 0,
+        error: None,
+    };
+
+    let code = match fs::read_to_string(path) {
+        Ok(code) => code,
+        Err(error) => {
+            report.error = Some(FileError::Read(error.to_string()));
+            return report;
+        }
+    };
+    report.bytes = code.len();
+    let parser_options = options.parser_options(path, &code);
+
+    let start = time::precise_time_ns();
+    let result = panic::catch_unwind(|| parser::parse(&code, &parser_options).map(|_| ()));
+    report.time = time::precise_time_ns() - start;
+
+    report.error = match result {
+        Ok(Ok(())) => None,
+        Ok(Err(diagnostic)) => Some(FileError::Syntax(diagnostic)),
+        Err(payload) => {
+            let message = match payload.downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => payload.downcast_ref::<String>().cloned().unwrap_or_default(),
+            };
+            Some(FileError::Panic(message))
+        }
+    };
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    // A directory of files that is removed once the test is done.
+    struct TempDir(PathBuf);
+    impl TempDir {
+        fn new(name: &str, files: &[(&str, &str)]) -> TempDir {
+            let dir = env::temp_dir().join(format!("jsparse-{}-{}", name, process::id()));
+            for &(path, code) in files {
+                let path = dir.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, code).unwrap();
+            }
+            TempDir(dir)
+        }
+    }
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn it_finds_files_by_extension() {
+        let dir = TempDir::new("find", &[
+            ("b.js", ""),
+            ("a/c.mjs", ""),
+            ("a/d.cjs", ""),
+            ("a/e.ts", ""),
+            ("a/f/g.jsx", ""),
+            ("README.md", ""),
+        ]);
+
+        let files = find_files(&[&dir.0], &BatchOptions::default().extensions).unwrap();
+        let files: Vec<_> = files.iter().map(|path| path.strip_prefix(&dir.0).unwrap().to_path_buf()).collect();
+        assert_eq!(files, vec![
+            PathBuf::from("a/c.mjs"),
+            PathBuf::from("a/d.cjs"),
+            PathBuf::from("a/e.ts"),
+            PathBuf::from("a/f/g.jsx"),
+            PathBuf::from("b.js"),
+        ]);
+    }
+
+    #[test]
+    fn it_reports_files_in_order() {
+        let mut files = vec![("lib/module.mjs", "export default 1;"), ("lib/bad.js", "var = 1;")];
+        let names: Vec<_> = (0..20).map(|i| format!("src/{:02}.js", i)).collect();
+        files.extend(names.iter().map(|name| (&name[..], "a + b;")));
+        let dir = TempDir::new("order", &files);
+
+        let options = BatchOptions {
+            threads: 4,
+            ..BatchOptions::default()
+        };
+        let report = parse_paths(&[&dir.0], &options).unwrap();
+
+        assert_eq!(report.threads, 4);
+        assert_eq!(report.files.len(), 22);
+        assert!(report.files.windows(2).all(|pair| pair[0].path < pair[1].path));
+        assert_eq!(report.files[1].source_type, SourceType::Module);
+        assert_eq!(report.files[1].error, None);
+
+        let errors: Vec<_> = report.errors().map(|(path, error)| (path.to_path_buf(), error.to_string())).collect();
+        assert_eq!(errors, vec![(dir.0.join("lib/bad.js"), "UnexpectedToken (1:4)".to_string())]);
+        assert!(report.to_text(false).ends_with(" on 4 threads, 1 with errors\n"));
+    }
+
+    #[test]
+    fn it_parses_deeply_nested_files() {
+        let source = format!("{}a{};", "(".repeat(1000), ")".repeat(1000));
+        let dir = TempDir::new("nested", &[("nested.js", &source[..])]);
+
+        let report = parse_paths(&[&dir.0], &BatchOptions::default()).unwrap();
+        assert_eq!(report.files[0].error, None);
+    }
+
+    #[test]
+    fn it_reports_unreadable_files() {
+        let report = parse_files(vec![PathBuf::from("/nonexistent/a.js")], &BatchOptions::default());
+        assert_eq!(report.threads, 1);
+        match report.files[0].error {
+            Some(FileError::Read(_)) => {}
+            ref error => panic!("Expected a read error, got {:?}", error),
+        }
+    }
+}
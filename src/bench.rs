@@ -0,0 +1,296 @@
+//! Measuring the throughput of the tokenizer and parser.
+//!
+//! Tokenizing depends on the parser to know whether a "/" starts a regular
+//! expression, so each file is first parsed once while recording the hint
+//! the parser gave for every token. The tokenize phase then replays those
+//! hints against a bare tokenizer, timing the tokenizer on its own.
+
+use std::hint::black_box;
+
+use failure::Error;
+use time;
+
+use estree::Value;
+use parser::{self, Parser, ParserOptions};
+use tokenizer::{tokens, Hint, IntoTokenizer, TokenRange, Tokenizer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchOptions {
+    // Runs of every phase that are timed.
+    pub iterations: usize,
+
+    // Runs of every phase before timing starts, to warm up caches.
+    pub warmup: usize,
+}
+impl Default for BenchOptions {
+    fn default() -> BenchOptions {
+        BenchOptions {
+            iterations: 10,
+            warmup: 2,
+        }
+    }
+}
+
+/// A named piece of code to benchmark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchFile {
+    pub name: String,
+    pub code: String,
+    pub options: ParserOptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Tokenize,
+    Parse,
+}
+impl Phase {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Phase::Tokenize => "tokenize",
+            Phase::Parse => "parse",
+        }
+    }
+}
+
+/// The time taken to run a phase over every file, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTimes {
+    pub phase: Phase,
+    pub min: u64,
+    pub median: u64,
+    pub max: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchReport {
+    pub files: usize,
+    pub bytes: usize,
+
+    // Tokens read by the tokenizer, including whitespace and comments.
+    pub tokens: usize,
+
+    pub iterations: usize,
+    pub warmup: usize,
+    pub phases: Vec<PhaseTimes>,
+}
+impl BenchReport {
+    /// Megabytes of code processed per second, at the median time.
+    pub fn mb_per_second(&self, times: &PhaseTimes) -> f64 {
+        self.bytes as f64 / 1e6 / seconds(times.median)
+    }
+
+    /// Tokens processed per second, at the median time.
+    pub fn tokens_per_second(&self, times: &PhaseTimes) -> f64 {
+        self.tokens as f64 / seconds(times.median)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "{} files, {:.2} MB, {} tokens, {} iterations after {} warmup\n",
+            self.files,
+            self.bytes as f64 / 1e6,
+            self.tokens,
+            self.iterations,
+            self.warmup,
+        );
+        for times in &self.phases {
+            out.push_str(&format!(
+                "{:<10} min {:>10}  median {:>10}  max {:>10}  {:>9.2} MB/s  {:>7.2}M tokens/s\n",
+                times.phase.name(),
+                format_ns(times.min),
+                format_ns(times.median),
+                format_ns(times.max),
+                self.mb_per_second(times),
+                self.tokens_per_second(times) / 1e6,
+            ));
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let phases = self.phases.iter().map(|times| {
+            Value::Object(vec![
+                ("phase".into(), times.phase.name().into()),
+                ("min_ns".into(), (times.min as f64).into()),
+                ("median_ns".into(), (times.median as f64).into()),
+                ("max_ns".into(), (times.max as f64).into()),
+                ("mb_per_second".into(), self.mb_per_second(times).into()),
+                ("tokens_per_second".into(), self.tokens_per_second(times).into()),
+            ])
+        });
+
+        Value::Object(vec![
+            ("files".into(), self.files.into()),
+            ("bytes".into(), self.bytes.into()),
+            ("tokens".into(), self.tokens.into()),
+            ("iterations".into(), self.iterations.into()),
+            ("warmup".into(), self.warmup.into()),
+            ("phases".into(), Value::Array(phases.collect())),
+        ]).to_string()
+    }
+}
+
+/// Time tokenizing and parsing the given files.
+pub fn bench(files: &[BenchFile], options: &BenchOptions) -> Result<BenchReport, Error> {
+    if options.iterations == 0 {
+        bail!("At least one iteration is required");
+    }
+
+    let hints = files.iter()
+        .map(|file| record_hints(&file.code, &file.options).map_err(|e| format_err!("{}: {}", file.name, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tokenize = time_phase(Phase::Tokenize, options, || {
+        for (file, hints) in files.iter().zip(&hints) {
+            replay_hints(&file.code, hints);
+        }
+    });
+    let parse = time_phase(Phase::Parse, options, || {
+        for file in files {
+            let _ = black_box(parser::parse(&file.code, &file.options));
+        }
+    });
+
+    Ok(BenchReport {
+        files: files.len(),
+        bytes: files.iter().map(|file| file.code.len()).sum(),
+        // The last token of each file is the end of the file.
+        tokens: hints.iter().map(|hints| hints.len() - 1).sum(),
+        iterations: options.iterations,
+        warmup: options.warmup,
+        phases: vec![tokenize, parse],
+    })
+}
+
+fn time_phase<F: FnMut()>(phase: Phase, options: &BenchOptions, mut run: F) -> PhaseTimes {
+    for _ in 0..options.warmup {
+        run();
+    }
+
+    let mut times: Vec<u64> = (0..options.iterations).map(|_| {
+        let start = time::precise_time_ns();
+        run();
+        time::precise_time_ns() - start
+    }).collect();
+    times.sort();
+
+    PhaseTimes {
+        phase,
+        min: times[0],
+        median: times[times.len() / 2],
+        max: times[times.len() - 1],
+    }
+}
+
+fn seconds(ns: u64) -> f64 {
+    ns.max(1) as f64 / 1e9
+}
+
+fn format_ns(ns: u64) -> String {
+    format!("{:.3}ms", ns as f64 / 1e6)
+}
+
+// Parse the code, returning the hint the parser gave for each token it read.
+// Tokens read by speculative parses that were rewound are left out, as they
+// are by `parser::tokenize`.
+fn record_hints(code: &str, options: &ParserOptions) -> Result<Vec<Hint>, Error> {
+    let hints = parser::try_source_types(options, |options| {
+        let mut p = Parser::with_options(code.into_tokenizer(), options);
+        p.record_tokens();
+        if let Err(error) = p.parse_source() {
+            return Err(p.diagnostic(&error));
+        }
+
+        Ok(p.take_recorded_tokens().into_iter().map(|(_, _, hint)| hint).collect())
+    })?;
+    Ok(hints)
+}
+
+fn replay_hints(code: &str, hints: &[Hint]) {
+    let mut tok = code.into_tokenizer();
+    let mut token = tokens::EOFToken {}.into();
+    let mut range = TokenRange::default();
+    for hint in hints {
+        tok.next_token(hint, (&mut token, &mut range));
+    }
+    black_box(&token);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::SyntaxExtensions;
+
+    fn file(code: &str) -> BenchFile {
+        BenchFile {
+            name: "test.js".into(),
+            code: code.into(),
+            options: ParserOptions::default(),
+        }
+    }
+
+    #[test]
+    fn it_replays_tokens() {
+        // The regular expression is only tokenized correctly with the
+        // parser's hints.
+        let code = "var a = /b/g.test(c) / 2; `x${a}y`;";
+        let hints = record_hints(code, &ParserOptions::default()).unwrap();
+        let (tokens, error) = parser::tokenize(code, &Default::default());
+        assert!(error.is_none());
+        let recorded: Vec<_> = tokens.collect();
+
+        let mut tok = code.into_tokenizer();
+        let mut replayed = vec![];
+        for hint in &hints {
+            let mut token = tokens::EOFToken {}.into();
+            let mut range = TokenRange::default();
+            tok.next_token(hint, (&mut token, &mut range));
+            replayed.push((token, range));
+        }
+        assert_eq!(replayed.pop().map(|(token, _)| token), Some(tokens::EOFToken {}.into()));
+        assert_eq!(replayed, recorded);
+    }
+
+    #[test]
+    fn it_reports_every_phase() {
+        let options = BenchOptions {
+            iterations: 3,
+            warmup: 1,
+        };
+        let report = bench(&[file("a + b;"), file("c;")], &options).unwrap();
+
+        assert_eq!((report.files, report.bytes, report.tokens), (2, 8, 8));
+        assert_eq!(report.phases.iter().map(|times| times.phase).collect::<Vec<_>>(), vec![Phase::Tokenize, Phase::Parse]);
+        for times in &report.phases {
+            assert!(times.min <= times.median && times.median <= times.max);
+        }
+        assert!(report.to_text().starts_with("2 files, 0.00 MB, 8 tokens, 3 iterations after 1 warmup\n"));
+        assert!(report.to_json().starts_with("{\"files\":2,\"bytes\":8,\"tokens\":8,"));
+    }
+
+    #[test]
+    fn it_counts_tokens_of_speculative_parses_once() {
+        // Type arguments and arrow functions are parsed speculatively in
+        // TypeScript, reading some tokens again once a parse is given up on.
+        let code = include_str!("../tests/fixtures/typescript/generics/input.js");
+        let options = ParserOptions {
+            extensions: SyntaxExtensions {
+                typescript: true,
+                ..SyntaxExtensions::default()
+            },
+            ..ParserOptions::default()
+        };
+        let report = bench(&[BenchFile { name: "generics.ts".into(), code: code.into(), options }], &Default::default()).unwrap();
+
+        let (tokens, error) = parser::tokenize(code, &options);
+        assert!(error.is_none());
+        assert_eq!(report.tokens, tokens.count());
+    }
+
+    #[test]
+    fn it_reports_parse_errors() {
+        let error = bench(&[file("a;"), file("b +;")], &Default::default()).unwrap_err();
+        assert_eq!(error.to_string(), "test.js: UnexpectedToken (1:3)");
+    }
+}
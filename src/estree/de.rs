@@ -0,0 +1,2333 @@
+use std::fmt;
+
+use failure::Fail;
+use ucd::Codepoint;
+
+use ast::{NodeChildren, NodePosition, PositionRange, NodeBox};
+use ast::{alias, classes, decorators, expression, functions, general, jsx, literal, modules, objects, patterns,
+          root, statement};
+use estree::json::{self, Value};
+
+/// An error from converting JSON into jsparse's AST, usually because the JSON
+/// uses a node or shape that jsparse has no equivalent for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializeError {
+    pub message: String,
+}
+impl Fail for DeserializeError {}
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+impl From<json::SyntaxError> for DeserializeError {
+    fn from(err: json::SyntaxError) -> DeserializeError {
+        DeserializeError {
+            message: err.to_string(),
+        }
+    }
+}
+
+type Result<T> = ::std::result::Result<T, DeserializeError>;
+
+fn error<T, S: Into<String>>(message: S) -> Result<T> {
+    Err(DeserializeError {
+        message: message.into(),
+    })
+}
+
+/// Build an AST from an ESTree or Babel JSON string.
+pub fn from_json(text: &str) -> Result<root::Ast<'static>> {
+    from_value(&json::parse(text)?)
+}
+
+/// Build an AST from an ESTree "Program" or a Babel "File" value.
+///
+/// Nodes keep the positions given by "start" and "end", or by "range", along
+/// with "loc". These are taken as-is, so for source text outside of ASCII the
+/// offsets and columns count UTF-16 code units, as ESTree does.
+pub fn from_value(value: &Value) -> Result<root::Ast<'static>> {
+    let node = Node::new(value)?;
+    match node.kind {
+        "File" => from_value(node.field("program")?),
+        "Program" => program(&node),
+        _ => node.unexpected("a Program"),
+    }
+}
+
+// Build a node from its child fields, leaving its tokens and position empty.
+// The position is filled in by "located" from the JSON node it was read from.
+macro_rules! build {
+    ($($ty:ident)::+ { $($field:ident: $value:expr),* $(,)* }) => {
+        build!($($ty)::+ { $($field: $value),*; })
+    };
+    ($($ty:ident)::+ { $($field:ident: $value:expr),* $(,)*; $($token:ident),* }) => {
+        $($ty)::+ {
+            $($field: $value,)*
+            $($token: Default::default(),)*
+            position: None,
+            comments: None,
+        }
+    };
+}
+
+// An object value with a "type" field.
+#[derive(Clone, Copy)]
+struct Node<'a> {
+    value: &'a Value,
+    kind: &'a str,
+}
+impl<'a> Node<'a> {
+    fn new(value: &'a Value) -> Result<Node<'a>> {
+        match value.get("type").and_then(Value::as_str) {
+            Some(kind) => Ok(Node { value, kind }),
+            None => error(format!("Expected a node, found {}", value)),
+        }
+    }
+
+    // A field that must be present and not null.
+    fn field(&self, key: &str) -> Result<&'a Value> {
+        match self.opt(key) {
+            Some(value) => Ok(value),
+            None => error(format!("Missing \"{}\" in {}", key, self.kind)),
+        }
+    }
+
+    fn opt(&self, key: &str) -> Option<&'a Value> {
+        self.value.get(key).filter(|value| !value.is_null())
+    }
+
+    fn node(&self, key: &str) -> Result<Node<'a>> {
+        Node::new(self.field(key)?)
+    }
+
+    fn opt_node(&self, key: &str) -> Result<Option<Node<'a>>> {
+        self.opt(key).map(Node::new).transpose()
+    }
+
+    fn str(&self, key: &str) -> Result<&'a str> {
+        match self.field(key)?.as_str() {
+            Some(s) => Ok(s),
+            None => error(format!("Expected \"{}\" in {} to be a string", key, self.kind)),
+        }
+    }
+
+    // A boolean flag, where a missing flag is false.
+    fn flag(&self, key: &str) -> bool {
+        self.opt(key).and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    fn array(&self, key: &str) -> Result<&'a [Value]> {
+        match self.field(key)?.as_array() {
+            Some(items) => Ok(items),
+            None => error(format!("Expected \"{}\" in {} to be an array", key, self.kind)),
+        }
+    }
+
+    // An array that may be left out.
+    fn opt_array(&self, key: &str) -> Result<&'a [Value]> {
+        match self.opt(key) {
+            Some(_) => self.array(key),
+            None => Ok(&[]),
+        }
+    }
+
+    fn nodes(&self, key: &str) -> Result<Vec<Node<'a>>> {
+        self.array(key)?.iter().map(Node::new).collect()
+    }
+
+    // The raw source text of a literal, from ESTree's "raw" or Babel's "extra".
+    fn raw(&self) -> Option<&'a str> {
+        self.opt("raw")
+            .or_else(|| self.opt("extra").and_then(|extra| extra.get("raw")))
+            .and_then(Value::as_str)
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        error(format!("Expected {}, found {}", expected, self.kind))
+    }
+
+    fn position(&self) -> Option<NodePosition> {
+        let offset = |value: &Value| value.as_f64().filter(|n| *n >= 0.0).map(|n| n as usize);
+        let (start, end) = match (self.opt("start").and_then(offset), self.opt("end").and_then(offset)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => match self.opt("range").and_then(Value::as_array) {
+                Some([start, end]) => (offset(start)?, offset(end)?),
+                _ => return None,
+            },
+        };
+
+        let loc = self.opt("loc")?;
+        let point = |key: &str| {
+            let point = loc.get(key)?;
+            Some((offset(point.get("line")?)?, offset(point.get("column")?)?))
+        };
+
+        Some(NodePosition {
+            start,
+            end,
+            range: PositionRange {
+                start: point("start")?,
+                end: point("end")?,
+            },
+            in_flow_comment: false,
+        })
+    }
+}
+
+// Give a newly built node the position of the JSON node it was read from,
+// unless it was taken from a child that already has its own.
+fn located<T: NodeChildren<'static>>(node: &Node, mut value: T) -> T {
+    if let Some(position) = node.position() {
+        let mut nodes = vec![];
+        value.collect_mut(&mut nodes);
+        if let Some(built) = nodes.into_iter().next() {
+            if built.position().is_none() {
+                built.set_position(Some(position));
+            }
+        }
+    }
+    value
+}
+
+// Split a list into the leading items, which are followed by commas, and the
+// last item, as most jsparse lists are stored.
+fn split_last<T>(mut items: Vec<T>) -> (Vec<(T, ::ast::KeywordData)>, Option<T>) {
+    let last = items.pop();
+    (items.into_iter().map(|item| (item, Default::default())).collect(), last)
+}
+
+// Remove the quotes around the raw text of a string literal.
+fn unquote(raw: &str) -> Option<&str> {
+    let quote = raw.chars().next()?;
+    if raw.len() >= 2 && (quote == '"' || quote == '\'') && raw.ends_with(quote) {
+        Some(&raw[1..raw.len() - 1])
+    } else {
+        None
+    }
+}
+
+// Strings are stored in their escaped source form, so values without any raw
+// text need their backslashes escaped.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\")
+}
+
+
+// Programs and statements
+
+fn program(node: &Node) -> Result<root::Ast<'static>> {
+    let (directives, body) = prologue(node)?;
+
+    Ok(located(node, if node.opt("sourceType").and_then(Value::as_str) == Some("module") {
+        let body = body.iter().map(module_item).collect::<Result<_>>()?;
+        build!(root::Module { directives: directives, body: body }).into()
+    } else {
+        let body = body.iter().map(statement_item).collect::<Result<_>>()?;
+        build!(root::Script { directives: directives, body: body }).into()
+    }))
+}
+
+// Split the directives from the rest of a program or function body. ESTree
+// marks them with a "directive" field, while Babel stores them separately.
+fn prologue<'a>(node: &Node<'a>) -> Result<(Vec<functions::Directive<'static>>, Vec<Node<'a>>)> {
+    let mut directives = node
+        .opt_array("directives")?
+        .iter()
+        .map(|value| {
+            let literal = Node::new(value)?.node("value")?;
+            let value = match literal.raw().and_then(unquote) {
+                Some(raw) => raw.to_string(),
+                None => escape(literal.str("value")?),
+            };
+            Ok(directive(value))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut body = node.nodes("body")?;
+    let count = body
+        .iter()
+        .take_while(|item| item.kind == "ExpressionStatement" && item.opt("directive").is_some())
+        .count();
+    for item in body.drain(..count) {
+        directives.push(directive(item.str("directive")?.to_string()));
+    }
+
+    Ok((directives, body))
+}
+
+fn directive(value: String) -> functions::Directive<'static> {
+    build!(functions::Directive {
+        value: build!(functions::DirectiveLiteral { value: value.into(); tokens_prefix }),
+        ; token_semi
+    })
+}
+
+fn module_item(node: &Node) -> Result<alias::ModuleStatementItem<'static>> {
+    Ok(located(node, match node.kind {
+        "ImportDeclaration" => import_declaration(node)?,
+        "ExportNamedDeclaration" => export_named_declaration(node)?,
+        "ExportDefaultDeclaration" => export_default_declaration(node)?,
+        "ExportAllDeclaration" => {
+            let source = string_literal(&node.node("source")?)?;
+            match node.opt_node("exported")? {
+                Some(exported) => build!(modules::ExportNamespace {
+                    namespace: module_identifier(&exported)?,
+                    source: source;
+                    token_export, token_star, token_as, token_from, token_semi
+                }).into(),
+                None => build!(modules::ExportAllSpecifiers {
+                    source: source;
+                    token_export, token_star, token_from, token_semi
+                }).into(),
+            }
+        }
+        _ => statement_item(node)?.into(),
+    }))
+}
+
+fn statement_item(node: &Node) -> Result<alias::StatementItem<'static>> {
+    Ok(match node.kind {
+        "FunctionDeclaration" => function_declaration(node)?.into(),
+        "ClassDeclaration" => class_declaration(node)?.into(),
+        "VariableDeclaration" => match node.str("kind")? {
+            "let" => let_declaration(node)?.into(),
+            "const" => const_declaration(node)?.into(),
+            _ => variable_statement(node)?.into(),
+        },
+        _ => statement(node)?.into(),
+    })
+}
+
+fn statement(node: &Node) -> Result<alias::Statement<'static>> {
+    Ok(located(node, match node.kind {
+        "BlockStatement" => block(node)?.into(),
+        "EmptyStatement" => build!(statement::EmptyStatement { ; token_semi }).into(),
+        "DebuggerStatement" => build!(statement::DebuggerStatement { ; token_debugger, token_semi }).into(),
+        // Annex B's function declarations as the body of an "if" statement
+        // or a label, since those in statement lists are read as items.
+        "FunctionDeclaration" => function_declaration(node)?.into(),
+        "ExpressionStatement" => build!(statement::ExpressionStatement {
+            expression: expression(&node.node("expression")?)?;
+            token_prefix, token_semi
+        }).into(),
+        "VariableDeclaration" if node.str("kind")? == "var" => variable_statement(node)?.into(),
+        "IfStatement" => {
+            let test = expression(&node.node("test")?)?;
+            let consequent = NodeBox::new(statement(&node.node("consequent")?)?);
+            match node.opt_node("alternate")? {
+                Some(alternate) => build!(statement::IfElseStatement {
+                    test: test,
+                    consequent: consequent,
+                    alternate: NodeBox::new(statement(&alternate)?);
+                    token_if, token_paren_l, token_paren_r, token_else
+                }).into(),
+                None => build!(statement::IfStatement {
+                    test: test,
+                    consequent: consequent;
+                    token_if, token_paren_l, token_paren_r
+                }).into(),
+            }
+        }
+        "ForStatement" => {
+            let init = match node.opt_node("init")? {
+                Some(init) => Some(match (init.kind, init.opt("kind").and_then(Value::as_str)) {
+                    ("VariableDeclaration", Some("let")) => let_declaration(&init)?.into(),
+                    ("VariableDeclaration", Some("const")) => const_declaration(&init)?.into(),
+                    ("VariableDeclaration", _) => variable_statement(&init)?.into(),
+                    _ => statement::ForInit::Expression(expression(&init)?),
+                }),
+                None => None,
+            };
+            build!(statement::ForStatement {
+                init: init,
+                test: node.opt_node("test")?.map(|test| expression(&test)).transpose()?,
+                update: node.opt_node("update")?.map(|update| expression(&update)).transpose()?,
+                body: NodeBox::new(statement(&node.node("body")?)?);
+                token_for, token_paren_l, token_init_semi, token_test_semi, token_paren_r
+            }).into()
+        }
+        "ForInStatement" => {
+            let left = node.node("left")?;
+            let left = match for_declaration(&left)? {
+                Some(("var", pattern, init)) => build!(statement::ForInVarPattern {
+                    pattern: pattern,
+                    init: init;
+                    token_var
+                }).into(),
+                Some(("let", pattern, None)) => build!(statement::ForLetPattern { pattern: pattern; token_let }).into(),
+                Some(("const", pattern, None)) => {
+                    build!(statement::ForConstPattern { pattern: pattern; token_const }).into()
+                }
+                Some(_) => return error("Only \"var\" declarations in a for-in loop may have an initializer"),
+                None => statement::ForInInit::Complex(complex_assign(&left)?),
+            };
+            build!(statement::ForInStatement {
+                left: left,
+                right: NodeBox::new(expression(&node.node("right")?)?),
+                body: NodeBox::new(statement(&node.node("body")?)?);
+                token_for, token_paren_l, token_in, token_paren_r
+            }).into()
+        }
+        "ForOfStatement" => {
+            let left = node.node("left")?;
+            let left = match for_declaration(&left)? {
+                Some((_, _, Some(_))) => return error("Declarations in a for-of loop may not have an initializer"),
+                Some(("var", pattern, None)) => build!(statement::ForVarPattern { pattern: pattern; token_var }).into(),
+                Some(("let", pattern, None)) => build!(statement::ForLetPattern { pattern: pattern; token_let }).into(),
+                Some((_, pattern, None)) => build!(statement::ForConstPattern { pattern: pattern; token_const }).into(),
+                None => statement::ForOfInit::Complex(complex_assign(&left)?),
+            };
+            let right = NodeBox::new(expression(&node.node("right")?)?);
+            let body = NodeBox::new(statement(&node.node("body")?)?);
+
+            if node.flag("await") {
+                build!(statement::ForAwaitStatement {
+                    left: left,
+                    right: right,
+                    body: body;
+                    token_for, token_await, token_paren_l, token_of, token_paren_r
+                }).into()
+            } else {
+                build!(statement::ForOfStatement {
+                    left: left,
+                    right: right,
+                    body: body;
+                    token_for, token_paren_l, token_of, token_paren_r
+                }).into()
+            }
+        }
+        "WhileStatement" => build!(statement::WhileStatement {
+            test: NodeBox::new(expression(&node.node("test")?)?),
+            body: NodeBox::new(statement(&node.node("body")?)?);
+            token_while, token_paren_l, token_paren_r
+        }).into(),
+        "DoWhileStatement" => build!(statement::DoWhileStatement {
+            body: NodeBox::new(statement(&node.node("body")?)?),
+            test: NodeBox::new(expression(&node.node("test")?)?);
+            token_do, token_while, token_paren_l, token_paren_r, token_semi
+        }).into(),
+        "SwitchStatement" => {
+            let cases = node
+                .nodes("cases")?
+                .iter()
+                .map(|case| {
+                    let consequent = case.nodes("consequent")?.iter().map(statement_item).collect::<Result<_>>()?;
+                    Ok(match case.opt_node("test")? {
+                        Some(test) => build!(statement::SwitchCase {
+                            test: NodeBox::new(expression(&test)?),
+                            consequent: consequent;
+                            token_case, token_colon
+                        }).into(),
+                        None => build!(statement::SwitchDefault {
+                            consequent: consequent;
+                            token_default, token_colon
+                        }).into(),
+                    })
+                })
+                .collect::<Result<_>>()?;
+
+            build!(statement::SwitchStatement {
+                discriminant: NodeBox::new(expression(&node.node("discriminant")?)?),
+                cases: cases;
+                token_switch, token_paren_l, token_paren_r, token_curly_l, token_curly_r
+            }).into()
+        }
+        "WithStatement" => build!(statement::WithStatement {
+            object: NodeBox::new(expression(&node.node("object")?)?),
+            body: NodeBox::new(statement(&node.node("body")?)?);
+            token_with, token_paren_l, token_paren_r
+        }).into(),
+        "LabeledStatement" => build!(statement::LabelledStatement {
+            label: label(&node.node("label")?)?,
+            body: NodeBox::new(statement(&node.node("body")?)?);
+            tokens_prefix, token_colon
+        }).into(),
+        "ThrowStatement" => build!(statement::ThrowStatement {
+            argument: NodeBox::new(expression(&node.node("argument")?)?);
+            token_throw, token_semi
+        }).into(),
+        "TryStatement" => {
+            let body = block(&node.node("block")?)?;
+            let catch = match node.opt_node("handler")? {
+                Some(handler) => Some(build!(statement::CatchClause {
+                    param: match handler.opt_node("param")? {
+                        Some(param) => Some(build!(statement::CatchParam {
+                            argument: binding_pattern(&param)?;
+                            token_paren_l, type_annotation, token_paren_r
+                        })),
+                        None => None,
+                    },
+                    body: block(&handler.node("body")?)?;
+                    token_catch
+                })),
+                None => None,
+            };
+            let finalizer = node.opt_node("finalizer")?.map(|finalizer| block(&finalizer)).transpose()?;
+
+            match (catch, finalizer) {
+                (Some(catch), Some(finalizer)) => build!(statement::TryCatchFinallyStatement {
+                    body: body,
+                    catch: catch,
+                    finalizer: finalizer;
+                    token_try, token_finally
+                }).into(),
+                (Some(catch), None) => build!(statement::TryCatchStatement { body: body, catch: catch; token_try }).into(),
+                (None, Some(finalizer)) => build!(statement::TryFinallyStatement {
+                    body: body,
+                    finalizer: finalizer;
+                    token_try, token_finally
+                }).into(),
+                (None, None) => return error("TryStatement needs a handler or a finalizer"),
+            }
+        }
+        "ContinueStatement" => build!(statement::ContinueStatement {
+            label: label_value(node)?;
+            token_continue, token_semi
+        }).into(),
+        "BreakStatement" => build!(statement::BreakStatement {
+            label: label_value(node)?;
+            token_break, token_semi
+        }).into(),
+        "ReturnStatement" => build!(statement::ReturnStatement {
+            value: match node.opt_node("argument")? {
+                Some(argument) => Some(build!(statement::ReturnValue {
+                    expression: NodeBox::new(expression(&argument)?);
+                    token_prefix
+                })),
+                None => None,
+            };
+            token_return, token_semi
+        }).into(),
+        "ClassDeclaration" | "VariableDeclaration" => {
+            return error(format!("{} is not allowed as the body of a statement", node.kind))
+        }
+        _ => return node.unexpected("a statement"),
+    }))
+}
+
+fn block(node: &Node) -> Result<statement::BlockStatement<'static>> {
+    if node.kind != "BlockStatement" {
+        return node.unexpected("a BlockStatement");
+    }
+    Ok(located(node, build!(statement::BlockStatement {
+        body: node.nodes("body")?.iter().map(statement_item).collect::<Result<_>>()?;
+        token_curly_l, token_curly_r
+    })))
+}
+
+fn label(node: &Node) -> Result<statement::LabelIdentifier<'static>> {
+    Ok(located(node, build!(statement::LabelIdentifier { value: binding_name(node)?.into(), raw: None })))
+}
+
+fn label_value(node: &Node) -> Result<Option<statement::LabelValue<'static>>> {
+    match node.opt_node("label")? {
+        Some(id) => Ok(Some(build!(statement::LabelValue { label: label(&id)?; tokens_prefix }))),
+        None => Ok(None),
+    }
+}
+
+// The declarators of a "VariableDeclaration", as a binding pattern and an
+// optional initializer.
+fn declarators(node: &Node) -> Result<Vec<(patterns::BindingPattern<'static>, Option<general::Initializer<'static>>)>> {
+    let declarators = node.nodes("declarations")?;
+    if declarators.is_empty() {
+        return error("VariableDeclaration needs at least one declarator");
+    }
+    declarators
+        .iter()
+        .map(|declarator| {
+            let init = declarator.opt_node("init")?.map(|init| initializer(&init)).transpose()?;
+            Ok((binding_pattern(&declarator.node("id")?)?, init))
+        })
+        .collect()
+}
+
+fn variable_statement(node: &Node) -> Result<statement::VariableStatement<'static>> {
+    let declarators = declarators(node)?
+        .into_iter()
+        .map(|(id, init)| build!(statement::VariableDeclarator { id: id, init: init; definite, type_annotation }))
+        .collect();
+    let (declarators, last) = split_last(declarators);
+
+    Ok(located(node, build!(statement::VariableStatement {
+        declarators: declarators,
+        last_declarator: last.unwrap();
+        token_var, token_semi
+    })))
+}
+
+fn let_declaration(node: &Node) -> Result<statement::LetDeclaration<'static>> {
+    let declarators = declarators(node)?
+        .into_iter()
+        .map(|(id, init)| build!(statement::LetDeclarator { id: id, init: init; definite, type_annotation }))
+        .collect();
+    let (declarators, last) = split_last(declarators);
+
+    Ok(located(node, build!(statement::LetDeclaration {
+        declarators: declarators,
+        last_declarator: last.unwrap();
+        token_let, token_semi
+    })))
+}
+
+fn const_declaration(node: &Node) -> Result<statement::ConstDeclaration<'static>> {
+    let declarators = declarators(node)?
+        .into_iter()
+        .map(|(id, init)| match init {
+            Some(init) => Ok(build!(statement::ConstDeclarator { id: id, init: init; type_annotation })),
+            None => error("Const declarations must have an initializer"),
+        })
+        .collect::<Result<_>>()?;
+    let (declarators, last) = split_last(declarators);
+
+    Ok(located(node, build!(statement::ConstDeclaration {
+        declarators: declarators,
+        last_declarator: last.unwrap();
+        token_const, token_semi
+    })))
+}
+
+// The declaration on the left of a for-in or for-of loop, if it is one.
+fn for_declaration(
+    node: &Node,
+) -> Result<Option<(&'static str, patterns::BindingPattern<'static>, Option<general::Initializer<'static>>)>> {
+    if node.kind != "VariableDeclaration" {
+        return Ok(None);
+    }
+
+    let kind = match node.str("kind")? {
+        "let" => "let",
+        "const" => "const",
+        _ => "var",
+    };
+    let mut declarators = declarators(node)?;
+    if declarators.len() != 1 {
+        return error("The head of a for-in or for-of loop must have a single declarator");
+    }
+    let (pattern, init) = declarators.remove(0);
+
+    Ok(Some((kind, pattern, init)))
+}
+
+
+// Functions
+
+fn function_kind(node: &Node) -> functions::FunctionKind {
+    match (node.flag("generator"), node.flag("async")) {
+        (false, false) => functions::FunctionKind::Normal,
+        (true, false) => functions::FunctionKind::Generator,
+        (false, true) => functions::FunctionKind::Async,
+        (true, true) => functions::FunctionKind::AsyncGenerator,
+    }
+}
+
+fn function_declaration(node: &Node) -> Result<functions::FunctionDeclaration<'static>> {
+    Ok(located(node, build!(functions::FunctionDeclaration {
+        kind: function_kind(node),
+        id: binding_identifier(&node.node("id")?)?,
+        params: function_params(node)?,
+        body: function_body(&node.node("body")?)?,
+    })))
+}
+
+fn function_expression(node: &Node) -> Result<functions::FunctionExpression<'static>> {
+    Ok(located(node, build!(functions::FunctionExpression {
+        kind: function_kind(node),
+        id: node.opt_node("id")?.map(|id| binding_identifier(&id)).transpose()?,
+        params: function_params(node)?,
+        body: function_body(&node.node("body")?)?,
+    })))
+}
+
+fn arrow_function(node: &Node) -> Result<functions::ArrowFunctionExpression<'static>> {
+    let kind = match (node.flag("generator"), node.flag("async")) {
+        (false, false) => functions::ArrowFunctionKind::Normal,
+        (true, false) => functions::ArrowFunctionKind::Generator,
+        (false, true) => functions::ArrowFunctionKind::Async,
+        (true, true) => functions::ArrowFunctionKind::AsyncGenerator,
+    };
+
+    let params = node.nodes("params")?;
+    let params = match params.first() {
+        Some(param) if params.len() == 1 && param.kind == "Identifier" => {
+            functions::ArrowFunctionParams::from(binding_identifier(param)?)
+        }
+        _ => functions::ArrowFunctionParams::from(function_params(node)?),
+    };
+
+    let body = node.node("body")?;
+    let body = if body.kind == "BlockStatement" {
+        functions::ArrowFunctionBody::Block(function_body(&body)?)
+    } else {
+        build!(functions::ArrowFunctionExpressionBody { expression: NodeBox::new(expression(&body)?) }).into()
+    };
+
+    Ok(located(node, build!(functions::ArrowFunctionExpression { kind: kind, params: params, body: body })))
+}
+
+fn function_params(node: &Node) -> Result<functions::FunctionParams<'static>> {
+    let mut params = node.nodes("params")?;
+    let last = params.pop();
+
+    let params = params
+        .iter()
+        .map(|param| Ok((function_param(param)?, Default::default())))
+        .collect::<Result<_>>()?;
+    let last_param = match last {
+        Some(ref param) if param.kind == "RestElement" => Some(
+            build!(functions::FunctionRestParam {
+                id: binding_pattern(&param.node("argument")?)?;
+                type_annotation
+            }).into(),
+        ),
+        Some(ref param) => Some(function_param(param)?.into()),
+        None => None,
+    };
+
+    Ok(build!(functions::FunctionParams {
+        params: params,
+        last_param: last_param;
+        type_parameters, return_type, predicate
+    }))
+}
+
+fn function_param(node: &Node) -> Result<functions::FunctionParam<'static>> {
+    let decorators = node
+        .opt_array("decorators")?
+        .iter()
+        .map(|decorator| {
+            Ok(build!(functions::FunctionParamDecorator {
+                value: decorator_value(&Node::new(decorator)?)?
+            }))
+        })
+        .collect::<Result<_>>()?;
+
+    let (id, init) = with_default(node)?;
+    Ok(located(node, build!(functions::FunctionParam {
+        decorators: decorators,
+        id: binding_pattern(&id)?,
+        init: init;
+        modifiers, optional, type_annotation
+    })))
+}
+
+fn function_body(node: &Node) -> Result<functions::FunctionBody<'static>> {
+    if node.kind != "BlockStatement" {
+        return node.unexpected("a BlockStatement");
+    }
+    let (directives, body) = prologue(node)?;
+
+    Ok(located(node, build!(functions::FunctionBody {
+        directives: directives,
+        body: body.iter().map(statement_item).collect::<Result<_>>()?,
+    })))
+}
+
+// Methods store their function in "value" in ESTree, but inline in Babel.
+fn method_function<'a>(node: &Node<'a>) -> Result<Node<'a>> {
+    match node.opt_node("value")? {
+        Some(value) if value.kind == "FunctionExpression" => Ok(value),
+        _ => Ok(*node),
+    }
+}
+
+fn method_kind(node: &Node, function: &Node) -> Result<objects::MethodKind> {
+    Ok(match node.opt("kind").and_then(Value::as_str) {
+        Some("get") => objects::MethodKind::Get,
+        Some("set") => objects::MethodKind::Set,
+        _ => match (function.flag("generator"), function.flag("async")) {
+            (false, false) => objects::MethodKind::Normal,
+            (true, false) => objects::MethodKind::Generator,
+            (false, true) => objects::MethodKind::Async,
+            (true, true) => objects::MethodKind::AsyncGenerator,
+        },
+    })
+}
+
+
+// Classes
+
+// The parts shared by class declarations and expressions.
+type ClassParts = (
+    Vec<classes::ClassDecorator<'static>>,
+    Option<classes::ClassName<'static>>,
+    Option<classes::ClassHeritage<'static>>,
+    classes::ClassBody<'static>,
+);
+
+fn class_parts(node: &Node) -> Result<ClassParts> {
+    let decorators = node
+        .opt_array("decorators")?
+        .iter()
+        .map(|decorator| {
+            Ok(build!(classes::ClassDecorator {
+                value: decorator_value(&Node::new(decorator)?)?;
+                tokens_prefix
+            }))
+        })
+        .collect::<Result<_>>()?;
+    let id = match node.opt_node("id")? {
+        Some(id) => Some(binding_identifier(&id)?.into()),
+        None => None,
+    };
+    let heritage = match node.opt_node("superClass")? {
+        Some(heritage) => Some(expression(&heritage)?.into()),
+        None => None,
+    };
+    let items = node
+        .node("body")?
+        .nodes("body")?
+        .iter()
+        .map(class_item)
+        .collect::<Result<_>>()?;
+
+    Ok((decorators, id, heritage, build!(classes::ClassBody { items: items; token_curly_l, token_curly_r })))
+}
+
+fn class_declaration(node: &Node) -> Result<classes::ClassDeclaration<'static>> {
+    let (decorators, id, heritage, body) = class_parts(node)?;
+    let id = match id {
+        Some(id) => id,
+        None => return error("Missing \"id\" in ClassDeclaration"),
+    };
+
+    Ok(located(node, build!(classes::ClassDeclaration {
+        decorators: decorators,
+        id: id,
+        heritage: heritage,
+        body: body;
+        modifiers, token_class, type_parameters, implements
+    })))
+}
+
+fn class_expression(node: &Node) -> Result<classes::ClassExpression<'static>> {
+    let (decorators, id, heritage, body) = class_parts(node)?;
+
+    Ok(located(node, build!(classes::ClassExpression {
+        decorators: decorators,
+        id: id,
+        heritage: heritage,
+        body: body;
+        token_class, type_parameters, implements
+    })))
+}
+
+fn class_item(node: &Node) -> Result<classes::ClassItem<'static>> {
+    let decorators = node
+        .opt_array("decorators")?
+        .iter()
+        .map(|decorator| {
+            Ok(build!(classes::ClassItemDecorator {
+                value: decorator_value(&Node::new(decorator)?)?;
+                tokens_prefix
+            }))
+        })
+        .collect::<Result<_>>()?;
+    let pos = if node.flag("static") {
+        Some(Default::default())
+    } else {
+        None
+    };
+
+    let key = node.node("key")?;
+    let id = match key.kind {
+        "PrivateIdentifier" | "PrivateName" => classes::ClassFieldId::Private(private_name(&key)?),
+        _ => property_name(&key, node.flag("computed"))?.into(),
+    };
+
+    Ok(located(node, match node.kind {
+        "MethodDefinition" | "ClassMethod" | "ClassPrivateMethod" | "TSDeclareMethod" => {
+            let function = method_function(node)?;
+            build!(classes::ClassMethod {
+                decorators: decorators,
+                pos: pos,
+                kind: method_kind(node, &function)?,
+                id: id,
+                params: function_params(&function)?,
+                body: function_body(&function.node("body")?)?;
+                modifiers
+            }).into()
+        }
+        "PropertyDefinition" | "ClassProperty" | "ClassPrivateProperty" => build!(classes::ClassField {
+            decorators: decorators,
+            pos: pos,
+            id: id,
+            init: node.opt_node("value")?.map(|value| initializer(&value)).transpose()?;
+            modifiers, optional, definite, type_annotation
+        }).into(),
+        _ => return node.unexpected("a class element"),
+    }))
+}
+
+fn private_name(node: &Node) -> Result<general::PropertyIdentifier<'static>> {
+    let name = match node.kind {
+        "PrivateName" => identifier_name(&node.node("id")?)?,
+        _ => node.str("name")?,
+    };
+    if !is_identifier_name(name, false) {
+        return error(format!("Invalid identifier \"#{}\" in {}", name, node.kind));
+    }
+    Ok(located(node, name.into()))
+}
+
+fn decorator_value(node: &Node) -> Result<decorators::DecoratorValue<'static>> {
+    if node.kind != "Decorator" {
+        return node.unexpected("a Decorator");
+    }
+
+    // Simple member chains and calls of them have their own nodes, while any
+    // other expression is kept as-is.
+    fn member_chain(node: &Node) -> Result<Option<decorators::DecoratorValueExpression<'static>>> {
+        Ok(match node.kind {
+            "Identifier" => Some(reference_identifier(node)?.into()),
+            "MemberExpression" if !node.flag("computed") && node.node("property")?.kind == "Identifier" => {
+                match member_chain(&node.node("object")?)? {
+                    Some(object) => Some(build!(decorators::DecoratorMemberAccess {
+                        object: NodeBox::new(object),
+                        property: identifier_name(&node.node("property")?)?.into(),
+                    }).into()),
+                    None => None,
+                }
+            }
+            _ => None,
+        })
+    }
+
+    let expr = node.node("expression")?;
+    if let Some(value) = member_chain(&expr)? {
+        return Ok(located(node, value.into()));
+    }
+    if expr.kind == "CallExpression" {
+        if let Some(callee) = member_chain(&expr.node("callee")?)? {
+            return Ok(located(node, build!(decorators::DecoratorCallExpression {
+                callee: callee,
+                arguments: call_arguments(&expr)?,
+            }).into()));
+        }
+    }
+    Ok(located(node, build!(decorators::DecoratorExpression { expression: expression(&expr)? }).into()))
+}
+
+
+// Modules
+
+fn import_declaration(node: &Node) -> Result<alias::ModuleStatementItem<'static>> {
+    if let Some(kind) = node.opt("importKind").and_then(Value::as_str) {
+        if kind != "value" {
+            return error(format!("Unsupported \"import {}\" declaration", kind));
+        }
+    }
+
+    let source = string_literal(&node.node("source")?)?;
+
+    let mut default = None;
+    let mut namespace = None;
+    let mut specifiers = vec![];
+    for specifier in node.opt_array("specifiers")? {
+        let specifier = Node::new(specifier)?;
+        let local = binding_identifier(&specifier.node("local")?)?;
+        match specifier.kind {
+            "ImportDefaultSpecifier" if default.is_none() && specifiers.is_empty() => default = Some(local),
+            "ImportNamespaceSpecifier" if namespace.is_none() && specifiers.is_empty() => namespace = Some(local),
+            "ImportSpecifier" if namespace.is_none() => {
+                let imported = specifier.node("imported")?;
+                specifiers.push(if identifier_name(&imported)? == local.value {
+                    build!(modules::NormalImportSpecifier { local: local }).into()
+                } else {
+                    build!(modules::AliasedImportSpecifier {
+                        imported: module_identifier(&imported)?,
+                        local: local;
+                        token_as
+                    }).into()
+                });
+            }
+            _ => return specifier.unexpected("an import specifier that jsparse supports here"),
+        }
+    }
+
+    Ok(located(node, match (default, namespace, specifiers.is_empty()) {
+        (None, None, true) => build!(modules::ImportSourceDeclaration {
+            source: source;
+            token_import, token_semi
+        }).into(),
+        (Some(default), None, true) => build!(modules::ImportNamedDeclaration {
+            default: default,
+            source: source;
+            token_import, token_from, token_semi
+        }).into(),
+        (Some(default), Some(namespace), _) => build!(modules::ImportNamedAndNamespaceDeclaration {
+            default: default,
+            namespace: namespace,
+            source: source;
+            token_import, token_comma, token_star, token_as, token_from, token_semi
+        }).into(),
+        (None, Some(namespace), _) => build!(modules::ImportNamespaceDeclaration {
+            namespace: namespace,
+            source: source;
+            token_import, token_star, token_as, token_from, token_semi
+        }).into(),
+        (Some(default), None, false) => {
+            let (specifiers, last) = split_last(specifiers);
+            build!(modules::ImportNamedAndSpecifiersDeclaration {
+                default: default,
+                specifiers: specifiers,
+                last_specifier: last,
+                source: source;
+                token_import, token_comma, token_curly_l, token_curly_r, token_from, token_semi
+            }).into()
+        }
+        (None, None, false) => {
+            let (specifiers, last) = split_last(specifiers);
+            build!(modules::ImportSpecifiersDeclaration {
+                specifiers: specifiers,
+                last_specifier: last,
+                source: source;
+                token_import, token_curly_l, token_curly_r, token_from, token_semi
+            }).into()
+        }
+    }))
+}
+
+fn export_named_declaration(node: &Node) -> Result<alias::ModuleStatementItem<'static>> {
+    if let Some(kind) = node.opt("exportKind").and_then(Value::as_str) {
+        if kind != "value" {
+            return error(format!("Unsupported \"export {}\" declaration", kind));
+        }
+    }
+
+    if let Some(declaration) = node.opt_node("declaration")? {
+        return Ok(located(node, match statement_item(&declaration)? {
+            alias::StatementItem::Function(exported) => {
+                build!(modules::ExportFunctionDeclaration { exported: NodeBox::into_inner(exported); token_export }).into()
+            }
+            alias::StatementItem::Class(exported) => {
+                build!(modules::ExportClassDeclaration { exported: NodeBox::into_inner(exported); token_export }).into()
+            }
+            alias::StatementItem::Variable(exported) => {
+                build!(modules::ExportVarStatement { exported: NodeBox::into_inner(exported); token_export }).into()
+            }
+            alias::StatementItem::Let(exported) => {
+                build!(modules::ExportLetDeclaration { exported: NodeBox::into_inner(exported); token_export }).into()
+            }
+            alias::StatementItem::Const(exported) => {
+                build!(modules::ExportConstDeclaration { exported: NodeBox::into_inner(exported); token_export }).into()
+            }
+            _ => return declaration.unexpected("an exported declaration"),
+        }));
+    }
+
+    let specifiers = node.nodes("specifiers")?;
+    let source = match node.opt_node("source")? {
+        Some(source) => string_literal(&source)?,
+        None => {
+            let specifiers = specifiers
+                .iter()
+                .map(|specifier| {
+                    let local = specifier.node("local")?;
+                    let exported = specifier.node("exported")?;
+                    Ok(if identifier_name(&exported)? == identifier_name(&local)? {
+                        build!(modules::NormalLocalExportSpecifier { local: reference_identifier(&local)? }).into()
+                    } else {
+                        build!(modules::AliasedLocalExportSpecifier {
+                            local: reference_identifier(&local)?,
+                            exported: module_identifier(&exported)?;
+                            token_as
+                        }).into()
+                    })
+                })
+                .collect::<Result<_>>()?;
+            let (specifiers, last) = split_last(specifiers);
+
+            return Ok(located(node, build!(modules::ExportLocalBindings {
+                specifiers: specifiers,
+                last_specifier: last;
+                token_export, token_curly_l, token_curly_r, token_semi
+            }).into()));
+        }
+    };
+
+    // Babel's proposal for "export foo from" has its own specifier types.
+    let mut rest = &specifiers[..];
+    let default = match rest.first() {
+        Some(specifier) if specifier.kind == "ExportDefaultSpecifier" => {
+            rest = &rest[1..];
+            Some(module_identifier(&specifier.node("exported")?)?)
+        }
+        _ => None,
+    };
+    let namespace = match rest.first() {
+        Some(specifier) if specifier.kind == "ExportNamespaceSpecifier" => {
+            rest = &rest[1..];
+            Some(module_identifier(&specifier.node("exported")?)?)
+        }
+        _ => None,
+    };
+    let specifiers = rest
+        .iter()
+        .map(|specifier| {
+            if specifier.kind != "ExportSpecifier" {
+                return specifier.unexpected("an ExportSpecifier");
+            }
+            let local = specifier.node("local")?;
+            let exported = specifier.node("exported")?;
+            Ok(if identifier_name(&exported)? == identifier_name(&local)? {
+                build!(modules::NormalSourceExportSpecifier { imported: module_identifier(&local)? }).into()
+            } else {
+                build!(modules::AliasedSourceExportSpecifier {
+                    imported: module_identifier(&local)?,
+                    exported: module_identifier(&exported)?;
+                    token_as
+                }).into()
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(located(node, match (default, namespace, specifiers.is_empty()) {
+        (Some(default), None, true) => build!(modules::ExportNamedSpecifier {
+            default: default,
+            source: source;
+            token_export, token_from, token_semi
+        }).into(),
+        (Some(default), Some(namespace), true) => build!(modules::ExportNamedAndNamespace {
+            default: default,
+            namespace: namespace,
+            source: source;
+            token_export, token_comma, token_star, token_as, token_from, token_semi
+        }).into(),
+        (None, Some(namespace), true) => build!(modules::ExportNamespace {
+            namespace: namespace,
+            source: source;
+            token_export, token_star, token_as, token_from, token_semi
+        }).into(),
+        (Some(default), None, false) => {
+            let (specifiers, last) = split_last(specifiers);
+            build!(modules::ExportNamedAndSpecifiers {
+                default: default,
+                specifiers: specifiers,
+                last_specifier: last,
+                source: source;
+                token_export, token_comma, token_curly_l, token_curly_r, token_from, token_semi
+            }).into()
+        }
+        (None, None, _) => {
+            let (specifiers, last) = split_last(specifiers);
+            build!(modules::ExportSourceSpecifiers {
+                specifiers: specifiers,
+                last_specifier: last,
+                source: source;
+                token_export, token_curly_l, token_curly_r, token_from, token_semi
+            }).into()
+        }
+        (_, Some(_), false) => return error("Export specifiers can not be combined with a namespace export"),
+    }))
+}
+
+fn export_default_declaration(node: &Node) -> Result<alias::ModuleStatementItem<'static>> {
+    let declaration = node.node("declaration")?;
+
+    Ok(located(node, match declaration.kind {
+        "FunctionDeclaration" => build!(functions::ExportDefaultFunctionDeclaration {
+            kind: function_kind(&declaration),
+            id: declaration.opt_node("id")?.map(|id| binding_identifier(&id)).transpose()?,
+            params: function_params(&declaration)?,
+            body: function_body(&declaration.node("body")?)?,
+        }).into(),
+        "ClassDeclaration" => {
+            let (decorators, id, heritage, body) = class_parts(&declaration)?;
+            build!(classes::ExportDefaultClassDeclaration {
+                decorators: decorators,
+                id: id,
+                heritage: heritage,
+                body: body;
+                token_export, token_default, modifiers, token_class, type_parameters, implements
+            }).into()
+        }
+        _ => build!(modules::ExportDefaultExpression {
+            expression: expression(&declaration)?;
+            token_export, token_default, token_semi
+        }).into(),
+    }))
+}
+
+fn module_identifier(node: &Node) -> Result<modules::ModuleIdentifier<'static>> {
+    Ok(located(node, build!(modules::ModuleIdentifier { value: identifier_name(node)?.into(), raw: None })))
+}
+
+
+// Identifiers and literals
+
+// Whether a name can be printed as an identifier. Names are stored without
+// escapes, so anything else would print as different code. JSX names may
+// also contain dashes.
+fn is_identifier_name(name: &str, jsx: bool) -> bool {
+    let mut chars = name.chars();
+    let start = match chars.next() {
+        Some(c) => c == '$' || c == '_' || c.is_id_start(),
+        None => false,
+    };
+    start && chars.all(|c| c == '$' || c.is_id_continue() || c == '\u{200C}' || c == '\u{200D}' || (jsx && c == '-'))
+}
+
+fn identifier_name<'a>(node: &Node<'a>) -> Result<&'a str> {
+    let name = match node.kind {
+        "Identifier" | "JSXIdentifier" => node.str("name")?,
+        _ => return node.unexpected("an Identifier"),
+    };
+    if !is_identifier_name(name, node.kind == "JSXIdentifier") {
+        return error(format!("Invalid identifier \"{}\" in {}", name, node.kind));
+    }
+    Ok(name)
+}
+
+// An identifier that refers to a binding, which can't be a reserved word.
+fn binding_name<'a>(node: &Node<'a>) -> Result<&'a str> {
+    let name = identifier_name(node)?;
+    match name {
+        "break" | "case" | "catch" | "class" | "const" | "continue" | "debugger" | "default" | "delete" | "do" |
+        "else" | "enum" | "export" | "extends" | "false" | "finally" | "for" | "function" | "if" | "import" |
+        "in" | "instanceof" | "new" | "null" | "return" | "super" | "switch" | "this" | "throw" | "true" |
+        "try" | "typeof" | "var" | "void" | "while" | "with" => {
+            error(format!("Reserved word \"{}\" used as an identifier", name))
+        }
+        _ => Ok(name),
+    }
+}
+
+fn binding_identifier(node: &Node) -> Result<general::BindingIdentifier<'static>> {
+    Ok(located(node, binding_name(node)?.into()))
+}
+
+fn reference_identifier(node: &Node) -> Result<general::ReferenceIdentifier<'static>> {
+    Ok(located(node, binding_name(node)?.into()))
+}
+
+fn initializer(node: &Node) -> Result<general::Initializer<'static>> {
+    Ok(build!(general::Initializer { expression: NodeBox::new(expression(node)?); token_eq }))
+}
+
+fn is_string_literal(node: &Node) -> bool {
+    node.kind == "StringLiteral" || (node.kind == "Literal" && node.opt("value").and_then(Value::as_str).is_some())
+}
+
+fn string_literal(node: &Node) -> Result<literal::String<'static>> {
+    if !is_string_literal(node) {
+        return node.unexpected("a string literal");
+    }
+    let value = match node.raw().and_then(unquote) {
+        Some(raw) => raw.to_string(),
+        None => escape(node.str("value")?),
+    };
+    Ok(located(node, value.into()))
+}
+
+fn numeric_literal(node: &Node) -> Result<literal::Numeric<'static>> {
+    match node.opt("value").and_then(Value::as_f64) {
+        Some(value) => Ok(located(node, build!(literal::Numeric {
+            raw: node.raw().map(Into::into),
+            value: value,
+        }))),
+        None => node.unexpected("a numeric literal"),
+    }
+}
+
+fn literal(node: &Node) -> Result<alias::Expression<'static>> {
+    if node.kind == "RegExpLiteral" || node.opt("regex").is_some() {
+        // ESTree nests the pattern and flags in a "regex" object.
+        let regex = if node.kind == "RegExpLiteral" {
+            *node
+        } else {
+            Node {
+                value: node.field("regex")?,
+                kind: node.kind,
+            }
+        };
+        return Ok(located(node, build!(literal::RegExp {
+            value: regex.str("pattern")?.into(),
+            flags: regex.opt("flags").and_then(Value::as_str).unwrap_or("").chars().collect(),
+        }).into()));
+    }
+    if node.opt("bigint").is_some() {
+        return error("BigInt literals are not supported");
+    }
+
+    if node.kind == "NullLiteral" {
+        return Ok(located(node, literal::Null::default().into()));
+    }
+    Ok(located(node, match node.value.get("value") {
+        Some(&Value::Null) => literal::Null::default().into(),
+        Some(&Value::Bool(value)) => build!(literal::Boolean { value: value }).into(),
+        Some(&Value::Number(_)) => numeric_literal(node)?.into(),
+        Some(&Value::String(_)) => string_literal(node)?.into(),
+        _ => return node.unexpected("a literal"),
+    }))
+}
+
+fn property_name(node: &Node, computed: bool) -> Result<general::PropertyName<'static>> {
+    if computed {
+        return Ok(build!(general::ComputedPropertyName { expression: NodeBox::new(expression(node)?) }).into());
+    }
+
+    Ok(match node.kind {
+        "Identifier" => general::PropertyName::Identifier(identifier_name(node)?.into()),
+        _ if is_string_literal(node) => string_literal(node)?.into(),
+        "Literal" | "NumericLiteral" => numeric_literal(node)?.into(),
+        _ => return node.unexpected("a property name"),
+    })
+}
+
+
+// Expressions
+
+fn expression(node: &Node) -> Result<alias::Expression<'static>> {
+    let expr = located(node, expression_inner(node)?);
+
+    // Babel marks parenthesized expressions rather than creating nodes.
+    let parenthesized = node
+        .opt("extra")
+        .and_then(|extra| extra.get("parenthesized"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    Ok(if parenthesized {
+        build!(expression::ParenthesizedExpression { expr: NodeBox::new(expr); token_paren_l, token_paren_r }).into()
+    } else {
+        expr
+    })
+}
+
+macro_rules! binary {
+    ($ty:ident, $token:ident, $node:expr) => {
+        build!(expression::$ty {
+            left: NodeBox::new(expression(&$node.node("left")?)?),
+            right: NodeBox::new(expression(&$node.node("right")?)?);
+            $token
+        }).into()
+    };
+}
+
+macro_rules! assign {
+    ($ty:ident, $token:ident, $node:expr) => {
+        build!(expression::$ty {
+            left: NodeBox::new(simple_assign(&$node.node("left")?)?),
+            right: NodeBox::new(expression(&$node.node("right")?)?);
+            $token
+        }).into()
+    };
+}
+
+macro_rules! unary {
+    ($ty:ident, $token:ident, $node:expr) => {
+        build!(expression::$ty {
+            value: NodeBox::new(expression(&$node.node("argument")?)?);
+            $token
+        }).into()
+    };
+}
+
+fn expression_inner(node: &Node) -> Result<alias::Expression<'static>> {
+    Ok(match node.kind {
+        "Identifier" => reference_identifier(node)?.into(),
+        "ThisExpression" => expression::ThisExpression::default().into(),
+        "Literal" | "StringLiteral" | "NumericLiteral" | "BooleanLiteral" | "NullLiteral" | "RegExpLiteral" => {
+            literal(node)?
+        }
+        "ArrayExpression" => {
+            let mut elements = node.array("elements")?.to_vec();
+            let last = match elements.last() {
+                Some(last) if !last.is_null() => elements.pop(),
+                _ => None,
+            };
+
+            build!(objects::ArrayExpression {
+                elements: elements
+                    .iter()
+                    .map(|element| {
+                        let element = if element.is_null() {
+                            None
+                        } else {
+                            Some(array_item(&Node::new(element)?)?)
+                        };
+                        Ok((element, Default::default()))
+                    })
+                    .collect::<Result<_>>()?,
+                last_element: last.map(|last| array_item(&Node::new(&last)?)).transpose()?,
+            }).into()
+        }
+        "ObjectExpression" => {
+            let properties = node.nodes("properties")?.iter().map(object_item).collect::<Result<_>>()?;
+            let (properties, last) = split_last(properties);
+
+            build!(objects::ObjectExpression {
+                properties: properties,
+                last_property: last;
+                token_curly_left, token_curly_right
+            }).into()
+        }
+        "FunctionExpression" => function_expression(node)?.into(),
+        "ArrowFunctionExpression" => arrow_function(node)?.into(),
+        "ClassExpression" => class_expression(node)?.into(),
+        "TemplateLiteral" => template(node)?.into(),
+        "TaggedTemplateExpression" => build!(expression::TaggedTemplateLiteral {
+            tag: NodeBox::new(expression(&node.node("tag")?)?),
+            template: template(&node.node("quasi")?)?;
+            tokens_sep
+        }).into(),
+        "ParenthesizedExpression" => build!(expression::ParenthesizedExpression {
+            expr: NodeBox::new(expression(&node.node("expression")?)?);
+            token_paren_l, token_paren_r
+        }).into(),
+        "ChainExpression" => expression(&node.node("expression")?)?,
+        "MemberExpression" | "OptionalMemberExpression" => member(node)?,
+        "CallExpression" | "OptionalCallExpression" => {
+            let callee = node.node("callee")?;
+            match callee.kind {
+                "Super" => build!(expression::SuperCallExpression {
+                    arguments: call_arguments(node)?;
+                    token_super
+                }).into(),
+                "Import" => import_call(&node.nodes("arguments")?)?,
+                _ if node.flag("optional") => build!(expression::OptionalCallExpression {
+                    callee: NodeBox::new(expression(&callee)?),
+                    arguments: call_arguments(node)?;
+                    token_question
+                }).into(),
+                _ => build!(expression::CallExpression {
+                    callee: NodeBox::new(expression(&callee)?),
+                    arguments: call_arguments(node)?,
+                }).into(),
+            }
+        }
+        "NewExpression" => expression::NewExpression::from(build!(expression::NormalNewExpression {
+            callee: NodeBox::new(expression(&node.node("callee")?)?),
+            arguments: call_arguments(node)?;
+            token_new
+        })).into(),
+        "ImportExpression" => import_call(&[node.node("source")?])?,
+        "ConditionalExpression" => build!(expression::ConditionalExpression {
+            test: NodeBox::new(expression(&node.node("test")?)?),
+            consequent: NodeBox::new(expression(&node.node("consequent")?)?),
+            alternate: NodeBox::new(expression(&node.node("alternate")?)?);
+            token_question, token_colon
+        }).into(),
+        "SequenceExpression" => {
+            let mut expressions = node.nodes("expressions")?.into_iter();
+            let first = match expressions.next() {
+                Some(first) => expression(&first)?,
+                None => return error("SequenceExpression needs at least one expression"),
+            };
+            expressions.try_fold(first, |left, right| -> Result<alias::Expression> {
+                Ok(build!(expression::SequenceExpression {
+                    left: NodeBox::new(left),
+                    right: NodeBox::new(expression(&right)?);
+                    token_comma
+                }).into())
+            })?
+        }
+        "AssignmentExpression" => assignment_expression(node)?,
+        "BinaryExpression" | "LogicalExpression" => binary_expression(node)?,
+        "UnaryExpression" => unary_expression(node)?,
+        "UpdateExpression" => update_expression(node)?,
+        "AwaitExpression" => unary!(AwaitExpression, token_await, node),
+        "YieldExpression" => match node.opt_node("argument")? {
+            Some(argument) if node.flag("delegate") => build!(expression::DelegateYieldExpression {
+                value: NodeBox::new(expression(&argument)?);
+                token_yield, token_star
+            }).into(),
+            argument => build!(expression::YieldExpression {
+                value: argument.map(|argument| expression(&argument).map(NodeBox::new)).transpose()?;
+                token_yield
+            }).into(),
+        },
+        "MetaProperty" => {
+            let meta = identifier_name(&node.node("meta")?)?;
+            let property = identifier_name(&node.node("property")?)?;
+            match (meta, property) {
+                ("new", "target") => build!(expression::NewTargetExpression {
+                    ; token_new, token_period, token_target
+                }).into(),
+                ("import", "meta") => build!(expression::ImportMetaExpression {
+                    ; token_import, token_period, token_meta
+                }).into(),
+                ("function", "sent") => build!(expression::FunctionSentExpression {
+                    ; token_function, token_period, token_send
+                }).into(),
+                ("function", "arguments") => build!(expression::FunctionArgumentsExpression {
+                    ; token_function, token_period, token_arguments
+                }).into(),
+                _ => return error(format!("Unsupported meta property \"{}.{}\"", meta, property)),
+            }
+        }
+        "BindExpression" => match node.opt_node("object")? {
+            Some(object) => build!(expression::BindExpression {
+                left: NodeBox::new(expression(&object)?),
+                right: NodeBox::new(expression(&node.node("callee")?)?);
+                token_coloncolon
+            }).into(),
+            None => build!(expression::BindMemberExpression {
+                value: NodeBox::new(expression(&node.node("callee")?)?);
+                token_coloncolon
+            }).into(),
+        },
+        "DoExpression" => build!(expression::DoExpression { body: block(&node.node("body")?)?; token_do }).into(),
+        "JSXElement" => jsx_element(node)?.into(),
+        _ => return node.unexpected("an expression"),
+    })
+}
+
+fn assignment_expression(node: &Node) -> Result<alias::Expression<'static>> {
+    Ok(match node.str("operator")? {
+        "=" => build!(expression::AssignmentExpression {
+            left: NodeBox::new(complex_assign(&node.node("left")?)?),
+            right: NodeBox::new(expression(&node.node("right")?)?);
+            token_eq
+        }).into(),
+        "+=" => assign!(AddAssignExpression, token_pluseq, node),
+        "-=" => assign!(SubtractAssignExpression, token_minuseq, node),
+        "<<=" => assign!(LeftShiftAssignExpression, token_langleangleeq, node),
+        ">>=" => assign!(RightShiftAssignExpression, token_rangleangleeq, node),
+        ">>>=" => assign!(RightShiftSignedAssignExpression, token_rangleangleangleeq, node),
+        "/=" => assign!(DivideAssignExpression, token_slasheq, node),
+        "*=" => assign!(MultiplyAssignExpression, token_stareq, node),
+        "%=" => assign!(ModulusAssignExpression, token_percenteq, node),
+        "&=" => assign!(BitAndAssignExpression, token_ampeq, node),
+        "|=" => assign!(BitOrAssignExpression, token_bareq, node),
+        "^=" => assign!(BitXorAssignExpression, token_careteq, node),
+        "**=" => assign!(PowerAssignExpression, token_starstareq, node),
+        op => return error(format!("Unsupported assignment operator \"{}\"", op)),
+    })
+}
+
+fn binary_expression(node: &Node) -> Result<alias::Expression<'static>> {
+    Ok(match node.str("operator")? {
+        "+" => binary!(AddExpression, token_plus, node),
+        "-" => binary!(SubtractExpression, token_minus, node),
+        "<<" => binary!(LeftShiftExpression, token_langleangle, node),
+        ">>" => binary!(RightShiftExpression, token_rangleangle, node),
+        ">>>" => binary!(RightShiftSignedExpression, token_rangleangleangle, node),
+        "/" => binary!(DivideExpression, token_slash, node),
+        "*" => binary!(MultiplyExpression, token_star, node),
+        "%" => binary!(ModulusExpression, token_percent, node),
+        "&" => binary!(BitAndExpression, token_amp, node),
+        "|" => binary!(BitOrExpression, token_bar, node),
+        "^" => binary!(BitXorExpression, token_caret, node),
+        "**" => binary!(PowerExpression, token_starstar, node),
+        "==" => binary!(EqualExpression, token_eqeq, node),
+        "===" => binary!(StrictEqualExpression, token_eqeqeq, node),
+        "!=" => binary!(NotEqualExpression, token_neeq, node),
+        "!==" => binary!(StrictNotEqualExpression, token_neeqeq, node),
+        "<" => binary!(LessThanExpression, token_langle, node),
+        "<=" => binary!(LessThanEqualExpression, token_langleeq, node),
+        ">" => binary!(GreaterThanExpression, token_rangle, node),
+        ">=" => binary!(GreaterThanEqualExpression, token_rangleeq, node),
+        "in" => binary!(InExpression, token_in, node),
+        "instanceof" => binary!(InstanceofExpression, token_instanceof, node),
+        "&&" => binary!(AndExpression, token_ampamp, node),
+        "||" => binary!(OrExpression, token_barbar, node),
+        op => return error(format!("Unsupported binary operator \"{}\"", op)),
+    })
+}
+
+fn unary_expression(node: &Node) -> Result<alias::Expression<'static>> {
+    Ok(match node.str("operator")? {
+        "delete" => unary!(DeleteExpression, token_delete, node),
+        "void" => unary!(VoidExpression, token_void, node),
+        "typeof" => unary!(TypeofExpression, token_typeof, node),
+        "+" => unary!(PositiveExpression, token_plus, node),
+        "-" => unary!(NegativeExpression, token_minus, node),
+        "~" => unary!(BitNegateExpression, token_tilde, node),
+        "!" => unary!(NegateExpression, token_exclam, node),
+        op => return error(format!("Unsupported unary operator \"{}\"", op)),
+    })
+}
+
+fn update_expression(node: &Node) -> Result<alias::Expression<'static>> {
+    let value = simple_assign(&node.node("argument")?)?;
+    Ok(match (node.str("operator")?, node.flag("prefix")) {
+        ("++", true) => build!(expression::PreIncrementExpression {
+            value: value;
+            token_plusplus
+        }).into(),
+        ("--", true) => build!(expression::PreDecrementExpression {
+            value: value;
+            token_minusminus
+        }).into(),
+        ("++", false) => build!(expression::PostIncrementExpression {
+            value: value;
+            tokens_separator, token_plusplus
+        }).into(),
+        ("--", false) => build!(expression::PostDecrementExpression {
+            value: value;
+            tokens_separator, token_minusminus
+        }).into(),
+        (op, _) => return error(format!("Unsupported update operator \"{}\"", op)),
+    })
+}
+
+fn import_call(arguments: &[Node]) -> Result<alias::Expression<'static>> {
+    match arguments.first() {
+        Some(argument) if arguments.len() == 1 => Ok(build!(expression::ImportCallExpression {
+            argument: NodeBox::new(expression(argument)?);
+            token_import
+        }).into()),
+        _ => error("import() needs a single argument"),
+    }
+}
+
+fn template(node: &Node) -> Result<expression::TemplateLiteral<'static>> {
+    fn part(node: &Node) -> Result<expression::TemplatePart<'static>> {
+        let raw = Node {
+            value: node.field("value")?,
+            kind: node.kind,
+        }.str("raw")?;
+        Ok(build!(expression::TemplatePart { value: raw.into(), raw_value: Some(raw.into()) }))
+    }
+
+    let mut quasis = node.nodes("quasis")?;
+    let expressions = node.nodes("expressions")?;
+    let last = match quasis.pop() {
+        Some(last) if quasis.len() == expressions.len() => last,
+        _ => return error("TemplateLiteral needs one more quasi than expressions"),
+    };
+
+    Ok(located(node, build!(expression::TemplateLiteral {
+        parts: quasis
+            .iter()
+            .zip(expressions.iter())
+            .map(|(quasi, expr)| Ok((part(quasi)?, Default::default(), expression(expr)?, Default::default())))
+            .collect::<Result<_>>()?,
+        last_part: part(&last)?;
+        token_tick_open, token_tick_close
+    })))
+}
+
+fn call_arguments(node: &Node) -> Result<expression::CallArguments<'static>> {
+    let args = node
+        .nodes("arguments")?
+        .iter()
+        .map(|arg| {
+            Ok(if arg.kind == "SpreadElement" {
+                build!(expression::CallArgumentSpread {
+                    expression: NodeBox::new(expression(&arg.node("argument")?)?);
+                    token_ellipsis
+                }).into()
+            } else {
+                expression(arg)?.into()
+            })
+        })
+        .collect::<Result<_>>()?;
+    let (args, last) = split_last(args);
+
+    Ok(build!(expression::CallArguments { args: args, last_arg: last; token_paren_l, token_paren_r }))
+}
+
+fn member(node: &Node) -> Result<alias::Expression<'static>> {
+    let object = node.node("object")?;
+    let property = node.node("property")?;
+    let computed = node.flag("computed");
+    let private = property.kind == "PrivateIdentifier" || property.kind == "PrivateName";
+
+    if object.kind == "Super" {
+        let property = if computed {
+            build!(expression::ComputedPropertyAccess {
+                expression: NodeBox::new(expression(&property)?);
+                tokens_prefix, token_square_l, token_square_r
+            }).into()
+        } else {
+            build!(expression::IdentifierPropertyAccess {
+                id: identifier_name(&property)?.into();
+                token_period, tokens_separator
+            }).into()
+        };
+        return Ok(build!(expression::SuperMemberExpression { property: property; token_super }).into());
+    }
+
+    let object = NodeBox::new(expression(&object)?);
+    Ok(if node.flag("optional") {
+        let property = if computed {
+            build!(expression::OptionalComputedPropertyAccess {
+                expression: NodeBox::new(expression(&property)?);
+                token_question_period, tokens_separator, token_square_l, token_square_r
+            }).into()
+        } else if private {
+            build!(expression::OptionalPrivatePropertyAccess {
+                property: private_name(&property)?;
+                token_question_period, token_hash, tokens_separator
+            }).into()
+        } else {
+            build!(expression::OptionalIdentifierPropertyAccess {
+                id: identifier_name(&property)?.into();
+                token_question_period, tokens_separator
+            }).into()
+        };
+        build!(expression::OptionalMemberExpression { object: object, property: property }).into()
+    } else {
+        member_expression(object, &property, computed)?.into()
+    })
+}
+
+fn member_expression(object: NodeBox<alias::Expression<'static>>, property: &Node, computed: bool) -> Result<expression::MemberExpression<'static>> {
+    let property = if computed {
+        build!(expression::ComputedPropertyAccess {
+            expression: NodeBox::new(expression(property)?);
+            tokens_prefix, token_square_l, token_square_r
+        }).into()
+    } else if property.kind == "PrivateIdentifier" || property.kind == "PrivateName" {
+        build!(expression::PrivatePropertyAccess {
+            property: private_name(property)?;
+            token_period, token_hash, tokens_separator
+        }).into()
+    } else {
+        build!(expression::IdentifierPropertyAccess {
+            id: identifier_name(property)?.into();
+            token_period, tokens_separator
+        }).into()
+    };
+
+    Ok(build!(expression::MemberExpression { object: object, property: property }))
+}
+
+
+// Objects and arrays
+
+fn array_item(node: &Node) -> Result<objects::ArrayItem<'static>> {
+    Ok(located(node, if node.kind == "SpreadElement" {
+        build!(objects::ArraySpreadItem {
+            expression: NodeBox::new(expression(&node.node("argument")?)?);
+            token_prefix
+        }).into()
+    } else {
+        build!(objects::ArrayExpressionItem { expression: NodeBox::new(expression(node)?); token_prefix }).into()
+    }))
+}
+
+fn object_item(node: &Node) -> Result<objects::ObjectItem<'static>> {
+    match node.kind {
+        "SpreadElement" | "SpreadProperty" => {
+            return Ok(located(node, build!(objects::ObjectSpreadElement {
+                expression: NodeBox::new(expression(&node.node("argument")?)?)
+            }).into()));
+        }
+        "Property" | "ObjectProperty" | "ObjectMethod" => {}
+        _ => return node.unexpected("an object property"),
+    }
+
+    let key = node.node("key")?;
+    let computed = node.flag("computed");
+    let kind = node.opt("kind").and_then(Value::as_str).unwrap_or("init");
+
+    if node.kind == "ObjectMethod" || node.flag("method") || kind == "get" || kind == "set" {
+        let function = method_function(node)?;
+        return Ok(located(node, build!(objects::ObjectMethod {
+            kind: method_kind(node, &function)?,
+            id: property_name(&key, computed)?,
+            params: function_params(&function)?,
+            body: function_body(&function.node("body")?)?,
+        }).into()));
+    }
+
+    let value = node.node("value")?;
+    if node.flag("shorthand") && value.kind == "Identifier" {
+        return Ok(located(node, build!(objects::ObjectShorthandProperty { id: reference_identifier(&value)? }).into()));
+    }
+
+    Ok(located(node, build!(objects::ObjectProperty {
+        name: property_name(&key, computed)?,
+        value: NodeBox::new(expression(&value)?),
+    }).into()))
+}
+
+
+// Patterns
+
+// Split an "AssignmentPattern" into its target and default value.
+fn with_default<'a>(node: &Node<'a>) -> Result<(Node<'a>, Option<general::Initializer<'static>>)> {
+    if node.kind == "AssignmentPattern" {
+        Ok((node.node("left")?, Some(initializer(&node.node("right")?)?)))
+    } else {
+        Ok((*node, None))
+    }
+}
+
+fn binding_pattern(node: &Node) -> Result<patterns::BindingPattern<'static>> {
+    Ok(located(node, match node.kind {
+        "Identifier" => binding_identifier(node)?.into(),
+        "ObjectPattern" => {
+            let mut properties = node.nodes("properties")?;
+            let last = properties.pop();
+
+            let properties = properties
+                .iter()
+                .map(|property| {
+                    Ok((
+                        match object_binding_property(property)? {
+                            patterns::ObjectBindingPatternLastProperty::Identifier(p) => p.into(),
+                            patterns::ObjectBindingPatternLastProperty::Pattern(p) => p.into(),
+                            patterns::ObjectBindingPatternLastProperty::Rest(_) => {
+                                return error("A rest element must be last in an ObjectPattern")
+                            }
+                        },
+                        Default::default(),
+                    ))
+                })
+                .collect::<Result<_>>()?;
+
+            build!(patterns::ObjectBindingPattern {
+                properties: properties,
+                last_property: last.map(|last| object_binding_property(&last)).transpose()?,
+            }).into()
+        }
+        "ArrayPattern" => {
+            let mut elements = node.array("elements")?.to_vec();
+            let last = match elements.last() {
+                Some(last) if !last.is_null() => elements.pop(),
+                _ => None,
+            };
+
+            let items = elements
+                .iter()
+                .map(|element| {
+                    let element = if element.is_null() {
+                        None
+                    } else {
+                        let (id, init) = with_default(&Node::new(element)?)?;
+                        Some(build!(patterns::ArrayBindingPatternElement { id: binding_pattern(&id)?, init: init }))
+                    };
+                    Ok((element, Default::default()))
+                })
+                .collect::<Result<_>>()?;
+            let last_item = match last {
+                Some(ref last) => {
+                    let last = Node::new(last)?;
+                    Some(if last.kind == "RestElement" {
+                        build!(patterns::ArrayBindingRestElement {
+                            pattern: NodeBox::new(binding_pattern(&last.node("argument")?)?);
+                            token_ellipsis
+                        }).into()
+                    } else {
+                        let (id, init) = with_default(&last)?;
+                        patterns::ArrayBindingPatternLastElement::Pattern(NodeBox::new(
+                            build!(patterns::ArrayBindingPatternElement { id: binding_pattern(&id)?, init: init }),
+                        ))
+                    })
+                }
+                None => None,
+            };
+
+            build!(patterns::ArrayBindingPattern { items: items, last_item: last_item }).into()
+        }
+        _ => return node.unexpected("a binding pattern"),
+    }))
+}
+
+fn object_binding_property(node: &Node) -> Result<patterns::ObjectBindingPatternLastProperty<'static>> {
+    if node.kind == "RestElement" || node.kind == "RestProperty" {
+        return Ok(located(node, build!(patterns::ObjectBindingPatternRestProperty {
+            pattern: binding_identifier(&node.node("argument")?)?;
+            token_ellipsis
+        }).into()));
+    }
+
+    let (value, init) = with_default(&node.node("value")?)?;
+    Ok(located(node, if node.flag("shorthand") && value.kind == "Identifier" {
+        build!(patterns::ObjectBindingPatternIdentifierProperty { id: binding_identifier(&value)?, init: init }).into()
+    } else {
+        build!(patterns::ObjectBindingPatternPatternProperty {
+            name: property_name(&node.node("key")?, node.flag("computed"))?,
+            pattern: NodeBox::new(binding_pattern(&value)?),
+            init: init,
+        }).into()
+    }))
+}
+
+fn simple_assign(node: &Node) -> Result<patterns::LeftHandSimpleAssign<'static>> {
+    Ok(located(node, match node.kind {
+        "Identifier" => reference_identifier(node)?.into(),
+        "MemberExpression" if !node.flag("optional") && node.node("object")?.kind != "Super" => {
+            let object = NodeBox::new(expression(&node.node("object")?)?);
+            member_expression(object, &node.node("property")?, node.flag("computed"))?.into()
+        }
+        "ParenthesizedExpression" => build!(patterns::ParenthesizedAssignmentPattern {
+            pattern: NodeBox::new(simple_assign(&node.node("expression")?)?);
+            token_paren_l, token_paren_r
+        }).into(),
+        _ => return node.unexpected("an assignment target"),
+    }))
+}
+
+fn complex_assign(node: &Node) -> Result<patterns::LeftHandComplexAssign<'static>> {
+    Ok(located(node, match node.kind {
+        "ObjectPattern" | "ObjectExpression" => {
+            let mut properties = node.nodes("properties")?;
+            let last = properties.pop();
+
+            let properties = properties
+                .iter()
+                .map(|property| {
+                    Ok((
+                        match object_assign_property(property)? {
+                            patterns::ObjectAssignmentPatternLastProperty::Identifier(p) => p.into(),
+                            patterns::ObjectAssignmentPatternLastProperty::Pattern(p) => p.into(),
+                            patterns::ObjectAssignmentPatternLastProperty::Rest(_) => {
+                                return error("A rest element must be last in an ObjectPattern")
+                            }
+                        },
+                        Default::default(),
+                    ))
+                })
+                .collect::<Result<_>>()?;
+
+            build!(patterns::ObjectAssignmentPattern {
+                properties: properties,
+                last_property: last.map(|last| object_assign_property(&last)).transpose()?;
+                token_curly_l, token_curly_r
+            }).into()
+        }
+        "ArrayPattern" | "ArrayExpression" => {
+            let mut elements = node.array("elements")?.to_vec();
+            let last = match elements.last() {
+                Some(last) if !last.is_null() => elements.pop(),
+                _ => None,
+            };
+
+            let items = elements
+                .iter()
+                .map(|element| {
+                    let element = if element.is_null() {
+                        None
+                    } else {
+                        Some(array_assign_element(&Node::new(element)?)?)
+                    };
+                    Ok((element, Default::default()))
+                })
+                .collect::<Result<_>>()?;
+            let last_item = match last {
+                Some(ref last) => {
+                    let last = Node::new(last)?;
+                    Some(if last.kind == "RestElement" {
+                        build!(patterns::ArrayAssignmentRestElement {
+                            pattern: NodeBox::new(complex_assign(&last.node("argument")?)?);
+                            token_ellipsis
+                        }).into()
+                    } else {
+                        array_assign_element(&last)?.into()
+                    })
+                }
+                None => None,
+            };
+
+            build!(patterns::ArrayAssignmentPattern { items: items, last_item: last_item }).into()
+        }
+        _ => match simple_assign(node)? {
+            patterns::LeftHandSimpleAssign::Identifier(id) => id.into(),
+            patterns::LeftHandSimpleAssign::Member(member) => member.into(),
+            patterns::LeftHandSimpleAssign::Parenthesized(pattern) => pattern.into(),
+        },
+    }))
+}
+
+fn array_assign_element(node: &Node) -> Result<patterns::ArrayAssignmentPatternElement<'static>> {
+    let (id, init) = with_default(node)?;
+    Ok(located(node, build!(patterns::ArrayAssignmentPatternElement { id: NodeBox::new(complex_assign(&id)?), init: init })))
+}
+
+fn object_assign_property(node: &Node) -> Result<patterns::ObjectAssignmentPatternLastProperty<'static>> {
+    if node.kind == "RestElement" || node.kind == "RestProperty" {
+        return Ok(located(node, build!(patterns::ObjectAssignmentPatternRestProperty {
+            pattern: NodeBox::new(simple_assign(&node.node("argument")?)?);
+            token_ellipsis
+        }).into()));
+    }
+
+    let (value, init) = with_default(&node.node("value")?)?;
+    Ok(located(node, if node.flag("shorthand") && value.kind == "Identifier" {
+        build!(patterns::ObjectAssignmentPatternIdentifierProperty {
+            id: reference_identifier(&value)?,
+            init: init,
+        }).into()
+    } else {
+        build!(patterns::ObjectAssignmentPatternPatternProperty {
+            name: property_name(&node.node("key")?, node.flag("computed"))?,
+            pattern: NodeBox::new(complex_assign(&value)?),
+            init: init,
+        }).into()
+    }))
+}
+
+
+// JSX
+
+fn jsx_element(node: &Node) -> Result<jsx::Element<'static>> {
+    if node.kind != "JSXElement" {
+        return node.unexpected("a JSXElement");
+    }
+    let opening = node.node("openingElement")?;
+
+    let attributes = opening
+        .nodes("attributes")?
+        .iter()
+        .map(|attribute| {
+            Ok(match attribute.kind {
+                "JSXSpreadAttribute" => build!(jsx::SpreadAttribute {
+                    expression: expression(&attribute.node("argument")?)?
+                }).into(),
+                "JSXAttribute" => {
+                    let name = attribute.node("name")?;
+                    let name = match name.kind {
+                        "JSXNamespacedName" => jsx_namespaced_name(&name)?.into(),
+                        _ => jsx_identifier(&name)?.into(),
+                    };
+                    let value = match attribute.opt_node("value")? {
+                        Some(value) => Some(match value.kind {
+                            "JSXExpressionContainer" => build!(jsx::ExpressionAttribute {
+                                expression: expression(&value.node("expression")?)?
+                            }).into(),
+                            "JSXElement" => jsx_element(&value)?.into(),
+                            _ if is_string_literal(&value) => build!(jsx::StringAttribute {
+                                raw: None,
+                                value: match value.raw().and_then(unquote) {
+                                    Some(raw) => raw.into(),
+                                    None => value.str("value")?.into(),
+                                },
+                            }).into(),
+                            _ => return value.unexpected("a JSX attribute value"),
+                        }),
+                        None => None,
+                    };
+                    build!(jsx::PairAttribute { name: name, value: value }).into()
+                }
+                _ => return attribute.unexpected("a JSX attribute"),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let children = node
+        .nodes("children")?
+        .iter()
+        .map(|child| {
+            Ok(match child.kind {
+                "JSXText" => {
+                    let value = child.raw().map_or_else(|| child.str("value"), Ok)?;
+                    build!(jsx::Text { value: value.into(), raw: None }).into()
+                }
+                "JSXElement" => jsx_element(child)?.into(),
+                "JSXExpressionContainer" => {
+                    let expr = child.node("expression")?;
+                    if expr.kind == "JSXEmptyExpression" {
+                        jsx::Empty::default().into()
+                    } else {
+                        build!(jsx::Expression { expression: expression(&expr)? }).into()
+                    }
+                }
+                "JSXSpreadChild" => build!(jsx::ExpressionSpread {
+                    expression: expression(&child.node("expression")?)?
+                }).into(),
+                _ => return child.unexpected("a JSX child"),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let closing = match node.opt_node("closingElement")? {
+        Some(closing) => Some(jsx_element_name(&closing.node("name")?)?),
+        None => None,
+    };
+
+    Ok(located(node, build!(jsx::Element {
+        opening: jsx_element_name(&opening.node("name")?)?,
+        attributes: attributes,
+        children: children,
+        closing: closing,
+    })))
+}
+
+fn jsx_identifier(node: &Node) -> Result<jsx::Identifier<'static>> {
+    if node.kind != "JSXIdentifier" {
+        return node.unexpected("a JSXIdentifier");
+    }
+    Ok(located(node, build!(jsx::Identifier { raw: None, value: identifier_name(node)?.into() })))
+}
+
+fn jsx_namespaced_name(node: &Node) -> Result<jsx::NamespacedName<'static>> {
+    Ok(located(node, build!(jsx::NamespacedName {
+        namespace: jsx_identifier(&node.node("namespace")?)?,
+        name: jsx_identifier(&node.node("name")?)?,
+    })))
+}
+
+fn jsx_member(node: &Node) -> Result<jsx::MemberExpression<'static>> {
+    let object = node.node("object")?;
+    let object = match object.kind {
+        "JSXMemberExpression" => jsx_member(&object)?.into(),
+        _ => jsx_identifier(&object)?.into(),
+    };
+    Ok(located(node, build!(jsx::MemberExpression {
+        object: NodeBox::new(object),
+        property: jsx_identifier(&node.node("property")?)?,
+    })))
+}
+
+fn jsx_element_name(node: &Node) -> Result<jsx::ElementName<'static>> {
+    Ok(match node.kind {
+        "JSXMemberExpression" => jsx_member(node)?.into(),
+        "JSXNamespacedName" => jsx_namespaced_name(node)?.into(),
+        _ => jsx_identifier(node)?.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use estree::to_json;
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
+
+    fn print(ast: &root::Ast<'_>) -> String {
+        match *ast {
+            root::Ast::Script(ref script) => format!("{}", script),
+            root::Ast::Module(ref module) => format!("{}", module),
+        }
+    }
+
+    fn round_trip_script(code: &str) {
+        let ast: root::Ast = Parser::new(code.into_tokenizer()).parse_script().unwrap().into();
+        let json = to_json(&ast, code);
+        assert_eq!(print(&from_json(&json).unwrap()), print(&ast), "{}", json);
+    }
+    fn round_trip_module(code: &str) {
+        let ast: root::Ast = Parser::new(code.into_tokenizer()).parse_module().unwrap().into();
+        let json = to_json(&ast, code);
+        assert_eq!(print(&from_json(&json).unwrap()), print(&ast), "{}", json);
+    }
+
+    #[test]
+    fn it_round_trips_statements() {
+        round_trip_script(
+            "'use strict';
+            var a = 1, [b, , ...c] = d;
+            if (a) b; else if (c) d;
+            for (var i = 0; i < 10; i++) continue;
+            for (let {x, y: [z = 1]} of list) break;
+            for (var k in obj) {}
+            for (a.b in obj);
+            outer: while (true) break outer;
+            do x--; while (x > 0);
+            switch (a) { case 1: b(); default: c(); }
+            try { a(); } catch (e) { b(); } finally { c(); }
+            try {} catch {}
+            throw new Error('oops');",
+        );
+    }
+
+    #[test]
+    fn it_round_trips_expressions() {
+        round_trip_script(
+            "a = b += c >>>= d;
+            x = a ? b : c, y = !a && b || typeof c;
+            ({a, b: 1, [c]: d, get e() {}, *f() {}, ...g});
+            [a, , ...b];
+            tag`a${b}c\\n`;
+            a.b[c](...d);
+            new A(b);
+            (function* f(a, b = 1, ...c) { yield* a; });
+            async x => await x;
+            (a, b) => { 'use strict'; return a; };
+            ({a, b: [c]} = d);
+            /ab+c/gi.test('x\\'y');",
+        );
+    }
+
+    #[test]
+    fn it_round_trips_modules() {
+        round_trip_module(
+            "import a, {b, c as d} from 'x';
+            import * as ns from 'y';
+            import 'z';
+            export const e = 1;
+            export {a as f, b};
+            export * from 'w';
+            export {g as h} from 'v';
+            export default class A extends B { static m() { super.m(); } }",
+        );
+    }
+
+    #[test]
+    fn it_round_trips_precedence() {
+        let cases = [
+            ("(a = 1) + 2;", "(a=1)+2;"),
+            ("(a, b) + c;", "(a,b)+c;"),
+            ("(a ? b : c).d;", "(a?b:c).d;"),
+            ("(a + b) * c;", "(a+b)*c;"),
+            ("a + (b + c);", "a+(b+c);"),
+            ("(a ** b) ** c;", "(a**b)**c;"),
+            ("(-a) ** b;", "(-a)**b;"),
+            ("-(-a);", "- -a;"),
+            ("(async () => 1)();", "(async()=>1)();"),
+            ("new (a())();", "new(a())();"),
+            ("new (a().b)();", "new(a().b)();"),
+            ("new (a.b());", "new(a.b())();"),
+            ("(function () {})();", "(function(){})();"),
+            ("({}).a;", "({}).a;"),
+            ("a = (b, c);", "a=(b,c);"),
+            ("(yield_ = 1) ? b : c;", "(yield_=1)?b:c;"),
+        ];
+        for &(code, expected) in cases.iter() {
+            let ast: root::Ast = Parser::new(code.into_tokenizer()).parse_script().unwrap().into();
+            assert_eq!(print(&from_json(&to_json(&ast, code)).unwrap()), expected, "{}", code);
+        }
+    }
+
+    #[test]
+    fn it_keeps_positions() {
+        let code = "var a = 1;\nfoo(a, [b]);";
+        let ast: root::Ast = Parser::new(code.into_tokenizer()).parse_script().unwrap().into();
+        let read = from_json(&to_json(&ast, code)).unwrap();
+
+        let script = match read {
+            root::Ast::Script(ref script) => script,
+            _ => panic!("expected a script"),
+        };
+        let position = script.body[1].position().unwrap();
+        assert_eq!((position.start, position.end), (11, 23));
+        assert_eq!((position.range.start, position.range.end), ((2, 0), (2, 12)));
+
+        let call = match script.body[1] {
+            alias::StatementItem::Expression(ref statement) => match statement.expression {
+                alias::Expression::Call(ref call) => call,
+                _ => panic!("expected a call"),
+            },
+            _ => panic!("expected an expression statement"),
+        };
+        let callee = call.callee.position().unwrap();
+        assert_eq!((callee.start, callee.end), (11, 14));
+        let position = script.position.as_ref().unwrap();
+        assert_eq!((position.start, position.end), (0, 23));
+    }
+
+    #[test]
+    fn it_reads_ranges() {
+        let ast = from_json(
+            r#"{"type": "Program", "range": [0, 2], "loc": {"start": {"line": 1, "column": 0}, "end": {"line": 1, "column": 2}},
+                "body": [{"type": "ExpressionStatement", "expression": {"type": "Identifier", "name": "a"}}]}"#,
+        ).unwrap();
+
+        match ast {
+            root::Ast::Script(ref script) => {
+                assert_eq!(script.position.as_ref().map(|p| (p.start, p.end)), Some((0, 2)));
+                assert!(script.body[0].position().is_none());
+            }
+            _ => panic!("expected a script"),
+        }
+    }
+
+    #[test]
+    fn it_reads_jsx() {
+        let ast = from_json(
+            r#"{"type": "Program", "body": [{"type": "ExpressionStatement", "expression": {
+                "type": "JSXElement",
+                "openingElement": {"type": "JSXOpeningElement", "selfClosing": false,
+                    "name": {"type": "JSXMemberExpression",
+                        "object": {"type": "JSXIdentifier", "name": "A"},
+                        "property": {"type": "JSXIdentifier", "name": "B"}},
+                    "attributes": [
+                        {"type": "JSXAttribute", "name": {"type": "JSXIdentifier", "name": "c"},
+                            "value": {"type": "Literal", "value": "d", "raw": "\"d\""}},
+                        {"type": "JSXSpreadAttribute", "argument": {"type": "Identifier", "name": "e"}}
+                    ]},
+                "children": [
+                    {"type": "JSXText", "value": "text", "raw": "text"},
+                    {"type": "JSXExpressionContainer", "expression": {"type": "Identifier", "name": "f"}},
+                    {"type": "JSXElement", "children": [],
+                        "openingElement": {"type": "JSXOpeningElement", "selfClosing": true, "attributes": [],
+                            "name": {"type": "JSXIdentifier", "name": "g"}},
+                        "closingElement": null}
+                ],
+                "closingElement": {"type": "JSXClosingElement", "name": {"type": "JSXMemberExpression",
+                    "object": {"type": "JSXIdentifier", "name": "A"},
+                    "property": {"type": "JSXIdentifier", "name": "B"}}}
+            }}]}"#,
+        ).unwrap();
+
+        let element = match ast {
+            root::Ast::Script(ref script) => match script.body[0] {
+                alias::StatementItem::Expression(ref statement) => match statement.expression {
+                    alias::Expression::JSX(ref element) => element,
+                    _ => panic!("expected an element"),
+                },
+                _ => panic!("expected an expression statement"),
+            },
+            _ => panic!("expected a script"),
+        };
+        match element.opening {
+            jsx::ElementName::Member(ref member) => assert_eq!(member.property.value, "B"),
+            _ => panic!("expected a member name"),
+        }
+        assert_eq!(element.attributes.len(), 2);
+        assert_eq!(element.children.len(), 3);
+        assert!(element.closing.is_some());
+        match element.children[2] {
+            jsx::Child::Element(ref child) => assert!(child.closing.is_none()),
+            _ => panic!("expected a child element"),
+        }
+    }
+
+    #[test]
+    fn it_reads_babel_files() {
+        let ast = from_json(
+            r#"{"type": "File", "program": {
+                "type": "Program", "sourceType": "module",
+                "directives": [{"type": "Directive", "value": {
+                    "type": "DirectiveLiteral", "value": "use strict", "extra": {"raw": "'use strict'"}
+                }}],
+                "body": [{"type": "ExpressionStatement", "expression": {
+                    "type": "BinaryExpression", "operator": "*",
+                    "left": {"type": "NumericLiteral", "value": 1, "extra": {"raw": "1", "parenthesized": true}},
+                    "right": {"type": "StringLiteral", "value": "x", "extra": {"raw": "\"x\""}}
+                }}]
+            }}"#,
+        ).unwrap();
+
+        match ast {
+            root::Ast::Module(_) => {}
+            _ => panic!("expected a module"),
+        }
+        assert_eq!(print(&ast), "'use strict';(1)*'x';");
+    }
+
+    #[test]
+    fn it_rejects_unsupported_shapes() {
+        let error = |body: &str| {
+            from_json(&format!(r#"{{"type": "Program", "body": [{}]}}"#, body))
+                .unwrap_err()
+                .message
+        };
+
+        assert_eq!(
+            error(r#"{"type": "ExpressionStatement", "expression": {"type": "LogicalExpression", "operator": "??",
+                "left": {"type": "Identifier", "name": "a"}, "right": {"type": "Identifier", "name": "b"}}}"#),
+            "Unsupported binary operator \"??\""
+        );
+        assert_eq!(error(r#"{"type": "IfStatement", "consequent": {"type": "EmptyStatement"}}"#), "Missing \"test\" in IfStatement");
+        assert_eq!(error(r#"{"type": "TSEnumDeclaration"}"#), "Expected a statement, found TSEnumDeclaration");
+        assert_eq!(from_json("{").unwrap_err().message, "Invalid JSON at offset 1");
+    }
+
+    #[test]
+    fn it_rejects_invalid_identifiers() {
+        let error = |name: &str| {
+            from_json(&format!(
+                r#"{{"type": "Program", "body": [{{"type": "ExpressionStatement", "expression": {{
+                    "type": "MemberExpression", "computed": false,
+                    "object": {{"type": "Identifier", "name": "{}"}},
+                    "property": {{"type": "Identifier", "name": "b"}}
+                }}}}]}}"#,
+                name
+            )).err().map(|err| err.message)
+        };
+
+        assert_eq!(error("a b"), Some("Invalid identifier \"a b\" in Identifier".to_string()));
+        assert_eq!(error(""), Some("Invalid identifier \"\" in Identifier".to_string()));
+        assert_eq!(error("1a"), Some("Invalid identifier \"1a\" in Identifier".to_string()));
+        assert_eq!(error("class"), Some("Reserved word \"class\" used as an identifier".to_string()));
+        assert_eq!(error("$é_1"), None);
+    }
+}
@@ -0,0 +1,441 @@
+use std::char;
+use std::fmt::{self, Write};
+
+use failure::Fail;
+
+use ast::Str;
+use stack;
+
+/// A JSON document. Object keys keep their insertion order so that serialized
+/// nodes list "type" and the location fields first, like other ESTree tools.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+impl Value {
+    /// Look up a key on an object value.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match *self {
+            Value::Object(ref fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match *self {
+            Value::Array(ref items) => Some(items),
+            _ => None,
+        }
+    }
+    /// Serialize the value with each array item and object field on its own
+    /// line, indented by two spaces.
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0).unwrap();
+        out
+    }
+
+    fn write_pretty<W: Write>(&self, out: &mut W, depth: usize) -> fmt::Result {
+        let indent = |out: &mut W, depth: usize| write!(out, "\n{:1$}", "", depth * 2);
+        match *self {
+            Value::Array(ref items) if !items.is_empty() => {
+                out.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.write_char(',')?;
+                    }
+                    indent(out, depth + 1)?;
+                    stack::grow(|| item.write_pretty(out, depth + 1))?;
+                }
+                indent(out, depth)?;
+                out.write_char(']')
+            }
+            Value::Object(ref fields) if !fields.is_empty() => {
+                out.write_char('{')?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.write_char(',')?;
+                    }
+                    indent(out, depth + 1)?;
+                    write_string(out, key)?;
+                    out.write_str(": ")?;
+                    stack::grow(|| value.write_pretty(out, depth + 1))?;
+                }
+                indent(out, depth)?;
+                out.write_char('}')
+            }
+            ref value => write!(out, "{}", value),
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(*self, Value::Null)
+    }
+}
+impl From<bool> for Value {
+    fn from(b: bool) -> Value {
+        Value::Bool(b)
+    }
+}
+impl From<f64> for Value {
+    fn from(n: f64) -> Value {
+        Value::Number(n)
+    }
+}
+impl From<usize> for Value {
+    fn from(n: usize) -> Value {
+        Value::Number(n as f64)
+    }
+}
+impl<'a> From<&'a str> for Value {
+    fn from(s: &'a str) -> Value {
+        Value::String(s.into())
+    }
+}
+impl From<String> for Value {
+    fn from(s: String) -> Value {
+        Value::String(s)
+    }
+}
+impl From<Str<'_>> for Value {
+    fn from(s: Str) -> Value {
+        Value::String(s.into())
+    }
+}
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Value {
+        v.map(Into::into).unwrap_or(Value::Null)
+    }
+}
+impl From<Vec<Value>> for Value {
+    fn from(items: Vec<Value>) -> Value {
+        Value::Array(items)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            // JSON has no representation for NaN or the infinities.
+            Value::Number(n) if !n.is_finite() => f.write_str("null"),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(ref s) => write_string(f, s),
+            Value::Array(ref items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    stack::grow(|| write!(f, "{}", item))?;
+                }
+                f.write_char(']')
+            }
+            Value::Object(ref fields) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    stack::grow(|| write!(f, ":{}", value))?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// An error from parsing text that is not valid JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// The byte offset of the unexpected character.
+    pub offset: usize,
+}
+impl Fail for SyntaxError {}
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid JSON at offset {}", self.offset)
+    }
+}
+
+/// Parse a JSON document.
+pub fn parse(text: &str) -> Result<Value, SyntaxError> {
+    let mut reader = Reader { text, pos: 0 };
+    let value = reader.value()?;
+    reader.skip_whitespace();
+    if reader.pos < text.len() {
+        return Err(reader.error());
+    }
+    Ok(value)
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+impl<'a> Reader<'a> {
+    fn error(&self) -> SyntaxError {
+        SyntaxError { offset: self.pos }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, expected: u8) -> Result<(), SyntaxError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn eat_word(&mut self, word: &str, value: Value) -> Result<Value, SyntaxError> {
+        if !self.text[self.pos..].starts_with(word) {
+            return Err(self.error());
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, SyntaxError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.eat_word("null", Value::Null),
+            Some(b't') => self.eat_word("true", Value::Bool(true)),
+            Some(b'f') => self.eat_word("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(stack::grow(|| self.value())?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Value::Array(items));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error());
+                    }
+                    let key = self.string()?;
+                    self.eat(b':')?;
+                    fields.push((key, stack::grow(|| self.value())?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Value::Object(fields));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, SyntaxError> {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') | Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E') = self.peek() {
+            self.pos += 1;
+        }
+        self.text[start..self.pos]
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| SyntaxError { offset: start })
+    }
+
+    fn string(&mut self) -> Result<String, SyntaxError> {
+        // Skip the opening quote.
+        self.pos += 1;
+
+        let mut out = String::new();
+        loop {
+            let c = match self.text[self.pos..].chars().next() {
+                Some(c) => c,
+                None => return Err(self.error()),
+            };
+            self.pos += c.len_utf8();
+
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escape = self.peek().ok_or_else(|| self.error())?;
+                    self.pos += 1;
+                    match escape {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'n' => out.push('\n'),
+                        b'r' => out.push('\r'),
+                        b't' => out.push('\t'),
+                        b'u' => {
+                            let code = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&code) && self.text[self.pos..].starts_with("\\u") {
+                                let pos = self.pos;
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                if (0xdc00..0xe000).contains(&low) {
+                                    let code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                                    out.extend(char::from_u32(code));
+                                    continue;
+                                }
+                                self.pos = pos;
+                            }
+                            out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error());
+                        }
+                    }
+                }
+                c if (c as u32) < 0x20 => {
+                    self.pos -= 1;
+                    return Err(self.error());
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, SyntaxError> {
+        let code = self.text
+            .get(self.pos..self.pos + 4)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.error())?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+fn write_string<W: Write>(out: &mut W, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_serializes() {
+        let value = Value::Object(vec![
+            ("type".into(), "Literal".into()),
+            ("value".into(), Value::Number(1.5)),
+            ("raw".into(), "'a\"\n'".into()),
+            ("items".into(), vec![Value::Null, true.into(), 3usize.into()].into()),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            "{\"type\":\"Literal\",\"value\":1.5,\"raw\":\"'a\\\"\\n'\",\"items\":[null,true,3]}"
+        );
+    }
+
+    #[test]
+    fn it_serializes_pretty() {
+        let value = Value::Object(vec![
+            ("a".into(), vec![1usize.into(), Value::Object(vec![])].into()),
+            ("b".into(), Value::Array(vec![])),
+            ("c".into(), Value::Object(vec![("d".into(), "e".into())])),
+        ]);
+
+        assert_eq!(
+            value.to_pretty_string(),
+            "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": [],\n  \"c\": {\n    \"d\": \"e\"\n  }\n}"
+        );
+        assert_eq!(parse(&value.to_pretty_string()), Ok(value));
+    }
+
+    #[test]
+    fn it_parses() {
+        let value = parse(" { \"a\" : [1, -2.5e1, true, null], \"b\": \"x\\n\\u00e9\\ud83d\\ude00\" } ").unwrap();
+
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                ("a".into(), vec![1.0.into(), (-25.0).into(), true.into(), Value::Null].into()),
+                ("b".into(), "x\n\u{e9}\u{1F600}".into()),
+            ])
+        );
+        assert_eq!(parse(&value.to_string()), Ok(value));
+    }
+
+    #[test]
+    fn it_rejects_invalid_json() {
+        assert_eq!(parse("[1,]"), Err(SyntaxError { offset: 3 }));
+        assert_eq!(parse("{\"a\" 1}"), Err(SyntaxError { offset: 5 }));
+        assert_eq!(parse("\"a"), Err(SyntaxError { offset: 2 }));
+        assert_eq!(parse("1 2"), Err(SyntaxError { offset: 2 }));
+    }
+}
@@ -0,0 +1,15 @@
+//! Conversion between jsparse's AST and ESTree JSON, the format used by most
+//! JavaScript tooling (https://github.com/estree/estree).
+//!
+//! jsparse's AST is more fine-grained than ESTree, e.g. it has separate nodes
+//! for "a + b" and "a === b", so serialization maps each node to its ESTree
+//! equivalent, like "BinaryExpression" with an "operator". Deserialization goes
+//! the other way, also accepting Babel's variant of the format.
+
+mod de;
+pub mod json;
+mod ser;
+
+pub use self::de::{from_json, from_value, DeserializeError};
+pub use self::json::Value;
+pub use self::ser::{to_json, to_value, Serialize, Serializer};
@@ -0,0 +1,3340 @@
+use std::mem;
+
+use ast::{alias, classes, decorators, expression, flow, functions, general, jsx, literal, modules, objects,
+          patterns, root, statement, typescript, KeywordData, SourcePosition, TokenData, NodeBox};
+use estree::json::Value;
+use stack;
+
+type Span = (usize, usize);
+
+/// Converts nodes to ESTree JSON values.
+///
+/// The original source is needed to fill in "raw" values, which jsparse does
+/// not store for every literal, and to convert byte offsets into the UTF-16
+/// offsets and columns that JavaScript tools expect.
+pub struct Serializer<'code> {
+    source: &'code str,
+    line_starts: Vec<usize>,
+
+    // The UTF-16 offset of every byte offset, if the source is not ASCII.
+    utf16: Option<Vec<usize>>,
+
+    // Set while serializing the object or callee of a member or call
+    // expression, so optional chains are only wrapped once at the top.
+    chain: bool,
+}
+
+/// Implemented by every node that can be converted to ESTree.
+pub trait Serialize {
+    fn serialize(&self, s: &mut Serializer) -> Value;
+}
+impl<T: Serialize> Serialize for NodeBox<T> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        stack::grow(|| (**self).serialize(s))
+    }
+}
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        match *self {
+            Some(ref node) => node.serialize(s),
+            None => Value::Null,
+        }
+    }
+}
+
+/// Serialize a parsed file as an ESTree "Program" JSON string.
+pub fn to_json(ast: &root::Ast, source: &str) -> String {
+    to_value(ast, source).to_string()
+}
+
+/// Serialize a parsed file as an ESTree "Program" value.
+pub fn to_value(ast: &root::Ast, source: &str) -> Value {
+    Serializer::new(source).serialize(ast)
+}
+
+impl<'code> Serializer<'code> {
+    pub fn new(source: &'code str) -> Serializer<'code> {
+        let mut line_starts = vec![0];
+        let mut chars = source.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\r' if chars.peek().map(|&(_, c)| c) == Some('\n') => {}
+                '\n' | '\r' | '\u{2028}' | '\u{2029}' => line_starts.push(i + c.len_utf8()),
+                _ => {}
+            }
+        }
+
+        let utf16 = if source.is_ascii() {
+            None
+        } else {
+            let mut offsets = Vec::with_capacity(source.len() + 1);
+            let mut offset = 0;
+            for c in source.chars() {
+                for _ in 0..c.len_utf8() {
+                    offsets.push(offset);
+                }
+                offset += c.len_utf16();
+            }
+            offsets.push(offset);
+            Some(offsets)
+        };
+
+        Serializer {
+            source,
+            line_starts,
+            utf16,
+            chain: false,
+        }
+    }
+
+    pub fn serialize<T: Serialize + ?Sized>(&mut self, node: &T) -> Value {
+        node.serialize(self)
+    }
+
+    // Serialize the object or callee of a member or call expression.
+    fn chain_link<T: Serialize + ?Sized>(&mut self, node: &T) -> Value {
+        self.chain = true;
+        let value = node.serialize(self);
+        self.chain = false;
+        value
+    }
+
+    fn list<T: Serialize, L: Serialize>(&mut self, items: &[(T, KeywordData)], last: &Option<L>) -> Vec<Value> {
+        let mut values: Vec<_> = items.iter().map(|item| item.0.serialize(self)).collect();
+        if let Some(ref last) = *last {
+            values.push(last.serialize(self));
+        }
+        values
+    }
+
+    fn node(&self, span: Option<Span>, kind: &str, fields: Vec<(&str, Value)>) -> Value {
+        let mut out = vec![("type".to_string(), Value::from(kind))];
+
+        if let Some((start, end)) = span {
+            let start_position = self.location(start);
+            let end_position = self.location(end);
+            out.push(("start".into(), self.utf16_offset(start).into()));
+            out.push(("end".into(), self.utf16_offset(end).into()));
+            out.push(("loc".into(), object(vec![("start", start_position), ("end", end_position)])));
+            out.push((
+                "range".into(),
+                vec![self.utf16_offset(start).into(), self.utf16_offset(end).into()].into(),
+            ));
+        }
+
+        out.extend(fields.into_iter().map(|(key, value)| (key.to_string(), value)));
+        Value::Object(out)
+    }
+
+    fn utf16_offset(&self, offset: usize) -> usize {
+        match self.utf16 {
+            Some(ref offsets) => offsets.get(offset).cloned().unwrap_or(offset),
+            None => offset,
+        }
+    }
+
+    fn location(&self, offset: usize) -> Value {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        let column = self.utf16_offset(offset) - self.utf16_offset(self.line_starts[line]);
+
+        object(vec![("line", (line + 1).into()), ("column", column.into())])
+    }
+
+    // The byte offset of the start of a token.
+    fn token_start<T: TokenData + ?Sized>(&self, token: &T) -> Option<usize> {
+        let (line, column) = token.position()?.start;
+        let line_start = *self.line_starts.get(line.checked_sub(1)?)?;
+
+        Some(match self.source[line_start..].char_indices().nth(column) {
+            Some((i, _)) => line_start + i,
+            None => self.source.len(),
+        })
+    }
+
+    // The byte offset after skipping the given number of keywords from the
+    // given offset, along with any whitespace and comments around them.
+    fn skip_words(&self, mut offset: usize, count: usize) -> usize {
+        for _ in 0..count {
+            offset = self.skip_trivia(offset);
+            offset += self.source[offset..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(self.source.len() - offset);
+        }
+        self.skip_trivia(offset)
+    }
+
+    fn skip_trivia(&self, mut offset: usize) -> usize {
+        loop {
+            let rest = &self.source[offset..];
+            if rest.starts_with("//") {
+                offset += rest.find(is_line_terminator).unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                offset += comment.find("*/").map(|i| i + 4).unwrap_or(rest.len());
+            } else {
+                match rest.chars().next() {
+                    Some(c) if c.is_whitespace() || c == '\u{feff}' => offset += c.len_utf8(),
+                    _ => return offset,
+                }
+            }
+        }
+    }
+
+    // The source text of a node, if it has a position.
+    fn raw(&self, span: Option<Span>) -> Option<&'code str> {
+        span.and_then(|(start, end)| self.source.get(start..end))
+    }
+
+    fn function(
+        &mut self,
+        span: Option<Span>,
+        kind: &str,
+        id: Value,
+        (generator, is_async): (bool, bool),
+        params: &functions::FunctionParams,
+        body: &functions::FunctionBody,
+    ) -> Value {
+        let params_value = self.serialize(params);
+        let body = self.serialize(body);
+        let function = self.node(span, kind, vec![
+            ("id", id),
+            ("expression", false.into()),
+            ("generator", generator.into()),
+            ("async", is_async.into()),
+            ("params", params_value),
+            ("body", body),
+        ]);
+        self.typed_function(function, params)
+    }
+
+    // Add the TypeScript type parameters and return type of a function, if
+    // it has any.
+    fn typed_function(&mut self, mut function: Value, params: &functions::FunctionParams) -> Value {
+        if let Some(ref type_parameters) = params.type_parameters {
+            let type_parameters = self.serialize(type_parameters);
+            push_field(&mut function, "typeParameters", type_parameters);
+        }
+        if let Some(ref return_type) = params.return_type {
+            let return_type = self.serialize(return_type);
+            push_field(&mut function, "returnType", return_type);
+        }
+        if let Some(ref predicate) = params.predicate {
+            let predicate = self.serialize(predicate);
+            push_field(&mut function, "predicate", predicate);
+        }
+        function
+    }
+
+    fn method_value(&mut self, kind: &objects::MethodKind, params: &functions::FunctionParams, body: &functions::FunctionBody) -> Value {
+        let value_span = join(span(params), span(body));
+        self.function(value_span, "FunctionExpression", Value::Null, method_flags(kind), params, body)
+    }
+
+    fn class(
+        &mut self,
+        span: Option<Span>,
+        kind: &str,
+        decorators: &[classes::ClassDecorator],
+        id: &Option<classes::ClassName>,
+        heritage: &Option<classes::ClassHeritage>,
+        body: &classes::ClassBody,
+    ) -> Value {
+        let mut fields = vec![];
+        if !decorators.is_empty() {
+            fields.push(("decorators", self.serialize_all(decorators)));
+        }
+        fields.push(("id", self.serialize(id)));
+        fields.push(("superClass", self.serialize(heritage)));
+        if let Some(type_arguments) = heritage.as_ref().and_then(|heritage| heritage.type_arguments.as_ref()) {
+            fields.push(("superTypeArguments", self.serialize(type_arguments)));
+        }
+        fields.push(("body", self.serialize(body)));
+
+        self.node(span, kind, fields)
+    }
+
+    // Add the TypeScript modifiers, type parameters and interfaces of a class.
+    fn typed_class(
+        &mut self,
+        mut class: Value,
+        modifiers: &[typescript::Modifier],
+        type_parameters: &Option<typescript::TypeParameters>,
+        implements: &[typescript::TypeReference],
+    ) -> Value {
+        for (key, value) in modifier_fields(self, modifiers) {
+            push_field(&mut class, key, value);
+        }
+        if let Some(ref type_parameters) = *type_parameters {
+            let type_parameters = self.serialize(type_parameters);
+            push_field(&mut class, "typeParameters", type_parameters);
+        }
+        if !implements.is_empty() {
+            let implements = implements.iter().map(|reference| self.heritage(reference)).collect::<Vec<_>>();
+            push_field(&mut class, "implements", implements.into());
+        }
+        class
+    }
+
+    // An interface in the "extends" list of an interface or the "implements"
+    // list of a class.
+    fn heritage(&mut self, reference: &typescript::TypeReference) -> Value {
+        let expression = self.serialize(&reference.name);
+        let type_arguments = self.serialize(&reference.type_arguments);
+        self.node(span(reference), "TSInterfaceHeritage", vec![
+            ("expression", expression),
+            ("typeArguments", type_arguments),
+        ])
+    }
+
+    // A type wrapped in a "TSTypeAnnotation", like the return type of a
+    // function type.
+    fn type_annotation(&mut self, span: Option<Span>, value: &typescript::Type) -> Value {
+        let value = self.serialize(value);
+        self.node(span, "TSTypeAnnotation", vec![("typeAnnotation", value)])
+    }
+
+    fn serialize_all<T: Serialize>(&mut self, nodes: &[T]) -> Value {
+        nodes.iter().map(|node| node.serialize(self)).collect::<Vec<_>>().into()
+    }
+
+    fn with_default(&mut self, span: Option<Span>, pattern: Value, init: &Option<general::Initializer>) -> Value {
+        match *init {
+            Some(ref init) => {
+                let right = self.serialize(init);
+                self.node(span, "AssignmentPattern", vec![("left", pattern), ("right", right)])
+            }
+            None => pattern,
+        }
+    }
+
+    fn property(&mut self, span: Option<Span>, key: &general::PropertyName, value: Value, kind: &str, method: bool) -> Value {
+        let computed = matches!(*key, general::PropertyName::Computed(_));
+        let key = self.serialize(key);
+        self.node(span, "Property", vec![
+            ("key", key),
+            ("value", value),
+            ("kind", kind.into()),
+            ("method", method.into()),
+            ("shorthand", false.into()),
+            ("computed", computed.into()),
+        ])
+    }
+
+    fn shorthand_property(&mut self, span: Option<Span>, key: Value, value: Value) -> Value {
+        self.node(span, "Property", vec![
+            ("key", key),
+            ("value", value),
+            ("kind", "init".into()),
+            ("method", false.into()),
+            ("shorthand", true.into()),
+            ("computed", false.into()),
+        ])
+    }
+
+    fn variable_declaration(&mut self, span: Option<Span>, kind: &str, declarations: Vec<Value>) -> Value {
+        self.node(span, "VariableDeclaration", vec![
+            ("declarations", declarations.into()),
+            ("kind", kind.into()),
+        ])
+    }
+
+    fn declarator(
+        &mut self,
+        span: Option<Span>,
+        id: &patterns::BindingPattern,
+        definite: bool,
+        type_annotation: Option<&typescript::TypeAnnotation>,
+        init: Option<&general::Initializer>,
+    ) -> Value {
+        let mut id = self.serialize(id);
+        if let Some(type_annotation) = type_annotation {
+            let type_annotation = self.serialize(type_annotation);
+            push_field(&mut id, "typeAnnotation", type_annotation);
+        }
+        let init = match init {
+            Some(init) => self.serialize(init),
+            None => Value::Null,
+        };
+        let mut fields = vec![("id", id), ("init", init)];
+        if definite {
+            fields.push(("definite", true.into()));
+        }
+        self.node(span, "VariableDeclarator", fields)
+    }
+
+    // A declaration inside of a "for" head, e.g. "let x" in "for (let x of y)".
+    fn for_declaration(&mut self, node_span: Option<Span>, kind: &str, pattern: &patterns::BindingPattern, init: Option<&general::Initializer>) -> Value {
+        let declarator_span = join(span(pattern), init.map_or(span(pattern), span));
+        let declarator = self.declarator(declarator_span, pattern, false, None, init);
+        self.variable_declaration(node_span, kind, vec![declarator])
+    }
+
+    fn member(&mut self, span: Option<Span>, object: Value, property: Value, computed: bool, optional: bool) -> Value {
+        self.node(span, "MemberExpression", vec![
+            ("object", object),
+            ("property", property),
+            ("computed", computed.into()),
+            ("optional", optional.into()),
+        ])
+    }
+
+    // The callee of a call or "new" expression, and its TypeScript type
+    // arguments, like "<T>" in "f<T>()".
+    fn callee(&mut self, callee: &alias::Expression) -> (Value, Option<Value>) {
+        match *callee {
+            alias::Expression::Instantiation(ref n) => {
+                let callee = self.chain_link(&n.expression);
+                (callee, Some(self.serialize(&n.type_arguments)))
+            }
+            _ => (self.chain_link(callee), None),
+        }
+    }
+
+    fn call(&mut self, span: Option<Span>, callee: Value, arguments: &expression::CallArguments, optional: bool) -> Value {
+        let arguments = self.serialize(arguments);
+        self.node(span, "CallExpression", vec![
+            ("callee", callee),
+            ("arguments", arguments),
+            ("optional", optional.into()),
+        ])
+    }
+
+    fn literal(&self, span: Option<Span>, value: Value, raw: String) -> Value {
+        self.node(span, "Literal", vec![("value", value), ("raw", raw.into())])
+    }
+
+    fn identifier(&self, span: Option<Span>, name: &str) -> Value {
+        self.node(span, "Identifier", vec![("name", name.into())])
+    }
+
+    // An identifier for a keyword token, like "target" in "new.target".
+    fn token_identifier<T: TokenData + ?Sized>(&self, token: &T, name: &str) -> Value {
+        let span = self.token_start(token).map(|start| (start, start + name.len()));
+        self.identifier(span, name)
+    }
+
+    fn meta_property<T: TokenData + ?Sized, U: TokenData + ?Sized>(&self, span: Option<Span>, meta: (&T, &str), property: (&U, &str)) -> Value {
+        let meta = self.token_identifier(meta.0, meta.1);
+        let property = self.token_identifier(property.0, property.1);
+        self.node(span, "MetaProperty", vec![("meta", meta), ("property", property)])
+    }
+
+    fn super_node<T: TokenData + ?Sized>(&self, token: &T) -> Value {
+        let span = self.token_start(token).map(|start| (start, start + "super".len()));
+        self.node(span, "Super", vec![])
+    }
+
+    fn import_declaration(&mut self, span: Option<Span>, specifiers: Vec<Value>, source: &literal::String) -> Value {
+        let source = self.serialize(source);
+        self.node(span, "ImportDeclaration", vec![("specifiers", specifiers.into()), ("source", source)])
+    }
+
+    fn export_named(&mut self, span: Option<Span>, declaration: Value, specifiers: Vec<Value>, source: Option<&literal::String>) -> Value {
+        let source = match source {
+            Some(source) => self.serialize(source),
+            None => Value::Null,
+        };
+        self.node(span, "ExportNamedDeclaration", vec![
+            ("declaration", declaration),
+            ("specifiers", specifiers.into()),
+            ("source", source),
+        ])
+    }
+
+    fn export_default(&mut self, span: Option<Span>, declaration: Value) -> Value {
+        self.node(span, "ExportDefaultDeclaration", vec![("declaration", declaration)])
+    }
+
+    // A specifier that starts with a "*" token, e.g. "* as ns".
+    fn namespace_specifier<T: Serialize + SourcePosition>(&mut self, kind: &str, key: &str, star: &KeywordData, id: &T) -> Value {
+        let span = self.token_start(star).and_then(|start| span(id).map(|(_, end)| (start, end)));
+        let id = self.serialize(id);
+        self.node(span, kind, vec![(key, id)])
+    }
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    Value::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+// Add a field to an already serialized node.
+fn push_field(node: &mut Value, key: &str, value: Value) {
+    if let Value::Object(ref mut fields) = *node {
+        fields.push((key.into(), value));
+    }
+}
+
+// The fields for TypeScript modifiers, leaving out the ones that are absent.
+fn modifier_fields(s: &Serializer, modifiers: &[typescript::Modifier]) -> Vec<(&'static str, Value)> {
+    modifiers.iter().map(|modifier| match modifier.kind {
+        typescript::ModifierKind::Public => ("accessibility", "public".into()),
+        typescript::ModifierKind::Private => ("accessibility", "private".into()),
+        typescript::ModifierKind::Protected => ("accessibility", "protected".into()),
+        typescript::ModifierKind::Readonly => ("readonly", true.into()),
+        typescript::ModifierKind::Abstract => ("abstract", true.into()),
+        typescript::ModifierKind::Override => ("override", true.into()),
+        typescript::ModifierKind::Declare => ("declare", true.into()),
+        typescript::ModifierKind::Covariant => ("variance", s.node(span(modifier), "Variance", vec![("kind", "plus".into())])),
+        typescript::ModifierKind::Contravariant => ("variance", s.node(span(modifier), "Variance", vec![("kind", "minus".into())])),
+        typescript::ModifierKind::In => ("in", true.into()),
+        typescript::ModifierKind::Out => ("out", true.into()),
+    }).collect()
+}
+
+fn span<T: SourcePosition + ?Sized>(node: &T) -> Option<Span> {
+    node.source_position().map(|position| (position.start, position.end))
+}
+
+fn join(start: Option<Span>, end: Option<Span>) -> Option<Span> {
+    match (start, end) {
+        (Some((start, _)), Some((_, end))) => Some((start, end)),
+        _ => None,
+    }
+}
+
+fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+fn function_flags(kind: &functions::FunctionKind) -> (bool, bool) {
+    match *kind {
+        functions::FunctionKind::Normal => (false, false),
+        functions::FunctionKind::Generator => (true, false),
+        functions::FunctionKind::Async => (false, true),
+        functions::FunctionKind::AsyncGenerator => (true, true),
+    }
+}
+
+fn method_flags(kind: &objects::MethodKind) -> (bool, bool) {
+    match *kind {
+        objects::MethodKind::Normal | objects::MethodKind::Get | objects::MethodKind::Set => (false, false),
+        objects::MethodKind::Generator => (true, false),
+        objects::MethodKind::Async => (false, true),
+        objects::MethodKind::AsyncGenerator => (true, true),
+    }
+}
+
+fn method_kind(kind: &objects::MethodKind) -> &'static str {
+    match *kind {
+        objects::MethodKind::Get => "get",
+        objects::MethodKind::Set => "set",
+        _ => "init",
+    }
+}
+
+/// Decode the escape sequences in the source text of a string literal.
+///
+/// The tokenizer keeps string values in their escaped source form, while
+/// ESTree's "value" is the string itself.
+fn cook(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let c = match chars.next() {
+            Some(c) => c,
+            None => break,
+        };
+        match c {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'v' => out.push('\u{b}'),
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            '0'..='7' => {
+                // Legacy octal escapes, which also covers "\0".
+                let mut value = c.to_digit(8).unwrap();
+                let max_len = if c <= '3' { 3 } else { 2 };
+                for _ in 1..max_len {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                out.extend(::std::char::from_u32(value));
+            }
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                out.extend(u32::from_str_radix(&hex, 16).ok().and_then(::std::char::from_u32));
+            }
+            'u' => {
+                let hex: String = if chars.peek() == Some(&'{') {
+                    chars.next();
+                    chars.by_ref().take_while(|&c| c != '}').collect()
+                } else {
+                    chars.by_ref().take(4).collect()
+                };
+                let code = u32::from_str_radix(&hex, 16).unwrap_or(0xfffd);
+
+                // Combine surrogate pairs written as two escapes.
+                if (0xd800..0xdc00).contains(&code) {
+                    let mut rest = chars.clone();
+                    if rest.next() == Some('\\') && rest.next() == Some('u') {
+                        let low: String = rest.by_ref().take(4).collect();
+                        if let Ok(low) = u32::from_str_radix(&low, 16) {
+                            if (0xdc00..0xe000).contains(&low) {
+                                chars = rest;
+                                out.extend(::std::char::from_u32(0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00)));
+                                continue;
+                            }
+                        }
+                    }
+                }
+                out.push(::std::char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Decode the character references in JSX text and attribute strings.
+///
+/// Numeric references and the common named ones are decoded, and anything
+/// else is kept as it is written.
+fn decode_entities(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let end = rest[1..].find([';', '&']).map(|end| end + 1);
+        let decoded = match end {
+            Some(end) if rest.as_bytes()[end] == b';' => {
+                let name = &rest[1..end];
+                let c = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok().and_then(::std::char::from_u32)
+                } else if let Some(decimal) = name.strip_prefix('#') {
+                    decimal.parse().ok().and_then(::std::char::from_u32)
+                } else {
+                    match name {
+                        "amp" => Some('&'),
+                        "lt" => Some('<'),
+                        "gt" => Some('>'),
+                        "quot" => Some('"'),
+                        "apos" => Some('\''),
+                        "nbsp" => Some('\u{a0}'),
+                        _ => None,
+                    }
+                };
+                c.map(|c| (c, end + 1))
+            }
+            _ => None,
+        };
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+
+    out
+}
+
+// Whether an expression is an optional chain, like "a?.b.c", that needs to be
+// wrapped in a "ChainExpression".
+fn is_optional_chain(expr: &alias::Expression) -> bool {
+    match *expr {
+        alias::Expression::OptionalMember(_) | alias::Expression::OptionalCall(_) => true,
+        alias::Expression::Member(ref n) => is_optional_chain(&n.object),
+        alias::Expression::Call(ref n) => is_optional_chain(&n.callee),
+        _ => false,
+    }
+}
+
+// Serialize enums whose items are serialized as-is.
+macro_rules! serialize_enum {
+    ($($module:ident::$name:ident { $($key:ident,)* })*) => {
+        $(
+            impl Serialize for $module::$name<'_> {
+                fn serialize(&self, s: &mut Serializer) -> Value {
+                    use ast::$module::$name as Enum;
+
+                    match *self {
+                        $(
+                            Enum::$key(ref n) => n.serialize(s),
+                        )*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+serialize_enum! {
+    root::Ast { Script, Module, }
+    alias::Function { ClassMethod, ObjectMethod, DefaultDeclaration, Declaration, Expression, Arrow, }
+    alias::Method { ClassMethod, ObjectMethod, }
+    alias::ModuleStatementItem {
+        Block, Variable, Empty, Expression, If, IfElse, For, ForIn, ForOf, ForAwait, While, DoWhile,
+        Switch, Continue, Break, Return, With, Labelled, Throw, TryCatch, TryCatchFinally, TryFinally,
+        Debugger, Function, Class, Let, Const, ExportDefaultClass, ExportDefaultFunction,
+        ExportDefaultExpression, ExportClass, ExportFunction, ExportVariable, ExportLet, ExportConst,
+        ExportLocalBindings, ExportSourceSpecifiers, ExportAll, ExportNamed, ExportNamedAndNamespace,
+        ExportNamespace, ExportNamedAndSpecifiers, ImportSource, ImportNamed, ImportNamedAndNamespace,
+        ImportNamespace, ImportNamedAndSpecifiers, ImportSpecifiers, TypeScript, ExportTypeScript,
+        ExportAssignment, ExportTypes, ImportType,
+    }
+    alias::StatementItem {
+        Block, Variable, Empty, Expression, If, IfElse, For, ForIn, ForOf, ForAwait, While, DoWhile,
+        Switch, Continue, Break, Return, With, Labelled, Throw, TryCatch, TryCatchFinally, TryFinally,
+        Debugger, Function, Class, Let, Const, TypeScript,
+    }
+    alias::Statement {
+        Block, Variable, Empty, Expression, If, IfElse, For, ForIn, ForOf, ForAwait, While, DoWhile,
+        Switch, Continue, Break, Return, With, Labelled, Throw, TryCatch, TryCatchFinally, TryFinally,
+        Debugger, Function,
+    }
+    alias::ExportDeclaration {
+        DefaultClass, DefaultFunction, DefaultExpression, Class, Function, Variable, Let, Const,
+        LocalBindings, SourceSpecifiers, All, Named, NamedAndNamespace, Namespace, NamedAndSpecifiers,
+        TypeScript, Assignment, Types,
+    }
+    alias::ImportDeclaration { Source, Named, NamedAndNamespace, Namespace, NamedAndSpecifiers, Specifiers, Type, }
+    classes::ClassFieldId { Public, Private, }
+    decorators::DecoratorValue { Property, Call, Expression, }
+    decorators::DecoratorValueExpression { Identifier, Member, }
+    expression::CallArgument { Expression, Spread, }
+    expression::NewExpression { Normal, Empty, }
+    functions::FunctionLastParam { Param, Rest, }
+    functions::ArrowFunctionBody { Expression, Block, }
+    general::PropertyName { Identifier, String, Number, Computed, }
+    jsx::ElementName { Identifier, Member, Namespaced, }
+    jsx::MemberObject { Identifier, Member, }
+    jsx::Attribute { Spread, Pair, }
+    jsx::AttributeName { Identifier, Namespaced, }
+    jsx::AttributeValue { String, Expression, Element, }
+    jsx::Child { Empty, Text, Element, Expression, Spread, }
+    modules::ImportSpecifier { Normal, Aliased, Type, }
+    modules::LocalExportSpecifier { Normal, Aliased, }
+    modules::SourceExportSpecifier { Normal, Aliased, }
+    objects::ObjectItem { Method, Property, Shorthand, Spread, }
+    objects::ArrayItem { Expression, Spread, }
+    patterns::LeftHandSimpleAssign { Identifier, Member, Parenthesized, }
+    patterns::LeftHandComplexAssign { Parenthesized, Identifier, Member, Object, Array, }
+    patterns::ObjectAssignmentPatternProperty { Identifier, Pattern, }
+    patterns::ObjectAssignmentPatternLastProperty { Identifier, Pattern, Rest, }
+    patterns::ArrayAssignmentPatternLastElement { Pattern, Rest, }
+    patterns::BindingPattern { Identifier, Object, Array, }
+    patterns::ObjectBindingPatternProperty { Identifier, Pattern, }
+    patterns::ObjectBindingPatternLastProperty { Identifier, Pattern, Rest, }
+    patterns::ArrayBindingPatternLastElement { Pattern, Rest, }
+    statement::ForInit { Var, Let, Const, Expression, }
+    statement::ForInInit { Var, Let, Const, Complex, }
+    statement::ForOfInit { Var, Let, Const, Complex, }
+    statement::SwitchClause { Case, Default, }
+    typescript::Type {
+        Keyword, Reference, Literal, Array, Tuple, Union, Intersection, Function, Object, Parenthesized,
+        Query, Operator, IndexedAccess, Predicate, Conditional, Infer, Mapped, NamedTupleMember, Optional,
+        Rest, TemplateLiteral, Import, Maybe, ExactObject,
+    }
+    typescript::TypeName { Identifier, Qualified, }
+    typescript::TypeQueryName { Name, Import, }
+    typescript::TypeMember { Property, Method, Call, Construct, Index, Spread, }
+    typescript::Declaration { Interface, TypeAlias, Enum, Declare, FunctionSignature, Module, ImportEquals, Opaque, Variable, }
+    typescript::ModuleName { Identifier, Qualified, String, }
+    typescript::ModuleReference { Name, External, }
+}
+
+impl Serialize for alias::Expression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        use ast::alias::Expression::*;
+
+        let in_chain = mem::replace(&mut s.chain, false);
+        if !in_chain && is_optional_chain(self) {
+            s.chain = true;
+            let expression = self.serialize(s);
+            return s.node(span(self), "ChainExpression", vec![("expression", expression)]);
+        }
+
+        match *self {
+            Binding(ref n) => n.serialize(s),
+            This(ref n) => n.serialize(s),
+            Array(ref n) => n.serialize(s),
+            Object(ref n) => n.serialize(s),
+            Null(ref n) => n.serialize(s),
+            Boolean(ref n) => n.serialize(s),
+            Numeric(ref n) => n.serialize(s),
+            String(ref n) => n.serialize(s),
+            Function(ref n) => n.serialize(s),
+            Class(ref n) => n.serialize(s),
+            Regex(ref n) => n.serialize(s),
+            Template(ref n) => n.serialize(s),
+            TaggedTemplate(ref n) => n.serialize(s),
+            Parenthesized(ref n) => n.serialize(s),
+            Member(ref n) => n.serialize(s),
+            OptionalMember(ref n) => n.serialize(s),
+            SuperMember(ref n) => n.serialize(s),
+            Call(ref n) => n.serialize(s),
+            OptionalCall(ref n) => n.serialize(s),
+            New(ref n) => n.serialize(s),
+            ImportCall(ref n) => n.serialize(s),
+            SuperCall(ref n) => n.serialize(s),
+            Conditional(ref n) => n.serialize(s),
+            Assign(ref n) => n.serialize(s),
+            Sequence(ref n) => n.serialize(s),
+            Arrow(ref n) => n.serialize(s),
+            Do(ref n) => n.serialize(s),
+            JSX(ref n) => n.serialize(s),
+            Add(ref n) => n.serialize(s),
+            Subtract(ref n) => n.serialize(s),
+            LeftShift(ref n) => n.serialize(s),
+            RightShift(ref n) => n.serialize(s),
+            RightShiftSigned(ref n) => n.serialize(s),
+            Divide(ref n) => n.serialize(s),
+            Multiply(ref n) => n.serialize(s),
+            Modulus(ref n) => n.serialize(s),
+            BitAnd(ref n) => n.serialize(s),
+            BitOr(ref n) => n.serialize(s),
+            BitXor(ref n) => n.serialize(s),
+            Power(ref n) => n.serialize(s),
+            Compare(ref n) => n.serialize(s),
+            StrictCompare(ref n) => n.serialize(s),
+            NegateCompare(ref n) => n.serialize(s),
+            NegateStrictCompare(ref n) => n.serialize(s),
+            LessThan(ref n) => n.serialize(s),
+            LessThanEq(ref n) => n.serialize(s),
+            GreaterThan(ref n) => n.serialize(s),
+            GreaterThanEq(ref n) => n.serialize(s),
+            In(ref n) => n.serialize(s),
+            Instanceof(ref n) => n.serialize(s),
+            And(ref n) => n.serialize(s),
+            Or(ref n) => n.serialize(s),
+            Bind(ref n) => n.serialize(s),
+            AddAssign(ref n) => n.serialize(s),
+            SubtractAssign(ref n) => n.serialize(s),
+            LeftShiftAssign(ref n) => n.serialize(s),
+            RightShiftAssign(ref n) => n.serialize(s),
+            RightShiftSignedAssign(ref n) => n.serialize(s),
+            DivideAssign(ref n) => n.serialize(s),
+            MultiplyAssign(ref n) => n.serialize(s),
+            ModulusAssign(ref n) => n.serialize(s),
+            BitAndAssign(ref n) => n.serialize(s),
+            BitOrAssign(ref n) => n.serialize(s),
+            BitXorAssign(ref n) => n.serialize(s),
+            PowerAssign(ref n) => n.serialize(s),
+            PreIncrement(ref n) => n.serialize(s),
+            PreDecrement(ref n) => n.serialize(s),
+            PostIncrement(ref n) => n.serialize(s),
+            PostDecrement(ref n) => n.serialize(s),
+            Delete(ref n) => n.serialize(s),
+            Void(ref n) => n.serialize(s),
+            Typeof(ref n) => n.serialize(s),
+            Positive(ref n) => n.serialize(s),
+            Negative(ref n) => n.serialize(s),
+            BitNegate(ref n) => n.serialize(s),
+            Negate(ref n) => n.serialize(s),
+            Await(ref n) => n.serialize(s),
+            Yield(ref n) => n.serialize(s),
+            DelegateYield(ref n) => n.serialize(s),
+            BindMember(ref n) => n.serialize(s),
+            NewTarget(ref n) => n.serialize(s),
+            ImportMeta(ref n) => n.serialize(s),
+            FunctionSent(ref n) => n.serialize(s),
+            FunctionArguments(ref n) => n.serialize(s),
+            As(ref n) => n.serialize(s),
+            Satisfies(ref n) => n.serialize(s),
+            NonNull(ref n) => n.serialize(s),
+            Instantiation(ref n) => n.serialize(s),
+            TypeAssertion(ref n) => n.serialize(s),
+            TypeCast(ref n) => n.serialize(s),
+        }
+    }
+}
+
+
+// Root
+
+impl Serialize for root::Script<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut body = s.serialize_all(&self.directives);
+        if let Value::Array(ref mut items) = body {
+            items.extend(self.body.iter().map(|item| item.serialize(s)));
+        }
+        s.node(span(self), "Program", vec![("body", body), ("sourceType", "script".into())])
+    }
+}
+impl Serialize for root::Module<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut body = s.serialize_all(&self.directives);
+        if let Value::Array(ref mut items) = body {
+            items.extend(self.body.iter().map(|item| item.serialize(s)));
+        }
+        s.node(span(self), "Program", vec![("body", body), ("sourceType", "module".into())])
+    }
+}
+
+
+// Literals and identifiers
+
+impl Serialize for literal::Null<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.literal(span(self), Value::Null, "null".into())
+    }
+}
+impl Serialize for literal::Boolean<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.literal(span(self), self.value.into(), self.value.to_string())
+    }
+}
+impl Serialize for literal::Numeric<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let raw = match self.raw {
+            Some(ref raw) => raw.to_string(),
+            None => s.raw(span(self)).map(Into::into).unwrap_or_else(|| self.value.to_string()),
+        };
+        s.literal(span(self), self.value.into(), raw)
+    }
+}
+impl Serialize for literal::String<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let raw = match self.raw {
+            Some(ref raw) => raw.to_string(),
+            None => s.raw(span(self)).map(Into::into).unwrap_or_else(|| format!("'{}'", self.value)),
+        };
+        s.literal(span(self), cook(&self.value).into(), raw)
+    }
+}
+impl Serialize for literal::RegExp<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let flags: String = self.flags.iter().collect();
+        let raw = s.raw(span(self)).map(Into::into).unwrap_or_else(|| format!("/{}/{}", self.value, flags));
+        let regex = object(vec![("pattern", self.value.as_str().into()), ("flags", flags.into())]);
+
+        s.node(span(self), "Literal", vec![("value", Value::Null), ("raw", raw.into()), ("regex", regex)])
+    }
+}
+
+impl Serialize for general::BindingIdentifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.identifier(span(self), &self.value)
+    }
+}
+impl Serialize for general::ReferenceIdentifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.identifier(span(self), &self.value)
+    }
+}
+impl Serialize for general::PropertyIdentifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.identifier(span(self), &self.value)
+    }
+}
+impl Serialize for general::ComputedPropertyName<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for general::Initializer<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for modules::ModuleIdentifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.identifier(span(self), &self.value)
+    }
+}
+impl Serialize for statement::LabelIdentifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.identifier(span(self), &self.value)
+    }
+}
+
+
+// Statements
+
+impl Serialize for functions::Directive<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.value);
+        s.node(span(self), "ExpressionStatement", vec![
+            ("expression", expression),
+            ("directive", self.value.value.as_str().into()),
+        ])
+    }
+}
+impl Serialize for functions::DirectiveLiteral<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let raw = s.raw(span(self)).map(Into::into).unwrap_or_else(|| format!("'{}'", self.value));
+        s.literal(span(self), cook(&self.value).into(), raw)
+    }
+}
+impl Serialize for statement::BlockStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let body = s.serialize_all(&self.body);
+        s.node(span(self), "BlockStatement", vec![("body", body)])
+    }
+}
+impl Serialize for statement::VariableStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut declarations = s.list(&self.declarators, &None::<statement::VariableDeclarator>);
+        declarations.push(s.serialize(&self.last_declarator));
+        s.variable_declaration(span(self), "var", declarations)
+    }
+}
+impl Serialize for statement::VariableDeclarator<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.declarator(span(self), &self.id, self.definite, self.type_annotation.as_ref(), self.init.as_ref())
+    }
+}
+impl Serialize for statement::LetDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut declarations = s.list(&self.declarators, &None::<statement::LetDeclarator>);
+        declarations.push(s.serialize(&self.last_declarator));
+        s.variable_declaration(span(self), "let", declarations)
+    }
+}
+impl Serialize for statement::LetDeclarator<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.declarator(span(self), &self.id, self.definite, self.type_annotation.as_ref(), self.init.as_ref())
+    }
+}
+impl Serialize for statement::ConstDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut declarations = s.list(&self.declarators, &None::<statement::ConstDeclarator>);
+        declarations.push(s.serialize(&self.last_declarator));
+        s.variable_declaration(span(self), "const", declarations)
+    }
+}
+impl Serialize for statement::ConstDeclarator<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.declarator(span(self), &self.id, false, self.type_annotation.as_ref(), Some(&self.init))
+    }
+}
+impl Serialize for statement::ExpressionStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        s.node(span(self), "ExpressionStatement", vec![("expression", expression)])
+    }
+}
+impl Serialize for statement::IfStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let test = s.serialize(&self.test);
+        let consequent = s.serialize(&self.consequent);
+        s.node(span(self), "IfStatement", vec![
+            ("test", test),
+            ("consequent", consequent),
+            ("alternate", Value::Null),
+        ])
+    }
+}
+impl Serialize for statement::IfElseStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let test = s.serialize(&self.test);
+        let consequent = s.serialize(&self.consequent);
+        let alternate = s.serialize(&self.alternate);
+        s.node(span(self), "IfStatement", vec![
+            ("test", test),
+            ("consequent", consequent),
+            ("alternate", alternate),
+        ])
+    }
+}
+impl Serialize for statement::ForStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let init = s.serialize(&self.init);
+        let test = s.serialize(&self.test);
+        let update = s.serialize(&self.update);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "ForStatement", vec![
+            ("init", init),
+            ("test", test),
+            ("update", update),
+            ("body", body),
+        ])
+    }
+}
+impl Serialize for statement::ForInStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let left = s.serialize(&self.left);
+        let right = s.serialize(&self.right);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "ForInStatement", vec![("left", left), ("right", right), ("body", body)])
+    }
+}
+impl Serialize for statement::ForOfStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let left = s.serialize(&self.left);
+        let right = s.serialize(&self.right);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "ForOfStatement", vec![
+            ("await", false.into()),
+            ("left", left),
+            ("right", right),
+            ("body", body),
+        ])
+    }
+}
+impl Serialize for statement::ForAwaitStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let left = s.serialize(&self.left);
+        let right = s.serialize(&self.right);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "ForOfStatement", vec![
+            ("await", true.into()),
+            ("left", left),
+            ("right", right),
+            ("body", body),
+        ])
+    }
+}
+impl Serialize for statement::ForInVarPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.for_declaration(span(self), "var", &self.pattern, self.init.as_ref())
+    }
+}
+impl Serialize for statement::ForVarPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.for_declaration(span(self), "var", &self.pattern, None)
+    }
+}
+impl Serialize for statement::ForLetPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.for_declaration(span(self), "let", &self.pattern, None)
+    }
+}
+impl Serialize for statement::ForConstPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.for_declaration(span(self), "const", &self.pattern, None)
+    }
+}
+impl Serialize for statement::WhileStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let test = s.serialize(&self.test);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "WhileStatement", vec![("test", test), ("body", body)])
+    }
+}
+impl Serialize for statement::DoWhileStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let body = s.serialize(&self.body);
+        let test = s.serialize(&self.test);
+        s.node(span(self), "DoWhileStatement", vec![("body", body), ("test", test)])
+    }
+}
+impl Serialize for statement::SwitchStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let discriminant = s.serialize(&self.discriminant);
+        let cases = s.serialize_all(&self.cases);
+        s.node(span(self), "SwitchStatement", vec![("discriminant", discriminant), ("cases", cases)])
+    }
+}
+impl Serialize for statement::SwitchCase<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let test = s.serialize(&self.test);
+        let consequent = s.serialize_all(&self.consequent);
+        s.node(span(self), "SwitchCase", vec![("test", test), ("consequent", consequent)])
+    }
+}
+impl Serialize for statement::SwitchDefault<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let consequent = s.serialize_all(&self.consequent);
+        s.node(span(self), "SwitchCase", vec![("test", Value::Null), ("consequent", consequent)])
+    }
+}
+impl Serialize for statement::WithStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.serialize(&self.object);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "WithStatement", vec![("object", object), ("body", body)])
+    }
+}
+impl Serialize for statement::LabelledStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let label = s.serialize(&self.label);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "LabeledStatement", vec![("label", label), ("body", body)])
+    }
+}
+impl Serialize for statement::ThrowStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.argument);
+        s.node(span(self), "ThrowStatement", vec![("argument", argument)])
+    }
+}
+impl Serialize for statement::TryCatchStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let block = s.serialize(&self.body);
+        let handler = s.serialize(&self.catch);
+        s.node(span(self), "TryStatement", vec![
+            ("block", block),
+            ("handler", handler),
+            ("finalizer", Value::Null),
+        ])
+    }
+}
+impl Serialize for statement::TryCatchFinallyStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let block = s.serialize(&self.body);
+        let handler = s.serialize(&self.catch);
+        let finalizer = s.serialize(&self.finalizer);
+        s.node(span(self), "TryStatement", vec![
+            ("block", block),
+            ("handler", handler),
+            ("finalizer", finalizer),
+        ])
+    }
+}
+impl Serialize for statement::TryFinallyStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let block = s.serialize(&self.body);
+        let finalizer = s.serialize(&self.finalizer);
+        s.node(span(self), "TryStatement", vec![
+            ("block", block),
+            ("handler", Value::Null),
+            ("finalizer", finalizer),
+        ])
+    }
+}
+impl Serialize for statement::CatchClause<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let param = s.serialize(&self.param);
+        let body = s.serialize(&self.body);
+        s.node(span(self), "CatchClause", vec![("param", param), ("body", body)])
+    }
+}
+impl Serialize for statement::CatchParam<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut argument = s.serialize(&self.argument);
+        if let Some(ref type_annotation) = self.type_annotation {
+            let type_annotation = s.serialize(type_annotation);
+            push_field(&mut argument, "typeAnnotation", type_annotation);
+        }
+        argument
+    }
+}
+impl Serialize for statement::ContinueStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let label = s.serialize(&self.label);
+        s.node(span(self), "ContinueStatement", vec![("label", label)])
+    }
+}
+impl Serialize for statement::BreakStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let label = s.serialize(&self.label);
+        s.node(span(self), "BreakStatement", vec![("label", label)])
+    }
+}
+impl Serialize for statement::LabelValue<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.label)
+    }
+}
+impl Serialize for statement::ReturnStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.value);
+        s.node(span(self), "ReturnStatement", vec![("argument", argument)])
+    }
+}
+impl Serialize for statement::ReturnValue<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for statement::DebuggerStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.node(span(self), "DebuggerStatement", vec![])
+    }
+}
+impl Serialize for statement::EmptyStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.node(span(self), "EmptyStatement", vec![])
+    }
+}
+
+
+// Functions
+
+impl Serialize for functions::FunctionDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let id = s.serialize(&self.id);
+        s.function(span(self), "FunctionDeclaration", id, function_flags(&self.kind), &self.params, &self.body)
+    }
+}
+impl Serialize for functions::FunctionExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let id = s.serialize(&self.id);
+        s.function(span(self), "FunctionExpression", id, function_flags(&self.kind), &self.params, &self.body)
+    }
+}
+impl Serialize for functions::ExportDefaultFunctionDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // The function starts after the "export default" keywords.
+        let function_span = span(self).map(|(start, end)| (s.skip_words(start, 2), end));
+
+        let id = s.serialize(&self.id);
+        let declaration = s.function(
+            function_span,
+            "FunctionDeclaration",
+            id,
+            function_flags(&self.kind),
+            &self.params,
+            &self.body,
+        );
+        s.export_default(span(self), declaration)
+    }
+}
+impl Serialize for functions::ArrowFunctionExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let (generator, is_async) = match self.kind {
+            functions::ArrowFunctionKind::Normal => (false, false),
+            functions::ArrowFunctionKind::Async => (false, true),
+            functions::ArrowFunctionKind::Generator => (true, false),
+            functions::ArrowFunctionKind::AsyncGenerator => (true, true),
+        };
+        let params = match self.params {
+            functions::ArrowFunctionParams::Singular(ref id) => vec![s.serialize(id)].into(),
+            functions::ArrowFunctionParams::Normal(ref params) => s.serialize(params),
+        };
+        let expression = matches!(self.body, functions::ArrowFunctionBody::Expression(_));
+        let body = s.serialize(&self.body);
+
+        let arrow = s.node(span(self), "ArrowFunctionExpression", vec![
+            ("id", Value::Null),
+            ("expression", expression.into()),
+            ("generator", generator.into()),
+            ("async", is_async.into()),
+            ("params", params),
+            ("body", body),
+        ]);
+        match self.params {
+            functions::ArrowFunctionParams::Normal(ref params) => s.typed_function(arrow, params),
+            functions::ArrowFunctionParams::Singular(_) => arrow,
+        }
+    }
+}
+impl Serialize for functions::ArrowFunctionExpressionBody<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for functions::FunctionParams<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.list(&self.params, &self.last_param).into()
+    }
+}
+impl Serialize for functions::FunctionParam<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut pattern = s.serialize(&self.id);
+        if !self.decorators.is_empty() {
+            let decorators = s.serialize_all(&self.decorators);
+            push_field(&mut pattern, "decorators", decorators);
+        }
+        if self.optional {
+            push_field(&mut pattern, "optional", true.into());
+        }
+        if let Some(ref type_annotation) = self.type_annotation {
+            let type_annotation = s.serialize(type_annotation);
+            push_field(&mut pattern, "typeAnnotation", type_annotation);
+        }
+        let param = s.with_default(span(self), pattern, &self.init);
+        if self.modifiers.is_empty() {
+            return param;
+        }
+
+        let mut fields = modifier_fields(s, &self.modifiers);
+        fields.push(("parameter", param));
+        s.node(span(self), "TSParameterProperty", fields)
+    }
+}
+impl Serialize for functions::FunctionRestParam<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.id);
+        let mut fields = vec![("argument", argument)];
+        if let Some(ref type_annotation) = self.type_annotation {
+            fields.push(("typeAnnotation", s.serialize(type_annotation)));
+        }
+        s.node(span(self), "RestElement", fields)
+    }
+}
+impl Serialize for functions::FunctionParamDecorator<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.value);
+        s.node(span(self), "Decorator", vec![("expression", expression)])
+    }
+}
+impl Serialize for functions::FunctionBody<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut body = s.serialize_all(&self.directives);
+        if let Value::Array(ref mut items) = body {
+            items.extend(self.body.iter().map(|item| item.serialize(s)));
+        }
+        s.node(span(self), "BlockStatement", vec![("body", body)])
+    }
+}
+
+
+// Classes
+
+impl Serialize for classes::ClassDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut fields = vec![];
+        if !self.decorators.is_empty() {
+            fields.push(("decorators", s.serialize_all(&self.decorators)));
+        }
+        fields.push(("id", s.serialize(&self.id)));
+        fields.push(("superClass", s.serialize(&self.heritage)));
+        if let Some(type_arguments) = self.heritage.as_ref().and_then(|heritage| heritage.type_arguments.as_ref()) {
+            fields.push(("superTypeArguments", s.serialize(type_arguments)));
+        }
+        fields.push(("body", s.serialize(&self.body)));
+
+        let class = s.node(span(self), "ClassDeclaration", fields);
+        s.typed_class(class, &self.modifiers, &self.type_parameters, &self.implements)
+    }
+}
+impl Serialize for classes::ClassExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let class = s.class(span(self), "ClassExpression", &self.decorators, &self.id, &self.heritage, &self.body);
+        s.typed_class(class, &[], &self.type_parameters, &self.implements)
+    }
+}
+impl Serialize for classes::ExportDefaultClassDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // Decorators may come before or after the "export default" keywords.
+        let class_start = span(self).and_then(|(start, _)| {
+            let class_start = s.token_start(&self.token_class)?;
+            Some(match self.decorators.first().and_then(span) {
+                Some((decorator_start, _)) if decorator_start > start => decorator_start,
+                _ => class_start,
+            })
+        });
+        let class_span = class_start.and_then(|start| span(self).map(|(_, end)| (start, end)));
+
+        let declaration = s.class(class_span, "ClassDeclaration", &self.decorators, &self.id, &self.heritage, &self.body);
+        let declaration = s.typed_class(declaration, &self.modifiers, &self.type_parameters, &self.implements);
+        s.export_default(span(self), declaration)
+    }
+}
+impl Serialize for classes::ClassName<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.id)
+    }
+}
+impl Serialize for classes::ClassHeritage<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for classes::ClassBody<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let body: Vec<_> = self.items
+            .iter()
+            .filter_map(|item| match *item {
+                classes::ClassItem::Method(ref n) => Some(s.serialize(n)),
+                classes::ClassItem::Field(ref n) => Some(s.serialize(n)),
+                classes::ClassItem::MethodSignature(ref n) => Some(s.serialize(n)),
+                classes::ClassItem::IndexSignature(ref n) => Some(s.serialize(n)),
+                classes::ClassItem::Empty(_) => None,
+            })
+            .collect();
+        s.node(span(self), "ClassBody", vec![("body", body.into())])
+    }
+}
+impl Serialize for classes::ClassMethod<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let is_static = self.pos.is_some();
+        let computed = matches!(self.id, classes::ClassFieldId::Public(general::PropertyName::Computed(_)));
+        let is_constructor = !is_static && matches!(self.kind, objects::MethodKind::Normal) && match self.id {
+            classes::ClassFieldId::Public(general::PropertyName::Identifier(ref id)) => id.value == "constructor",
+            classes::ClassFieldId::Public(general::PropertyName::String(ref id)) => cook(&id.value) == "constructor",
+            _ => false,
+        };
+        let kind = match self.kind {
+            objects::MethodKind::Get => "get",
+            objects::MethodKind::Set => "set",
+            _ if is_constructor => "constructor",
+            _ => "method",
+        };
+
+        let mut fields = vec![];
+        if !self.decorators.is_empty() {
+            fields.push(("decorators", s.serialize_all(&self.decorators)));
+        }
+        fields.push(("static", is_static.into()));
+        fields.push(("computed", computed.into()));
+        fields.push(("key", s.serialize(&self.id)));
+        fields.push(("kind", kind.into()));
+        fields.push(("value", s.method_value(&self.kind, &self.params, &self.body)));
+        fields.extend(modifier_fields(s, &self.modifiers));
+
+        s.node(span(self), "MethodDefinition", fields)
+    }
+}
+impl Serialize for classes::ClassField<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let computed = matches!(self.id, classes::ClassFieldId::Public(general::PropertyName::Computed(_)));
+
+        let mut fields = vec![];
+        if !self.decorators.is_empty() {
+            fields.push(("decorators", s.serialize_all(&self.decorators)));
+        }
+        fields.push(("static", self.pos.is_some().into()));
+        fields.push(("computed", computed.into()));
+        fields.push(("key", s.serialize(&self.id)));
+        fields.push(("value", s.serialize(&self.init)));
+        fields.extend(modifier_fields(s, &self.modifiers).into_iter().filter(|&(key, _)| key != "abstract"));
+        if self.optional {
+            fields.push(("optional", true.into()));
+        }
+        if self.definite {
+            fields.push(("definite", true.into()));
+        }
+        if let Some(ref type_annotation) = self.type_annotation {
+            fields.push(("typeAnnotation", s.serialize(type_annotation)));
+        }
+
+        let is_abstract = self.modifiers.iter().any(|modifier| matches!(modifier.kind, typescript::ModifierKind::Abstract));
+        let kind = if is_abstract { "TSAbstractPropertyDefinition" } else { "PropertyDefinition" };
+        s.node(span(self), kind, fields)
+    }
+}
+impl Serialize for classes::ClassDecorator<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.value);
+        s.node(span(self), "Decorator", vec![("expression", expression)])
+    }
+}
+impl Serialize for classes::ClassItemDecorator<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.value);
+        s.node(span(self), "Decorator", vec![("expression", expression)])
+    }
+}
+
+
+// Decorators
+
+impl Serialize for decorators::DecoratorExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for decorators::DecoratorMemberAccess<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.serialize(&self.object);
+        let property = s.serialize(&self.property);
+        s.member(span(self), object, property, false, false)
+    }
+}
+impl Serialize for decorators::DecoratorCallExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let callee = s.serialize(&self.callee);
+        s.call(span(self), callee, &self.arguments, false)
+    }
+}
+
+
+// Expressions
+
+impl Serialize for expression::ThisExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.node(span(self), "ThisExpression", vec![])
+    }
+}
+impl Serialize for expression::ParenthesizedExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // Like most ESTree tools, parentheses are not represented as nodes.
+        s.serialize(&self.expr)
+    }
+}
+impl Serialize for expression::TaggedTemplateLiteral<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let tag = s.serialize(&self.tag);
+        let quasi = s.serialize(&self.template);
+        s.node(span(self), "TaggedTemplateExpression", vec![("tag", tag), ("quasi", quasi)])
+    }
+}
+impl Serialize for expression::TemplateLiteral<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut quasis = vec![];
+        let mut expressions = vec![];
+        for (part, _, expr, _) in self.parts.iter() {
+            quasis.push(template_element(s, part, false));
+            expressions.push(s.serialize(expr));
+        }
+        quasis.push(template_element(s, &self.last_part, true));
+
+        s.node(span(self), "TemplateLiteral", vec![
+            ("quasis", quasis.into()),
+            ("expressions", expressions.into()),
+        ])
+    }
+}
+fn template_element(s: &mut Serializer, part: &expression::TemplatePart, tail: bool) -> Value {
+    let raw = part.raw_value.as_ref().unwrap_or(&part.value);
+    let value = object(vec![("raw", raw.as_str().into()), ("cooked", cook(raw).into())]);
+    s.node(span(part), "TemplateElement", vec![("value", value), ("tail", tail.into())])
+}
+impl Serialize for expression::CallArguments<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.list(&self.args, &self.last_arg).into()
+    }
+}
+impl Serialize for expression::CallArgumentExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for expression::CallArgumentSpread<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.expression);
+        s.node(span(self), "SpreadElement", vec![("argument", argument)])
+    }
+}
+impl Serialize for expression::CallExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let (callee, type_arguments) = s.callee(&self.callee);
+        let mut call = s.call(span(self), callee, &self.arguments, false);
+        if let Some(type_arguments) = type_arguments {
+            push_field(&mut call, "typeArguments", type_arguments);
+        }
+        call
+    }
+}
+impl Serialize for expression::OptionalCallExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let (callee, type_arguments) = s.callee(&self.callee);
+        let mut call = s.call(span(self), callee, &self.arguments, true);
+        if let Some(type_arguments) = type_arguments {
+            push_field(&mut call, "typeArguments", type_arguments);
+        }
+        call
+    }
+}
+impl Serialize for expression::NormalNewExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let (callee, type_arguments) = s.callee(&self.callee);
+        let arguments = s.serialize(&self.arguments);
+        let mut fields = vec![("callee", callee), ("arguments", arguments)];
+        if let Some(type_arguments) = type_arguments {
+            fields.push(("typeArguments", type_arguments));
+        }
+        s.node(span(self), "NewExpression", fields)
+    }
+}
+impl Serialize for expression::EmptyNewExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let callee = s.serialize(&self.callee);
+        s.node(span(self), "NewExpression", vec![("callee", callee), ("arguments", vec![].into())])
+    }
+}
+impl Serialize for expression::ImportCallExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let source = s.serialize(&self.argument);
+        s.node(span(self), "ImportExpression", vec![("source", source)])
+    }
+}
+impl Serialize for expression::SuperCallExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let callee = s.super_node(&self.token_super);
+        s.call(span(self), callee, &self.arguments, false)
+    }
+}
+impl Serialize for expression::MemberExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.chain_link(&self.object);
+        let computed = matches!(self.property, expression::PropertyAccess::Computed(_));
+        let property = s.serialize(&self.property);
+        s.member(span(self), object, property, computed, false)
+    }
+}
+impl Serialize for expression::OptionalMemberExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.chain_link(&self.object);
+        let computed = matches!(self.property, expression::OptionalPropertyAccess::Computed(_));
+        let property = s.serialize(&self.property);
+        s.member(span(self), object, property, computed, true)
+    }
+}
+impl Serialize for expression::SuperMemberExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.super_node(&self.token_super);
+        let computed = matches!(self.property, expression::SuperMemberAccess::Computed(_));
+        let property = s.serialize(&self.property);
+        s.member(span(self), object, property, computed, false)
+    }
+}
+serialize_enum! {
+    expression::PropertyAccess { Identifier, Computed, Private, }
+    expression::OptionalPropertyAccess { Identifier, Computed, Private, }
+    expression::SuperMemberAccess { Identifier, Computed, }
+}
+impl Serialize for expression::IdentifierPropertyAccess<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.id)
+    }
+}
+impl Serialize for expression::ComputedPropertyAccess<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for expression::PrivatePropertyAccess<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        private_identifier(s, &self.property)
+    }
+}
+impl Serialize for expression::OptionalIdentifierPropertyAccess<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.id)
+    }
+}
+impl Serialize for expression::OptionalComputedPropertyAccess<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for expression::OptionalPrivatePropertyAccess<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        private_identifier(s, &self.property)
+    }
+}
+fn private_identifier(s: &mut Serializer, id: &general::PropertyIdentifier) -> Value {
+    // Include the "#" in the location.
+    let id_span = span(id).map(|(start, end)| (start.saturating_sub(1), end));
+    s.node(id_span, "PrivateIdentifier", vec![("name", id.value.as_str().into())])
+}
+
+macro_rules! serialize_update {
+    ($($name:ident: $op:expr, $prefix:expr;)*) => {
+        $(
+            impl Serialize for expression::$name<'_> {
+                fn serialize(&self, s: &mut Serializer) -> Value {
+                    let argument = s.serialize(&self.value);
+                    s.node(span(self), "UpdateExpression", vec![
+                        ("operator", $op.into()),
+                        ("prefix", $prefix.into()),
+                        ("argument", argument),
+                    ])
+                }
+            }
+        )*
+    };
+}
+serialize_update! {
+    PostIncrementExpression: "++", false;
+    PostDecrementExpression: "--", false;
+    PreIncrementExpression: "++", true;
+    PreDecrementExpression: "--", true;
+}
+
+macro_rules! serialize_unary {
+    ($($name:ident: $op:expr;)*) => {
+        $(
+            impl Serialize for expression::$name<'_> {
+                fn serialize(&self, s: &mut Serializer) -> Value {
+                    let argument = s.serialize(&self.value);
+                    s.node(span(self), "UnaryExpression", vec![
+                        ("operator", $op.into()),
+                        ("prefix", true.into()),
+                        ("argument", argument),
+                    ])
+                }
+            }
+        )*
+    };
+}
+serialize_unary! {
+    DeleteExpression: "delete";
+    VoidExpression: "void";
+    TypeofExpression: "typeof";
+    PositiveExpression: "+";
+    NegativeExpression: "-";
+    BitNegateExpression: "~";
+    NegateExpression: "!";
+}
+
+impl Serialize for expression::AwaitExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.value);
+        s.node(span(self), "AwaitExpression", vec![("argument", argument)])
+    }
+}
+impl Serialize for expression::YieldExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.value);
+        s.node(span(self), "YieldExpression", vec![("delegate", false.into()), ("argument", argument)])
+    }
+}
+impl Serialize for expression::DelegateYieldExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.value);
+        s.node(span(self), "YieldExpression", vec![("delegate", true.into()), ("argument", argument)])
+    }
+}
+
+macro_rules! serialize_binary {
+    ($($name:ident: $kind:expr, $op:expr;)*) => {
+        $(
+            impl Serialize for expression::$name<'_> {
+                fn serialize(&self, s: &mut Serializer) -> Value {
+                    let left = s.serialize(&self.left);
+                    let right = s.serialize(&self.right);
+                    s.node(span(self), $kind, vec![
+                        ("left", left),
+                        ("operator", $op.into()),
+                        ("right", right),
+                    ])
+                }
+            }
+        )*
+    };
+}
+serialize_binary! {
+    AddExpression: "BinaryExpression", "+";
+    SubtractExpression: "BinaryExpression", "-";
+    LeftShiftExpression: "BinaryExpression", "<<";
+    RightShiftExpression: "BinaryExpression", ">>";
+    RightShiftSignedExpression: "BinaryExpression", ">>>";
+    DivideExpression: "BinaryExpression", "/";
+    MultiplyExpression: "BinaryExpression", "*";
+    ModulusExpression: "BinaryExpression", "%";
+    BitAndExpression: "BinaryExpression", "&";
+    BitOrExpression: "BinaryExpression", "|";
+    BitXorExpression: "BinaryExpression", "^";
+    PowerExpression: "BinaryExpression", "**";
+    EqualExpression: "BinaryExpression", "==";
+    StrictEqualExpression: "BinaryExpression", "===";
+    NotEqualExpression: "BinaryExpression", "!=";
+    StrictNotEqualExpression: "BinaryExpression", "!==";
+    LessThanExpression: "BinaryExpression", "<";
+    LessThanEqualExpression: "BinaryExpression", "<=";
+    GreaterThanExpression: "BinaryExpression", ">";
+    GreaterThanEqualExpression: "BinaryExpression", ">=";
+    InExpression: "BinaryExpression", "in";
+    InstanceofExpression: "BinaryExpression", "instanceof";
+    AndExpression: "LogicalExpression", "&&";
+    OrExpression: "LogicalExpression", "||";
+
+    AssignmentExpression: "AssignmentExpression", "=";
+    AddAssignExpression: "AssignmentExpression", "+=";
+    SubtractAssignExpression: "AssignmentExpression", "-=";
+    LeftShiftAssignExpression: "AssignmentExpression", "<<=";
+    RightShiftAssignExpression: "AssignmentExpression", ">>=";
+    RightShiftSignedAssignExpression: "AssignmentExpression", ">>>=";
+    DivideAssignExpression: "AssignmentExpression", "/=";
+    MultiplyAssignExpression: "AssignmentExpression", "*=";
+    ModulusAssignExpression: "AssignmentExpression", "%=";
+    BitAndAssignExpression: "AssignmentExpression", "&=";
+    BitOrAssignExpression: "AssignmentExpression", "|=";
+    BitXorAssignExpression: "AssignmentExpression", "^=";
+    PowerAssignExpression: "AssignmentExpression", "**=";
+}
+
+impl Serialize for expression::BindExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.serialize(&self.left);
+        let callee = s.serialize(&self.right);
+        s.node(span(self), "BindExpression", vec![("object", object), ("callee", callee)])
+    }
+}
+impl Serialize for expression::BindMemberExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let callee = s.serialize(&self.value);
+        s.node(span(self), "BindExpression", vec![("object", Value::Null), ("callee", callee)])
+    }
+}
+impl Serialize for expression::ConditionalExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let test = s.serialize(&self.test);
+        let consequent = s.serialize(&self.consequent);
+        let alternate = s.serialize(&self.alternate);
+        s.node(span(self), "ConditionalExpression", vec![
+            ("test", test),
+            ("consequent", consequent),
+            ("alternate", alternate),
+        ])
+    }
+}
+impl Serialize for expression::SequenceExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // Sequences are nested pairs in jsparse, but a flat list in ESTree.
+        fn flatten(s: &mut Serializer, expr: &alias::Expression, out: &mut Vec<Value>) {
+            match *expr {
+                alias::Expression::Sequence(ref seq) => {
+                    flatten(s, &seq.left, out);
+                    flatten(s, &seq.right, out);
+                }
+                ref expr => out.push(s.serialize(expr)),
+            }
+        }
+
+        let mut expressions = vec![];
+        flatten(s, &self.left, &mut expressions);
+        flatten(s, &self.right, &mut expressions);
+        s.node(span(self), "SequenceExpression", vec![("expressions", expressions.into())])
+    }
+}
+impl Serialize for expression::DoExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let body = s.serialize(&self.body);
+        s.node(span(self), "DoExpression", vec![("body", body)])
+    }
+}
+impl Serialize for expression::NewTargetExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.meta_property(span(self), (&self.token_new, "new"), (&self.token_target, "target"))
+    }
+}
+impl Serialize for expression::ImportMetaExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.meta_property(span(self), (&self.token_import, "import"), (&self.token_meta, "meta"))
+    }
+}
+impl Serialize for expression::FunctionSentExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.meta_property(span(self), (&self.token_function, "function"), (&self.token_send, "sent"))
+    }
+}
+impl Serialize for expression::FunctionArgumentsExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.meta_property(span(self), (&self.token_function, "function"), (&self.token_arguments, "arguments"))
+    }
+}
+
+
+// Objects and arrays
+
+impl Serialize for objects::ObjectExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let properties = s.list(&self.properties, &self.last_property);
+        s.node(span(self), "ObjectExpression", vec![("properties", properties.into())])
+    }
+}
+impl Serialize for objects::ObjectMethod<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let is_method = !matches!(self.kind, objects::MethodKind::Get | objects::MethodKind::Set);
+        let value = s.method_value(&self.kind, &self.params, &self.body);
+        s.property(span(self), &self.id, value, method_kind(&self.kind), is_method)
+    }
+}
+impl Serialize for objects::ObjectProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let value = s.serialize(&self.value);
+        s.property(span(self), &self.name, value, "init", false)
+    }
+}
+impl Serialize for objects::ObjectShorthandProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let key = s.serialize(&self.id);
+        let value = s.serialize(&self.id);
+        s.shorthand_property(span(self), key, value)
+    }
+}
+impl Serialize for objects::ObjectSpreadElement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.expression);
+        s.node(span(self), "SpreadElement", vec![("argument", argument)])
+    }
+}
+impl Serialize for objects::ArrayExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let elements = s.list(&self.elements, &self.last_element);
+        s.node(span(self), "ArrayExpression", vec![("elements", elements.into())])
+    }
+}
+impl Serialize for objects::ArrayExpressionItem<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.expression)
+    }
+}
+impl Serialize for objects::ArraySpreadItem<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.expression);
+        s.node(span(self), "SpreadElement", vec![("argument", argument)])
+    }
+}
+
+
+// Patterns
+
+impl Serialize for patterns::ParenthesizedAssignmentPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.serialize(&self.pattern)
+    }
+}
+impl Serialize for patterns::ObjectAssignmentPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let properties = s.list(&self.properties, &self.last_property);
+        s.node(span(self), "ObjectPattern", vec![("properties", properties.into())])
+    }
+}
+impl Serialize for patterns::ObjectAssignmentPatternIdentifierProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let key = s.serialize(&self.id);
+        let id = s.serialize(&self.id);
+        let value = s.with_default(span(self), id, &self.init);
+        s.shorthand_property(span(self), key, value)
+    }
+}
+impl Serialize for patterns::ObjectAssignmentPatternPatternProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let pattern = s.serialize(&self.pattern);
+        let value = s.with_default(join(span(&self.pattern), span(&self.init)), pattern, &self.init);
+        s.property(span(self), &self.name, value, "init", false)
+    }
+}
+impl Serialize for patterns::ObjectAssignmentPatternRestProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.pattern);
+        s.node(span(self), "RestElement", vec![("argument", argument)])
+    }
+}
+impl Serialize for patterns::ArrayAssignmentPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let elements = s.list(&self.items, &self.last_item);
+        s.node(span(self), "ArrayPattern", vec![("elements", elements.into())])
+    }
+}
+impl Serialize for patterns::ArrayAssignmentPatternElement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let pattern = s.serialize(&self.id);
+        s.with_default(span(self), pattern, &self.init)
+    }
+}
+impl Serialize for patterns::ArrayAssignmentRestElement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.pattern);
+        s.node(span(self), "RestElement", vec![("argument", argument)])
+    }
+}
+impl Serialize for patterns::ObjectBindingPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let properties = s.list(&self.properties, &self.last_property);
+        s.node(span(self), "ObjectPattern", vec![("properties", properties.into())])
+    }
+}
+impl Serialize for patterns::ObjectBindingPatternIdentifierProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let key = s.serialize(&self.id);
+        let id = s.serialize(&self.id);
+        let value = s.with_default(span(self), id, &self.init);
+        s.shorthand_property(span(self), key, value)
+    }
+}
+impl Serialize for patterns::ObjectBindingPatternPatternProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let pattern = s.serialize(&self.pattern);
+        let value = s.with_default(join(span(&self.pattern), span(&self.init)), pattern, &self.init);
+        s.property(span(self), &self.name, value, "init", false)
+    }
+}
+impl Serialize for patterns::ObjectBindingPatternRestProperty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.pattern);
+        s.node(span(self), "RestElement", vec![("argument", argument)])
+    }
+}
+impl Serialize for patterns::ArrayBindingPattern<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let elements = s.list(&self.items, &self.last_item);
+        s.node(span(self), "ArrayPattern", vec![("elements", elements.into())])
+    }
+}
+impl Serialize for patterns::ArrayBindingPatternElement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let pattern = s.serialize(&self.id);
+        s.with_default(span(self), pattern, &self.init)
+    }
+}
+impl Serialize for patterns::ArrayBindingRestElement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.pattern);
+        s.node(span(self), "RestElement", vec![("argument", argument)])
+    }
+}
+
+
+// Modules
+
+impl Serialize for modules::ImportSourceDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.import_declaration(span(self), vec![], &self.source)
+    }
+}
+impl Serialize for modules::ImportNamedDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = vec![default_specifier(s, &self.default)];
+        s.import_declaration(span(self), specifiers, &self.source)
+    }
+}
+impl Serialize for modules::ImportNamedAndNamespaceDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = vec![
+            default_specifier(s, &self.default),
+            s.namespace_specifier("ImportNamespaceSpecifier", "local", &self.token_star, &self.namespace),
+        ];
+        s.import_declaration(span(self), specifiers, &self.source)
+    }
+}
+impl Serialize for modules::ImportNamespaceDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = vec![
+            s.namespace_specifier("ImportNamespaceSpecifier", "local", &self.token_star, &self.namespace),
+        ];
+        s.import_declaration(span(self), specifiers, &self.source)
+    }
+}
+impl Serialize for modules::ImportNamedAndSpecifiersDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut specifiers = vec![default_specifier(s, &self.default)];
+        specifiers.extend(s.list(&self.specifiers, &self.last_specifier));
+        s.import_declaration(span(self), specifiers, &self.source)
+    }
+}
+impl Serialize for modules::ImportSpecifiersDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = s.list(&self.specifiers, &self.last_specifier);
+        s.import_declaration(span(self), specifiers, &self.source)
+    }
+}
+fn default_specifier(s: &mut Serializer, local: &general::BindingIdentifier) -> Value {
+    let id = s.serialize(local);
+    s.node(span(local), "ImportDefaultSpecifier", vec![("local", id)])
+}
+impl Serialize for modules::NormalImportSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let imported = s.serialize(&self.local);
+        let local = s.serialize(&self.local);
+        s.node(span(self), "ImportSpecifier", vec![("imported", imported), ("local", local)])
+    }
+}
+impl Serialize for modules::AliasedImportSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let imported = s.serialize(&self.imported);
+        let local = s.serialize(&self.local);
+        s.node(span(self), "ImportSpecifier", vec![("imported", imported), ("local", local)])
+    }
+}
+impl Serialize for flow::TypeImportSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let (imported, local) = match *self.specifier {
+            modules::ImportSpecifier::Normal(ref n) => (s.serialize(&n.local), s.serialize(&n.local)),
+            modules::ImportSpecifier::Aliased(ref n) => (s.serialize(&n.imported), s.serialize(&n.local)),
+            modules::ImportSpecifier::Type(ref n) => return s.serialize(n),
+        };
+        let kind = match self.kind {
+            flow::ImportKind::Type => "type",
+            flow::ImportKind::Typeof => "typeof",
+        };
+        s.node(span(self), "ImportSpecifier", vec![
+            ("imported", imported),
+            ("local", local),
+            ("importKind", kind.into()),
+        ])
+    }
+}
+impl Serialize for modules::ExportDefaultExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = s.serialize(&self.expression);
+        s.export_default(span(self), declaration)
+    }
+}
+impl Serialize for modules::ExportClassDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = s.serialize(&self.exported);
+        s.export_named(span(self), declaration, vec![], None)
+    }
+}
+impl Serialize for modules::ExportFunctionDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = s.serialize(&self.exported);
+        s.export_named(span(self), declaration, vec![], None)
+    }
+}
+impl Serialize for modules::ExportVarStatement<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = s.serialize(&self.exported);
+        s.export_named(span(self), declaration, vec![], None)
+    }
+}
+impl Serialize for modules::ExportLetDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = s.serialize(&self.exported);
+        s.export_named(span(self), declaration, vec![], None)
+    }
+}
+impl Serialize for modules::ExportConstDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = s.serialize(&self.exported);
+        s.export_named(span(self), declaration, vec![], None)
+    }
+}
+impl Serialize for modules::ExportLocalBindings<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = s.list(&self.specifiers, &self.last_specifier);
+        s.export_named(span(self), Value::Null, specifiers, None)
+    }
+}
+impl Serialize for modules::NormalLocalExportSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let local = s.serialize(&self.local);
+        let exported = s.serialize(&self.local);
+        s.node(span(self), "ExportSpecifier", vec![("local", local), ("exported", exported)])
+    }
+}
+impl Serialize for modules::AliasedLocalExportSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let local = s.serialize(&self.local);
+        let exported = s.serialize(&self.exported);
+        s.node(span(self), "ExportSpecifier", vec![("local", local), ("exported", exported)])
+    }
+}
+impl Serialize for modules::ExportSourceSpecifiers<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = s.list(&self.specifiers, &self.last_specifier);
+        s.export_named(span(self), Value::Null, specifiers, Some(&self.source))
+    }
+}
+impl Serialize for modules::NormalSourceExportSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let local = s.serialize(&self.imported);
+        let exported = s.serialize(&self.imported);
+        s.node(span(self), "ExportSpecifier", vec![("local", local), ("exported", exported)])
+    }
+}
+impl Serialize for modules::AliasedSourceExportSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let local = s.serialize(&self.imported);
+        let exported = s.serialize(&self.exported);
+        s.node(span(self), "ExportSpecifier", vec![("local", local), ("exported", exported)])
+    }
+}
+impl Serialize for modules::ExportAllSpecifiers<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let source = s.serialize(&self.source);
+        s.node(span(self), "ExportAllDeclaration", vec![("exported", Value::Null), ("source", source)])
+    }
+}
+impl Serialize for modules::ExportNamespace<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let exported = s.serialize(&self.namespace);
+        let source = s.serialize(&self.source);
+        s.node(span(self), "ExportAllDeclaration", vec![("exported", exported), ("source", source)])
+    }
+}
+// "export foo from" is a proposal, so these use Babel's specifier types.
+impl Serialize for modules::ExportNamedSpecifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = vec![export_default_specifier(s, &self.default)];
+        s.export_named(span(self), Value::Null, specifiers, Some(&self.source))
+    }
+}
+impl Serialize for modules::ExportNamedAndNamespace<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = vec![
+            export_default_specifier(s, &self.default),
+            s.namespace_specifier("ExportNamespaceSpecifier", "exported", &self.token_star, &self.namespace),
+        ];
+        s.export_named(span(self), Value::Null, specifiers, Some(&self.source))
+    }
+}
+impl Serialize for modules::ExportNamedAndSpecifiers<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut specifiers = vec![export_default_specifier(s, &self.default)];
+        specifiers.extend(s.list(&self.specifiers, &self.last_specifier));
+        s.export_named(span(self), Value::Null, specifiers, Some(&self.source))
+    }
+}
+fn export_default_specifier(s: &mut Serializer, exported: &modules::ModuleIdentifier) -> Value {
+    let id = s.serialize(exported);
+    s.node(span(exported), "ExportDefaultSpecifier", vec![("exported", id)])
+}
+
+
+// JSX
+//
+// jsparse does not record where opening and closing tags start and end, so
+// those nodes are serialized without locations.
+
+impl Serialize for jsx::Element<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let name = s.serialize(&self.opening);
+        let attributes = s.serialize_all(&self.attributes);
+        let opening = s.node(None, "JSXOpeningElement", vec![
+            ("name", name),
+            ("attributes", attributes),
+            ("selfClosing", (self.children.is_empty() && self.closing.is_none()).into()),
+        ]);
+
+        let closing = if self.children.is_empty() && self.closing.is_none() {
+            Value::Null
+        } else {
+            let name = match self.closing {
+                Some(ref closing) => s.serialize(closing),
+                None => s.serialize(&self.opening),
+            };
+            s.node(None, "JSXClosingElement", vec![("name", name)])
+        };
+
+        let children = s.serialize_all(&self.children);
+        s.node(span(self), "JSXElement", vec![
+            ("openingElement", opening),
+            ("closingElement", closing),
+            ("children", children),
+        ])
+    }
+}
+impl Serialize for jsx::Identifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.node(span(self), "JSXIdentifier", vec![("name", self.value.as_str().into())])
+    }
+}
+impl Serialize for jsx::MemberExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.serialize(&self.object);
+        let property = s.serialize(&self.property);
+        s.node(span(self), "JSXMemberExpression", vec![("object", object), ("property", property)])
+    }
+}
+impl Serialize for jsx::NamespacedName<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let namespace = s.serialize(&self.namespace);
+        let name = s.serialize(&self.name);
+        s.node(span(self), "JSXNamespacedName", vec![("namespace", namespace), ("name", name)])
+    }
+}
+impl Serialize for jsx::SpreadAttribute<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.expression);
+        s.node(span(self), "JSXSpreadAttribute", vec![("argument", argument)])
+    }
+}
+impl Serialize for jsx::PairAttribute<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let name = s.serialize(&self.name);
+        let value = s.serialize(&self.value);
+        s.node(span(self), "JSXAttribute", vec![("name", name), ("value", value)])
+    }
+}
+impl Serialize for jsx::StringAttribute<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let raw = match self.raw {
+            Some(ref raw) => raw.to_string(),
+            None => s.raw(span(self)).map(Into::into).unwrap_or_else(|| format!("\"{}\"", self.value)),
+        };
+        s.literal(span(self), decode_entities(&self.value).into(), raw)
+    }
+}
+impl Serialize for jsx::ExpressionAttribute<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        s.node(span(self), "JSXExpressionContainer", vec![("expression", expression)])
+    }
+}
+impl Serialize for jsx::Expression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        s.node(span(self), "JSXExpressionContainer", vec![("expression", expression)])
+    }
+}
+impl Serialize for jsx::ExpressionSpread<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        s.node(span(self), "JSXSpreadChild", vec![("expression", expression)])
+    }
+}
+impl Serialize for jsx::Empty<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // The empty expression lies between the curly braces.
+        let inner = span(self).map(|(start, end)| (start + 1, end.saturating_sub(1).max(start + 1)));
+        let expression = s.node(inner, "JSXEmptyExpression", vec![]);
+        s.node(span(self), "JSXExpressionContainer", vec![("expression", expression)])
+    }
+}
+impl Serialize for jsx::Text<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let raw = match self.raw {
+            Some(ref raw) => raw.to_string(),
+            None => s.raw(span(self)).map(Into::into).unwrap_or_else(|| self.value.to_string()),
+        };
+        s.node(span(self), "JSXText", vec![("value", decode_entities(&self.value).into()), ("raw", raw.into())])
+    }
+}
+
+
+// TypeScript
+
+impl Serialize for typescript::TypeIdentifier<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.identifier(span(self), &self.value)
+    }
+}
+impl Serialize for typescript::TypeAnnotation<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        s.type_annotation(span(self), &self.value)
+    }
+}
+impl Serialize for typescript::KeywordType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let kind = match self.kind {
+            typescript::TypeKeyword::Any => "TSAnyKeyword",
+            typescript::TypeKeyword::Unknown => "TSUnknownKeyword",
+            typescript::TypeKeyword::Number => "TSNumberKeyword",
+            typescript::TypeKeyword::String => "TSStringKeyword",
+            typescript::TypeKeyword::Boolean => "TSBooleanKeyword",
+            typescript::TypeKeyword::Bigint => "TSBigIntKeyword",
+            typescript::TypeKeyword::Symbol => "TSSymbolKeyword",
+            typescript::TypeKeyword::Object => "TSObjectKeyword",
+            typescript::TypeKeyword::Never => "TSNeverKeyword",
+            typescript::TypeKeyword::Void => "TSVoidKeyword",
+            typescript::TypeKeyword::Undefined => "TSUndefinedKeyword",
+            typescript::TypeKeyword::Null => "TSNullKeyword",
+            typescript::TypeKeyword::This => "TSThisType",
+        };
+        s.node(span(self), kind, vec![])
+    }
+}
+impl Serialize for typescript::TypeReference<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let name = s.serialize(&self.name);
+        let type_arguments = s.serialize(&self.type_arguments);
+        s.node(span(self), "TSTypeReference", vec![("typeName", name), ("typeArguments", type_arguments)])
+    }
+}
+impl Serialize for typescript::QualifiedTypeName<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let left = s.serialize(&self.left);
+        let right = s.serialize(&self.right);
+        s.node(span(self), "TSQualifiedName", vec![("left", left), ("right", right)])
+    }
+}
+impl Serialize for typescript::LiteralType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let literal = match *self {
+            typescript::LiteralType::String(ref n) => s.serialize(n),
+            typescript::LiteralType::Numeric(ref n) => s.serialize(n),
+            typescript::LiteralType::Boolean(ref n) => s.serialize(n),
+            typescript::LiteralType::Negative(ref n) => s.serialize(n),
+        };
+        s.node(span(self), "TSLiteralType", vec![("literal", literal)])
+    }
+}
+impl Serialize for typescript::NegativeLiteralType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let argument = s.serialize(&self.value);
+        s.node(span(self), "UnaryExpression", vec![
+            ("operator", "-".into()),
+            ("prefix", true.into()),
+            ("argument", argument),
+        ])
+    }
+}
+impl Serialize for typescript::ArrayType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let element = s.serialize(&self.element);
+        s.node(span(self), "TSArrayType", vec![("elementType", element)])
+    }
+}
+impl Serialize for typescript::TupleType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let elements = s.serialize_all(&self.elements);
+        s.node(span(self), "TSTupleType", vec![("elementTypes", elements)])
+    }
+}
+impl Serialize for typescript::UnionType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let types = s.serialize_all(&self.types);
+        s.node(span(self), "TSUnionType", vec![("types", types)])
+    }
+}
+impl Serialize for typescript::IntersectionType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let types = s.serialize_all(&self.types);
+        s.node(span(self), "TSIntersectionType", vec![("types", types)])
+    }
+}
+impl Serialize for typescript::FunctionType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // The return type annotation starts at the "=>" token.
+        let return_span = s.token_start(&self.token_arrow).and_then(|start| span(&*self.value).map(|(_, end)| (start, end)));
+
+        let params = s.serialize(&self.params);
+        let return_type = s.type_annotation(return_span, &self.value);
+        let mut fields = vec![("params", params), ("returnType", return_type)];
+        if let Some(ref type_parameters) = self.params.type_parameters {
+            fields.push(("typeParameters", s.serialize(type_parameters)));
+        }
+        if self.token_new.is_none() {
+            return s.node(span(self), "TSFunctionType", fields);
+        }
+        fields.push(("abstract", self.token_abstract.is_some().into()));
+        s.node(span(self), "TSConstructorType", fields)
+    }
+}
+impl Serialize for typescript::ObjectType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // Flow's "..." of an inexact object is a flag rather than a member.
+        let is_inexact = |member: &&typescript::TypeMember| {
+            matches!(**member, typescript::TypeMember::Spread(ref spread) if spread.is_inexact())
+        };
+        let members: Vec<_> = self.members.iter()
+            .filter(|member| !is_inexact(member))
+            .map(|member| s.serialize(member))
+            .collect();
+        let mut fields = vec![("members", members.into())];
+        if self.members.iter().any(|member| is_inexact(&member)) {
+            fields.push(("inexact", true.into()));
+        }
+        s.node(span(self), "TSTypeLiteral", fields)
+    }
+}
+impl Serialize for typescript::ParenthesizedType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // Like parenthesized expressions, the parens are left out.
+        s.serialize(&self.value)
+    }
+}
+impl Serialize for typescript::TypeQuery<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let name = s.serialize(&self.name);
+        s.node(span(self), "TSTypeQuery", vec![("exprName", name)])
+    }
+}
+impl Serialize for typescript::ImportType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let source = s.serialize(&self.source);
+        let argument = s.node(span(&self.source), "TSLiteralType", vec![("literal", source)]);
+        let qualifier = s.serialize(&self.qualifier);
+        let type_arguments = s.serialize(&self.type_arguments);
+        s.node(span(self), "TSImportType", vec![
+            ("argument", argument),
+            ("qualifier", qualifier),
+            ("typeArguments", type_arguments),
+        ])
+    }
+}
+impl Serialize for typescript::TypeOperator<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let operator = match self.kind {
+            typescript::TypeOperatorKind::Keyof => "keyof",
+            typescript::TypeOperatorKind::Unique => "unique",
+            typescript::TypeOperatorKind::Readonly => "readonly",
+        };
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSTypeOperator", vec![("operator", operator.into()), ("typeAnnotation", value)])
+    }
+}
+impl Serialize for typescript::IndexedAccessType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let object = s.serialize(&self.object);
+        let index = s.serialize(&self.index);
+        s.node(span(self), "TSIndexedAccessType", vec![("objectType", object), ("indexType", index)])
+    }
+}
+impl Serialize for typescript::TypePredicate<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let name = if &*self.name.value == "this" {
+            s.node(span(&self.name), "TSThisType", vec![])
+        } else {
+            s.serialize(&self.name)
+        };
+        let value = match self.value {
+            Some((_, ref value)) => s.type_annotation(span(&**value), value),
+            None => Value::Null,
+        };
+        s.node(span(self), "TSTypePredicate", vec![
+            ("asserts", self.token_asserts.is_some().into()),
+            ("parameterName", name),
+            ("typeAnnotation", value),
+        ])
+    }
+}
+impl Serialize for typescript::ConditionalType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let check = s.serialize(&self.check);
+        let extends = s.serialize(&self.extends);
+        let true_type = s.serialize(&self.true_type);
+        let false_type = s.serialize(&self.false_type);
+        s.node(span(self), "TSConditionalType", vec![
+            ("checkType", check),
+            ("extendsType", extends),
+            ("trueType", true_type),
+            ("falseType", false_type),
+        ])
+    }
+}
+impl Serialize for typescript::InferType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let param = s.serialize(&self.param);
+        s.node(span(self), "TSInferType", vec![("typeParameter", param)])
+    }
+}
+impl Serialize for typescript::MappedType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        // The key and its constraint are a type parameter, like "K in T".
+        let name = s.serialize(&self.key);
+        let constraint = s.serialize(&self.constraint);
+        let param = s.node(join(span(&self.key), span(&*self.constraint)), "TSTypeParameter", vec![
+            ("name", name),
+            ("constraint", constraint),
+            ("default", Value::Null),
+        ]);
+        let name_type = s.serialize(&self.name_type);
+        let type_annotation = match self.type_annotation {
+            Some(ref annotation) => s.serialize(&annotation.value),
+            None => Value::Null,
+        };
+
+        let mut fields = vec![("typeParameter", param), ("nameType", name_type)];
+        if let Some(ref readonly) = self.readonly {
+            fields.push(("readonly", mapped_type_modifier(readonly)));
+        }
+        if let Some(ref optional) = self.optional {
+            fields.push(("optional", mapped_type_modifier(optional)));
+        }
+        fields.push(("typeAnnotation", type_annotation));
+        s.node(span(self), "TSMappedType", fields)
+    }
+}
+fn mapped_type_modifier(modifier: &typescript::MappedTypeModifier) -> Value {
+    match *modifier {
+        typescript::MappedTypeModifier::Plain => true.into(),
+        typescript::MappedTypeModifier::Plus => "+".into(),
+        typescript::MappedTypeModifier::Minus => "-".into(),
+    }
+}
+impl Serialize for typescript::NamedTupleMember<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let label = s.serialize(&self.label);
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSNamedTupleMember", vec![
+            ("label", label),
+            ("elementType", value),
+            ("optional", self.optional.into()),
+        ])
+    }
+}
+impl Serialize for typescript::OptionalType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSOptionalType", vec![("typeAnnotation", value)])
+    }
+}
+impl Serialize for typescript::RestType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSRestType", vec![("typeAnnotation", value)])
+    }
+}
+impl Serialize for typescript::TemplateLiteralType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut quasis = vec![];
+        let mut types = vec![];
+        for (part, _, value, _) in self.parts.iter() {
+            quasis.push(template_element(s, part, false));
+            types.push(s.serialize(value));
+        }
+        quasis.push(template_element(s, &self.last_part, true));
+
+        s.node(span(self), "TSTemplateLiteralType", vec![("quasis", quasis.into()), ("types", types.into())])
+    }
+}
+impl Serialize for typescript::PropertySignature<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let computed = matches!(self.name, general::PropertyName::Computed(_));
+
+        let mut fields = vec![
+            ("computed", computed.into()),
+            ("key", s.serialize(&self.name)),
+            ("optional", self.optional.into()),
+        ];
+        fields.extend(modifier_fields(s, &self.modifiers));
+        fields.push(("typeAnnotation", s.serialize(&self.type_annotation)));
+        s.node(span(self), "TSPropertySignature", fields)
+    }
+}
+impl Serialize for typescript::MethodSignature<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let computed = matches!(self.name, general::PropertyName::Computed(_));
+        let key = s.serialize(&self.name);
+        let params = s.serialize(&self.params);
+        let signature = s.node(span(self), "TSMethodSignature", vec![
+            ("computed", computed.into()),
+            ("key", key),
+            ("kind", "method".into()),
+            ("optional", self.optional.into()),
+            ("params", params),
+        ]);
+        s.typed_function(signature, &self.params)
+    }
+}
+impl Serialize for typescript::CallSignature<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let params = s.serialize(&self.params);
+        let signature = s.node(span(self), "TSCallSignatureDeclaration", vec![("params", params)]);
+        s.typed_function(signature, &self.params)
+    }
+}
+impl Serialize for typescript::ConstructSignature<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let params = s.serialize(&self.params);
+        let signature = s.node(span(self), "TSConstructSignatureDeclaration", vec![("params", params)]);
+        s.typed_function(signature, &self.params)
+    }
+}
+impl Serialize for typescript::IndexSignature<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut key = s.serialize(&self.key);
+        let key_type = s.serialize(&self.key_type);
+        push_field(&mut key, "typeAnnotation", key_type);
+
+        let mut fields = vec![
+            ("parameters", vec![key].into()),
+            ("typeAnnotation", s.serialize(&self.type_annotation)),
+        ];
+        fields.extend(modifier_fields(s, &self.modifiers));
+        s.node(span(self), "TSIndexSignature", fields)
+    }
+}
+impl Serialize for typescript::TypeParameters<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let params = s.serialize_all(&self.params);
+        s.node(span(self), "TSTypeParameterDeclaration", vec![("params", params)])
+    }
+}
+impl Serialize for typescript::TypeParameter<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let name = s.serialize(&self.id);
+        let constraint = s.serialize(&self.constraint);
+        let default = s.serialize(&self.default);
+        let mut parameter = s.node(span(self), "TSTypeParameter", vec![
+            ("name", name),
+            ("constraint", constraint),
+            ("default", default),
+        ]);
+        if let Some(ref bound) = self.bound {
+            let bound = s.serialize(bound);
+            push_field(&mut parameter, "bound", bound);
+        }
+        for (key, value) in modifier_fields(s, &self.modifiers) {
+            push_field(&mut parameter, key, value);
+        }
+        parameter
+    }
+}
+impl Serialize for typescript::TypeArguments<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let params = s.serialize_all(&self.arguments);
+        s.node(span(self), "TSTypeParameterInstantiation", vec![("params", params)])
+    }
+}
+impl Serialize for typescript::AsExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSAsExpression", vec![("expression", expression), ("typeAnnotation", value)])
+    }
+}
+impl Serialize for typescript::SatisfiesExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSSatisfiesExpression", vec![("expression", expression), ("typeAnnotation", value)])
+    }
+}
+impl Serialize for typescript::NonNullExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        s.node(span(self), "TSNonNullExpression", vec![("expression", expression)])
+    }
+}
+impl Serialize for typescript::InstantiationExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        let type_arguments = s.serialize(&self.type_arguments);
+        s.node(span(self), "TSInstantiationExpression", vec![
+            ("expression", expression),
+            ("typeArguments", type_arguments),
+        ])
+    }
+}
+impl Serialize for typescript::TypeAssertion<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let value = s.serialize(&self.value);
+        let expression = s.serialize(&self.expression);
+        s.node(span(self), "TSTypeAssertion", vec![("typeAnnotation", value), ("expression", expression)])
+    }
+}
+impl Serialize for typescript::InterfaceDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let id = s.serialize(&self.id);
+        let type_parameters = s.serialize(&self.type_parameters);
+        let extends = self.extends.iter().map(|reference| s.heritage(reference)).collect::<Vec<_>>();
+        let members = s.serialize_all(&self.body.members);
+        let body = s.node(span(&self.body), "TSInterfaceBody", vec![("body", members)]);
+        s.node(span(self), "TSInterfaceDeclaration", vec![
+            ("id", id),
+            ("typeParameters", type_parameters),
+            ("extends", extends.into()),
+            ("body", body),
+        ])
+    }
+}
+impl Serialize for typescript::TypeAliasDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let id = s.serialize(&self.id);
+        let type_parameters = s.serialize(&self.type_parameters);
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSTypeAliasDeclaration", vec![
+            ("id", id),
+            ("typeParameters", type_parameters),
+            ("typeAnnotation", value),
+        ])
+    }
+}
+impl Serialize for typescript::EnumDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let id = s.serialize(&self.id);
+        let members = s.serialize_all(&self.members);
+        s.node(span(self), "TSEnumDeclaration", vec![
+            ("const", self.is_const.into()),
+            ("id", id),
+            ("members", members),
+        ])
+    }
+}
+impl Serialize for typescript::EnumMember<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let computed = matches!(self.name, general::PropertyName::Computed(_));
+        let id = s.serialize(&self.name);
+        let initializer = s.serialize(&self.init);
+        s.node(span(self), "TSEnumMember", vec![
+            ("computed", computed.into()),
+            ("id", id),
+            ("initializer", initializer),
+        ])
+    }
+}
+impl Serialize for typescript::FunctionSignatureDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let (generator, is_async) = function_flags(&self.kind);
+        let id = s.serialize(&self.id);
+        let params = s.serialize(&self.params);
+        let function = s.node(span(self), "TSDeclareFunction", vec![
+            ("id", id),
+            ("expression", false.into()),
+            ("generator", generator.into()),
+            ("async", is_async.into()),
+            ("params", params),
+        ]);
+        s.typed_function(function, &self.params)
+    }
+}
+impl Serialize for typescript::DeclareDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        declared_item(s, &self.declaration)
+    }
+}
+
+// The node for what follows "declare", marked as declared unless it's one
+// of Flow's nodes that are only ever declared.
+fn declared_item(s: &mut Serializer, item: &typescript::DeclaredItem) -> Value {
+    let mut declaration = match *item {
+        typescript::DeclaredItem::Variable(ref n) => s.serialize(n),
+        typescript::DeclaredItem::Function(ref n) => s.serialize(n),
+        typescript::DeclaredItem::Class(ref n) => s.serialize(n),
+        typescript::DeclaredItem::Enum(ref n) => s.serialize(n),
+        typescript::DeclaredItem::Interface(ref n) => s.serialize(n),
+        typescript::DeclaredItem::TypeAlias(ref n) => s.serialize(n),
+        typescript::DeclaredItem::Module(ref n) => s.serialize(n),
+        typescript::DeclaredItem::Opaque(ref n) => s.serialize(n),
+        typescript::DeclaredItem::Export(ref n) => return s.serialize(n),
+        typescript::DeclaredItem::ModuleExports(ref n) => return s.serialize(n),
+    };
+
+    push_field(&mut declaration, "declare", true.into());
+    declaration
+}
+impl Serialize for typescript::DeclareVariableDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let kind = match self.kind {
+            typescript::DeclareVariableKind::Var => "var",
+            typescript::DeclareVariableKind::Let => "let",
+            typescript::DeclareVariableKind::Const => "const",
+        };
+        let declarations = self.declarators.iter().map(|declarator| s.serialize(declarator)).collect();
+        s.variable_declaration(span(self), kind, declarations)
+    }
+}
+impl Serialize for typescript::ExportDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = s.serialize(&self.exported);
+        s.export_named(span(self), declaration, vec![], None)
+    }
+}
+impl Serialize for typescript::QualifiedModuleName<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let left = s.serialize(&self.left);
+        let right = s.serialize(&self.right);
+        s.node(span(self), "TSQualifiedName", vec![("left", left), ("right", right)])
+    }
+}
+impl Serialize for typescript::ModuleDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let kind = match self.kind {
+            typescript::ModuleKind::Namespace => "namespace",
+            typescript::ModuleKind::Module => "module",
+            typescript::ModuleKind::Global => "global",
+        };
+        let id = match self.id {
+            Some(ref id) => s.serialize(id),
+            None => s.token_identifier(&self.token_kind, "global"),
+        };
+
+        let mut fields = vec![("id", id)];
+        if let Some(ref body) = self.body {
+            fields.push(("body", s.serialize(body)));
+        }
+        fields.push(("kind", kind.into()));
+        s.node(span(self), "TSModuleDeclaration", fields)
+    }
+}
+impl Serialize for typescript::ModuleBlock<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let body = s.serialize_all(&self.body);
+        s.node(span(self), "TSModuleBlock", vec![("body", body)])
+    }
+}
+impl Serialize for typescript::ImportEqualsDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let id = s.serialize(&self.id);
+        let value = s.serialize(&self.value);
+        s.node(span(self), "TSImportEqualsDeclaration", vec![
+            ("id", id),
+            ("moduleReference", value),
+            ("importKind", "value".into()),
+        ])
+    }
+}
+impl Serialize for typescript::ExternalModuleReference<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let source = s.serialize(&self.source);
+        s.node(span(self), "TSExternalModuleReference", vec![("expression", source)])
+    }
+}
+impl Serialize for typescript::ExportAssignment<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        s.node(span(self), "TSExportAssignment", vec![("expression", expression)])
+    }
+}
+impl Serialize for typescript::ExportTypeSpecifiers<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let specifiers = s.list(&self.specifiers, &self.last_specifier);
+        let source = self.source.as_ref().map(|(_, source)| source);
+        let mut declaration = s.export_named(span(self), Value::Null, specifiers, source);
+        push_field(&mut declaration, "exportKind", "type".into());
+        declaration
+    }
+}
+impl Serialize for typescript::ClassMethodSignature<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let is_abstract = self.modifiers.iter().any(|modifier| matches!(modifier.kind, typescript::ModifierKind::Abstract));
+        let computed = matches!(self.id, classes::ClassFieldId::Public(general::PropertyName::Computed(_)));
+        let kind = match self.kind {
+            objects::MethodKind::Get => "get",
+            objects::MethodKind::Set => "set",
+            _ => "method",
+        };
+        let (generator, is_async) = method_flags(&self.kind);
+
+        let params = s.serialize(&self.params);
+        let value = s.node(span(&self.params), "TSEmptyBodyFunctionExpression", vec![
+            ("id", Value::Null),
+            ("generator", generator.into()),
+            ("async", is_async.into()),
+            ("params", params),
+            ("body", Value::Null),
+        ]);
+        let value = s.typed_function(value, &self.params);
+
+        let mut fields = vec![
+            ("static", self.pos.is_some().into()),
+            ("computed", computed.into()),
+            ("key", s.serialize(&self.id)),
+            ("kind", kind.into()),
+            ("value", value),
+        ];
+        if self.optional {
+            fields.push(("optional", true.into()));
+        }
+        fields.extend(modifier_fields(s, &self.modifiers).into_iter().filter(|&(key, _)| key != "abstract"));
+
+        let kind = if is_abstract { "TSAbstractMethodDefinition" } else { "MethodDefinition" };
+        s.node(span(self), kind, fields)
+    }
+}
+
+
+// Flow
+
+impl Serialize for flow::MaybeType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let value = s.serialize(&self.value);
+        s.node(span(self), "NullableTypeAnnotation", vec![("typeAnnotation", value)])
+    }
+}
+impl Serialize for flow::ExactObjectType<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let members = s.serialize_all(&self.members);
+        s.node(span(self), "ObjectTypeAnnotation", vec![("properties", members), ("exact", true.into())])
+    }
+}
+impl Serialize for flow::SpreadTypeMember<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let value = s.serialize(&self.value);
+        s.node(span(self), "ObjectTypeSpreadProperty", vec![("argument", value)])
+    }
+}
+impl Serialize for flow::Predicate<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        match self.value {
+            Some(ref value) => {
+                let value = s.serialize(value);
+                s.node(span(self), "DeclaredPredicate", vec![("value", value)])
+            }
+            None => s.node(span(self), "InferredPredicate", vec![]),
+        }
+    }
+}
+impl Serialize for flow::OpaqueTypeDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let id = s.serialize(&self.id);
+        let type_parameters = s.serialize(&self.type_parameters);
+        let supertype = s.serialize(&self.supertype);
+        let value = s.serialize(&self.value);
+        s.node(span(self), "OpaqueType", vec![
+            ("id", id),
+            ("typeParameters", type_parameters),
+            ("supertype", supertype),
+            ("impltype", value),
+        ])
+    }
+}
+impl Serialize for flow::DeclareExportDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let declaration = match self.exported {
+            flow::DeclareExport::Item(ref item) => declared_item(s, item),
+            flow::DeclareExport::Type(ref export) => s.serialize(&export.value),
+        };
+        s.node(span(self), "DeclareExportDeclaration", vec![
+            ("default", self.token_default.is_some().into()),
+            ("declaration", declaration),
+            ("specifiers", Value::Array(vec![])),
+            ("source", Value::Null),
+        ])
+    }
+}
+impl Serialize for flow::DeclareModuleExports<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let type_annotation = s.serialize(&self.type_annotation);
+        s.node(span(self), "DeclareModuleExports", vec![("typeAnnotation", type_annotation)])
+    }
+}
+impl Serialize for flow::TypeCastExpression<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let expression = s.serialize(&self.expression);
+        let type_annotation = s.serialize(&self.type_annotation);
+        s.node(span(self), "TypeCastExpression", vec![
+            ("expression", expression),
+            ("typeAnnotation", type_annotation),
+        ])
+    }
+}
+impl Serialize for flow::ImportTypeDeclaration<'_> {
+    fn serialize(&self, s: &mut Serializer) -> Value {
+        let mut specifiers = vec![];
+        if let Some(ref default) = self.default {
+            specifiers.push(default_specifier(s, default));
+        }
+        if let Some((ref token_star, ref namespace)) = self.namespace {
+            specifiers.push(s.namespace_specifier("ImportNamespaceSpecifier", "local", token_star, namespace));
+        }
+        if let Some(ref items) = self.specifiers {
+            specifiers.extend(s.list(items, &self.last_specifier));
+        }
+
+        let kind = match self.kind {
+            flow::ImportKind::Type => "type",
+            flow::ImportKind::Typeof => "typeof",
+        };
+        let mut declaration = s.import_declaration(span(self), specifiers, &self.source);
+        push_field(&mut declaration, "importKind", kind.into());
+        declaration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
+
+    fn script(code: &str) -> Value {
+        let ast = Parser::new(code.into_tokenizer()).parse_script().unwrap().into();
+        to_value(&ast, code)
+    }
+    fn module(code: &str) -> Value {
+        let ast = Parser::new(code.into_tokenizer()).parse_module().unwrap().into();
+        to_value(&ast, code)
+    }
+
+    // Strip locations to keep the expected trees readable.
+    fn shape(value: &Value) -> String {
+        fn strip(value: &Value) -> Value {
+            match *value {
+                Value::Object(ref fields) => Value::Object(
+                    fields
+                        .iter()
+                        .filter(|(key, _)| !["start", "end", "loc", "range"].contains(&key.as_str()))
+                        .map(|(key, value)| (key.clone(), strip(value)))
+                        .collect(),
+                ),
+                Value::Array(ref items) => Value::Array(items.iter().map(strip).collect()),
+                ref value => value.clone(),
+            }
+        }
+        strip(value).to_string()
+    }
+
+    fn first_expression(program: &Value) -> &Value {
+        program.get("body").unwrap().as_array().unwrap()[0].get("expression").unwrap()
+    }
+
+    #[test]
+    fn it_serializes_locations() {
+        let program = script("a;\n  b + 1;");
+
+        assert_eq!(
+            program.to_string(),
+            "{\"type\":\"Program\",\"start\":0,\"end\":11,\
+             \"loc\":{\"start\":{\"line\":1,\"column\":0},\"end\":{\"line\":2,\"column\":8}},\"range\":[0,11],\
+             \"body\":[\
+             {\"type\":\"ExpressionStatement\",\"start\":0,\"end\":2,\
+             \"loc\":{\"start\":{\"line\":1,\"column\":0},\"end\":{\"line\":1,\"column\":2}},\"range\":[0,2],\
+             \"expression\":{\"type\":\"Identifier\",\"start\":0,\"end\":1,\
+             \"loc\":{\"start\":{\"line\":1,\"column\":0},\"end\":{\"line\":1,\"column\":1}},\"range\":[0,1],\
+             \"name\":\"a\"}},\
+             {\"type\":\"ExpressionStatement\",\"start\":5,\"end\":11,\
+             \"loc\":{\"start\":{\"line\":2,\"column\":2},\"end\":{\"line\":2,\"column\":8}},\"range\":[5,11],\
+             \"expression\":{\"type\":\"BinaryExpression\",\"start\":5,\"end\":10,\
+             \"loc\":{\"start\":{\"line\":2,\"column\":2},\"end\":{\"line\":2,\"column\":7}},\"range\":[5,10],\
+             \"left\":{\"type\":\"Identifier\",\"start\":5,\"end\":6,\
+             \"loc\":{\"start\":{\"line\":2,\"column\":2},\"end\":{\"line\":2,\"column\":3}},\"range\":[5,6],\
+             \"name\":\"b\"},\"operator\":\"+\",\
+             \"right\":{\"type\":\"Literal\",\"start\":9,\"end\":10,\
+             \"loc\":{\"start\":{\"line\":2,\"column\":6},\"end\":{\"line\":2,\"column\":7}},\"range\":[9,10],\
+             \"value\":1,\"raw\":\"1\"}}}],\"sourceType\":\"script\"}"
+        );
+    }
+
+    #[test]
+    fn it_uses_utf16_offsets() {
+        let program = script("'\u{1F600}'; a;");
+        let second = &program.get("body").unwrap().as_array().unwrap()[1];
+
+        // The emoji is two UTF-16 code units, but four bytes.
+        assert_eq!(second.get("start"), Some(&Value::from(6usize)));
+        assert_eq!(
+            second.get("loc").unwrap().get("start").unwrap().get("column"),
+            Some(&Value::from(6usize))
+        );
+    }
+
+    #[test]
+    fn it_maps_operators() {
+        assert_eq!(
+            shape(first_expression(&script("a === b && c;"))),
+            "{\"type\":\"LogicalExpression\",\
+             \"left\":{\"type\":\"BinaryExpression\",\"left\":{\"type\":\"Identifier\",\"name\":\"a\"},\
+             \"operator\":\"===\",\"right\":{\"type\":\"Identifier\",\"name\":\"b\"}},\
+             \"operator\":\"&&\",\"right\":{\"type\":\"Identifier\",\"name\":\"c\"}}"
+        );
+        assert_eq!(
+            shape(first_expression(&script("x >>>= -y++;"))),
+            "{\"type\":\"AssignmentExpression\",\"left\":{\"type\":\"Identifier\",\"name\":\"x\"},\
+             \"operator\":\">>>=\",\"right\":{\"type\":\"UnaryExpression\",\"operator\":\"-\",\"prefix\":true,\
+             \"argument\":{\"type\":\"UpdateExpression\",\"operator\":\"++\",\"prefix\":false,\
+             \"argument\":{\"type\":\"Identifier\",\"name\":\"y\"}}}}"
+        );
+    }
+
+    #[test]
+    fn it_flattens_sequences_and_parentheses() {
+        assert_eq!(
+            shape(first_expression(&script("(a, (b), c);"))),
+            "{\"type\":\"SequenceExpression\",\"expressions\":[\
+             {\"type\":\"Identifier\",\"name\":\"a\"},{\"type\":\"Identifier\",\"name\":\"b\"},\
+             {\"type\":\"Identifier\",\"name\":\"c\"}]}"
+        );
+    }
+
+    #[test]
+    fn it_decodes_literals() {
+        assert_eq!(
+            shape(&script("'use strict'; 0; \"a\\n\\x41\\u{42}\";")),
+            "{\"type\":\"Program\",\"body\":[\
+             {\"type\":\"ExpressionStatement\",\
+             \"expression\":{\"type\":\"Literal\",\"value\":\"use strict\",\"raw\":\"'use strict'\"},\
+             \"directive\":\"use strict\"},\
+             {\"type\":\"ExpressionStatement\",\
+             \"expression\":{\"type\":\"Literal\",\"value\":0,\"raw\":\"0\"}},\
+             {\"type\":\"ExpressionStatement\",\
+             \"expression\":{\"type\":\"Literal\",\"value\":\"a\\nAB\",\"raw\":\"\\\"a\\\\n\\\\x41\\\\u{42}\\\"\"}}],\
+             \"sourceType\":\"script\"}"
+        );
+        assert_eq!(
+            shape(first_expression(&script("/a+/gi;"))),
+            "{\"type\":\"Literal\",\"value\":null,\"raw\":\"/a+/gi\",\"regex\":{\"pattern\":\"a+\",\"flags\":\"gi\"}}"
+        );
+    }
+
+    #[test]
+    fn it_wraps_optional_chains() {
+        // The parser does not handle optional chaining yet, so build "a?.b.c()".
+        let optional = expression::OptionalMemberExpression {
+            object: NodeBox::new(general::ReferenceIdentifier::from("a").into()),
+            property: expression::OptionalIdentifierPropertyAccess {
+                token_question_period: Default::default(),
+                tokens_separator: Default::default(),
+                id: "b".into(),
+                position: None,
+                comments: None,
+            }.into(),
+            position: None,
+            comments: None,
+        };
+        let member = expression::MemberExpression {
+            object: NodeBox::new(optional.into()),
+            property: expression::IdentifierPropertyAccess {
+                token_period: Default::default(),
+                tokens_separator: Default::default(),
+                id: "c".into(),
+                position: None,
+                comments: None,
+            }.into(),
+            position: None,
+            comments: None,
+        };
+        let call: alias::Expression = expression::CallExpression {
+            callee: NodeBox::new(member.into()),
+            arguments: Default::default(),
+            position: None,
+            comments: None,
+        }.into();
+
+        assert_eq!(
+            Serializer::new("").serialize(&call).to_string(),
+            "{\"type\":\"ChainExpression\",\"expression\":{\"type\":\"CallExpression\",\
+             \"callee\":{\"type\":\"MemberExpression\",\
+             \"object\":{\"type\":\"MemberExpression\",\"object\":{\"type\":\"Identifier\",\"name\":\"a\"},\
+             \"property\":{\"type\":\"Identifier\",\"name\":\"b\"},\"computed\":false,\"optional\":true},\
+             \"property\":{\"type\":\"Identifier\",\"name\":\"c\"},\"computed\":false,\"optional\":false},\
+             \"arguments\":[],\"optional\":false}}"
+        );
+    }
+
+    #[test]
+    fn it_maps_patterns() {
+        let program = script("var {a, b: [c = 1, ...d]} = e;");
+        let declaration = &program.get("body").unwrap().as_array().unwrap()[0];
+
+        assert_eq!(
+            shape(declaration),
+            "{\"type\":\"VariableDeclaration\",\"declarations\":[{\"type\":\"VariableDeclarator\",\
+             \"id\":{\"type\":\"ObjectPattern\",\"properties\":[\
+             {\"type\":\"Property\",\"key\":{\"type\":\"Identifier\",\"name\":\"a\"},\
+             \"value\":{\"type\":\"Identifier\",\"name\":\"a\"},\"kind\":\"init\",\"method\":false,\
+             \"shorthand\":true,\"computed\":false},\
+             {\"type\":\"Property\",\"key\":{\"type\":\"Identifier\",\"name\":\"b\"},\
+             \"value\":{\"type\":\"ArrayPattern\",\"elements\":[\
+             {\"type\":\"AssignmentPattern\",\"left\":{\"type\":\"Identifier\",\"name\":\"c\"},\
+             \"right\":{\"type\":\"Literal\",\"value\":1,\"raw\":\"1\"}},\
+             {\"type\":\"RestElement\",\"argument\":{\"type\":\"Identifier\",\"name\":\"d\"}}]},\
+             \"kind\":\"init\",\"method\":false,\"shorthand\":false,\"computed\":false}]},\
+             \"init\":{\"type\":\"Identifier\",\"name\":\"e\"}}],\"kind\":\"var\"}"
+        );
+    }
+
+    #[test]
+    fn it_maps_classes() {
+        let program = script("class A extends B { constructor() {} static get x() {} }");
+        let class = &program.get("body").unwrap().as_array().unwrap()[0];
+        let body = class.get("body").unwrap().get("body").unwrap().as_array().unwrap();
+
+        assert_eq!(class.get("type"), Some(&Value::from("ClassDeclaration")));
+        assert_eq!(body[0].get("kind"), Some(&Value::from("constructor")));
+        assert_eq!(body[1].get("kind"), Some(&Value::from("get")));
+        assert_eq!(body[1].get("static"), Some(&Value::from(true)));
+        assert_eq!(body[1].get("value").unwrap().get("start"), Some(&Value::from(49usize)));
+    }
+
+    #[test]
+    fn it_maps_modules() {
+        let program = module("import a, * as b from 'c'; export default function () {} export { a as d };");
+        let body = program.get("body").unwrap().as_array().unwrap();
+
+        assert_eq!(
+            shape(&body[0]),
+            "{\"type\":\"ImportDeclaration\",\"specifiers\":[\
+             {\"type\":\"ImportDefaultSpecifier\",\"local\":{\"type\":\"Identifier\",\"name\":\"a\"}},\
+             {\"type\":\"ImportNamespaceSpecifier\",\"local\":{\"type\":\"Identifier\",\"name\":\"b\"}}],\
+             \"source\":{\"type\":\"Literal\",\"value\":\"c\",\"raw\":\"'c'\"}}"
+        );
+        assert_eq!(body[0].get("specifiers").unwrap().as_array().unwrap()[1].get("start"), Some(&Value::from(10usize)));
+
+        let declaration = body[1].get("declaration").unwrap();
+        assert_eq!(declaration.get("type"), Some(&Value::from("FunctionDeclaration")));
+        assert_eq!(declaration.get("start"), Some(&Value::from(42usize)));
+        assert_eq!(declaration.get("id"), Some(&Value::Null));
+
+        assert_eq!(
+            shape(&body[2]),
+            "{\"type\":\"ExportNamedDeclaration\",\"declaration\":null,\"specifiers\":[\
+             {\"type\":\"ExportSpecifier\",\"local\":{\"type\":\"Identifier\",\"name\":\"a\"},\
+             \"exported\":{\"type\":\"Identifier\",\"name\":\"d\"}}],\"source\":null}"
+        );
+    }
+
+    #[test]
+    fn it_cooks_escapes() {
+        assert_eq!(cook("a\\tb"), "a\tb");
+        assert_eq!(cook("\\0\\101\\8"), "\0A8");
+        assert_eq!(cook("\\ud83d\\ude00"), "\u{1F600}");
+        assert_eq!(cook("a\\\r\nb"), "ab");
+    }
+    #[test]
+    fn it_decodes_entities() {
+        assert_eq!(decode_entities("a &amp; b &lt;c&gt;"), "a & b <c>");
+        assert_eq!(decode_entities("&#65;&#x42;&nbsp;"), "AB\u{a0}");
+        assert_eq!(decode_entities("&foo; & &amp"), "&foo; & &amp");
+    }
+}
@@ -0,0 +1,555 @@
+//! Randomized testing of the tokenizer, parser and printer.
+//!
+//! Inputs are generated from a seeded random number generator, either as
+//! random text, as a soup of JavaScript tokens, as deeply nested code, or by
+//! mutating a corpus of valid programs, and each is run through a `Target`
+//! that checks one property that must hold for every input. Inputs take turns
+//! being parsed with each of the options in `parser_options`. The first input
+//! that breaks the property is shrunk to a smaller input that still does, and
+//! is reported along with its options.
+
+use std::fmt;
+use std::panic;
+
+use failure::Fail;
+
+use ast::{self, FormatOptions};
+use estree::{self, Value};
+use parser::{self, ParserOptions, SourceType, SyntaxExtensions, MAX_NESTING_DEPTH};
+use stack;
+use tokenizer::{tokens, Hint, IntoTokenizer, TokenRange, Tokenizer};
+
+// Valid programs that mutated inputs start from, covering most of the syntax
+// that the parser supports.
+const SEEDS: &[&str] = &[
+    "var a = 1, b = 'str', c = \"dq\\n\\x41\\u{1F600}\";",
+    "let {a, b: [c, ...d] = []} = e;",
+    "const f = (a, b = 2, ...c) => a + b * c;",
+    "function* g(a) { yield a; yield* b; return; }",
+    "async function h() { await a; for (const x of y) {} }",
+    "class A extends B { constructor() { super(); } static m() {} get x() { return 1; } set x(v) {} }",
+    "if (a) b; else if (c) { d(); } else e;",
+    "for (var i = 0; i < 10; i++) continue;",
+    "for (const k in o) break;",
+    "for (let v of [1, , 2, ...r]) {}",
+    "while (a) do b; while (c);",
+    "switch (a) { case 1: b; break; default: c; }",
+    "try { a(); } catch (e) { b(); } finally { c(); }",
+    "label: for (;;) { break label; }",
+    "a = b ? c : d, e += f, g **= 2, h >>>= 1;",
+    "x = typeof a === 'string' && !b || void 0 in c instanceof d;",
+    "new A(b).c[d](...e), new.target;",
+    "`a${b}c${`d${e}`}`;",
+    "tag`x${y}`;",
+    "/re[/]g/gi.test(s) / 2;",
+    "o = { a, b: 1, [c]: 2, d() {}, get e() { return 1; }, ...f, 'g': 3, 4: 5 };",
+    "0x1F + 0o17 + 0b101 + .5e10 + 1.5E-3 - -a + +b - --c;",
+    "a\n++b\nc\n(d)",
+    "// comment\n/* block */ a; /** doc */",
+    "'use strict'; delete a.b;",
+    "({ a, b } = c); [d, e] = [e, d];",
+    "async (a) => { await a; }; async x => x;",
+    "with (a) { b; } debugger;",
+    "x = /a/g in b ? /c/ instanceof d : typeof /e/i; void /=/;",
+    "function* f() { yield /a/; return /b/.c; } switch (a) { case /d/: throw /e/; }",
+    "let a: string = b as any; interface I<T> { x?: T; m(): void } type U = A | B[];",
+    "// @flow\ntype A = ?{ +b: number }; opaque type C = D; function f<T>(x: T): T %checks { return x; }",
+    "<div className=\"a\" {...b}>{c} text <br /></div>;",
+    "if (a) function f() {} a <!-- b\n--> c\n",
+];
+
+// Fragments that token soup and mutations are built from.
+const FRAGMENTS: &[&str] = &[
+    "var", "let", "const", "function", "class", "extends", "if", "else", "for", "while", "do",
+    "in", "of", "return", "yield", "async", "await", "new", "this", "super", "import", "export",
+    "default", "from", "as", "get", "set", "static", "typeof", "delete", "void", "switch", "case",
+    "try", "catch", "finally", "throw", "break", "continue", "debugger", "with", "null", "true",
+    "a", "b", "$", "_x", "{", "}", "(", ")", "[", "]", ";", ",", ".", "...", "?", ":", "=", "=>",
+    "==", "===", "!", "!=", "+", "++", "-", "--", "*", "**", "/", "/=", "%", "<", "<<", ">", ">>>",
+    "&", "&&", "|", "||", "^", "~", "+=", "**=", "0", "1", "0x", "0x1F", "0o", "0o9", "0b2", ".5",
+    "1e", "1e+", "08", "'", "\"", "'a'", "\"\\", "'\\u{", "'\\x4'", "'\\9'", "`", "`a${", "${",
+    "}`", "`${a}`", "/a/g", "/[/]/", "/", "//", "/*", "*/", "/* a */", "// a\n", "\\", "\\u0061",
+    "\n", "\r\n", " ", "\t", "\u{2028}", "\u{a0}", "\u{feff}", "é", "π", "😀", "#", "@",
+    "return /a/g", "typeof /a/", "/a/g in b", "/a/i instanceof b", "case /a/:", "yield /a/", "1 in a",
+    "1..a", "void /=/", "a\n/b/g", ":", "?:", "<", ">", "</", "/>", "<a>", "</a>", "<T>", "as",
+    "type", "interface", "declare", "%checks", "+", "opaque", "<!--", "-->", "enum", "keyof",
+];
+
+// Pairs of fragments that nest inside themselves, for deeply nested inputs.
+const NESTING: &[(&str, &str)] = &[
+    ("(", ")"), ("[", "]"), ("{", "}"), ("{a:", "}"), ("a ? (", ") : b"), ("function(){", "}"),
+    ("`${", "}`"), ("!", ""), ("new ", ""), ("if (a) ", ""), ("x => ", ""), ("<a>", "</a>"),
+    ("Array<", ">"), ("/a/g in (", ")"),
+];
+
+// Characters that random text is mostly made of.
+const CHARS: &str = "abxyz019_$ \n\t{}()[];,.<>+-*/%=!&|^~?:'\"`\\#@";
+
+/// A property that is checked against every input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The tokenizer never panics, and splits any code into tokens that cover
+    /// it exactly, whatever hints it is given.
+    Tokenize,
+
+    /// The parser never panics, whatever options it is given.
+    Parse,
+
+    /// Code that parses is printed, and the printed code parses to the same
+    /// AST.
+    RoundTrip,
+}
+impl Target {
+    pub const ALL: &'static [Target] = &[Target::Tokenize, Target::Parse, Target::RoundTrip];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Target::Tokenize => "tokenize",
+            Target::Parse => "parse",
+            Target::RoundTrip => "round-trip",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Target> {
+        Target::ALL.iter().cloned().find(|target| target.name() == name)
+    }
+
+    /// Check the input parsed with the given options, returning a description
+    /// of the problem if the property does not hold for it.
+    pub fn check(&self, code: &str, options: &ParserOptions) -> Result<(), String> {
+        let result = panic::catch_unwind(|| match *self {
+            Target::Tokenize => check_tokenize(code),
+            Target::Parse => {
+                let _ = parser::parse(code, options);
+                Ok(())
+            }
+            Target::RoundTrip => check_round_trip(code, options),
+        });
+        match result {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = match payload.downcast_ref::<&str>() {
+                    Some(message) => message.to_string(),
+                    None => payload.downcast_ref::<String>().cloned().unwrap_or_default(),
+                };
+                Err(format!("Panicked: {}", message))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzOptions {
+    pub iterations: usize,
+    pub seed: u64,
+
+    // The maximum length of generated inputs, in bytes. Mutated seeds and
+    // nested inputs may be longer.
+    pub max_len: usize,
+}
+impl Default for FuzzOptions {
+    fn default() -> FuzzOptions {
+        FuzzOptions {
+            iterations: 10000,
+            seed: 0,
+            max_len: 64,
+        }
+    }
+}
+
+/// An input that a target failed on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzFailure {
+    pub target: Target,
+    pub input: String,
+    pub options: ParserOptions,
+    pub message: String,
+}
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed on {:?} with {:?}: {}", self.target.name(), self.input, self.options, self.message)
+    }
+}
+impl Fail for FuzzFailure {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzReport {
+    pub target: Target,
+    pub seed: u64,
+    pub inputs: usize,
+
+    // Inputs that parsed with their options, to show how much of the parser
+    // past its error paths was reached.
+    pub parsed: usize,
+}
+impl FuzzReport {
+    pub fn to_text(&self) -> String {
+        format!(
+            "{}: {} inputs from seed {}, {} parsed, no failures\n",
+            self.target.name(),
+            self.inputs,
+            self.seed,
+            self.parsed,
+        )
+    }
+}
+
+/// Check the target against the given inputs, then against random inputs
+/// that are partly made by mutating them.
+pub fn fuzz(target: Target, inputs: &[String], options: &FuzzOptions) -> Result<FuzzReport, FuzzFailure> {
+    let mut corpus: Vec<&str> = SEEDS.to_vec();
+    corpus.extend(inputs.iter().map(|input| &input[..]));
+
+    let parser_options = parser_options();
+    let mut rng = Rng::new(options.seed);
+    let mut report = FuzzReport {
+        target,
+        seed: options.seed,
+        inputs: 0,
+        parsed: 0,
+    };
+    for i in 0..inputs.len() + options.iterations {
+        let input = match inputs.get(i) {
+            Some(input) => input.clone(),
+            None => generate(&mut rng, &corpus, options.max_len),
+        };
+
+        let parser_options = &parser_options[i % parser_options.len()];
+        if let Err(message) = target.check(&input, parser_options) {
+            let (input, message) = shrink(|input| target.check(input, parser_options), input, message);
+            return Err(FuzzFailure {
+                target,
+                input,
+                options: *parser_options,
+                message,
+            });
+        }
+
+        report.inputs += 1;
+        if parses(&input, parser_options) {
+            report.parsed += 1;
+        }
+    }
+    Ok(report)
+}
+
+/// The options that inputs are parsed with: scripts and modules, sloppy code
+/// without Annex B, and each of the syntax extensions.
+pub fn parser_options() -> Vec<ParserOptions> {
+    let typescript = SyntaxExtensions {
+        typescript: true,
+        ..SyntaxExtensions::default()
+    };
+    let flow = SyntaxExtensions {
+        flow: true,
+        ..SyntaxExtensions::default()
+    };
+    let jsx = SyntaxExtensions {
+        jsx: true,
+        ..SyntaxExtensions::default()
+    };
+
+    let mut options = vec![];
+    for &source_type in &[SourceType::Script, SourceType::Module] {
+        for &extensions in &[SyntaxExtensions::default(), typescript, flow, jsx] {
+            options.push(ParserOptions {
+                source_type,
+                extensions,
+                ..ParserOptions::default()
+            });
+        }
+    }
+    options.push(ParserOptions {
+        annex_b: false,
+        ..ParserOptions::script()
+    });
+    options
+}
+
+fn parses(code: &str, options: &ParserOptions) -> bool {
+    panic::catch_unwind(|| parser::parse(code, options).is_ok()).unwrap_or(false)
+}
+
+fn check_tokenize(code: &str) -> Result<(), String> {
+    for &(expression, template) in &[(false, false), (true, false), (false, true), (true, true)] {
+        let hint = Hint::default().expression(expression).template(template);
+        let mut tok = code.into_tokenizer();
+        let mut end = 0;
+        loop {
+            let mut token = tokens::EOFToken {}.into();
+            let mut range = TokenRange::default();
+            tok.next_token(&hint, (&mut token, &mut range));
+
+            if range.start.offset != end {
+                return Err(format!("Token {:?} starts at {} after a token ending at {}", token, range.start.offset, end));
+            }
+            if let tokens::Token::EOF(_) = token {
+                if range.end.offset != code.len() {
+                    return Err(format!("Tokenizing ended at {} of {}", range.end.offset, code.len()));
+                }
+                break;
+            }
+            if range.end.offset <= range.start.offset || !code.is_char_boundary(range.end.offset) {
+                return Err(format!("Token {:?} at {} has an invalid end {}", token, range.start.offset, range.end.offset));
+            }
+            end = range.end.offset;
+        }
+    }
+    Ok(())
+}
+
+fn check_round_trip(code: &str, parser_options: &ParserOptions) -> Result<(), String> {
+    // Without parentheses in the tree, the printer has to add back the ones
+    // that precedence needs.
+    for &preserve_parens in &[true, false] {
+        let parser_options = ParserOptions { preserve_parens, ..*parser_options };
+        let ast = match parser::parse(code, &parser_options) {
+            Ok(ast) => ast,
+            Err(_) => continue,
+        };
+        let expected = shape(&estree::to_value(&ast, code));
+        let parens = if preserve_parens { "" } else { " without parentheses" };
+
+        for options in &[FormatOptions::default(), FormatOptions::pretty()] {
+            let printed = ast::format(&ast, options).map_err(|e| format!("Failed to print{}: {:?}", parens, e))?;
+            let reparsed = parser::parse(&printed, &parser_options)
+                .map_err(|e| format!("Printed {:?}{} does not parse: {}", printed, parens, e))?;
+            if shape(&estree::to_value(&reparsed, &printed)) != expected {
+                return Err(format!("Printed {:?}{} parses to a different AST", printed, parens));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Serialize the tree without locations or raw source text, which change when
+// code is printed.
+fn shape(value: &Value) -> String {
+    fn strip(value: &Value) -> Value {
+        match *value {
+            Value::Object(ref fields) => Value::Object(
+                fields
+                    .iter()
+                    .filter(|(key, _)| !["start", "end", "loc", "range", "raw"].contains(&key.as_str()))
+                    .map(|(key, value)| (key.clone(), stack::grow(|| strip(value))))
+                    .collect(),
+            ),
+            Value::Array(ref items) => Value::Array(items.iter().map(|item| stack::grow(|| strip(item))).collect()),
+            ref value => value.clone(),
+        }
+    }
+    strip(value).to_string()
+}
+
+fn generate(rng: &mut Rng, corpus: &[&str], max_len: usize) -> String {
+    let len = rng.below(max_len + 1);
+    match rng.below(5) {
+        0 => {
+            let chars: Vec<char> = CHARS.chars().collect();
+            let mut text = String::new();
+            while text.len() < len {
+                text.push(match rng.below(10) {
+                    0 => rng.char(),
+                    1 => (rng.below(0x80) as u8) as char,
+                    _ => *rng.pick(&chars),
+                });
+            }
+            text
+        }
+        1 => {
+            let mut text = String::new();
+            while text.len() < len {
+                let fragment = *rng.pick(FRAGMENTS);
+                text.push_str(fragment);
+                if rng.below(2) == 0 {
+                    text.push(' ');
+                }
+            }
+            text
+        }
+        2 => {
+            // Nested around the parser's limit as often as not.
+            let (open, close) = *rng.pick(NESTING);
+            let depth = match rng.below(2) {
+                0 => rng.below(16),
+                _ => MAX_NESTING_DEPTH as usize - 16 + rng.below(32),
+            };
+            let inner = match rng.below(2) {
+                0 => *rng.pick(FRAGMENTS),
+                _ => "a",
+            };
+            format!("{}{}{}", open.repeat(depth), inner, close.repeat(depth))
+        }
+        _ => {
+            let mut text = rng.pick(corpus).to_string();
+            for _ in 0..1 + rng.below(3) {
+                mutate(rng, &mut text, corpus);
+            }
+            text
+        }
+    }
+}
+
+fn mutate(rng: &mut Rng, text: &mut String, corpus: &[&str]) {
+    let start = rng.boundary(text);
+    let end = start + rng.boundary(&text[start..]);
+    let fragment = *rng.pick(FRAGMENTS);
+    match rng.below(4) {
+        0 => text.replace_range(start..end, ""),
+        1 => text.insert_str(start, fragment),
+        2 => text.replace_range(start..end, fragment),
+        _ => {
+            let other = *rng.pick(corpus);
+            let from = rng.boundary(other);
+            let to = from + rng.boundary(&other[from..]);
+            text.insert_str(start, &other[from..to]);
+        }
+    }
+}
+
+// Remove as much of the input as possible while the target still fails on
+// it, returning the smaller input along with its failure.
+fn shrink<F: Fn(&str) -> Result<(), String>>(check: F, mut input: String, mut message: String) -> (String, String) {
+    let mut size = input.len() / 2;
+    while size > 0 {
+        let mut start = 0;
+        while start < input.len() {
+            let mut end = (start + size).min(input.len());
+            while !input.is_char_boundary(end) {
+                end += 1;
+            }
+
+            let mut smaller = input.clone();
+            smaller.replace_range(start..end, "");
+            match check(&smaller) {
+                Err(m) => {
+                    input = smaller;
+                    message = m;
+                }
+                Ok(()) => {
+                    start = end;
+                }
+            }
+        }
+        size /= 2;
+    }
+    (input, message)
+}
+
+// A xorshift* generator, so that runs are reproducible from their seed
+// without depending on an external crate.
+struct Rng(u64);
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // Mix the seed so that nearby seeds give unrelated sequences. The
+        // state must never be zero.
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Rng((z ^ (z >> 31)) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    // Any Unicode scalar value, favoring the Basic Multilingual Plane.
+    fn char(&mut self) -> char {
+        let max = if self.below(4) == 0 { 0x11_0000 } else { 0x1_0000 };
+        loop {
+            if let Some(c) = ::std::char::from_u32(self.below(max) as u32) {
+                return c;
+            }
+        }
+    }
+
+    // A random char boundary in the text.
+    fn boundary(&mut self, text: &str) -> usize {
+        let mut offset = self.below(text.len() + 1);
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_starts_from_valid_seeds() {
+        for seed in SEEDS {
+            assert!(parser_options().iter().any(|options| parses(seed, options)), "{:?} does not parse", seed);
+        }
+    }
+
+    #[test]
+    fn it_holds_for_every_target() {
+        let options = FuzzOptions {
+            iterations: 300,
+            ..FuzzOptions::default()
+        };
+        for &target in Target::ALL {
+            if let Err(failure) = fuzz(target, &[], &options) {
+                panic!("{}", failure);
+            }
+        }
+    }
+
+    #[test]
+    fn it_holds_for_past_failures() {
+        // Nesting that overflowed the stack once it parsed, or took
+        // exponential time to fail at the limit.
+        let depth = MAX_NESTING_DEPTH as usize;
+        let nested = format!("{}a{}", "[".repeat(depth - 16), "]".repeat(depth - 16));
+        assert!(parses(&nested, &ParserOptions::script()));
+
+        // Words printed against the flags of a regular expression or the end
+        // of a number, "let" printed without the parentheses that made it an
+        // expression, operators printed into an HTML comment or into "!==",
+        // and a division read as a regular expression.
+        let inputs = [
+            "/a/g in b".to_string(),
+            "/a/i instanceof b".into(),
+            "x = typeof /a/\nin b".into(),
+            "1 in a; 1e3 instanceof b".into(),
+            "(((((let)))))".into(),
+            "a < !--b\n-->".into(),
+            "o! == '' & c instanceof d".into(),
+            "d: typeof (e) / i; void /=/".into(),
+            nested,
+            format!("x = {}b{}", "a ? (".repeat(depth), ") : c".repeat(depth)),
+        ];
+        for options in parser_options() {
+            for &target in Target::ALL {
+                for input in &inputs {
+                    if let Err(message) = target.check(input, &options) {
+                        panic!("{} failed on {:?} with {:?}: {}", target.name(), input, options, message);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_shrinks_failures() {
+        let check = |input: &str| match (input.find('x'), input.rfind('y')) {
+            (Some(x), Some(y)) if x < y => Err(format!("{} apart", y - x)),
+            _ => Ok(()),
+        };
+        let shrunk = shrink(check, "a; x = 1; b; y(2);".into(), "11 apart".into());
+        assert_eq!(shrunk, ("xy".into(), "1 apart".into()));
+    }
+}
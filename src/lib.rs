@@ -1,14 +1,36 @@
 extern crate ucd;
 extern crate time;
+extern crate stacker;
+extern crate bumpalo;
 
 #[macro_use] extern crate failure;
 
-// #[macro_use]
-// pub mod ast;
+#[macro_use]
+pub mod ast;
 
-mod tokenizer;
+pub mod tokenizer;
 
 pub use tokenizer::IntoTokenizer;
 pub use tokenizer::Tokenizer;
 
 pub mod parser;
+
+pub use parser::tokenize;
+
+pub mod estree;
+
+pub mod analysis;
+
+pub mod minify;
+
+pub mod arena;
+
+pub mod lossless;
+
+pub mod bench;
+
+pub mod batch;
+
+pub mod fuzz;
+
+mod stack;
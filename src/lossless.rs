@@ -0,0 +1,386 @@
+//! Printing trees back out with their original formatting.
+//!
+//! The formatter prints every token from the AST, so comments, whitespace,
+//! quotes, number spellings and redundant parentheses are normalized away.
+//! A `Lossless` tree keeps the source it was parsed from, and when printed
+//! reuses that source for everything that has not changed:
+//!
+//! ```ignore
+//! let mut script = Lossless::parse_script(code, &ParserOptions::script())?;
+//! rename(&mut *script);
+//! let output = script.print()?;
+//! ```
+//!
+//! Printing an unmodified tree reproduces the source byte-for-byte. Nodes
+//! that were modified or created are printed by the formatter, while their
+//! unchanged children and the whitespace and comments between them are
+//! still copied from the source, so edits produce minimal diffs.
+//!
+//! Changes are found by comparing the formatter's output for each node with
+//! its output for the node that was originally parsed at the same position.
+
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut, Range};
+
+use failure::Error;
+
+use ast::{root, NodeDisplay, NodeFormatter, PrintedNode, SourcePosition};
+use parser::{self, ParserOptions};
+
+/// A tree along with the source it was parsed from.
+pub struct Lossless<'src, T> {
+    source: &'src str,
+    original: Printed,
+    tree: T,
+}
+
+impl<'src> Lossless<'src, root::Script<'src>> {
+    /// Parse a script, keeping its source for printing, whatever the source
+    /// type in the options.
+    pub fn parse_script(code: &'src str, options: &ParserOptions) -> Result<Lossless<'src, root::Script<'src>>, Error> {
+        let script = parser::parse_script(code, options)?;
+        Lossless::new(code, script)
+    }
+}
+impl<'src> Lossless<'src, root::Module<'src>> {
+    /// Parse a module, keeping its source for printing, whatever the source
+    /// type in the options.
+    pub fn parse_module(code: &'src str, options: &ParserOptions) -> Result<Lossless<'src, root::Module<'src>>, Error> {
+        let module = parser::parse_module(code, options)?;
+        Lossless::new(code, module)
+    }
+}
+impl<'src, T: NodeDisplay + SourcePosition> Lossless<'src, T> {
+    /// Wrap a tree that was parsed from the given source, before any changes
+    /// have been made to it.
+    pub fn new(source: &'src str, tree: T) -> Result<Lossless<'src, T>, Error> {
+        Ok(Lossless {
+            source,
+            original: Printed::new(&tree)?,
+            tree,
+        })
+    }
+
+    pub fn source(&self) -> &'src str {
+        self.source
+    }
+
+    pub fn into_inner(self) -> T {
+        self.tree
+    }
+
+    /// Print the tree, reusing the source for every part that is unchanged.
+    pub fn print(&self) -> Result<String, Error> {
+        let printed = Printed::new(&self.tree)?;
+        let printer = Printer::new(self.source, &self.original, &printed);
+
+        let mut out = String::with_capacity(self.source.len());
+        printer.root(&mut out);
+        Ok(out)
+    }
+}
+impl<'src, T> Deref for Lossless<'src, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.tree
+    }
+}
+impl<'src, T> DerefMut for Lossless<'src, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.tree
+    }
+}
+
+// The formatter's output for a tree, split up by node.
+struct Printed {
+    output: String,
+    nodes: Vec<PrintedNode>,
+    children: Vec<Vec<usize>>,
+
+    // The index of each node in its parent's children.
+    index: Vec<usize>,
+
+    // How many ancestors share the node's position, to tell apart nodes
+    // that wrap a single child, like expression statements.
+    depth: Vec<usize>,
+}
+impl Printed {
+    fn new<T: NodeDisplay + SourcePosition>(tree: &T) -> Result<Printed, Error> {
+        let mut f = NodeFormatter::new();
+        f.record_nodes();
+        f.node(tree).map_err(|e| format_err!("Failed to print tree: {:?}", e))?;
+        let mut nodes = f.take_printed_nodes();
+
+        // Spaces separating words belong between nodes rather than to them.
+        let output = f.output;
+        for node in nodes.iter_mut() {
+            let text = &output[node.output.clone()];
+            let start = node.output.start + text.len() - text.trim_start().len();
+            node.output = start..start.max(node.output.start + text.trim_end().len());
+        }
+
+        let mut children = vec![vec![]; nodes.len()];
+        let mut index = vec![0; nodes.len()];
+        let mut depth = vec![0; nodes.len()];
+        for (i, node) in nodes.iter().enumerate() {
+            if let Some(parent) = node.parent {
+                index[i] = children[parent].len();
+                children[parent].push(i);
+                if node.position.is_some() && node.position == nodes[parent].position {
+                    depth[i] = depth[parent] + 1;
+                }
+            }
+        }
+
+        Ok(Printed {
+            output,
+            nodes,
+            children,
+            index,
+            depth,
+        })
+    }
+
+    fn text(&self, node: usize) -> &str {
+        &self.output[self.nodes[node].output.clone()]
+    }
+
+    // The output between the given child of a node and the one before it,
+    // where the first and last gaps are bounded by the node itself.
+    fn gap(&self, node: usize, gap: usize) -> &str {
+        let children = &self.children[node];
+        let start = match gap {
+            0 => self.nodes[node].output.start,
+            _ => self.nodes[children[gap - 1]].output.end,
+        };
+        let end = match children.get(gap) {
+            Some(&child) => self.nodes[child].output.start,
+            None => self.nodes[node].output.end,
+        };
+        &self.output[start..end]
+    }
+
+    // The source between the given child of a node and the one before it.
+    fn source_gap(&self, node: usize, gap: usize) -> Option<Range<usize>> {
+        let children = &self.children[node];
+        let start = match gap {
+            0 => self.nodes[node].position.as_ref()?.start,
+            _ => self.nodes[children[gap - 1]].position.as_ref()?.end,
+        };
+        let end = match children.get(gap) {
+            Some(&child) => self.nodes[child].position.as_ref()?.start,
+            None => self.nodes[node].position.as_ref()?.end,
+        };
+        Some(start..end)
+    }
+}
+
+struct Printer<'a> {
+    source: &'a str,
+    original: &'a Printed,
+    printed: &'a Printed,
+
+    // Original nodes by their position and depth.
+    originals: HashMap<(usize, usize, usize), usize>,
+}
+impl<'a> Printer<'a> {
+    fn new(source: &'a str, original: &'a Printed, printed: &'a Printed) -> Printer<'a> {
+        let mut originals = HashMap::new();
+        for (i, node) in original.nodes.iter().enumerate() {
+            if let Some(ref position) = node.position {
+                originals.entry((position.start, position.end, original.depth[i])).or_insert(i);
+            }
+        }
+
+        Printer {
+            source,
+            original,
+            printed,
+            originals,
+        }
+    }
+
+    // The node that was originally parsed where the given node came from.
+    fn original(&self, node: usize) -> Option<usize> {
+        let position = self.printed.nodes[node].position.as_ref()?;
+        self.originals.get(&(position.start, position.end, self.printed.depth[node])).cloned()
+    }
+
+    fn root(&self, out: &mut String) {
+        if self.printed.nodes.is_empty() {
+            return;
+        }
+
+        // Keep anything before and after the first and last tokens.
+        let position = self.original(0).and_then(|node| self.original.nodes[node].position.clone());
+        match position.filter(|position| self.source.get(position.clone()).is_some()) {
+            Some(position) => {
+                out.push_str(&self.source[..position.start]);
+                self.node(0, out);
+                out.push_str(&self.source[position.end..]);
+            }
+            None => self.node(0, out),
+        }
+    }
+
+    fn node(&self, node: usize, out: &mut String) {
+        let original = self.original(node);
+        if let Some(original) = original {
+            if self.original.text(original) == self.printed.text(node) {
+                let position = self.original.nodes[original].position.clone().unwrap();
+                if let Some(text) = self.source.get(position) {
+                    out.push_str(text);
+                    return;
+                }
+            }
+        }
+
+        // If only children changed, everything between them is kept as is.
+        // Otherwise the gaps next to unchanged children are taken from
+        // wherever those children were originally.
+        let children = &self.printed.children[node];
+        let same_shape = original.is_some_and(|original| {
+            self.original.children[original].len() == children.len()
+                && (0..children.len() + 1).all(|gap| self.original.gap(original, gap) == self.printed.gap(node, gap))
+        });
+
+        for gap in 0..children.len() + 1 {
+            let printed = self.printed.gap(node, gap);
+            let slots = match (original, same_shape) {
+                (Some(original), true) => vec![(original, gap)],
+                _ => {
+                    let before = self.slot_before(node, gap);
+                    let after = self.slot_after(node, gap);
+                    if before == after {
+                        before.into_iter().collect()
+                    } else {
+                        after.into_iter().chain(before).collect()
+                    }
+                }
+            };
+
+            let kept = slots.into_iter().filter_map(|(original, gap)| self.kept_gap(original, gap, printed)).next();
+            out.push_str(kept.unwrap_or(printed));
+
+            if let Some(&child) = children.get(gap) {
+                self.node(child, out);
+            }
+        }
+    }
+
+    // The original gap following whatever precedes the given gap.
+    fn slot_before(&self, node: usize, gap: usize) -> Option<(usize, usize)> {
+        match gap {
+            0 => self.original(node).map(|original| (original, 0)),
+            _ => {
+                let child = self.original(self.printed.children[node][gap - 1])?;
+                let parent = self.original.nodes[child].parent?;
+                Some((parent, self.original.index[child] + 1))
+            }
+        }
+    }
+
+    // The original gap preceding whatever follows the given gap.
+    fn slot_after(&self, node: usize, gap: usize) -> Option<(usize, usize)> {
+        match self.printed.children[node].get(gap) {
+            Some(&child) => {
+                let child = self.original(child)?;
+                let parent = self.original.nodes[child].parent?;
+                Some((parent, self.original.index[child]))
+            }
+            None => self.original(node).map(|original| (original, self.original.children[original].len())),
+        }
+    }
+
+    // The source of an original gap, if it separated the same tokens.
+    fn kept_gap(&self, original: usize, gap: usize, printed: &str) -> Option<&'a str> {
+        if self.original.gap(original, gap) != printed {
+            return None;
+        }
+        self.source.get(self.original.source_gap(original, gap)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::general::{BindingIdentifier, ReferenceIdentifier};
+    use ast::visit::{VisitMut, Walk};
+    use ast::{alias, statement};
+
+    struct Rename(&'static str, &'static str);
+    impl<'src> VisitMut<'src> for Rename {
+        fn visit_binding_identifier(&mut self, node: &mut BindingIdentifier<'src>) {
+            if node.value == self.0 {
+                node.value = self.1.into();
+            }
+        }
+        fn visit_reference_identifier(&mut self, node: &mut ReferenceIdentifier<'src>) {
+            if node.value == self.0 {
+                node.value = self.1.into();
+            }
+        }
+    }
+
+    const CODE: &str = "\
+// Leading comment
+var a = \"double\",   b = 0x10; /* trailing */
+
+function f ( x ) {
+    return (x + a) * 1e3; // done
+}
+";
+
+    #[test]
+    fn it_reprints_unmodified_trees() {
+        let script = Lossless::parse_script(CODE, &ParserOptions::script()).unwrap();
+        assert_eq!(script.print().unwrap(), CODE);
+
+        let code = "import  {a as b}  from \"c\";\nexport default ( b );\n";
+        let module = Lossless::parse_module(code, &ParserOptions::module()).unwrap();
+        assert_eq!(module.print().unwrap(), code);
+    }
+
+    #[test]
+    fn it_parses_with_options() {
+        let options = ParserOptions {
+            extensions: parser::SyntaxExtensions {
+                jsx: true,
+                ..Default::default()
+            },
+            ..ParserOptions::script()
+        };
+        let code = "render(<A  b=\"c\" />);\n";
+
+        assert_eq!(Lossless::parse_script(code, &options).unwrap().print().unwrap(), code);
+        assert!(Lossless::parse_script(code, &ParserOptions::script()).is_err());
+    }
+
+    #[test]
+    fn it_keeps_formatting_around_changes() {
+        let mut script = Lossless::parse_script(CODE, &ParserOptions::script()).unwrap();
+        script.walk_mut(&mut Rename("x", "value"));
+
+        assert_eq!(script.print().unwrap(), CODE.replace("x )", "value )").replace("(x +", "(value +"));
+    }
+
+    #[test]
+    fn it_keeps_formatting_between_remaining_statements() {
+        let mut script = Lossless::parse_script("a();\n\n// b\nb();\nc();\n", &ParserOptions::script()).unwrap();
+        script.body.remove(1);
+        assert_eq!(script.print().unwrap(), "a();\nc();\n");
+
+        let mut script = Lossless::parse_script("a();\nb();\n", &ParserOptions::script()).unwrap();
+        script.body.swap(0, 1);
+        assert_eq!(script.print().unwrap(), "b();\na();\n");
+    }
+
+    #[test]
+    fn it_prints_new_nodes() {
+        let mut script = Lossless::parse_script("if (a) {\n  b();\n}\n", &ParserOptions::script()).unwrap();
+        script.body.push(alias::StatementItem::from(statement::DebuggerStatement::default()));
+
+        assert_eq!(script.print().unwrap(), "if (a) {\n  b();\n}\ndebugger;\n");
+    }
+}
@@ -1,47 +1,387 @@
 extern crate jsparse;
-use std::io::Read;
-// extern crate flame;
-// extern crate cpuprofiler;
+#[macro_use] extern crate failure;
+extern crate time;
 
-// use cpuprofiler::PROFILER;
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::panic;
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+
+use failure::Error;
+
+use jsparse::ast::{self, FormatOptions};
+use jsparse::batch::{self, BatchOptions};
+use jsparse::bench::{self, BenchFile, BenchOptions};
+use jsparse::estree;
+use jsparse::fuzz::{self, FuzzOptions, Target};
+use jsparse::minify::{self, MinifyOptions};
+use jsparse::parser::{self, ParserOptions, SourceType};
+
+const USAGE: &str = "\
+Usage: jsparse <command> [--module | --script] [--typescript | --flow] [file ...]
+       jsparse check [--threads <n>] [--timings] [--json] [path ...]
+       jsparse bench [--iterations <n>] [--warmup <n>] [--json] [file ...]
+       jsparse fuzz [--target <name>] [--iterations <n>] [--seed <n>] [--max-len <n>] [file ...]
+
+Commands:
+    tokens    Print every token, including whitespace and comments
+    ast       Print the ESTree JSON of each file, one per line
+    check     Report syntax errors, exiting with status 1 if there are any,
+              searching directories for .js, .mjs, .cjs, .jsx, .ts, .mts, .cts
+              and .tsx files
+    print     Print the code regenerated from the AST
+    strip     Print the code with TypeScript's and Flow's types removed
+    minify    Print minified code
+    bench     Time tokenizing and parsing the files
+    fuzz      Check the tokenizer, parser and printer against random code,
+              starting with the files and mutating them
+
+Options:
+    --module      Parse files as modules
+    --script      Parse files as scripts, the default except for .mjs, .ts,
+                  .mts and .tsx files
+    --typescript  Parse files as TypeScript, the default for .ts, .mts, .cts
+                  and .tsx files
+    --flow        Parse files with Flow's types, the default for other files
+                  with an @flow comment at the top
+
+Check options:
+    --threads <n>     Number of threads to parse on, one per CPU by default
+    --timings         List every file with its parse time
+    --json            Print the report as JSON
 
-struct Framer {
-    // t: flame::SpanGuard
+Bench options:
+    --iterations <n>  Number of timed runs, 10 by default
+    --warmup <n>      Number of runs before timing starts, 2 by default
+    --json            Print the results as JSON
+
+Fuzz options:
+    --target <name>   Only run tokenize, parse or round-trip, rather than all
+    --iterations <n>  Number of random inputs per target, 10000 by default
+    --seed <n>        Seed for the random inputs, based on the time by default
+    --max-len <n>     Maximum length of random inputs, 64 by default
+
+Files are read from stdin if none are given, or if one is \"-\", except by fuzz.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Tokens,
+    Ast,
+    Check,
+    Print,
+    Strip,
+    Minify,
+    Bench,
+    Fuzz,
 }
-impl Framer {
-    fn new() -> Framer {
-        // flame::start("cpu-heavy calculation");
 
-        // PROFILER.lock().unwrap().start("./my-prof.profile").unwrap();
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    files: Vec<String>,
 
-        Framer { }
+    batch: BatchOptions,
+    bench: BenchOptions,
+    fuzz: FuzzOptions,
+    targets: Vec<Target>,
+    timings: bool,
+    json: bool,
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("jsparse: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let batch = match args.command {
+        Command::Bench => Some(run_bench(&args)),
+        Command::Fuzz => Some(run_fuzz(&args)),
+        Command::Check if args.files.iter().any(|path| path != "-") => Some(run_check(&args)),
+        _ => None,
+    };
+    match batch {
+        Some(Ok(true)) => return,
+        Some(Ok(false)) => process::exit(1),
+        Some(Err(error)) => {
+            eprintln!("jsparse: {}", error);
+            process::exit(1);
+        }
+        None => {}
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut failed = false;
+    for (i, path) in args.files.iter().enumerate() {
+        // Text output from several files is separated by a header for each.
+        if args.files.len() > 1 && (args.command == Command::Tokens || args.command == Command::Print || args.command == Command::Strip) {
+            let separator = if i == 0 { "" } else { "\n" };
+            let _ = writeln!(out, "{}==> {} <==", separator, display_path(path));
+        }
+
+        let result = read_file(path).and_then(|code| {
+            let options = parser_options(&args, path, &code);
+            run(args.command, &code, &options, &mut out)
+        });
+        if let Err(error) = result {
+            // Stop quietly when the output is closed, e.g. when piped to head.
+            if error.downcast_ref::<io::Error>().map(|e| e.kind()) == Some(io::ErrorKind::BrokenPipe) {
+                break;
+            }
+
+            let _ = out.flush();
+            eprintln!("{}: {}", display_path(path), error);
+            failed = true;
+        }
+    }
+
+    if failed {
+        process::exit(1);
     }
 }
-impl Drop for Framer {
-    fn drop(&mut self) {
-        // PROFILER.lock().unwrap().stop().unwrap();
-        // flame::end("cpu-heavy calculation");
 
-        // flame::dump_html(&mut ::std::fs::File::create("flame-graph.html").unwrap()).unwrap()
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Args>, String> {
+    let command = match args.next().as_ref().map(|arg| &arg[..]) {
+        Some("tokens") => Command::Tokens,
+        Some("ast") => Command::Ast,
+        Some("check") => Command::Check,
+        Some("print") => Command::Print,
+        Some("strip") => Command::Strip,
+        Some("minify") => Command::Minify,
+        Some("bench") => Command::Bench,
+        Some("fuzz") => Command::Fuzz,
+        Some("-h") | Some("--help") | Some("help") => return Ok(None),
+        Some(command) => return Err(format!("Unknown command \"{}\"", command)),
+        None => return Err("Missing command".into()),
+    };
+
+    let mut source_type = None;
+    let mut files = vec![];
+    let mut batch = BatchOptions::default();
+    let mut bench = BenchOptions::default();
+    let mut fuzz = FuzzOptions {
+        seed: time::precise_time_ns(),
+        ..FuzzOptions::default()
+    };
+    let mut targets = Target::ALL.to_vec();
+    let mut timings = false;
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        match &arg[..] {
+            "--module" | "--script" => {
+                let requested = if arg == "--module" { SourceType::Module } else { SourceType::Script };
+                if source_type.is_some_and(|source_type| source_type != requested) {
+                    return Err("Options \"--module\" and \"--script\" cannot be combined".into());
+                }
+                source_type = Some(requested);
+            }
+            "--typescript" if batch.parser.extensions.flow => {
+                return Err("Options \"--typescript\" and \"--flow\" cannot be combined".into());
+            }
+            "--flow" if batch.parser.extensions.typescript => {
+                return Err("Options \"--typescript\" and \"--flow\" cannot be combined".into());
+            }
+            "--typescript" => batch.parser.extensions.typescript = true,
+            "--flow" => batch.parser.extensions.flow = true,
+            "--warmup" if command != Command::Bench => {
+                return Err(format!("Option \"{}\" is only supported by bench", arg));
+            }
+            "--iterations" if command != Command::Bench && command != Command::Fuzz => {
+                return Err(format!("Option \"{}\" is only supported by bench and fuzz", arg));
+            }
+            "--target" | "--seed" | "--max-len" if command != Command::Fuzz => {
+                return Err(format!("Option \"{}\" is only supported by fuzz", arg));
+            }
+            "--threads" | "--timings" if command != Command::Check => {
+                return Err(format!("Option \"{}\" is only supported by check", arg));
+            }
+            "--json" if command != Command::Bench && command != Command::Check => {
+                return Err(format!("Option \"{}\" is only supported by check and bench", arg));
+            }
+            "--iterations" if command == Command::Fuzz => fuzz.iterations = count_arg(&arg, args.next())?,
+            "--iterations" => bench.iterations = count_arg(&arg, args.next())?,
+            "--seed" => fuzz.seed = count_arg(&arg, args.next())? as u64,
+            "--max-len" => fuzz.max_len = count_arg(&arg, args.next())?,
+            "--target" => {
+                let name = args.next().ok_or_else(|| format!("Option \"{}\" expects a fuzz target", arg))?;
+                let target = Target::from_name(&name).ok_or_else(|| format!("Unknown fuzz target \"{}\"", name))?;
+                targets = vec![target];
+            }
+            "--warmup" => bench.warmup = count_arg(&arg, args.next())?,
+            "--threads" => batch.threads = count_arg(&arg, args.next())?,
+            "--timings" => timings = true,
+            "--json" => json = true,
+            "-h" | "--help" => return Ok(None),
+            "-" => files.push(arg),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option \"{}\"", arg)),
+            _ => files.push(arg),
+        }
     }
+    if files.is_empty() && command != Command::Fuzz {
+        files.push("-".into());
+    }
+    if command == Command::Check && files.len() > 1 && files.iter().any(|path| path == "-") {
+        return Err("Stdin cannot be checked along with other files".into());
+    }
+    batch.source_type = source_type;
+
+    Ok(Some(Args {
+        command,
+        files,
+        batch,
+        bench,
+        fuzz,
+        targets,
+        timings,
+        json,
+    }))
 }
 
-use std::fs::File;
+fn count_arg(option: &str, value: Option<String>) -> Result<usize, String> {
+    match value.as_ref().map(|value| value.parse()) {
+        Some(Ok(count)) => Ok(count),
+        _ => Err(format!("Option \"{}\" expects a number", option)),
+    }
+}
 
-fn main() {
-    let mut it = std::env::args();
-    let _name = it.next().unwrap();
-    let filename = it.next().unwrap();
+fn parser_options(args: &Args, path: &str, code: &str) -> ParserOptions {
+    args.batch.parser_options(Path::new(path), code)
+}
 
-    let s = {
-        let mut f = File::open(filename).unwrap();
+fn display_path(path: &str) -> &str {
+    if path == "-" { "<stdin>" } else { path }
+}
 
-        let mut s = String::new();
-        f.read_to_string(&mut s).unwrap();
-        // std::io::stdin().read_to_string(&mut s).unwrap();
-        s
-    };
+fn read_file(path: &str) -> Result<String, Error> {
+    let mut code = String::new();
+    if path == "-" {
+        io::stdin().read_to_string(&mut code)?;
+    } else {
+        File::open(path)?.read_to_string(&mut code)?;
+    }
+    Ok(code)
+}
 
-    let _f = Framer::new();
-    let _: () = jsparse::parser::parse_root(&s[..]);
+// Check every file under the paths, returning whether all of them parsed.
+fn run_check(args: &Args) -> Result<bool, Error> {
+    let report = batch::parse_paths(&args.files, &args.batch)?;
+    if args.json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report.to_text(args.timings));
+    }
+    let ok = report.errors().next().is_none();
+    Ok(ok)
+}
+
+fn run_bench(args: &Args) -> Result<bool, Error> {
+    let files = args.files.iter().map(|path| {
+        let code = read_file(path).map_err(|e| format_err!("{}: {}", display_path(path), e))?;
+        Ok(BenchFile {
+            name: display_path(path).into(),
+            options: parser_options(args, path, &code),
+            code,
+        })
+    }).collect::<Result<Vec<_>, Error>>()?;
+
+    let report = bench::bench(&files, &args.bench)?;
+    if args.json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(true)
+}
+
+fn run_fuzz(args: &Args) -> Result<bool, Error> {
+    let inputs = args.files.iter()
+        .map(|path| read_file(path).map_err(|e| format_err!("{}: {}", display_path(path), e)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // Panics are expected while a failing input is shrunk, and are reported
+    // along with the input. The previous hook is put back afterwards.
+    let previous = Arc::new(panic::take_hook());
+    panic::set_hook(Box::new(|_| {}));
+    let mut ok = true;
+    for &target in &args.targets {
+        match fuzz::fuzz(target, &inputs, &args.fuzz) {
+            Ok(report) => print!("{}", report.to_text()),
+            Err(failure) => {
+                println!("{}\nRerun with --seed {}", failure, args.fuzz.seed);
+                ok = false;
+                break;
+            }
+        }
+    }
+    drop(panic::take_hook());
+    panic::set_hook(Box::new(move |info| previous(info)));
+    Ok(ok)
+}
+
+fn run<W: Write>(command: Command, code: &str, options: &ParserOptions, out: &mut W) -> Result<(), Error> {
+    match command {
+        Command::Tokens => {
+            let (tokens, error) = parser::tokenize(code, options);
+            for (token, range) in tokens {
+                let token = format!("{:?}", token);
+                let kind = token.split('(').next().unwrap_or(&token);
+                writeln!(
+                    out,
+                    "{}:{}-{}:{}\t{}\t{:?}",
+                    range.start.line,
+                    range.start.column,
+                    range.end.line,
+                    range.end.column,
+                    kind,
+                    &code[range.start.offset..range.end.offset],
+                )?;
+            }
+            if let Some(error) = error {
+                return Err(error);
+            }
+        }
+        Command::Ast => {
+            let ast = parser::parse(code, options)?;
+            writeln!(out, "{}", estree::to_json(&ast, code))?;
+        }
+        Command::Check => {
+            parser::parse(code, options)?;
+        }
+        Command::Print => {
+            let ast = parser::parse(code, options)?;
+            let output = ast::format(&ast, &FormatOptions::pretty())
+                .map_err(|e| format_err!("Failed to print code: {:?}", e))?;
+            writeln!(out, "{}", output.trim_end())?;
+        }
+        Command::Strip => {
+            let ast = parser::parse(code, options)?;
+            let format_options = FormatOptions {
+                strip_types: true,
+                ..FormatOptions::pretty()
+            };
+            let output = ast::format(&ast, &format_options)
+                .map_err(|e| format_err!("Failed to print code: {:?}", e))?;
+            writeln!(out, "{}", output.trim_end())?;
+        }
+        Command::Minify => {
+            let options = MinifyOptions {
+                parser: *options,
+                ..MinifyOptions::default()
+            };
+            writeln!(out, "{}", minify::minify(code, &options)?)?;
+        }
+        Command::Bench | Command::Fuzz => unreachable!("bench and fuzz run over every file at once"),
+    }
+    Ok(())
 }
@@ -0,0 +1,605 @@
+//! Rewrites that shorten code without changing what it does.
+
+use std::mem;
+
+use ast::alias::{self, Expression, Statement};
+use ast::fold::{Fold, FoldChildren};
+use ast::visit::{Visit, VisitMut, Visitable, Walk};
+use ast::{self, classes, expression, functions, literal, objects, root, statement, NodeBox};
+
+/// A value that an expression is known to evaluate to.
+#[derive(Debug, Clone, PartialEq)]
+enum Constant {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    // In the escaped form that string literals store.
+    String(String),
+}
+impl Constant {
+    fn truthy(&self) -> bool {
+        match *self {
+            Constant::Undefined | Constant::Null => false,
+            Constant::Bool(b) => b,
+            Constant::Number(n) => n != 0.0 && !n.is_nan(),
+            Constant::String(ref s) => !s.is_empty(),
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match *self {
+            Constant::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    // The string form of the value, where it is the same in JS and Rust.
+    fn string(&self) -> Option<String> {
+        match *self {
+            Constant::String(ref s) => Some(s.clone()),
+            Constant::Number(0.0) => Some("0".into()),
+            Constant::Number(n) if n.fract() == 0.0 && n.abs() < 1e21 => Some(format!("{}", n)),
+            _ => None,
+        }
+    }
+
+    fn strict_equals(&self, other: &Constant) -> Option<bool> {
+        match (self, other) {
+            // Equal strings may be escaped differently.
+            (&Constant::String(_), &Constant::String(_)) => None,
+            (a, b) => Some(a == b),
+        }
+    }
+
+    fn into_expression(self) -> Option<Expression<'static>> {
+        Some(match self {
+            Constant::Undefined => unary(Unary::Void, number(0.0)),
+            Constant::Null => literal::Null::default().into(),
+            Constant::Bool(b) => unary(Unary::Not, number(if b { 0.0 } else { 1.0 })),
+            Constant::Number(n) if !n.is_finite() => return None,
+            Constant::Number(n) if n < 0.0 || (n == 0.0 && n.is_sign_negative()) => {
+                unary(Unary::Negative, number(-n))
+            }
+            Constant::Number(n) => number(n),
+            Constant::String(s) => literal::String::from(s).into(),
+        })
+    }
+}
+
+enum Unary {
+    Void,
+    Not,
+    Negative,
+}
+
+fn unary<'src>(op: Unary, value: Expression<'src>) -> Expression<'src> {
+    let value = NodeBox::new(value);
+    match op {
+        Unary::Void => expression::VoidExpression {
+            token_void: Default::default(),
+            value,
+            position: None,
+            comments: None,
+        }.into(),
+        Unary::Not => expression::NegateExpression {
+            token_exclam: Default::default(),
+            value,
+            position: None,
+            comments: None,
+        }.into(),
+        Unary::Negative => expression::NegativeExpression {
+            token_minus: Default::default(),
+            value,
+            position: None,
+            comments: None,
+        }.into(),
+    }
+}
+
+fn number(value: f64) -> Expression<'static> {
+    literal::Numeric::from(value).into()
+}
+
+// A sequence on the left keeps all but its last expression outside, so
+// "(a, b) && c" is written as "a, b && c".
+fn and<'src>(left: Expression<'src>, right: Expression<'src>) -> Expression<'src> {
+    match left {
+        Expression::Sequence(n) => {
+            let n = NodeBox::into_inner(n);
+            sequence(Some(NodeBox::into_inner(n.left)), and(NodeBox::into_inner(n.right), right))
+        }
+        left => expression::AndExpression {
+            left: NodeBox::new(left),
+            token_ampamp: Default::default(),
+            right: NodeBox::new(right),
+            position: None,
+            comments: None,
+        }.into(),
+    }
+}
+
+fn or<'src>(left: Expression<'src>, right: Expression<'src>) -> Expression<'src> {
+    match left {
+        Expression::Sequence(n) => {
+            let n = NodeBox::into_inner(n);
+            sequence(Some(NodeBox::into_inner(n.left)), or(NodeBox::into_inner(n.right), right))
+        }
+        left => expression::OrExpression {
+            left: NodeBox::new(left),
+            token_barbar: Default::default(),
+            right: NodeBox::new(right),
+            position: None,
+            comments: None,
+        }.into(),
+    }
+}
+
+fn conditional<'src>(test: Expression<'src>, consequent: Expression<'src>, alternate: Expression<'src>) -> Expression<'src> {
+    match test {
+        Expression::Sequence(n) => {
+            let n = NodeBox::into_inner(n);
+            sequence(Some(NodeBox::into_inner(n.left)), conditional(NodeBox::into_inner(n.right), consequent, alternate))
+        }
+        test => expression::ConditionalExpression {
+            test: NodeBox::new(test),
+            token_question: Default::default(),
+            consequent: NodeBox::new(consequent),
+            token_colon: Default::default(),
+            alternate: NodeBox::new(alternate),
+            position: None,
+            comments: None,
+        }.into(),
+    }
+}
+
+fn sequence<'src>(left: Option<Expression<'src>>, right: Expression<'src>) -> Expression<'src> {
+    match left {
+        Some(left) => expression::SequenceExpression {
+            left: NodeBox::new(left),
+            token_comma: Default::default(),
+            right: NodeBox::new(right),
+            position: None,
+            comments: None,
+        }.into(),
+        None => right,
+    }
+}
+
+fn printed_len(expr: &Expression<'_>) -> Option<usize> {
+    ast::format(expr, &Default::default()).ok().map(|s| s.len())
+}
+
+fn empty() -> Statement<'static> {
+    Statement::default()
+}
+
+// Strip the "!" from a condition that can be flipped, returning whether
+// there was one.
+fn negated(test: Expression<'_>) -> (bool, Expression<'_>) {
+    match test {
+        Expression::Negate(n) => (true, NodeBox::into_inner(NodeBox::into_inner(n).value)),
+        test => (false, test),
+    }
+}
+
+// Whether removing a statement would also remove a declaration that is
+// hoisted out of it.
+fn declares_var(node: &Statement<'_>) -> bool {
+    struct Finder(bool);
+    impl Visit<'_> for Finder {
+        fn visit_variable_statement(&mut self, _: &statement::VariableStatement<'_>) {
+            self.0 = true;
+        }
+        fn visit_for_in_var_pattern(&mut self, _: &statement::ForInVarPattern<'_>) {
+            self.0 = true;
+        }
+        fn visit_for_var_pattern(&mut self, _: &statement::ForVarPattern<'_>) {
+            self.0 = true;
+        }
+        // Functions in blocks are also hoisted in sloppy mode.
+        fn visit_function_declaration(&mut self, _: &functions::FunctionDeclaration<'_>) {
+            self.0 = true;
+        }
+
+        fn visit_function_expression(&mut self, _: &functions::FunctionExpression<'_>) {}
+        fn visit_arrow_function_expression(&mut self, _: &functions::ArrowFunctionExpression<'_>) {}
+        fn visit_object_method(&mut self, _: &objects::ObjectMethod<'_>) {}
+        fn visit_class_method(&mut self, _: &classes::ClassMethod<'_>) {}
+    }
+
+    let mut finder = Finder(false);
+    node.accept(&mut finder);
+    finder.0
+}
+
+/// Statement lists hold a mix of statements and declarations.
+trait ListItem<'src>: Sized {
+    fn into_statement(self) -> Result<Statement<'src>, Self>;
+    fn from_statement(stmt: Statement<'src>) -> Self;
+    fn is_hoisted(&self) -> bool;
+}
+
+macro_rules! list_item {
+    ($item:ident) => {
+        impl<'src> ListItem<'src> for alias::$item<'src> {
+            fn into_statement(self) -> Result<Statement<'src>, Self> {
+                list_item!(@match self, $item, [
+                    Block, Variable, Empty, Expression, If, IfElse, For, ForIn, ForOf, ForAwait,
+                    While, DoWhile, Switch, Continue, Break, Return, With, Labelled, Throw,
+                    TryCatch, TryCatchFinally, TryFinally, Debugger,
+                ])
+            }
+            fn from_statement(stmt: Statement<'src>) -> Self {
+                alias::StatementItem::from(stmt).into()
+            }
+            fn is_hoisted(&self) -> bool {
+                matches!(*self, alias::$item::Variable(_) | alias::$item::Function(_))
+            }
+        }
+    };
+    (@match $value:expr, $item:ident, [$($variant:ident,)*]) => {
+        match $value {
+            $(alias::$item::$variant(n) => Ok(Statement::$variant(n)),)*
+            item => Err(item),
+        }
+    };
+}
+list_item!(StatementItem);
+list_item!(ModuleStatementItem);
+
+/// Folds constants and shortens statements. Expressions and statements that
+/// are nested in other nodes are rewritten by `Fold`, and statement lists by
+/// `VisitMut`.
+pub struct Compressor {
+    // False if the code declares its own "undefined".
+    pub undefined: bool,
+}
+impl Compressor {
+    fn constant(&self, expr: &Expression<'_>) -> Option<Constant> {
+        macro_rules! numbers {
+            ($n:expr, |$a:ident, $b:ident| $value:expr) => {{
+                let $a = self.constant(&$n.left)?.number()?;
+                let $b = self.constant(&$n.right)?.number()?;
+                $value
+            }};
+        }
+
+        Some(match *expr {
+            Expression::Parenthesized(ref n) => return self.constant(&n.expr),
+            Expression::Null(_) => Constant::Null,
+            Expression::Boolean(ref n) => Constant::Bool(n.value),
+            Expression::Numeric(ref n) => Constant::Number(n.value),
+            Expression::String(ref n) => Constant::String(n.raw.as_ref().unwrap_or(&n.value).to_string()),
+            Expression::Binding(ref n) if self.undefined && n.value == "undefined" => Constant::Undefined,
+            Expression::Void(ref n) => {
+                self.constant(&n.value)?;
+                Constant::Undefined
+            }
+            Expression::Negate(ref n) => Constant::Bool(!self.constant(&n.value)?.truthy()),
+            Expression::Negative(ref n) => Constant::Number(-self.constant(&n.value)?.number()?),
+            Expression::Positive(ref n) => Constant::Number(self.constant(&n.value)?.number()?),
+            Expression::Add(ref n) => {
+                let left = self.constant(&n.left)?;
+                let right = self.constant(&n.right)?;
+                match (left, right) {
+                    (Constant::Number(a), Constant::Number(b)) => Constant::Number(a + b),
+                    (left @ Constant::String(_), right) | (left, right @ Constant::String(_)) => {
+                        let (left, right) = (left.string()?, right.string()?);
+                        // Joining "\0" and "1" would form a different escape.
+                        let digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+                        if left.contains('\\') && digit(left.chars().last()) && digit(right.chars().next()) {
+                            return None;
+                        }
+                        Constant::String(left + &right)
+                    }
+                    _ => return None,
+                }
+            }
+            Expression::Subtract(ref n) => numbers!(n, |a, b| Constant::Number(a - b)),
+            Expression::Multiply(ref n) => numbers!(n, |a, b| Constant::Number(a * b)),
+            Expression::Divide(ref n) => numbers!(n, |a, b| Constant::Number(a / b)),
+            Expression::Modulus(ref n) => numbers!(n, |a, b| Constant::Number(a % b)),
+            Expression::Power(ref n) => numbers!(n, |a, b| Constant::Number(a.powf(b))),
+            Expression::LessThan(ref n) => numbers!(n, |a, b| Constant::Bool(a < b)),
+            Expression::LessThanEq(ref n) => numbers!(n, |a, b| Constant::Bool(a <= b)),
+            Expression::GreaterThan(ref n) => numbers!(n, |a, b| Constant::Bool(a > b)),
+            Expression::GreaterThanEq(ref n) => numbers!(n, |a, b| Constant::Bool(a >= b)),
+            Expression::StrictCompare(ref n) => {
+                Constant::Bool(self.constant(&n.left)?.strict_equals(&self.constant(&n.right)?)?)
+            }
+            Expression::NegateStrictCompare(ref n) => {
+                Constant::Bool(!self.constant(&n.left)?.strict_equals(&self.constant(&n.right)?)?)
+            }
+            Expression::And(ref n) => {
+                let left = self.constant(&n.left)?;
+                if left.truthy() {
+                    self.constant(&n.right)?
+                } else {
+                    left
+                }
+            }
+            Expression::Or(ref n) => {
+                let left = self.constant(&n.left)?;
+                if left.truthy() {
+                    left
+                } else {
+                    self.constant(&n.right)?
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    fn compress_expression<'src>(&self, expr: Expression<'src>) -> Expression<'src> {
+        let expr = match expr {
+            // The printer adds any parentheses that are needed.
+            Expression::Parenthesized(n) => return self.compress_expression(NodeBox::into_inner(NodeBox::into_inner(n).expr)),
+            Expression::Boolean(n) => return Constant::Bool(n.value).into_expression().unwrap(),
+
+            // Already as short as they can be.
+            Expression::Null(_) | Expression::Numeric(_) | Expression::String(_) => return expr,
+
+            Expression::And(n) => match self.constant(&n.left) {
+                Some(ref c) if c.truthy() => NodeBox::into_inner(NodeBox::into_inner(n).right),
+                Some(_) => NodeBox::into_inner(NodeBox::into_inner(n).left),
+                None => Expression::And(n),
+            },
+            Expression::Or(n) => match self.constant(&n.left) {
+                Some(ref c) if c.truthy() => NodeBox::into_inner(NodeBox::into_inner(n).left),
+                Some(_) => NodeBox::into_inner(NodeBox::into_inner(n).right),
+                None => Expression::Or(n),
+            },
+            Expression::Conditional(n) => {
+                let constant = self.constant(&n.test);
+                let n = NodeBox::into_inner(n);
+                match constant {
+                    Some(ref c) if c.truthy() => NodeBox::into_inner(n.consequent),
+                    Some(_) => NodeBox::into_inner(n.alternate),
+                    None => match negated(NodeBox::into_inner(n.test)) {
+                        (true, test) => conditional(test, NodeBox::into_inner(n.alternate), NodeBox::into_inner(n.consequent)),
+                        (false, test) => conditional(test, NodeBox::into_inner(n.consequent), NodeBox::into_inner(n.alternate)),
+                    },
+                }
+            }
+            expr => expr,
+        };
+
+        // Only replace the expression with its value if that is shorter.
+        if let Some(folded) = self.constant(&expr).and_then(Constant::into_expression) {
+            if let (Some(a), Some(b)) = (printed_len(&folded), printed_len(&expr)) {
+                if a <= b {
+                    return folded;
+                }
+            }
+        }
+        expr
+    }
+
+    fn compress_statement<'src>(&self, stmt: Statement<'src>) -> Statement<'src> {
+        match stmt {
+            Statement::Block(mut n) if n.body.len() <= 1 => match n.body.pop().map(ListItem::into_statement) {
+                Some(Ok(stmt)) => stmt,
+                Some(Err(item)) => {
+                    n.body.push(item);
+                    Statement::Block(n)
+                }
+                None => empty(),
+            },
+            Statement::If(n) => match self.constant(&n.test) {
+                Some(ref c) if c.truthy() => NodeBox::into_inner(NodeBox::into_inner(n).consequent),
+                Some(_) if !declares_var(&n.consequent) => empty(),
+                Some(_) => Statement::If(n),
+                None => {
+                    let n = NodeBox::into_inner(n);
+                    match NodeBox::into_inner(n.consequent) {
+                        Statement::Expression(e) => {
+                            let e = NodeBox::into_inner(e);
+                            let expr = match negated(n.test) {
+                                (true, test) => or(test, e.expression),
+                                (false, test) => and(test, e.expression),
+                            };
+                            statement::ExpressionStatement::new(expr).into()
+                        }
+                        Statement::Empty(_) => statement::ExpressionStatement::new(n.test).into(),
+                        consequent => statement::IfStatement {
+                            consequent: NodeBox::new(consequent),
+                            ..n
+                        }.into(),
+                    }
+                }
+            },
+            Statement::IfElse(n) => match self.constant(&n.test) {
+                Some(ref c) if c.truthy() && !declares_var(&n.alternate) => NodeBox::into_inner(NodeBox::into_inner(n).consequent),
+                Some(ref c) if !c.truthy() && !declares_var(&n.consequent) => NodeBox::into_inner(NodeBox::into_inner(n).alternate),
+                Some(_) => Statement::IfElse(n),
+                None => {
+                    let n = NodeBox::into_inner(n);
+                    let (test, consequent, alternate) = match negated(n.test) {
+                        (true, test) => (test, NodeBox::into_inner(n.alternate), NodeBox::into_inner(n.consequent)),
+                        (false, test) => (test, NodeBox::into_inner(n.consequent), NodeBox::into_inner(n.alternate)),
+                    };
+                    match (consequent, alternate) {
+                        (Statement::Expression(a), Statement::Expression(b)) => {
+                            let (a, b) = (NodeBox::into_inner(a), NodeBox::into_inner(b));
+                            statement::ExpressionStatement::new(conditional(test, a.expression, b.expression)).into()
+                        }
+                        (Statement::Return(mut a), Statement::Return(b)) if a.value.is_some() && b.value.is_some() => {
+                            let value = a.value.as_mut().unwrap();
+                            let consequent = mem::replace(&mut *value.expression, number(0.0));
+                            let alternate = NodeBox::into_inner(b).value.unwrap().expression;
+                            *value.expression = conditional(test, consequent, NodeBox::into_inner(alternate));
+                            a.into()
+                        }
+                        (consequent, Statement::Empty(_)) => self.compress_statement(
+                            statement::IfStatement {
+                                token_if: n.token_if,
+                                token_paren_l: n.token_paren_l,
+                                test,
+                                token_paren_r: n.token_paren_r,
+                                consequent: NodeBox::new(consequent),
+                                position: None,
+                                comments: None,
+                            }.into(),
+                        ),
+                        (consequent, alternate) => statement::IfElseStatement {
+                            test,
+                            consequent: NodeBox::new(consequent),
+                            alternate: NodeBox::new(alternate),
+                            ..n
+                        }.into(),
+                    }
+                }
+            },
+            Statement::While(n) => match self.constant(&n.test) {
+                Some(ref c) if !c.truthy() && !declares_var(&n.body) => empty(),
+                _ => Statement::While(n),
+            },
+            stmt => stmt,
+        }
+    }
+
+    fn compress_list<'src, T: ListItem<'src>>(&self, items: Vec<T>) -> Vec<T> {
+        let mut out = vec![];
+        let mut pending: Option<Expression> = None;
+        let mut unreachable = false;
+
+        for item in items {
+            let stmt = match item.into_statement() {
+                Ok(stmt) => self.compress_statement(stmt),
+                Err(item) => {
+                    if let Some(expr) = pending.take() {
+                        out.push(T::from_statement(statement::ExpressionStatement::new(expr).into()));
+                    }
+                    out.push(item);
+                    continue;
+                }
+            };
+
+            // Code after a jump can only matter for what it declares.
+            if unreachable {
+                let item = T::from_statement(stmt);
+                if item.is_hoisted() {
+                    out.push(item);
+                }
+                continue;
+            }
+
+            match stmt {
+                Statement::Empty(_) => {}
+                Statement::Block(n) if n.body.iter().all(item_is_statement) => {
+                    let items = self.compress_list(NodeBox::into_inner(n).body);
+                    for item in items {
+                        match item.into_statement() {
+                            Ok(Statement::Expression(e)) => pending = Some(sequence(pending.take(), NodeBox::into_inner(e).expression)),
+                            Ok(stmt) => {
+                                if let Some(expr) = pending.take() {
+                                    out.push(T::from_statement(statement::ExpressionStatement::new(expr).into()));
+                                }
+                                out.push(T::from_statement(stmt));
+                            }
+                            Err(_) => unreachable!(),
+                        }
+                    }
+                }
+                Statement::Expression(e) => pending = Some(sequence(pending.take(), NodeBox::into_inner(e).expression)),
+                Statement::Return(mut n) => {
+                    if let Some(expr) = pending.take() {
+                        match n.value {
+                            Some(ref mut value) => {
+                                let right = mem::replace(&mut *value.expression, number(0.0));
+                                *value.expression = sequence(Some(expr), right);
+                            }
+                            None => out.push(T::from_statement(statement::ExpressionStatement::new(expr).into())),
+                        }
+                    }
+                    out.push(T::from_statement(n.into()));
+                    unreachable = true;
+                }
+                Statement::Throw(mut n) => {
+                    if let Some(expr) = pending.take() {
+                        let right = mem::replace(&mut *n.argument, number(0.0));
+                        *n.argument = sequence(Some(expr), right);
+                    }
+                    out.push(T::from_statement(n.into()));
+                    unreachable = true;
+                }
+                Statement::If(mut n) => {
+                    if let Some(expr) = pending.take() {
+                        let right = mem::replace(&mut n.test, number(0.0));
+                        n.test = sequence(Some(expr), right);
+                    }
+                    out.push(T::from_statement(n.into()));
+                }
+                Statement::IfElse(mut n) => {
+                    if let Some(expr) = pending.take() {
+                        let right = mem::replace(&mut n.test, number(0.0));
+                        n.test = sequence(Some(expr), right);
+                    }
+                    out.push(T::from_statement(n.into()));
+                }
+                stmt => {
+                    if let Some(expr) = pending.take() {
+                        out.push(T::from_statement(statement::ExpressionStatement::new(expr).into()));
+                    }
+                    unreachable = matches!(stmt, Statement::Break(_) | Statement::Continue(_));
+                    out.push(T::from_statement(stmt));
+                }
+            }
+        }
+        if let Some(expr) = pending {
+            out.push(T::from_statement(statement::ExpressionStatement::new(expr).into()));
+        }
+
+        out
+    }
+}
+
+fn item_is_statement(item: &alias::StatementItem) -> bool {
+    !matches!(
+        *item,
+        alias::StatementItem::Function(_)
+            | alias::StatementItem::Class(_)
+            | alias::StatementItem::Let(_)
+            | alias::StatementItem::Const(_)
+    )
+}
+
+impl<'src> Fold<'src> for Compressor {
+    fn fold_expression(&mut self, node: Expression<'src>) -> Expression<'src> {
+        let node = node.fold_children(self);
+        self.compress_expression(node)
+    }
+
+    fn fold_statement(&mut self, node: Statement<'src>) -> Statement<'src> {
+        let node = node.fold_children(self);
+        self.compress_statement(node)
+    }
+}
+
+impl VisitMut<'_> for Compressor {
+    fn visit_script(&mut self, node: &mut root::Script<'_>) {
+        node.walk_mut(self);
+        node.body = self.compress_list(mem::take(&mut node.body));
+    }
+    fn visit_module(&mut self, node: &mut root::Module<'_>) {
+        node.walk_mut(self);
+        node.body = self.compress_list(mem::take(&mut node.body));
+    }
+    fn visit_block_statement(&mut self, node: &mut statement::BlockStatement<'_>) {
+        node.walk_mut(self);
+        node.body = self.compress_list(mem::take(&mut node.body));
+    }
+    fn visit_function_body(&mut self, node: &mut functions::FunctionBody<'_>) {
+        node.walk_mut(self);
+        node.body = self.compress_list(mem::take(&mut node.body));
+    }
+    fn visit_switch_case(&mut self, node: &mut statement::SwitchCase<'_>) {
+        node.walk_mut(self);
+        node.consequent = self.compress_list(mem::take(&mut node.consequent));
+    }
+    fn visit_switch_default(&mut self, node: &mut statement::SwitchDefault<'_>) {
+        node.walk_mut(self);
+        node.consequent = self.compress_list(mem::take(&mut node.consequent));
+    }
+}
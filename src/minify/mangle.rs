@@ -0,0 +1,362 @@
+//! Renames local bindings to the shortest names that are free in their scope.
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use analysis::scope::{self, BindingId, ScopeKind, ScopeTree};
+use ast::alias::Expression;
+use ast::general::{BindingIdentifier, PropertyIdentifier, PropertyName, ReferenceIdentifier};
+use ast::patterns::{self, BindingPattern, LeftHandComplexAssign};
+use ast::visit::{Visit, VisitMut, Walk};
+use ast::{jsx, objects, root, Str, NodeBox};
+
+// Names that can never be used for a binding, along with those that are
+// allowed but would be confusing or break sloppy-mode code.
+const RESERVED: &[&str] = &[
+    "arguments", "await", "break", "case", "catch", "class", "const", "continue", "debugger",
+    "default", "delete", "do", "else", "enum", "eval", "export", "extends", "false", "finally",
+    "for", "function", "if", "implements", "import", "in", "instanceof", "interface", "let", "new",
+    "null", "package", "private", "protected", "public", "return", "static", "super", "switch",
+    "this", "throw", "true", "try", "typeof", "var", "void", "while", "with", "yield",
+];
+
+/// Rename the local bindings of a script. Top-level declarations are globals
+/// that other scripts may use, so they keep their names.
+pub fn mangle_script(script: &mut root::Script) {
+    Shorthands { expand: true }.visit_script(script);
+    {
+        let tree = scope::analyze_script(script);
+        let names = assign_names(&tree, &jsx_names(script));
+        Renamer::new(&tree, names).visit_script(script);
+    }
+    Shorthands { expand: false }.visit_script(script);
+}
+
+/// Rename the local bindings of a module. Top-level declarations keep their
+/// names so that exports are unaffected.
+pub fn mangle_module(module: &mut root::Module) {
+    Shorthands { expand: true }.visit_module(module);
+    {
+        let tree = scope::analyze_module(module);
+        let names = assign_names(&tree, &jsx_names(module));
+        Renamer::new(&tree, names).visit_module(module);
+    }
+    Shorthands { expand: false }.visit_module(module);
+}
+
+// JSX tag and attribute names are not tracked as references, so any binding
+// that shares a name with one is left alone.
+fn jsx_names<'src, T: Walk<'src>>(node: &T) -> HashSet<String> {
+    struct Names(HashSet<String>);
+    impl Visit<'_> for Names {
+        fn visit_jsx_identifier(&mut self, node: &jsx::Identifier<'_>) {
+            self.0.insert(node.value.to_string());
+        }
+    }
+
+    let mut names = Names(HashSet::new());
+    node.walk(&mut names);
+    names.0
+}
+
+// The nth generated name: "a" through "_", then two characters and so on.
+fn generate(mut n: usize) -> String {
+    const FIRST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ$_";
+    const REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ$_0123456789";
+
+    let mut name = String::new();
+    name.push(FIRST[n % FIRST.len()] as char);
+    n /= FIRST.len();
+    while n > 0 {
+        n -= 1;
+        name.push(REST[n % REST.len()] as char);
+        n /= REST.len();
+    }
+    name
+}
+
+fn assign_names(tree: &ScopeTree, jsx: &HashSet<String>) -> HashMap<BindingId, String> {
+    // Scopes whose bindings may be looked up by name at runtime, through a
+    // "with" statement or a direct call to "eval" inside of them.
+    let mut dynamic = HashSet::new();
+    for (id, scope) in tree.scopes() {
+        if scope.kind == ScopeKind::With {
+            dynamic.extend(tree.ancestors(id));
+        }
+    }
+    for (_, reference) in tree.references() {
+        if reference.binding.is_none() && reference.name == "eval" {
+            dynamic.extend(tree.ancestors(reference.scope));
+        }
+    }
+
+    let fixed = |id: BindingId| {
+        let binding = tree.binding(id);
+        let kind = tree.scope(binding.scope).kind;
+        kind == ScopeKind::Global
+            || kind == ScopeKind::Module
+            || dynamic.contains(&binding.scope)
+            || jsx.contains(binding.name.as_str())
+            // A declared "arguments" may still be the arguments object, as
+            // "var arguments;" doesn't replace it.
+            || binding.name == "arguments"
+            // Bindings tied to another binding of the same name, like
+            // functions in blocks that Annex B also binds in the enclosing
+            // function, must keep the same name in both scopes.
+            || binding.linked
+    };
+
+    let mut reserved: HashSet<&str> = RESERVED.iter().cloned().collect();
+    reserved.extend(tree.free_variables());
+    reserved.extend(jsx.iter().map(String::as_str));
+    reserved.extend(tree.bindings().filter(|&(id, _)| fixed(id)).map(|(_, b)| b.name.as_str()));
+
+    let mut names = HashMap::new();
+    for (id, scope) in tree.scopes() {
+        // Names given to outer bindings must not be shadowed, since they may
+        // be referenced from inside of this scope.
+        let taken: HashSet<&String> = tree.ancestors(id)
+            .skip(1)
+            .flat_map(|ancestor| tree.scope(ancestor).bindings.iter())
+            .filter_map(|binding| names.get(binding))
+            .collect();
+
+        // The most referenced bindings get the shortest names.
+        let mut bindings: Vec<BindingId> = scope.bindings.iter().cloned().filter(|&b| !fixed(b)).collect();
+        bindings.sort_by_key(|&b| ::std::cmp::Reverse(tree.binding(b).references.len()));
+
+        let mut n = 0;
+        let mut assigned = vec![];
+        for binding in bindings {
+            let name = loop {
+                let name = generate(n);
+                n += 1;
+                if !reserved.contains(name.as_str()) && !taken.contains(&name) {
+                    break name;
+                }
+            };
+            assigned.push((binding, name));
+        }
+        names.extend(assigned);
+    }
+    names
+}
+
+// Applies new names, matching identifiers to bindings by the order in which
+// the scope analysis visited them.
+struct Renamer<'a> {
+    declarations: &'a [Option<BindingId>],
+    references: Vec<Option<BindingId>>,
+    names: HashMap<BindingId, String>,
+    declaration: usize,
+    reference: usize,
+}
+impl<'a> Renamer<'a> {
+    fn new(tree: &'a ScopeTree, names: HashMap<BindingId, String>) -> Renamer<'a> {
+        Renamer {
+            declarations: tree.declarations(),
+            references: tree.references().map(|(_, reference)| reference.binding).collect(),
+            names,
+            declaration: 0,
+            reference: 0,
+        }
+    }
+
+    fn name(&self, binding: Option<BindingId>) -> Option<String> {
+        binding.and_then(|binding| self.names.get(&binding)).cloned()
+    }
+}
+impl<'a> VisitMut<'_> for Renamer<'a> {
+    fn visit_binding_identifier(&mut self, node: &mut BindingIdentifier<'_>) {
+        let binding = self.declarations[self.declaration];
+        self.declaration += 1;
+        if let Some(name) = self.name(binding) {
+            node.value = name.into();
+            node.raw = None;
+        }
+    }
+
+    fn visit_reference_identifier(&mut self, node: &mut ReferenceIdentifier<'_>) {
+        let binding = self.references[self.reference];
+        self.reference += 1;
+        if let Some(name) = self.name(binding) {
+            node.value = name.into();
+            node.raw = None;
+        }
+    }
+}
+
+// Shorthand properties name both the property and the binding, so they are
+// expanded before renaming and collapsed again afterwards where the names
+// still match.
+struct Shorthands {
+    expand: bool,
+}
+
+fn property_name<'src>(value: &str, raw: &Option<Str<'src>>) -> PropertyName<'src> {
+    PropertyIdentifier {
+        raw: raw.clone(),
+        ..PropertyIdentifier::new(value)
+    }.into()
+}
+
+fn same_name(name: &PropertyName, value: &str) -> bool {
+    match *name {
+        PropertyName::Identifier(ref id) => id.value == value,
+        _ => false,
+    }
+}
+
+fn object_item(item: objects::ObjectItem, expand: bool) -> objects::ObjectItem {
+    match item {
+        // "__proto__: value" sets the prototype, while the shorthand does not.
+        objects::ObjectItem::Shorthand(n) if expand && n.id.value != "__proto__" => {
+            let n = NodeBox::into_inner(n);
+            objects::ObjectProperty {
+                name: property_name(&n.id.value, &n.id.raw),
+                value: NodeBox::new(n.id.into()),
+                position: n.position,
+                comments: n.comments,
+            }.into()
+        }
+        objects::ObjectItem::Property(n) if !expand => {
+            let n = NodeBox::into_inner(n);
+            match NodeBox::into_inner(n.value) {
+                Expression::Binding(id) if same_name(&n.name, &id.value) && id.value != "__proto__" => {
+                    objects::ObjectShorthandProperty {
+                        id: NodeBox::into_inner(id),
+                        position: n.position,
+                        comments: n.comments,
+                    }.into()
+                }
+                value => objects::ObjectProperty {
+                    value: NodeBox::new(value),
+                    ..n
+                }.into(),
+            }
+        }
+        item => item,
+    }
+}
+
+fn binding_property(
+    n: patterns::ObjectBindingPatternIdentifierProperty,
+) -> patterns::ObjectBindingPatternPatternProperty {
+    patterns::ObjectBindingPatternPatternProperty {
+        name: property_name(&n.id.value, &n.id.raw),
+        pattern: NodeBox::new(BindingPattern::Identifier(n.id)),
+        init: n.init,
+        position: n.position,
+        comments: n.comments,
+    }
+}
+
+fn binding_shorthand(
+    n: patterns::ObjectBindingPatternPatternProperty,
+) -> Result<patterns::ObjectBindingPatternIdentifierProperty, NodeBox<patterns::ObjectBindingPatternPatternProperty>> {
+    match NodeBox::into_inner(n.pattern) {
+        BindingPattern::Identifier(id) if same_name(&n.name, &id.value) => {
+            Ok(patterns::ObjectBindingPatternIdentifierProperty {
+                id,
+                init: n.init,
+                position: n.position,
+                comments: n.comments,
+            })
+        }
+        pattern => Err(NodeBox::new(patterns::ObjectBindingPatternPatternProperty {
+            pattern: NodeBox::new(pattern),
+            ..n
+        })),
+    }
+}
+
+fn assignment_property(
+    n: patterns::ObjectAssignmentPatternIdentifierProperty,
+) -> patterns::ObjectAssignmentPatternPatternProperty {
+    patterns::ObjectAssignmentPatternPatternProperty {
+        name: property_name(&n.id.value, &n.id.raw),
+        pattern: NodeBox::new(LeftHandComplexAssign::Identifier(n.id)),
+        init: n.init,
+        position: n.position,
+        comments: n.comments,
+    }
+}
+
+fn assignment_shorthand(
+    n: patterns::ObjectAssignmentPatternPatternProperty,
+) -> Result<patterns::ObjectAssignmentPatternIdentifierProperty, NodeBox<patterns::ObjectAssignmentPatternPatternProperty>> {
+    match NodeBox::into_inner(n.pattern) {
+        LeftHandComplexAssign::Identifier(id) if same_name(&n.name, &id.value) => {
+            Ok(patterns::ObjectAssignmentPatternIdentifierProperty {
+                id,
+                init: n.init,
+                position: n.position,
+                comments: n.comments,
+            })
+        }
+        pattern => Err(NodeBox::new(patterns::ObjectAssignmentPatternPatternProperty {
+            pattern: NodeBox::new(pattern),
+            ..n
+        })),
+    }
+}
+
+// Convert between the identifier and pattern variants of a property enum.
+macro_rules! shorthand {
+    ($expand:expr, $item:expr, $kind:ident, $to_pattern:ident, $to_shorthand:ident) => {{
+        use ast::patterns::$kind as Kind;
+        match $item {
+            Kind::Identifier(n) if $expand => Kind::Pattern($to_pattern(n)),
+            Kind::Pattern(n) if !$expand => match $to_shorthand(n) {
+                Ok(n) => Kind::Identifier(n),
+                Err(n) => Kind::Pattern(NodeBox::into_inner(n)),
+            },
+            item => item,
+        }
+    }};
+}
+
+impl VisitMut<'_> for Shorthands {
+    fn visit_object_expression(&mut self, node: &mut objects::ObjectExpression<'_>) {
+        node.walk_mut(self);
+
+        let expand = self.expand;
+        node.properties = mem::take(&mut node.properties)
+            .into_iter()
+            .map(|(item, comma)| (object_item(item, expand), comma))
+            .collect();
+        node.last_property = node.last_property.take().map(|item| object_item(item, expand));
+    }
+
+    fn visit_object_binding_pattern(&mut self, node: &mut patterns::ObjectBindingPattern<'_>) {
+        node.walk_mut(self);
+
+        let expand = self.expand;
+        node.properties = mem::take(&mut node.properties)
+            .into_iter()
+            .map(|(item, comma)| {
+                let item = shorthand!(expand, item, ObjectBindingPatternProperty, binding_property, binding_shorthand);
+                (item, comma)
+            })
+            .collect();
+        node.last_property = node.last_property.take().map(|item| {
+            shorthand!(expand, item, ObjectBindingPatternLastProperty, binding_property, binding_shorthand)
+        });
+    }
+
+    fn visit_object_assignment_pattern(&mut self, node: &mut patterns::ObjectAssignmentPattern<'_>) {
+        node.walk_mut(self);
+
+        let expand = self.expand;
+        node.properties = mem::take(&mut node.properties)
+            .into_iter()
+            .map(|(item, comma)| {
+                let item = shorthand!(expand, item, ObjectAssignmentPatternProperty, assignment_property, assignment_shorthand);
+                (item, comma)
+            })
+            .collect();
+        node.last_property = node.last_property.take().map(|item| {
+            shorthand!(expand, item, ObjectAssignmentPatternLastProperty, assignment_property, assignment_shorthand)
+        });
+    }
+}
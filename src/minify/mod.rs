@@ -0,0 +1,288 @@
+//! Minification of scripts and modules.
+//!
+//! The formatter already prints code without whitespace, so minifying is a
+//! matter of rewriting the AST into a shorter equivalent: `compress` folds
+//! constants and merges statements, and `mangle` gives local bindings short
+//! names.
+
+mod compress;
+mod mangle;
+
+use failure::Error;
+
+use analysis::scope;
+use ast::fold::Foldable;
+use ast::visit::VisitMut;
+use ast::{self, root, FormatOptions, Semicolons, TrailingCommas};
+use parser::{parse, ParserOptions};
+
+pub use self::mangle::{mangle_module, mangle_script};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinifyOptions {
+    pub parser: ParserOptions,
+
+    // Fold constants, remove dead code and merge statements.
+    pub compress: bool,
+
+    // Rename local bindings.
+    pub mangle: bool,
+}
+impl Default for MinifyOptions {
+    fn default() -> MinifyOptions {
+        MinifyOptions {
+            parser: ParserOptions::default(),
+            compress: true,
+            mangle: true,
+        }
+    }
+}
+
+/// Minify the given code and print the result.
+pub fn minify(code: &str, options: &MinifyOptions) -> Result<String, Error> {
+    let format = FormatOptions {
+        semicolons: Semicolons::AsNeeded,
+        trailing_commas: TrailingCommas::Never,
+        strip_types: true,
+        shortest_literals: true,
+        ..FormatOptions::default()
+    };
+
+    let output = match parse(code, &options.parser)? {
+        root::Ast::Script(script) => ast::format(&minify_script(script, options), &format),
+        root::Ast::Module(module) => ast::format(&minify_module(module, options), &format),
+    };
+    output.map_err(|e| format_err!("Failed to print minified code: {:?}", e))
+}
+
+/// Minify a parsed script.
+pub fn minify_script<'src>(mut script: root::Script<'src>, options: &MinifyOptions) -> root::Script<'src> {
+    if options.compress {
+        let undefined = !declares_undefined(&scope::analyze_script(&script));
+        let mut compressor = compress::Compressor { undefined };
+        for _ in 0..2 {
+            script = script.fold(&mut compressor);
+            compressor.visit_script(&mut script);
+        }
+    }
+    if options.mangle {
+        mangle_script(&mut script);
+    }
+    script
+}
+
+/// Minify a parsed module.
+pub fn minify_module<'src>(mut module: root::Module<'src>, options: &MinifyOptions) -> root::Module<'src> {
+    if options.compress {
+        let undefined = !declares_undefined(&scope::analyze_module(&module));
+        let mut compressor = compress::Compressor { undefined };
+        for _ in 0..2 {
+            module = module.fold(&mut compressor);
+            compressor.visit_module(&mut module);
+        }
+    }
+    if options.mangle {
+        mangle_module(&mut module);
+    }
+    module
+}
+
+fn declares_undefined(tree: &scope::ScopeTree) -> bool {
+    tree.bindings().any(|(_, binding)| binding.name == "undefined")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn min(code: &str) -> String {
+        minify(code, &MinifyOptions::default()).unwrap()
+    }
+
+    fn compress(code: &str) -> String {
+        let options = MinifyOptions {
+            mangle: false,
+            ..MinifyOptions::default()
+        };
+        minify(code, &options).unwrap()
+    }
+
+    #[test]
+    fn it_folds_constants() {
+        assert_eq!(compress("a = 1 + 2 * 3;"), "a=7");
+        assert_eq!(compress("a = 'x' + 1 + 'y';"), "a='x1y'");
+        assert_eq!(compress("a = '\\0' + 1;"), "a='\\0'+1");
+        assert_eq!(compress("a = 1 / 3;"), "a=1/3");
+        assert_eq!(compress("a = -(2 - 5);"), "a=3");
+        assert_eq!(compress("a = 1 < 2;"), "a=!0");
+        assert_eq!(compress("a = true; b = false; c = undefined;"), "a=!0,b=!1,c=void 0");
+    }
+
+    #[test]
+    fn it_removes_dead_branches() {
+        assert_eq!(compress("if (1) a(); else b();"), "a()");
+        assert_eq!(compress("if (0) { a(); } c();"), "c()");
+        assert_eq!(compress("if (0) { var a = 1; }"), "if(0)var a=1");
+        assert_eq!(compress("while (false) a();"), "");
+        assert_eq!(compress("x = 0 ? a : b; y = 1 && c;"), "x=b,y=c");
+        assert_eq!(compress("function f() { return 1; a(); var b; }"), "function f(){return 1;var b}");
+    }
+
+    #[test]
+    fn it_shortens_conditionals() {
+        assert_eq!(compress("if (a) b();"), "a&&b()");
+        assert_eq!(compress("if (!a) b();"), "a||b()");
+        assert_eq!(compress("if (a) b(); else c();"), "a?b():c()");
+        assert_eq!(compress("if (!a) b(); else c();"), "a?c():b()");
+        assert_eq!(compress("function f() { if (a) return b; else return c; }"), "function f(){return a?b:c}");
+        assert_eq!(compress("if (a) { b(); } else {}"), "a&&b()");
+    }
+
+    #[test]
+    fn it_merges_statements() {
+        assert_eq!(compress("a(); b(); c();"), "a(),b(),c()");
+        assert_eq!(compress("function f() { a(); return b; }"), "function f(){return a(),b}");
+        assert_eq!(compress("a(); if (b) { c(); d(); }"), "a(),b&&(c(),d())");
+        assert_eq!(compress("{ a(); } { let b; }"), "a();{let b}");
+    }
+
+    #[test]
+    fn it_keeps_shadowed_undefined() {
+        assert_eq!(compress("var undefined = 1; a = undefined;"), "var undefined=1;a=undefined");
+    }
+
+    #[test]
+    fn it_mangles_locals() {
+        assert_eq!(
+            min("function outer(first, second) { var total = first + second; return total * first; }"),
+            "function outer(a,b){var c=a+b;return c*a}"
+        );
+        assert_eq!(min("var top = 1; (function (inner) { return inner + top; })(2);"), "var top=1;(function(a){return a+top})(2)");
+    }
+
+    #[test]
+    fn it_avoids_capturing_names() {
+        assert_eq!(
+            min("function f(x) { return function (y) { return a + x + y; }; }"),
+            "function f(b){return function(c){return a+b+c}}"
+        );
+    }
+
+    #[test]
+    fn it_keeps_shorthand_properties() {
+        assert_eq!(
+            min("function f(value, { key }) { return { value, key }; }"),
+            "function f(a,{key:b}){return{value:a,key:b}}"
+        );
+        assert_eq!(min("function f({ a }) { return { a }; }"), "function f({a}){return{a}}");
+    }
+
+    #[test]
+    fn it_skips_dynamic_scopes() {
+        assert_eq!(min("function f(value) { eval('value'); }"), "function f(value){eval('value')}");
+        assert_eq!(min("function f(value) { with (o) value; }"), "function f(value){with(o)value}");
+        assert_eq!(
+            min("function f(value) { return () => { if (value) eval('value'); }; }"),
+            "function f(value){return()=>{value&&eval('value')}}"
+        );
+        assert_eq!(
+            min("function f(value) { if (value) { with (o) { g(); } } }"),
+            "function f(value){if(value)with(o)g()}"
+        );
+    }
+
+    #[test]
+    fn it_keeps_arguments() {
+        assert_eq!(min("function f(x) { var arguments; return arguments[x]; }"), "function f(a){var arguments;return arguments[a]}");
+        assert_eq!(min("function f(arguments) { return arguments; }"), "function f(arguments){return arguments}");
+    }
+
+    // The estree of code parsed without parentheses, without positions and
+    // raw text, so that code can be compared to its minified version.
+    fn shape(code: &str) -> String {
+        let options = ParserOptions {
+            preserve_parens: false,
+            ..ParserOptions::default()
+        };
+        let ast = parse(code, &options).unwrap();
+        let mut value = ::estree::to_value(&ast, code);
+        strip(&mut value);
+        value.to_string()
+    }
+    fn strip(value: &mut ::estree::Value) {
+        match *value {
+            ::estree::Value::Object(ref mut fields) => {
+                fields.retain(|(key, _)| !["start", "end", "loc", "range", "raw"].contains(&key.as_str()));
+                for (_, value) in fields.iter_mut() {
+                    strip(value);
+                }
+            }
+            ::estree::Value::Array(ref mut items) => items.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn it_keeps_needed_parentheses() {
+        let cases = [
+            ("((a, b) => a - b)(5, 3);", "((a,b)=>a-b)(5,3)"),
+            ("(a = b)(c);", "(a=b)(c)"),
+            ("new (a => a);", "new(a=>a)"),
+            ("x = (() => 1) || 2;", "x=(()=>1)||2"),
+            ("x = (y => y) ? 1 : 2;", "x=(y=>y)?1:2"),
+            ("(a => a).call();", "(a=>a).call()"),
+            ("(a += 1).b;", "(a+=1).b"),
+            ("function* g() { (yield a) + 1; }", "function*g(){(yield a)+1}"),
+            ("for (var x = (a in b);;) c();", "for(var x=(a in b);;)c()"),
+            ("for (x = (a in b);;) c();", "for(x=(a in b);;)c()"),
+            ("for (var x = () => (a in b);;) c();", "for(var x=()=>(a in b);;)c()"),
+            ("for (var x = (a in b) in c) d();", "for(var x=(a in b)in c)d()"),
+            ("for (var x = function () { return a in b; };;) c();", "for(var x=function(){return a in b};;)c()"),
+            ("x = 'key' in d;", "x='key'in d"),
+            ("new (a().b)();", "new(a().b)()"),
+        ];
+        for &(code, expected) in &cases {
+            let minified = compress(code);
+            assert_eq!(minified, expected);
+            assert_eq!(shape(&minified), shape(code), "{:?} changed meaning", code);
+        }
+    }
+
+    #[test]
+    fn it_keeps_parameter_defaults_apart_from_body_vars() {
+        assert_eq!(
+            min("function f(p = x) { var x = 1; return [p, x]; }"),
+            "function f(a=x){var b=1;return[a,b]}"
+        );
+        assert_eq!(
+            min("function f(p = () => y) { var y = 2; return p(); }"),
+            "function f(a=()=>y){var b=2;return a()}"
+        );
+        assert_eq!(min("function f(p = 1) { var p; return p; }"), "function f(p=1){var p;return p}");
+    }
+
+    #[test]
+    fn it_shortens_literals() {
+        assert_eq!(compress("a = [0.5, 1000000, \"it's\"];"), "a=[.5,1e6,\"it's\"]");
+    }
+
+    #[test]
+    fn it_keeps_catch_params_redeclared_with_var() {
+        assert_eq!(
+            min("function f(x) { try { throw x; } catch (e) { var e = 2; return e; } }"),
+            "function f(a){try{throw a}catch(e){var e=2;return e}}"
+        );
+    }
+
+    #[test]
+    fn it_keeps_hoisted_block_functions() {
+        assert_eq!(
+            min("function f(x) { if (x) { function g() {} } return g; }"),
+            "function f(a){if(a){function g(){}}return g}"
+        );
+        assert_eq!(
+            min("function f(x) { 'use strict'; { function g() {} g(x); } }"),
+            "function f(a){'use strict';{function b(){}b(a)}}"
+        );
+    }
+}
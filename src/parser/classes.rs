@@ -1,82 +1,403 @@
-use tokenizer::{Tokenizer, tokens};
-use parser::{Parser, Flag};
-use parser::utils::{OptResult, TokenResult};
+use ast::KeywordData;
+use ast::classes;
+use ast::functions;
+use ast::general::PropertyName;
+use ast::objects;
+use ast::typescript;
+use ast::NodeBox;
+use tokenizer::{self, Tokenizer, tokens};
+use parser::{EcmaVersion, Parser, Flag, LookaheadResult};
+use parser::utils::{OptResult, Result, TokenResult};
 
 impl<'code, T> Parser<'code, T>
 where
     T: Tokenizer<'code>
 {
-    pub fn parse_class_declaration(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("class"));
+    pub fn parse_class_declaration(&mut self) -> OptResult<classes::ClassDeclaration<'code>> {
+        let start = self.start();
+        let decorators = self.parse_class_decorators()?;
+        if decorators.is_empty() {
+            try_value!(self.keyword("class"));
+        } else {
+            eat_value!(self.keyword("class"));
+        }
+        let token_class = self.token_data();
+
+        let id = eat_value!(self.parse_class_name());
+        let type_parameters = opt_value!(self.parse_type_parameters()?);
+        let heritage = opt_value!(self.parse_class_heritage()?);
+        let implements = self.parse_class_implements()?;
+        let body = eat_value!(self.parse_class_body()?);
+
+        Ok(TokenResult::Some(self.located(start, classes::ClassDeclaration {
+            decorators,
+            modifiers: Default::default(),
+            token_class,
+            id,
+            type_parameters,
+            heritage,
+            implements,
+            body,
+            position: None,
+            comments: None,
+        })))
+    }
 
-        let _id = if self.flags.allow_default {
-            opt_value!(self.binding_identifier())
+    pub fn parse_export_default_class_declaration(&mut self, start: tokenizer::Position) -> OptResult<classes::ExportDefaultClassDeclaration<'code>> {
+        let decorators = self.parse_class_decorators()?;
+        if decorators.is_empty() {
+            try_value!(self.keyword("class"));
         } else {
-            Some(eat_value!(self.binding_identifier()))
-        };
+            eat_value!(self.keyword("class"));
+        }
+        let token_class = self.token_data();
+
+        let id = opt_value!(self.parse_class_name());
+        let type_parameters = opt_value!(self.parse_type_parameters()?);
+        let heritage = opt_value!(self.parse_class_heritage()?);
+        let implements = self.parse_class_implements()?;
+        let body = eat_value!(self.parse_class_body()?);
+
+        Ok(TokenResult::Some(self.located(start, classes::ExportDefaultClassDeclaration {
+            decorators,
+            token_export: Default::default(),
+            token_default: Default::default(),
+            modifiers: Default::default(),
+            token_class,
+            id,
+            type_parameters,
+            heritage,
+            implements,
+            body,
+            position: None,
+            comments: None,
+        })))
+    }
 
-        let _parent = opt_value!(self.parse_class_heritage()?);
+    #[inline(never)]
+    pub fn parse_class_expression(&mut self) -> OptResult<classes::ClassExpression<'code>> {
+        let start = self.start();
+        let decorators = self.parse_class_decorators()?;
+        if decorators.is_empty() {
+            try_value!(self.keyword("class"));
+        } else {
+            eat_value!(self.keyword("class"));
+        }
+        let token_class = self.token_data();
+
+        let id = opt_value!(self.parse_class_name());
+        let type_parameters = opt_value!(self.parse_type_parameters()?);
+        let heritage = opt_value!(self.parse_class_heritage()?);
+        let implements = self.parse_class_implements()?;
+        let body = eat_value!(self.parse_class_body()?);
+
+        Ok(TokenResult::Some(self.located(start, classes::ClassExpression {
+            decorators,
+            token_class,
+            id,
+            type_parameters,
+            heritage,
+            implements,
+            body,
+            position: None,
+            comments: None,
+        })))
+    }
 
-        eat_value!(self.parse_class_body()?);
+    fn parse_class_name(&mut self) -> TokenResult<classes::ClassName<'code>> {
+        let start = self.start();
+        let id = try_token!(self.parse_binding_identifier());
 
-        Ok(TokenResult::Some(()))
+        TokenResult::Some(self.located(start, classes::ClassName {
+            tokens_prefix: Default::default(),
+            id,
+            position: None,
+            comments: None,
+        }))
     }
-    pub fn parse_class_expression(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("class"));
 
-        Ok(TokenResult::Some(()))
+    fn parse_class_heritage(&mut self) -> OptResult<classes::ClassHeritage<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("extends"));
+        let token_extends = self.token_data();
+
+        // All parts of a class are strict mode code.
+        let mut parser = self.with(Flag::Strict);
+        parser.expect_expression();
+        let expr = eat_value!(parser.parse_left_hand_side_expression()?);
+        let type_arguments = opt_value!(parser.parse_type_arguments()?);
+
+        Ok(TokenResult::Some(parser.located(start, classes::ClassHeritage {
+            token_extends,
+            tokens_extends_post: Default::default(),
+            expression: NodeBox::new(expr),
+            type_arguments,
+            position: None,
+            comments: None,
+        })))
     }
 
-    fn parse_class_heritage(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("extends"));
+    // implements A, B<T>
+    fn parse_class_implements(&mut self) -> Result<Vec<typescript::TypeReference<'code>>> {
+        let mut implements = vec![];
+        if !self.has_types() {
+            return Ok(implements);
+        }
 
-        self.expect_expression();
-        eat_value!(self.parse_left_hand_side_expression()?);
+        if let TokenResult::Some(_) = self.keyword("implements") {
+            loop {
+                implements.push(eat_value!(self.parse_type_reference()?));
+                if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
+                    break;
+                }
+            }
+        }
+        Ok(implements)
+    }
 
-        Ok(TokenResult::Some(()))
+    fn parse_class_body(&mut self) -> OptResult<classes::ClassBody<'code>> {
+        self.nested(Self::parse_class_body_nested)
     }
 
-    fn parse_class_body(&mut self) -> OptResult<()> {
+    fn parse_class_body_nested(&mut self) -> OptResult<classes::ClassBody<'code>> {
+        let start = self.start();
         let mut parser = self.without(Flag::Template);
+        let mut parser = parser.with(Flag::Strict);
         eat_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+        let token_curly_l = parser.token_data();
 
-        while let TokenResult::Some(_) = parser.parse_class_item()? {
+        let mut items = vec![];
+        while let TokenResult::Some(item) = parser.parse_class_item()? {
+            items.push(item);
         }
 
         eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
-
-        Ok(TokenResult::Some(()))
+        let token_curly_r = parser.token_data();
+
+        Ok(TokenResult::Some(parser.located(start, classes::ClassBody {
+            token_curly_l,
+            items,
+            token_curly_r,
+            position: None,
+            comments: None,
+        })))
     }
 
-    fn parse_class_item(&mut self) -> OptResult<()> {
+    fn parse_class_item(&mut self) -> OptResult<classes::ClassItem<'code>> {
+        let start = self.start();
+
         if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Semicolon) {
-            return Ok(TokenResult::Some(()));
+            let token = self.token_data();
+
+            return Ok(TokenResult::Some(self.located(start, classes::ClassEmpty {
+                token,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        let decorators = self.parse_class_item_decorators()?;
+        if self.has_types() {
+            return self.parse_typescript_class_item(start, decorators);
         }
 
-        let head = try_value!(self.parse_method_head(true)?);
+        let head = if decorators.is_empty() {
+            try_value!(self.parse_method_head(true)?)
+        } else {
+            eat_value!(self.parse_method_head(true)?)
+        };
+        let (params, body) = eat_value!(self.parse_method_tail(&head)?);
 
-        eat_value!(self.parse_method_tail(head)?);
+        let kind = head.method_kind();
+        let MethodHead { name, stat, .. } = head;
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, classes::ClassMethod {
+            decorators,
+            modifiers: Default::default(),
+            pos: stat,
+            kind,
+            id: classes::ClassFieldId::Public(name),
+            params,
+            body,
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    pub fn parse_method_head(&mut self, allow_static: bool) -> OptResult<MethodHead> {
-        let mut stat = if allow_static {
-            opt_value!(self.keyword("static"))
+    // The class members of TypeScript and Flow, which can also be fields, signatures
+    // without a body and index signatures, e.g. "private readonly a?: A;".
+    fn parse_typescript_class_item(
+        &mut self,
+        start: tokenizer::Position,
+        decorators: Vec<classes::ClassItemDecorator<'code>>,
+    ) -> OptResult<classes::ClassItem<'code>> {
+        let mut modifiers = vec![];
+        let mut stat = None;
+        loop {
+            let modifier_start = self.start();
+            if let Some(kind) = self.modifier_kind() {
+                self.pop();
+                modifiers.push(self.located(modifier_start, typescript::Modifier::from(kind)));
+            } else if stat.is_none() && self.is_modifier("static") {
+                eat_value!(self.keyword("static"));
+                let token_static = self.token_data();
+
+                stat = Some(self.located(modifier_start, classes::StaticPosition {
+                    token_static,
+                    position: None,
+                    comments: None,
+                }));
+            } else {
+                break;
+            }
+        }
+        modifiers.extend(self.parse_variance());
+
+        if let TokenResult::Some(mut signature) = self.parse_index_signature(start, &mut modifiers)? {
+            if stat.is_some() {
+                bail!("Index signatures can't be static");
+            }
+            opt_value!(self.punc(tokens::PunctuatorToken::Semicolon));
+            signature.position = None;
+
+            return Ok(TokenResult::Some(self.located(start, signature).into()));
+        }
+
+        let head = if decorators.is_empty() && modifiers.is_empty() && stat.is_none() {
+            try_value!(self.parse_method_head(false)?)
+        } else {
+            eat_value!(self.parse_method_head(false)?)
+        };
+        let optional = opt_value!(self.punc(tokens::PunctuatorToken::Question)).is_some();
+
+        let is_field = head.is_plain() && !matches!(
+            *self.token(),
+            tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) |
+            tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle)
+        );
+        if is_field {
+            let definite = !optional && self.parse_definite()?;
+            let type_annotation = opt_value!(self.parse_type_annotation()?);
+            let init = opt_value!(self.with(Flag::In).parse_initializer()?);
+            if definite && init.is_some() {
+                bail!("Declarations with initializers cannot also have definite assignment assertions");
+            }
+            let _: KeywordData = eat_value!(self.semicolon_token());
+
+            return Ok(TokenResult::Some(self.located(start, classes::ClassField {
+                decorators,
+                modifiers,
+                pos: stat,
+                id: classes::ClassFieldId::Public(head.name),
+                optional,
+                definite,
+                type_annotation,
+                init,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        let (params, body) = eat_value!(self.parse_method_tail_or_signature(&head, true)?);
+        let kind = head.method_kind();
+        let id = classes::ClassFieldId::Public(head.name);
+
+        let item = match body {
+            Some(body) => {
+                if optional {
+                    bail!("Only method signatures can be optional");
+                }
+
+                self.located(start, classes::ClassMethod {
+                    decorators,
+                    modifiers,
+                    pos: stat,
+                    kind,
+                    id,
+                    params,
+                    body,
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            None => {
+                if !decorators.is_empty() {
+                    bail!("Method signatures can't have decorators");
+                }
+                let token_semi = eat_value!(self.semicolon_token());
+
+                self.located(start, typescript::ClassMethodSignature {
+                    modifiers,
+                    pos: stat,
+                    kind,
+                    id,
+                    optional,
+                    params,
+                    token_semi,
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+        };
+
+        Ok(TokenResult::Some(item))
+    }
+
+    // Words like "get" are only modifiers if they are followed by a method name.
+    fn is_modifier(&mut self, modifier: &str) -> bool {
+        match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) if name == modifier => {}
+            _ => return false,
+        }
+
+        match self.ident_lookahead() {
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(ref punc), .. }) => {
+                !matches!(
+                    *punc,
+                    tokens::PunctuatorToken::ParenOpen |
+                    tokens::PunctuatorToken::LAngle |
+                    tokens::PunctuatorToken::Question |
+                    tokens::PunctuatorToken::Eq |
+                    tokens::PunctuatorToken::Colon |
+                    tokens::PunctuatorToken::Comma |
+                    tokens::PunctuatorToken::CurlyClose |
+                    tokens::PunctuatorToken::Semicolon
+                )
+            }
+            Some(&LookaheadResult { token: tokens::Token::EOF(_), .. }) => false,
+            Some(&LookaheadResult { line, .. }) => modifier != "async" || !line,
+            None => false,
+        }
+    }
+
+    pub fn parse_method_head(&mut self, allow_static: bool) -> OptResult<MethodHead<'code>> {
+        let start = self.start();
+        let stat = if allow_static && self.is_modifier("static") {
+            eat_value!(self.keyword("static"));
+            let token_static = self.token_data();
+
+            Some(self.located(start, classes::StaticPosition {
+                token_static,
+                position: None,
+                comments: None,
+            }))
         } else {
             None
         };
 
-        let mut kind = if let TokenResult::Some(_) = self.keyword("get") {
+        let kind = if self.is_modifier("get") {
+            eat_value!(self.keyword("get"));
             MethodKind::Get
-        } else if let TokenResult::Some(_) = self.keyword("set") {
+        } else if self.is_modifier("set") {
+            eat_value!(self.keyword("set"));
             MethodKind::Set
         } else {
             MethodKind::None
         };
 
         let async = if let MethodKind::None = kind {
-            if let TokenResult::Some(_) = self.keyword("async") {
+            if self.is_modifier("async") {
+                eat_value!(self.keyword("async"));
                 true
             } else {
                 false
@@ -85,94 +406,125 @@ where
             false
         };
 
-        let star = if async {
-            None
+        let generator = if let MethodKind::None = kind {
+            opt_value!(self.punc(tokens::PunctuatorToken::Star)).is_some()
         } else {
-            opt_value!(self.punc(tokens::PunctuatorToken::Star))
+            false
         };
+        if async {
+            self.require_version(EcmaVersion::Es2017, "Async function")?;
+        }
+        if async && generator {
+            self.require_version(EcmaVersion::Es2018, "Async generator")?;
+        }
 
-        let is_ident = false;
-        let _name = if async && self.no_line_terminator() {
-            if let TokenResult::Some(_) = self.parse_property_name()? {
-                // async fn method
-            } else {
-                // method named  "async"
-            }
+        let name = if stat.is_some() || async || generator {
+            eat_value!(self.parse_property_name()?)
+        } else if let MethodKind::None = kind {
+            try_value!(self.parse_property_name()?)
         } else {
-            if async {
-                // has lineterminator
-                // method named  "async"
-            } else if let TokenResult::Some(_) = self.parse_property_name()? {
-
-            } else {
-                match (&stat, &kind, &star) {
-                    (_, _, &Some(_)) => bail!("expected method name"),
-                    (_, &MethodKind::Get, _) => {
-                        kind = MethodKind::None;
-                    }
-                    (_, &MethodKind::Set, _) => {
-                        kind = MethodKind::None;
-                    },
-                    (&Some(_), _, _) => {
-                        stat = None;
-                    }
-                    _ => return Ok(TokenResult::None),
-                }
-            }
+            eat_value!(self.parse_property_name()?)
         };
 
-        let _silence = stat;
-
         Ok(TokenResult::Some(MethodHead {
+            name,
+            stat,
             kind,
-            is_ident,
-            generator: star.is_some(),
+            generator,
             async,
         }))
     }
 
-    pub fn parse_method_tail(&mut self, head: MethodHead) -> OptResult<()> {
-        match head.kind {
+    pub fn parse_method_tail(&mut self, head: &MethodHead<'code>) -> OptResult<(functions::FunctionParams<'code>, functions::FunctionBody<'code>)> {
+        let (params, body) = try_value!(self.parse_method_tail_or_signature(head, false)?);
+
+        Ok(TokenResult::Some((params, body.expect("method bodies are required"))))
+    }
+
+    /// Parses the parameters and body of a method. With `allow_signature`, a
+    /// TypeScript method without a body is allowed too, like "a(): void;".
+    pub fn parse_method_tail_or_signature(&mut self, head: &MethodHead<'code>, allow_signature: bool) -> OptResult<(functions::FunctionParams<'code>, Option<functions::FunctionBody<'code>>)> {
+        let start = self.start();
+
+        let result = match head.kind {
             MethodKind::Get => {
                 try_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
                 eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+                let return_type = opt_value!(self.parse_return_type()?).map(NodeBox::new);
+                let params = self.located(start, functions::FunctionParams {
+                    return_type,
+                    predicate: None,
+                    ..functions::FunctionParams::default()
+                });
 
-                eat_value!(self.parse_function_body()?);
+                let body = self.parse_method_body(allow_signature)?;
+
+                (params, body)
             }
             MethodKind::Set => {
                 try_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
 
-                if let TokenResult::None = self.parse_binding_pattern()? {
-                    eat_value!(self.binding_identifier());
-                }
+                let param_start = self.start();
+                let decorators = self.parse_function_param_decorators()?;
+                let id = eat_value!(self.parse_binding_element_target()?);
+                let type_annotation = opt_value!(self.parse_type_annotation()?);
+                let init = opt_value!(self.with(Flag::In).parse_initializer()?);
+                let param = self.located(param_start, functions::FunctionParam {
+                    decorators,
+                    modifiers: Default::default(),
+                    id,
+                    optional: false,
+                    type_annotation,
+                    init,
+                    position: None,
+                    comments: None,
+                });
 
                 eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
-
-                eat_value!(self.parse_function_body()?);
+                let params = self.located(start, functions::FunctionParams {
+                    type_parameters: None,
+                    params: vec![],
+                    last_param: Some(param.into()),
+                    return_type: None,
+                    predicate: None,
+                    position: None,
+                    comments: None,
+                });
+
+                let body = self.parse_method_body(allow_signature)?;
+
+                (params, body)
             }
             MethodKind::None => {
-                if head.async {
-                    let mut parser = self.without(Flag::Yield);
-
-                    try_value!(parser.parse_function_params()?);
-                    eat_value!(parser.with(Flag::Await).parse_function_body()?);
-                } else if head.generator {
-                    let mut parser = self.without(Flag::Await);
-                    let mut parser = parser.with(Flag::Yield);
-
-                    try_value!(parser.parse_function_params()?);
-                    eat_value!(parser.parse_function_body()?);
+                let mut parser = if head.generator {
+                    self.with(Flag::Yield)
+                } else {
+                    self.without(Flag::Yield)
+                };
+                let mut parser = if head.async {
+                    parser.with(Flag::Await)
                 } else {
-                    let mut parser = self.without(Flag::Await);
-                    let mut parser = parser.without(Flag::Yield);
+                    parser.without(Flag::Await)
+                };
 
-                    try_value!(parser.parse_function_params()?);
-                    eat_value!(parser.parse_function_body()?);
-                }
+                let params_start = parser.start();
+                let params = try_value!(parser.parse_typed_function_params(true)?);
+                let body = parser.parse_method_body(allow_signature)?;
+                parser.check_params(params_start, &params, true, body.as_ref())?;
+
+                (params, body)
             }
         };
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(result))
+    }
+
+    fn parse_method_body(&mut self, allow_signature: bool) -> Result<Option<functions::FunctionBody<'code>>> {
+        Ok(if allow_signature {
+            opt_value!(self.parse_function_body()?)
+        } else {
+            Some(eat_value!(self.parse_function_body()?))
+        })
     }
 }
 
@@ -184,9 +536,31 @@ pub enum MethodKind {
 }
 
 #[derive(Debug)]
-pub struct MethodHead {
-    kind: MethodKind,
-    is_ident: bool,
-    generator: bool,
-    async: bool,
+pub struct MethodHead<'code> {
+    pub name: PropertyName<'code>,
+    pub stat: Option<classes::StaticPosition<'code>>,
+    pub kind: MethodKind,
+    pub generator: bool,
+    pub async: bool,
+}
+
+impl<'code> MethodHead<'code> {
+    // A name with no modifiers, which could also be a property.
+    pub fn is_plain(&self) -> bool {
+        match self.kind {
+            MethodKind::None => self.stat.is_none() && !self.generator && !self.async,
+            _ => false,
+        }
+    }
+
+    pub fn method_kind(&self) -> objects::MethodKind {
+        match (&self.kind, self.async, self.generator) {
+            (&MethodKind::Get, _, _) => objects::MethodKind::Get,
+            (&MethodKind::Set, _, _) => objects::MethodKind::Set,
+            (&MethodKind::None, true, true) => objects::MethodKind::AsyncGenerator,
+            (&MethodKind::None, true, false) => objects::MethodKind::Async,
+            (&MethodKind::None, false, true) => objects::MethodKind::Generator,
+            (&MethodKind::None, false, false) => objects::MethodKind::Normal,
+        }
+    }
 }
@@ -0,0 +1,175 @@
+// Attach the comments collected while parsing to the nodes of the AST.
+//
+// A comment inside of a node is handed down to the child that contains it.
+// Otherwise, a comment that follows a child on the same line trails that
+// child, and any other comment leads the child after it. Comments with no
+// sibling on either side end up as inner comments of the enclosing node.
+
+use ast::{self, Node, CommentNode, NodePosition, Separators};
+use tokenizer::tokens;
+
+impl From<tokens::CommentFormat> for ast::CommentFormat {
+    fn from(format: tokens::CommentFormat) -> ast::CommentFormat {
+        match format {
+            tokens::CommentFormat::Line => ast::CommentFormat::Line,
+            tokens::CommentFormat::Block => ast::CommentFormat::Block,
+            tokens::CommentFormat::HTMLOpen => ast::CommentFormat::HTMLOpen,
+            tokens::CommentFormat::HTMLClose => ast::CommentFormat::HTMLClose,
+            tokens::CommentFormat::Hashbang => ast::CommentFormat::Hashbang,
+        }
+    }
+}
+
+pub fn attach_comments<'code>(node: &mut dyn Node<'code>, comments: Vec<CommentNode<'code>>) {
+    if comments.is_empty() {
+        return;
+    }
+
+    let has_children = {
+        let mut children = vec![];
+        node.children_mut(&mut children);
+        !located_children(children).is_empty()
+    };
+    if !has_children {
+        let inner = &mut node.comments_mut().inner;
+        inner.extend(comments.into_iter().map(Separators::Comment));
+        return;
+    }
+
+    let mut children = vec![];
+    node.children_mut(&mut children);
+    let mut children = located_children(children);
+    children.sort_by_key(|child| position(&**child).start);
+
+    let mut nested: Vec<Vec<CommentNode>> = children.iter().map(|_| vec![]).collect();
+
+    // Siblings never overlap, so their ends are sorted along with their
+    // starts and each comment's neighbours can be found by binary search.
+    let spans: Vec<NodePosition> = children.iter().map(|child| position(&**child).clone()).collect();
+
+    for comment in comments {
+        let (start, end, start_line, end_line) = match comment.position {
+            Some(ref pos) => (pos.start, pos.end, pos.range.start.0, pos.range.end.0),
+            None => continue,
+        };
+
+        let containing = spans.partition_point(|pos| pos.start <= start).checked_sub(1);
+        if let Some(i) = containing.filter(|&i| end <= spans[i].end) {
+            nested[i].push(comment);
+            continue;
+        }
+
+        let prev = spans.partition_point(|pos| pos.end <= start).checked_sub(1);
+        let next = Some(spans.partition_point(|pos| pos.start < end)).filter(|&i| i < spans.len());
+
+        let prev_on_line = prev.is_some_and(|i| spans[i].range.end.0 == start_line);
+        let next_on_line = next.is_some_and(|i| spans[i].range.start.0 == end_line);
+
+        let comments = match (prev, next) {
+            (Some(i), _) if prev_on_line && !next_on_line => &mut children[i].comments_mut().trailing,
+            (_, Some(i)) => &mut children[i].comments_mut().leading,
+            (Some(i), _) => &mut children[i].comments_mut().trailing,
+            (None, None) => unreachable!("a comment outside of every child has a sibling"),
+        };
+        comments.push(Separators::Comment(comment));
+    }
+
+    for (child, comments) in children.into_iter().zip(nested) {
+        attach_comments(child, comments);
+    }
+}
+
+// Nodes that were not given a position can't be compared with comments, so
+// look through them to their own children.
+fn located_children<'a, 'code>(nodes: Vec<&'a mut dyn Node<'code>>) -> Vec<&'a mut dyn Node<'code>> {
+    let mut out = vec![];
+    for node in nodes {
+        if node.position().is_some() {
+            out.push(node);
+        } else {
+            let mut children = vec![];
+            node.children_mut(&mut children);
+            out.extend(located_children(children));
+        }
+    }
+    out
+}
+
+fn position<'a>(node: &'a dyn Node<'_>) -> &'a NodePosition {
+    node.position().expect("children are filtered to located nodes")
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{root, alias, LeadingComments, TrailingComments, InnerComments};
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
+
+    fn parse(code: &str) -> root::Script<'_> {
+        Parser::new(code.into_tokenizer()).parse_script().unwrap()
+    }
+
+    fn values<'a, I: Iterator<Item = &'a ::ast::CommentNode<'a>>>(it: I) -> Vec<&'a str> {
+        it.map(|c| &*c.value).collect()
+    }
+
+    #[test]
+    fn it_attaches_leading_comments() {
+        let script = parse("// first\n/* second */ foo;\nbar;");
+
+        assert_eq!(values(script.body[0].leading_comments()), vec![" first", " second "]);
+        assert_eq!(values(script.body[1].leading_comments()), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn it_attaches_trailing_comments() {
+        let script = parse("foo; // after foo\nbar;");
+
+        assert_eq!(values(script.body[0].trailing_comments()), vec![" after foo"]);
+        assert_eq!(values(script.body[1].leading_comments()), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn it_attaches_comments_to_the_last_node() {
+        let script = parse("foo;\n// end");
+
+        assert_eq!(values(script.body[0].trailing_comments()), vec![" end"]);
+    }
+
+    #[test]
+    fn it_attaches_inner_comments() {
+        let script = parse("{ /* empty */ }");
+
+        assert_eq!(values(script.body[0].inner_comments()), vec![" empty "]);
+    }
+
+    #[test]
+    fn it_attaches_comments_to_nested_nodes() {
+        let script = parse("function foo() {\n  // inside\n  return 1;\n}");
+
+        let func = match script.body[0] {
+            alias::StatementItem::Function(ref func) => func,
+            ref item => panic!("expected a function, got {:?}", item),
+        };
+        assert_eq!(values(func.body.body[0].leading_comments()), vec![" inside"]);
+    }
+
+    #[test]
+    fn it_attaches_comments_to_the_root() {
+        let script = parse("/* only */");
+
+        assert_eq!(values(script.inner_comments()), vec![" only "]);
+    }
+
+    #[test]
+    fn it_attaches_comments_among_many_siblings() {
+        let code: String = (0..100).map(|i| format!("/* {0} */ a{0}; // {0}\n\n", i)).collect();
+        let script = parse(&code);
+
+        for (i, item) in script.body.iter().enumerate() {
+            let expected = format!(" {} ", i);
+            assert_eq!(values(item.leading_comments()), vec![&expected[..]]);
+            assert_eq!(values(item.trailing_comments()), vec![&expected[..expected.len() - 1]]);
+        }
+    }
+}
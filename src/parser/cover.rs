@@ -0,0 +1,515 @@
+// Conversions from the expressions parsed for cover grammars into the
+// patterns and parameter lists that they turn out to represent.
+
+use ast::{alias, KeywordWrappedData, PositionRange, TokenData, NodeBox};
+use ast::expression;
+use ast::functions;
+use ast::general::{self, BindingIdentifier, PropertyIdentifier, ReferenceIdentifier};
+use ast::objects;
+use ast::patterns;
+use parser::utils::Result;
+
+// foo = ..., foo += ..., foo++
+pub fn simple_assign_target(expr: alias::Expression) -> Result<patterns::LeftHandSimpleAssign> {
+    Ok(match expr {
+        alias::Expression::Binding(id) => NodeBox::into_inner(id).into(),
+        alias::Expression::Member(member) => NodeBox::into_inner(member).into(),
+        alias::Expression::Parenthesized(paren) => parenthesized_pattern(NodeBox::into_inner(paren))?.into(),
+        _ => bail!("Invalid assignment target"),
+    })
+}
+
+// foo = ..., {foo} = ..., [foo] = ...
+pub fn complex_assign_target(expr: alias::Expression) -> Result<patterns::LeftHandComplexAssign> {
+    Ok(match expr {
+        alias::Expression::Object(obj) => object_assignment_pattern(NodeBox::into_inner(obj))?.into(),
+        alias::Expression::Array(arr) => array_assignment_pattern(NodeBox::into_inner(arr))?.into(),
+        expr => match simple_assign_target(expr)? {
+            patterns::LeftHandSimpleAssign::Identifier(id) => id.into(),
+            patterns::LeftHandSimpleAssign::Member(member) => member.into(),
+            patterns::LeftHandSimpleAssign::Parenthesized(paren) => paren.into(),
+        },
+    })
+}
+
+// function(foo, {foo}, [foo]) {}
+pub fn binding_pattern(expr: alias::Expression) -> Result<patterns::BindingPattern> {
+    pattern_binding(complex_assign_target(expr)?)
+}
+
+pub fn function_param(expr: alias::Expression) -> Result<functions::FunctionParam> {
+    Ok(match expr {
+        alias::Expression::Assign(assign) => {
+            let expression::AssignmentExpression { left, token_eq, right, position, .. } = NodeBox::into_inner(assign);
+
+            functions::FunctionParam {
+                decorators: vec![],
+                modifiers: Default::default(),
+                id: pattern_binding(NodeBox::into_inner(left))?,
+                optional: false,
+                type_annotation: None,
+                init: Some(initializer(token_eq, right)),
+                position,
+                comments: None,
+            }
+        }
+        expr => {
+            let position = expr.position().cloned().map(NodeBox::new);
+
+            functions::FunctionParam {
+                decorators: vec![],
+                modifiers: Default::default(),
+                id: binding_pattern(expr)?,
+                optional: false,
+                type_annotation: None,
+                init: None,
+                position,
+                comments: None,
+            }
+        }
+    })
+}
+
+// Split "a, b, c" back into its individual expressions.
+pub fn sequence_items(mut expr: alias::Expression) -> Vec<alias::Expression> {
+    let mut items = vec![];
+    while let alias::Expression::Sequence(seq) = expr {
+        let expression::SequenceExpression { left, right, .. } = NodeBox::into_inner(seq);
+
+        items.push(NodeBox::into_inner(right));
+        expr = NodeBox::into_inner(left);
+    }
+    items.push(expr);
+    items.reverse();
+
+    items
+}
+
+// foo => {}, (foo) => {}, async (foo) => {}
+pub fn arrow_params(expr: alias::Expression) -> Result<(functions::ArrowFunctionKind, functions::ArrowFunctionParams)> {
+    Ok(match expr {
+        alias::Expression::Binding(id) => (
+            functions::ArrowFunctionKind::Normal,
+            functions::ArrowFunctionParams::from(binding_identifier(NodeBox::into_inner(id))),
+        ),
+        alias::Expression::Parenthesized(paren) => {
+            let expression::ParenthesizedExpression { expr, position, .. } = NodeBox::into_inner(paren);
+
+            let mut params = vec![];
+            for item in sequence_items(NodeBox::into_inner(expr)) {
+                params.push((function_param(item)?, Default::default()));
+            }
+            let last_param = params.pop().map(|(param, _)| param.into());
+
+            (
+                functions::ArrowFunctionKind::Normal,
+                functions::FunctionParams {
+                    type_parameters: None,
+                    params,
+                    last_param,
+                    return_type: None,
+                    predicate: None,
+                    position,
+                    comments: None,
+                }.into(),
+            )
+        }
+
+        // Parameter lists that can only be arrow parameters are parsed
+        // directly, and have no body yet.
+        alias::Expression::Arrow(arrow) => {
+            match arrow.body {
+                functions::ArrowFunctionBody::Block(ref body) if body.position.is_none() => {}
+                _ => bail!("Invalid arrow function parameters"),
+            }
+
+            (functions::ArrowFunctionKind::Normal, NodeBox::into_inner(arrow).params)
+        }
+        alias::Expression::Call(call) => {
+            let expression::CallExpression { callee, arguments, .. } = NodeBox::into_inner(call);
+
+            match *callee {
+                alias::Expression::Binding(ref id) if id.value == "async" => {}
+                _ => bail!("Invalid arrow function parameters"),
+            }
+
+            let expression::CallArguments { args, last_arg, position, .. } = arguments;
+
+            let mut params = vec![];
+            for (arg, _) in args {
+                match arg {
+                    expression::CallArgument::Expression(arg) => {
+                        params.push((function_param(NodeBox::into_inner(arg.expression))?, Default::default()));
+                    }
+                    expression::CallArgument::Spread(_) => bail!("Rest parameter must be last parameter"),
+                }
+            }
+
+            let last_param = match last_arg {
+                Some(expression::CallArgument::Expression(arg)) => {
+                    Some(function_param(NodeBox::into_inner(arg.expression))?.into())
+                }
+                Some(expression::CallArgument::Spread(arg)) => {
+                    let expression::CallArgumentSpread { expression, position, .. } = arg;
+
+                    Some(functions::FunctionRestParam {
+                        id: binding_pattern(NodeBox::into_inner(expression))?,
+                        type_annotation: None,
+                        position,
+                        comments: None,
+                    }.into())
+                }
+                None => None,
+            };
+
+            (
+                functions::ArrowFunctionKind::Async,
+                functions::FunctionParams {
+                    type_parameters: None,
+                    params,
+                    last_param,
+                    return_type: None,
+                    predicate: None,
+                    position,
+                    comments: None,
+                }.into(),
+            )
+        }
+        _ => bail!("Invalid arrow function parameters"),
+    })
+}
+
+pub fn reference_identifier(id: PropertyIdentifier) -> ReferenceIdentifier {
+    ReferenceIdentifier {
+        value: id.value,
+        raw: id.raw,
+        position: id.position,
+        comments: None,
+    }
+}
+
+pub fn property_identifier<'code>(id: &ReferenceIdentifier<'code>) -> PropertyIdentifier<'code> {
+    PropertyIdentifier {
+        value: id.value.clone(),
+        raw: id.raw.clone(),
+        position: id.position.clone(),
+        comments: None,
+    }
+}
+
+// Token data is stored in a few different shapes, so conversions between
+// nodes only keep the position of the token.
+fn token_data<A: TokenData, B: From<PositionRange> + Default>(data: &A) -> B {
+    data.position().cloned().map(B::from).unwrap_or_default()
+}
+
+pub fn binding_identifier(id: ReferenceIdentifier) -> BindingIdentifier {
+    BindingIdentifier {
+        value: id.value,
+        raw: id.raw,
+        position: id.position,
+        comments: None,
+    }
+}
+
+fn initializer(token_eq: KeywordWrappedData, expr: NodeBox<alias::Expression>) -> general::Initializer {
+    general::Initializer {
+        token_eq,
+        position: expr.position().cloned().map(NodeBox::new),
+        expression: expr,
+        comments: None,
+    }
+}
+
+fn parenthesized_pattern(paren: expression::ParenthesizedExpression) -> Result<patterns::ParenthesizedAssignmentPattern> {
+    let expression::ParenthesizedExpression { token_paren_l, expr, token_paren_r, position, .. } = paren;
+
+    Ok(patterns::ParenthesizedAssignmentPattern {
+        token_paren_l: token_data(&token_paren_l),
+        pattern: NodeBox::new(simple_assign_target(NodeBox::into_inner(expr))?),
+        token_paren_r,
+        position,
+        comments: None,
+    })
+}
+
+fn object_assignment_pattern(obj: objects::ObjectExpression) -> Result<patterns::ObjectAssignmentPattern> {
+    let objects::ObjectExpression { token_curly_left, properties, last_property, token_curly_right, position, .. } = obj;
+
+    let mut props = vec![];
+    for (prop, sep) in properties {
+        props.push((object_assignment_property(prop)?, sep));
+    }
+
+    let last_property = match last_property {
+        Some(objects::ObjectItem::Spread(spread)) => {
+            let objects::ObjectSpreadElement { expression, position, .. } = NodeBox::into_inner(spread);
+
+            Some(patterns::ObjectAssignmentPatternRestProperty {
+                token_ellipsis: Default::default(),
+                pattern: NodeBox::new(simple_assign_target(NodeBox::into_inner(expression))?),
+                position,
+                comments: None,
+            }.into())
+        }
+        Some(prop) => Some(match object_assignment_property(prop)? {
+            patterns::ObjectAssignmentPatternProperty::Identifier(prop) => prop.into(),
+            patterns::ObjectAssignmentPatternProperty::Pattern(prop) => prop.into(),
+        }),
+        None => None,
+    };
+
+    Ok(patterns::ObjectAssignmentPattern {
+        token_curly_l: token_curly_left,
+        properties: props,
+        last_property,
+        token_curly_r: token_data(&token_curly_right),
+        position,
+        comments: None,
+    })
+}
+
+fn object_assignment_property(prop: objects::ObjectItem) -> Result<patterns::ObjectAssignmentPatternProperty> {
+    Ok(match prop {
+        objects::ObjectItem::Shorthand(prop) => {
+            let objects::ObjectShorthandProperty { id, position, .. } = NodeBox::into_inner(prop);
+
+            patterns::ObjectAssignmentPatternIdentifierProperty {
+                id,
+                init: None,
+                position,
+                comments: None,
+            }.into()
+        }
+        objects::ObjectItem::Property(prop) => {
+            let objects::ObjectProperty { name, value, position, .. } = NodeBox::into_inner(prop);
+
+            match NodeBox::into_inner(value) {
+                alias::Expression::Assign(assign) => {
+                    let expression::AssignmentExpression { left, token_eq, right, position: assign_position, .. } = NodeBox::into_inner(assign);
+
+                    // {foo = 4} is parsed as {foo: foo = 4}, sharing a start position.
+                    let shorthand = assign_position.as_ref().map(|p| p.start) == position.as_ref().map(|p| p.start);
+
+                    match NodeBox::into_inner(left) {
+                        patterns::LeftHandComplexAssign::Identifier(id) if shorthand => {
+                            patterns::ObjectAssignmentPatternIdentifierProperty {
+                                id,
+                                init: Some(initializer(token_eq, right)),
+                                position,
+                                comments: None,
+                            }.into()
+                        }
+                        left => patterns::ObjectAssignmentPatternPatternProperty {
+                            name,
+                            pattern: NodeBox::new(left),
+                            init: Some(initializer(token_eq, right)),
+                            position,
+                            comments: None,
+                        }.into(),
+                    }
+                }
+                value => patterns::ObjectAssignmentPatternPatternProperty {
+                    name,
+                    pattern: NodeBox::new(complex_assign_target(value)?),
+                    init: None,
+                    position,
+                    comments: None,
+                }.into(),
+            }
+        }
+        objects::ObjectItem::Spread(_) => bail!("Rest property must be last property"),
+        objects::ObjectItem::Method(_) => bail!("Invalid destructuring target"),
+    })
+}
+
+fn array_assignment_pattern(arr: objects::ArrayExpression) -> Result<patterns::ArrayAssignmentPattern> {
+    let objects::ArrayExpression { elements, last_element, position, .. } = arr;
+
+    let mut items = vec![];
+    for (item, sep) in elements {
+        items.push((
+            match item {
+                Some(item) => Some(array_assignment_element(item)?),
+                None => None,
+            },
+            sep,
+        ));
+    }
+
+    let last_item = match last_element {
+        Some(objects::ArrayItem::Spread(spread)) => {
+            let objects::ArraySpreadItem { expression, position, .. } = spread;
+
+            Some(patterns::ArrayAssignmentRestElement {
+                token_ellipsis: Default::default(),
+                pattern: NodeBox::new(complex_assign_target(NodeBox::into_inner(expression))?),
+                position,
+                comments: None,
+            }.into())
+        }
+        Some(item) => Some(array_assignment_element(item)?.into()),
+        None => None,
+    };
+
+    Ok(patterns::ArrayAssignmentPattern {
+        items,
+        last_item,
+        position,
+        comments: None,
+    })
+}
+
+fn array_assignment_element(item: objects::ArrayItem) -> Result<patterns::ArrayAssignmentPatternElement> {
+    let objects::ArrayExpressionItem { expression, position, .. } = match item {
+        objects::ArrayItem::Expression(item) => item,
+        objects::ArrayItem::Spread(_) => bail!("Rest element must be last element"),
+    };
+
+    Ok(match NodeBox::into_inner(expression) {
+        alias::Expression::Assign(assign) => {
+            let expression::AssignmentExpression { left, token_eq, right, position, .. } = NodeBox::into_inner(assign);
+
+            patterns::ArrayAssignmentPatternElement {
+                id: left,
+                init: Some(initializer(token_eq, right)),
+                position,
+                comments: None,
+            }
+        }
+        expr => patterns::ArrayAssignmentPatternElement {
+            id: NodeBox::new(complex_assign_target(expr)?),
+            init: None,
+            position,
+            comments: None,
+        },
+    })
+}
+
+// Binding patterns have the same structure as assignment patterns, but only
+// allow identifiers as targets.
+fn pattern_binding(pattern: patterns::LeftHandComplexAssign) -> Result<patterns::BindingPattern> {
+    Ok(match pattern {
+        patterns::LeftHandComplexAssign::Identifier(id) => binding_identifier(id).into(),
+        patterns::LeftHandComplexAssign::Object(obj) => {
+            let patterns::ObjectAssignmentPattern { properties, last_property, position, .. } = obj;
+
+            let mut props = vec![];
+            for (prop, sep) in properties {
+                props.push((
+                    match prop {
+                        patterns::ObjectAssignmentPatternProperty::Identifier(prop) => object_binding_identifier(prop).into(),
+                        patterns::ObjectAssignmentPatternProperty::Pattern(prop) => object_binding_pattern(prop)?.into(),
+                    },
+                    sep,
+                ));
+            }
+
+            let last_property = match last_property {
+                Some(patterns::ObjectAssignmentPatternLastProperty::Identifier(prop)) => {
+                    Some(object_binding_identifier(prop).into())
+                }
+                Some(patterns::ObjectAssignmentPatternLastProperty::Pattern(prop)) => {
+                    Some(object_binding_pattern(prop)?.into())
+                }
+                Some(patterns::ObjectAssignmentPatternLastProperty::Rest(prop)) => {
+                    let patterns::ObjectAssignmentPatternRestProperty { pattern, position, .. } = prop;
+
+                    let id = match NodeBox::into_inner(pattern) {
+                        patterns::LeftHandSimpleAssign::Identifier(id) => binding_identifier(id),
+                        _ => bail!("Invalid binding pattern"),
+                    };
+
+                    Some(patterns::ObjectBindingPatternRestProperty {
+                        token_ellipsis: Default::default(),
+                        pattern: id,
+                        position,
+                        comments: None,
+                    }.into())
+                }
+                None => None,
+            };
+
+            patterns::ObjectBindingPattern {
+                properties: props,
+                last_property,
+                position,
+                comments: None,
+            }.into()
+        }
+        patterns::LeftHandComplexAssign::Array(arr) => {
+            let patterns::ArrayAssignmentPattern { items, last_item, position, .. } = arr;
+
+            let mut elements = vec![];
+            for (item, sep) in items {
+                elements.push((
+                    match item {
+                        Some(item) => Some(array_binding_element(item)?),
+                        None => None,
+                    },
+                    sep,
+                ));
+            }
+
+            let last_item = match last_item {
+                Some(patterns::ArrayAssignmentPatternLastElement::Pattern(item)) => {
+                    Some(patterns::ArrayBindingPatternLastElement::Pattern(NodeBox::new(array_binding_element(item)?)))
+                }
+                Some(patterns::ArrayAssignmentPatternLastElement::Rest(item)) => {
+                    let patterns::ArrayAssignmentRestElement { pattern, position, .. } = item;
+
+                    Some(patterns::ArrayBindingRestElement {
+                        token_ellipsis: Default::default(),
+                        pattern: NodeBox::new(pattern_binding(NodeBox::into_inner(pattern))?),
+                        position,
+                        comments: None,
+                    }.into())
+                }
+                None => None,
+            };
+
+            patterns::ArrayBindingPattern {
+                items: elements,
+                last_item,
+                position,
+                comments: None,
+            }.into()
+        }
+        patterns::LeftHandComplexAssign::Member(_) |
+        patterns::LeftHandComplexAssign::Parenthesized(_) => bail!("Invalid binding pattern"),
+    })
+}
+
+fn object_binding_identifier(prop: patterns::ObjectAssignmentPatternIdentifierProperty) -> patterns::ObjectBindingPatternIdentifierProperty {
+    let patterns::ObjectAssignmentPatternIdentifierProperty { id, init, position, .. } = prop;
+
+    patterns::ObjectBindingPatternIdentifierProperty {
+        id: binding_identifier(id),
+        init,
+        position,
+        comments: None,
+    }
+}
+
+fn object_binding_pattern(prop: patterns::ObjectAssignmentPatternPatternProperty) -> Result<patterns::ObjectBindingPatternPatternProperty> {
+    let patterns::ObjectAssignmentPatternPatternProperty { name, pattern, init, position, .. } = prop;
+
+    Ok(patterns::ObjectBindingPatternPatternProperty {
+        name,
+        pattern: NodeBox::new(pattern_binding(NodeBox::into_inner(pattern))?),
+        init,
+        position,
+        comments: None,
+    })
+}
+
+fn array_binding_element(item: patterns::ArrayAssignmentPatternElement) -> Result<patterns::ArrayBindingPatternElement> {
+    let patterns::ArrayAssignmentPatternElement { id, init, position, .. } = item;
+
+    Ok(patterns::ArrayBindingPatternElement {
+        id: pattern_binding(NodeBox::into_inner(id))?,
+        init,
+        position,
+        comments: None,
+    })
+}
@@ -1,84 +1,143 @@
+use ast::alias;
+use ast::statement;
 use tokenizer::{Tokenizer, tokens};
-use parser::Parser;
+use parser::{Parser, Flag, LookaheadResult};
+use parser::statements::{let_declarator, const_declarator};
 use parser::utils::{OptResult, TokenResult};
 
 impl<'code, T> Parser<'code, T>
 where
     T: Tokenizer<'code>
 {
-    pub fn parse_declaration(&mut self) -> OptResult<()> {
+    pub fn parse_declaration(&mut self) -> OptResult<alias::StatementItem<'code>> {
         self.expect_expression();
 
+        if self.has_types() {
+            if let TokenResult::Some(decl) = self.parse_typescript_declaration()? {
+                self.check_cover_init()?;
+
+                return Ok(TokenResult::Some(decl));
+            }
+        }
 
         enum DeclType {
             Class,
             Function,
             Let,
             Const,
+            Async,
         }
 
+        let decorators = self.options.extensions.decorators;
         let t = match *self.token() {
             tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => {
                 match &**name {
                     "class" => DeclType::Class,
-                    // TODO: Async
                     "function" => DeclType::Function,
+                    "async" => DeclType::Async,
                     "let" => DeclType::Let,
                     "const" => DeclType::Const,
                     _ => return Ok(TokenResult::None),
                 }
             }
+            tokens::Token::Punctuator(tokens::PunctuatorToken::At) if decorators => DeclType::Class,
             _ => return Ok(TokenResult::None),
         };
 
-        eat_value!(match t {
-            DeclType::Class => self.parse_class_declaration()?,
-            DeclType::Function => self.parse_function_declaration()?,
-            DeclType::Let => self.parse_let_declaration()?,
-            DeclType::Const => self.parse_const_declaration()?,
-        });
+        // "async" is only a declaration if it is followed by "function".
+        let t = match t {
+            DeclType::Async => {
+                let is_function = if let Some(&LookaheadResult {
+                    line: false,
+                    token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+                    ..
+                }) = self.ident_lookahead() {
+                    name == "function"
+                } else {
+                    false
+                };
+
+                if !is_function {
+                    return Ok(TokenResult::None);
+                }
+                DeclType::Function
+            }
 
-        Ok(TokenResult::Some(()))
-    }
+            // In sloppy code, "let" is only a declaration if a binding follows
+            // it, and is otherwise an identifier, like in "let;" or "let = 1".
+            DeclType::Let if !self.flags.is_strict => {
+                let is_binding = match self.ident_lookahead() {
+                    Some(&LookaheadResult {
+                        token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+                        ..
+                    }) => name != "in" && name != "instanceof",
+                    Some(&LookaheadResult {
+                        token: tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen),
+                        ..
+                    }) |
+                    Some(&LookaheadResult {
+                        token: tokens::Token::Punctuator(tokens::PunctuatorToken::CurlyOpen),
+                        ..
+                    }) => true,
+                    _ => false,
+                };
+
+                if !is_binding {
+                    return Ok(TokenResult::None);
+                }
+                DeclType::Let
+            }
+            t => t,
+        };
 
-    pub fn parse_let_declaration(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("let"));
+        let decl = match t {
+            DeclType::Class => eat_value!(self.parse_class_declaration()?).into(),
+            DeclType::Function => eat_value!(self.parse_function_declaration()?).into(),
+            DeclType::Let => eat_value!(self.parse_let_declaration()?).into(),
+            DeclType::Const => eat_value!(self.parse_const_declaration()?).into(),
+            DeclType::Async => unreachable!(),
+        };
 
-        eat_value!(self.parse_lexical_declarator(false)?);
+        self.check_cover_init()?;
 
-        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
-            eat_value!(self.parse_lexical_declarator(false)?);
-        }
-        eat_value!(self.semicolon());
-
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(decl))
     }
 
-    pub fn parse_const_declaration(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("const"));
-
-        eat_value!(self.parse_lexical_declarator(true)?);
-
-        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
-            eat_value!(self.parse_lexical_declarator(true)?);
-        }
-        eat_value!(self.semicolon());
-
-        Ok(TokenResult::Some(()))
+    pub fn parse_let_declaration(&mut self) -> OptResult<statement::LetDeclaration<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("let"));
+        let token_let = self.token_data();
+
+        let declarators = eat_value!(self.with(Flag::In).parse_declarator_list()?);
+        let declarators = declarators.try_map(let_declarator)?;
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, statement::LetDeclaration {
+            token_let,
+            declarators: declarators.items,
+            last_declarator: declarators.last,
+            token_semi,
+            position: None,
+            comments: None,
+        })))
     }
 
-    pub fn parse_lexical_declarator(&mut self, initializer_required: bool) -> OptResult<()> {
-        if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-            eat_value!(self.parse_initializer()?);
-        } else {
-            eat_value!(self.binding_identifier());
-
-            if initializer_required {
-                eat_value!(self.parse_initializer()?);
-            } else {
-                opt_value!(self.parse_initializer()?);
-            }
-        }
-        Ok(TokenResult::Some(()))
+    pub fn parse_const_declaration(&mut self) -> OptResult<statement::ConstDeclaration<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("const"));
+        let token_const = self.token_data();
+
+        let declarators = eat_value!(self.with(Flag::In).parse_declarator_list()?);
+        let declarators = declarators.try_map(const_declarator)?;
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, statement::ConstDeclaration {
+            token_const,
+            declarators: declarators.items,
+            last_declarator: declarators.last,
+            token_semi,
+            position: None,
+            comments: None,
+        })))
     }
 }
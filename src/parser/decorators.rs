@@ -0,0 +1,115 @@
+use ast::classes;
+use ast::decorators;
+use ast::functions;
+use ast::NodeBox;
+use tokenizer::{Tokenizer, tokens};
+use parser::{Parser, Flag};
+use parser::utils::{OptResult, Result, TokenResult};
+
+impl<'code, T> Parser<'code, T>
+where
+    T: Tokenizer<'code>
+{
+    pub fn parse_class_decorators(&mut self) -> Result<Vec<classes::ClassDecorator<'code>>> {
+        let mut decorators = vec![];
+        loop {
+            let start = self.start();
+            let value = match self.parse_decorator()? {
+                TokenResult::Some(value) => value,
+                TokenResult::None => break,
+            };
+
+            decorators.push(self.located(start, classes::ClassDecorator {
+                tokens_prefix: Default::default(),
+                value,
+                position: None,
+                comments: None,
+            }));
+        }
+        Ok(decorators)
+    }
+
+    pub fn parse_class_item_decorators(&mut self) -> Result<Vec<classes::ClassItemDecorator<'code>>> {
+        let mut decorators = vec![];
+        loop {
+            let start = self.start();
+            let value = match self.parse_decorator()? {
+                TokenResult::Some(value) => value,
+                TokenResult::None => break,
+            };
+
+            decorators.push(self.located(start, classes::ClassItemDecorator {
+                tokens_prefix: Default::default(),
+                value,
+                position: None,
+                comments: None,
+            }));
+        }
+        Ok(decorators)
+    }
+
+    pub fn parse_function_param_decorators(&mut self) -> Result<Vec<functions::FunctionParamDecorator<'code>>> {
+        let mut decorators = vec![];
+        loop {
+            let start = self.start();
+            let value = match self.parse_decorator()? {
+                TokenResult::Some(value) => value,
+                TokenResult::None => break,
+            };
+
+            decorators.push(self.located(start, functions::FunctionParamDecorator {
+                value,
+                position: None,
+                comments: None,
+            }));
+        }
+        Ok(decorators)
+    }
+
+    // A decorator is either a chain of property accesses, optionally called,
+    // like "@a.b(c)", or any expression in parens, like "@(a[b])".
+    fn parse_decorator(&mut self) -> OptResult<decorators::DecoratorValue<'code>> {
+        if !self.options.extensions.decorators {
+            return Ok(TokenResult::None);
+        }
+        try_value!(self.punc(tokens::PunctuatorToken::At));
+
+        let start = self.start();
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::ParenOpen) {
+            self.expect_expression();
+            let expression = eat_value!(self.with(Flag::In).parse_expression()?);
+            eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+
+            return Ok(TokenResult::Some(self.located(start, decorators::DecoratorExpression {
+                expression,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        let id = eat_value!(self.parse_reference_identifier());
+        let mut callee = decorators::DecoratorValueExpression::from(id);
+        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Period) {
+            let property = eat_value!(self.parse_property_identifier());
+
+            callee = self.located(start, decorators::DecoratorMemberAccess {
+                object: NodeBox::new(callee),
+                property,
+                position: None,
+                comments: None,
+            }).into();
+        }
+
+        let value = if let TokenResult::Some(arguments) = self.parse_call_arguments()? {
+            self.located(start, decorators::DecoratorCallExpression {
+                callee,
+                arguments,
+                position: None,
+                comments: None,
+            }).into()
+        } else {
+            callee.into()
+        };
+        Ok(TokenResult::Some(value))
+    }
+}
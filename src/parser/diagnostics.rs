@@ -0,0 +1,93 @@
+use std::fmt;
+
+use failure::{Error, Fail};
+
+use tokenizer::{self, tokens, TokenRange, Tokenizer};
+use parser::Parser;
+
+/// A syntax error, located at the token that the parser stopped at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub range: TokenRange,
+}
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}:{})", self.message, self.range.start.line, self.range.start.column)
+    }
+}
+impl Fail for Diagnostic {}
+
+impl<'code, T> Parser<'code, T>
+where
+    T: Tokenizer<'code>
+{
+    /// Locate an error returned by a parse method at the next token, which
+    /// is the one that could not be parsed. Errors from `locate_error` keep
+    /// their own location.
+    pub fn diagnostic(&mut self, error: &Error) -> Diagnostic {
+        if let Some(diagnostic) = error.downcast_ref::<Diagnostic>() {
+            return diagnostic.clone();
+        }
+        self.start();
+
+        let next = &self.tokens[self.index as usize];
+        let message = match next.token {
+            // Say why the token is invalid rather than that it was unexpected.
+            tokens::Token::Invalid(invalid) => invalid.message().into(),
+            _ => error.to_string(),
+        };
+        Diagnostic {
+            message,
+            range: next.range,
+        }
+    }
+
+    /// Locate an error at the code from `start` to the last token that was
+    /// consumed, for errors found once the code is parsed, like an
+    /// initializer where none is allowed.
+    pub fn locate_error(&self, start: tokenizer::Position, error: Error) -> Error {
+        Diagnostic {
+            message: error.to_string(),
+            range: TokenRange {
+                start,
+                end: self.last_end,
+            },
+        }.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{parse, ParserOptions, SourceType};
+
+    fn diagnostic(code: &str, source_type: SourceType) -> String {
+        parse(code, &ParserOptions { source_type, ..ParserOptions::default() }).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn it_locates_errors() {
+        assert_eq!(diagnostic("var a = ;", SourceType::Script), "UnexpectedToken (1:8)");
+        assert_eq!(diagnostic("a;\nfoo(1 2);", SourceType::Script), "UnexpectedToken (2:6)");
+        assert_eq!(diagnostic("if (a) {", SourceType::Script), "UnexpectedToken (1:8)");
+    }
+
+    #[test]
+    fn it_explains_invalid_tokens() {
+        assert_eq!(diagnostic("a = 'b\nc';", SourceType::Script), "Unterminated string (1:4)");
+        assert_eq!(diagnostic("a = 0b12;", SourceType::Script), "Invalid number (1:4)");
+        assert_eq!(diagnostic("a;\n/* b", SourceType::Script), "Unterminated comment (2:0)");
+    }
+
+    #[test]
+    fn it_parses_by_source_type() {
+        assert!(parse("import a from 'a';", &ParserOptions::script()).is_err());
+        assert!(parse("import a from 'a';", &ParserOptions::module()).is_ok());
+
+        // Unambiguous code falls back to a module, and reports the error
+        // from whichever goal got further.
+        assert!(parse("with (a) {}", &ParserOptions { source_type: SourceType::Unambiguous, ..ParserOptions::default() }).is_ok());
+        assert!(parse("import a from 'a';", &ParserOptions { source_type: SourceType::Unambiguous, ..ParserOptions::default() }).is_ok());
+        assert_eq!(diagnostic("import a from 'a';\nvar b = ;", SourceType::Unambiguous), "UnexpectedToken (2:8)");
+    }
+}
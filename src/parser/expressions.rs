@@ -1,42 +1,142 @@
-use tokenizer::{Tokenizer, tokens};
-use parser::{Parser, Flag, LookaheadResult, is_binding_identifier};
-use parser::utils::{OptResult, TokenResult};
+use std::mem;
+
+use ast::alias;
+use ast::expression;
+use ast::functions;
+use ast::general::PropertyName;
+use ast::literal;
+use ast::objects;
+use ast::patterns;
+use ast::typescript;
+use ast::NodeBox;
+use tokenizer::{self, Tokenizer, tokens};
+use parser::{EcmaVersion, Parser, Flag, LookaheadResult, is_binding_identifier, node_start};
+use parser::cover;
+use parser::statements::has_legacy_octal_escape;
+use parser::utils::{OptResult, Result, TokenResult};
+
+// Build a binary node from a left and right expression.
+macro_rules! binary_node {
+    ($p:expr, $start:expr, $ty:ident, $token:ident: $data:expr, $left:expr, $right:expr) => (
+        alias::Expression::from($p.located($start, expression::$ty {
+            left: NodeBox::new($left),
+            $token: $data,
+            right: NodeBox::new($right),
+            position: None,
+            comments: None,
+        }))
+    );
+}
+
+// Build a unary node wrapping an expression.
+macro_rules! unary_node {
+    ($p:expr, $start:expr, $ty:ident, $token:ident: $data:expr, $value:expr) => (
+        alias::Expression::from($p.located($start, expression::$ty {
+            $token: $data,
+            value: NodeBox::new($value),
+            position: None,
+            comments: None,
+        }))
+    );
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Binary {
+    Or,
+    And,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Equal,
+    StrictEqual,
+    NotEqual,
+    StrictNotEqual,
+    LessThan,
+    GreaterThan,
+    LessThanEqual,
+    GreaterThanEqual,
+    In,
+    Instanceof,
+    LeftShift,
+    RightShift,
+    RightShiftSigned,
+    Add,
+    Subtract,
+    Multiply,
+    Modulus,
+    Divide,
+    As,
+    Satisfies,
+}
 
 impl<'code, T> Parser<'code, T>
 where
     T: Tokenizer<'code>
 {
-    pub fn parse_left_hand_side_expression(&mut self) -> OptResult<()> {
+    pub fn parse_left_hand_side_expression(&mut self) -> OptResult<alias::Expression<'code>> {
         self.parse_left_hand_expression(true)
     }
 
-    pub fn parse_expression(&mut self) -> OptResult<()> {
-        try_value!(self.parse_assignment_expression()?);
+    pub fn parse_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        let mut expr = try_value!(self.parse_assignment_expression()?);
 
         self.expect_expression();
         while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+            let token = self.token_data();
             self.expect_expression();
-            eat_value!(self.parse_assignment_expression()?);
+            let right = eat_value!(self.parse_assignment_expression()?);
+
+            expr = binary_node!(self, start, SequenceExpression, token_comma: token, expr, right);
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(expr))
+    }
+    pub fn parse_assignment_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        self.nested(Self::parse_assignment_expression_nested)
+    }
+
+    fn parse_assignment_expression_nested(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        let no_arrow_return_type = mem::replace(&mut self.no_arrow_return_type, false);
+
+        if let TokenResult::Some(expr) = self.parse_assignment_keyword_or_arrow(start, no_arrow_return_type)? {
+            return Ok(TokenResult::Some(expr));
+        }
+
+        // Any single-name initializers in the left side are only valid if the
+        // left side turns out to be a pattern, so they are tracked separately
+        // from the ones that came before it.
+        let outer_cover_init = mem::replace(&mut self.cover_init, false);
+
+        let left = match self.parse_conditional_expression()? {
+            TokenResult::Some(left) => left,
+            TokenResult::None => {
+                self.cover_init = outer_cover_init;
+                return Ok(TokenResult::None);
+            }
+        };
+
+        self.parse_assignment_rest(start, left, outer_cover_init)
     }
-    pub fn parse_assignment_expression(&mut self) -> OptResult<()> {
-        if let TokenResult::Some(_) = self.parse_yield_expression()? {
-            return Ok(TokenResult::Some(()));
+
+    // The assignment expressions that are known from their first tokens,
+    // which are yields and arrow functions that can't be parsed as an
+    // expression first.
+    #[inline(never)]
+    fn parse_assignment_keyword_or_arrow(&mut self, start: tokenizer::Position, no_arrow_return_type: bool) -> OptResult<alias::Expression<'code>> {
+        if let TokenResult::Some(expr) = self.parse_yield_expression()? {
+            return Ok(TokenResult::Some(expr));
         }
 
         let flags = self.flags;
 
         let maybe_async_arrow = if let Some(&LookaheadResult {
             line: false,
-            token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name })
+            token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+            ..
         }) = self.ident_lookahead() {
-            if is_binding_identifier(&flags, name) {
-                true
-            } else {
-                false
-            }
+            is_binding_identifier(&flags, name)
         } else {
             false
         };
@@ -46,22 +146,32 @@ where
                 if !self.no_line_terminator() {
                     unreachable!();
                 }
-                eat_value!(self.binding_identifier());
+                let id = eat_value!(self.parse_binding_identifier());
                 if !self.no_line_terminator() {
                     bail!("Unexpected line terminator between async arrow argument and arrow");
                 }
 
                 eat_value!(self.punc(tokens::PunctuatorToken::Arrow));
-                eat_value!(self.reify_arrow(())?);
 
-                return Ok(TokenResult::Some(()));
+                let params = functions::ArrowFunctionParams::from(id);
+                let arrow = eat_value!(self.reify_arrow(start, functions::ArrowFunctionKind::Async, params)?);
+
+                return Ok(TokenResult::Some(arrow));
             }
         }
 
-        // TODO: Alternatively this can kick off the cover lookahead here then skip reify
+        if self.has_types() {
+            return self.parse_typed_arrow(start, !no_arrow_return_type);
+        }
 
-        let left = try_value!(self.parse_conditional_expression()?);
+        Ok(TokenResult::None)
+    }
 
+    // The operator and right side of an assignment or arrow function, kept out
+    // of parse_assignment_expression so that converting the left side into a
+    // pattern doesn't add to the frame of every level of nested expressions.
+    #[inline(never)]
+    fn parse_assignment_rest(&mut self, start: tokenizer::Position, left: alias::Expression<'code>, outer_cover_init: bool) -> OptResult<alias::Expression<'code>> {
         #[derive(Debug)]
         enum Reify {
             Arrow,
@@ -94,157 +204,398 @@ where
             tokens::Token::Punctuator(tokens::PunctuatorToken::AmpEq) => Reify::AmpEq,
             tokens::Token::Punctuator(tokens::PunctuatorToken::CaretEq) => Reify::CaretEq,
             tokens::Token::Punctuator(tokens::PunctuatorToken::BarEq) => Reify::BarEq,
-            tokens::Token::Punctuator(tokens::PunctuatorToken::StarStarEq) => Reify::StarStarEq,
-            _ => return Ok(TokenResult::Some(())),
+            tokens::Token::Punctuator(tokens::PunctuatorToken::StarStarEq) => {
+                self.require_version(EcmaVersion::Es2016, "Exponentiation")?;
+                Reify::StarStarEq
+            }
+            _ => {
+                self.cover_init |= outer_cover_init;
+                return Ok(TokenResult::Some(left));
+            }
         };
 
         self.pop();
+        let token = self.token_data();
 
-        eat_value!(match t {
+        let expr = match t {
             Reify::Arrow => {
                 // TODO: No LineTerminator allowed before arrow.
-                // TODO: This needs to know if the left was an "async(foo)" to decide if "await" is allowed.
-                self.reify_arrow(left)?
-            }
-            Reify::Eq => self.reify_assignment(left, tokens::PunctuatorToken::Eq)?,
-            Reify::StarEq => self.reify_assignment(left, tokens::PunctuatorToken::StarEq)?,
-            Reify::SlashEq => self.reify_assignment(left, tokens::PunctuatorToken::SlashEq)?,
-            Reify::PercentEq => self.reify_assignment(left, tokens::PunctuatorToken::PercentEq)?,
-            Reify::PlusEq => self.reify_assignment(left, tokens::PunctuatorToken::PlusEq)?,
-            Reify::MinusEq => self.reify_assignment(left, tokens::PunctuatorToken::MinusEq)?,
-            Reify::LAngleAngleEq => self.reify_assignment(left, tokens::PunctuatorToken::LAngleAngleEq)?,
-            Reify::RAngleAngleEq => self.reify_assignment(left, tokens::PunctuatorToken::RAngleAngleEq)?,
-            Reify::RAngleAngleAngleEq => self.reify_assignment(left, tokens::PunctuatorToken::RAngleAngleAngleEq)?,
-            Reify::AmpEq => self.reify_assignment(left, tokens::PunctuatorToken::AmpEq)?,
-            Reify::CaretEq => self.reify_assignment(left, tokens::PunctuatorToken::CaretEq)?,
-            Reify::BarEq => self.reify_assignment(left, tokens::PunctuatorToken::BarEq)?,
-            Reify::StarStarEq => self.reify_assignment(left, tokens::PunctuatorToken::StarStarEq)?,
-        });
+                self.cover_init = outer_cover_init;
 
-        Ok(TokenResult::Some(()))
+                let (kind, params) = cover::arrow_params(left)?;
+                eat_value!(self.reify_arrow(start, kind, params)?)
+            }
+            Reify::Eq => {
+                let destructuring = matches!(left, alias::Expression::Object(_) | alias::Expression::Array(_));
+                if destructuring {
+                    self.cover_init = outer_cover_init;
+                } else {
+                    self.cover_init |= outer_cover_init;
+                }
+
+                let left = self.complex_assign_target(start, left)?;
+                let right = eat_value!(self.reify_assignment()?);
+
+                self.located(start, expression::AssignmentExpression {
+                    left: NodeBox::new(left),
+                    token_eq: token,
+                    right: NodeBox::new(right),
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            _ => {
+                self.cover_init |= outer_cover_init;
+
+                let left = self.simple_assign_target(start, left)?;
+                let right = eat_value!(self.reify_assignment()?);
+
+                match t {
+                    Reify::StarEq => binary_node!(self, start, MultiplyAssignExpression, token_stareq: token, left, right),
+                    Reify::SlashEq => binary_node!(self, start, DivideAssignExpression, token_slasheq: token, left, right),
+                    Reify::PercentEq => binary_node!(self, start, ModulusAssignExpression, token_percenteq: token, left, right),
+                    Reify::PlusEq => binary_node!(self, start, AddAssignExpression, token_pluseq: token, left, right),
+                    Reify::MinusEq => binary_node!(self, start, SubtractAssignExpression, token_minuseq: token, left, right),
+                    Reify::LAngleAngleEq => binary_node!(self, start, LeftShiftAssignExpression, token_langleangleeq: token, left, right),
+                    Reify::RAngleAngleEq => binary_node!(self, start, RightShiftAssignExpression, token_rangleangleeq: token, left, right),
+                    Reify::RAngleAngleAngleEq => binary_node!(self, start, RightShiftSignedAssignExpression, token_rangleangleangleeq: token, left, right),
+                    Reify::AmpEq => binary_node!(self, start, BitAndAssignExpression, token_ampeq: token, left, right),
+                    Reify::CaretEq => binary_node!(self, start, BitXorAssignExpression, token_careteq: token, left, right),
+                    Reify::BarEq => binary_node!(self, start, BitOrAssignExpression, token_bareq: token, left, right),
+                    Reify::StarStarEq => binary_node!(self, start, PowerAssignExpression, token_starstareq: token, left, right),
+                    Reify::Arrow | Reify::Eq => unreachable!(),
+                }
+            }
+        };
+
+        Ok(TokenResult::Some(expr))
     }
-    fn reify_arrow(&mut self, _left: ()) -> OptResult<()> {
-        self.expect_expression();
+    // Arrow functions with TypeScript's types, like "<T>(a: T): T => a", whose
+    // parameters can't be parsed as an expression first.
+    fn parse_typed_arrow(&mut self, start: tokenizer::Position, return_type: bool) -> OptResult<alias::Expression<'code>> {
+        let is_async = self.is_word_before(
+            "async",
+            &[tokens::PunctuatorToken::ParenOpen, tokens::PunctuatorToken::LAngle],
+        );
+        let is_params = matches!(
+            *self.token(),
+            tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) |
+            tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle)
+        );
+        if !is_async && !is_params {
+            return Ok(TokenResult::None);
+        }
+
+        let params = try_value!(self.speculate(|p| {
+            if is_async {
+                p.pop();
+            }
+            let params = try_value!(p.parse_typed_function_params(return_type)?);
+            if !p.no_line_terminator() {
+                return Ok(TokenResult::None);
+            }
+            try_value!(p.punc(tokens::PunctuatorToken::Arrow));
 
-        Ok(try_sequence!(
-            self.parse_block_statement()?,
-            self.parse_assignment_expression()?,
-        ))
+            Ok(TokenResult::Some(params))
+        }));
+
+        let kind = if is_async {
+            functions::ArrowFunctionKind::Async
+        } else {
+            functions::ArrowFunctionKind::Normal
+        };
+        self.reify_arrow(start, kind, params.into())
     }
-    fn reify_assignment(&mut self, _left: (), _op: tokens::PunctuatorToken) -> OptResult<()> {
+    fn reify_arrow(
+        &mut self,
+        start: tokenizer::Position,
+        kind: functions::ArrowFunctionKind,
+        params: functions::ArrowFunctionParams<'code>,
+    ) -> OptResult<alias::Expression<'code>> {
         self.expect_expression();
 
-        // println!("starting expr");
-        eat_value!(self.parse_assignment_expression()?);
+        if let functions::ArrowFunctionKind::Async = kind {
+            self.require_version(EcmaVersion::Es2017, "Async function")?;
+        }
+        let mut parser = if let functions::ArrowFunctionKind::Async = kind {
+            self.with(Flag::Await)
+        } else {
+            self.with(Flag::Noop)
+        };
+
+        let body_start = parser.start();
+        let body = if let TokenResult::Some(body) = parser.parse_function_body()? {
+            functions::ArrowFunctionBody::Block(body)
+        } else {
+            let expr = eat_value!(parser.parse_assignment_expression()?);
 
-        // println!("ending expr");
-        Ok(TokenResult::Some(()))
+            parser.located(body_start, functions::ArrowFunctionExpressionBody::from(expr)).into()
+        };
+        let block = match body {
+            functions::ArrowFunctionBody::Block(ref body) => Some(body),
+            _ => None,
+        };
+        parser.check_params(start, &params, true, block)?;
+
+        Ok(TokenResult::Some(parser.located(start, functions::ArrowFunctionExpression {
+            kind,
+            params,
+            body,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+    fn reify_assignment(&mut self) -> OptResult<alias::Expression<'code>> {
+        self.expect_expression();
+
+        Ok(TokenResult::Some(eat_value!(self.parse_assignment_expression()?)))
     }
 
-    fn parse_yield_expression(&mut self) -> OptResult<()> {
+    fn parse_yield_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("yield"));
+        let token_yield = self.token_data();
 
+        self.expect_expression();
         if self.no_line_terminator() {
-            self.expect_expression();
-            opt_value!(self.punc(tokens::PunctuatorToken::Star));
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Star) {
+                let token_star = self.token_data();
+                self.expect_expression();
+                let value = eat_value!(self.parse_assignment_expression()?);
+
+                return Ok(TokenResult::Some(self.located(start, expression::DelegateYieldExpression {
+                    token_yield,
+                    token_star,
+                    value: NodeBox::new(value),
+                    position: None,
+                    comments: None,
+                }).into()));
+            }
 
-            self.expect_expression();
-            opt_value!(self.parse_expression()?);
+            if let TokenResult::Some(value) = self.parse_assignment_expression()? {
+                return Ok(TokenResult::Some(self.located(start, expression::YieldExpression {
+                    token_yield,
+                    value: Some(NodeBox::new(value)),
+                    position: None,
+                    comments: None,
+                }).into()));
+            }
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, expression::YieldExpression {
+            token_yield,
+            value: None,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+    fn parse_conditional_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        let test = try_value!(self.parse_logical_or_expression()?);
+
+        if let tokens::Token::Punctuator(tokens::PunctuatorToken::Question) = *self.token() {
+            return self.parse_conditional_rest(start, test);
+        }
+        Ok(TokenResult::Some(test))
     }
-    fn parse_conditional_expression(&mut self) -> OptResult<()> {
-        try_value!(self.parse_logical_or_expression()?);
 
+    #[inline(never)]
+    fn parse_conditional_rest(&mut self, start: tokenizer::Position, test: alias::Expression<'code>) -> OptResult<alias::Expression<'code>> {
         if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Question) {
+            let token_question = self.token_data();
             self.expect_expression();
-            eat_value!(self.with(Flag::In).parse_assignment_expression()?);
+            let consequent = eat_value!(self.parse_conditional_consequent()?);
             eat_value!(self.punc(tokens::PunctuatorToken::Colon));
+            let token_colon = self.token_data();
 
             self.expect_expression();
-            eat_value!(self.parse_assignment_expression()?);
+            let alternate = eat_value!(self.parse_assignment_expression()?);
+
+            return Ok(TokenResult::Some(self.located(start, expression::ConditionalExpression {
+                test: NodeBox::new(test),
+                token_question,
+                consequent: NodeBox::new(consequent),
+                token_colon,
+                alternate: NodeBox::new(alternate),
+                position: None,
+                comments: None,
+            }).into()));
         }
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(test))
     }
 
-    fn parse_logical_or_expression(&mut self) -> OptResult<()> {
+    fn parse_conditional_consequent(&mut self) -> OptResult<alias::Expression<'code>> {
+        if !self.has_types() {
+            return self.with(Flag::In).parse_assignment_expression();
+        }
+
+        // In "a ? (b): c => d", the ":" could start the return type of an
+        // arrow function, so that is only tried if the ":" is still found
+        // after it.
+        if let TokenResult::Some(consequent) = self.speculate(|p| {
+            let consequent = try_value!(p.with(Flag::In).parse_assignment_expression()?);
+            match *p.token() {
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Colon) => Ok(TokenResult::Some(consequent)),
+                _ => Ok(TokenResult::None),
+            }
+        }) {
+            return Ok(TokenResult::Some(consequent));
+        }
+
+        self.no_arrow_return_type = true;
+        self.with(Flag::In).parse_assignment_expression()
+    }
+
+    fn parse_logical_or_expression(&mut self) -> OptResult<alias::Expression<'code>> {
         self.parse_fancy(0)
     }
-    fn parse_fancy(&mut self, mut precedence: u8) -> OptResult<()> {
-        try_value!(self.parse_exponential_expression()?);
+    #[inline(never)]
+    fn parse_binary_type(&mut self, start: tokenizer::Position, left: alias::Expression<'code>, op: Binary) -> Result<alias::Expression<'code>> {
+        let token = self.token_data();
+        let value = NodeBox::new(eat_value!(self.parse_type()?));
+
+        Ok(if let Binary::As = op {
+            self.located(start, typescript::AsExpression {
+                expression: NodeBox::new(left),
+                token_as: token,
+                value,
+                position: None,
+                comments: None,
+            }).into()
+        } else {
+            self.located(start, typescript::SatisfiesExpression {
+                expression: NodeBox::new(left),
+                token_satisfies: token,
+                value,
+                position: None,
+                comments: None,
+            }).into()
+        })
+    }
+    fn parse_fancy(&mut self, precedence: u8) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        let mut left = try_value!(self.parse_exponential_expression()?);
 
         let allow_in = self.flags.allow_in;
+        let typescript = self.options.extensions.typescript;
 
         loop {
-            let new_precedence = match *self.token() {
-                tokens::Token::Punctuator(tokens::PunctuatorToken::BarBar) => 1,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::AmpAmp) => 2,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Bar) => 3,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Caret) => 4,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Amp) => 5,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::EqEq) => 6,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::EqEqEq) => 6,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::ExclamEq) => 6,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::ExclamEqEq) => 6,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) => 7,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngle) => 7,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngleEq) => 7,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleEq) => 7,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngleAngle) => 8,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngle) => 8,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngleAngle) => 8,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Plus) => 9,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Minus) => 9,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Star) => 10,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Percent) => 10,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::Slash) => 10,
-                tokens::Token::Punctuator(tokens::PunctuatorToken::StarStar) => 11,
+            let same_line = self.no_line_terminator();
+            let (new_precedence, op) = match *self.token() {
+                tokens::Token::Punctuator(tokens::PunctuatorToken::BarBar) => (1, Binary::Or),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::AmpAmp) => (2, Binary::And),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Bar) => (3, Binary::BitOr),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Caret) => (4, Binary::BitXor),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Amp) => (5, Binary::BitAnd),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::EqEq) => (6, Binary::Equal),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::EqEqEq) => (6, Binary::StrictEqual),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::ExclamEq) => (6, Binary::NotEqual),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::ExclamEqEq) => (6, Binary::StrictNotEqual),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) => (7, Binary::LessThan),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngle) => (7, Binary::GreaterThan),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngleEq) => (7, Binary::LessThanEqual),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleEq) => (7, Binary::GreaterThanEqual),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngleAngle) => (8, Binary::LeftShift),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngle) => (8, Binary::RightShift),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngleAngle) => (8, Binary::RightShiftSigned),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Plus) => (9, Binary::Add),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Minus) => (9, Binary::Subtract),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Star) => (10, Binary::Multiply),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Percent) => (10, Binary::Modulus),
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Slash) => (10, Binary::Divide),
 
                 tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => {
                     match &**name {
-                        "in" if allow_in => 7,
-                        "instanceof" => 7,
+                        "in" if allow_in => (7, Binary::In),
+                        "instanceof" => (7, Binary::Instanceof),
+                        "as" if typescript && same_line => (7, Binary::As),
+                        "satisfies" if typescript && same_line => (7, Binary::Satisfies),
                         _ => break,
                     }
                 }
                 _ => break,
             };
 
-            self.pop();
-
-            self.expect_expression();
-
+            // All binary operators are left-associative, so anything with
+            // the same precedence is left for the caller to handle.
+            if new_precedence <= precedence {
+                break;
+            }
 
-            // if new_precedence === 12 {
-            //     self.parse_fancy()
-            // }
-            if new_precedence >= precedence && new_precedence != 11 {
-                precedence = new_precedence;
+            self.pop();
+            let token = self.token_data();
 
-                eat_value!(self.parse_unary_expression()?);
-            } else {
-                eat_value!(self.parse_fancy(new_precedence)?);
+            // The right side of these is a type rather than an expression.
+            if let Binary::As | Binary::Satisfies = op {
+                left = self.parse_binary_type(start, left, op)?;
+                continue;
             }
+
+            self.expect_expression();
+            let right = eat_value!(self.parse_fancy(new_precedence)?);
+
+            left = match op {
+                Binary::Or => binary_node!(self, start, OrExpression, token_barbar: token, left, right),
+                Binary::And => binary_node!(self, start, AndExpression, token_ampamp: token, left, right),
+                Binary::BitOr => binary_node!(self, start, BitOrExpression, token_bar: token, left, right),
+                Binary::BitXor => binary_node!(self, start, BitXorExpression, token_caret: token, left, right),
+                Binary::BitAnd => binary_node!(self, start, BitAndExpression, token_amp: token, left, right),
+                Binary::Equal => binary_node!(self, start, EqualExpression, token_eqeq: token, left, right),
+                Binary::StrictEqual => binary_node!(self, start, StrictEqualExpression, token_eqeqeq: token, left, right),
+                Binary::NotEqual => binary_node!(self, start, NotEqualExpression, token_neeq: token, left, right),
+                Binary::StrictNotEqual => binary_node!(self, start, StrictNotEqualExpression, token_neeqeq: token, left, right),
+                Binary::LessThan => binary_node!(self, start, LessThanExpression, token_langle: token, left, right),
+                Binary::GreaterThan => binary_node!(self, start, GreaterThanExpression, token_rangle: token, left, right),
+                Binary::LessThanEqual => binary_node!(self, start, LessThanEqualExpression, token_langleeq: token, left, right),
+                Binary::GreaterThanEqual => binary_node!(self, start, GreaterThanEqualExpression, token_rangleeq: token, left, right),
+                Binary::In => binary_node!(self, start, InExpression, token_in: token, left, right),
+                Binary::Instanceof => binary_node!(self, start, InstanceofExpression, token_instanceof: token, left, right),
+                Binary::LeftShift => binary_node!(self, start, LeftShiftExpression, token_langleangle: token, left, right),
+                Binary::RightShift => binary_node!(self, start, RightShiftExpression, token_rangleangle: token, left, right),
+                Binary::RightShiftSigned => binary_node!(self, start, RightShiftSignedExpression, token_rangleangleangle: token, left, right),
+                Binary::Add => binary_node!(self, start, AddExpression, token_plus: token, left, right),
+                Binary::Subtract => binary_node!(self, start, SubtractExpression, token_minus: token, left, right),
+                Binary::Multiply => binary_node!(self, start, MultiplyExpression, token_star: token, left, right),
+                Binary::Modulus => binary_node!(self, start, ModulusExpression, token_percent: token, left, right),
+                Binary::Divide => binary_node!(self, start, DivideExpression, token_slash: token, left, right),
+                Binary::As | Binary::Satisfies => unreachable!(),
+            };
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(left))
     }
 
-    fn parse_exponential_expression(&mut self) -> OptResult<()> {
-        try_value!(self.parse_unary_expression()?);
-
-        // TODO:
-        // if is_update_expression(expr) {
-            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::StarStar) {
-                self.expect_expression();
-                eat_value!(self.parse_exponential_expression()?);
+    fn parse_exponential_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        let left = try_value!(self.parse_unary_expression()?);
+
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::StarStar) {
+            self.require_version(EcmaVersion::Es2016, "Exponentiation")?;
+            let token = self.token_data();
+            match left {
+                alias::Expression::Delete(_) | alias::Expression::Void(_) |
+                alias::Expression::Typeof(_) | alias::Expression::Positive(_) |
+                alias::Expression::Negative(_) | alias::Expression::BitNegate(_) |
+                alias::Expression::Negate(_) | alias::Expression::Await(_) => {
+                    bail!("Unary expressions must be wrapped in parens to be the base of an exponent");
+                }
+                _ => {}
             }
-        // }
 
-        Ok(TokenResult::Some(()))
+            self.expect_expression();
+            let right = eat_value!(self.parse_exponential_expression()?);
+
+            return Ok(TokenResult::Some(binary_node!(self, start, PowerExpression, token_starstar: token, left, right)));
+        }
+
+        Ok(TokenResult::Some(left))
+    }
+    // The value of a prefix operator, which can be another prefix operator
+    // without passing through parse_assignment_expression's depth check.
+    pub fn parse_unary_operand(&mut self) -> OptResult<alias::Expression<'code>> {
+        self.nested(Self::parse_unary_expression)
     }
-    fn parse_unary_expression(&mut self) -> OptResult<()> {
+    fn parse_unary_expression(&mut self) -> OptResult<alias::Expression<'code>> {
         enum UnaryType {
             Delete,
             Void,
@@ -254,10 +605,13 @@ where
             Tilde,
             Exclam,
             Await,
+            TypeAssertion,
             Unknown,
         }
 
+        let type_assertions = self.options.extensions.typescript && !self.options.extensions.jsx;
         let t = match *self.token() {
+            tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) if type_assertions => UnaryType::TypeAssertion,
             tokens::Token::Punctuator(tokens::PunctuatorToken::Plus) => UnaryType::Plus,
             tokens::Token::Punctuator(tokens::PunctuatorToken::Minus) => UnaryType::Minus,
             tokens::Token::Punctuator(tokens::PunctuatorToken::Tilde) => UnaryType::Tilde,
@@ -274,7 +628,7 @@ where
             _ => UnaryType::Unknown,
         };
 
-        match t {
+        let expr = match t {
             UnaryType::Delete => eat_value!(self.parse_delete_expression()?),
             UnaryType::Void => eat_value!(self.parse_void_expression()?),
             UnaryType::Typeof => eat_value!(self.parse_typeof_expression()?),
@@ -282,210 +636,473 @@ where
             UnaryType::Minus => eat_value!(self.parse_minus_expression()?),
             UnaryType::Tilde => eat_value!(self.parse_tilde_expression()?),
             UnaryType::Exclam => eat_value!(self.parse_exclam_expression()?),
-            UnaryType::Await => eat_value!(self.parse_await_expression()?),
+            UnaryType::Await => {
+                if let TokenResult::Some(expr) = self.parse_await_expression()? {
+                    expr
+                } else {
+                    try_value!(self.parse_update_expression()?)
+                }
+            }
+            UnaryType::TypeAssertion => eat_value!(self.parse_type_assertion()?).into(),
             UnaryType::Unknown => try_value!(self.parse_update_expression()?),
-        }
+        };
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(expr))
     }
-    fn parse_delete_expression(&mut self) -> OptResult<()> {
+    fn parse_delete_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("delete"));
+        let token = self.token_data();
 
         self.expect_expression();
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        eat_value!(self.parse_unary_expression()?);
-
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, DeleteExpression, token_delete: token, value)))
     }
-    fn parse_void_expression(&mut self) -> OptResult<()> {
+    fn parse_void_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("void"));
+        let token = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_unary_expression()?);
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, VoidExpression, token_void: token, value)))
     }
-    fn parse_typeof_expression(&mut self) -> OptResult<()> {
+    fn parse_typeof_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("typeof"));
+        let token = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_unary_expression()?);
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, TypeofExpression, token_typeof: token, value)))
     }
-    fn parse_plus_expression(&mut self) -> OptResult<()> {
+    fn parse_plus_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::Plus));
+        let token = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_unary_expression()?);
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, PositiveExpression, token_plus: token, value)))
     }
-    fn parse_minus_expression(&mut self) -> OptResult<()> {
+    fn parse_minus_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::Minus));
+        let token = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_unary_expression()?);
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, NegativeExpression, token_minus: token, value)))
     }
-    fn parse_tilde_expression(&mut self) -> OptResult<()> {
+    fn parse_tilde_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::Tilde));
+        let token = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_unary_expression()?);
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, BitNegateExpression, token_tilde: token, value)))
     }
-    fn parse_exclam_expression(&mut self) -> OptResult<()> {
+    fn parse_exclam_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::Exclam));
+        let token = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_unary_expression()?);
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, NegateExpression, token_exclam: token, value)))
     }
-    fn parse_await_expression(&mut self) -> OptResult<()> {
+    fn parse_await_expression(&mut self) -> OptResult<alias::Expression<'code>> {
         if !self.flags.allow_await {
             return Ok(TokenResult::None);
         }
 
+        let start = self.start();
         try_value!(self.keyword("await"));
+        let token = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_unary_expression()?);
+        let value = eat_value!(self.parse_unary_operand()?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(unary_node!(self, start, AwaitExpression, token_await: token, value)))
     }
-    fn parse_update_expression(&mut self) -> OptResult<()> {
-        let op = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::PlusPlus) {
-            true
-        } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::MinusMinus) {
-            true
-        } else {
-            false
-        };
+    fn parse_update_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
 
-        if op {
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::PlusPlus) {
+            let token_plusplus = self.token_data();
             self.expect_expression();
-            eat_value!(self.parse_update_expression()?);
-        } else {
-            try_value!(self.parse_left_hand_expression(true)?);
+            let value = eat_value!(self.parse_unary_operand()?);
+            let value = self.simple_assign_target(start, value)?;
+
+            return Ok(TokenResult::Some(self.located(start, expression::PreIncrementExpression {
+                token_plusplus,
+                value,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::MinusMinus) {
+            let token_minusminus = self.token_data();
+            self.expect_expression();
+            let value = eat_value!(self.parse_unary_operand()?);
+            let value = self.simple_assign_target(start, value)?;
+
+            return Ok(TokenResult::Some(self.located(start, expression::PreDecrementExpression {
+                token_minusminus,
+                value,
+                position: None,
+                comments: None,
+            }).into()));
+        }
 
-            if self.no_line_terminator() {
-                if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::PlusPlus) {
+        let value = try_value!(self.parse_left_hand_expression(true)?);
 
-                } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::MinusMinus) {
+        self.parse_update_suffix(start, value)
+    }
 
-                }
+    #[inline(never)]
+    fn parse_update_suffix(&mut self, start: tokenizer::Position, value: alias::Expression<'code>) -> OptResult<alias::Expression<'code>> {
+        if self.no_line_terminator() {
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::PlusPlus) {
+                return Ok(TokenResult::Some(self.located(start, expression::PostIncrementExpression {
+                    value: self.simple_assign_target(start, value)?,
+                    tokens_separator: Default::default(),
+                    token_plusplus: self.token_data(),
+                    position: None,
+                    comments: None,
+                }).into()));
+            } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::MinusMinus) {
+                return Ok(TokenResult::Some(self.located(start, expression::PostDecrementExpression {
+                    value: self.simple_assign_target(start, value)?,
+                    tokens_separator: Default::default(),
+                    token_minusminus: self.token_data(),
+                    position: None,
+                    comments: None,
+                }).into()));
             }
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(value))
+    }
+
+    // The target of an assignment or update, which in strict mode code can't
+    // be "eval" or "arguments".
+    pub fn simple_assign_target(&self, start: tokenizer::Position, expr: alias::Expression<'code>) -> Result<patterns::LeftHandSimpleAssign<'code>> {
+        self.check_strict_target(start, &expr)?;
+        cover::simple_assign_target(expr)
+    }
+    pub fn complex_assign_target(&self, start: tokenizer::Position, expr: alias::Expression<'code>) -> Result<patterns::LeftHandComplexAssign<'code>> {
+        self.check_strict_target(start, &expr)?;
+        cover::complex_assign_target(expr)
     }
-    fn parse_left_hand_expression(&mut self, allow_call: bool) -> OptResult<()> {
-        if let TokenResult::Some(_) = self.keyword("new") {
-            eat_value!(self.parse_left_hand_expression(false)?);
+    fn check_strict_target(&self, start: tokenizer::Position, expr: &alias::Expression<'code>) -> Result<()> {
+        match *expr {
+            alias::Expression::Binding(ref id) if self.flags.is_strict && (id.value == "eval" || id.value == "arguments") => {
+                Err(self.locate_error(start, format_err!("\"{}\" can't be assigned to in strict mode code", id.value)))
+            }
+            alias::Expression::Parenthesized(ref paren) => self.check_strict_target(start, &paren.expr),
+            _ => Ok(()),
+        }
+    }
+
+    fn parse_left_hand_expression(&mut self, allow_call: bool) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+
+        let object = if let TokenResult::Some(_) = self.keyword("new") {
+            let token_new = self.token_range();
+
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Period) {
+                let token_period = self.token_data();
+                eat_value!(self.keyword("target"));
+
+                self.located(start, expression::NewTargetExpression {
+                    token_new: token_new.into(),
+                    token_period,
+                    token_target: self.token_data(),
+                    position: None,
+                    comments: None,
+                }).into()
+            } else {
+                let callee = eat_value!(self.nested(|p| p.parse_left_hand_expression(false))?);
+
+                if let TokenResult::Some(arguments) = self.parse_call_arguments()? {
+                    expression::NewExpression::from(self.located(start, expression::NormalNewExpression {
+                        token_new: token_new.into(),
+                        callee: NodeBox::new(callee),
+                        arguments,
+                        position: None,
+                        comments: None,
+                    })).into()
+                } else {
+                    expression::NewExpression::from(self.located(start, expression::EmptyNewExpression {
+                        token_new: token_new.into(),
+                        callee: NodeBox::new(callee),
+                        position: None,
+                        comments: None,
+                    })).into()
+                }
+            }
         } else {
-            if let TokenResult::None = try_sequence!(
+            try_value!(try_sequence!(
                 self.parse_primary_expression()?,
-                self.parse_meta_property_expression()?,
                 self.parse_super_expression()?,
-            ) {
-                return Ok(TokenResult::None);
-            }
-        }
+            ))
+        };
 
-        // println!("starting member");
+        self.parse_left_hand_suffixes(start, object, allow_call)
+    }
 
+    // The member, call and template suffixes are parsed outside of
+    // parse_left_hand_expression so that their locals don't add to the stack
+    // frame that every level of nested expressions recurses through.
+    #[inline(never)]
+    fn parse_left_hand_suffixes(&mut self, start: tokenizer::Position, mut object: alias::Expression<'code>, allow_call: bool) -> OptResult<alias::Expression<'code>> {
+        let experimental = self.options.extensions.experimental_operators;
+        let typescript = self.options.extensions.typescript;
+        let types = self.has_types();
         loop {
             enum LeftType {
                 Ident,
                 Call,
                 Computed,
                 Template,
+                Bind,
+                NonNull,
+                TypeArguments,
             }
 
+            let same_line = self.no_line_terminator();
             let t = match *self.token() {
                 tokens::Token::Punctuator(tokens::PunctuatorToken::Period) => LeftType::Ident,
                 tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen) => LeftType::Computed,
                 tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) => LeftType::Call,
                 tokens::Token::Template(tokens::TemplateToken { format: tokens::TemplateFormat::NoSubstitution, .. }) |
                 tokens::Token::Template(tokens::TemplateToken { format: tokens::TemplateFormat::Head, .. }) => LeftType::Template,
+                tokens::Token::Punctuator(tokens::PunctuatorToken::ColonColon) if experimental => LeftType::Bind,
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Exclam) if typescript && same_line => LeftType::NonNull,
+                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) if types => LeftType::TypeArguments,
                 _ => break,
             };
 
-            match t {
+            object = match t {
                 LeftType::Ident => {
-                    self.pop();
-                    // eat_value!(self.punc(tokens::PunctuatorToken::Period));
-                    eat_value!(self.with(Flag::In).identifier());
+                    let property = eat_value!(self.parse_identifier_property_access()?);
+
+                    self.located(start, expression::MemberExpression {
+                        object: NodeBox::new(object),
+                        property: property.into(),
+                        position: None,
+                        comments: None,
+                    }).into()
+                }
+                LeftType::Call if allow_call => {
+                    let arguments = eat_value!(self.parse_call_arguments()?);
+
+                    self.located(start, expression::CallExpression {
+                        callee: NodeBox::new(object),
+                        arguments,
+                        position: None,
+                        comments: None,
+                    }).into()
                 }
-                LeftType::Call if allow_call => eat_value!(self.parse_call_arguments()?),
                 LeftType::Computed => {
-                    self.pop();
-                    // eat_value!(self.punc(tokens::PunctuatorToken::SquareOpen));
-                    eat_value!(self.with(Flag::In).parse_expression()?);
-                    eat_value!(self.punc(tokens::PunctuatorToken::SquareClose));
-                },
+                    let property = eat_value!(self.parse_computed_property_access()?);
+
+                    self.located(start, expression::MemberExpression {
+                        object: NodeBox::new(object),
+                        property: property.into(),
+                        position: None,
+                        comments: None,
+                    }).into()
+                }
 
                 // TODO: Not allowed for 'super'.
-                LeftType::Template => eat_value!(self.parse_template_literal_expression()?),
+                LeftType::Template => {
+                    let template = eat_value!(self.parse_template_literal(true)?);
+
+                    self.located(start, expression::TaggedTemplateLiteral {
+                        tag: NodeBox::new(object),
+                        tokens_sep: Default::default(),
+                        template,
+                        position: None,
+                        comments: None,
+                    }).into()
+                }
+                LeftType::Bind => {
+                    eat_value!(self.punc(tokens::PunctuatorToken::ColonColon));
+                    self.expect_expression();
+                    let callee = eat_value!(self.nested(|p| p.parse_left_hand_expression(false))?);
+
+                    self.located(start, expression::BindExpression {
+                        left: NodeBox::new(object),
+                        token_coloncolon: Default::default(),
+                        right: NodeBox::new(callee),
+                        position: None,
+                        comments: None,
+                    }).into()
+                }
+                LeftType::NonNull => {
+                    eat_value!(self.punc(tokens::PunctuatorToken::Exclam));
+                    let token_exclam = self.token_data();
+
+                    self.located(start, typescript::NonNullExpression {
+                        expression: NodeBox::new(object),
+                        token_exclam,
+                        position: None,
+                        comments: None,
+                    }).into()
+                }
+                LeftType::TypeArguments => {
+                    let type_arguments = match self.parse_expression_type_arguments() {
+                        TokenResult::Some(type_arguments) => type_arguments,
+                        TokenResult::None => break,
+                    };
+
+                    self.located(start, typescript::InstantiationExpression {
+                        expression: NodeBox::new(object),
+                        type_arguments,
+                        position: None,
+                        comments: None,
+                    }).into()
+                }
                 _ => break,
-            }
+            };
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(object))
     }
 
+    fn parse_identifier_property_access(&mut self) -> OptResult<expression::IdentifierPropertyAccess<'code>> {
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::Period));
+        let token_period = self.token_data();
+
+        let id = eat_value!(self.parse_property_identifier());
+
+        Ok(TokenResult::Some(self.located(start, expression::IdentifierPropertyAccess {
+            token_period,
+            tokens_separator: Default::default(),
+            id,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    fn parse_computed_property_access(&mut self) -> OptResult<expression::ComputedPropertyAccess<'code>> {
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::SquareOpen));
+        let token_square_l = self.token_data();
 
-    fn parse_call_arguments(&mut self) -> OptResult<()> {
+        self.expect_expression();
+        let expr = eat_value!(self.with(Flag::In).parse_expression()?);
+        eat_value!(self.punc(tokens::PunctuatorToken::SquareClose));
+
+        Ok(TokenResult::Some(self.located(start, expression::ComputedPropertyAccess {
+            tokens_prefix: Default::default(),
+            token_square_l,
+            expression: NodeBox::new(expr),
+            token_square_r: self.token_data(),
+            position: None,
+            comments: None,
+        })))
+    }
+
+    pub fn parse_call_arguments(&mut self) -> OptResult<expression::CallArguments<'code>> {
+        let start = self.start();
         let mut parser = self.with(Flag::In);
 
         try_value!(parser.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = parser.token_data();
 
+        let mut args = vec![];
+        let mut last_arg = None;
         loop {
             parser.expect_expression();
 
-            if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Ellipsis) {
+            let arg_start = parser.start();
+            let arg: expression::CallArgument = if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Ellipsis) {
+                let token_ellipsis = parser.token_data();
                 parser.expect_expression();
-                eat_value!(parser.parse_assignment_expression()?);
-
-                if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Comma) {
-                } else {
-                    break;
-                }
-            }
+                let expr = eat_value!(parser.parse_assignment_expression()?);
+
+                parser.located(arg_start, expression::CallArgumentSpread {
+                    token_ellipsis,
+                    expression: NodeBox::new(expr),
+                    position: None,
+                    comments: None,
+                }).into()
+            } else if let TokenResult::Some(expr) = parser.parse_assignment_expression()? {
+                parser.located(arg_start, expression::CallArgumentExpression {
+                    tokens_prefix: Default::default(),
+                    expression: NodeBox::new(expr),
+                    position: None,
+                    comments: None,
+                }).into()
+            } else {
+                break;
+            };
 
-            if let TokenResult::Some(_) = parser.parse_assignment_expression()? {
-                if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Comma) {
-                } else {
-                    break;
-                }
+            if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Comma) {
+                args.push((arg, parser.token_data()));
             } else {
+                last_arg = Some(arg);
                 break;
             }
         }
 
         eat_value!(parser.punc(tokens::PunctuatorToken::ParenClose));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(parser.located(start, expression::CallArguments {
+            token_paren_l,
+            args,
+            last_arg,
+            token_paren_r: parser.token_data(),
+            position: None,
+            comments: None,
+        })))
     }
 
-    fn parse_meta_property_expression(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("new"));
-        eat_value!(self.punc(tokens::PunctuatorToken::Period));
-        eat_value!(self.keyword("target"));
-
-        Ok(TokenResult::Some(()))
-    }
-    fn parse_super_expression(&mut self) -> OptResult<()> {
+    fn parse_super_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("super"));
+        let token_super = self.token_data();
+
+        if let TokenResult::Some(property) = self.parse_identifier_property_access()? {
+            return Ok(TokenResult::Some(self.located(start, expression::SuperMemberExpression {
+                token_super,
+                property: property.into(),
+                position: None,
+                comments: None,
+            }).into()));
+        }
+        if let TokenResult::Some(property) = self.parse_computed_property_access()? {
+            return Ok(TokenResult::Some(self.located(start, expression::SuperMemberExpression {
+                token_super,
+                property: property.into(),
+                position: None,
+                comments: None,
+            }).into()));
+        }
 
-        Ok(TokenResult::Some(()))
+        let arguments = eat_value!(self.parse_call_arguments()?);
+
+        Ok(TokenResult::Some(self.located(start, expression::SuperCallExpression {
+            token_super,
+            arguments,
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_primary_expression(&mut self) -> OptResult<()> {
+    fn parse_primary_expression(&mut self) -> OptResult<alias::Expression<'code>> {
         enum PrimaryType {
             This,
             Ident,
@@ -502,9 +1119,16 @@ where
 
             Function,
             Class,
+
+            Jsx,
+            BindMember,
+            Do,
         }
 
         let flags = self.flags;
+        let jsx = self.options.extensions.jsx;
+        let decorators = self.options.extensions.decorators;
+        let experimental = self.options.extensions.experimental_operators;
 
         let t = match *self.token() {
             tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => {
@@ -514,9 +1138,9 @@ where
                     "false" => PrimaryType::False,
                     "null" => PrimaryType::Null,
 
-                    // TODO: Ignores async functions
                     "function" => PrimaryType::Function,
                     "class" => PrimaryType::Class,
+                    "do" if experimental => PrimaryType::Do,
                     _ => {
                         if is_binding_identifier(&flags, name) {
                             PrimaryType::Ident
@@ -533,12 +1157,18 @@ where
             tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen) => PrimaryType::Array,
             tokens::Token::Punctuator(tokens::PunctuatorToken::CurlyOpen) => PrimaryType::Object,
             tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) => PrimaryType::Paren,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) if jsx => PrimaryType::Jsx,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::At) if decorators => PrimaryType::Class,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::ColonColon) if experimental => PrimaryType::BindMember,
             _ => return Ok(TokenResult::None),
         };
 
-        match t {
+        let expr = match t {
             PrimaryType::This => eat_value!(self.parse_this_expression()?),
-            PrimaryType::Ident => eat_value!(self.parse_identifier_reference_expression()?),
+            PrimaryType::Ident => eat_value!(try_sequence!(
+                self.parse_identifier_reference_expression()?,
+                self.parse_function_expression()?.map(From::from),
+            )),
             PrimaryType::Number => eat_value!(self.parse_numeric_expression()?),
             PrimaryType::String => eat_value!(self.parse_string_expression()?),
             PrimaryType::True => eat_value!(self.parse_true_expression()?),
@@ -547,201 +1177,521 @@ where
             PrimaryType::Array => eat_value!(self.parse_array_literal_expression()?),
             PrimaryType::Object => eat_value!(self.parse_object_literal_expression()?),
             PrimaryType::Regex => eat_value!(self.parse_regular_expression_literal_expression()?),
-            PrimaryType::Template => eat_value!(self.parse_template_literal_expression()?),
+            PrimaryType::Template => eat_value!(self.parse_into_expression(|p| p.parse_template_literal(false))?),
             PrimaryType::Paren => eat_value!(self.parse_cover_parenthesized_expression()?),
-            PrimaryType::Function => eat_value!(self.parse_function_expression()?),
-            PrimaryType::Class => eat_value!(self.parse_class_expression()?),
-        }
+            PrimaryType::Function => eat_value!(self.parse_into_expression(Self::parse_function_expression)?),
+            PrimaryType::Class => eat_value!(self.parse_into_expression(Self::parse_class_expression)?),
+            PrimaryType::Jsx => eat_value!(self.parse_into_expression(Self::parse_jsx_element)?),
+            PrimaryType::BindMember => eat_value!(self.parse_bind_member_expression()?),
+            PrimaryType::Do => eat_value!(self.parse_do_expression()?),
+        };
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(expr))
+    }
 
+    // Parses a node that is larger than an expression and converts it in a
+    // separate frame, so the primary expression's frame, which every level
+    // of nested expressions passes through, doesn't need room for it.
+    #[inline(never)]
+    fn parse_into_expression<N, F>(&mut self, parse: F) -> OptResult<alias::Expression<'code>>
+    where
+        F: FnOnce(&mut Self) -> OptResult<N>,
+        alias::Expression<'code>: From<N>,
+    {
+        Ok(parse(self)?.map(From::from))
+    }
 
+    // ::foo.bar
+    #[inline(never)]
+    fn parse_bind_member_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::ColonColon));
 
-        // Ok(try_sequence!(
-        //     self.parse_this_expression()?,
-        //     self.parse_identifier_reference_expression()?,
-        //     self.parse_literal_expression()?,
-        //     self.parse_array_literal_expression()?,
-        //     self.parse_object_literal_expression()?,
-        //     self.parse_function_expression()?,
-        //     self.parse_class_expression()?,
-        //     self.parse_regular_expression_literal_expression()?,
-        //     self.parse_template_literal_expression()?,
-        //     self.parse_cover_parenthesized_expression()?,
-        // ))
+        self.expect_expression();
+        let value = eat_value!(self.nested(|p| p.parse_left_hand_expression(false))?);
+
+        Ok(TokenResult::Some(self.located(start, expression::BindMemberExpression {
+            token_coloncolon: Default::default(),
+            value: NodeBox::new(value),
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_this_expression(&mut self) -> OptResult<()> {
+    // do { foo; }
+    fn parse_do_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("do"));
+        let token_do = self.token_range();
+
+        let body = eat_value!(self.parse_block_statement()?);
+
+        Ok(TokenResult::Some(self.located(start, expression::DoExpression {
+            token_do: token_do.into(),
+            body,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    fn parse_this_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("this"));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, expression::ThisExpression {
+            token_this: self.token_data(),
+            position: None,
+            comments: None,
+        }).into()))
     }
-    fn parse_identifier_reference_expression(&mut self) -> OptResult<()> {
-        if let Some(ahead) = self.ident_lookahead() {
-            match ahead.token {
-                tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name}) => {
-                    if name == "function" {
-                        return Ok(TokenResult::None);
-                    }
+    #[inline(never)]
+    fn parse_identifier_reference_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let is_async = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => name == "async",
+            _ => false,
+        };
+
+        if is_async {
+            if let Some(&LookaheadResult {
+                line: false,
+                token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+                ..
+            }) = self.ident_lookahead() {
+                if name == "function" {
+                    return Ok(TokenResult::None);
                 }
-                _ => {}
             }
-
         }
 
-        try_value!(self.reference_identifier());
+        let id = try_value!(self.parse_reference_identifier());
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(id.into()))
     }
-    // fn parse_literal_expression(&mut self) -> OptResult<()> {
-    //     Ok(try_sequence!(
-    //         self.parse_null_expression()?,
-    //         self.parse_true_expression()?,
-    //         self.parse_false_expression()?,
-    //         self.parse_numeric_expression()?,
-    //         self.parse_string_expression()?,
-    //     ))
-    // }
-    fn parse_null_expression(&mut self) -> OptResult<()> {
+    fn parse_null_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("null"));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, literal::Null::default()).into()))
     }
-    fn parse_true_expression(&mut self) -> OptResult<()> {
+    fn parse_true_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("true"));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, literal::Boolean::from(true)).into()))
     }
-    fn parse_false_expression(&mut self) -> OptResult<()> {
+    fn parse_false_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.keyword("false"));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, literal::Boolean::from(false)).into()))
     }
-    fn parse_numeric_expression(&mut self) -> OptResult<()> {
-        try_value!(self.numeric());
+    fn parse_numeric_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        let tok = try_value!(self.numeric());
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, literal::Numeric::from(tok.value)).into()))
+    }
+    fn parse_string_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        Ok(self.parse_string_literal().map(From::from))
     }
-    fn parse_string_expression(&mut self) -> OptResult<()> {
-        try_value!(self.string());
+    pub fn parse_string_literal(&mut self) -> TokenResult<literal::String<'code>> {
+        let start = self.start();
+        let tok = try_token!(self.string());
+        let value = self.text(tok.value);
 
-        Ok(TokenResult::Some(()))
+        TokenResult::Some(self.located(start, literal::String::from(value)))
     }
-    fn parse_array_literal_expression(&mut self) -> OptResult<()> {
+    #[inline(never)]
+    fn parse_array_literal_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::SquareOpen));
 
-        loop {
-            if let TokenResult::Some(_) = self.parse_array_item()? {
-            }
+        let mut elements = vec![];
+        let last_element = loop {
+            self.expect_expression();
+            let item = opt_value!(self.parse_array_item()?);
 
-            if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
-                break;
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+                elements.push((item, self.token_data()));
+            } else {
+                break item;
             }
-        }
+        };
 
         eat_value!(self.punc(tokens::PunctuatorToken::SquareClose));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, objects::ArrayExpression {
+            elements,
+            last_element,
+            position: None,
+            comments: None,
+        }).into()))
     }
-    fn parse_object_literal_expression(&mut self) -> OptResult<()> {
+    #[inline(never)]
+    fn parse_object_literal_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         let mut parser = self.without(Flag::Template);
         try_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+        let token_curly_left = parser.token_data();
 
-        loop {
-            if let TokenResult::Some(_) = parser.parse_object_property()? {
-            }
-
-            if let TokenResult::None = parser.punc(tokens::PunctuatorToken::Comma) {
+        let mut properties = vec![];
+        let mut last_property = None;
+        while let TokenResult::Some(prop) = parser.parse_object_property()? {
+            if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Comma) {
+                properties.push((prop, parser.token_data()));
+            } else {
+                last_property = Some(prop);
                 break;
             }
         }
 
         eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(parser.located(start, objects::ObjectExpression {
+            token_curly_left,
+            properties,
+            last_property,
+            token_curly_right: parser.token_data(),
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_object_property(&mut self) -> OptResult<()> {
+    fn parse_object_property(&mut self) -> OptResult<objects::ObjectItem<'code>> {
+        let start = self.start();
 
         if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Ellipsis) {
+            self.require_version(EcmaVersion::Es2018, "Object spread property")?;
             self.expect_expression();
-            eat_value!(self.parse_assignment_expression()?);
-            return Ok(TokenResult::Some(()));
+            let expr = eat_value!(self.parse_assignment_expression()?);
+
+            return Ok(TokenResult::Some(self.located(start, objects::ObjectSpreadElement {
+                expression: NodeBox::new(expr),
+                position: None,
+                comments: None,
+            }).into()));
         }
 
-        // TODO: Disallow static
-        let head = try_value!(self.parse_method_head(true)?);
+        let head = try_value!(self.parse_method_head(false)?);
+
+        if head.is_plain() {
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Colon) {
+                self.expect_expression();
+                let value = eat_value!(self.parse_assignment_expression()?);
+
+                return Ok(TokenResult::Some(self.located(start, objects::ObjectProperty {
+                    name: head.name,
+                    value: NodeBox::new(value),
+                    position: None,
+                    comments: None,
+                }).into()));
+            }
+
+            // Methods can have type parameters, like "m<T>(x: T) {}".
+            let has_types = self.has_types();
+            let is_method = match *self.token() {
+                tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) => true,
+                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) => has_types,
+                _ => false,
+            };
 
-        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Colon) {
+            if !is_method {
+                return Ok(TokenResult::Some(self.parse_object_shorthand(start, head.name)?));
+            }
+        }
+
+        let kind = head.method_kind();
+        let (params, body) = eat_value!(self.parse_method_tail(&head)?);
+
+        Ok(TokenResult::Some(self.located(start, objects::ObjectMethod {
+            kind,
+            id: head.name,
+            params,
+            body,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    // {foo}
+    // {foo = 4}, which is only valid if the object is later used as a pattern.
+    fn parse_object_shorthand(&mut self, start: tokenizer::Position, name: PropertyName<'code>) -> Result<objects::ObjectItem<'code>> {
+        let id = match name {
+            PropertyName::Identifier(id) => {
+                if !self.is_binding_identifier(&id.value) {
+                    bail!("Unexpected reserved word in shorthand property");
+                }
+                cover::reference_identifier(id)
+            }
+            _ => bail!("Expected ':' after property name"),
+        };
+
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Eq) {
+            let token_eq = self.token_data();
             self.expect_expression();
-            eat_value!(self.parse_assignment_expression()?);
-        } else {
-            // TODO: This needs to handle singlename prop access
-            // eat_value!(self.parse_method_tail(head)?);
-            opt_value!(self.parse_method_tail(head)?);
+            let init = eat_value!(self.with(Flag::In).parse_assignment_expression()?);
+
+            self.cover_init = true;
+
+            let name = cover::property_identifier(&id);
+            let value = self.located(start, expression::AssignmentExpression {
+                left: NodeBox::new(id.into()),
+                token_eq,
+                right: NodeBox::new(init),
+                position: None,
+                comments: None,
+            });
+
+            return Ok(self.located(start, objects::ObjectProperty {
+                name: name.into(),
+                value: NodeBox::new(value.into()),
+                position: None,
+                comments: None,
+            }).into());
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(self.located(start, objects::ObjectShorthandProperty::from(id)).into())
     }
-    fn parse_array_item(&mut self) -> OptResult<()> {
+    fn parse_array_item(&mut self) -> OptResult<objects::ArrayItem<'code>> {
+        let start = self.start();
+
         if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Ellipsis) {
-            eat_value!(self.parse_assignment_expression()?);
-        } else if let TokenResult::Some(_) = self.parse_assignment_expression()? {
+            self.expect_expression();
+            let expr = eat_value!(self.parse_assignment_expression()?);
+
+            return Ok(TokenResult::Some(self.located(start, objects::ArraySpreadItem {
+                token_prefix: Default::default(),
+                expression: NodeBox::new(expr),
+                position: None,
+                comments: None,
+            }).into()));
         }
-        Ok(TokenResult::Some(()))
-    }
 
-    fn parse_regular_expression_literal_expression(&mut self) -> OptResult<()> {
-        try_value!(self.regex());
+        let expr = try_value!(self.parse_assignment_expression()?);
+
+        Ok(TokenResult::Some(self.located(start, objects::ArrayExpressionItem {
+            token_prefix: Default::default(),
+            expression: NodeBox::new(expr),
+            position: None,
+            comments: None,
+        }).into()))
+    }
 
-        Ok(TokenResult::Some(()))
+    #[inline(never)]
+    fn parse_regular_expression_literal_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
+        let tok = try_value!(self.regex());
+        if (!self.annex_b() || tok.flags.contains('u')) && has_annex_b_control_escape(&tok.pattern) {
+            bail!("\\c in a regular expression must be followed by a letter");
+        }
+        let value = self.text(tok.pattern);
+
+        Ok(TokenResult::Some(self.located(start, literal::RegExp {
+            value,
+            flags: tok.flags.chars().collect(),
+            position: None,
+            comments: None,
+        }).into()))
     }
-    fn parse_template_literal_expression(&mut self) -> OptResult<()> {
+    fn parse_template_literal(&mut self, tagged: bool) -> OptResult<expression::TemplateLiteral<'code>> {
+        let start = self.start();
         let tok = try_value!(self.template());
+        self.check_template_escapes(start, &tok, tagged)?;
+
+        let mut parts = vec![];
+        let first = self.template_part(tok.clone());
+        let mut last_part = self.located(start, first);
 
         if tok.format == tokens::TemplateFormat::Head {
             let mut parser = self.with(Flag::Template);
+            let mut parser = parser.with(Flag::In);
             loop {
-                eat_value!(parser.parse_expression()?);
+                parser.expect_expression();
+                let expr = eat_value!(parser.parse_expression()?);
 
+                let part_start = parser.start();
                 let next = eat_value!(parser.template_tail());
+                parser.check_template_escapes(part_start, &next, tagged)?;
+                let is_tail = next.format == tokens::TemplateFormat::Tail;
+
+                let next_part = parser.template_part(next);
+                let part = mem::replace(&mut last_part, parser.located(part_start, next_part));
+                parts.push((part, Default::default(), expr, Default::default()));
 
-                if next.format == tokens::TemplateFormat::Tail {
+                if is_tail {
                     break;
                 }
             }
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, expression::TemplateLiteral {
+            token_tick_open: Default::default(),
+            parts,
+            last_part,
+            token_tick_close: Default::default(),
+            position: None,
+            comments: None,
+        })))
     }
-    fn parse_cover_parenthesized_expression(&mut self) -> OptResult<()> {
+    #[inline(never)]
+    fn parse_cover_parenthesized_expression(&mut self) -> OptResult<alias::Expression<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = self.token_data();
 
-        self.expect_expression();
+        let mut parser = self.with(Flag::In);
 
-        if let Some(_) = opt_value!(self.parse_assignment_expression()?) {
-            while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
-                self.expect_expression();
-                if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Ellipsis) {
-                    if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-                    } else {
-                        eat_value!(self.binding_identifier());
-                    }
-                } else {
-                    eat_value!(self.parse_assignment_expression()?)
-                }
+        let mut expr: Option<alias::Expression> = None;
+        let mut token_comma = None;
+        let mut rest = None;
+        let mut trailing_comma = false;
+        loop {
+            parser.expect_expression();
+
+            let item_start = parser.start();
+            if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Ellipsis) {
+                rest = Some(parser.parse_cover_rest_param(item_start)?);
+                break;
             }
-        } else {
-            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Ellipsis) {
-                if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-                } else {
-                    eat_value!(self.binding_identifier());
+
+            match parser.parse_assignment_expression()? {
+                TokenResult::Some(item) => {
+                    expr = Some(match expr.take() {
+                        Some(left) => {
+                            let seq_start = node_start(left.position());
+
+                            let token = token_comma.take().unwrap_or_default();
+                            binary_node!(parser, seq_start, SequenceExpression, token_comma: token, left, item)
+                        }
+                        None => item,
+                    });
+                }
+                TokenResult::None => {
+                    trailing_comma = expr.is_some();
+                    break;
                 }
             }
+
+            if let TokenResult::None = parser.punc(tokens::PunctuatorToken::Comma) {
+                break;
+            }
+            token_comma = Some(parser.token_data());
         }
 
-        eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+        // Flow's type casts, like "(a: A)".
+        let type_cast = parser.options.extensions.flow && rest.is_none() &&
+            matches!(*parser.token(), tokens::Token::Punctuator(tokens::PunctuatorToken::Colon));
+        if type_cast {
+            if let Some(expression) = expr.take() {
+                let cast = parser.parse_type_cast_rest(start, token_paren_l, expression)?;
+                return Ok(TokenResult::Some(cast.into()));
+            }
+        }
 
-        Ok(TokenResult::Some(()))
+        eat_value!(parser.punc(tokens::PunctuatorToken::ParenClose));
+        let token_paren_r = parser.token_data();
+
+        // "()", "(...rest)" and "(a, )" are only allowed as arrow function parameters.
+        if rest.is_some() || trailing_comma || expr.is_none() {
+            match *parser.token() {
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Arrow) => {}
+                _ => bail!("Expected arrow function after parameter list"),
+            }
+
+            return Ok(TokenResult::Some(parser.cover_arrow_head(start, expr, rest)?));
+        }
+
+        Ok(TokenResult::Some(parser.located(start, expression::ParenthesizedExpression {
+            token_paren_l,
+            expr: NodeBox::new(expr.expect("parenthesized expressions without an arrow are not empty")),
+            token_paren_r,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    // "(...a) => {}", where the rest element can only be an arrow parameter.
+    #[inline(never)]
+    fn parse_cover_rest_param(&mut self, start: tokenizer::Position) -> Result<functions::FunctionRestParam<'code>> {
+        let id = eat_value!(self.parse_binding_element_target()?);
+
+        Ok(self.located(start, functions::FunctionRestParam {
+            id,
+            type_annotation: None,
+            position: None,
+            comments: None,
+        }))
+    }
+
+    // An arrow function for parameters that can only be arrow parameters,
+    // like "()" or "(a, ...b)". The arrow itself is completed once the body
+    // is parsed, at which point the empty body is replaced.
+    #[inline(never)]
+    fn cover_arrow_head(
+        &mut self,
+        start: tokenizer::Position,
+        expr: Option<alias::Expression<'code>>,
+        rest: Option<functions::FunctionRestParam<'code>>,
+    ) -> Result<alias::Expression<'code>> {
+        let mut params = vec![];
+        if let Some(expr) = expr {
+            for item in cover::sequence_items(expr) {
+                params.push((cover::function_param(item)?, Default::default()));
+            }
+        }
+        let params = self.located(start, functions::FunctionParams {
+            type_parameters: None,
+            params,
+            last_param: rest.map(From::from),
+            return_type: None,
+            predicate: None,
+            position: None,
+            comments: None,
+        });
+
+        Ok(self.located(start, functions::ArrowFunctionExpression {
+            kind: Default::default(),
+            params: params.into(),
+            body: Default::default(),
+            position: None,
+            comments: None,
+        }).into())
+    }
+
+    // Escapes like "\01" are never allowed in templates, though tagged ones
+    // can hold any escape since their tag gets the raw text.
+    fn check_template_escapes(&self, start: tokenizer::Position, tok: &tokens::TemplateToken<'code>, tagged: bool) -> Result<()> {
+        if !tagged && has_legacy_octal_escape(&tok.raw) {
+            return Err(self.locate_error(start, format_err!("Octal escapes are not allowed in templates")));
+        }
+        Ok(())
+    }
+
+    pub fn template_part(&mut self, tok: tokens::TemplateToken<'code>) -> expression::TemplatePart<'code> {
+        expression::TemplatePart {
+            value: self.text(tok.cooked),
+            raw_value: Some(self.text(tok.raw)),
+            position: None,
+            comments: None,
+        }
+    }
+}
+
+// Whether the pattern has a "\c" that isn't followed by a letter, which
+// Annex B reads as a literal backslash and "c" outside of unicode patterns.
+// Patterns are otherwise left for the engine to validate.
+fn has_annex_b_control_escape(pattern: &str) -> bool {
+    let bytes = pattern.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            i += 1;
+            continue;
+        }
+        if bytes.get(i + 1) == Some(&b'c') && !bytes.get(i + 2).is_some_and(u8::is_ascii_alphabetic) {
+            return true;
+        }
+        i += 2;
     }
+    false
 }
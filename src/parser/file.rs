@@ -1,42 +1,273 @@
-use tokenizer::Tokenizer;
-use parser::Parser;
-use parser::utils::{OptResult, Result, TokenResult};
+use std::mem;
+
+use ast::{self, alias, patterns, root, NodeBox};
+use ast::fold::{Fold, FoldChildren, Foldable};
+use tokenizer::{self, IntoTokenizer, Tokenizer};
+use parser::{Diagnostic, Flag, Parser, ParserOptions, SourceType};
+use parser::comments::attach_comments;
+use parser::utils::{OptResult, Result};
+
+/// Parse code with the given options, locating any syntax error.
+pub fn parse<'code>(code: &'code str, options: &ParserOptions) -> ::std::result::Result<root::Ast<'code>, Diagnostic> {
+    let ast = try_source_types(options, |options| {
+        let mut p = Parser::with_options(code.into_tokenizer(), options);
+        p.parse_source().map_err(|error| p.diagnostic(&error))
+    })?;
+
+    if options.preserve_parens {
+        Ok(ast)
+    } else {
+        Ok(ast.fold(&mut StripParens))
+    }
+}
+
+/// Parse code as a script, whatever the source type in the options.
+pub fn parse_script<'code>(code: &'code str, options: &ParserOptions) -> ::std::result::Result<root::Script<'code>, Diagnostic> {
+    match parse(code, &ParserOptions { source_type: SourceType::Script, ..*options })? {
+        root::Ast::Script(script) => Ok(script),
+        root::Ast::Module(_) => unreachable!("scripts are never parsed as modules"),
+    }
+}
+
+/// Parse code as a module, whatever the source type in the options.
+pub fn parse_module<'code>(code: &'code str, options: &ParserOptions) -> ::std::result::Result<root::Module<'code>, Diagnostic> {
+    match parse(code, &ParserOptions { source_type: SourceType::Module, ..*options })? {
+        root::Ast::Module(module) => Ok(module),
+        root::Ast::Script(_) => unreachable!("modules are never parsed as scripts"),
+    }
+}
+
+/// Run a parse with the options as they are, or for unambiguous code, with
+/// the options for a script and then for a module if that fails. If both
+/// fail, the error from further into the code is returned, since the goal
+/// that got further is more likely to be the intended one.
+pub fn try_source_types<R, F>(options: &ParserOptions, mut parse: F) -> ::std::result::Result<R, Diagnostic>
+where
+    F: FnMut(&ParserOptions) -> ::std::result::Result<R, Diagnostic>
+{
+    if options.source_type != SourceType::Unambiguous {
+        return parse(options);
+    }
+
+    let script_error = match parse(&ParserOptions { source_type: SourceType::Script, ..*options }) {
+        Ok(result) => return Ok(result),
+        Err(error) => error,
+    };
+    parse(&ParserOptions { source_type: SourceType::Module, ..*options }).map_err(|module_error| {
+        if module_error.range.start.offset > script_error.range.start.offset {
+            module_error
+        } else {
+            script_error
+        }
+    })
+}
+
+// Replaces parenthesized expressions and patterns with their contents. The
+// printer adds back any parentheses that the precedence of operators needs.
+pub struct StripParens;
+impl<'code> Fold<'code> for StripParens {
+    fn fold_expression(&mut self, node: alias::Expression<'code>) -> alias::Expression<'code> {
+        match node {
+            alias::Expression::Parenthesized(paren) => self.fold_expression(NodeBox::into_inner(NodeBox::into_inner(paren).expr)),
+            node => node.fold_children(self),
+        }
+    }
+
+    fn fold_left_hand_simple_assign(&mut self, node: patterns::LeftHandSimpleAssign<'code>) -> patterns::LeftHandSimpleAssign<'code> {
+        match node {
+            patterns::LeftHandSimpleAssign::Parenthesized(paren) => self.fold_left_hand_simple_assign(NodeBox::into_inner(paren.pattern)),
+            node => node.fold_children(self),
+        }
+    }
+
+    fn fold_left_hand_complex_assign(&mut self, node: patterns::LeftHandComplexAssign<'code>) -> patterns::LeftHandComplexAssign<'code> {
+        match node {
+            patterns::LeftHandComplexAssign::Parenthesized(paren) => {
+                match self.fold_left_hand_simple_assign(NodeBox::into_inner(paren.pattern)) {
+                    patterns::LeftHandSimpleAssign::Identifier(id) => id.into(),
+                    patterns::LeftHandSimpleAssign::Member(member) => member.into(),
+                    patterns::LeftHandSimpleAssign::Parenthesized(_) => unreachable!("parentheses were stripped"),
+                }
+            }
+            node => node.fold_children(self),
+        }
+    }
+}
 
 impl<'code, T> Parser<'code, T>
 where
     T: Tokenizer<'code>
 {
-    pub fn parse_script(&mut self) -> Result<()> {
-        while let TokenResult::Some(_) = self.parse_script_item()? {
+    /// Parse the code as the goal that the parser's options ask for. Code
+    /// with an unambiguous source type is parsed as a script here, and only
+    /// falls back to a module when parsed with `parse`.
+    pub fn parse_source(&mut self) -> Result<root::Ast<'code>> {
+        let allow_return = if self.options.allow_return_outside_function { Flag::Return } else { Flag::Noop };
+        let mut p = self.with(allow_return);
+
+        match p.options.source_type {
+            SourceType::Script | SourceType::Unambiguous => p.parse_script().map(root::Ast::from),
+            SourceType::Module => {
+                let mut p = p.with(Flag::Module);
+                let mut p = p.with(Flag::Strict);
+                p.parse_module().map(root::Ast::from)
+            }
         }
+    }
+
+    pub fn parse_script(&mut self) -> Result<root::Script<'code>> {
+        let (directives, body) = self.parse_body_items(Parser::parse_script_item)?;
 
         eat_value!(self.eof());
-        Ok(())
+
+        let mut script = self.located(file_start(), root::Script {
+            directives,
+            body,
+            position: None,
+            comments: None,
+        });
+        self.attach_comments(&mut script);
+
+        Ok(script)
     }
-    pub fn parse_module(&mut self) -> Result<()> {
-        while let TokenResult::Some(_) = self.parse_module_item()? {
-        }
+    pub fn parse_module(&mut self) -> Result<root::Module<'code>> {
+        let (directives, body) = self.parse_body_items(Parser::parse_module_item)?;
 
         eat_value!(self.eof());
-        Ok(())
+
+        let mut module = self.located(file_start(), root::Module {
+            directives,
+            body,
+            position: None,
+            comments: None,
+        });
+        self.attach_comments(&mut module);
+
+        Ok(module)
     }
 
-    fn parse_script_item(&mut self) -> OptResult<()> {
+    /// Parse a single top-level item of a script.
+    pub fn parse_script_item(&mut self) -> OptResult<alias::StatementItem<'code>> {
         self.expect_expression();
 
         Ok(try_sequence!(
             self.parse_declaration()?,
-            self.parse_statement()?,
+            self.parse_statement()?.map(Into::into),
         ))
     }
 
-    fn parse_module_item(&mut self) -> OptResult<()> {
+    /// Parse a single top-level item of a module.
+    pub fn parse_module_item(&mut self) -> OptResult<alias::ModuleStatementItem<'code>> {
         self.expect_expression();
 
         Ok(try_sequence!(
-            self.parse_script_item()?,
             self.parse_import_declaration()?,
             self.parse_export_declaration()?,
+            self.parse_script_item()?.map(Into::into),
         ))
     }
+
+    fn attach_comments(&mut self, root: &mut dyn ast::Node<'code>) {
+        let comments = mem::take(&mut self.comments);
+
+        if self.options.attach_comments {
+            attach_comments(root, comments);
+        }
+    }
+}
+
+fn file_start() -> tokenizer::Position {
+    tokenizer::Position {
+        offset: 0,
+        line: 1,
+        column: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{self, Node};
+    use parser::Parser;
+    use tokenizer::IntoTokenizer;
+
+    // Every node should have a position that lies within its parent's.
+    fn check_positions(node: &mut dyn Node, parent: &ast::NodePosition) {
+        let position = match node.position() {
+            Some(position) => position.clone(),
+            None => panic!("missing position on {:?}", node),
+        };
+        assert!(parent.start <= position.start && position.end <= parent.end,
+            "{:?} is outside of its parent {:?}: {:?}", position, parent, node);
+        assert!(position.start <= position.end);
+
+        let mut children = vec![];
+        node.children_mut(&mut children);
+        for child in children {
+            check_positions(child, &position);
+        }
+    }
+
+    fn check_module(code: &str) {
+        let mut module = Parser::new(code.into_tokenizer()).parse_module().unwrap();
+
+        let root = module.position().unwrap().clone();
+        assert_eq!(root.start, 0);
+        assert_eq!(root.end, code.len());
+        check_positions(&mut module, &root);
+    }
+
+    #[test]
+    fn it_positions_every_node() {
+        check_module("
+            import a, {b as c, d} from \"x\";
+            import * as ns from \"y\";
+            export default async function () {}
+            export const q = (a, b = 2, ...c) => a + b;
+            export { q as r, a };
+            let {x, y: [z = 1, ...w]} = obj, k = async x => await x;
+            class A extends B { static *gen() { yield* 1; } get x() { return this.y / 2; } set x(v) {} }
+            for (const [a, b] of c) { if (a) continue; else break; }
+            for (let i = 0; i < 10; i++) label: while (false) {}
+            for (var key in obj);
+            [a, b] = [b, a, ...c]; ({a = 1, b} = {});
+            x = a ? b : c => d;
+            var re = /ab+c/g.test(s), t = tag`a${b}c${d}e`;
+            new Foo(1)(2).bar[3].baz;
+            new.target; super.x; typeof a; delete a.b; void 0; !a; ~a; -a; +a; a++; --a;
+            switch (a) { case 1: break; default: }
+            try { throw new Error(); } catch ({message}) {} finally {}
+            do {} while (a)
+            with (a) {}
+            debugger;
+            ({ a, b: 1, [c]: 2, d() {}, get e() {}, async *f() {}, ...g });
+            (function* gen() { yield; })();
+        ");
+    }
+
+    #[test]
+    fn it_positions_tokens() {
+        use ast::{alias, PositionRange, TokenData};
+
+        let script = Parser::new("let a;\nif (a) {\n  a = 1, b;\n}".into_tokenizer()).parse_script().unwrap();
+        let range = |start, end| Some(PositionRange { start, end });
+
+        let decl = match script.body[0] {
+            alias::StatementItem::Let(ref decl) => decl,
+            ref item => panic!("expected a let declaration, got {:?}", item),
+        };
+        assert_eq!(decl.token_let.position().cloned(), range((1, 0), (1, 3)));
+        assert_eq!(decl.token_semi.position().cloned(), range((1, 5), (1, 6)));
+
+        let stmt = match script.body[1] {
+            alias::StatementItem::If(ref stmt) => stmt,
+            ref item => panic!("expected an if statement, got {:?}", item),
+        };
+        assert_eq!(stmt.token_if.position().cloned(), range((2, 0), (2, 2)));
+        assert_eq!(stmt.token_paren_l.position().cloned(), range((2, 3), (2, 4)));
+        assert_eq!(stmt.token_paren_r.position().cloned(), range((2, 5), (2, 6)));
+
+        let position = stmt.position.as_ref().unwrap();
+        assert_eq!((position.start, position.end), (7, 29));
+        assert_eq!(position.range, PositionRange { start: (2, 0), end: (4, 1) });
+    }
 }
@@ -0,0 +1,547 @@
+use ast::{KeywordData, KeywordSuffixData, NodeBox};
+use ast::alias;
+use ast::flow;
+use ast::typescript;
+use tokenizer::{self, Tokenizer, tokens};
+use parser::{Parser, Flag, LookaheadResult};
+use parser::utils::{OptResult, Result, TokenResult};
+
+/// Whether the comments at the start of the code mark it as a Flow file with
+/// `@flow`.
+pub fn has_flow_pragma(code: &str) -> bool {
+    let mut rest = code.trim_start();
+    if rest.starts_with("#!") {
+        rest = rest.find('\n').map(|i| &rest[i..]).unwrap_or("").trim_start();
+    }
+
+    loop {
+        let comment = if rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            &rest[..end]
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            match body.find("*/") {
+                Some(i) => &rest[..i + 4],
+                None => rest,
+            }
+        } else {
+            return false;
+        };
+
+        let mut text = comment;
+        while let Some(i) = text.find("@flow") {
+            text = &text[i + "@flow".len()..];
+            match text.chars().next() {
+                Some(c) if c.is_alphanumeric() || c == '_' => {}
+                _ => return true,
+            }
+        }
+
+        rest = rest[comment.len()..].trim_start();
+    }
+}
+
+impl<'code, T> Parser<'code, T>
+where
+    T: Tokenizer<'code>
+{
+    // ?string
+    pub fn parse_maybe_type(&mut self) -> OptResult<flow::MaybeType<'code>> {
+        if !self.options.extensions.flow {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::Question));
+        let token_question = self.token_data();
+        let value = eat_value!(self.nested(Self::parse_type_operator)?);
+
+        Ok(TokenResult::Some(self.located(start, flow::MaybeType {
+            token_question,
+            value: NodeBox::new(value),
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // {| a: A |}
+    pub fn parse_exact_object_type(&mut self) -> OptResult<flow::ExactObjectType<'code>> {
+        if !self.options.extensions.flow {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        let mut parser = self.without(Flag::Template);
+        let empty = {
+            let opening = parser.speculate(|p| {
+                try_value!(p.punc(tokens::PunctuatorToken::CurlyOpen));
+                if let TokenResult::Some(_) = p.punc(tokens::PunctuatorToken::BarBar) {
+                    return Ok(TokenResult::Some(true));
+                }
+                try_value!(p.punc(tokens::PunctuatorToken::Bar));
+                Ok(TokenResult::Some(false))
+            });
+            try_value!(opening)
+        };
+
+        // The "||" of "{||}" both opens and closes the object.
+        let members = if empty {
+            vec![]
+        } else {
+            let members = parser.parse_type_members()?;
+            if members.iter().any(|member| matches!(*member, typescript::TypeMember::Spread(ref spread) if spread.is_inexact())) {
+                bail!("Exact object types can't be inexact");
+            }
+            eat_value!(parser.punc(tokens::PunctuatorToken::Bar));
+            members
+        };
+        eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+        Ok(TokenResult::Some(parser.located(start, flow::ExactObjectType {
+            members,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // %checks, after a function's return type or in place of it when
+    // "colon" is set, like "(x): %checks"
+    pub fn parse_predicate(&mut self, colon: bool) -> OptResult<flow::Predicate<'code>> {
+        if !self.options.extensions.flow {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        let (token_colon, token_percent) = try_value!(self.speculate(|p| {
+            let token_colon = if colon {
+                try_value!(p.punc(tokens::PunctuatorToken::Colon));
+                Some(p.token_data())
+            } else {
+                None
+            };
+            try_value!(p.punc(tokens::PunctuatorToken::Percent));
+            let token_percent = p.token_data();
+            try_value!(p.keyword("checks"));
+            Ok(TokenResult::Some((token_colon, token_percent)))
+        }));
+        let token_checks = self.token_data();
+
+        let value = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::ParenOpen) {
+            let value = eat_value!(self.with(Flag::In).parse_expression()?);
+            eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+            Some(NodeBox::new(value))
+        } else {
+            None
+        };
+
+        Ok(TokenResult::Some(self.located(start, flow::Predicate {
+            token_colon,
+            token_percent,
+            token_checks,
+            value,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // The "+" or "-" before a property or type parameter
+    pub fn parse_variance(&mut self) -> Option<typescript::Modifier<'code>> {
+        if !self.options.extensions.flow {
+            return None;
+        }
+
+        let start = self.start();
+        let kind = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Plus) {
+            typescript::ModifierKind::Covariant
+        } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Minus) {
+            typescript::ModifierKind::Contravariant
+        } else {
+            return None;
+        };
+
+        Some(self.located(start, typescript::Modifier::from(kind)))
+    }
+
+    // ...A, as a member of an object type, or the "..." of an inexact one
+    pub fn parse_spread_type_member(&mut self) -> OptResult<flow::SpreadTypeMember<'code>> {
+        if !self.options.extensions.flow {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::Ellipsis));
+        let token_ellipsis = self.token_data();
+        let value = match *self.token() {
+            tokens::Token::Punctuator(tokens::PunctuatorToken::Comma) |
+            tokens::Token::Punctuator(tokens::PunctuatorToken::Semicolon) |
+            tokens::Token::Punctuator(tokens::PunctuatorToken::CurlyClose) => None,
+            _ => Some(NodeBox::new(eat_value!(self.parse_type()?))),
+        };
+
+        Ok(TokenResult::Some(self.located(start, flow::SpreadTypeMember {
+            token_ellipsis,
+            value,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // opaque type A: B = C;
+    //
+    // Opaque types that are declared, rather than defined, leave out "= C".
+    pub fn parse_opaque_type_declaration(&mut self, declared: bool) -> OptResult<flow::OpaqueTypeDeclaration<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("opaque"));
+        let token_opaque = self.token_data();
+        eat_value!(self.keyword("type"));
+        let token_type = self.token_data();
+
+        let id = eat_value!(self.parse_type_identifier());
+        let type_parameters = opt_value!(self.parse_type_parameters()?);
+        let supertype = opt_value!(self.parse_type_annotation()?);
+        let value = if declared {
+            None
+        } else {
+            eat_value!(self.punc(tokens::PunctuatorToken::Eq));
+            Some(NodeBox::new(eat_value!(self.parse_type()?)))
+        };
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, flow::OpaqueTypeDeclaration {
+            token_opaque,
+            token_type,
+            id,
+            type_parameters,
+            supertype,
+            value,
+            token_semi,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // The "export function f(): void;" of "declare export function f(): void;"
+    //
+    // Other than functions and classes, what is exported by default is a
+    // type, like "declare export default A;".
+    pub fn parse_declare_export_declaration(&mut self) -> Result<flow::DeclareExportDeclaration<'code>> {
+        let start = self.start();
+        eat_value!(self.keyword("export"));
+        let token_export = self.token_data();
+        let token_default = opt_value!(self.keyword("default")).map(|_| self.token_data());
+
+        let is_declaration = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => {
+                matches!(&**name, "function" | "async" | "class")
+            }
+            _ => false,
+        };
+        let exported = if token_default.is_none() || is_declaration {
+            let item = self.parse_declared_item()?;
+            match item {
+                typescript::DeclaredItem::Export(_) |
+                typescript::DeclaredItem::ModuleExports(_) |
+                typescript::DeclaredItem::Module(_) => bail!("Unexpected declaration after \"declare export\""),
+                _ => {}
+            }
+            NodeBox::new(item).into()
+        } else {
+            let type_start = self.start();
+            let value = eat_value!(self.parse_type()?);
+            let token_semi = eat_value!(self.semicolon_token());
+
+            self.located(type_start, flow::DeclareExportType {
+                value: NodeBox::new(value),
+                token_semi,
+                position: None,
+                comments: None,
+            }).into()
+        };
+
+        Ok(self.located(start, flow::DeclareExportDeclaration {
+            token_export,
+            token_default,
+            exported,
+            position: None,
+            comments: None,
+        }))
+    }
+
+    // The "module.exports: A;" of "declare module.exports: A;"
+    pub fn parse_declare_module_exports(&mut self) -> Result<flow::DeclareModuleExports<'code>> {
+        let start = self.start();
+        eat_value!(self.keyword("module"));
+        let token_module = self.token_data();
+        eat_value!(self.punc(tokens::PunctuatorToken::Period));
+        let token_period = self.token_data();
+        eat_value!(self.keyword("exports"));
+        let type_annotation = eat_value!(self.parse_type_annotation()?);
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(self.located(start, flow::DeclareModuleExports {
+            token_module,
+            token_period,
+            type_annotation,
+            token_semi,
+            position: None,
+            comments: None,
+        }))
+    }
+
+    // (a: A)
+    //
+    // Called with the expression once the ":" is reached, so the annotation
+    // and the closing paren are what's left.
+    pub fn parse_type_cast_rest(
+        &mut self,
+        start: tokenizer::Position,
+        token_paren_l: KeywordSuffixData,
+        expression: alias::Expression<'code>,
+    ) -> Result<flow::TypeCastExpression<'code>> {
+        let type_annotation = match self.parse_type_annotation()? {
+            TokenResult::Some(type_annotation) => type_annotation,
+            TokenResult::None => bail!("Expected a type annotation in the type cast"),
+        };
+        if let TokenResult::None = self.punc(tokens::PunctuatorToken::ParenClose) {
+            bail!("Expected \")\" after the type cast");
+        }
+        let token_paren_r = self.token_data();
+
+        Ok(self.located(start, flow::TypeCastExpression {
+            token_paren_l,
+            expression: NodeBox::new(expression),
+            type_annotation,
+            token_paren_r,
+            position: None,
+            comments: None,
+        }))
+    }
+
+    // Whether the current word is the "type" or "typeof" of a type import,
+    // rather than a default import named "type", like "import type from 'a';".
+    // TypeScript only has type imports, without Flow's "typeof".
+    pub fn is_import_kind(&mut self) -> bool {
+        let flow = self.options.extensions.flow;
+        match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) if name == "type" || (flow && name == "typeof") => {}
+            _ => return false,
+        }
+
+        match self.ident_lookahead() {
+            Some(&LookaheadResult { token: tokens::Token::IdentifierName(ref id), .. }) => id.name != "from",
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(ref punc), .. }) => matches!(
+                *punc,
+                tokens::PunctuatorToken::CurlyOpen |
+                tokens::PunctuatorToken::Star
+            ),
+            _ => false,
+        }
+    }
+
+    // import type A, { B } from "a";
+    //
+    // Called once "import" is parsed, and only parses anything if it is
+    // followed by "type" or "typeof".
+    pub fn parse_import_type_declaration(
+        &mut self,
+        start: tokenizer::Position,
+        token_import: KeywordData,
+    ) -> OptResult<flow::ImportTypeDeclaration<'code>> {
+        if !self.is_import_kind() {
+            return Ok(TokenResult::None);
+        }
+
+        let kind = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) if name == "type" => flow::ImportKind::Type,
+            _ => flow::ImportKind::Typeof,
+        };
+        self.pop();
+        let token_kind = self.token_data();
+
+        let default = opt_value!(self.parse_binding_identifier());
+        let has_names = match default {
+            Some(_) => opt_value!(self.punc(tokens::PunctuatorToken::Comma)).is_some(),
+            None => true,
+        };
+
+        let mut namespace = None;
+        let mut specifiers = None;
+        let mut last_specifier = None;
+        if !has_names {
+            // Only the default type is imported.
+        } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Star) {
+            let token_star = self.token_data();
+            eat_value!(self.keyword("as"));
+            namespace = Some((token_star, eat_value!(self.parse_binding_identifier())));
+        } else {
+            eat_value!(self.punc(tokens::PunctuatorToken::CurlyOpen));
+
+            let mut items = vec![];
+            while let TokenResult::Some(spec) = self.parse_import_specifier()? {
+                if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+                    items.push((spec, self.token_data()));
+                } else {
+                    last_specifier = Some(spec);
+                    break;
+                }
+            }
+            specifiers = Some(items);
+
+            eat_value!(self.punc(tokens::PunctuatorToken::CurlyClose));
+        }
+
+        eat_value!(self.keyword("from"));
+        let token_from = self.token_data();
+        let source = eat_value!(self.parse_string_literal());
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, flow::ImportTypeDeclaration {
+            token_import,
+            kind,
+            token_kind,
+            default,
+            namespace,
+            specifiers,
+            last_specifier,
+            token_from,
+            source,
+            token_semi,
+            position: None,
+            comments: None,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{format, FormatOptions};
+    use parser::{self, ParserOptions};
+    use super::has_flow_pragma;
+
+    fn parse(code: &str) -> ::ast::root::Ast<'_> {
+        let mut options = ParserOptions::module();
+        options.extensions.flow = true;
+        parser::parse(code, &options).unwrap()
+    }
+
+    fn print(code: &str) -> String {
+        format(&parse(code), &Default::default()).unwrap()
+    }
+
+    fn strip(code: &str) -> String {
+        let options = FormatOptions { strip_types: true, ..Default::default() };
+        format(&parse(code), &options).unwrap()
+    }
+
+    fn strip_pretty(code: &str) -> String {
+        let options = FormatOptions { strip_types: true, ..FormatOptions::pretty() };
+        format(&parse(code), &options).unwrap()
+    }
+
+    #[test]
+    fn it_finds_the_pragma() {
+        assert!(has_flow_pragma("// @flow\nlet a;"));
+        assert!(has_flow_pragma("#!/usr/bin/env node\n/**\n * @flow strict\n */\nlet a;"));
+        assert!(has_flow_pragma("// first\n/* @flow */"));
+        assert!(!has_flow_pragma("// @noflow\nlet a;"));
+        assert!(!has_flow_pragma("// @flowtype\nlet a;"));
+        assert!(!has_flow_pragma("let a; // @flow"));
+    }
+
+    #[test]
+    fn it_requires_the_extension() {
+        assert!(parser::parse("let a: ?string;", &ParserOptions::module()).is_err());
+        assert!(parser::parse("opaque type A = B;", &ParserOptions::module()).is_err());
+
+        let mut options = ParserOptions::module();
+        options.extensions.typescript = true;
+        assert!(parser::parse("let a: ?string;", &options).is_err());
+        assert!(parser::parse("let a: {| b: B |};", &options).is_err());
+    }
+
+    #[test]
+    fn it_prints_types() {
+        assert_eq!(print("let a: ?string[] = b;"), "let a:?string[]=b;");
+        assert_eq!(print("let a: {| b: B, ...C |};"), "let a:{|b:B;...C;|};");
+        assert_eq!(print("let a: {||} | {| b: B |};"), "let a:{||}|{|b:B;|};");
+        assert_eq!(print("function f<T: Object>(a: T) {}"), "function f<T:Object>(a:T){}");
+    }
+
+    #[test]
+    fn it_strips_annotations() {
+        assert_eq!(strip("let a: ?string = b;"), "let a=b;");
+        assert_eq!(strip("function f(a: {| b: ?B |}, c?: C): D {}"), "function f(a,c){}");
+        assert_eq!(strip("const f = (a: A): B => a;"), "const f=(a)=>a;");
+    }
+
+    #[test]
+    fn it_strips_declarations() {
+        assert_eq!(strip("opaque type A: B = C; declare opaque type D; export type E = F;"), "");
+        assert_eq!(strip("import type A, { B } from 'a'; import typeof * as C from 'c';"), "");
+        assert_eq!(strip("import type from 'a';"), "import type from'a';");
+    }
+
+    #[test]
+    fn it_strips_type_import_specifiers() {
+        assert_eq!(strip("import { type A, typeof B, c } from 'a';"), "import{c}from'a';");
+        assert_eq!(strip("import D, { type A } from 'a';"), "import D from'a';");
+        assert_eq!(strip("import { type, typeof as c } from 'a';"), "import{type,typeof as c}from'a';");
+    }
+
+    #[test]
+    fn it_strips_variance() {
+        assert_eq!(print("class A { +b: B; static -c: C = d; }"), "class A{+b:B;static-c:C=d;}");
+        assert_eq!(strip("class A { +b: B; static -c: C = d; }"), "class A{b;static c=d;}");
+        assert_eq!(print("type A = { +b: B, -c: C };"), "type A={+b:B;-c:C;};");
+    }
+
+    #[test]
+    fn it_parses_inexact_objects() {
+        assert_eq!(print("type A = { b: B, ... };"), "type A={b:B;...;};");
+        assert_eq!(print("type A = {...};"), "type A={...;};");
+
+        let mut options = ParserOptions::module();
+        options.extensions.flow = true;
+        assert!(parser::parse("type A = { ..., b: B };", &options).is_err());
+        assert!(parser::parse("type A = {| b: B, ... |};", &options).is_err());
+    }
+
+    #[test]
+    fn it_strips_declared_exports() {
+        assert_eq!(print("declare export default A;"), "declare export default A;");
+        assert_eq!(strip("declare export function f(): void; declare export default A; let b;"), "let b;");
+        assert_eq!(strip("declare module 'a' { declare module.exports: A; } let b;"), "let b;");
+        assert_eq!(strip("declare module A { declare export var b: B; } let c;"), "let c;");
+    }
+
+    #[test]
+    fn it_strips_type_casts() {
+        assert_eq!(print("(a: any);"), "(a:any);");
+        assert_eq!(strip("(a: any);"), "a;");
+        assert_eq!(strip("f((a + b: number) * c);"), "f((a+b)*c);");
+    }
+
+    #[test]
+    fn it_strips_comment_syntax() {
+        assert_eq!(strip("let a /*: string */ = b;"), "let a=b;");
+        assert_eq!(strip("function f(a /*: A */) /*: B */ {}"), "function f(a){}");
+        assert_eq!(strip("/*:: type A = B; */ let c;"), "let c;");
+        assert_eq!(strip("/* flow-include type A = B; */ let c;"), "let c;");
+        assert_eq!(strip("/* just a comment */ let c;"), "let c;");
+    }
+
+    #[test]
+    fn it_strips_the_code_in_comments() {
+        assert_eq!(strip("function p(/*:: a: number */) {}"), "function p(){}");
+        assert_eq!(strip("function q(/*:: a: A, */ b /*: B */) {}"), "function q(b){}");
+        assert_eq!(strip("class A { /*:: b: B; */ c = 1; }"), "class A{c=1;}");
+        assert_eq!(strip("f/*:: <T> */(a);"), "f(a);");
+        assert_eq!(print("function p(/*:: a: number */) {}"), "function p(a:number){}");
+    }
+
+    #[test]
+    fn it_leaves_no_lines_for_stripped_declarations() {
+        assert_eq!(strip_pretty("function f() {\n  type T = number;\n}"), "function f() {}");
+        assert_eq!(strip_pretty("function f() {\n  /*:: type T = number; */\n}"), "function f() {}");
+        assert_eq!(strip_pretty("a();\ntype T = number;\nb();"), "a();\nb();");
+    }
+}
@@ -1,132 +1,291 @@
-use tokenizer::{Tokenizer, tokens};
-use parser::{Parser, Flag, LookaheadResult};
-use parser::utils::{OptResult, TokenResult};
-// use parser::utils;
+use std::mem;
+
+use ast::functions;
+use ast::general::{self, BindingIdentifier};
+use ast::patterns;
+use ast::typescript;
+use ast::visit::{Visit, Visitable, Walk};
+use ast::Str;
+use tokenizer::{self, Tokenizer, tokens};
+use parser::{EcmaVersion, Parser, Flag, LookaheadResult};
+use parser::statements::is_use_strict;
+use parser::utils::{OptResult, Result, TokenResult};
 
 impl<'code, T> Parser<'code, T>
 where
     T: Tokenizer<'code>
 {
-    pub fn parse_function_declaration(&mut self) -> OptResult<()> {
-        let maybe_async = if let Some(&LookaheadResult {
-            line: false,
-            token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name })
-        }) = self.ident_lookahead() {
-            name == "function"
-        } else {
-            false
-        };
-
-        let star = if maybe_async {
-            try_value!(self.keyword("async"));
-            eat_value!(self.keyword("function"));
-
-            None
-        } else {
-            try_value!(self.keyword("function"));
-
-            opt_value!(self.punc(tokens::PunctuatorToken::Star))
-        };
-
-        if self.flags.allow_default {
-            opt_value!(self.binding_identifier());
-        } else {
-            eat_value!(self.binding_identifier());
-        }
+    pub fn parse_function_declaration(&mut self) -> OptResult<functions::FunctionDeclaration<'code>> {
+        let start = self.start();
+        let (kind, id, params, body) = try_value!(self.parse_function(true, false)?);
 
-        if maybe_async {
-            let mut parser = self.without(Flag::Yield);
+        Ok(TokenResult::Some(self.located(start, functions::FunctionDeclaration {
+            kind,
+            id: id.expect("function declarations require a name"),
+            params,
+            body: body.expect("function bodies are required"),
+            position: None,
+            comments: None,
+        })))
+    }
 
-            eat_value!(parser.parse_function_params()?);
-            eat_value!(parser.with(Flag::Await).parse_function_body()?);
-        } else if let Some(_) = star {
-            let mut parser = self.without(Flag::Await);
-            let mut parser = parser.with(Flag::Yield);
+    pub fn parse_export_default_function_declaration(&mut self, start: tokenizer::Position) -> OptResult<functions::ExportDefaultFunctionDeclaration<'code>> {
+        let (kind, id, params, body) = try_value!(self.parse_function(false, false)?);
 
-            eat_value!(parser.parse_function_params()?);
-            eat_value!(parser.parse_function_body()?);
-        } else {
-            let mut parser = self.without(Flag::Await);
-            let mut parser = parser.without(Flag::Yield);
+        Ok(TokenResult::Some(self.located(start, functions::ExportDefaultFunctionDeclaration {
+            kind,
+            id,
+            params,
+            body: body.expect("function bodies are required"),
+            position: None,
+            comments: None,
+        })))
+    }
 
-            eat_value!(parser.parse_function_params()?);
-            eat_value!(parser.parse_function_body()?);
-        }
+    #[inline(never)]
+    pub fn parse_function_expression(&mut self) -> OptResult<functions::FunctionExpression<'code>> {
+        let start = self.start();
+        let (kind, id, params, body) = try_value!(self.parse_function(false, false)?);
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, functions::FunctionExpression {
+            kind,
+            id,
+            params,
+            body: body.expect("function bodies are required"),
+            position: None,
+            comments: None,
+        })))
     }
 
-    pub fn parse_function_expression(&mut self) -> OptResult<()> {
+    /// Parses a function. With `allow_signature`, a TypeScript function
+    /// without a body is allowed too, like "function f(a: string): void;".
+    pub fn parse_function(&mut self, id_required: bool, allow_signature: bool) -> OptResult<(functions::FunctionKind, Option<BindingIdentifier<'code>>, functions::FunctionParams<'code>, Option<functions::FunctionBody<'code>>)> {
         let maybe_async = if let Some(&LookaheadResult {
             line: false,
-            token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name })
+            token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+            ..
         }) = self.ident_lookahead() {
             name == "function"
         } else {
             false
         };
 
-        let star = if maybe_async {
+        if maybe_async {
             try_value!(self.keyword("async"));
             eat_value!(self.keyword("function"));
-
-            TokenResult::None
         } else {
             try_value!(self.keyword("function"));
+        }
 
-            self.punc(tokens::PunctuatorToken::Star)
-        };
-
-        opt_value!(self.binding_identifier());
-
+        let star = opt_value!(self.punc(tokens::PunctuatorToken::Star)).is_some();
         if maybe_async {
-            let mut parser = self.without(Flag::Yield);
+            self.require_version(EcmaVersion::Es2017, "Async function")?;
+        }
+        if maybe_async && star {
+            self.require_version(EcmaVersion::Es2018, "Async generator")?;
+        }
 
-            eat_value!(parser.parse_function_params()?);
-            eat_value!(parser.with(Flag::Await).parse_function_body()?);
-        } else if let TokenResult::Some(_) = star {
-            let mut parser = self.without(Flag::Await);
-            let mut parser = parser.with(Flag::Yield);
+        let id = if id_required {
+            Some(eat_value!(self.parse_binding_identifier()))
+        } else {
+            opt_value!(self.parse_binding_identifier())
+        };
+
+        let kind = match (maybe_async, star) {
+            (true, true) => functions::FunctionKind::AsyncGenerator,
+            (true, false) => functions::FunctionKind::Async,
+            (false, true) => functions::FunctionKind::Generator,
+            (false, false) => functions::FunctionKind::Normal,
+        };
 
-            eat_value!(parser.parse_function_params()?);
-            eat_value!(parser.parse_function_body()?);
+        let mut parser = if star {
+            self.with(Flag::Yield)
+        } else {
+            self.without(Flag::Yield)
+        };
+        let mut parser = if maybe_async {
+            parser.with(Flag::Await)
         } else {
-            let mut parser = self.without(Flag::Await);
-            let mut parser = parser.without(Flag::Yield);
+            parser.without(Flag::Await)
+        };
 
-            eat_value!(parser.parse_function_params()?);
-            eat_value!(parser.parse_function_body()?);
-        }
+        let params_start = parser.start();
+        let params = eat_value!(parser.parse_typed_function_params(true)?);
+        let body = if allow_signature {
+            opt_value!(parser.parse_function_body()?)
+        } else {
+            Some(eat_value!(parser.parse_function_body()?))
+        };
+        parser.check_params(params_start, &params, false, body.as_ref())?;
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some((kind, id, params, body)))
     }
 
-    pub fn parse_function_params(&mut self) -> OptResult<()> {
+    pub fn parse_function_params(&mut self) -> OptResult<functions::FunctionParams<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
 
+        let mut params = vec![];
+        let mut last_param = None;
         loop {
-            if let TokenResult::Some(_) = self.parse_binding_rest_element()? {
+            let param_start = self.start();
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Ellipsis) {
+                let id = eat_value!(self.parse_binding_element_target()?);
+                let type_annotation = opt_value!(self.parse_type_annotation()?);
+
+                last_param = Some(self.located(param_start, functions::FunctionRestParam {
+                    id,
+                    type_annotation,
+                    position: None,
+                    comments: None,
+                }).into());
                 break;
             }
 
-            if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-                opt_value!(self.parse_initializer()?);
-            } else if let TokenResult::Some(_) = self.binding_identifier() {
-                opt_value!(self.parse_initializer()?);
+            let decorators = self.parse_function_param_decorators()?;
+            let modifiers = self.parse_parameter_modifiers();
+            let id = match self.parse_this_param() {
+                TokenResult::Some(id) => TokenResult::Some(id.into()),
+                TokenResult::None => self.parse_binding_element_target()?,
+            };
+            let id = match id {
+                TokenResult::Some(id) => id,
+                TokenResult::None if decorators.is_empty() && modifiers.is_empty() => break,
+                TokenResult::None => bail!("Expected a parameter after its decorators"),
+            };
+            let optional = self.has_types() &&
+                opt_value!(self.punc(tokens::PunctuatorToken::Question)).is_some();
+            let type_annotation = opt_value!(self.parse_type_annotation()?);
+            let init = opt_value!(self.with(Flag::In).parse_initializer()?);
+
+            let param = self.located(param_start, functions::FunctionParam {
+                decorators,
+                modifiers,
+                id,
+                optional,
+                type_annotation,
+                init,
+                position: None,
+                comments: None,
+            });
+
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+                params.push((param, self.token_data()));
             } else {
+                last_param = Some(param.into());
                 break;
             }
+        }
 
-            if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
-                break;
+        eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+
+        Ok(TokenResult::Some(self.located(start, functions::FunctionParams {
+            type_parameters: None,
+            params,
+            last_param,
+            return_type: None,
+            predicate: None,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // The "this" parameter of TypeScript and Flow, like "function f(this: A) {}".
+    fn parse_this_param(&mut self) -> TokenResult<BindingIdentifier<'code>> {
+        if !self.has_types() || !self.is_word_before("this", &[tokens::PunctuatorToken::Colon]) {
+            return TokenResult::None;
+        }
+
+        let start = self.start();
+        self.pop();
+        TokenResult::Some(self.located(start, BindingIdentifier::from("this")))
+    }
+
+    /// Check the names that parameters bind. Only sloppy mode functions with
+    /// plain parameters, like "function f(a, a) {}", may repeat a name, and
+    /// only those can have a "use strict" directive. Arrow functions and
+    /// methods are `unique`, never allowing a repeated name.
+    pub fn check_params<P: Visitable<'code>>(&self, start: tokenizer::Position, params: &P, unique: bool, body: Option<&functions::FunctionBody<'code>>) -> Result<()> {
+        let mut names = ParamNames { names: vec![], simple: true };
+        params.accept(&mut names);
+
+        let use_strict = body.is_some_and(|body| body.directives.iter().any(is_use_strict));
+        if use_strict && !names.simple {
+            return Err(self.locate_error(start, format_err!("\"use strict\" is not allowed in functions with non-simple parameters")));
+        }
+
+        if unique || use_strict || self.flags.is_strict || !names.simple {
+            for (i, name) in names.names.iter().enumerate() {
+                if names.names[..i].contains(name) {
+                    return Err(self.locate_error(start, format_err!("Duplicate parameter \"{}\"", name)));
+                }
             }
         }
+        Ok(())
+    }
 
-        eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+    pub fn parse_function_body(&mut self) -> OptResult<functions::FunctionBody<'code>> {
+        self.nested(Self::parse_function_body_nested)
+    }
+
+    fn parse_function_body_nested(&mut self) -> OptResult<functions::FunctionBody<'code>> {
+        let start = self.start();
+        let mut parser = self.without(Flag::Template);
+        let mut parser = parser.with(Flag::Return);
+        let mut parser = parser.without(Flag::Loop);
+        let mut parser = parser.without(Flag::Switch);
+        try_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+
+        // Initializers in an enclosing object literal don't affect the body,
+        // and "break" and "continue" can't name labels outside of it.
+        let outer_cover_init = mem::replace(&mut parser.cover_init, false);
+        let outer_labels = mem::take(&mut parser.labels);
+
+        let items = parser.parse_body_items(Parser::parse_statement_list_item);
+        parser.labels = outer_labels;
+        let (directives, body) = items?;
+
+        eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
 
-        Ok(TokenResult::Some(()))
+        parser.cover_init = outer_cover_init;
+
+        Ok(TokenResult::Some(parser.located(start, functions::FunctionBody {
+            directives,
+            body,
+            position: None,
+            comments: None,
+        })))
+    }
+}
+
+// The names that parameters bind, and whether the parameters are simple,
+// meaning plain names without default values. Types and the expressions in
+// default values are skipped, since they bind names of their own.
+struct ParamNames<'code> {
+    names: Vec<Str<'code>>,
+    simple: bool,
+}
+impl<'code> Visit<'code> for ParamNames<'code> {
+    fn visit_binding_identifier(&mut self, node: &general::BindingIdentifier<'code>) {
+        self.names.push(node.value.clone());
+    }
+    fn visit_initializer(&mut self, _: &general::Initializer<'code>) {
+        self.simple = false;
+    }
+    fn visit_computed_property_name(&mut self, _: &general::ComputedPropertyName<'code>) {
+        self.simple = false;
+    }
+    fn visit_function_rest_param(&mut self, node: &functions::FunctionRestParam<'code>) {
+        self.simple = false;
+        node.walk(self);
+    }
+    fn visit_object_binding_pattern(&mut self, node: &patterns::ObjectBindingPattern<'code>) {
+        self.simple = false;
+        node.walk(self);
     }
-    pub fn parse_function_body(&mut self) -> OptResult<()> {
-        self.parse_block_statement()
+    fn visit_array_binding_pattern(&mut self, node: &patterns::ArrayBindingPattern<'code>) {
+        self.simple = false;
+        node.walk(self);
     }
+    fn visit_type_annotation(&mut self, _: &typescript::TypeAnnotation<'code>) {}
 }
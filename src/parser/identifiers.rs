@@ -0,0 +1,51 @@
+use ast::general::{BindingIdentifier, ReferenceIdentifier, PropertyIdentifier};
+use ast::statement::LabelIdentifier;
+use ast::modules::ModuleIdentifier;
+use tokenizer::Tokenizer;
+use parser::Parser;
+use parser::utils::TokenResult;
+
+impl<'code, T> Parser<'code, T>
+where
+    T: Tokenizer<'code>
+{
+    pub fn parse_binding_identifier(&mut self) -> TokenResult<BindingIdentifier<'code>> {
+        let start = self.start();
+        let ident = try_token!(self.binding_identifier());
+        let name = self.text(ident.name);
+
+        TokenResult::Some(self.located(start, BindingIdentifier::from(name)))
+    }
+
+    pub fn parse_reference_identifier(&mut self) -> TokenResult<ReferenceIdentifier<'code>> {
+        let start = self.start();
+        let ident = try_token!(self.reference_identifier());
+        let name = self.text(ident.name);
+
+        TokenResult::Some(self.located(start, ReferenceIdentifier::from(name)))
+    }
+
+    pub fn parse_label_identifier(&mut self) -> TokenResult<LabelIdentifier<'code>> {
+        let start = self.start();
+        let ident = try_token!(self.label_identifier());
+        let name = self.text(ident.name);
+
+        TokenResult::Some(self.located(start, LabelIdentifier::new(name)))
+    }
+
+    pub fn parse_property_identifier(&mut self) -> TokenResult<PropertyIdentifier<'code>> {
+        let start = self.start();
+        let ident = try_token!(self.identifier());
+        let name = self.text(ident.name);
+
+        TokenResult::Some(self.located(start, PropertyIdentifier::from(name)))
+    }
+
+    pub fn parse_module_identifier(&mut self) -> TokenResult<ModuleIdentifier<'code>> {
+        let start = self.start();
+        let ident = try_token!(self.identifier());
+        let name = self.text(ident.name);
+
+        TokenResult::Some(self.located(start, ModuleIdentifier::new(name)))
+    }
+}
@@ -0,0 +1,1216 @@
+//! Reparsing a tree after edits to the code it was parsed from.
+//!
+//! Editors keep a tree for the code being edited, and reparse it as the code
+//! changes rather than parsing all of it again:
+//!
+//! ```ignore
+//! let options = ParserOptions::script();
+//! let mut script = parse_script(&code, &options)?;
+//! let edit = TextEdit { range: 10..11, text: "b".into() };
+//! let code = reparse_script(&mut script, &code, &[edit], &options)?;
+//! ```
+//!
+//! Only the items of the smallest function body or block that contains the
+//! edits are parsed again, with the same flags the parser had there, like
+//! whether the code is strict or `yield` is allowed. Edits outside of any
+//! block, or that touch the braces or directives of the ones around them,
+//! reparse the top-level items they touch instead.
+//!
+//! Items are parsed starting from the end of the item before the edits, and
+//! parsing stops once it reaches the start of an item past the edits. From
+//! there the old items are reused, and the positions of everything after the
+//! edits are shifted to match the new code. Edits inside the comments around
+//! an item reparse that item too, since its comments are read again. The
+//! result is the same tree that parsing the new code gives.
+//!
+//! The new code is only returned, so the tree can't borrow from it. Reused
+//! items keep borrowing from the code they were first parsed from, while
+//! reparsed items own their strings.
+
+use std::ops::Range;
+
+use ast::{alias, classes, functions, objects, root, statement};
+use ast::{CommentNode, IntoOwned, Node, NodeChildren, NodeComments, NodePosition, PositionRange, Separators};
+use ast::functions::Directive;
+use ast::fold::Foldable;
+use ast::visit::{Visitable, VisitMut, Walk};
+use parser::{self, Flag, Parser, ParserOptions};
+use parser::comments::attach_comments;
+use parser::file::StripParens;
+use parser::utils::{OptResult, Result, TokenResult};
+use parser::statements::{is_directive, is_use_strict};
+use tokenizer::{tokens, Position, SliceTokenizer};
+
+/// A replacement of part of the code, by byte offsets into the code as it
+/// was before the edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// Apply edits to the code in order, each to the result of the ones before.
+pub fn apply_edits(code: &str, edits: &[TextEdit]) -> Result<String> {
+    let mut code = code.to_string();
+    for edit in edits {
+        let Range { start, end } = edit.range;
+        if start > end || !code.is_char_boundary(start) || !code.is_char_boundary(end) {
+            bail!("Edit {:?} is outside of the code", edit.range);
+        }
+        code.replace_range(start..end, &edit.text);
+    }
+    Ok(code)
+}
+
+/// Update a script parsed from `code` with the given options to match the
+/// code after the given edits, and return the new code. If the new code
+/// fails to parse, the script is left unchanged.
+pub fn reparse_script(script: &mut root::Script<'_>, code: &str, edits: &[TextEdit], options: &ParserOptions) -> Result<String> {
+    reparse(script, code, edits, options)
+}
+
+/// Update a module parsed from `code` with the given options to match the
+/// code after the given edits, and return the new code. If the new code
+/// fails to parse, the module is left unchanged.
+pub fn reparse_module(module: &mut root::Module<'_>, code: &str, edits: &[TextEdit], options: &ParserOptions) -> Result<String> {
+    reparse(module, code, edits, options)
+}
+
+// The part of the code that differs between the old and new code.
+struct Change<'code> {
+    new_code: &'code str,
+    options: &'code ParserOptions,
+
+    // The length of the common prefix, where the change ends in each code,
+    // and how much longer the new code is.
+    prefix: usize,
+    old_changed: usize,
+    new_changed: usize,
+    delta: isize,
+}
+impl<'code> Change<'code> {
+    fn new(old_code: &str, new_code: &'code str, options: &'code ParserOptions) -> Change<'code> {
+        let prefix = old_code.bytes().zip(new_code.bytes()).take_while(|&(a, b)| a == b).count();
+        let suffix = old_code[prefix..].bytes().rev().zip(new_code[prefix..].bytes().rev()).take_while(|&(a, b)| a == b).count();
+
+        Change {
+            new_code,
+            options,
+            prefix,
+            old_changed: old_code.len() - suffix,
+            new_changed: new_code.len() - suffix,
+            delta: new_code.len() as isize - old_code.len() as isize,
+        }
+    }
+
+    // Whether a node in the old code contains the change without it touching
+    // the node's first or last character, like the braces of a block.
+    fn within(&self, position: Option<&NodePosition>) -> bool {
+        position.is_some_and(|p| p.start < self.prefix && self.old_changed < p.end)
+    }
+}
+
+// The flags that the parser has where items are parsed.
+#[derive(Debug, Clone, Copy)]
+struct Context {
+    module: bool,
+    strict: bool,
+    allow_return: bool,
+    allow_yield: bool,
+    allow_await: bool,
+    in_loop: bool,
+    in_switch: bool,
+
+    // Inside of a labelled statement, whose label "break" and "continue"
+    // could name. Items there are only reparsed along with the label.
+    labelled: bool,
+}
+impl Context {
+    fn function(self, kind: &functions::FunctionKind) -> Context {
+        use ast::functions::FunctionKind::*;
+
+        Context {
+            allow_yield: matches!(*kind, Generator | AsyncGenerator),
+            allow_await: matches!(*kind, Async | AsyncGenerator),
+            ..self.body()
+        }
+    }
+
+    fn method(self, kind: &objects::MethodKind) -> Context {
+        use ast::objects::MethodKind::*;
+
+        match *kind {
+            // Accessors keep the flags from around them.
+            Get | Set => self.body(),
+            _ => Context {
+                allow_yield: matches!(*kind, Generator | AsyncGenerator),
+                allow_await: matches!(*kind, Async | AsyncGenerator),
+                ..self.body()
+            },
+        }
+    }
+
+    // The flags inside of a function body, which loops, switches and labels
+    // around the function don't reach.
+    fn body(self) -> Context {
+        Context {
+            in_loop: false,
+            in_switch: false,
+            labelled: false,
+            ..self
+        }
+    }
+
+    // Parse items from the start position until one of them ends past the
+    // change and is followed by an item that `reusable` accepts. The items
+    // either run to the end of the code or to a closing curly brace.
+    fn parse<'code, 'src, I, F>(
+        self,
+        change: &Change<'code>,
+        start: Position,
+        reusable: &dyn Fn(usize) -> bool,
+        parse_item: F,
+        closing: bool,
+    ) -> Result<Parsed<I>>
+    where
+        I: Foldable<'src>,
+        F: FnMut(&mut Parser<'code, SliceTokenizer<'code>>) -> OptResult<I>,
+    {
+        let mut p = Parser::with_options(SliceTokenizer::at(change.new_code, start), change.options);
+        let mut p = p.with(flag(self.module, Flag::Module));
+        let mut p = p.with(flag(self.strict, Flag::Strict));
+        let mut p = p.with(flag(self.allow_return, Flag::Return));
+        let mut p = p.with(flag(self.allow_yield, Flag::Yield));
+        let mut p = p.with(flag(self.allow_await, Flag::Await));
+        let mut p = p.with(flag(self.in_loop, Flag::Loop));
+        let mut p = p.with(flag(self.in_switch, Flag::Switch));
+        parse_items(&mut p, parse_item, change.new_changed, reusable, closing)
+    }
+}
+
+fn flag(set: bool, flag: Flag) -> Flag {
+    if set {
+        flag
+    } else {
+        Flag::Noop
+    }
+}
+
+// The items parsed from part of the code, owning their strings since the
+// new code does not outlive the reparse.
+struct Parsed<I> {
+    items: Vec<I>,
+    comments: Vec<CommentNode<'static>>,
+
+    // The start of the token that parsing stopped at, and whether it was
+    // the end of the items rather than the start of an item to reuse.
+    stop: Position,
+    eof: bool,
+}
+
+trait Root<'src>: Node<'src> + Sized {
+    type Item: NodeChildren<'src> + Foldable<'src> + Visitable<'src>;
+
+    fn parse(code: &str, options: &ParserOptions) -> Result<Self>;
+
+    fn parse_item<'code>(p: &mut Parser<'code, SliceTokenizer<'code>>) -> OptResult<Self::Item>;
+
+    // The flags for the top-level items, which are strict if the body has a
+    // "use strict" directive.
+    fn context(options: &ParserOptions, strict: bool) -> Context;
+
+    fn parts(&mut self) -> (&mut Vec<Directive<'src>>, &mut Vec<Self::Item>);
+
+    // Whether the item would be a directive at the start of the body.
+    fn is_directive(item: &Self::Item) -> bool;
+}
+impl<'src> Root<'src> for root::Script<'src> {
+    type Item = alias::StatementItem<'src>;
+
+    fn parse(code: &str, options: &ParserOptions) -> Result<root::Script<'src>> {
+        Ok(parser::parse_script(code, options)?.into_owned())
+    }
+
+    fn parse_item<'code>(p: &mut Parser<'code, SliceTokenizer<'code>>) -> OptResult<Self::Item> {
+        owned(p.parse_script_item())
+    }
+
+    fn context(options: &ParserOptions, strict: bool) -> Context {
+        Context {
+            module: false,
+            strict,
+            allow_return: options.allow_return_outside_function,
+            allow_yield: false,
+            allow_await: false,
+            in_loop: false,
+            in_switch: false,
+            labelled: false,
+        }
+    }
+
+    fn parts(&mut self) -> (&mut Vec<Directive<'src>>, &mut Vec<Self::Item>) {
+        (&mut self.directives, &mut self.body)
+    }
+
+    fn is_directive(item: &Self::Item) -> bool {
+        is_statement_directive(item)
+    }
+}
+impl<'src> Root<'src> for root::Module<'src> {
+    type Item = alias::ModuleStatementItem<'src>;
+
+    fn parse(code: &str, options: &ParserOptions) -> Result<root::Module<'src>> {
+        Ok(parser::parse_module(code, options)?.into_owned())
+    }
+
+    fn parse_item<'code>(p: &mut Parser<'code, SliceTokenizer<'code>>) -> OptResult<Self::Item> {
+        owned(p.parse_module_item())
+    }
+
+    fn context(options: &ParserOptions, _strict: bool) -> Context {
+        Context {
+            module: true,
+            strict: true,
+            allow_return: options.allow_return_outside_function,
+            allow_yield: false,
+            allow_await: false,
+            in_loop: false,
+            in_switch: false,
+            labelled: false,
+        }
+    }
+
+    fn parts(&mut self) -> (&mut Vec<Directive<'src>>, &mut Vec<Self::Item>) {
+        (&mut self.directives, &mut self.body)
+    }
+
+    fn is_directive(item: &Self::Item) -> bool {
+        match *item {
+            alias::ModuleStatementItem::Expression(ref stmt) => is_directive(stmt),
+            _ => false,
+        }
+    }
+}
+
+// Copy the strings of an item parsed from the new code, so that it can join
+// a tree that outlives the new code.
+fn owned<I: IntoOwned>(item: OptResult<I>) -> OptResult<I::Owned> {
+    Ok(item?.map(IntoOwned::into_owned))
+}
+
+fn is_statement_directive(item: &alias::StatementItem<'_>) -> bool {
+    match *item {
+        alias::StatementItem::Expression(ref stmt) => is_directive(stmt),
+        _ => false,
+    }
+}
+
+fn parse_items<'code, 'src, I, F>(
+    p: &mut Parser<'code, SliceTokenizer<'code>>,
+    mut parse_item: F,
+    changed: usize,
+    reusable: &dyn Fn(usize) -> bool,
+    closing: bool,
+) -> Result<Parsed<I>>
+where
+    I: Foldable<'src>,
+    F: FnMut(&mut Parser<'code, SliceTokenizer<'code>>) -> OptResult<I>,
+{
+    let mut items = vec![];
+    let eof = loop {
+        // Items start in expression position, which decides whether a
+        // leading "/" starts a regular expression.
+        p.expect_expression();
+        let next = p.start();
+        if next.offset >= changed && reusable(next.offset) {
+            break false;
+        }
+
+        match parse_item(p)? {
+            TokenResult::Some(item) => items.push(item),
+            TokenResult::None => break true,
+        }
+    };
+
+    let stop = p.start();
+    if eof && closing {
+        eat_value!(p.punc(tokens::PunctuatorToken::CurlyClose));
+    } else if eof {
+        eat_value!(p.eof());
+    }
+    if !p.options.preserve_parens {
+        items = items.fold(&mut StripParens);
+    }
+
+    Ok(Parsed {
+        items,
+        comments: p.comments.drain(..).map(IntoOwned::into_owned).collect(),
+        stop,
+        eof,
+    })
+}
+
+fn reparse<'src, R: Root<'src>>(root: &mut R, code: &str, edits: &[TextEdit], options: &ParserOptions) -> Result<String> {
+    let new_code = apply_edits(code, edits)?;
+    let change = Change::new(code, &new_code, options);
+    if !reparse_nested(root, &change) && !reparse_items(root, &change)? {
+        *root = R::parse(&new_code, options)?;
+    }
+    Ok(new_code)
+}
+
+// Replace the items of the innermost function body or block around the
+// change, or return false if there is none that can be reparsed on its own.
+fn reparse_nested<'src, R: Root<'src>>(root: &mut R, change: &Change<'_>) -> bool {
+    let (holder, plan) = {
+        let (directives, body) = root.parts();
+        let mut finder = Finder {
+            change,
+            context: R::context(change.options, directives.iter().any(is_use_strict)),
+            found: None,
+        };
+        for item in body.iter_mut() {
+            if change.within(item_node(item).position()) {
+                item.accept_mut(&mut finder);
+            }
+        }
+        match finder.found {
+            Some(found) => found,
+            None => return false,
+        }
+    };
+
+    plan.shift.tree(root);
+
+    let mut splicer = Splicer {
+        holder,
+        plan: Some(plan),
+    };
+    let (_, body) = root.parts();
+    for item in body.iter_mut() {
+        item.accept_mut(&mut splicer);
+    }
+    true
+}
+
+// Replace the top-level items affected by the change, or return false if the
+// whole code needs to be parsed again.
+fn reparse_items<'src, R: Root<'src>>(root: &mut R, change: &Change<'_>) -> Result<bool> {
+    let end = match root.position() {
+        Some(position) => end_of(position),
+        None => return Ok(false),
+    };
+
+    let plan = {
+        let (directives, body) = root.parts();
+
+        // Directives change how everything after them is parsed.
+        let directives_end = directives.last().and_then(|d| d.position()).map(end_of);
+        if body.is_empty() || directives_end.is_some_and(|end| change.prefix <= end.offset) {
+            return Ok(false);
+        }
+
+        let start = directives_end.unwrap_or(Position {
+            offset: 0,
+            line: 1,
+            column: 0,
+        });
+        let context = R::context(change.options, directives.iter().any(is_use_strict));
+        let parse = |start, reusable: &dyn Fn(usize) -> bool| context.parse(change, start, reusable, R::parse_item, false);
+        match plan(change, body, start, end, parse)? {
+            Some(plan) => plan,
+            None => return Ok(false),
+        }
+    };
+    if plan.first == 0 && plan.parsed.items.first().is_some_and(R::is_directive) {
+        return Ok(false);
+    }
+
+    plan.shift.tree(root);
+
+    let (start, stop) = (plan.start.offset, plan.parsed.stop.offset);
+    let inner = {
+        let (directives, body) = root.parts();
+        splice(plan, body, directives.last_mut().map(|d| d as &mut dyn Node<'src>))
+    };
+    adopt_comments(root, start, stop, inner);
+    Ok(true)
+}
+
+// The items of a body to replace, and the ones parsed in their place.
+struct Plan<I> {
+    first: usize,
+    next: usize,
+
+    // Where parsing started, and how the code after the parsed items moved.
+    start: Position,
+    parsed: Parsed<I>,
+    shift: Shift,
+}
+
+// Parse the items of a body that touch the change again. `start` is where
+// the first item of the body may start, and `end` where the body ends in the
+// old code.
+fn plan<'src, I, F>(change: &Change<'_>, items: &mut [I], mut start: Position, end: Position, parse: F) -> Result<Option<Plan<I>>>
+where
+    I: NodeChildren<'src>,
+    F: FnOnce(Position, &dyn Fn(usize) -> bool) -> Result<Parsed<I>>,
+{
+    let positions = match items.iter_mut().map(|item| item_node(item).position().cloned()).collect::<Option<Vec<_>>>() {
+        Some(positions) => positions,
+        None => return Ok(None),
+    };
+    let extents: Vec<_> = items.iter_mut().zip(&positions).map(|(item, position)| extent(item_node(item), position)).collect();
+
+    // The items touching the change, and the one before them, since automatic
+    // semicolon insertion may now continue it into the next line. Items whose
+    // comments the change touches are reparsed too, so that the comments are
+    // read from the new code.
+    let touched = extents.iter().position(|e| e.end >= change.prefix).unwrap_or(positions.len());
+    let first = touched.saturating_sub(1);
+    let after = extents.iter().position(|e| e.start > change.old_changed).unwrap_or(positions.len());
+    if first > 0 {
+        start = end_of(&positions[first - 1]);
+    }
+
+    let reusable = |offset: usize| {
+        let old = offset as isize - change.delta;
+        positions[after..].binary_search_by_key(&old, |p| p.start as isize).is_ok()
+    };
+    let parsed = parse(start, &reusable)?;
+
+    // The first item that is kept after the reparsed ones.
+    let next = match parsed.eof {
+        true => positions.len(),
+        false => {
+            let old = (parsed.stop.offset as isize - change.delta) as usize;
+            after + positions[after..].binary_search_by_key(&old, |p| p.start).unwrap()
+        }
+    };
+    let shift = Shift {
+        old: positions.get(next).map(start_of).unwrap_or(end),
+        new: parsed.stop,
+    };
+
+    Ok(Some(Plan {
+        first,
+        next,
+        start,
+        parsed,
+        shift,
+    }))
+}
+
+// Replace the planned items, and return the comments between them that were
+// not attached to any item. `before` is the node before the first item of
+// the body, if any.
+fn splice<'a, 'src, I: NodeChildren<'src>>(plan: Plan<I>, items: &'a mut Vec<I>, before: Option<&'a mut dyn Node<'src>>) -> Vec<Separators<'src>> {
+    let Plan { first, next, start, parsed, .. } = plan;
+    let count = parsed.items.len();
+    items.splice(first..next, parsed.items);
+
+    // Comments between the reparsed items and the ones around them are
+    // attached again, since they may now belong to the reparsed items.
+    let mut region = Region {
+        children: vec![],
+        comments: Default::default(),
+    };
+    let prev = match first {
+        0 => before,
+        _ => None,
+    };
+    let from = if prev.is_none() { first.saturating_sub(1) } else { first };
+    let to = (first + count + 1).min(items.len());
+    region.children.extend(prev);
+    region.children.extend(items[from..to].iter_mut().map(item_node));
+
+    for child in region.children.iter_mut() {
+        detach_comments(&mut **child, start.offset, parsed.stop.offset);
+    }
+    attach_comments(&mut region, parsed.comments);
+
+    region.comments.inner
+}
+
+// Give the node holding a body the comments in it that no item took, in
+// place of the ones it had between the given offsets.
+fn adopt_comments<'src>(holder: &mut dyn Node<'src>, start: usize, end: usize, inner: Vec<Separators<'src>>) {
+    detach_comments(holder, start, end);
+    if !inner.is_empty() {
+        holder.comments_mut().inner.extend(inner);
+    }
+}
+
+// Which body the reparsed items belong to, by the kind of node holding them
+// and its start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Holder {
+    Function(usize),
+    Block(usize),
+}
+
+// Finds the innermost function body or block around the change whose items
+// can be reparsed on their own, keeping track of the flags to parse them
+// with.
+struct Finder<'a, 'code: 'a, 'src> {
+    change: &'a Change<'code>,
+    context: Context,
+    found: Option<(Holder, Plan<alias::StatementItem<'src>>)>,
+}
+impl<'a, 'code, 'src> Finder<'a, 'code, 'src> {
+    // Walk into a node around the change with the given flags, returning
+    // whether the change was inside it.
+    fn within<N: Node<'src> + Walk<'src>>(&mut self, node: &mut N, context: Context) -> bool {
+        if self.found.is_some() || !self.change.within(node.position()) {
+            return false;
+        }
+
+        let outer = self.context;
+        self.context = context;
+        node.walk_mut(self);
+        self.context = outer;
+        true
+    }
+
+    fn plan(&self, items: &mut [alias::StatementItem<'src>], start: Position, position: &NodePosition, context: Context) -> Option<Plan<alias::StatementItem<'src>>> {
+        if context.labelled {
+            return None;
+        }
+
+        let end = Position {
+            offset: position.end - 1,
+            line: position.range.end.0,
+            column: position.range.end.1 - 1,
+        };
+        let change = self.change;
+        let parse = |start, reusable: &dyn Fn(usize) -> bool| {
+            context.parse(change, start, reusable, |p| owned(p.parse_statement_list_item()), true)
+        };
+
+        // Errors are left for reparsing the items around this body to find.
+        let plan = plan(change, items, start, end, parse).ok()??;
+
+        // The body must still end at the same closing curly brace.
+        if plan.parsed.eof && plan.parsed.stop.offset as isize != end.offset as isize + change.delta {
+            return None;
+        }
+        Some(plan)
+    }
+}
+impl<'a, 'code, 'src> VisitMut<'src> for Finder<'a, 'code, 'src> {
+    fn visit_function_declaration(&mut self, node: &mut functions::FunctionDeclaration<'src>) {
+        let context = self.context.function(&node.kind);
+        self.within(node, context);
+    }
+
+    fn visit_function_expression(&mut self, node: &mut functions::FunctionExpression<'src>) {
+        let context = self.context.function(&node.kind);
+        self.within(node, context);
+    }
+
+    fn visit_export_default_function_declaration(&mut self, node: &mut functions::ExportDefaultFunctionDeclaration<'src>) {
+        let context = self.context.function(&node.kind);
+        self.within(node, context);
+    }
+
+    fn visit_arrow_function_expression(&mut self, node: &mut functions::ArrowFunctionExpression<'src>) {
+        use ast::functions::ArrowFunctionKind::*;
+
+        let context = Context {
+            allow_await: self.context.allow_await || matches!(node.kind, Async | AsyncGenerator),
+            ..self.context.body()
+        };
+        self.within(node, context);
+    }
+
+    fn visit_object_method(&mut self, node: &mut objects::ObjectMethod<'src>) {
+        let context = self.context.method(&node.kind);
+        self.within(node, context);
+    }
+
+    fn visit_class_method(&mut self, node: &mut classes::ClassMethod<'src>) {
+        let context = self.context.method(&node.kind);
+        self.within(node, context);
+    }
+
+    fn visit_class_heritage(&mut self, node: &mut classes::ClassHeritage<'src>) {
+        let context = Context { strict: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_class_body(&mut self, node: &mut classes::ClassBody<'src>) {
+        let context = Context { strict: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_function_body(&mut self, node: &mut functions::FunctionBody<'src>) {
+        let context = Context {
+            strict: self.context.strict || node.directives.iter().any(is_use_strict),
+            allow_return: true,
+            ..self.context
+        };
+        if !self.within(node, context) || self.found.is_some() {
+            return;
+        }
+
+        let position = match node.position {
+            Some(ref position) => position.clone(),
+            None => return,
+        };
+        let directives_end = node.directives.last().and_then(|d| d.position()).map(end_of);
+        if directives_end.is_some_and(|end| self.change.prefix <= end.offset) {
+            return;
+        }
+
+        let start = directives_end.unwrap_or_else(|| after_curly(&position));
+        let plan = match self.plan(&mut node.body, start, &position, context) {
+            Some(plan) => plan,
+            None => return,
+        };
+        if plan.first == 0 && plan.parsed.items.first().is_some_and(is_statement_directive) {
+            return;
+        }
+        self.found = Some((Holder::Function(position.start), plan));
+    }
+
+    fn visit_while_statement(&mut self, node: &mut statement::WhileStatement<'src>) {
+        let context = Context { in_loop: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_do_while_statement(&mut self, node: &mut statement::DoWhileStatement<'src>) {
+        let context = Context { in_loop: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_for_statement(&mut self, node: &mut statement::ForStatement<'src>) {
+        let context = Context { in_loop: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_for_in_statement(&mut self, node: &mut statement::ForInStatement<'src>) {
+        let context = Context { in_loop: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_for_of_statement(&mut self, node: &mut statement::ForOfStatement<'src>) {
+        let context = Context { in_loop: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_for_await_statement(&mut self, node: &mut statement::ForAwaitStatement<'src>) {
+        let context = Context { in_loop: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_switch_statement(&mut self, node: &mut statement::SwitchStatement<'src>) {
+        let context = Context { in_switch: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_labelled_statement(&mut self, node: &mut statement::LabelledStatement<'src>) {
+        let context = Context { labelled: true, ..self.context };
+        self.within(node, context);
+    }
+
+    fn visit_block_statement(&mut self, node: &mut statement::BlockStatement<'src>) {
+        let context = self.context;
+        if !self.within(node, context) || self.found.is_some() {
+            return;
+        }
+
+        let position = match node.position {
+            Some(ref position) => position.clone(),
+            None => return,
+        };
+        if let Some(plan) = self.plan(&mut node.body, after_curly(&position), &position, context) {
+            self.found = Some((Holder::Block(position.start), plan));
+        }
+    }
+}
+
+// Replaces the items of the body that a plan was made for.
+struct Splicer<'src> {
+    holder: Holder,
+    plan: Option<Plan<alias::StatementItem<'src>>>,
+}
+impl<'src> Splicer<'src> {
+    // Whether the body could be inside a node at the given position.
+    fn around(&self, position: Option<&NodePosition>) -> bool {
+        let start = match self.holder {
+            Holder::Function(start) | Holder::Block(start) => start,
+        };
+        self.plan.is_some() && position.is_none_or(|p| p.start <= start && start < p.end)
+    }
+}
+impl<'src> VisitMut<'src> for Splicer<'src> {
+    fn visit_function_body(&mut self, node: &mut functions::FunctionBody<'src>) {
+        if !self.around(node.position()) {
+            return;
+        }
+        if node.position().map(|p| Holder::Function(p.start)) != Some(self.holder) {
+            return node.walk_mut(self);
+        }
+
+        let plan = self.plan.take().unwrap();
+        let (start, stop) = (plan.start.offset, plan.parsed.stop.offset);
+        let inner = splice(plan, &mut node.body, node.directives.last_mut().map(|d| d as &mut dyn Node<'src>));
+        adopt_comments(node, start, stop, inner);
+    }
+
+    fn visit_block_statement(&mut self, node: &mut statement::BlockStatement<'src>) {
+        if !self.around(node.position()) {
+            return;
+        }
+        if node.position().map(|p| Holder::Block(p.start)) != Some(self.holder) {
+            return node.walk_mut(self);
+        }
+
+        let plan = self.plan.take().unwrap();
+        let (start, stop) = (plan.start.offset, plan.parsed.stop.offset);
+        let inner = splice(plan, &mut node.body, None);
+        adopt_comments(node, start, stop, inner);
+    }
+}
+
+// The node wrapped by an item.
+fn item_node<'src, I: NodeChildren<'src>>(item: &mut I) -> &mut dyn Node<'src> {
+    let mut nodes = vec![];
+    item.collect_mut(&mut nodes);
+    nodes.pop().expect("Items wrap a node")
+}
+
+fn start_of(position: &NodePosition) -> Position {
+    Position {
+        offset: position.start,
+        line: position.range.start.0,
+        column: position.range.start.1,
+    }
+}
+
+fn end_of(position: &NodePosition) -> Position {
+    Position {
+        offset: position.end,
+        line: position.range.end.0,
+        column: position.range.end.1,
+    }
+}
+
+// Just past the opening curly brace of a body.
+fn after_curly(position: &NodePosition) -> Position {
+    Position {
+        offset: position.start + 1,
+        line: position.range.start.0,
+        column: position.range.start.1 + 1,
+    }
+}
+
+// The part of the code covered by a node and the comments around it.
+fn extent(node: &dyn Node<'_>, position: &NodePosition) -> Range<usize> {
+    let mut extent = position.start..position.end;
+    if let Some(comments) = node.comments() {
+        for comment in comments.leading.iter().chain(comments.trailing.iter()) {
+            if let Separators::Comment(CommentNode { position: Some(ref p), .. }) = *comment {
+                extent.start = extent.start.min(p.start);
+                extent.end = extent.end.max(p.end);
+            }
+        }
+    }
+    extent
+}
+
+fn outside(comment: &Separators<'_>, start: usize, end: usize) -> bool {
+    match *comment {
+        Separators::Comment(CommentNode { position: Some(ref p), .. }) => p.end <= start || p.start >= end,
+        _ => true,
+    }
+}
+
+// Remove the comments that a node holds from between the given offsets.
+fn detach_comments(node: &mut dyn Node<'_>, start: usize, end: usize) {
+    if node.comments().is_none() {
+        return;
+    }
+
+    let empty = {
+        let comments = node.comments_mut();
+        comments.leading.retain(|c| outside(c, start, end));
+        comments.trailing.retain(|c| outside(c, start, end));
+        comments.inner.retain(|c| outside(c, start, end));
+        comments.leading.is_empty() && comments.trailing.is_empty() && comments.inner.is_empty()
+    };
+    if empty {
+        node.set_comments(None);
+    }
+}
+
+// Moves positions at or after a point in the old code to where that point
+// is in the new code.
+struct Shift {
+    old: Position,
+    new: Position,
+}
+impl Shift {
+    fn offset(&self, offset: usize) -> usize {
+        (offset as isize + self.new.offset as isize - self.old.offset as isize) as usize
+    }
+
+    fn line_column(&self, (line, column): (usize, usize)) -> (usize, usize) {
+        let line_delta = self.new.line as isize - self.old.line as isize;
+        let column = match line == self.old.line {
+            true => (column as isize + self.new.column as isize - self.old.column as isize) as usize,
+            false => column,
+        };
+        ((line as isize + line_delta) as usize, column)
+    }
+
+    fn range(&self, range: &mut PositionRange) {
+        range.start = self.line_column(range.start);
+        range.end = self.line_column(range.end);
+    }
+
+    fn position(&self, position: &mut NodePosition) {
+        position.start = self.offset(position.start);
+        self.end(position);
+        position.range.start = self.line_column(position.range.start);
+    }
+
+    fn end(&self, position: &mut NodePosition) {
+        position.end = self.offset(position.end);
+        position.range.end = self.line_column(position.range.end);
+    }
+
+    // Shift a node that starts at or after the point.
+    fn node(&self, node: &mut dyn Node<'_>) {
+        if let Some(mut position) = node.position().cloned() {
+            self.position(&mut position);
+            node.set_position(Some(position));
+        }
+
+        if node.comments().is_some() {
+            let comments = node.comments_mut();
+            let all = comments.leading.iter_mut().chain(comments.trailing.iter_mut()).chain(comments.inner.iter_mut());
+            for comment in all {
+                if let Separators::Comment(CommentNode { position: Some(ref mut position), .. }) = *comment {
+                    self.position(position);
+                }
+            }
+        }
+
+        let mut tokens = vec![];
+        node.tokens_mut(&mut tokens);
+        for range in tokens {
+            self.range(range);
+        }
+
+        let mut children = vec![];
+        node.children_mut(&mut children);
+        for child in children {
+            self.node(child);
+        }
+    }
+
+    // Shift the parts of a tree that are at or after the point, leaving the
+    // nodes that end before it alone.
+    fn tree(&self, node: &mut dyn Node<'_>) {
+        match node.position().map(|p| (p.start, p.end)) {
+            Some((start, _)) if start >= self.old.offset => return self.node(node),
+            Some((_, end)) if end < self.old.offset => return,
+            Some(_) => {
+                let mut position = node.position().cloned().unwrap();
+                self.end(&mut position);
+                node.set_position(Some(position));
+            }
+            None => {}
+        }
+
+        if node.comments().is_some() {
+            let comments = node.comments_mut();
+            let all = comments.leading.iter_mut().chain(comments.trailing.iter_mut()).chain(comments.inner.iter_mut());
+            for comment in all {
+                if let Separators::Comment(CommentNode { position: Some(ref mut position), .. }) = *comment {
+                    if position.start >= self.old.offset {
+                        self.position(position);
+                    }
+                }
+            }
+        }
+
+        let point = (self.old.line, self.old.column);
+        let mut tokens = vec![];
+        node.tokens_mut(&mut tokens);
+        for range in tokens {
+            if range.start >= point {
+                self.range(range);
+            }
+        }
+
+        let mut children = vec![];
+        node.children_mut(&mut children);
+        for child in children {
+            self.tree(child);
+        }
+    }
+}
+
+// The reparsed items and their neighbours, for attaching comments.
+#[derive(Debug)]
+struct Region<'a, 'src: 'a> {
+    children: Vec<&'a mut dyn Node<'src>>,
+    comments: NodeComments<'src>,
+}
+impl<'a, 'src> Node<'src> for Region<'a, 'src> {
+    fn position(&self) -> Option<&NodePosition> {
+        None
+    }
+    fn set_position(&mut self, _position: Option<NodePosition>) {}
+
+    fn comments(&self) -> Option<&NodeComments<'src>> {
+        Some(&self.comments)
+    }
+    fn comments_mut(&mut self) -> &mut NodeComments<'src> {
+        &mut self.comments
+    }
+    fn set_comments(&mut self, comments: Option<NodeComments<'src>>) {
+        self.comments = comments.unwrap_or_default();
+    }
+
+    fn children_mut<'b>(&'b mut self, out: &mut Vec<&'b mut dyn Node<'src>>) {
+        for child in self.children.iter_mut() {
+            out.push(&mut **child);
+        }
+    }
+
+    fn tokens_mut<'b>(&'b mut self, _out: &mut Vec<&'b mut PositionRange>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::{LeadingComments, TrailingComments};
+
+    fn edit(start: usize, end: usize, text: &str) -> TextEdit {
+        TextEdit {
+            range: start..end,
+            text: text.into(),
+        }
+    }
+
+    // Reparsing should give exactly the tree that parsing the new code does.
+    fn check_script<'src>(code: &'src str, edits: &[TextEdit]) -> root::Script<'src> {
+        check_script_with(code, edits, &ParserOptions::script())
+    }
+
+    fn check_script_with<'src>(code: &'src str, edits: &[TextEdit], options: &ParserOptions) -> root::Script<'src> {
+        let mut script = parser::parse_script(code, options).unwrap();
+        let new_code = reparse_script(&mut script, code, edits, options).unwrap();
+        assert_eq!(format!("{:?}", script), format!("{:?}", parser::parse_script(&new_code, options).unwrap()));
+        script
+    }
+
+    // Whether the edits are reparsed within a function body or block.
+    fn is_nested(code: &str, edits: &[TextEdit]) -> bool {
+        let options = ParserOptions::script();
+        let mut script = parser::parse_script(code, &options).unwrap();
+        let new_code = apply_edits(code, edits).unwrap();
+        reparse_nested(&mut script, &Change::new(code, &new_code, &options))
+    }
+
+    const CODE: &str = "\
+var a = 1;
+function f(x) {
+  return x + a;
+}
+if (a) {
+  f(2);
+}
+a = f(3);
+";
+
+    #[test]
+    fn it_applies_edits_in_order() {
+        assert_eq!(apply_edits("abc", &[edit(1, 2, "xy"), edit(0, 1, "")]).unwrap(), "xyc");
+        assert!(apply_edits("abc", &[edit(2, 5, "")]).is_err());
+    }
+
+    #[test]
+    fn it_reparses_changed_items() {
+        let offset = CODE.find("x + a").unwrap();
+        check_script(CODE, &[edit(offset, offset + 1, "(x * 2)")]);
+        check_script(CODE, &[edit(0, 3, "let")]);
+        check_script(CODE, &[edit(CODE.len(), CODE.len(), "f(4);\n")]);
+    }
+
+    #[test]
+    fn it_shifts_following_items() {
+        let offset = CODE.find("var a").unwrap() + "var a = 1;".len();
+        let script = check_script(CODE, &[edit(offset, offset, "\nvar b = [\n  1,\n];")]);
+        assert_eq!(script.body.len(), 5);
+
+        let offset = CODE.find("{\n  f(2)").unwrap();
+        check_script(CODE, &[edit(offset, offset, "{ g(); }")]);
+    }
+
+    #[test]
+    fn it_reparses_nested_bodies() {
+        let code = "\
+function f(x) {
+  'use strict';
+  if (x) {
+    g(x); // g
+    h();
+  }
+  return () => { x++; };
+}
+f(1);
+";
+        let offset = code.find("g(x)").unwrap();
+        check_script(code, &[edit(offset, offset + 1, "gg")]);
+        assert!(is_nested(code, &[edit(offset, offset + 1, "gg")]));
+        check_script(code, &[edit(offset, offset, "a;\nb\n")]);
+        check_script(code, &[edit(offset + 5, offset + 6, "\n")]);
+
+        let offset = code.find("x++").unwrap();
+        check_script(code, &[edit(offset, offset + 3, "let y = x in {}")]);
+
+        // Closing the block early leaves the function body around it to
+        // reparse.
+        let offset = code.find("h()").unwrap();
+        let script = check_script(code, &[edit(offset, offset, "} {")]);
+        assert!(is_nested(code, &[edit(offset, offset, "} {")]));
+        match script.body[0] {
+            alias::StatementItem::Function(ref f) => assert_eq!(f.body.body.len(), 3),
+            _ => panic!("Expected a function"),
+        }
+        assert_eq!(script.body.len(), 2);
+    }
+
+    #[test]
+    fn it_reparses_nested_bodies_with_their_flags() {
+        let code = "async function* f() {\n  a;\n}\nclass A { m() { b; } }\n";
+        let offset = code.find("a;").unwrap();
+        check_script(code, &[edit(offset, offset + 1, "yield await a")]);
+
+        // "let" is an identifier in sloppy code, but not in class bodies.
+        let offset = code.find("b;").unwrap();
+        check_script(code, &[edit(offset, offset + 1, "let\nx")]);
+
+        check_script(code, &[edit(offset, offset + 1, "yield\na")]);
+    }
+
+    #[test]
+    fn it_reparses_loop_bodies_with_their_flags() {
+        let code = "while (a) {\n  b;\n}\nc: for (;;) {\n  d;\n}\n";
+        let offset = code.find("b;").unwrap();
+        check_script(code, &[edit(offset, offset + 1, "break")]);
+        assert!(is_nested(code, &[edit(offset, offset + 1, "break")]));
+
+        // Labels are only known to the items around the labelled statement.
+        let offset = code.find("d;").unwrap();
+        check_script(code, &[edit(offset, offset + 1, "continue c")]);
+        assert!(!is_nested(code, &[edit(offset, offset + 1, "continue c")]));
+    }
+
+    #[test]
+    fn it_keeps_comments_in_nested_bodies() {
+        let code = "if (a) {\n  // a\n}\nfunction f() { /* b */ }\n";
+        let script = check_script(code, &[edit(9, 9, "\n  b();")]);
+        assert_eq!(script.body.len(), 2);
+
+        let offset = code.find("/* b */").unwrap() + 8;
+        check_script(code, &[edit(offset, offset, "c(); ")]);
+    }
+
+    #[test]
+    fn it_reparses_items_joined_by_the_edit() {
+        // Without the semicolon, "a" is called with the parenthesized "b".
+        let script = check_script("a;\n(b);\nc;\n", &[edit(1, 2, "")]);
+        assert_eq!(script.body.len(), 2);
+
+        // Wrapping everything in a block leaves a single item.
+        let script = check_script(CODE, &[edit(CODE.len(), CODE.len(), "}\n"), edit(0, 0, "{\n")]);
+        assert_eq!(script.body.len(), 1);
+    }
+
+    #[test]
+    fn it_reparses_new_directives() {
+        let script = check_script("a;\nb;\n", &[edit(0, 1, "'use strict'")]);
+        assert_eq!(script.directives.len(), 1);
+    }
+
+    #[test]
+    fn it_keeps_comments_around_the_edit() {
+        let code = "a(); // a\n// b\nb();\n/* c */ c();\n";
+        let offset = code.find("b()").unwrap();
+        let script = check_script(code, &[edit(offset, offset + 1, "bb")]);
+
+        let comments = |i: usize| {
+            let item = &script.body[i];
+            (item.leading_comments().count(), item.trailing_comments().count())
+        };
+        assert_eq!(comments(0), (0, 1));
+        assert_eq!(comments(1), (1, 0));
+        assert_eq!(comments(2), (1, 0));
+    }
+
+    #[test]
+    fn it_reparses_edits_inside_comments() {
+        let code = "// head\nvar a = 1;\nb(); // tail\nc();\n";
+        let script = check_script(code, &[edit(2, 4, "")]);
+        assert_eq!(script.body[0].leading_comments().count(), 1);
+        check_script(code, &[edit(2, 4, "a\n")]);
+        check_script(code, &[edit(0, 0, "/* new */ ")]);
+
+        let offset = code.find("tail").unwrap();
+        let script = check_script(code, &[edit(offset, offset + 2, "")]);
+        assert_eq!(script.body[1].trailing_comments().count(), 1);
+        check_script(code, &[edit(offset, offset + 2, "\nd();//")]);
+
+        let code = "function f() {\n  // head\n  a(); /* tail */\n  b();\n}\n";
+        let offset = code.find("head").unwrap();
+        check_script(code, &[edit(offset, offset + 2, "")]);
+        let offset = code.find("tail").unwrap();
+        check_script(code, &[edit(offset, offset + 2, "")]);
+    }
+
+    #[test]
+    fn it_reparses_modules() {
+        let code = "import a from 'a';\nexport const b = a;\nexport default b;\n";
+        let offset = code.find("= a").unwrap() + 2;
+
+        let options = ParserOptions::module();
+        let mut module = parser::parse_module(code, &options).unwrap();
+        let new_code = reparse_module(&mut module, code, &[edit(offset, offset + 1, "a + 1")], &options).unwrap();
+        assert_eq!(format!("{:?}", module), format!("{:?}", parser::parse_module(&new_code, &options).unwrap()));
+    }
+
+    #[test]
+    fn it_leaves_the_tree_unchanged_on_errors() {
+        let options = ParserOptions::script();
+        let mut script = parser::parse_script(CODE, &options).unwrap();
+        assert!(reparse_script(&mut script, CODE, &[edit(0, 3, "var var")], &options).is_err());
+        assert_eq!(format!("{:?}", script), format!("{:?}", parser::parse_script(CODE, &options).unwrap()));
+    }
+
+    #[test]
+    fn it_reparses_with_options() {
+        let options = ParserOptions {
+            allow_return_outside_function: true,
+            preserve_parens: false,
+            ..ParserOptions::script()
+        };
+        let code = "a();
+return (b);
+c();
+";
+        let offset = code.find("(b)").unwrap();
+        check_script_with(code, &[edit(offset, offset + 3, "(b + 1) * 2")], &options);
+
+        let mut script = parser::parse_script("a();
+b();
+", &ParserOptions::script()).unwrap();
+        assert!(reparse_script(&mut script, "a();
+b();
+", &[edit(5, 5, "return ")], &ParserOptions::script()).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+// JSX elements, parsed when the JSX syntax extension is enabled.
+//
+// The tokenizer reads tags and the text between them differently from the
+// rest of the code, so the parser switches it between the three with the
+// JsxTag and JsxText flags. A token is read when the parser first looks at
+// it, so each flag is set after consuming the token before the part of the
+// element that it applies to.
+
+use ast::jsx;
+use ast::NodeBox;
+use tokenizer::{self, Tokenizer, tokens};
+use parser::{Parser, Flag};
+use parser::utils::{OptResult, Result, TokenResult};
+
+impl<'code, T> Parser<'code, T>
+where
+    T: Tokenizer<'code>
+{
+    #[inline(never)]
+    pub fn parse_jsx_element(&mut self) -> OptResult<jsx::Element<'code>> {
+        if !self.options.extensions.jsx {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        let mut parser = self.without(Flag::Template);
+        let mut parser = parser.with(Flag::JsxTag);
+        try_value!(parser.punc(tokens::PunctuatorToken::LAngle));
+
+        parser.parse_jsx_element_after_angle(start).map(TokenResult::Some)
+    }
+
+    // The rest of an element after its "<", with the JsxTag flag set.
+    fn parse_jsx_element_after_angle(&mut self, start: tokenizer::Position) -> Result<jsx::Element<'code>> {
+        self.nested(|p| p.parse_jsx_element_after_angle_nested(start))
+    }
+
+    fn parse_jsx_element_after_angle_nested(&mut self, start: tokenizer::Position) -> Result<jsx::Element<'code>> {
+        let opening = eat_value!(self.parse_jsx_element_name());
+
+        let mut attributes = vec![];
+        while let TokenResult::Some(attribute) = self.parse_jsx_attribute()? {
+            attributes.push(attribute);
+        }
+
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Slash) {
+            eat_value!(self.punc(tokens::PunctuatorToken::RAngle));
+
+            return Ok(self.located(start, jsx::Element {
+                opening,
+                attributes,
+                children: vec![],
+                closing: None,
+                position: None,
+                comments: None,
+            }));
+        }
+        eat_value!(self.punc(tokens::PunctuatorToken::RAngle));
+
+        let mut children = vec![];
+        let closing = {
+            let mut parser = self.with(Flag::JsxText);
+            loop {
+                let child_start = parser.start();
+                if let TokenResult::Some(text) = parser.jsx_text() {
+                    let value = parser.text(text.value);
+                    children.push(parser.located(child_start, jsx::Text {
+                        value,
+                        raw: None,
+                        position: None,
+                        comments: None,
+                    }).into());
+                    continue;
+                }
+                if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::CurlyOpen) {
+                    children.push(parser.parse_jsx_child_expression(child_start)?);
+                    continue;
+                }
+
+                eat_value!(parser.punc(tokens::PunctuatorToken::LAngle));
+                let mut parser = parser.with(Flag::JsxTag);
+                if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Slash) {
+                    let closing = eat_value!(parser.parse_jsx_element_name());
+                    if element_name(&closing) != element_name(&opening) {
+                        bail!("Expected corresponding closing tag for <{}>", element_name(&opening));
+                    }
+                    eat_value!(parser.punc(tokens::PunctuatorToken::RAngle));
+                    break closing;
+                }
+                children.push(parser.parse_jsx_element_after_angle(child_start)?.into());
+            }
+        };
+
+        Ok(self.located(start, jsx::Element {
+            opening,
+            attributes,
+            children,
+            closing: Some(closing),
+            position: None,
+            comments: None,
+        }))
+    }
+
+    // A child in curly braces, after the "{".
+    fn parse_jsx_child_expression(&mut self, start: tokenizer::Position) -> Result<jsx::Child<'code>> {
+        let mut parser = self.without(Flag::JsxText);
+        parser.expect_expression();
+
+        if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::CurlyClose) {
+            return Ok(parser.located(start, jsx::Empty {
+                position: None,
+                comments: None,
+            }).into());
+        }
+
+        let child = if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Ellipsis) {
+            let expression = eat_value!(parser.with(Flag::In).parse_assignment_expression()?);
+            eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+            parser.located(start, jsx::ExpressionSpread {
+                expression,
+                position: None,
+                comments: None,
+            }).into()
+        } else {
+            let expression = eat_value!(parser.with(Flag::In).parse_expression()?);
+            eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+            parser.located(start, jsx::Expression {
+                expression,
+                position: None,
+                comments: None,
+            }).into()
+        };
+        Ok(child)
+    }
+
+    fn parse_jsx_attribute(&mut self) -> OptResult<jsx::Attribute<'code>> {
+        let start = self.start();
+
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::CurlyOpen) {
+            let mut parser = self.without(Flag::JsxTag);
+            parser.expect_expression();
+
+            eat_value!(parser.punc(tokens::PunctuatorToken::Ellipsis));
+            let expression = eat_value!(parser.with(Flag::In).parse_assignment_expression()?);
+            eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+            return Ok(TokenResult::Some(parser.located(start, jsx::SpreadAttribute {
+                expression,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        let id = try_value!(self.parse_jsx_identifier());
+        let name = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Colon) {
+            let name = eat_value!(self.parse_jsx_identifier());
+
+            self.located(start, jsx::NamespacedName {
+                namespace: id,
+                name,
+                position: None,
+                comments: None,
+            }).into()
+        } else {
+            id.into()
+        };
+
+        let value = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Eq) {
+            Some(self.parse_jsx_attribute_value()?)
+        } else {
+            None
+        };
+
+        Ok(TokenResult::Some(self.located(start, jsx::PairAttribute {
+            name,
+            value,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    fn parse_jsx_attribute_value(&mut self) -> Result<jsx::AttributeValue<'code>> {
+        let start = self.start();
+
+        if let TokenResult::Some(string) = self.string() {
+            let value = self.text(string.value);
+
+            return Ok(self.located(start, jsx::StringAttribute {
+                raw: None,
+                value,
+                position: None,
+                comments: None,
+            }).into());
+        }
+
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::CurlyOpen) {
+            let mut parser = self.without(Flag::JsxTag);
+            parser.expect_expression();
+
+            let expression = eat_value!(parser.with(Flag::In).parse_assignment_expression()?);
+            eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+            return Ok(parser.located(start, jsx::ExpressionAttribute {
+                expression,
+                position: None,
+                comments: None,
+            }).into());
+        }
+
+        eat_value!(self.punc(tokens::PunctuatorToken::LAngle));
+        Ok(self.parse_jsx_element_after_angle(start)?.into())
+    }
+
+    fn parse_jsx_element_name(&mut self) -> TokenResult<jsx::ElementName<'code>> {
+        let start = self.start();
+        let id = try_token!(self.parse_jsx_identifier());
+
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Colon) {
+            let name = try_token!(self.parse_jsx_identifier());
+
+            return TokenResult::Some(self.located(start, jsx::NamespacedName {
+                namespace: id,
+                name,
+                position: None,
+                comments: None,
+            }).into());
+        }
+
+        let mut name = jsx::ElementName::from(id);
+        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Period) {
+            let object = match name {
+                jsx::ElementName::Identifier(id) => jsx::MemberObject::from(id),
+                jsx::ElementName::Member(member) => member.into(),
+                jsx::ElementName::Namespaced(_) => unreachable!("namespaced names are returned above"),
+            };
+            let property = try_token!(self.parse_jsx_identifier());
+
+            name = self.located(start, jsx::MemberExpression {
+                object: NodeBox::new(object),
+                property,
+                position: None,
+                comments: None,
+            }).into();
+        }
+        TokenResult::Some(name)
+    }
+
+    fn parse_jsx_identifier(&mut self) -> TokenResult<jsx::Identifier<'code>> {
+        let start = self.start();
+        let id = try_token!(self.identifier());
+        let value = self.text(id.name);
+
+        TokenResult::Some(self.located(start, jsx::Identifier {
+            raw: None,
+            value,
+            position: None,
+            comments: None,
+        }))
+    }
+}
+
+// The name as it is written, to match a closing tag to its opening tag.
+fn element_name(name: &jsx::ElementName) -> String {
+    fn member_name(member: &jsx::MemberExpression) -> String {
+        let object = match *member.object {
+            jsx::MemberObject::Identifier(ref id) => id.value.to_string(),
+            jsx::MemberObject::Member(ref member) => member_name(member),
+        };
+        format!("{}.{}", object, &*member.property.value)
+    }
+
+    match *name {
+        jsx::ElementName::Identifier(ref id) => id.value.to_string(),
+        jsx::ElementName::Member(ref member) => member_name(member),
+        jsx::ElementName::Namespaced(ref name) => format!("{}:{}", &*name.namespace.value, &*name.name.value),
+    }
+}
@@ -7,74 +7,31 @@ mod expressions;
 mod declarations;
 mod statements;
 mod classes;
+mod decorators;
+mod jsx;
 mod functions;
-
-use time;
-
+mod identifiers;
+mod cover;
+mod comments;
+mod options;
+mod tokenize;
+pub mod incremental;
+mod diagnostics;
+mod typescript;
+mod flow;
+
+use std::borrow::Cow;
 use std::ops::{Deref, DerefMut};
-use tokenizer::{self, IntoTokenizer, Tokenizer, Hint, tokens};
+use ast;
+use stack;
+use tokenizer::{self, Tokenizer, Hint, tokens};
 use self::utils::TokenResult;
 
-pub fn parse_root<'code, T: 'code, P>(t: T) -> P
-where
-    T: IntoTokenizer<'code>,
-    P: FromTokenizer
-{
-    FromTokenizer::from_tokenizer(t)
-}
-
-pub trait FromTokenizer {
-    fn from_tokenizer<'code, T: IntoTokenizer<'code> + 'code>(t: T) -> Self;
-}
-
-struct Test {}
-
-impl FromTokenizer for Test {
-    fn from_tokenizer<'code, T: IntoTokenizer<'code> + 'code>(t: T) -> Test {
-        let mut p = Parser {
-            tok: t.into_tokenizer(),
-            hint: Default::default(),
-            flags: Default::default(),
-            flags_stack: vec![],
-
-            tokens: Default::default(),
-            index: 0,
-            count: 0,
-        };
-
-        p.parse_module().unwrap();
-
-        Test {}
-    }
-}
-
-
-impl FromTokenizer for () {
-    fn from_tokenizer<'code, T: IntoTokenizer<'code> + 'code>(t: T) -> () {
-        let mut p = Parser {
-            tok: t.into_tokenizer(),
-            hint: Default::default(),
-            flags: Default::default(),
-            flags_stack: vec![],
-
-            tokens: Default::default(),
-            index: 0,
-            count: 0,
-        };
-
-        println!("starting");
-
-        let t_start = time::precise_time_ns();
-
-        p.parse_module().unwrap();
-
-        let total_parse = time::precise_time_ns() - t_start;
-
-        println!("Total parsing time: {}ms", total_parse as f64 / 1e6);
-
-        ()
-    }
-}
+pub use self::file::{parse, parse_module, parse_script, try_source_types};
+pub use self::flow::has_flow_pragma;
+pub use self::diagnostics::Diagnostic;
+pub use self::options::{EcmaVersion, ParserOptions, SourceType, SyntaxExtensions};
+pub use self::tokenize::{tokenize, Tokens};
 
 pub struct ParserProxy<'parser, 'code: 'parser, T: Tokenizer<'code> + 'code>(&'parser mut Parser<'code, T>);
 
@@ -127,6 +84,32 @@ where
 pub struct LookaheadResult<'code> {
     line: bool,
     token: tokens::Token<'code>,
+    range: tokenizer::TokenRange,
+
+    // The offset of the Flow comment that the token was read from, if any.
+    flow_comment: Option<usize>,
+
+    // Whether the token was read by `ident_lookahead`, which picks the hint
+    // for it from the identifier before it.
+    lookahead: bool,
+}
+
+
+// The state of the parser at a point that it can rewind to.
+struct Checkpoint<'code, T> {
+    tok: T,
+    hint: Hint,
+    flags: GrammarFlags,
+    tokens: [LookaheadResult<'code>; 2],
+    index: u8,
+    count: u8,
+    recorded: Option<usize>,
+    last_end: tokenizer::Position,
+    last_range: tokenizer::TokenRange,
+    last_flow_comment: Option<usize>,
+    comments: usize,
+    cover_init: bool,
+    no_arrow_return_type: bool,
 }
 
 
@@ -143,6 +126,19 @@ pub enum Flag {
 
     Template,
     // Curly,
+
+    // Reading the inside of a JSX tag, or the children of a JSX element.
+    // Setting either one clears the other.
+    JsxTag,
+    JsxText,
+
+    // Inside of a TypeScript "declare", where variables need no initializer.
+    Ambient,
+
+    // Inside the body of a loop, which "break" and "continue" can leave, or
+    // of a switch, which only "break" can leave. Function bodies clear both.
+    Loop,
+    Switch,
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -156,14 +152,36 @@ struct GrammarFlags {
     is_strict: bool,
 
     expect_template: bool,
+    in_jsx_tag: bool,
+    in_jsx_text: bool,
+    in_ambient: bool,
+    in_loop: bool,
+    in_switch: bool,
 }
 
+// A label around the statement being parsed, which "break" and "continue"
+// can name.
+#[derive(Debug)]
+struct Label<'code> {
+    name: ast::Str<'code>,
+
+    // The start of the statement that it labels, past any other labels.
+    body: usize,
+
+    // Whether that statement is a loop, which "continue" can also target.
+    is_loop: bool,
+}
+
+/// How deeply expressions and statements may nest before parsing fails.
+pub const MAX_NESTING_DEPTH: u32 = 1024;
+
 #[derive(Debug)]
 pub struct Parser<'code, T: 'code>
 where
     T: Tokenizer<'code>
 {
     tok: T,
+    options: ParserOptions,
     hint: Hint,
     flags: GrammarFlags,
     flags_stack: Vec<GrammarFlags>,
@@ -171,9 +189,47 @@ where
     index: u8,
     count: u8,
 
-    // Track first location of non-object-literal single-name assignment
+    // When set, every token read from the tokenizer, including whitespace,
+    // line terminators and comments, is appended here in source order.
+    recorded: Option<Vec<(tokens::Token<'code>, tokenizer::TokenRange, Hint)>>,
+
+    // The end of the last non-trivia token that was consumed, used as the end
+    // position of nodes as they are completed.
+    last_end: tokenizer::Position,
+
+    // The range of the last non-trivia token that was consumed, stored
+    // alongside keywords and punctuators in the AST.
+    last_range: tokenizer::TokenRange,
+
+    // The offset of the Flow comment that the last non-trivia token that was
+    // consumed was read from, if any.
+    last_flow_comment: Option<usize>,
+
+    // Every comment read so far, in source order, waiting to be attached to
+    // the AST once parsing has finished.
+    comments: Vec<ast::CommentNode<'code>>,
+
+    // Track if there is a non-object-literal single-name assignment that
+    // has not been converted into a pattern yet.
     // { foo = 4 } = {} -> allowed in patterns, not objects
-    // cover_pattern
+    cover_init: bool,
+
+    // Set while reparsing the consequent of "a ? (b) : c => d", so that the
+    // arrow function isn't given a return type that takes the ":".
+    no_arrow_return_type: bool,
+
+    // The labels around the statement being parsed, innermost last. Function
+    // bodies start a new list.
+    labels: Vec<Label<'code>>,
+
+    // How many expressions and statements are currently being parsed inside
+    // of one another, limited so deeply nested input can't overflow the stack.
+    depth: u32,
+
+    // Set once the nesting limit is reached. Parsing fails from then on, even
+    // in a different way after speculative parsing gives up, since otherwise
+    // each level of nesting could try again and take exponential time.
+    too_deep: bool,
 
 
     // Track if there was a rest element in the expression
@@ -183,17 +239,145 @@ where
 }
 
 impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
+    pub fn new(tok: T) -> Parser<'code, T> {
+        Parser::with_options(tok, &Default::default())
+    }
+
+    /// Create a parser for the syntax that the options allow. The source type
+    /// is not applied here, since the caller picks the goal to parse.
+    pub fn with_options(tok: T, options: &ParserOptions) -> Parser<'code, T> {
+        Parser {
+            tok,
+            options: *options,
+            hint: Hint::default()
+                .hashbang(options.allow_hashbang)
+                .flow_comments(options.extensions.flow)
+                .html_comments(options.annex_b),
+            flags: Default::default(),
+            flags_stack: vec![],
+
+            tokens: Default::default(),
+            index: 0,
+            count: 0,
+
+            recorded: None,
+
+            last_end: Default::default(),
+            last_range: Default::default(),
+            last_flow_comment: None,
+            comments: vec![],
+            cover_init: false,
+            no_arrow_return_type: false,
+            labels: vec![],
+            depth: 0,
+            too_deep: false,
+        }
+    }
+
+    // Remember the current position in the code, so that syntax that can only
+    // be told apart by trying to parse it can go back and try again.
+    fn checkpoint(&self) -> Checkpoint<'code, T> {
+        Checkpoint {
+            tok: self.tok.clone(),
+            hint: self.hint,
+            flags: self.flags,
+            tokens: self.tokens.clone(),
+            index: self.index,
+            count: self.count,
+            recorded: self.recorded.as_ref().map(Vec::len),
+            last_end: self.last_end,
+            last_range: self.last_range,
+            last_flow_comment: self.last_flow_comment,
+            comments: self.comments.len(),
+            cover_init: self.cover_init,
+            no_arrow_return_type: self.no_arrow_return_type,
+        }
+    }
+
+    fn rewind(&mut self, checkpoint: Checkpoint<'code, T>) {
+        self.tok = checkpoint.tok;
+        self.hint = checkpoint.hint;
+        self.flags = checkpoint.flags;
+        self.tokens = checkpoint.tokens;
+        self.index = checkpoint.index;
+        self.count = checkpoint.count;
+        if let (Some(recorded), Some(len)) = (self.recorded.as_mut(), checkpoint.recorded) {
+            recorded.truncate(len);
+        }
+        self.last_end = checkpoint.last_end;
+        self.last_range = checkpoint.last_range;
+        self.last_flow_comment = checkpoint.last_flow_comment;
+        self.comments.truncate(checkpoint.comments);
+        self.cover_init = checkpoint.cover_init;
+        self.no_arrow_return_type = checkpoint.no_arrow_return_type;
+    }
+
+    /// Try to parse something, going back to where it started if it isn't
+    /// there or fails to parse.
+    pub fn speculate<R, F>(&mut self, parse: F) -> TokenResult<R>
+    where
+        F: FnOnce(&mut Self) -> utils::OptResult<R>
+    {
+        let checkpoint = self.checkpoint();
+        match parse(self) {
+            Ok(TokenResult::Some(value)) => TokenResult::Some(value),
+            Ok(TokenResult::None) | Err(_) => {
+                self.rewind(checkpoint);
+                TokenResult::None
+            }
+        }
+    }
+
+    /// Run a parse function that can recurse back into itself, failing once
+    /// the input is nested more than `MAX_NESTING_DEPTH` levels deep.
+    pub fn nested<R, F>(&mut self, parse: F) -> utils::Result<R>
+    where
+        F: FnOnce(&mut Self) -> utils::Result<R>
+    {
+        if self.too_deep || self.depth >= MAX_NESTING_DEPTH {
+            self.too_deep = true;
+            bail!("Too deeply nested");
+        }
+
+        self.depth += 1;
+        let result = stack::grow(|| parse(self));
+        self.depth -= 1;
+        result
+    }
+
+    /// Start keeping a copy of every token the parser reads, trivia included.
+    pub fn record_tokens(&mut self) {
+        self.recorded = Some(vec![]);
+    }
+
+    /// Take the tokens recorded since `record_tokens` was called, with the
+    /// hint that each one was read with. Tokens that were read while trying
+    /// a parse that was given up on are left out.
+    pub fn take_recorded_tokens(&mut self) -> Vec<(tokens::Token<'code>, tokenizer::TokenRange, Hint)> {
+        self.recorded.take().unwrap_or_default()
+    }
+
     pub fn expect_expression(&mut self) {
+        // A token that was already read as lookahead was read with its own
+        // hint, and this one must not carry over to the token after it, like
+        // the "/" in "typeof a / b".
+        if self.count > 0 && self.tokens[self.index as usize].lookahead {
+            return;
+        }
         self.hint = self.hint.expression(true);
     }
 
     pub fn semicolon(&mut self) -> TokenResult<()> {
-        self.semicolon_inner(false)
+        self.semicolon_inner(false).map(|_| ())
+    }
+    /// Like `semicolon`, but returns the position of the ";" if there was one.
+    pub fn semicolon_token<D: From<ast::PositionRange> + Default>(&mut self) -> TokenResult<D> {
+        self.semicolon_inner(false).map(|range| range.map(From::from).unwrap_or_default())
     }
-    pub fn semicolon_dowhile(&mut self) -> TokenResult<()> {
-        self.semicolon_inner(true)
+    pub fn semicolon_dowhile<D: From<ast::PositionRange> + Default>(&mut self) -> TokenResult<D> {
+        self.semicolon_inner(true).map(|range| range.map(From::from).unwrap_or_default())
     }
-    fn semicolon_inner(&mut self, was_do_while: bool) -> TokenResult<()> {
+    fn semicolon_inner(&mut self, was_do_while: bool) -> TokenResult<Option<ast::PositionRange>> {
         let exists = {
             let (line, token) = self.token_and_line();
 
@@ -214,10 +398,11 @@ impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
         // println!("Performing ASI");
         if exists {
             self.pop();
+            TokenResult::Some(Some(self.token_range()))
         } else {
             self.expect_expression();
+            TokenResult::Some(None)
         }
-        TokenResult::Some(())
     }
 
     pub fn with<'parser>(&'parser mut self, flag: Flag) -> ParserProxy<'parser, 'code, T> {
@@ -243,18 +428,47 @@ impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
             Flag::Await => { self.flags.allow_await = val; }
             Flag::Return => { self.flags.allow_return = val; }
             Flag::Default => { self.flags.allow_default = val; }
-            Flag::Module => { self.flags.is_module = val; }
+            Flag::Module => {
+                self.flags.is_module = val;
+                self.sync_html_comments_hint();
+            }
             Flag::Strict => { self.flags.is_strict = val; }
             Flag::Template => {
                 self.flags.expect_template = val;
                 self.hint = self.hint.template(val);
             }
+            Flag::JsxTag => {
+                self.flags.in_jsx_tag = val;
+                self.flags.in_jsx_text &= !val;
+                self.sync_jsx_hint();
+            }
+            Flag::JsxText => {
+                self.flags.in_jsx_text = val;
+                self.flags.in_jsx_tag &= !val;
+                self.sync_jsx_hint();
+            }
+            Flag::Ambient => { self.flags.in_ambient = val; }
+            Flag::Loop => { self.flags.in_loop = val; }
+            Flag::Switch => { self.flags.in_switch = val; }
             Flag::Noop => { /* useful if you want to consistently pass a ParserProxy */}
         }
     }
     fn pop_flags(&mut self) {
         self.flags = self.flags_stack.pop().unwrap();
         self.hint = self.hint.template(self.flags.expect_template);
+        self.sync_jsx_hint();
+        self.sync_html_comments_hint();
+    }
+    fn sync_jsx_hint(&mut self) {
+        self.hint = self.hint.jsx_tag(self.flags.in_jsx_tag).jsx_text(self.flags.in_jsx_text);
+    }
+    fn sync_html_comments_hint(&mut self) {
+        self.hint = self.hint.html_comments(self.options.annex_b && !self.flags.is_module);
+    }
+
+    /// Whether the syntax of Annex B is allowed, which is only in sloppy mode.
+    pub fn annex_b(&self) -> bool {
+        self.options.annex_b && !self.flags.is_strict
     }
 
     pub fn token(&mut self) -> &tokens::Token {
@@ -266,6 +480,9 @@ impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
             &mut self.tokens[self.index as usize].token,
             tokens::EOFToken {}.into(),
         );
+        self.last_range = self.tokens[self.index as usize].range;
+        self.last_end = self.last_range.end;
+        self.last_flow_comment = self.tokens[self.index as usize].flow_comment;
 
         self.index = (self.index + 1) % 2;
         self.count -= 1;
@@ -277,21 +494,83 @@ impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
 
     fn token_and_line(&mut self) -> (bool, &tokens::Token) {
         if self.count == 0 {
-            read_token(&mut self.tok, &mut self.hint, &mut self.tokens[0]);
+            read_token(&mut self.tok, &mut self.hint, &mut self.tokens[0], &mut self.recorded, &mut self.comments);
+            self.tokens[0].lookahead = false;
             self.index = 0;
             self.count += 1;
 
             // println!("Populated {:?}", self.tokens[0]);
         }
 
-        let LookaheadResult { line, ref token } = self.tokens[self.index as usize];
+        // Legacy octals are read before it is known whether they are in strict
+        // code, like after a "use strict" directive, so they are only rejected
+        // once the parser gets to them.
+        let legacy_octal = match self.tokens[self.index as usize].token {
+            tokens::Token::NumericLiteral(ref n) => n.legacy_octal,
+            tokens::Token::StringLiteral(ref s) => s.legacy_octal,
+            _ => false,
+        };
+        if legacy_octal && !self.annex_b() {
+            self.tokens[self.index as usize].token = tokens::InvalidToken::LegacyOctal.into();
+        }
+
+        let LookaheadResult { line, ref token, .. } = self.tokens[self.index as usize];
         (line, token)
     }
 
+    /// The start position of the next token.
+    pub fn start(&mut self) -> tokenizer::Position {
+        self.token_and_line();
+
+        self.tokens[self.index as usize].range.start
+    }
+
+    /// Set the position of a node that started at 'start' and ends with the
+    /// last token that was consumed.
+    pub fn located<N: ast::Node<'code>>(&self, start: tokenizer::Position, mut node: N) -> N {
+        let mut position = node_position(start, self.last_end);
+
+        // A node ending in a Flow comment that opened before it started is
+        // entirely inside of that comment.
+        position.in_flow_comment = matches!(self.last_flow_comment, Some(open) if open <= start.offset);
+
+        node.set_position(Some(position));
+        node
+    }
+
+    /// The text of a token as an AST string, borrowing from the source if
+    /// the token's text was not cooked.
+    pub fn text(&self, s: Cow<'code, str>) -> ast::Str<'code> {
+        s.into()
+    }
+
+    /// The position of the last token that was consumed, for the keyword and
+    /// punctuator data stored in nodes.
+    pub fn token_data<D: From<ast::PositionRange>>(&self) -> D {
+        self.token_range().into()
+    }
+
+    /// The line/column range of the last token that was consumed.
+    pub fn token_range(&self) -> ast::PositionRange {
+        ast::PositionRange {
+            start: (self.last_range.start.line, self.last_range.start.column),
+            end: (self.last_range.end.line, self.last_range.end.column),
+        }
+    }
+
     pub fn no_line_terminator(&mut self) -> bool {
         !self.token_and_line().0
     }
 
+    /// Fail if the options are for an ECMAScript version from before the
+    /// given feature was added.
+    pub fn require_version(&self, version: EcmaVersion, feature: &str) -> utils::Result<()> {
+        if self.options.ecma_version < version {
+            bail!("{} is only supported from ES{}", feature, version.year());
+        }
+        Ok(())
+    }
+
     pub fn is_binding_identifier(&self, name: &str) -> bool {
         is_binding_identifier(&self.flags, name)
     }
@@ -311,8 +590,12 @@ impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
 
             let mut hint = self.hint.expression(expect_expression);
 
-            read_token(&mut self.tok, &mut hint, &mut self.tokens[look_index]);
+            read_token(&mut self.tok, &mut hint, &mut self.tokens[look_index], &mut self.recorded, &mut self.comments);
+            self.tokens[look_index].lookahead = true;
             self.count += 1;
+
+            // The hint for the token after the identifier has been used up.
+            self.hint = self.hint.expression(false);
         }
 
         Some(&self.tokens[look_index])
@@ -371,6 +654,18 @@ impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
         }
     }
 
+    pub fn jsx_text(&mut self) -> TokenResult<tokens::JSXTextToken<'code>> {
+        if matches!(*self.token(), tokens::Token::JSXText(_)) {
+            if let tokens::Token::JSXText(t) = self.pop() {
+                TokenResult::Some(t)
+            } else {
+                unreachable!("already matched JSX text");
+            }
+        } else {
+            TokenResult::None
+        }
+    }
+
     pub fn regex(&mut self) -> TokenResult<tokens::RegularExpressionLiteralToken<'code>> {
         let same = match *self.token() {
             tokens::Token::RegularExpressionLiteral(_) => { true }
@@ -504,29 +799,47 @@ impl<'code, T: Tokenizer<'code>> Parser<'code, T> {
     }
 }
 
-fn read_token<'code, T>(tok: &mut T, hint: &mut Hint, out: &mut LookaheadResult<'code>)
+fn read_token<'code, T>(
+    tok: &mut T,
+    hint: &mut Hint,
+    out: &mut LookaheadResult<'code>,
+    recorded: &mut Option<Vec<(tokens::Token<'code>, tokenizer::TokenRange, Hint)>>,
+    comments: &mut Vec<ast::CommentNode<'code>>,
+)
 where
     T: Tokenizer<'code> + 'code
 {
     out.line = false;
 
-    let mut pos = tokenizer::TokenRange::default();
     loop {
         // TODO: Explore allocating a token and passing it into next_token
 
-        tok.next_token(hint, (&mut out.token, &mut pos));
+        tok.next_token(hint, (&mut out.token, &mut out.range));
+        let pos = out.range;
+
+        if let Some(ref mut recorded) = *recorded {
+            recorded.push((out.token.clone(), pos, *hint));
+        }
+
         match out.token {
             tokens::Token::Whitespace(_) => {}
             tokens::Token::LineTerminator(_) => {
                 out.line = true;
             }
-            tokens::Token::Comment(_) => {
+            tokens::Token::Comment(ref comment) => {
                 if pos.start.line != pos.end.line {
                     out.line = true;
                 }
+
+                comments.push(ast::CommentNode {
+                    format: comment.format.into(),
+                    value: comment.value.clone().into(),
+                    position: Some(ast::NodeBox::new(node_position(pos.start, pos.end))),
+                });
             }
             _ => {
                 *hint = hint.expression(false);
+                out.flow_comment = tok.flow_comment();
 
                 // println!("{:?}", (line, t.clone()));
                 break;
@@ -535,6 +848,30 @@ where
     }
 }
 
+fn node_position(start: tokenizer::Position, end: tokenizer::Position) -> ast::NodePosition {
+    ast::NodePosition {
+        start: start.offset,
+        end: end.offset,
+        range: ast::PositionRange {
+            start: (start.line, start.column),
+            end: (end.line, end.column),
+        },
+        in_flow_comment: false,
+    }
+}
+
+// The start of an already-parsed node, for nodes that wrap it.
+fn node_start(position: Option<&ast::NodePosition>) -> tokenizer::Position {
+    match position {
+        Some(pos) => tokenizer::Position {
+            offset: pos.start,
+            line: pos.range.start.0,
+            column: pos.range.start.1,
+        },
+        None => Default::default(),
+    }
+}
+
 fn is_binding_identifier(flags: &GrammarFlags, s: &str) -> bool {
     // TODO: In strict mode specifically, 'arguments' and 'eval' aren't allowed as binding names,
     // just labels and references.
@@ -569,10 +906,88 @@ fn is_binding_identifier(flags: &GrammarFlags, s: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::thread;
     use parser;
+    use tokenizer::IntoTokenizer;
 
     #[test]
     fn it_parses() {
-        parser::Test::from_tokenizer("this;");
+        parser::parse("this;", &ParserOptions::module()).unwrap();
+    }
+
+    #[test]
+    fn it_borrows_source_text() {
+        use ast::visit::{Visit, Walk};
+        use ast::{general, literal, Str, TrailingComments};
+
+        struct Strings<'code>(Vec<Str<'code>>);
+        impl<'code> Visit<'code> for Strings<'code> {
+            fn visit_binding_identifier(&mut self, node: &general::BindingIdentifier<'code>) {
+                self.0.push(node.value.clone());
+            }
+            fn visit_string(&mut self, node: &literal::String<'code>) {
+                self.0.push(node.value.clone());
+            }
+        }
+
+        let script = Parser::new("var foo = 'bar'; // baz".into_tokenizer()).parse_script().unwrap();
+        let mut strings = Strings(vec![]);
+        script.walk(&mut strings);
+
+        assert_eq!(strings.0, vec!["foo", "bar"]);
+        assert!(strings.0.iter().all(Str::is_borrowed));
+        assert!(script.body[0].trailing_comments().all(|c| c.value.is_borrowed()));
+    }
+
+    fn nest(open: &str, inner: &str, close: &str, depth: usize) -> String {
+        format!("x = {}{}{}", open.repeat(depth), inner, close.repeat(depth))
+    }
+
+    #[test]
+    fn it_limits_nesting_depth() {
+        let options = ParserOptions::script();
+        for code in &[
+            nest("(", "a", ")", 100_000),
+            nest("[", "", "]", 100_000),
+            nest("{a:", "", "}", 100_000),
+            nest("function f() {", "", "}", 100_000),
+            nest("!", "a", "", 100_000),
+            nest("new ", "a", "", 100_000),
+            "{".repeat(100_000),
+        ] {
+            let error = parser::parse(code, &options).unwrap_err();
+            assert_eq!(error.message, "Too deeply nested");
+        }
+    }
+
+    #[test]
+    fn it_parses_up_to_the_nesting_limit_on_default_threads() {
+        thread::spawn(|| {
+            let options = ParserOptions::script();
+            for &(open, inner, close) in &[
+                ("(", "a", ")"),
+                ("[", "", "]"),
+                ("{a:", "1", "}"),
+                ("(function(){", "", "})"),
+                ("a ? (", "b", ") : c"),
+                ("`${", "a", "}`"),
+            ] {
+                let parses = |depth| parser::parse(&nest(open, inner, close, depth), &options).is_ok();
+
+                // The deepest nesting that still parses.
+                let (mut low, mut high) = (1, MAX_NESTING_DEPTH as usize);
+                while low < high {
+                    let depth = (low + high + 1) / 2;
+                    match parses(depth) {
+                        true => low = depth,
+                        false => high = depth - 1,
+                    }
+                }
+                assert!(low >= MAX_NESTING_DEPTH as usize / 8, "{} parses {} levels deep", open, low);
+
+                let error = parser::parse(&nest(open, inner, close, low + 1), &options).unwrap_err();
+                assert_eq!(error.message, "Too deeply nested");
+            }
+        }).join().unwrap();
     }
 }
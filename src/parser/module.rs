@@ -1,132 +1,552 @@
+use ast::{KeywordData, KeywordWrappedData, NodeBox};
+use ast::alias;
+use ast::general::{BindingIdentifier, ReferenceIdentifier};
+use ast::modules::{self, ModuleIdentifier};
+use ast::flow;
+use ast::typescript;
 use tokenizer::{Tokenizer, tokens};
-use parser::{Parser, Flag};
-use parser::utils::{OptResult, TokenResult};
+use parser::{Parser, Flag, LookaheadResult};
+use parser::utils::{OptResult, Result, TokenResult};
 
 impl<'code, T> Parser<'code, T>
 where
     T: Tokenizer<'code>
 {
-    pub fn parse_import_declaration(&mut self) -> OptResult<()>  {
+    pub fn parse_import_declaration(&mut self) -> OptResult<alias::ModuleStatementItem<'code>>  {
+        if self.options.extensions.typescript {
+            if let TokenResult::Some(decl) = self.parse_import_equals_declaration()? {
+                return Ok(TokenResult::Some(typescript::Declaration::from(decl).into()));
+            }
+        }
+
+        let start = self.start();
         try_value!(self.keyword("import"));
+        let token_import = self.token_data();
 
-        if let TokenResult::Some(_) = self.string() {
+        if self.has_types() && self.is_import_kind() {
+            let decl = eat_value!(self.parse_import_type_declaration(start, token_import)?);
+            return Ok(TokenResult::Some(decl.into()));
+        }
 
-        } else {
-            let try_names = if let TokenResult::Some(_) = self.binding_identifier() {
-                opt_value!(self.punc(tokens::PunctuatorToken::Comma)).is_some()
-            } else {
-                true
-            };
+        if let TokenResult::Some(source) = self.parse_string_literal() {
+            let token_semi = eat_value!(self.semicolon_token());
 
-            if try_names {
-                if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Star) {
-                    eat_value!(self.keyword("as"));
-                    eat_value!(self.binding_identifier());
-                } else {
-                    eat_value!(self.punc(tokens::PunctuatorToken::CurlyOpen));
+            return Ok(TokenResult::Some(self.located(start, modules::ImportSourceDeclaration {
+                token_import,
+                source,
+                token_semi,
+                position: None,
+                comments: None,
+            }).into()));
+        }
 
-                    while let TokenResult::Some(_) = self.parse_import_specifier()? {
-                        if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
-                            break;
-                        }
-                    }
+        let default = opt_value!(self.parse_binding_identifier());
+        let token_comma = match default {
+            Some(_) => opt_value!(self.punc(tokens::PunctuatorToken::Comma)).map(|_| self.token_data()),
+            None => Some(Default::default()),
+        };
+
+        enum Names<'code> {
+            None,
+            Namespace(KeywordData, KeywordWrappedData, BindingIdentifier<'code>),
+            Specifiers(KeywordData, SpecifierList<modules::ImportSpecifier<'code>>, KeywordData),
+        }
 
-                    eat_value!(self.punc(tokens::PunctuatorToken::CurlyClose));
+        let names = if token_comma.is_none() {
+            Names::None
+        } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Star) {
+            let token_star = self.token_data();
+            eat_value!(self.keyword("as"));
+            let token_as = self.token_data();
+
+            Names::Namespace(token_star, token_as, eat_value!(self.parse_binding_identifier()))
+        } else {
+            eat_value!(self.punc(tokens::PunctuatorToken::CurlyOpen));
+            let token_curly_l = self.token_data();
+
+            let mut specifiers = SpecifierList::default();
+            while let TokenResult::Some(spec) = self.parse_import_specifier()? {
+                if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+                    specifiers.items.push((spec, self.token_data()));
+                } else {
+                    specifiers.last = Some(spec);
+                    break;
                 }
             }
 
-            eat_value!(self.keyword("from"));
-            eat_value!(self.string());
-        }
+            eat_value!(self.punc(tokens::PunctuatorToken::CurlyClose));
+            let token_curly_r = self.token_data();
+
+            Names::Specifiers(token_curly_l, specifiers, token_curly_r)
+        };
+        let token_comma = token_comma.unwrap_or_default();
 
-        eat_value!(self.semicolon());
+        eat_value!(self.keyword("from"));
+        let token_from = self.token_data();
+        let source = eat_value!(self.parse_string_literal());
+        let token_semi = eat_value!(self.semicolon_token());
+
+        let decl: alias::ModuleStatementItem = match (default, names) {
+            (Some(default), Names::None) => self.located(start, modules::ImportNamedDeclaration {
+                token_import,
+                default,
+                token_from,
+                source,
+                token_semi,
+                position: None,
+                comments: None,
+            }).into(),
+            (Some(default), Names::Namespace(token_star, token_as, namespace)) => self.located(start, modules::ImportNamedAndNamespaceDeclaration {
+                token_import,
+                default,
+                token_comma,
+                token_star,
+                token_as,
+                namespace,
+                token_from,
+                source,
+                token_semi,
+                position: None,
+                comments: None,
+            }).into(),
+            (None, Names::Namespace(token_star, token_as, namespace)) => self.located(start, modules::ImportNamespaceDeclaration {
+                token_import,
+                token_star,
+                token_as,
+                namespace,
+                token_from,
+                source,
+                token_semi,
+                position: None,
+                comments: None,
+            }).into(),
+            (Some(default), Names::Specifiers(token_curly_l, specifiers, token_curly_r)) => {
+                self.located(start, modules::ImportNamedAndSpecifiersDeclaration {
+                    token_import,
+                    default,
+                    token_comma,
+                    token_curly_l,
+                    specifiers: specifiers.items,
+                    last_specifier: specifiers.last,
+                    token_curly_r,
+                    token_from,
+                    source,
+                    token_semi,
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            (None, Names::Specifiers(token_curly_l, specifiers, token_curly_r)) => {
+                self.located(start, modules::ImportSpecifiersDeclaration {
+                    token_import,
+                    token_curly_l,
+                    specifiers: specifiers.items,
+                    last_specifier: specifiers.last,
+                    token_curly_r,
+                    token_from,
+                    source,
+                    token_semi,
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            (None, Names::None) => unreachable!("names are always parsed without a default"),
+        };
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(decl))
     }
 
-    fn parse_import_specifier(&mut self) -> OptResult<()> {
-        try_value!(self.identifier());
+    pub fn parse_import_specifier(&mut self) -> OptResult<modules::ImportSpecifier<'code>> {
+        let start = self.start();
 
-        if let TokenResult::Some(_) = self.keyword("as") {
-            // TODO: Validate BindingIdentifier
-            eat_value!(self.identifier());
-        } else {
-            // TODO: Validate first is BindingIdentifier
+        // "type A", or Flow's "typeof A", but not an import named "type",
+        // like "type as A".
+        let flow = self.options.extensions.flow;
+        let kind = match *self.token() {
+            tokens::Token::IdentifierName(ref id) if id.name == "type" => Some(flow::ImportKind::Type),
+            tokens::Token::IdentifierName(ref id) if flow && id.name == "typeof" => Some(flow::ImportKind::Typeof),
+            _ => None,
+        };
+        let kind = kind.filter(|_| self.has_types() && matches!(
+            self.ident_lookahead(),
+            Some(&LookaheadResult { token: tokens::Token::IdentifierName(ref id), .. }) if id.name != "as"
+        ));
+        if let Some(kind) = kind {
+            self.pop();
+            let token_kind = self.token_data();
+            let specifier = eat_value!(self.parse_import_specifier()?);
+
+            return Ok(TokenResult::Some(self.located(start, flow::TypeImportSpecifier {
+                kind,
+                token_kind,
+                specifier: NodeBox::new(specifier),
+                position: None,
+                comments: None,
+            }).into()));
         }
 
-        Ok(TokenResult::Some(()))
+        let imported = try_value!(self.parse_module_identifier());
+
+        let spec = if let TokenResult::Some(_) = self.keyword("as") {
+            let token_as = self.token_data();
+            let local = eat_value!(self.parse_binding_identifier());
+
+            self.located(start, modules::AliasedImportSpecifier {
+                imported,
+                token_as,
+                local,
+                position: None,
+                comments: None,
+            }).into()
+        } else {
+            if !self.is_binding_identifier(&imported.value) {
+                bail!("Unexpected reserved word in import specifier");
+            }
+
+            self.located(start, modules::NormalImportSpecifier {
+                local: binding_identifier(imported),
+                position: None,
+                comments: None,
+            }).into()
+        };
+
+        Ok(TokenResult::Some(spec))
     }
 
-    pub fn parse_export_declaration(&mut self) -> OptResult<()>  {
+    pub fn parse_export_declaration(&mut self) -> OptResult<alias::ModuleStatementItem<'code>>  {
+        let start = self.start();
         try_value!(self.keyword("export"));
+        let token_export = self.token_data();
 
         if let TokenResult::Some(_) = self.keyword("default") {
-            let mut parser = self.with(Flag::Default);
+            let token_default = self.token_data();
 
-            if let TokenResult::Some(_) = parser.parse_function_declaration()? {
+            return self.with(Flag::Default).parse_export_default(start, token_export, token_default).map(TokenResult::Some);
+        }
 
-            } else if let TokenResult::Some(_) = parser.parse_class_declaration()? {
+        if self.options.extensions.typescript {
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Eq) {
+                let token_eq = self.token_data();
+                return self.parse_export_assignment(start, token_export, token_eq).map(TokenResult::Some);
+            }
+        }
+        if self.has_types() && self.is_word_before("type", &[tokens::PunctuatorToken::CurlyOpen]) {
+            return self.parse_export_type_specifiers(start, token_export).map(TokenResult::Some);
+        }
 
-            } else {
-                eat_value!(parser.with(Flag::In).parse_expression()?);
-                eat_value!(parser.semicolon());
+        let declaration = if self.has_types() {
+            self.parse_typescript_declaration()?
+        } else {
+            TokenResult::None
+        };
+
+        let decl: alias::ModuleStatementItem = if let TokenResult::Some(decl) = declaration {
+            match decl {
+                alias::StatementItem::TypeScript(exported) => self.located(start, typescript::ExportDeclaration {
+                    token_export,
+                    exported: NodeBox::into_inner(exported),
+                    position: None,
+                    comments: None,
+                }).into(),
+                alias::StatementItem::Class(exported) => self.located(start, modules::ExportClassDeclaration {
+                    token_export,
+                    exported: NodeBox::into_inner(exported),
+                    position: None,
+                    comments: None,
+                }).into(),
+                alias::StatementItem::Function(exported) => self.located(start, modules::ExportFunctionDeclaration {
+                    token_export,
+                    exported: NodeBox::into_inner(exported),
+                    position: None,
+                    comments: None,
+                }).into(),
+                _ => unreachable!(),
             }
+        } else if let TokenResult::Some(exported) = self.parse_variable_statement()? {
+            self.located(start, modules::ExportVarStatement {
+                token_export,
+                exported,
+                position: None,
+                comments: None,
+            }).into()
+        } else if let TokenResult::Some(exported) = self.parse_let_declaration()? {
+            self.located(start, modules::ExportLetDeclaration {
+                token_export,
+                exported,
+                position: None,
+                comments: None,
+            }).into()
+        } else if let TokenResult::Some(exported) = self.parse_const_declaration()? {
+            self.located(start, modules::ExportConstDeclaration {
+                token_export,
+                exported,
+                position: None,
+                comments: None,
+            }).into()
+        } else if let TokenResult::Some(exported) = self.parse_function_declaration()? {
+            self.located(start, modules::ExportFunctionDeclaration {
+                token_export,
+                exported,
+                position: None,
+                comments: None,
+            }).into()
+        } else if let TokenResult::Some(exported) = self.parse_class_declaration()? {
+            self.located(start, modules::ExportClassDeclaration {
+                token_export,
+                exported,
+                position: None,
+                comments: None,
+            }).into()
+        } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Star) {
+            let token_star = self.token_data();
+            eat_value!(self.keyword("from"));
+            let token_from = self.token_data();
+            let source = eat_value!(self.parse_string_literal());
+            let token_semi = eat_value!(self.semicolon_token());
+
+            self.located(start, modules::ExportAllSpecifiers {
+                token_export,
+                token_star,
+                token_from,
+                source,
+                token_semi,
+                position: None,
+                comments: None,
+            }).into()
         } else {
-            if let TokenResult::Some(_) = self.parse_variable_statement()? {
+            eat_value!(self.punc(tokens::PunctuatorToken::CurlyOpen));
+            let token_curly_l = self.token_data();
+
+            let mut specifiers = SpecifierList::default();
+            while let TokenResult::Some(spec) = self.parse_export_specifier()? {
+                if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+                    specifiers.items.push((spec, self.token_data()));
+                } else {
+                    specifiers.last = Some(spec);
+                    break;
+                }
+            }
 
-            } else if let TokenResult::Some(_) = self.parse_let_declaration()? {
+            eat_value!(self.punc(tokens::PunctuatorToken::CurlyClose));
+            let token_curly_r = self.token_data();
 
-            } else if let TokenResult::Some(_) = self.parse_const_declaration()? {
+            if let TokenResult::Some(_) = self.keyword("from") {
+                let token_from = self.token_data();
+                let source = eat_value!(self.parse_string_literal());
+                let token_semi = eat_value!(self.semicolon_token());
 
-            } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Star) {
-                eat_value!(self.keyword("from"));
-                eat_value!(self.string());
+                self.located(start, modules::ExportSourceSpecifiers {
+                    token_export,
+                    token_curly_l,
+                    specifiers: specifiers.items,
+                    last_specifier: specifiers.last,
+                    token_curly_r,
+                    token_from,
+                    source,
+                    token_semi,
+                    position: None,
+                    comments: None,
+                }).into()
             } else {
-                eat_value!(self.punc(tokens::PunctuatorToken::CurlyOpen));
+                let token_semi = eat_value!(self.semicolon_token());
 
-                while let TokenResult::Some(_) = self.parse_export_specifier()? {
-                    if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
-                        break;
-                    }
+                let mut locals = SpecifierList::default();
+                for (spec, token_comma) in specifiers.items {
+                    locals.items.push((self.local_export_specifier(spec)?, token_comma));
                 }
+                if let Some(spec) = specifiers.last {
+                    locals.last = Some(self.local_export_specifier(spec)?);
+                }
+
+                self.located(start, modules::ExportLocalBindings {
+                    token_export,
+                    token_curly_l,
+                    specifiers: locals.items,
+                    last_specifier: locals.last,
+                    token_curly_r,
+                    token_semi,
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+        };
 
-                eat_value!(self.punc(tokens::PunctuatorToken::CurlyClose));
+        Ok(TokenResult::Some(decl))
+    }
 
-                if let TokenResult::Some(_) = self.keyword("from") {
-                    eat_value!(self.string());
-                } else {
-                    // TODO: Validate BindingIdentifier for local specifiers
-                }
+    fn parse_export_default(&mut self, start: ::tokenizer::Position, token_export: KeywordData, token_default: KeywordData) -> Result<alias::ModuleStatementItem<'code>> {
+        if let TokenResult::Some(decl) = self.parse_export_default_function_declaration(start)? {
+            return Ok(decl.into());
+        }
+        if let TokenResult::Some(mut decl) = self.parse_export_default_class_declaration(start)? {
+            decl.token_export = token_export;
+            decl.token_default = token_default;
+            return Ok(decl.into());
+        }
+
+        self.expect_expression();
+        let expression = eat_value!(self.with(Flag::In).parse_assignment_expression()?);
+        let token_semi = eat_value!(self.semicolon_token());
+        self.check_cover_init()?;
+
+        Ok(self.located(start, modules::ExportDefaultExpression {
+            token_export,
+            token_default,
+            expression,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into())
+    }
+
+    // export = a;
+    fn parse_export_assignment(&mut self, start: ::tokenizer::Position, token_export: KeywordData, token_eq: KeywordData) -> Result<alias::ModuleStatementItem<'code>> {
+        self.expect_expression();
+        let expression = eat_value!(self.with(Flag::In).parse_assignment_expression()?);
+        let token_semi = eat_value!(self.semicolon_token());
+        self.check_cover_init()?;
+
+        Ok(self.located(start, typescript::ExportAssignment {
+            token_export,
+            token_eq,
+            expression,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into())
+    }
+
+    // export type { A, B as C } from "a";
+    fn parse_export_type_specifiers(&mut self, start: ::tokenizer::Position, token_export: KeywordData) -> Result<alias::ModuleStatementItem<'code>> {
+        eat_value!(self.keyword("type"));
+        let token_type = self.token_data();
+        eat_value!(self.punc(tokens::PunctuatorToken::CurlyOpen));
+
+        let mut specifiers = SpecifierList::default();
+        while let TokenResult::Some(spec) = self.parse_export_specifier()? {
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+                specifiers.items.push((spec, self.token_data()));
+            } else {
+                specifiers.last = Some(spec);
+                break;
             }
+        }
+        eat_value!(self.punc(tokens::PunctuatorToken::CurlyClose));
+
+        let source = if let TokenResult::Some(_) = self.keyword("from") {
+            let token_from = self.token_data();
+            Some((token_from, eat_value!(self.parse_string_literal())))
+        } else {
+            None
+        };
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(self.located(start, typescript::ExportTypeSpecifiers {
+            token_export,
+            token_type,
+            specifiers: specifiers.items,
+            last_specifier: specifiers.last,
+            source,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into())
+    }
+
+    fn parse_export_specifier(&mut self) -> OptResult<modules::SourceExportSpecifier<'code>> {
+        let start = self.start();
+        let imported = try_value!(self.parse_module_identifier());
+
+        let spec = if let TokenResult::Some(_) = self.keyword("as") {
+            let token_as = self.token_data();
+            let exported = eat_value!(self.parse_module_identifier());
+
+            self.located(start, modules::AliasedSourceExportSpecifier {
+                imported,
+                token_as,
+                exported,
+                position: None,
+                comments: None,
+            }).into()
+        } else {
+            self.located(start, modules::NormalSourceExportSpecifier {
+                imported,
+                position: None,
+                comments: None,
+            }).into()
+        };
 
-            eat_value!(self.semicolon());
+        Ok(TokenResult::Some(spec))
+    }
+
+        // Without a "from", the specifiers refer to local bindings.
+    fn local_export_specifier(&self, spec: modules::SourceExportSpecifier<'code>) -> Result<modules::LocalExportSpecifier<'code>> {
+        let local_name = match spec {
+            modules::SourceExportSpecifier::Normal(ref spec) => &spec.imported.value,
+            modules::SourceExportSpecifier::Aliased(ref spec) => &spec.imported.value,
+        };
+        if !self.is_binding_identifier(local_name) {
+            bail!("Unexpected reserved word in export specifier");
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(match spec {
+            modules::SourceExportSpecifier::Normal(spec) => modules::NormalLocalExportSpecifier {
+                local: reference_identifier(spec.imported),
+                position: spec.position,
+                comments: None,
+            }.into(),
+            modules::SourceExportSpecifier::Aliased(spec) => modules::AliasedLocalExportSpecifier {
+                local: reference_identifier(spec.imported),
+                token_as: spec.token_as,
+                exported: spec.exported,
+                position: spec.position,
+                comments: None,
+            }.into(),
+        })
     }
+}
 
-    fn parse_export_specifier(&mut self) -> OptResult<()> {
-        try_value!(self.identifier());
+// A list of specifiers that may have a trailing comma, so every item but the
+// last is followed by a comma.
+struct SpecifierList<T> {
+    items: Vec<(T, KeywordData)>,
+    last: Option<T>,
+}
 
-        if let TokenResult::Some(_) = self.keyword("as") {
-            eat_value!(self.identifier());
+impl<T> Default for SpecifierList<T> {
+    fn default() -> SpecifierList<T> {
+        SpecifierList {
+            items: vec![],
+            last: None,
         }
+    }
+}
+
+fn binding_identifier(id: ModuleIdentifier) -> BindingIdentifier {
+    BindingIdentifier {
+        value: id.value,
+        raw: id.raw,
+        position: id.position,
+        comments: None,
+    }
+}
 
-        Ok(TokenResult::Some(()))
+fn reference_identifier(id: ModuleIdentifier) -> ReferenceIdentifier {
+    ReferenceIdentifier {
+        value: id.value,
+        raw: id.raw,
+        position: id.position,
+        comments: None,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use parser;
-    use parser::Parser;
-    use parser::FromTokenizer;
-    use tokenizer::IntoTokenizer;
+    use parser::{self, ParserOptions};
 
     #[test]
     fn it_parses_imports() {
-        parser::Test::from_tokenizer::<>("
+        parser::parse("
             import \"foo\";
             import foo from \"foo\";
             import * as ns from \"foo\";
@@ -135,18 +555,18 @@ mod tests {
             import foo, * as ns from \"foo\";
             import foo, { named, named as other } from \"foo\";
             import foo, { named, named as other, } from \"foo\";
-        ");
+        ", &ParserOptions::module()).unwrap();
     }
 
     #[test]
     fn it_parses_exports() {
-        parser::Test::from_tokenizer("
+        parser::parse("
             export * from \"foo\";
             export { foo, foo as other } from \"foo\";
             export { foo, foo as other, } from \"foo\";
             export { foo, foo as other };
             export { foo, foo as other, };
             export default this;
-        ");
+        ", &ParserOptions::module()).unwrap();
     }
 }
@@ -0,0 +1,139 @@
+/// The goal symbol that code is parsed with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SourceType {
+    #[default]
+    Script,
+    Module,
+
+    /// Parse as a script, falling back to a module if the code only parses
+    /// as one, e.g. because it has import or export declarations.
+    Unambiguous,
+}
+
+/// The edition of the language to accept. Syntax from later editions is
+/// reported as an error.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EcmaVersion {
+    Es2015,
+    Es2016,
+    Es2017,
+    Es2018,
+    #[default]
+    Es2019,
+}
+impl EcmaVersion {
+    pub const ALL: [EcmaVersion; 5] = [
+        EcmaVersion::Es2015,
+        EcmaVersion::Es2016,
+        EcmaVersion::Es2017,
+        EcmaVersion::Es2018,
+        EcmaVersion::Es2019,
+    ];
+
+    /// The version for a year, e.g. 2017, or an edition number, e.g. 8.
+    pub fn from_number(number: u32) -> Option<EcmaVersion> {
+        let year = if number < 2015 { number + 2009 } else { number };
+        EcmaVersion::ALL.iter().cloned().find(|version| version.year() == year)
+    }
+
+    pub fn year(&self) -> u32 {
+        match *self {
+            EcmaVersion::Es2015 => 2015,
+            EcmaVersion::Es2016 => 2016,
+            EcmaVersion::Es2017 => 2017,
+            EcmaVersion::Es2018 => 2018,
+            EcmaVersion::Es2019 => 2019,
+        }
+    }
+}
+
+/// Syntax that is not part of the language, but is widely used with it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyntaxExtensions {
+    /// JSX elements, e.g. `<div className="a">{b}</div>`.
+    pub jsx: bool,
+
+    /// Decorators on classes, class members and parameters, e.g. `@a class {}`.
+    pub decorators: bool,
+
+    /// The bind operator, e.g. `a::b` and `::a.b`, and do expressions, e.g.
+    /// `do { 1 }`.
+    pub experimental_operators: bool,
+
+    /// TypeScript's type annotations and declarations, e.g.
+    /// `let a: string = b as string;` and `interface A { b: number }`.
+    pub typescript: bool,
+
+    /// Flow's type annotations and declarations, e.g. `let a: ?string = b;`
+    /// and `opaque type A = string;`, including those written in comments,
+    /// e.g. `let a /*: string */ = b;`.
+    pub flow: bool,
+}
+
+/// Everything that changes what code the parser accepts, or what it
+/// produces from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    pub source_type: SourceType,
+    pub ecma_version: EcmaVersion,
+    pub extensions: SyntaxExtensions,
+
+    /// Allow "return" statements at the top level, as in CommonJS modules.
+    pub allow_return_outside_function: bool,
+
+    /// Allow a "#!" line at the start of the code, read as a comment.
+    pub allow_hashbang: bool,
+
+    /// Keep parenthesized expressions in the AST, rather than only the
+    /// expressions inside them.
+    pub preserve_parens: bool,
+
+    /// Attach comments to the nodes around them.
+    pub attach_comments: bool,
+
+    /// Accept the web-compatibility syntax of Annex B in sloppy mode code,
+    /// like legacy octals, "<!--" comments and function declarations as the
+    /// body of "if" statements and labels. HTML-like comments are also
+    /// accepted in strict mode scripts, but never in modules.
+    pub annex_b: bool,
+}
+impl Default for ParserOptions {
+    fn default() -> ParserOptions {
+        ParserOptions {
+            source_type: SourceType::Script,
+            ecma_version: EcmaVersion::default(),
+            extensions: SyntaxExtensions::default(),
+            allow_return_outside_function: false,
+            allow_hashbang: false,
+            preserve_parens: true,
+            attach_comments: true,
+            annex_b: true,
+        }
+    }
+}
+impl ParserOptions {
+    pub fn script() -> ParserOptions {
+        ParserOptions::default()
+    }
+
+    pub fn module() -> ParserOptions {
+        ParserOptions {
+            source_type: SourceType::Module,
+            ..ParserOptions::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_versions_by_year_or_edition() {
+        assert_eq!(EcmaVersion::from_number(2017), Some(EcmaVersion::Es2017));
+        assert_eq!(EcmaVersion::from_number(8), Some(EcmaVersion::Es2017));
+        assert_eq!(EcmaVersion::from_number(6), Some(EcmaVersion::Es2015));
+        assert_eq!(EcmaVersion::from_number(5), None);
+        assert_eq!(EcmaVersion::from_number(2020), None);
+    }
+}
@@ -1,41 +1,17 @@
-
-use tokenizer::{Tokenizer, tokens};
-use parser::{Parser, Flag, LookaheadResult};
-use parser::utils::{OptResult, TokenResult};
-
-// enum ForInit {
-//     // Can occur in any type of for-init.
-//     SingleVar,
-//     SingleLet,
-//     SingleConst,
-
-//     // Allowed in for and for..in
-//     SingleInitializedVar,
-
-//     // Allowed in for
-//     SingleInitializedLet,
-//     SingleInitializedConst,
-
-//     // Allowed in for
-//     MultiVar,
-//     MultiLet,
-//     MultiConst,
-
-//     // allowed in any for-init
-//     LeftHandExpression,
-
-//     // allowed in for
-//     Expression,
-//     None,
-// }
-
-// #[derive(Debug)]
-// enum LoopType {
-//     Any,
-//     For,
-//     ForAndForIn,
-//     ForX
-// }
+use std::mem;
+
+use ast::alias;
+use ast::functions;
+use ast::general;
+use ast::literal;
+use ast::patterns;
+use ast::statement;
+use ast::{KeywordData, KeywordWrappedData};
+use ast::NodeBox;
+use tokenizer::{self, Tokenizer, tokens};
+use parser::{EcmaVersion, Parser, Flag, Label, LookaheadResult, is_binding_identifier};
+use parser::cover;
+use parser::utils::{OptResult, ParseError, Result, TokenResult};
 
 enum StatementType {
     Block,
@@ -56,12 +32,34 @@ enum StatementType {
     Unknown,
 }
 
+// The declaration parsed at the start of a for loop head.
+enum ForDeclaration<'code> {
+    Var(KeywordData, CommaList<statement::VariableDeclarator<'code>>),
+    Let(KeywordData, CommaList<statement::VariableDeclarator<'code>>),
+    Const(KeywordData, CommaList<statement::VariableDeclarator<'code>>),
+}
+
+// The parts of a for-of loop, which is a ForAwaitStatement if it had an "await".
+struct ForOf<'code> {
+    token_for: KeywordData,
+    token_await: Option<KeywordData>,
+    token_paren_l: KeywordData,
+    left: statement::ForOfInit<'code>,
+    token_of: KeywordWrappedData,
+    right: alias::Expression<'code>,
+    token_paren_r: KeywordData,
+    body: alias::Statement<'code>,
+}
 
 impl<'code, T> Parser<'code, T>
 where
     T: Tokenizer<'code>
 {
-    pub fn parse_statement(&mut self) -> OptResult<()> {
+    pub fn parse_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        self.nested(Self::parse_statement_nested)
+    }
+
+    fn parse_statement_nested(&mut self) -> OptResult<alias::Statement<'code>> {
         self.expect_expression();
 
         let stmt_type = match *self.token() {
@@ -88,10 +86,10 @@ where
             _ => StatementType::Unknown,
         };
 
-        match stmt_type {
-            StatementType::Block => eat_value!(self.parse_block_statement()?),
+        let stmt = match stmt_type {
+            StatementType::Block => eat_value!(self.parse_block_statement()?).into(),
             StatementType::Empty => eat_value!(self.parse_empty_statement()?),
-            StatementType::Var => eat_value!(self.parse_variable_statement()?),
+            StatementType::Var => eat_value!(self.parse_variable_statement()?).into(),
             StatementType::If => eat_value!(self.parse_if_statement()?),
             StatementType::Switch => eat_value!(self.parse_breakable_statement()?),
             StatementType::While => eat_value!(self.parse_breakable_statement()?),
@@ -106,27 +104,83 @@ where
             StatementType::Debugger => eat_value!(self.parse_debugger_statement()?),
 
             StatementType::Unknown => {
-                return Ok(try_sequence!(
+                try_value!(try_sequence!(
                     self.parse_labelled_statement()?,
                     self.parse_expression_statement()?,
-                ));
+                ))
             }
-        }
+        };
 
+        self.check_cover_init()?;
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(stmt))
     }
 
-    fn parse_statement_list_item(&mut self) -> OptResult<()> {
+    pub fn parse_statement_list_item(&mut self) -> OptResult<alias::StatementItem<'code>> {
+        self.nested(Self::parse_statement_list_item_nested)
+    }
+
+    fn parse_statement_list_item_nested(&mut self) -> OptResult<alias::StatementItem<'code>> {
         Ok(try_sequence!(
             self.parse_declaration()?,
-            self.parse_statement()?,
+            self.parse_statement()?.map(From::from),
         ))
     }
 
-    pub fn parse_block_statement(&mut self) -> OptResult<()> {
+    /// Parse the items of a script, module or function body, with the
+    /// directives at its start split out. A "use strict" directive makes the
+    /// rest of the body strict.
+    pub fn parse_body_items<I, F>(&mut self, mut parse_item: F) -> Result<(Vec<functions::Directive<'code>>, Vec<I>)>
+    where
+        I: BodyItem<'code>,
+        F: FnMut(&mut Self) -> OptResult<I>,
+    {
+        let mut directives = vec![];
+        let first = loop {
+            let item = match parse_item(self)? {
+                TokenResult::Some(item) => item,
+                TokenResult::None => return Ok((directives, vec![])),
+            };
+            let directive = match item.into_directive() {
+                Ok(directive) => directive,
+                Err(item) => break item,
+            };
+
+            if is_use_strict(&directive) && !self.flags.is_strict {
+                // The directives before it were read as sloppy mode code.
+                if directives.iter().any(|d: &functions::Directive| has_legacy_octal_escape(&d.value.value)) {
+                    bail!("Legacy octal escapes are not allowed before a \"use strict\" directive");
+                }
+                directives.push(directive);
+
+                let (rest, body) = self.with(Flag::Strict).parse_body_items(parse_item)?;
+                directives.extend(rest);
+                return Ok((directives, body));
+            }
+            directives.push(directive);
+        };
+
+        let mut body = vec![first];
+        while let TokenResult::Some(item) = parse_item(self)? {
+            body.push(item);
+        }
+        Ok((directives, body))
+    }
+
+    // Object literals like {foo = 4} are only valid once they've been
+    // converted into a pattern.
+    pub fn check_cover_init(&mut self) -> Result<()> {
+        if self.cover_init {
+            bail!("Unexpected initializer in object literal");
+        }
+        Ok(())
+    }
+
+    pub fn parse_block_statement(&mut self) -> OptResult<statement::BlockStatement<'code>> {
+        let start = self.start();
         let mut parser = self.without(Flag::Template);
         try_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+        let token_curly_l = parser.token_data();
 
         let mut body = vec![];
         while let TokenResult::Some(item) = parser.parse_statement_list_item()? {
@@ -134,197 +188,431 @@ where
         }
 
         eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
-
-        Ok(TokenResult::Some(()))
+        let token_curly_r = parser.token_data();
+
+        Ok(TokenResult::Some(parser.located(start, statement::BlockStatement {
+            token_curly_l,
+            body,
+            token_curly_r,
+            position: None,
+            comments: None,
+        })))
     }
 
-    pub fn parse_variable_statement(&mut self) -> OptResult<()> {
+    pub fn parse_variable_statement(&mut self) -> OptResult<statement::VariableStatement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("var"));
+        let token_var = self.token_data();
+
+        let declarators = eat_value!(self.with(Flag::In).parse_declarator_list()?);
+        for decl in declarators.iter() {
+            if decl.init.is_none() {
+                require_identifier(&decl.id)?;
+            }
+        }
+
+        let token_semi = eat_value!(self.semicolon_token());
 
-        eat_value!(self.with(Flag::In).parse_var_declarator()?);
+        Ok(TokenResult::Some(self.located(start, statement::VariableStatement {
+            token_var,
+            declarators: declarators.items,
+            last_declarator: declarators.last,
+            token_semi,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // A comma-separated list of declarators, validated by the caller.
+    pub fn parse_declarator_list(&mut self) -> OptResult<CommaList<statement::VariableDeclarator<'code>>> {
+        let mut items = vec![];
+        let mut last = try_value!(self.parse_declarator()?);
 
         while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
-            eat_value!(self.with(Flag::In).parse_var_declarator()?);
+            let token_comma = self.token_data();
+            let next = eat_value!(self.parse_declarator()?);
+            items.push((mem::replace(&mut last, next), token_comma));
         }
 
-        eat_value!(self.semicolon());
-
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(CommaList { items, last }))
     }
 
-    pub fn parse_var_declarator(&mut self) -> OptResult<()> {
-        if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-            eat_value!(self.parse_initializer()?);
-        } else {
-            eat_value!(self.binding_identifier());
-            opt_value!(self.parse_initializer()?);
+    pub fn parse_declarator(&mut self) -> OptResult<statement::VariableDeclarator<'code>> {
+        let start = self.start();
+        let id = try_value!(self.parse_binding_element_target()?);
+        let definite = self.parse_definite()?;
+        let type_annotation = opt_value!(self.parse_type_annotation()?);
+
+        let init = opt_value!(self.parse_initializer()?);
+        if definite && init.is_some() {
+            bail!("Declarations with initializers cannot also have definite assignment assertions");
         }
-        Ok(TokenResult::Some(()))
+
+        Ok(TokenResult::Some(self.located(start, statement::VariableDeclarator {
+            id,
+            definite,
+            type_annotation,
+            init,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    pub fn parse_binding_pattern(&mut self) -> OptResult<patterns::BindingPattern<'code>> {
+        self.nested(Self::parse_binding_pattern_nested)
     }
 
-    pub fn parse_binding_pattern(&mut self) -> OptResult<()> {
+    fn parse_binding_pattern_nested(&mut self) -> OptResult<patterns::BindingPattern<'code>> {
         Ok(try_sequence!(
-            self.parse_object_binding_pattern()?,
-            self.parse_array_binding_pattern()?,
+            self.parse_object_binding_pattern()?.map(From::from),
+            self.parse_array_binding_pattern()?.map(From::from),
         ))
     }
 
-    pub fn parse_object_binding_pattern(&mut self) -> OptResult<()> {
+    // foo, {foo}, [foo]
+    pub fn parse_binding_element_target(&mut self) -> OptResult<patterns::BindingPattern<'code>> {
+        Ok(try_sequence!(
+            self.parse_binding_pattern()?,
+            self.parse_binding_identifier().map(From::from),
+        ))
+    }
+
+    pub fn parse_object_binding_pattern(&mut self) -> OptResult<patterns::ObjectBindingPattern<'code>> {
+        let start = self.start();
         let mut parser = self.without(Flag::Template);
 
         try_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
 
+        let mut properties = vec![];
+        let mut last_property = None;
         loop {
-            if let TokenResult::Some(_) = parser.parse_binding_property()? {
-                if let TokenResult::None = parser.punc(tokens::PunctuatorToken::Comma) {
-                    break;
-                }
-            } else {
+            let prop_start = parser.start();
+            if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Ellipsis) {
+                parser.require_version(EcmaVersion::Es2018, "Object rest property")?;
+                let token_ellipsis = parser.token_data();
+                let pattern = eat_value!(parser.parse_binding_identifier());
+
+                last_property = Some(parser.located(prop_start, patterns::ObjectBindingPatternRestProperty {
+                    token_ellipsis,
+                    pattern,
+                    position: None,
+                    comments: None,
+                }).into());
                 break;
             }
 
+            let prop = match parser.parse_binding_property()? {
+                TokenResult::Some(prop) => prop,
+                TokenResult::None => break,
+            };
+
+            if let TokenResult::Some(_) = parser.punc(tokens::PunctuatorToken::Comma) {
+                properties.push((prop, parser.token_data()));
+            } else {
+                last_property = Some(match prop {
+                    patterns::ObjectBindingPatternProperty::Identifier(prop) => prop.into(),
+                    patterns::ObjectBindingPatternProperty::Pattern(prop) => prop.into(),
+                });
+                break;
+            }
         }
 
         eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(parser.located(start, patterns::ObjectBindingPattern {
+            properties,
+            last_property,
+            position: None,
+            comments: None,
+        })))
     }
 
     // foo = 4
     // foo: bar = 4
     // foo: {bar} = 4
     // "foo": bar = 4
-    fn parse_binding_property(&mut self) -> OptResult<()> {
-        let _name = try_value!(self.parse_property_name()?);
+    fn parse_binding_property(&mut self) -> OptResult<patterns::ObjectBindingPatternProperty<'code>> {
+        let start = self.start();
+        let name = try_value!(self.parse_property_name()?);
 
         if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Colon) {
-            eat_value!(self.parse_binding_element()?);
-        } else {
-            opt_value!(self.with(Flag::In).parse_initializer()?);
+            let pattern = eat_value!(self.parse_binding_element_target()?);
+            let init = opt_value!(self.with(Flag::In).parse_initializer()?);
+
+            return Ok(TokenResult::Some(self.located(start, patterns::ObjectBindingPatternPatternProperty {
+                name,
+                pattern: NodeBox::new(pattern),
+                init,
+                position: None,
+                comments: None,
+            }).into()));
         }
 
-        Ok(TokenResult::Some(()))
+        let id = match name {
+            general::PropertyName::Identifier(id) => {
+                if !self.is_binding_identifier(&id.value) {
+                    bail!("Unexpected reserved word in shorthand property");
+                }
+                cover::binding_identifier(cover::reference_identifier(id))
+            }
+            _ => bail!("Expected ':' after property name"),
+        };
+        let init = opt_value!(self.with(Flag::In).parse_initializer()?);
+
+        Ok(TokenResult::Some(self.located(start, patterns::ObjectBindingPatternIdentifierProperty {
+            id,
+            init,
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    pub fn parse_property_name(&mut self) -> OptResult<()> {
+    pub fn parse_property_name(&mut self) -> OptResult<general::PropertyName<'code>> {
         Ok(try_sequence!(
             self.parse_literal_property_name()?,
             self.parse_computed_property_name()?,
         ))
     }
 
-    fn parse_literal_property_name(&mut self) -> OptResult<()> {
-        if let TokenResult::Some(_) = self.identifier() {
-        } else if let TokenResult::Some(_) = self.string() {
-        } else if let TokenResult::Some(_) = self.numeric() {
+    fn parse_literal_property_name(&mut self) -> OptResult<general::PropertyName<'code>> {
+        let start = self.start();
+
+        let name = if let TokenResult::Some(id) = self.parse_property_identifier() {
+            id.into()
+        } else if let TokenResult::Some(s) = self.parse_string_literal() {
+            s.into()
+        } else if let TokenResult::Some(n) = self.numeric() {
+            self.located(start, literal::Numeric::from(n.value)).into()
         } else {
             return Ok(TokenResult::None);
-        }
+        };
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(name))
     }
 
-    fn parse_computed_property_name(&mut self) -> OptResult<()> {
+    fn parse_computed_property_name(&mut self) -> OptResult<general::PropertyName<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::SquareOpen));
 
         self.expect_expression();
-        opt_value!(self.with(Flag::In).parse_assignment_expression()?);
+        let expr = eat_value!(self.with(Flag::In).parse_assignment_expression()?);
 
         eat_value!(self.punc(tokens::PunctuatorToken::SquareClose));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, general::ComputedPropertyName {
+            expression: NodeBox::new(expr),
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    pub fn parse_array_binding_pattern(&mut self) -> OptResult<()> {
+    pub fn parse_array_binding_pattern(&mut self) -> OptResult<patterns::ArrayBindingPattern<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::SquareOpen));
 
-        loop {
-            if let TokenResult::Some(_) = self.parse_binding_rest_element()? {
-                break;
+        let mut items = vec![];
+        let last_item = loop {
+            let item_start = self.start();
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Ellipsis) {
+                let token_ellipsis = self.token_data();
+                let pattern = eat_value!(self.parse_binding_element_target()?);
+
+                break Some(self.located(item_start, patterns::ArrayBindingRestElement {
+                    token_ellipsis,
+                    pattern: NodeBox::new(pattern),
+                    position: None,
+                    comments: None,
+                }).into());
             }
 
-            if let TokenResult::Some(_) = self.parse_binding_element()? {
+            let item = opt_value!(self.parse_binding_element()?);
 
+            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+                items.push((item, self.token_data()));
+            } else {
+                break item.map(|item| patterns::ArrayBindingPatternLastElement::Pattern(NodeBox::new(item)));
             }
-
-            if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
-                break;
-            }
-        }
+        };
 
         eat_value!(self.punc(tokens::PunctuatorToken::SquareClose));
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, patterns::ArrayBindingPattern {
+            items,
+            last_item,
+            position: None,
+            comments: None,
+        })))
     }
 
-    pub fn parse_binding_rest_element(&mut self) -> OptResult<()> {
-        try_value!(self.punc(tokens::PunctuatorToken::Ellipsis));
-
-        if let TokenResult::None = self.parse_binding_pattern()? {
-            eat_value!(self.binding_identifier());
-        }
-
-        Ok(TokenResult::Some(()))
-    }
-    fn parse_binding_element(&mut self) -> OptResult<()> {
-        if let TokenResult::None = self.parse_binding_pattern()? {
-            try_value!(self.binding_identifier());
-        }
-        opt_value!(self.with(Flag::In).parse_initializer()?);
-
-        Ok(TokenResult::Some(()))
+    fn parse_binding_element(&mut self) -> OptResult<patterns::ArrayBindingPatternElement<'code>> {
+        let start = self.start();
+        let id = try_value!(self.parse_binding_element_target()?);
+        let init = opt_value!(self.with(Flag::In).parse_initializer()?);
+
+        Ok(TokenResult::Some(self.located(start, patterns::ArrayBindingPatternElement {
+            id,
+            init,
+            position: None,
+            comments: None,
+        })))
     }
 
-    pub fn parse_initializer(&mut self) -> OptResult<()> {
+    pub fn parse_initializer(&mut self) -> OptResult<general::Initializer<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::Eq));
+        let token_eq = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.parse_assignment_expression()?);
+        let expr = eat_value!(self.parse_assignment_expression()?);
+
+        Ok(TokenResult::Some(self.located(start, general::Initializer {
+            token_eq,
+            expression: NodeBox::new(expr),
+            position: None,
+            comments: None,
+        })))
+    }
+
+    fn is_declaration_start(&mut self) -> bool {
+        let is_async = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => match &**name {
+                "function" | "class" => return true,
+                "async" => true,
+                _ => false,
+            },
+            _ => false,
+        };
 
-        Ok(TokenResult::Some(()))
+        is_async && matches!(self.ident_lookahead(), Some(&LookaheadResult {
+            line: false,
+            token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+            ..
+        }) if name == "function")
     }
 
-    fn parse_empty_statement(&mut self) -> OptResult<()> {
+    fn parse_empty_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.punc(tokens::PunctuatorToken::Semicolon));
+        let token_semi = self.token_data();
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, statement::EmptyStatement {
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_expression_statement(&mut self) -> OptResult<()> {
-        try_value!(self.with(Flag::In).parse_expression()?);
+    fn parse_expression_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        // These start declarations even where they aren't allowed, like
+        // "while (a) function b() {}".
+        if self.is_declaration_start() {
+            return Ok(TokenResult::None);
+        }
 
-        eat_value!(self.semicolon());
+        let start = self.start();
+        let expression = try_value!(self.with(Flag::In).parse_expression()?);
 
-        Ok(TokenResult::Some(()))
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, statement::ExpressionStatement {
+            token_prefix: Default::default(),
+            expression,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_if_statement(&mut self) -> OptResult<()> {
+    fn parse_if_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("if"));
+        let token_if = self.token_data();
 
         eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = self.token_data();
 
         self.expect_expression();
-        eat_value!(self.with(Flag::In).parse_expression()?);
+        let test = eat_value!(self.with(Flag::In).parse_expression()?);
 
         eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+        let token_paren_r = self.token_data();
 
-        eat_value!(self.parse_statement()?);
+        let consequent = eat_value!(self.parse_if_body()?);
 
         if let TokenResult::Some(_) = self.keyword("else") {
-            eat_value!(self.parse_statement()?);
+            let token_else = self.token_data();
+            let alternate = eat_value!(self.parse_if_body()?);
+
+            return Ok(TokenResult::Some(self.located(start, statement::IfElseStatement {
+                token_if,
+                token_paren_l,
+                test,
+                token_paren_r,
+                consequent: NodeBox::new(consequent),
+                token_else,
+                alternate: NodeBox::new(alternate),
+                position: None,
+                comments: None,
+            }).into()));
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, statement::IfStatement {
+            token_if,
+            token_paren_l,
+            test,
+            token_paren_r,
+            consequent: NodeBox::new(consequent),
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_breakable_statement(&mut self) -> OptResult<()> {
+    // The body of an "if" statement, which Annex B also allows to be a
+    // function declaration.
+    fn parse_if_body(&mut self) -> OptResult<alias::Statement<'code>> {
+        if let TokenResult::Some(decl) = self.parse_annex_b_function()? {
+            return Ok(TokenResult::Some(decl.into()));
+        }
+        self.parse_body_statement()
+    }
+
+    // The body of a loop, "if" or "with" statement, which can't be a
+    // labelled function, like "while (a) b: function c() {}".
+    fn parse_body_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        self.nested(Self::parse_body_statement_nested)
+    }
+
+    fn parse_body_statement_nested(&mut self) -> OptResult<alias::Statement<'code>> {
+        let stmt = try_value!(self.parse_statement()?);
+        if is_labelled_function(&stmt) {
+            bail!("Labelled functions can't be the body of a statement");
+        }
+        Ok(TokenResult::Some(stmt))
+    }
+
+    // A function declaration in place of a statement, which Annex B allows
+    // in sloppy mode as long as it isn't a generator.
+    fn parse_annex_b_function(&mut self) -> OptResult<functions::FunctionDeclaration<'code>> {
+        let is_function = matches!(*self.token(), tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) if name == "function");
+        if !is_function || !self.annex_b() {
+            return Ok(TokenResult::None);
+        }
+
+        let decl = eat_value!(self.parse_function_declaration()?);
+        if let functions::FunctionKind::Generator = decl.kind {
+            bail!("Generators can only be declared at the top level or inside a block");
+        }
+        Ok(TokenResult::Some(decl))
+    }
+
+    fn parse_breakable_statement(&mut self) -> OptResult<alias::Statement<'code>> {
         Ok(try_sequence!(
             self.parse_iteration_statement()?,
             self.parse_switch_statement()?,
         ))
     }
 
-    fn parse_iteration_statement(&mut self) -> OptResult<()> {
+    fn parse_iteration_statement(&mut self) -> OptResult<alias::Statement<'code>> {
         Ok(try_sequence!(
             self.parse_do_while_statement()?,
             self.parse_while_statement()?,
@@ -332,381 +620,1052 @@ where
         ))
     }
 
-    fn parse_do_while_statement(&mut self) -> OptResult<()> {
+    fn parse_do_while_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("do"));
+        self.label_loop(start);
+        let token_do = self.token_data();
 
-        eat_value!(self.parse_statement()?);
+        let body = eat_value!(self.with(Flag::Loop).parse_body_statement()?);
 
         eat_value!(self.keyword("while"));
+        let token_while = self.token_data();
 
         eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = self.token_data();
         self.expect_expression();
-        eat_value!(self.with(Flag::In).parse_expression()?);
+        let test = eat_value!(self.with(Flag::In).parse_expression()?);
         eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
-
-        eat_value!(self.semicolon_dowhile());
-
-        Ok(TokenResult::Some(()))
+        let token_paren_r = self.token_data();
+
+        let token_semi = eat_value!(self.semicolon_dowhile());
+
+        Ok(TokenResult::Some(self.located(start, statement::DoWhileStatement {
+            token_do,
+            body: NodeBox::new(body),
+            token_while,
+            token_paren_l,
+            test: NodeBox::new(test),
+            token_paren_r,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_while_statement(&mut self) -> OptResult<()> {
+    fn parse_while_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("while"));
+        self.label_loop(start);
+        let token_while = self.token_data();
 
         eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = self.token_data();
         self.expect_expression();
-        eat_value!(self.with(Flag::In).parse_expression()?);
+        let test = eat_value!(self.with(Flag::In).parse_expression()?);
         eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
-
-        eat_value!(self.parse_statement()?);
-
-        Ok(TokenResult::Some(()))
+        let token_paren_r = self.token_data();
+
+        let body = eat_value!(self.with(Flag::Loop).parse_body_statement()?);
+
+        Ok(TokenResult::Some(self.located(start, statement::WhileStatement {
+            token_while,
+            token_paren_l,
+            test: NodeBox::new(test),
+            token_paren_r,
+            body: NodeBox::new(body),
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_for_statement(&mut self) -> OptResult<()> {
+    fn parse_for_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("for"));
+        self.label_loop(start);
+        let token_for = self.token_data();
 
-        eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
-
-        self.expect_expression();
-
-        let (kind, pattern, initialized, multiple, _bracket, left_hand) = if let TokenResult::Some(_) = self.keyword("var") {
-            let mut pattern = false;
-            let mut initialized = false;
-            if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-                pattern = true;
-
-                if let TokenResult::Some(_) = self.without(Flag::In).parse_initializer()? {
-                    initialized = true;
-                }
+        // for await (... of ...)
+        let token_await = if self.flags.allow_await {
+            if let TokenResult::Some(_) = self.keyword("await") {
+                Some(self.token_data())
             } else {
-                eat_value!(self.binding_identifier());
-                if let TokenResult::Some(_) = self.without(Flag::In).parse_initializer()? {
-                    initialized = true;
-                }
+                None
             }
+        } else {
+            None
+        };
 
-            let mut multiple = false;
-            if !pattern || initialized {
-                while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
-                    multiple = true;
-                    eat_value!(self.without(Flag::In).parse_var_declarator()?);
-                }
-            }
+        eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = self.token_data();
 
-            ("var", pattern, initialized, multiple, false, false)
-        } else if let TokenResult::Some(_) = self.keyword("const") {
-            let mut pattern = false;
-            let mut initialized = false;
-            if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-                pattern = true;
+        self.expect_expression();
+        let init_start = self.start();
 
-                if let TokenResult::Some(_) = self.without(Flag::In).parse_initializer()? {
-                    initialized = true;
-                }
-            } else {
-                eat_value!(self.binding_identifier());
-                if let TokenResult::Some(_) = self.without(Flag::In).parse_initializer()? {
-                    initialized = true;
-                }
-            }
+        let decl = if let TokenResult::Some(_) = self.keyword("var") {
+            let token_var = self.token_data();
+            let declarators = eat_value!(self.without(Flag::In).parse_declarator_list()?);
 
-            let mut multiple = false;
-            if !pattern || initialized {
-                while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
-                    multiple = true;
-                    eat_value!(self.without(Flag::In).parse_lexical_declarator(true)?);
-                }
-            }
+            Some(ForDeclaration::Var(token_var, declarators))
+        } else if let TokenResult::Some(_) = self.keyword("const") {
+            let token_const = self.token_data();
+            let declarators = eat_value!(self.without(Flag::In).parse_declarator_list()?);
 
-            ("const", pattern, initialized, multiple, false, false)
+            Some(ForDeclaration::Const(token_const, declarators))
         } else {
-            let (maybe_decl, bracket) = if let Some(&LookaheadResult { line: _line, ref token }) = self.ident_lookahead() {
+            let maybe_decl = if let Some(LookaheadResult { token, .. }) = self.ident_lookahead() {
                 match *token {
-                    tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => (name != "in" && name != "of", false),
-                    tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen) => (true, true),
-                    tokens::Token::Punctuator(tokens::PunctuatorToken::CurlyOpen) => (true, false),
-                    _ => (false, false),
+                    tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => name != "in" && name != "of",
+                    tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen) => true,
+                    tokens::Token::Punctuator(tokens::PunctuatorToken::CurlyOpen) => true,
+                    _ => false,
                 }
             } else {
-                (false, false)
+                false
             };
 
-
-            let decl = if maybe_decl {
+            if maybe_decl {
                 if let TokenResult::Some(_) = self.keyword("let") {
-                    let mut pattern = false;
-                    let mut initialized = false;
-                    if let TokenResult::Some(_) = self.parse_binding_pattern()? {
-                        pattern = true;
-
-                        if let TokenResult::Some(_) = self.without(Flag::In).parse_initializer()? {
-                            initialized = true;
-                        }
-                    } else {
-                        eat_value!(self.binding_identifier());
-                        if let TokenResult::Some(_) = self.without(Flag::In).parse_initializer()? {
-                            initialized = true;
-                        }
-                    }
+                    let token_let = self.token_data();
+                    let declarators = eat_value!(self.without(Flag::In).parse_declarator_list()?);
 
-                    let mut multiple = false;
-                    if !pattern || initialized {
-                        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
-                            multiple = true;
-                            eat_value!(self.without(Flag::In).parse_lexical_declarator(true)?);
-                        }
-                    }
-
-                    Some(("let", pattern, initialized, multiple, bracket, false))
+                    Some(ForDeclaration::Let(token_let, declarators))
                 } else {
                     None
                 }
             } else {
                 None
-            };
-
-            if let Some(decl) = decl {
-                decl
-            } else {
-                // TODO: What to do here? If this is a LeftHandSideExpression,
-                // the for can be any type, otherwise it _must_ be 'ForStatement'
-                opt_value!(self.without(Flag::In).parse_expression()?);
-
-                ("expression", false, false, false, false, true /* tmp */)
             }
         };
 
-        let (maybe_for, maybe_x) = match kind {
-            "var" | "let" => {
-                let maybe_for = !pattern || initialized;
-                let maybe_x = !multiple && !initialized;
-
-                (maybe_for, maybe_x)
-            }
-            "const" => {
-                let maybe_for = initialized;
-                let maybe_x = !multiple && !initialized;
+        let init = match decl {
+            Some(decl) => {
+                let single = match decl {
+                    ForDeclaration::Var(_, ref declarators) |
+                    ForDeclaration::Let(_, ref declarators) |
+                    ForDeclaration::Const(_, ref declarators) => declarators.items.is_empty(),
+                };
 
-                (maybe_for, maybe_x)
-            }
-            "expression" => {
-                if left_hand {
-                    // for/in/of
-                    (true, true)
+                let is_in = if let tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) = *self.token() {
+                    name == "in"
                 } else {
-                    // for
-                    (true, false)
-                }
-            }
-            _ => unreachable!(),
-        };
+                    false
+                };
+                let is_of = if let tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) = *self.token() {
+                    name == "of"
+                } else {
+                    false
+                };
 
-        let found = if maybe_for {
-            if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Semicolon) {
-                self.expect_expression();
-                opt_value!(self.parse_expression()?);
+                if single && (is_in || is_of) {
+                    if is_in && token_await.is_some() {
+                        return Err(ParseError {}.into());
+                    }
 
-                eat_value!(self.punc(tokens::PunctuatorToken::Semicolon));
+                    let stmt = if is_in {
+                        let left = for_in_init(decl, self.annex_b()).map_err(|error| self.locate_error(init_start, error))?;
+                        eat_value!(self.keyword("in"));
+                        let token_in = self.token_data();
+                        self.expect_expression();
+                        let right = eat_value!(self.with(Flag::In).parse_expression()?);
+                        eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+                        let token_paren_r = self.token_data();
+                        let body = eat_value!(self.with(Flag::Loop).parse_body_statement()?);
+
+                        self.located(start, statement::ForInStatement {
+                            token_for,
+                            token_paren_l,
+                            left,
+                            token_in,
+                            right: NodeBox::new(right),
+                            token_paren_r,
+                            body: NodeBox::new(body),
+                            position: None,
+                            comments: None,
+                        }).into()
+                    } else {
+                        let left = for_of_init(decl).map_err(|error| self.locate_error(init_start, error))?;
+                        eat_value!(self.keyword("of"));
+                        let token_of = self.token_data();
+                        self.expect_expression();
+                        let right = eat_value!(self.with(Flag::In).parse_assignment_expression()?);
+                        eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+                        let token_paren_r = self.token_data();
+                        let body = eat_value!(self.with(Flag::Loop).parse_body_statement()?);
+
+                        self.for_of_statement(start, ForOf {
+                            token_for,
+                            token_await,
+                            token_paren_l,
+                            left,
+                            token_of,
+                            right,
+                            token_paren_r,
+                            body,
+                        })
+                    };
+
+                    return Ok(TokenResult::Some(stmt));
+                }
 
-                self.expect_expression();
-                opt_value!(self.parse_expression()?);
-                true
-            } else {
-                false
+                Some(self.for_init(init_start, decl)?)
             }
-        } else {
-            false
-        };
+            None => {
+                let init = self.without(Flag::In).parse_expression()?;
+                match init {
+                    TokenResult::Some(expr) => {
+                        let left_hand = is_left_hand_expression(&expr);
+
+                        if left_hand {
+                            let is_in = match token_await {
+                                Some(_) => TokenResult::None,
+                                None => self.keyword("in"),
+                            };
+                            if let TokenResult::Some(_) = is_in {
+                                let token_in = self.token_data();
+                                self.cover_init = false;
+                                let left = self.complex_assign_target(init_start, expr)?;
+
+                                self.expect_expression();
+                                let right = eat_value!(self.with(Flag::In).parse_expression()?);
+                                eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+                                let token_paren_r = self.token_data();
+                                let body = eat_value!(self.with(Flag::Loop).parse_body_statement()?);
+
+                                return Ok(TokenResult::Some(self.located(start, statement::ForInStatement {
+                                    token_for,
+                                    token_paren_l,
+                                    left: left.into(),
+                                    token_in,
+                                    right: NodeBox::new(right),
+                                    token_paren_r,
+                                    body: NodeBox::new(body),
+                                    position: None,
+                                    comments: None,
+                                }).into()));
+                            }
+                            if let TokenResult::Some(_) = self.keyword("of") {
+                                let token_of = self.token_data();
+                                self.cover_init = false;
+                                let left = self.complex_assign_target(init_start, expr)?;
+
+                                self.expect_expression();
+                                let right = eat_value!(self.with(Flag::In).parse_assignment_expression()?);
+                                eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+                                let token_paren_r = self.token_data();
+                                let body = eat_value!(self.with(Flag::Loop).parse_body_statement()?);
+
+                                return Ok(TokenResult::Some(self.for_of_statement(start, ForOf {
+                                    token_for,
+                                    token_await,
+                                    token_paren_l,
+                                    left: left.into(),
+                                    token_of,
+                                    right,
+                                    token_paren_r,
+                                    body,
+                                })));
+                            }
+                        }
 
-        if !found {
-            if maybe_x {
-                if let TokenResult::Some(_) = self.keyword("in") {
-                    self.expect_expression();
-                    eat_value!(self.with(Flag::In).parse_assignment_expression()?);
-                } else if let TokenResult::Some(_) = self.keyword("of") {
-                    self.expect_expression();
-                    eat_value!(self.with(Flag::In).parse_assignment_expression()?);
-                } else {
-                    bail!("Invalid for loop");
+                        self.check_cover_init()?;
+                        Some(expr.into())
+                    }
+                    TokenResult::None => None,
                 }
-            } else {
-                bail!("bad for loop");
             }
+        };
+
+        if token_await.is_some() {
+            return Err(ParseError {}.into());
         }
 
+        eat_value!(self.punc(tokens::PunctuatorToken::Semicolon));
+        let token_init_semi = self.token_data();
+
+        self.expect_expression();
+        let test = opt_value!(self.with(Flag::In).parse_expression()?);
+
+        eat_value!(self.punc(tokens::PunctuatorToken::Semicolon));
+        let token_test_semi = self.token_data();
+
+        self.expect_expression();
+        let update = opt_value!(self.with(Flag::In).parse_expression()?);
+
         eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+        let token_paren_r = self.token_data();
+
+        let body = eat_value!(self.with(Flag::Loop).parse_body_statement()?);
+
+        Ok(TokenResult::Some(self.located(start, statement::ForStatement {
+            token_for,
+            token_paren_l,
+            init,
+            token_init_semi,
+            test,
+            token_test_semi,
+            update,
+            token_paren_r,
+            body: NodeBox::new(body),
+            position: None,
+            comments: None,
+        }).into()))
+    }
 
-        eat_value!(self.parse_statement()?);
+    fn for_of_statement(&self, start: tokenizer::Position, parts: ForOf<'code>) -> alias::Statement<'code> {
+        let ForOf { token_for, token_await, token_paren_l, left, token_of, right, token_paren_r, body } = parts;
+
+        match token_await {
+            Some(token_await) => self.located(start, statement::ForAwaitStatement {
+                token_for,
+                token_await,
+                token_paren_l,
+                left,
+                token_of,
+                right: NodeBox::new(right),
+                token_paren_r,
+                body: NodeBox::new(body),
+                position: None,
+                comments: None,
+            }).into(),
+            None => self.located(start, statement::ForOfStatement {
+                token_for,
+                token_paren_l,
+                left,
+                token_of,
+                right: NodeBox::new(right),
+                token_paren_r,
+                body: NodeBox::new(body),
+                position: None,
+                comments: None,
+            }).into(),
+        }
+    }
 
-        Ok(TokenResult::Some(()))
+    // for (var a = 1, b; ...; ...)
+    fn for_init(&mut self, start: tokenizer::Position, decl: ForDeclaration<'code>) -> Result<statement::ForInit<'code>> {
+        Ok(match decl {
+            ForDeclaration::Var(token_var, declarators) => {
+                for decl in declarators.iter() {
+                    if decl.init.is_none() {
+                        require_identifier(&decl.id)?;
+                    }
+                }
+
+                self.located(start, statement::VariableStatement {
+                    token_var,
+                    declarators: declarators.items,
+                    last_declarator: declarators.last,
+                    token_semi: Default::default(),
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            ForDeclaration::Let(token_let, declarators) => {
+                let declarators = declarators.try_map(let_declarator)?;
+
+                self.located(start, statement::LetDeclaration {
+                    token_let,
+                    declarators: declarators.items,
+                    last_declarator: declarators.last,
+                    token_semi: Default::default(),
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            ForDeclaration::Const(token_const, declarators) => {
+                let declarators = declarators.try_map(const_declarator)?;
+
+                self.located(start, statement::ConstDeclaration {
+                    token_const,
+                    declarators: declarators.items,
+                    last_declarator: declarators.last,
+                    token_semi: Default::default(),
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+        })
     }
 
-    fn parse_switch_statement(&mut self) -> OptResult<()> {
+    fn parse_switch_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("switch"));
+        let token_switch = self.token_data();
 
         eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = self.token_data();
         self.expect_expression();
-        eat_value!(self.with(Flag::In).parse_expression()?);
+        let discriminant = eat_value!(self.with(Flag::In).parse_expression()?);
         eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+        let token_paren_r = self.token_data();
 
 
         let mut parser = self.without(Flag::Template);
+        let mut parser = parser.with(Flag::Switch);
         eat_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+        let token_curly_l = parser.token_data();
 
-        let mut body = vec![];
+        let mut cases = vec![];
         let mut has_default = false;
         loop {
-            if let TokenResult::Some(_) = parser.parse_default_clause()? {
+            if let TokenResult::Some(item) = parser.parse_default_clause()? {
                 if has_default {
                     bail!("duplicate default statements");
                 }
                 has_default = true;
+                cases.push(item.into());
             } else if let TokenResult::Some(item) = parser.parse_case_clause()? {
-                body.push(item);
+                cases.push(item.into());
             } else {
                 break;
             }
         }
 
         eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
-
-        Ok(TokenResult::Some(()))
+        let token_curly_r = parser.token_data();
+
+        Ok(TokenResult::Some(parser.located(start, statement::SwitchStatement {
+            token_switch,
+            token_paren_l,
+            discriminant: NodeBox::new(discriminant),
+            token_paren_r,
+            token_curly_l,
+            cases,
+            token_curly_r,
+            position: None,
+            comments: None,
+        }).into()))
     }
-    fn parse_default_clause(&mut self) -> OptResult<()> {
+    fn parse_default_clause(&mut self) -> OptResult<statement::SwitchDefault<'code>> {
+        let start = self.start();
         try_value!(self.keyword("default"));
+        let token_default = self.token_data();
         eat_value!(self.punc(tokens::PunctuatorToken::Colon));
+        let token_colon = self.token_data();
 
-        let mut body = vec![];
+        let mut consequent = vec![];
         while let TokenResult::Some(item) = self.parse_statement_list_item()? {
-            body.push(item);
+            consequent.push(item);
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, statement::SwitchDefault {
+            token_default,
+            token_colon,
+            consequent,
+            position: None,
+            comments: None,
+        })))
     }
 
-    fn parse_case_clause(&mut self) -> OptResult<()> {
+    fn parse_case_clause(&mut self) -> OptResult<statement::SwitchCase<'code>> {
+        let start = self.start();
         try_value!(self.keyword("case"));
+        let token_case = self.token_data();
         self.expect_expression();
-        eat_value!(self.with(Flag::In).parse_expression()?);
+        let test = eat_value!(self.with(Flag::In).parse_expression()?);
         eat_value!(self.punc(tokens::PunctuatorToken::Colon));
+        let token_colon = self.token_data();
 
-        let mut body = vec![];
+        let mut consequent = vec![];
         while let TokenResult::Some(item) = self.parse_statement_list_item()? {
-            body.push(item);
+            consequent.push(item);
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, statement::SwitchCase {
+            token_case,
+            test: NodeBox::new(test),
+            token_colon,
+            consequent,
+            position: None,
+            comments: None,
+        })))
     }
 
 
-    fn parse_continue_statement(&mut self) -> OptResult<()> {
+    fn parse_continue_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("continue"));
+        let token_continue = self.token_data();
 
-        if self.no_line_terminator() {
-            opt_value!(self.label_identifier());
+        let label = if self.no_line_terminator() {
+            opt_value!(self.parse_label_value())
+        } else {
+            None
+        };
+
+        let valid = match label {
+            Some(ref label) => self.labels.iter().any(|l| l.is_loop && l.name == label.label.value),
+            None => self.flags.in_loop,
+        };
+        if !valid {
+            return Err(self.locate_error(start, format_err!("Illegal continue statement")));
         }
 
-        eat_value!(self.semicolon());
+        let token_semi = eat_value!(self.semicolon_token());
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, statement::ContinueStatement {
+            token_continue,
+            label,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
     }
-    fn parse_break_statement(&mut self) -> OptResult<()> {
+    fn parse_break_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("break"));
+        let token_break = self.token_data();
 
-        if self.no_line_terminator() {
-            opt_value!(self.label_identifier());
+        let label = if self.no_line_terminator() {
+            opt_value!(self.parse_label_value())
+        } else {
+            None
+        };
+
+        let valid = match label {
+            Some(ref label) => self.labels.iter().any(|l| l.name == label.label.value),
+            None => self.flags.in_loop || self.flags.in_switch,
+        };
+        if !valid {
+            return Err(self.locate_error(start, format_err!("Illegal break statement")));
         }
 
-        eat_value!(self.semicolon());
-        Ok(TokenResult::Some(()))
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, statement::BreakStatement {
+            token_break,
+            label,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+    fn parse_label_value(&mut self) -> TokenResult<statement::LabelValue<'code>> {
+        let start = self.start();
+        let label = try_token!(self.parse_label_identifier());
+
+        TokenResult::Some(self.located(start, statement::LabelValue {
+            tokens_prefix: Default::default(),
+            label,
+            position: None,
+            comments: None,
+        }))
     }
 
-    fn parse_return_statement(&mut self) -> OptResult<()> {
+    fn parse_return_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
+        let allow_return = self.flags.allow_return;
+        match *self.token() {
+            tokens::Token::IdentifierName(ref id) if id.name == "return" && !allow_return => {
+                bail!("Illegal return statement");
+            }
+            _ => {}
+        }
         try_value!(self.keyword("return"));
+        let token_return = self.token_data();
 
-        if self.no_line_terminator() {
+        let value = if self.no_line_terminator() {
             self.expect_expression();
-            opt_value!(self.with(Flag::In).parse_expression()?);
-        }
 
-        eat_value!(self.semicolon());
+            let value_start = self.start();
+            let expr = self.with(Flag::In).parse_expression()?;
+            match expr {
+                TokenResult::Some(expr) => Some(self.located(value_start, statement::ReturnValue {
+                    token_prefix: Default::default(),
+                    expression: NodeBox::new(expr),
+                    position: None,
+                    comments: None,
+                })),
+                TokenResult::None => None,
+            }
+        } else {
+            None
+        };
+
+        let token_semi = eat_value!(self.semicolon_token());
 
-        Ok(TokenResult::Some(()))
+        Ok(TokenResult::Some(self.located(start, statement::ReturnStatement {
+            token_return,
+            value,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_with_statement(&mut self) -> OptResult<()> {
+    fn parse_with_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
         try_value!(self.keyword("with"));
+        let token_with = self.token_data();
+        if self.flags.is_strict {
+            return Err(self.locate_error(start, format_err!("\"with\" is not allowed in strict mode code")));
+        }
 
         eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let token_paren_l = self.token_data();
         self.expect_expression();
-        eat_value!(self.with(Flag::In).parse_expression()?);
+        let object = eat_value!(self.with(Flag::In).parse_expression()?);
         eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+        let token_paren_r = self.token_data();
+
+        let body = eat_value!(self.parse_body_statement()?);
+
+        Ok(TokenResult::Some(self.located(start, statement::WithStatement {
+            token_with,
+            token_paren_l,
+            object: NodeBox::new(object),
+            token_paren_r,
+            body: NodeBox::new(body),
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    fn parse_labelled_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let flags = self.flags;
+        let is_identifier = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => is_binding_identifier(&flags, name),
+            _ => false,
+        };
+        if !is_identifier {
+            return Ok(TokenResult::None);
+        }
+
+        let is_label = matches!(self.ident_lookahead(), Some(&LookaheadResult {
+            token: tokens::Token::Punctuator(tokens::PunctuatorToken::Colon),
+            ..
+        }));
 
-        eat_value!(self.parse_statement()?);
+        if !is_label {
+            return Ok(TokenResult::None);
+        }
 
-        Ok(TokenResult::Some(()))
+        let start = self.start();
+        let label = eat_value!(self.parse_label_identifier());
+        if self.labels.iter().any(|l| l.name == label.value) {
+            return Err(self.locate_error(start, format_err!("Label \"{}\" has already been declared", label.value)));
+        }
+        eat_value!(self.punc(tokens::PunctuatorToken::Colon));
+        let token_colon = self.token_data();
+
+        // Labels directly on this one label the same statement.
+        let body_start = self.start().offset;
+        for outer in self.labels.iter_mut().filter(|l| l.body == start.offset) {
+            outer.body = body_start;
+        }
+        self.labels.push(Label {
+            name: label.value.clone(),
+            body: body_start,
+            is_loop: false,
+        });
+        let body = self.parse_labelled_body();
+        self.labels.pop();
+        let body = eat_value!(body?);
+
+        Ok(TokenResult::Some(self.located(start, statement::LabelledStatement {
+            tokens_prefix: Default::default(),
+            label,
+            token_colon,
+            body: NodeBox::new(body),
+            position: None,
+            comments: None,
+        }).into()))
     }
 
-    fn parse_labelled_statement(&mut self) -> OptResult<()> {
-        let is_label = if let Some(&LookaheadResult {
-            line: _line,
-            token: tokens::Token::Punctuator(tokens::PunctuatorToken::Colon),
-        }) = self.ident_lookahead() {
-            true
+    fn parse_labelled_body(&mut self) -> OptResult<alias::Statement<'code>> {
+        Ok(match self.parse_annex_b_function()? {
+            TokenResult::Some(decl) => TokenResult::Some(decl.into()),
+            TokenResult::None => self.parse_statement()?,
+        })
+    }
+
+    // Mark the labels on a loop starting at the given position, so that
+    // "continue" can name them.
+    fn label_loop(&mut self, start: tokenizer::Position) {
+        for label in self.labels.iter_mut().filter(|l| l.body == start.offset) {
+            label.is_loop = true;
+        }
+    }
+
+    fn parse_throw_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("throw"));
+        let token_throw = self.token_data();
+
+        self.expect_expression();
+        if !self.no_line_terminator() {
+            bail!("Unexpected line terminator after 'throw'");
+        }
+        let argument = eat_value!(self.with(Flag::In).parse_expression()?);
+
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, statement::ThrowStatement {
+            token_throw,
+            argument: NodeBox::new(argument),
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    fn parse_try_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("try"));
+        let token_try = self.token_data();
+
+        let body = eat_value!(self.parse_block_statement()?);
+
+        let catch_start = self.start();
+        let catch = if let TokenResult::Some(_) = self.keyword("catch") {
+            let token_catch = self.token_data();
+            let param_start = self.start();
+            let param = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::ParenOpen) {
+                let token_paren_l = self.token_data();
+                let argument = eat_value!(self.parse_binding_element_target()?);
+                let type_annotation = opt_value!(self.parse_type_annotation()?);
+
+                eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+                let token_paren_r = self.token_data();
+
+                Some(self.located(param_start, statement::CatchParam {
+                    token_paren_l,
+                    argument,
+                    type_annotation,
+                    token_paren_r,
+                    position: None,
+                    comments: None,
+                }))
+            } else {
+                self.require_version(EcmaVersion::Es2019, "Optional catch binding")?;
+                None
+            };
+
+            let body = eat_value!(self.parse_block_statement()?);
+
+            Some(self.located(catch_start, statement::CatchClause {
+                token_catch,
+                param,
+                body,
+                position: None,
+                comments: None,
+            }))
         } else {
-            false
+            None
         };
 
-        if is_label {
-            eat_value!(self.identifier());
-            eat_value!(self.punc(tokens::PunctuatorToken::Colon));
-            eat_value!(self.parse_statement()?);
-            Ok(TokenResult::Some(()))
+        let finalizer = if let TokenResult::Some(_) = self.keyword("finally") {
+            let token_finally = self.token_data();
+            Some((token_finally, eat_value!(self.parse_block_statement()?)))
         } else {
-            Ok(TokenResult::None)
-        }
+            None
+        };
+
+        let stmt = match (catch, finalizer) {
+            (Some(catch), Some((token_finally, finalizer))) => self.located(start, statement::TryCatchFinallyStatement {
+                token_try,
+                body,
+                catch,
+                token_finally,
+                finalizer,
+                position: None,
+                comments: None,
+            }).into(),
+            (Some(catch), None) => self.located(start, statement::TryCatchStatement {
+                token_try,
+                body,
+                catch,
+                position: None,
+                comments: None,
+            }).into(),
+            (None, Some((token_finally, finalizer))) => self.located(start, statement::TryFinallyStatement {
+                token_try,
+                body,
+                token_finally,
+                finalizer,
+                position: None,
+                comments: None,
+            }).into(),
+            (None, None) => bail!("Expected 'catch' or 'finally' after 'try' block"),
+        };
 
+        Ok(TokenResult::Some(stmt))
     }
 
-    fn parse_throw_statement(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("throw"));
+    fn parse_debugger_statement(&mut self) -> OptResult<alias::Statement<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("debugger"));
+        let token_debugger = self.token_data();
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, statement::DebuggerStatement {
+            token_debugger,
+            token_semi,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+}
 
-        if self.no_line_terminator() {
-            self.expect_expression();
-            eat_value!(self.with(Flag::In).parse_expression()?);
+// A non-empty comma-separated list, keeping each comma with the item before it.
+pub struct CommaList<T> {
+    pub items: Vec<(T, KeywordData)>,
+    pub last: T,
+}
+
+impl<T> CommaList<T> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter().map(|(item, _)| item).chain(Some(&self.last))
+    }
+
+    pub fn try_map<U, F: FnMut(T) -> Result<U>>(self, mut f: F) -> Result<CommaList<U>> {
+        let mut items = vec![];
+        for (item, comma) in self.items {
+            items.push((f(item)?, comma));
         }
 
-        Ok(TokenResult::Some(()))
+        Ok(CommaList {
+            items,
+            last: f(self.last)?,
+        })
     }
+}
 
-    fn parse_try_statement(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("try"));
+// Patterns in declarations must be initialized, outside of for-in/of loops.
+pub fn require_identifier(id: &patterns::BindingPattern) -> Result<()> {
+    match *id {
+        patterns::BindingPattern::Identifier(_) => Ok(()),
+        _ => bail!("Destructuring declarations require an initializer"),
+    }
+}
 
-        eat_value!(self.parse_block_statement()?);
+pub fn let_declarator(decl: statement::VariableDeclarator) -> Result<statement::LetDeclarator> {
+    let statement::VariableDeclarator { id, definite, type_annotation, init, position, .. } = decl;
 
-        if let TokenResult::Some(_) = self.keyword("catch") {
-            eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+    if init.is_none() {
+        require_identifier(&id)?;
+    }
 
-            if let TokenResult::None = self.parse_binding_pattern()? {
-                eat_value!(self.binding_identifier());
-            }
+    Ok(statement::LetDeclarator {
+        id,
+        definite,
+        type_annotation,
+        init,
+        position,
+        comments: None,
+    })
+}
 
-            eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+pub fn const_declarator(decl: statement::VariableDeclarator) -> Result<statement::ConstDeclarator> {
+    let statement::VariableDeclarator { id, type_annotation, init, position, .. } = decl;
+
+    let init = match init {
+        Some(init) => init,
+        None => bail!("Missing initializer in const declaration"),
+    };
+
+    Ok(statement::ConstDeclarator {
+        id,
+        type_annotation,
+        init,
+        position,
+        comments: None,
+    })
+}
 
-            eat_value!(self.parse_block_statement()?);
+// A directive prologue is the run of string-literal expression statements at
+// the start of a script, module or function body.
+pub fn is_directive(stmt: &statement::ExpressionStatement) -> bool {
+    matches!(stmt.expression, alias::Expression::String(_))
+}
+
+/// Whether the directive makes the code after it strict. Its text has to
+/// match exactly, without escapes.
+pub fn is_use_strict(directive: &functions::Directive) -> bool {
+    directive.value.value.as_str() == "use strict"
+}
+
+// Whether the source text of a string or template has an escape like "\1"
+// or "\01".
+pub fn has_legacy_octal_escape(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1) {
+            Some(&b'0') if bytes.get(i + 2).is_some_and(u8::is_ascii_digit) => return true,
+            Some(&(b'1'..=b'9')) => return true,
+            _ => i += 2,
         }
+    }
+    false
+}
 
-        if let TokenResult::Some(_) = self.keyword("finally") {
-            eat_value!(self.parse_block_statement()?);
+/// An item in the body of a script, module or function, which is a
+/// directive if it is a string at the start of the body.
+pub trait BodyItem<'code>: Sized {
+    fn into_directive(self) -> ::std::result::Result<functions::Directive<'code>, Self>;
+}
+impl<'code> BodyItem<'code> for alias::StatementItem<'code> {
+    fn into_directive(self) -> ::std::result::Result<functions::Directive<'code>, Self> {
+        match self {
+            alias::StatementItem::Expression(stmt) if is_directive(&stmt) => Ok(into_directive(NodeBox::into_inner(stmt))),
+            item => Err(item),
         }
+    }
+}
+impl<'code> BodyItem<'code> for alias::ModuleStatementItem<'code> {
+    fn into_directive(self) -> ::std::result::Result<functions::Directive<'code>, Self> {
+        match self {
+            alias::ModuleStatementItem::Expression(stmt) if is_directive(&stmt) => Ok(into_directive(NodeBox::into_inner(stmt))),
+            item => Err(item),
+        }
+    }
+}
 
-        Ok(TokenResult::Some(()))
+pub fn into_directive(stmt: statement::ExpressionStatement) -> functions::Directive {
+    let statement::ExpressionStatement { expression, token_semi, position, .. } = stmt;
+    let value = match expression {
+        alias::Expression::String(s) => NodeBox::into_inner(s),
+        _ => unreachable!("directives are string literals"),
+    };
+
+    functions::Directive {
+        value: functions::DirectiveLiteral {
+            tokens_prefix: Default::default(),
+            value: value.value,
+            position: value.position,
+            comments: None,
+        },
+        token_semi,
+        position,
+        comments: None,
     }
+}
 
-    fn parse_debugger_statement(&mut self) -> OptResult<()> {
-        try_value!(self.keyword("debugger"));
-        eat_value!(self.semicolon());
+fn is_left_hand_expression(expr: &alias::Expression) -> bool {
+    matches!(
+        *expr,
+        alias::Expression::Binding(_) |
+        alias::Expression::Member(_) |
+        alias::Expression::Parenthesized(_) |
+        alias::Expression::Object(_) |
+        alias::Expression::Array(_)
+    )
+}
 
-        Ok(TokenResult::Some(()))
+// Whether the statement is a label on a function declaration, possibly
+// inside of other labels, like "a: b: function c() {}".
+fn is_labelled_function(stmt: &alias::Statement) -> bool {
+    match *stmt {
+        alias::Statement::Labelled(ref stmt) => {
+            matches!(*stmt.body, alias::Statement::Function(_)) || is_labelled_function(&stmt.body)
+        }
+        _ => false,
     }
 }
+
+fn single_declarator(declarators: CommaList<statement::VariableDeclarator>) -> statement::VariableDeclarator {
+    debug_assert!(declarators.items.is_empty());
+    declarators.last
+}
+
+// for (var a in b), for (let [a] in b)
+fn for_in_init(decl: ForDeclaration, annex_b: bool) -> Result<statement::ForInInit> {
+    Ok(match decl {
+        ForDeclaration::Var(token_var, declarators) => {
+            let statement::VariableDeclarator { id, init, position, .. } = single_declarator(declarators);
+
+            if init.is_some() {
+                // Annex B allows an initializer, but only for plain identifiers.
+                if !annex_b {
+                    bail!("for-in loop variable declaration may not have an initializer");
+                }
+                require_identifier(&id)?;
+            }
+
+            statement::ForInVarPattern {
+                token_var,
+                pattern: id,
+                init,
+                position,
+                comments: None,
+            }.into()
+        }
+        ForDeclaration::Let(token_let, declarators) => {
+            let statement::VariableDeclarator { id, init, position, .. } = single_declarator(declarators);
+            if init.is_some() {
+                bail!("for-in loop variable declaration may not have an initializer");
+            }
+
+            statement::ForLetPattern {
+                token_let,
+                pattern: id,
+                position,
+                comments: None,
+            }.into()
+        }
+        ForDeclaration::Const(token_const, declarators) => {
+            let statement::VariableDeclarator { id, init, position, .. } = single_declarator(declarators);
+            if init.is_some() {
+                bail!("for-in loop variable declaration may not have an initializer");
+            }
+
+            statement::ForConstPattern {
+                token_const,
+                pattern: id,
+                position,
+                comments: None,
+            }.into()
+        }
+    })
+}
+
+// for (var a of b), for (let [a] of b)
+fn for_of_init(decl: ForDeclaration) -> Result<statement::ForOfInit> {
+    Ok(match decl {
+        ForDeclaration::Var(token_var, declarators) => {
+            let statement::VariableDeclarator { id, init, position, .. } = single_declarator(declarators);
+            if init.is_some() {
+                bail!("for-of loop variable declaration may not have an initializer");
+            }
+
+            statement::ForVarPattern {
+                token_var,
+                pattern: id,
+                position,
+                comments: None,
+            }.into()
+        }
+        ForDeclaration::Let(token_let, declarators) => {
+            let statement::VariableDeclarator { id, init, position, .. } = single_declarator(declarators);
+            if init.is_some() {
+                bail!("for-of loop variable declaration may not have an initializer");
+            }
+
+            statement::ForLetPattern {
+                token_let,
+                pattern: id,
+                position,
+                comments: None,
+            }.into()
+        }
+        ForDeclaration::Const(token_const, declarators) => {
+            let statement::VariableDeclarator { id, init, position, .. } = single_declarator(declarators);
+            if init.is_some() {
+                bail!("for-of loop variable declaration may not have an initializer");
+            }
+
+            statement::ForConstPattern {
+                token_const,
+                pattern: id,
+                position,
+                comments: None,
+            }.into()
+        }
+    })
+}
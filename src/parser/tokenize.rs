@@ -0,0 +1,185 @@
+use std::vec;
+
+use failure::Error;
+
+use tokenizer::{IntoTokenizer, TokenRange};
+use tokenizer::tokens::Token;
+use parser::{try_source_types, Parser, ParserOptions};
+
+/// An iterator over every token in a piece of source code, in order, including
+/// whitespace, line terminators and comments.
+#[derive(Debug)]
+pub struct Tokens<'code> {
+    it: vec::IntoIter<(Token<'code>, TokenRange)>,
+}
+impl<'code> Iterator for Tokens<'code> {
+    type Item = (Token<'code>, TokenRange);
+
+    fn next(&mut self) -> Option<(Token<'code>, TokenRange)> {
+        self.it.next()
+    }
+}
+
+/// Split the given code into tokens.
+///
+/// Whether a "/" starts a regular expression and whether a "}" continues a
+/// template literal depends on the surrounding grammar, so the code is run
+/// through the parser and the tokens are collected as the parser reads them.
+/// The trailing EOF token is not included.
+///
+/// If the code fails to parse, the tokens before the error are returned
+/// along with it.
+pub fn tokenize<'code>(code: &'code str, options: &ParserOptions) -> (Tokens<'code>, Option<Error>) {
+    // The tokens read by each goal that failed, by where it failed.
+    let mut failed = vec![];
+    let result = try_source_types(options, |options| {
+        let mut p = Parser::with_options(code.into_tokenizer(), options);
+        p.record_tokens();
+
+        let result = p.parse_source();
+        let tokens: Vec<_> = p.take_recorded_tokens().into_iter().map(|(token, range, _)| (token, range)).collect();
+        match result {
+            Ok(_) => Ok(tokens),
+            Err(error) => {
+                let diagnostic = p.diagnostic(&error);
+                failed.push((diagnostic.range.start.offset, tokens));
+                Err(diagnostic)
+            }
+        }
+    });
+
+    let (mut tokens, error) = match result {
+        Ok(tokens) => (tokens, None),
+        Err(diagnostic) => {
+            let offset = diagnostic.range.start.offset;
+            let mut tokens = failed.into_iter().find(|&(o, _)| o == offset).map(|(_, tokens)| tokens).unwrap_or_default();
+            tokens.retain(|token| token.1.start.offset < offset);
+            (tokens, Some(diagnostic.into()))
+        }
+    };
+    if let Some(&(Token::EOF(_), _)) = tokens.last() {
+        tokens.pop();
+    }
+
+    (Tokens {
+        it: tokens.into_iter(),
+    }, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::SourceType;
+    use tokenizer::tokens;
+
+    fn kinds(code: &str) -> Vec<Token<'_>> {
+        let (tokens, error) = tokenize(code, &Default::default());
+        assert!(error.is_none());
+        tokens.map(|(t, _)| t)
+            .collect()
+    }
+
+    #[test]
+    fn it_includes_trivia() {
+        assert_eq!(
+            kinds("a; // b\n"),
+            vec![
+                tokens::IdentifierNameToken { name: "a".into() }.into(),
+                tokens::PunctuatorToken::Semicolon.into(),
+                tokens::WhitespaceToken {}.into(),
+                tokens::CommentToken {
+                    format: tokens::CommentFormat::Line,
+                    value: " b".into(),
+                }.into(),
+                tokens::LineTerminatorToken {}.into(),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_tracks_ranges() {
+        let ranges: Vec<_> = tokenize("a =\n  b;", &Default::default())
+            .0
+            .map(|(_, r)| (r.start.offset, r.end.offset, r.start.line, r.start.column))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![(0, 1, 1, 0), (1, 2, 1, 1), (2, 3, 1, 2), (3, 4, 1, 3), (4, 6, 2, 0), (6, 7, 2, 2), (7, 8, 2, 3)],
+        );
+    }
+
+    #[test]
+    fn it_distinguishes_regex_from_division() {
+        assert_eq!(
+            kinds("a/b/c;"),
+            vec![
+                tokens::IdentifierNameToken { name: "a".into() }.into(),
+                tokens::PunctuatorToken::Slash.into(),
+                tokens::IdentifierNameToken { name: "b".into() }.into(),
+                tokens::PunctuatorToken::Slash.into(),
+                tokens::IdentifierNameToken { name: "c".into() }.into(),
+                tokens::PunctuatorToken::Semicolon.into(),
+            ],
+        );
+        assert_eq!(
+            kinds("a=/b/c;"),
+            vec![
+                tokens::IdentifierNameToken { name: "a".into() }.into(),
+                tokens::PunctuatorToken::Eq.into(),
+                tokens::RegularExpressionLiteralToken {
+                    pattern: "b".into(),
+                    flags: "c".into(),
+                }.into(),
+                tokens::PunctuatorToken::Semicolon.into(),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_resolves_template_continuations() {
+        assert_eq!(
+            kinds("`a${b}c`;"),
+            vec![
+                tokens::TemplateToken {
+                    format: tokens::TemplateFormat::Head,
+                    cooked: "a".into(),
+                    raw: "a".into(),
+                }.into(),
+                tokens::IdentifierNameToken { name: "b".into() }.into(),
+                tokens::TemplateToken {
+                    format: tokens::TemplateFormat::Tail,
+                    cooked: "c".into(),
+                    raw: "c".into(),
+                }.into(),
+                tokens::PunctuatorToken::Semicolon.into(),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_tokenizes_modules() {
+        let (tokens, error) = tokenize("export default 4;", &ParserOptions::module());
+        assert!(error.is_none());
+        assert_eq!(tokens.count(), 6);
+    }
+
+    #[test]
+    fn it_returns_the_tokens_before_an_error() {
+        let (tokens, error) = tokenize("a = 1;\nb c;", &Default::default());
+        assert!(error.is_some());
+        let tokens: Vec<_> = tokens.collect();
+        assert_eq!(tokens.len(), 9);
+        assert_eq!(tokens[7].0, tokens::IdentifierNameToken { name: "b".into() }.into());
+        assert_eq!(tokens[8].1.end.offset, "a = 1;\nb ".len());
+
+        // Unambiguous code keeps the tokens of the goal that got further.
+        let options = ParserOptions {
+            source_type: SourceType::Unambiguous,
+            ..Default::default()
+        };
+        let (tokens, error) = tokenize("import a from 'a';\nb c;", &options);
+        assert!(error.is_some());
+        assert_eq!(tokens.count(), 11);
+    }
+}
@@ -0,0 +1,1878 @@
+use std::mem;
+
+use ast::alias;
+use ast::functions;
+use ast::literal;
+use ast::typescript;
+use ast::NodeBox;
+use tokenizer::{self, Tokenizer, tokens};
+use parser::{Parser, Flag, LookaheadResult};
+use parser::utils::{OptResult, Result, TokenResult};
+
+impl<'code, T> Parser<'code, T>
+where
+    T: Tokenizer<'code>
+{
+    /// Whether type annotations are parsed, which TypeScript and Flow share.
+    pub fn has_types(&self) -> bool {
+        self.options.extensions.typescript || self.options.extensions.flow
+    }
+
+    // : string
+    pub fn parse_type_annotation(&mut self) -> OptResult<typescript::TypeAnnotation<'code>> {
+        if !self.has_types() {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::Colon));
+        let token_colon = self.token_data();
+        let value = eat_value!(self.parse_type()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeAnnotation {
+            token_colon,
+            value: NodeBox::new(value),
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // The "!" of "let a!: number", which asserts that the binding is assigned
+    // before it is read.
+    pub fn parse_definite(&mut self) -> Result<bool> {
+        if !self.options.extensions.typescript || !self.no_line_terminator() {
+            return Ok(false);
+        }
+        if let TokenResult::None = self.punc(tokens::PunctuatorToken::Exclam) {
+            return Ok(false);
+        }
+        if !matches!(*self.token(), tokens::Token::Punctuator(tokens::PunctuatorToken::Colon)) {
+            bail!("Declarations with definite assignment assertions must also have type annotations");
+        }
+        Ok(true)
+    }
+
+    // : string, x is string, or asserts x
+    pub fn parse_return_type(&mut self) -> OptResult<typescript::TypeAnnotation<'code>> {
+        if !self.has_types() {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::Colon));
+        let token_colon = self.token_data();
+        let value = eat_value!(self.parse_return_type_value()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeAnnotation {
+            token_colon,
+            value: NodeBox::new(value),
+            position: None,
+            comments: None,
+        })))
+    }
+
+    fn parse_return_type_value(&mut self) -> OptResult<typescript::Type<'code>> {
+        let next = match self.ident_lookahead() {
+            Some(&LookaheadResult {
+                line: false,
+                token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+                ..
+            }) => Some(name.to_string()),
+            _ => None,
+        };
+
+        // "asserts is T" is a predicate for a parameter named "asserts".
+        let is_assertion = self.options.extensions.typescript &&
+            matches!(*self.token(), tokens::Token::IdentifierName(ref id) if id.name == "asserts") &&
+            next.as_ref().is_some_and(|name| name != "is");
+        let is_predicate = next.as_ref().is_some_and(|name| name == "is");
+        if !is_assertion && !is_predicate {
+            return self.parse_type();
+        }
+
+        let start = self.start();
+        let token_asserts = if is_assertion {
+            self.pop();
+            Some(self.token_data())
+        } else {
+            None
+        };
+        let name = eat_value!(self.parse_type_identifier());
+
+        // Only assertions can leave out the type, like "asserts x".
+        let value = if let TokenResult::Some(_) = self.keyword("is") {
+            let token_is = self.token_data();
+            Some((token_is, NodeBox::new(eat_value!(self.parse_type()?))))
+        } else {
+            None
+        };
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypePredicate {
+            token_asserts,
+            name,
+            value,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    /// Function parameters, along with TypeScript's type parameters before
+    /// them and optionally its return type after them.
+    pub fn parse_typed_function_params(&mut self, return_type: bool) -> OptResult<functions::FunctionParams<'code>> {
+        let start = self.start();
+        let type_parameters = opt_value!(self.parse_type_parameters()?);
+        let mut params = if type_parameters.is_some() {
+            eat_value!(self.parse_function_params()?)
+        } else {
+            try_value!(self.parse_function_params()?)
+        };
+
+        params.type_parameters = type_parameters.map(NodeBox::new);
+        if return_type {
+            params.predicate = opt_value!(self.parse_predicate(true)?).map(NodeBox::new);
+            if params.predicate.is_none() {
+                params.return_type = opt_value!(self.parse_return_type()?).map(NodeBox::new);
+                params.predicate = opt_value!(self.parse_predicate(false)?).map(NodeBox::new);
+            }
+        }
+
+        Ok(TokenResult::Some(self.located(start, params)))
+    }
+
+    // The modifiers of constructor parameters, like "private x".
+    pub fn parse_parameter_modifiers(&mut self) -> Vec<typescript::Modifier<'code>> {
+        let mut modifiers = vec![];
+        if !self.options.extensions.typescript {
+            return modifiers;
+        }
+
+        while let Some(kind) = self.modifier_kind() {
+            let start = self.start();
+            self.pop();
+            modifiers.push(self.located(start, typescript::Modifier::from(kind)));
+        }
+        modifiers
+    }
+
+    // Words like "readonly" are only modifiers if they are followed by the
+    // name of whatever they modify.
+    pub fn modifier_kind(&mut self) -> Option<typescript::ModifierKind> {
+        if !self.options.extensions.typescript {
+            return None;
+        }
+
+        let kind = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => {
+                typescript::ModifierKind::from_name(name)
+            }
+            _ => None,
+        };
+        let kind = kind?;
+
+        match self.ident_lookahead() {
+            Some(&LookaheadResult { token: tokens::Token::IdentifierName(_), .. }) |
+            Some(&LookaheadResult { token: tokens::Token::StringLiteral(_), .. }) |
+            Some(&LookaheadResult { token: tokens::Token::NumericLiteral(_), .. }) |
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen), .. }) |
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(tokens::PunctuatorToken::CurlyOpen), .. }) |
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(tokens::PunctuatorToken::Star), .. }) => Some(kind),
+            _ => None,
+        }
+    }
+
+    pub fn parse_type(&mut self) -> OptResult<typescript::Type<'code>> {
+        self.nested(Self::parse_type_nested)
+    }
+
+    fn parse_type_nested(&mut self) -> OptResult<typescript::Type<'code>> {
+        if let TokenResult::Some(function) = self.parse_function_type()? {
+            return Ok(TokenResult::Some(function.into()));
+        }
+
+        let start = self.start();
+        let check = try_value!(self.parse_union_type()?);
+        if !self.options.extensions.typescript || !self.no_line_terminator() {
+            return Ok(TokenResult::Some(check));
+        }
+        if let TokenResult::None = self.keyword("extends") {
+            return Ok(TokenResult::Some(check));
+        }
+
+        // A extends B ? C : D
+        let token_extends = self.token_data();
+        let extends = if let TokenResult::Some(function) = self.parse_function_type()? {
+            function.into()
+        } else if let TokenResult::Some(infer) = self.speculate(|p| {
+            // The whole extends type can be "infer U extends B", which keeps
+            // its constraint even though "?" follows it.
+            let infer = try_value!(p.parse_infer_type(true)?);
+            match *p.token() {
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Question) => Ok(TokenResult::Some(infer)),
+                _ => Ok(TokenResult::None),
+            }
+        }) {
+            infer.into()
+        } else {
+            eat_value!(self.parse_union_type()?)
+        };
+        eat_value!(self.punc(tokens::PunctuatorToken::Question));
+        let token_question = self.token_data();
+        let true_type = eat_value!(self.parse_type()?);
+        eat_value!(self.punc(tokens::PunctuatorToken::Colon));
+        let token_colon = self.token_data();
+        let false_type = eat_value!(self.parse_type()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::ConditionalType {
+            check: NodeBox::new(check),
+            token_extends,
+            extends: NodeBox::new(extends),
+            token_question,
+            true_type: NodeBox::new(true_type),
+            token_colon,
+            false_type: NodeBox::new(false_type),
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    // (a: A) => B, new (a: A) => B, or abstract new (a: A) => B
+    fn parse_function_type(&mut self) -> OptResult<typescript::FunctionType<'code>> {
+        let start = self.start();
+        let is_abstract = self.options.extensions.typescript &&
+            matches!(*self.token(), tokens::Token::IdentifierName(ref id) if id.name == "abstract") &&
+            matches!(
+                self.ident_lookahead(),
+                Some(&LookaheadResult { line: false, token: tokens::Token::IdentifierName(ref id), .. }) if id.name == "new"
+            );
+        let token_abstract = if is_abstract {
+            self.pop();
+            Some(self.token_data())
+        } else {
+            None
+        };
+
+        let token_new = if self.is_word_before("new", &[tokens::PunctuatorToken::ParenOpen, tokens::PunctuatorToken::LAngle]) {
+            self.pop();
+            Some(self.token_data())
+        } else if token_abstract.is_some() {
+            bail!("Expected a constructor type");
+        } else {
+            match *self.token() {
+                tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) |
+                tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) => None,
+                _ => return Ok(TokenResult::None),
+            }
+        };
+
+        // "(A)" is a parenthesized type unless an arrow follows it.
+        let params = self.speculate(|p| {
+            let params = try_value!(p.parse_typed_function_params(false)?);
+            try_value!(p.punc(tokens::PunctuatorToken::Arrow));
+            Ok(TokenResult::Some(params))
+        });
+        let params = match params {
+            TokenResult::Some(params) => params,
+            TokenResult::None if token_new.is_some() => bail!("Expected a constructor type"),
+            TokenResult::None => return Ok(TokenResult::None),
+        };
+        let token_arrow = self.token_data();
+        let value = eat_value!(self.parse_return_type_value()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::FunctionType {
+            token_abstract,
+            token_new,
+            params: NodeBox::new(params),
+            token_arrow,
+            value: NodeBox::new(value),
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // A | B
+    fn parse_union_type(&mut self) -> OptResult<typescript::Type<'code>> {
+        let start = self.start();
+        let first = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Bar) {
+            eat_value!(self.parse_intersection_type()?)
+        } else {
+            try_value!(self.parse_intersection_type()?)
+        };
+
+        let mut types = vec![first];
+        while let TokenResult::Some(value) = self.parse_union_member()? {
+            types.push(value);
+        }
+        if types.len() == 1 {
+            return Ok(TokenResult::Some(types.remove(0)));
+        }
+
+        Ok(TokenResult::Some(self.located(start, typescript::UnionType {
+            types,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    // The "| B" of "A | B". In Flow, the "|" could instead be the start of
+    // the "|}" closing an exact object type, like "{| a: A |}".
+    fn parse_union_member(&mut self) -> OptResult<typescript::Type<'code>> {
+        if self.options.extensions.flow {
+            return Ok(self.speculate(|p| {
+                try_value!(p.punc(tokens::PunctuatorToken::Bar));
+                p.parse_intersection_type()
+            }));
+        }
+
+        try_value!(self.punc(tokens::PunctuatorToken::Bar));
+        Ok(TokenResult::Some(eat_value!(self.parse_intersection_type()?)))
+    }
+
+    // A & B
+    fn parse_intersection_type(&mut self) -> OptResult<typescript::Type<'code>> {
+        let start = self.start();
+        let first = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Amp) {
+            eat_value!(self.parse_type_operator()?)
+        } else {
+            try_value!(self.parse_type_operator()?)
+        };
+
+        let mut types = vec![first];
+        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Amp) {
+            types.push(eat_value!(self.parse_type_operator()?));
+        }
+        if types.len() == 1 {
+            return Ok(TokenResult::Some(types.remove(0)));
+        }
+
+        Ok(TokenResult::Some(self.located(start, typescript::IntersectionType {
+            types,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    // keyof T
+    pub fn parse_type_operator(&mut self) -> OptResult<typescript::Type<'code>> {
+        if let TokenResult::Some(maybe) = self.parse_maybe_type()? {
+            return Ok(TokenResult::Some(maybe.into()));
+        }
+        if let TokenResult::Some(infer) = self.parse_infer_type(false)? {
+            return Ok(TokenResult::Some(infer.into()));
+        }
+
+        let kind = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => match &**name {
+                "keyof" => Some(typescript::TypeOperatorKind::Keyof),
+                "unique" => Some(typescript::TypeOperatorKind::Unique),
+                "readonly" => Some(typescript::TypeOperatorKind::Readonly),
+                _ => None,
+            },
+            _ => None,
+        };
+        let kind = match kind {
+            Some(kind) if self.starts_type_after_word() => kind,
+            _ => return self.parse_postfix_type(),
+        };
+
+        let start = self.start();
+        self.pop();
+        let value = eat_value!(self.nested(Self::parse_type_operator)?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeOperator {
+            kind,
+            value: NodeBox::new(value),
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    // infer U, or infer U extends string
+    fn parse_infer_type(&mut self, extends_type: bool) -> OptResult<typescript::InferType<'code>> {
+        let is_infer = self.options.extensions.typescript &&
+            matches!(*self.token(), tokens::Token::IdentifierName(ref id) if id.name == "infer") &&
+            matches!(self.ident_lookahead(), Some(&LookaheadResult { token: tokens::Token::IdentifierName(_), .. }));
+        if !is_infer {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        self.pop();
+        let token_infer = self.token_data();
+        let param_start = self.start();
+        let id = eat_value!(self.parse_type_identifier());
+
+        // In "[infer U extends B ? C : D]", the "extends B" belongs to a
+        // conditional type instead, unless the infer type is the whole
+        // extends type of another conditional type.
+        let constraint = self.speculate(|p| {
+            try_value!(p.keyword("extends"));
+            let constraint = eat_value!(p.parse_union_type()?);
+            match *p.token() {
+                tokens::Token::Punctuator(tokens::PunctuatorToken::Question) if !extends_type => Ok(TokenResult::None),
+                _ => Ok(TokenResult::Some(constraint)),
+            }
+        });
+        let param = self.located(param_start, typescript::TypeParameter {
+            modifiers: vec![],
+            id,
+            bound: None,
+            constraint: match constraint {
+                TokenResult::Some(constraint) => Some(NodeBox::new(constraint)),
+                TokenResult::None => None,
+            },
+            default: None,
+            position: None,
+            comments: None,
+        });
+
+        Ok(TokenResult::Some(self.located(start, typescript::InferType {
+            token_infer,
+            param,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // Whether the word at the current token is followed by a type, like the
+    // "string" of "readonly string[]".
+    fn starts_type_after_word(&mut self) -> bool {
+        match self.ident_lookahead() {
+            Some(&LookaheadResult { token: tokens::Token::IdentifierName(_), .. }) |
+            Some(&LookaheadResult { token: tokens::Token::StringLiteral(_), .. }) |
+            Some(&LookaheadResult { token: tokens::Token::NumericLiteral(_), .. }) => true,
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(ref punc), .. }) => matches!(
+                *punc,
+                tokens::PunctuatorToken::ParenOpen |
+                tokens::PunctuatorToken::SquareOpen |
+                tokens::PunctuatorToken::CurlyOpen
+            ),
+            _ => false,
+        }
+    }
+
+    // T[], T["a"]
+    fn parse_postfix_type(&mut self) -> OptResult<typescript::Type<'code>> {
+        let start = self.start();
+        let mut value = try_value!(self.parse_primary_type()?);
+
+        while self.no_line_terminator() {
+            if let TokenResult::None = self.punc(tokens::PunctuatorToken::SquareOpen) {
+                break;
+            }
+
+            value = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::SquareClose) {
+                self.located(start, typescript::ArrayType {
+                    element: NodeBox::new(value),
+                    position: None,
+                    comments: None,
+                }).into()
+            } else {
+                let index = eat_value!(self.parse_type()?);
+                eat_value!(self.punc(tokens::PunctuatorToken::SquareClose));
+
+                self.located(start, typescript::IndexedAccessType {
+                    object: NodeBox::new(value),
+                    index: NodeBox::new(index),
+                    position: None,
+                    comments: None,
+                }).into()
+            };
+        }
+
+        Ok(TokenResult::Some(value))
+    }
+
+    fn parse_primary_type(&mut self) -> OptResult<typescript::Type<'code>> {
+        enum PrimaryType {
+            Paren,
+            Object,
+            Tuple,
+            String,
+            Number,
+            Negative,
+            Query,
+            Import,
+            Boolean(bool),
+            Keyword(typescript::TypeKeyword),
+            Reference,
+            Template,
+        }
+
+        let typescript = self.options.extensions.typescript;
+        let t = match *self.token() {
+            tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) => PrimaryType::Paren,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::CurlyOpen) => PrimaryType::Object,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen) => PrimaryType::Tuple,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::Minus) => PrimaryType::Negative,
+            tokens::Token::StringLiteral(_) => PrimaryType::String,
+            tokens::Token::NumericLiteral(_) => PrimaryType::Number,
+            tokens::Token::Template(_) if typescript => PrimaryType::Template,
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => match &**name {
+                "typeof" => PrimaryType::Query,
+                "import" if typescript => PrimaryType::Import,
+                "true" => PrimaryType::Boolean(true),
+                "false" => PrimaryType::Boolean(false),
+                name => match typescript::TypeKeyword::from_name(name) {
+                    Some(kind) => PrimaryType::Keyword(kind),
+                    None => PrimaryType::Reference,
+                },
+            },
+            _ => return Ok(TokenResult::None),
+        };
+
+        // Keywords like "string" are still names of namespaces in "string.A".
+        let t = match t {
+            PrimaryType::Keyword(_) if self.is_qualified_name() => PrimaryType::Reference,
+            t => t,
+        };
+
+        let start = self.start();
+        let value = match t {
+            PrimaryType::Paren => {
+                eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+                let value = eat_value!(self.parse_type()?);
+                eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+
+                self.located(start, typescript::ParenthesizedType {
+                    value: NodeBox::new(value),
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            PrimaryType::Object => {
+                if let TokenResult::Some(mapped) = self.parse_mapped_type()? {
+                    mapped.into()
+                } else if let TokenResult::Some(object) = self.parse_exact_object_type()? {
+                    object.into()
+                } else {
+                    eat_value!(self.parse_object_type()?).into()
+                }
+            }
+            PrimaryType::Tuple => eat_value!(self.parse_tuple_type()?).into(),
+            PrimaryType::String => {
+                let value = eat_value!(self.parse_string_literal());
+                typescript::LiteralType::from(value).into()
+            }
+            PrimaryType::Number => {
+                let tok = eat_value!(self.numeric());
+                let value = self.located(start, literal::Numeric::from(tok.value));
+                typescript::LiteralType::from(value).into()
+            }
+            PrimaryType::Negative => {
+                eat_value!(self.punc(tokens::PunctuatorToken::Minus));
+                let token_minus = self.token_data();
+                let number_start = self.start();
+                let tok = eat_value!(self.numeric());
+                let value = self.located(number_start, literal::Numeric::from(tok.value));
+
+                typescript::LiteralType::from(self.located(start, typescript::NegativeLiteralType {
+                    token_minus,
+                    value,
+                    position: None,
+                    comments: None,
+                })).into()
+            }
+            PrimaryType::Boolean(value) => {
+                self.pop();
+                typescript::LiteralType::from(self.located(start, literal::Boolean::from(value))).into()
+            }
+            PrimaryType::Query => {
+                eat_value!(self.keyword("typeof"));
+                let token_typeof = self.token_data();
+                let name = match self.parse_import_type()? {
+                    TokenResult::Some(import) => import.into(),
+                    TokenResult::None => eat_value!(self.parse_type_name()).into(),
+                };
+
+                self.located(start, typescript::TypeQuery {
+                    token_typeof,
+                    name,
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            PrimaryType::Keyword(kind) => {
+                self.pop();
+                self.located(start, typescript::KeywordType::from(kind)).into()
+            }
+            PrimaryType::Import => eat_value!(self.parse_import_type()?).into(),
+            PrimaryType::Reference => eat_value!(self.parse_type_reference()?).into(),
+            PrimaryType::Template => eat_value!(self.parse_template_literal_type()?).into(),
+        };
+
+        Ok(TokenResult::Some(value))
+    }
+
+    // `a${B}c`
+    fn parse_template_literal_type(&mut self) -> OptResult<typescript::TemplateLiteralType<'code>> {
+        let start = self.start();
+        let tok = try_value!(self.template());
+
+        let mut parts = vec![];
+        let first = self.template_part(tok.clone());
+        let mut last_part = self.located(start, first);
+
+        if tok.format == tokens::TemplateFormat::Head {
+            let mut parser = self.with(Flag::Template);
+            loop {
+                let value = eat_value!(parser.parse_type()?);
+
+                let part_start = parser.start();
+                let next = eat_value!(parser.template_tail());
+                let is_tail = next.format == tokens::TemplateFormat::Tail;
+
+                let next_part = parser.template_part(next);
+                let part = mem::replace(&mut last_part, parser.located(part_start, next_part));
+                parts.push((part, Default::default(), value, Default::default()));
+
+                if is_tail {
+                    break;
+                }
+            }
+        }
+
+        Ok(TokenResult::Some(self.located(start, typescript::TemplateLiteralType {
+            parts,
+            last_part,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // { readonly [K in keyof T]?: T[K] }
+    fn parse_mapped_type(&mut self) -> OptResult<typescript::MappedType<'code>> {
+        if !self.options.extensions.typescript {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        let mut parser = self.without(Flag::Template);
+
+        // "{ [a" is an index signature or a computed property name unless
+        // "in" follows the name.
+        let (readonly, key) = try_value!(parser.speculate(|p| {
+            try_value!(p.punc(tokens::PunctuatorToken::CurlyOpen));
+            let readonly = p.parse_mapped_type_modifier(true)?;
+            try_value!(p.punc(tokens::PunctuatorToken::SquareOpen));
+            let key = try_value!(p.parse_type_identifier());
+            try_value!(p.keyword("in"));
+            Ok(TokenResult::Some((readonly, key)))
+        }));
+        let constraint = eat_value!(parser.parse_type()?);
+        let name_type = if let TokenResult::Some(_) = parser.keyword("as") {
+            Some(NodeBox::new(eat_value!(parser.parse_type()?)))
+        } else {
+            None
+        };
+        eat_value!(parser.punc(tokens::PunctuatorToken::SquareClose));
+        let optional = parser.parse_mapped_type_modifier(false)?;
+        let type_annotation = opt_value!(parser.parse_type_annotation()?);
+        if let TokenResult::None = parser.punc(tokens::PunctuatorToken::Semicolon) {
+            opt_value!(parser.punc(tokens::PunctuatorToken::Comma));
+        }
+        eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+        Ok(TokenResult::Some(parser.located(start, typescript::MappedType {
+            readonly,
+            key,
+            constraint: NodeBox::new(constraint),
+            name_type,
+            optional,
+            type_annotation,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // The "readonly" or "?" of a mapped type, which a "+" or "-" before it
+    // adds or removes.
+    fn parse_mapped_type_modifier(&mut self, readonly: bool) -> Result<Option<typescript::MappedTypeModifier>> {
+        let modifier = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Plus) {
+            typescript::MappedTypeModifier::Plus
+        } else if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Minus) {
+            typescript::MappedTypeModifier::Minus
+        } else {
+            typescript::MappedTypeModifier::Plain
+        };
+
+        let found = if readonly {
+            matches!(self.keyword("readonly"), TokenResult::Some(_))
+        } else {
+            matches!(self.punc(tokens::PunctuatorToken::Question), TokenResult::Some(_))
+        };
+        match (found, modifier) {
+            (true, modifier) => Ok(Some(modifier)),
+            (false, typescript::MappedTypeModifier::Plain) => Ok(None),
+            (false, _) if readonly => bail!("Expected \"readonly\" after \"+\" or \"-\""),
+            (false, _) => bail!("Expected \"?\" after \"+\" or \"-\""),
+        }
+    }
+
+    fn is_qualified_name(&mut self) -> bool {
+        matches!(
+            self.ident_lookahead(),
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(tokens::PunctuatorToken::Period), .. })
+        )
+    }
+
+    // Foo<T>
+    pub fn parse_type_reference(&mut self) -> OptResult<typescript::TypeReference<'code>> {
+        let start = self.start();
+        let name = try_value!(self.parse_type_name());
+        let type_arguments = if self.no_line_terminator() {
+            opt_value!(self.parse_type_arguments()?)
+        } else {
+            None
+        };
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeReference {
+            name,
+            type_arguments,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // import("a").B<C>
+    fn parse_import_type(&mut self) -> OptResult<typescript::ImportType<'code>> {
+        if !self.options.extensions.typescript {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        try_value!(self.keyword("import"));
+        let token_import = self.token_data();
+        eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+        let source = eat_value!(self.parse_string_literal());
+        eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+
+        let qualifier = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Period) {
+            Some(eat_value!(self.parse_type_name()))
+        } else {
+            None
+        };
+        let type_arguments = if self.no_line_terminator() {
+            opt_value!(self.parse_type_arguments()?)
+        } else {
+            None
+        };
+
+        Ok(TokenResult::Some(self.located(start, typescript::ImportType {
+            token_import,
+            source,
+            qualifier,
+            type_arguments,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // a.b.C
+    fn parse_type_name(&mut self) -> TokenResult<typescript::TypeName<'code>> {
+        let start = self.start();
+        let mut name: typescript::TypeName = try_token!(self.parse_type_identifier()).into();
+
+        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Period) {
+            let token_period = self.token_data();
+            let right = try_token!(self.parse_property_identifier());
+
+            name = self.located(start, typescript::QualifiedTypeName {
+                left: NodeBox::new(name),
+                token_period,
+                right,
+                position: None,
+                comments: None,
+            }).into();
+        }
+
+        TokenResult::Some(name)
+    }
+
+    pub fn parse_type_identifier(&mut self) -> TokenResult<typescript::TypeIdentifier<'code>> {
+        let start = self.start();
+        let ident = try_token!(self.identifier());
+        let name = self.text(ident.name);
+
+        TokenResult::Some(self.located(start, typescript::TypeIdentifier::new(name)))
+    }
+
+    // [A, B]
+    fn parse_tuple_type(&mut self) -> OptResult<typescript::TupleType<'code>> {
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::SquareOpen));
+
+        let mut elements = vec![];
+        while let TokenResult::Some(element) = self.parse_tuple_element()? {
+            elements.push(element);
+            if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
+                break;
+            }
+        }
+        eat_value!(self.punc(tokens::PunctuatorToken::SquareClose));
+
+        Ok(TokenResult::Some(self.located(start, typescript::TupleType {
+            elements,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // A, ...A, or a labeled element like "a?: A"
+    fn parse_tuple_element(&mut self) -> OptResult<typescript::Type<'code>> {
+        if !self.options.extensions.typescript {
+            return self.parse_type();
+        }
+
+        let start = self.start();
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Ellipsis) {
+            let token_ellipsis = self.token_data();
+            let value = eat_value!(self.parse_tuple_member()?);
+
+            return Ok(TokenResult::Some(self.located(start, typescript::RestType {
+                token_ellipsis,
+                value: NodeBox::new(value),
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        self.parse_tuple_member()
+    }
+
+    // A, A?, or a?: A
+    fn parse_tuple_member(&mut self) -> OptResult<typescript::Type<'code>> {
+        let start = self.start();
+        let label = self.speculate(|p| {
+            let label = try_value!(p.parse_type_identifier());
+            let optional = opt_value!(p.punc(tokens::PunctuatorToken::Question)).is_some();
+            try_value!(p.punc(tokens::PunctuatorToken::Colon));
+            Ok(TokenResult::Some((label, optional, p.token_data())))
+        });
+        if let TokenResult::Some((label, optional, token_colon)) = label {
+            let value = eat_value!(self.parse_type()?);
+
+            return Ok(TokenResult::Some(self.located(start, typescript::NamedTupleMember {
+                label,
+                optional,
+                token_colon,
+                value: NodeBox::new(value),
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        let value = try_value!(self.parse_type()?);
+        if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Question) {
+            let token_question = self.token_data();
+
+            return Ok(TokenResult::Some(self.located(start, typescript::OptionalType {
+                value: NodeBox::new(value),
+                token_question,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        Ok(TokenResult::Some(value))
+    }
+
+    // { a: A; b(): B }
+    fn parse_object_type(&mut self) -> OptResult<typescript::ObjectType<'code>> {
+        let start = self.start();
+        let mut parser = self.without(Flag::Template);
+        try_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+        let members = parser.parse_type_members()?;
+        eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+        Ok(TokenResult::Some(parser.located(start, typescript::ObjectType {
+            members,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    /// The members of an object type, up to its closing bracket.
+    pub fn parse_type_members(&mut self) -> Result<Vec<typescript::TypeMember<'code>>> {
+        self.nested(Self::parse_type_members_nested)
+    }
+
+    fn parse_type_members_nested(&mut self) -> Result<Vec<typescript::TypeMember<'code>>> {
+        let mut members = vec![];
+        while let TokenResult::Some(member) = self.parse_type_member()? {
+            if matches!(members.last(), Some(typescript::TypeMember::Spread(spread)) if spread.is_inexact()) {
+                bail!("The \"...\" of an inexact object type must come last");
+            }
+            members.push(member);
+
+            // Members are separated by ";", "," or the end of a line.
+            if let TokenResult::None = self.punc(tokens::PunctuatorToken::Semicolon) {
+                if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
+                    if self.no_line_terminator() {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(members)
+    }
+
+    fn parse_type_member(&mut self) -> OptResult<typescript::TypeMember<'code>> {
+        let start = self.start();
+        if let TokenResult::Some(spread) = self.parse_spread_type_member()? {
+            return Ok(TokenResult::Some(spread.into()));
+        }
+
+        match *self.token() {
+            tokens::Token::Punctuator(tokens::PunctuatorToken::ParenOpen) |
+            tokens::Token::Punctuator(tokens::PunctuatorToken::LAngle) => {
+                let params = eat_value!(self.parse_typed_function_params(true)?);
+
+                return Ok(TokenResult::Some(self.located(start, typescript::CallSignature {
+                    params,
+                    position: None,
+                    comments: None,
+                }).into()));
+            }
+            _ => {}
+        }
+
+        let is_construct = self.is_word_before(
+            "new",
+            &[tokens::PunctuatorToken::ParenOpen, tokens::PunctuatorToken::LAngle],
+        );
+        if is_construct {
+            eat_value!(self.keyword("new"));
+            let token_new = self.token_data();
+            let params = eat_value!(self.parse_typed_function_params(true)?);
+
+            return Ok(TokenResult::Some(self.located(start, typescript::ConstructSignature {
+                token_new,
+                params,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        let mut modifiers = vec![];
+        if let Some(typescript::ModifierKind::Readonly) = self.modifier_kind() {
+            self.pop();
+            modifiers.push(self.located(start, typescript::Modifier::from(typescript::ModifierKind::Readonly)));
+        }
+        modifiers.extend(self.parse_variance());
+
+        if let TokenResult::Some(signature) = self.parse_index_signature(start, &mut modifiers)? {
+            return Ok(TokenResult::Some(signature.into()));
+        }
+
+        let name = if modifiers.is_empty() {
+            try_value!(self.parse_property_name()?)
+        } else {
+            eat_value!(self.parse_property_name()?)
+        };
+        let optional = opt_value!(self.punc(tokens::PunctuatorToken::Question)).is_some();
+
+        if let TokenResult::Some(params) = self.parse_typed_function_params(true)? {
+            return Ok(TokenResult::Some(self.located(start, typescript::MethodSignature {
+                name,
+                optional,
+                params,
+                position: None,
+                comments: None,
+            }).into()));
+        }
+
+        let type_annotation = opt_value!(self.parse_type_annotation()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::PropertySignature {
+            modifiers,
+            name,
+            optional,
+            type_annotation,
+            position: None,
+            comments: None,
+        }).into()))
+    }
+
+    // Whether the current token is the given word, followed by one of the
+    // given punctuators.
+    pub fn is_word_before(&mut self, word: &str, next: &[tokens::PunctuatorToken]) -> bool {
+        match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) if name == word => {}
+            _ => return false,
+        }
+
+        match self.ident_lookahead() {
+            Some(&LookaheadResult { token: tokens::Token::Punctuator(ref punc), .. }) => next.contains(punc),
+            _ => false,
+        }
+    }
+
+    // [key: string]: A
+    pub fn parse_index_signature(
+        &mut self,
+        start: tokenizer::Position,
+        modifiers: &mut Vec<typescript::Modifier<'code>>,
+    ) -> OptResult<typescript::IndexSignature<'code>> {
+        match *self.token() {
+            tokens::Token::Punctuator(tokens::PunctuatorToken::SquareOpen) => {}
+            _ => return Ok(TokenResult::None),
+        }
+
+        // "[a]" is a computed property name unless a type follows the name.
+        let (key, key_type) = try_value!(self.speculate(|p| {
+            try_value!(p.punc(tokens::PunctuatorToken::SquareOpen));
+            let key = try_value!(p.parse_type_identifier());
+            let key_type = try_value!(p.parse_type_annotation()?);
+            eat_value!(p.punc(tokens::PunctuatorToken::SquareClose));
+            Ok(TokenResult::Some((key, key_type)))
+        }));
+        let type_annotation = eat_value!(self.parse_type_annotation()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::IndexSignature {
+            modifiers: mem::take(modifiers),
+            key,
+            key_type,
+            type_annotation,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // <T extends U = V>
+    pub fn parse_type_parameters(&mut self) -> OptResult<typescript::TypeParameters<'code>> {
+        if !self.has_types() {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::LAngle));
+
+        let mut params = vec![];
+        while let TokenResult::Some(param) = self.parse_type_parameter()? {
+            params.push(param);
+            if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
+                break;
+            }
+        }
+        if params.is_empty() {
+            bail!("Expected a type parameter");
+        }
+        eat_value!(self.type_arguments_end());
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeParameters {
+            params,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    fn parse_type_parameter(&mut self) -> OptResult<typescript::TypeParameter<'code>> {
+        let start = self.start();
+
+        // Flow's variance, like "<+T>", or TypeScript's, like "<in out T>".
+        let mut modifiers: Vec<_> = self.parse_variance().into_iter().collect();
+        while let Some(modifier) = self.parse_variance_keyword() {
+            modifiers.push(modifier);
+        }
+        let id = match self.parse_type_identifier() {
+            TokenResult::Some(id) => id,
+            TokenResult::None if !modifiers.is_empty() => bail!("Expected a type parameter"),
+            TokenResult::None => return Ok(TokenResult::None),
+        };
+
+        // Flow bounds the parameter with a type annotation, like "<T: U>".
+        let bound = if self.options.extensions.flow {
+            opt_value!(self.parse_type_annotation()?)
+        } else {
+            None
+        };
+        let constraint = if let TokenResult::Some(_) = self.keyword("extends") {
+            Some(NodeBox::new(eat_value!(self.parse_type()?)))
+        } else {
+            None
+        };
+        let default = if let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Eq) {
+            Some(NodeBox::new(eat_value!(self.parse_type()?)))
+        } else {
+            None
+        };
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeParameter {
+            modifiers,
+            id,
+            bound,
+            constraint,
+            default,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // The "in" or "out" of "<in out T>", which are names of their own unless
+    // another name follows, like the "out" of "<out extends T>".
+    fn parse_variance_keyword(&mut self) -> Option<typescript::Modifier<'code>> {
+        if !self.options.extensions.typescript {
+            return None;
+        }
+
+        let kind = match *self.token() {
+            tokens::Token::IdentifierName(ref id) if id.name == "in" => typescript::ModifierKind::In,
+            tokens::Token::IdentifierName(ref id) if id.name == "out" => typescript::ModifierKind::Out,
+            _ => return None,
+        };
+        let is_modifier = matches!(
+            self.ident_lookahead(),
+            Some(&LookaheadResult { token: tokens::Token::IdentifierName(ref id), .. }) if id.name != "extends"
+        );
+        if !is_modifier {
+            return None;
+        }
+
+        let start = self.start();
+        self.pop();
+        Some(self.located(start, typescript::Modifier::from(kind)))
+    }
+
+    // <string, T>
+    pub fn parse_type_arguments(&mut self) -> OptResult<typescript::TypeArguments<'code>> {
+        self.nested(Self::parse_type_arguments_nested)
+    }
+
+    fn parse_type_arguments_nested(&mut self) -> OptResult<typescript::TypeArguments<'code>> {
+        if !self.has_types() {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::LAngle));
+
+        let mut arguments = vec![eat_value!(self.parse_type()?)];
+        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Comma) {
+            arguments.push(eat_value!(self.parse_type()?));
+        }
+        eat_value!(self.type_arguments_end());
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeArguments {
+            arguments,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    /// Type arguments in an expression, like the "<T>" of "f<T>(a)", which
+    /// are only type arguments if they can't be a comparison instead.
+    pub fn parse_expression_type_arguments(&mut self) -> TokenResult<typescript::TypeArguments<'code>> {
+        self.speculate(|p| {
+            let arguments = try_value!(p.parse_type_arguments()?);
+
+            let follows = match *p.token() {
+                tokens::Token::Punctuator(ref punc) => matches!(
+                    *punc,
+                    tokens::PunctuatorToken::ParenOpen |
+                    tokens::PunctuatorToken::ParenClose |
+                    tokens::PunctuatorToken::SquareClose |
+                    tokens::PunctuatorToken::CurlyClose |
+                    tokens::PunctuatorToken::Comma |
+                    tokens::PunctuatorToken::Semicolon
+                ),
+                tokens::Token::Template(tokens::TemplateToken { format: tokens::TemplateFormat::NoSubstitution, .. }) |
+                tokens::Token::Template(tokens::TemplateToken { format: tokens::TemplateFormat::Head, .. }) |
+                tokens::Token::EOF(_) => true,
+                _ => false,
+            };
+
+            Ok(if follows { TokenResult::Some(arguments) } else { TokenResult::None })
+        })
+    }
+
+    // The ">" at the end of a type argument list. The tokenizer reads the
+    // ">>" of "A<B<C>>" as one token, so only its first character is taken,
+    // leaving the rest as the next token.
+    pub fn type_arguments_end(&mut self) -> TokenResult<()> {
+        let rest = match *self.token() {
+            tokens::Token::Punctuator(tokens::PunctuatorToken::RAngle) => {
+                self.pop();
+                return TokenResult::Some(());
+            }
+            tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngle) => tokens::PunctuatorToken::RAngle,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngleAngle) => tokens::PunctuatorToken::RAngleAngle,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleEq) => tokens::PunctuatorToken::Eq,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngleEq) => tokens::PunctuatorToken::RAngleEq,
+            tokens::Token::Punctuator(tokens::PunctuatorToken::RAngleAngleAngleEq) => tokens::PunctuatorToken::RAngleAngleEq,
+            _ => return TokenResult::None,
+        };
+
+        let next = &mut self.tokens[self.index as usize];
+        let start = next.range.start;
+        let split = tokenizer::Position {
+            offset: start.offset + 1,
+            line: start.line,
+            column: start.column + 1,
+        };
+        next.token = tokens::Token::Punctuator(rest);
+        next.range.start = split;
+        next.line = false;
+
+        self.last_range = tokenizer::TokenRange { start, end: split };
+        self.last_end = split;
+
+        TokenResult::Some(())
+    }
+
+    /// <T>x, which is only parsed without JSX, where it would be an element.
+    pub fn parse_type_assertion(&mut self) -> OptResult<typescript::TypeAssertion<'code>> {
+        let start = self.start();
+        try_value!(self.punc(tokens::PunctuatorToken::LAngle));
+        let value = eat_value!(self.parse_type()?);
+        eat_value!(self.type_arguments_end());
+
+        self.expect_expression();
+        let expression = eat_value!(self.parse_unary_operand()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeAssertion {
+            value: NodeBox::new(value),
+            expression: NodeBox::new(expression),
+            position: None,
+            comments: None,
+        })))
+    }
+
+    /// The declarations of TypeScript and Flow, which are parsed before any
+    /// statement that could start with the same word.
+    pub fn parse_typescript_declaration(&mut self) -> OptResult<alias::StatementItem<'code>> {
+        enum DeclType {
+            Interface,
+            TypeAlias,
+            Enum,
+            ConstEnum,
+            Declare,
+            Abstract,
+            Function,
+            Module,
+            ImportEquals,
+            Opaque,
+            Variable,
+        }
+
+        let typescript = self.options.extensions.typescript;
+        let flow = self.options.extensions.flow;
+        let ambient = self.flags.in_ambient;
+        let t = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => match &**name {
+                "interface" => DeclType::Interface,
+                "type" => DeclType::TypeAlias,
+                "enum" if typescript => DeclType::Enum,
+                "const" if typescript && !ambient => DeclType::ConstEnum,
+                "var" | "let" | "const" if ambient => DeclType::Variable,
+                "declare" => DeclType::Declare,
+                "abstract" if typescript => DeclType::Abstract,
+                "function" | "async" => DeclType::Function,
+                "namespace" | "module" if typescript => DeclType::Module,
+                "import" if typescript => DeclType::ImportEquals,
+                "opaque" if flow => DeclType::Opaque,
+                _ => return Ok(TokenResult::None),
+            },
+            _ => return Ok(TokenResult::None),
+        };
+
+        // Other than "enum" and "function", these words are only keywords if
+        // they are followed by a name on the same line.
+        let next = match self.ident_lookahead() {
+            Some(&LookaheadResult {
+                line: false,
+                token: tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }),
+                ..
+            }) => Some(name.to_string()),
+            _ => None,
+        };
+        let t = match t {
+            DeclType::Variable if next.as_ref().is_some_and(|name| name == "enum") => DeclType::ConstEnum,
+            t => t,
+        };
+        let is_declaration = match (&t, next.as_ref().map(|name| &name[..])) {
+            (&DeclType::Enum, _) => true,
+            (&DeclType::Variable, _) => true,
+            (&DeclType::ConstEnum, Some("enum")) => true,
+            (&DeclType::Abstract, Some("class")) => true,
+            (&DeclType::Opaque, Some("type")) => true,
+            (&DeclType::Function, Some("function")) => true,
+            (&DeclType::Function, _) => matches!(*self.token(), tokens::Token::IdentifierName(ref id) if id.name == "function"),
+            (&DeclType::Interface, Some(_)) | (&DeclType::TypeAlias, Some(_)) | (&DeclType::Declare, Some(_)) => true,
+            (&DeclType::Module, Some(_)) | (&DeclType::ImportEquals, Some(_)) => true,
+            (&DeclType::Module, None) => matches!(
+                self.ident_lookahead(),
+                Some(&LookaheadResult { line: false, token: tokens::Token::StringLiteral(_), .. })
+            ),
+            _ => false,
+        };
+        if !is_declaration {
+            return Ok(TokenResult::None);
+        }
+
+        let start = self.start();
+        let decl: alias::StatementItem = match t {
+            DeclType::Interface => typescript::Declaration::from(eat_value!(self.parse_interface_declaration()?)).into(),
+            DeclType::TypeAlias => typescript::Declaration::from(eat_value!(self.parse_type_alias_declaration()?)).into(),
+            DeclType::Enum | DeclType::ConstEnum => {
+                typescript::Declaration::from(eat_value!(self.parse_enum_declaration()?)).into()
+            }
+            DeclType::Declare => typescript::Declaration::from(eat_value!(self.parse_declare_declaration()?)).into(),
+            DeclType::Abstract => eat_value!(self.parse_abstract_class_declaration()?).into(),
+            DeclType::Module => typescript::Declaration::from(eat_value!(self.parse_module_declaration()?)).into(),
+
+            // "import a" could still be the start of "import a from 'a';".
+            DeclType::ImportEquals => {
+                typescript::Declaration::from(try_value!(self.parse_import_equals_declaration()?)).into()
+            }
+            DeclType::Opaque => typescript::Declaration::from(eat_value!(self.parse_opaque_type_declaration(false)?)).into(),
+            DeclType::Variable => typescript::Declaration::from(self.parse_declare_variable_declaration()?).into(),
+            DeclType::Function => {
+                let (kind, id, params, body) = eat_value!(self.parse_function(true, typescript)?);
+                let id = id.expect("function declarations require a name");
+
+                match body {
+                    Some(body) => self.located(start, functions::FunctionDeclaration {
+                        kind,
+                        id,
+                        params,
+                        body,
+                        position: None,
+                        comments: None,
+                    }).into(),
+                    None => {
+                        let token_semi = eat_value!(self.semicolon_token());
+
+                        typescript::Declaration::from(self.located(start, typescript::FunctionSignatureDeclaration {
+                            kind,
+                            id,
+                            params,
+                            token_semi,
+                            position: None,
+                            comments: None,
+                        })).into()
+                    }
+                }
+            }
+        };
+
+        Ok(TokenResult::Some(decl))
+    }
+
+    // interface Foo<T> extends Bar { a: T }
+    fn parse_interface_declaration(&mut self) -> OptResult<typescript::InterfaceDeclaration<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("interface"));
+        let token_interface = self.token_data();
+
+        let id = eat_value!(self.parse_type_identifier());
+        let type_parameters = opt_value!(self.parse_type_parameters()?);
+
+        let mut extends = vec![];
+        if let TokenResult::Some(_) = self.keyword("extends") {
+            loop {
+                extends.push(eat_value!(self.parse_type_reference()?));
+                if let TokenResult::None = self.punc(tokens::PunctuatorToken::Comma) {
+                    break;
+                }
+            }
+        }
+
+        let body = eat_value!(self.parse_object_type()?);
+
+        Ok(TokenResult::Some(self.located(start, typescript::InterfaceDeclaration {
+            token_interface,
+            id,
+            type_parameters,
+            extends,
+            body,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // type Foo<T> = T[];
+    fn parse_type_alias_declaration(&mut self) -> OptResult<typescript::TypeAliasDeclaration<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("type"));
+        let token_type = self.token_data();
+
+        let id = eat_value!(self.parse_type_identifier());
+        let type_parameters = opt_value!(self.parse_type_parameters()?);
+        eat_value!(self.punc(tokens::PunctuatorToken::Eq));
+        let value = eat_value!(self.parse_type()?);
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, typescript::TypeAliasDeclaration {
+            token_type,
+            id,
+            type_parameters,
+            value: NodeBox::new(value),
+            token_semi,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // enum Foo { A, B = 2 }
+    fn parse_enum_declaration(&mut self) -> OptResult<typescript::EnumDeclaration<'code>> {
+        let start = self.start();
+        let is_const = opt_value!(self.keyword("const")).is_some();
+        if is_const {
+            eat_value!(self.keyword("enum"));
+        } else {
+            try_value!(self.keyword("enum"));
+        }
+        let token_enum = self.token_data();
+
+        let id = eat_value!(self.parse_binding_identifier());
+
+        let mut parser = self.without(Flag::Template);
+        eat_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+
+        let mut members = vec![];
+        loop {
+            let member_start = parser.start();
+            let name = match parser.parse_property_name()? {
+                TokenResult::Some(name) => name,
+                TokenResult::None => break,
+            };
+            let init = opt_value!(parser.with(Flag::In).parse_initializer()?);
+
+            members.push(parser.located(member_start, typescript::EnumMember {
+                name,
+                init,
+                position: None,
+                comments: None,
+            }));
+
+            if let TokenResult::None = parser.punc(tokens::PunctuatorToken::Comma) {
+                break;
+            }
+        }
+
+        eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+        Ok(TokenResult::Some(parser.located(start, typescript::EnumDeclaration {
+            is_const,
+            token_enum,
+            id,
+            members,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // declare const foo: Foo;
+    fn parse_declare_declaration(&mut self) -> OptResult<typescript::DeclareDeclaration<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("declare"));
+        let token_declare = self.token_data();
+        let declaration = self.with(Flag::Ambient).parse_declared_item()?;
+
+        Ok(TokenResult::Some(self.located(start, typescript::DeclareDeclaration {
+            token_declare,
+            declaration,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // The "const foo: Foo;" of "declare const foo: Foo;"
+    pub fn parse_declared_item(&mut self) -> Result<typescript::DeclaredItem<'code>> {
+        enum DeclareType {
+            Function,
+            Class,
+            Module,
+            Opaque,
+            Export,
+            ModuleExports,
+            Other,
+        }
+
+        let typescript = self.options.extensions.typescript;
+        let flow = self.options.extensions.flow;
+        let t = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => match &**name {
+                "function" | "async" => DeclareType::Function,
+                "class" => DeclareType::Class,
+                "namespace" | "module" | "global" if typescript => DeclareType::Module,
+                "module" if flow => DeclareType::Module,
+                "opaque" if flow => DeclareType::Opaque,
+                "export" if flow => DeclareType::Export,
+                _ => DeclareType::Other,
+            },
+            _ => DeclareType::Other,
+        };
+        let t = match t {
+            DeclareType::Module if flow && self.is_word_before("module", &[tokens::PunctuatorToken::Period]) => {
+                DeclareType::ModuleExports
+            }
+            t => t,
+        };
+
+        let decl_start = self.start();
+        let declaration: typescript::DeclaredItem = match t {
+            DeclareType::Function => {
+                let (kind, id, params, body) = eat_value!(self.parse_function(true, true)?);
+                if body.is_some() {
+                    bail!("Declared functions can't have a body");
+                }
+                let token_semi = eat_value!(self.semicolon_token());
+
+                self.located(decl_start, typescript::FunctionSignatureDeclaration {
+                    kind,
+                    id: id.expect("function declarations require a name"),
+                    params,
+                    token_semi,
+                    position: None,
+                    comments: None,
+                }).into()
+            }
+            DeclareType::Class => eat_value!(self.parse_class_declaration()?).into(),
+            DeclareType::Module => eat_value!(self.parse_module_declaration()?).into(),
+            DeclareType::Opaque => eat_value!(self.parse_opaque_type_declaration(true)?).into(),
+            DeclareType::Export => self.parse_declare_export_declaration()?.into(),
+            DeclareType::ModuleExports => self.parse_declare_module_exports()?.into(),
+            DeclareType::Other => match eat_value!(self.parse_typescript_declaration()?) {
+                alias::StatementItem::TypeScript(decl) => match NodeBox::into_inner(decl) {
+                    typescript::Declaration::Interface(decl) => decl.into(),
+                    typescript::Declaration::TypeAlias(decl) => decl.into(),
+                    typescript::Declaration::Enum(decl) => decl.into(),
+                    typescript::Declaration::Variable(decl) => decl.into(),
+                    _ => bail!("Unexpected declaration after \"declare\""),
+                },
+                alias::StatementItem::Class(decl) => NodeBox::into_inner(decl).into(),
+                _ => bail!("Unexpected declaration after \"declare\""),
+            },
+        };
+
+        Ok(declaration)
+    }
+
+    // The "const foo: Foo;" of "declare const foo: Foo;", or a variable
+    // anywhere inside of a declared namespace or module.
+    fn parse_declare_variable_declaration(&mut self) -> Result<typescript::DeclareVariableDeclaration<'code>> {
+        let start = self.start();
+        let kind = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => match &**name {
+                "var" => typescript::DeclareVariableKind::Var,
+                "let" => typescript::DeclareVariableKind::Let,
+                _ => typescript::DeclareVariableKind::Const,
+            },
+            _ => unreachable!("ambient variables start with var, let or const"),
+        };
+        self.pop();
+
+        let declarators = eat_value!(self.with(Flag::In).parse_declarator_list()?);
+        let declarators: Vec<_> = declarators.items.into_iter().map(|(decl, _)| decl).chain(Some(declarators.last)).collect();
+        for decl in declarators.iter() {
+            if decl.init.is_some() && !matches!(kind, typescript::DeclareVariableKind::Const) {
+                bail!("Initializers are not allowed in ambient contexts");
+            }
+        }
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(self.located(start, typescript::DeclareVariableDeclaration {
+            kind,
+            declarators,
+            token_semi,
+            position: None,
+            comments: None,
+        }))
+    }
+
+    // namespace A.B { export const c = 1; }
+    // module "a" {}
+    // global {}
+    fn parse_module_declaration(&mut self) -> OptResult<typescript::ModuleDeclaration<'code>> {
+        let start = self.start();
+        let kind = match *self.token() {
+            tokens::Token::IdentifierName(tokens::IdentifierNameToken { ref name }) => match &**name {
+                "namespace" => typescript::ModuleKind::Namespace,
+                "module" => typescript::ModuleKind::Module,
+                "global" => typescript::ModuleKind::Global,
+                _ => return Ok(TokenResult::None),
+            },
+            _ => return Ok(TokenResult::None),
+        };
+        self.pop();
+        let token_kind = self.token_data();
+
+        let id = match kind {
+            typescript::ModuleKind::Global => None,
+            _ => Some(eat_value!(self.parse_module_name(matches!(kind, typescript::ModuleKind::Module)))),
+        };
+
+        // Only modules named by a string can leave out the body, like
+        // "declare module 'a';".
+        let body = match self.parse_module_block()? {
+            TokenResult::Some(body) => Some(body),
+            TokenResult::None => match id {
+                Some(typescript::ModuleName::String(_)) => {
+                    eat_value!(self.semicolon());
+                    None
+                }
+                _ => bail!("Expected the body of a namespace"),
+            },
+        };
+
+        Ok(TokenResult::Some(self.located(start, typescript::ModuleDeclaration {
+            kind,
+            token_kind,
+            id,
+            body,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // A.B, or "a" when strings are allowed
+    fn parse_module_name(&mut self, strings: bool) -> TokenResult<typescript::ModuleName<'code>> {
+        if strings {
+            if let TokenResult::Some(name) = self.parse_string_literal() {
+                return TokenResult::Some(name.into());
+            }
+        }
+
+        let start = self.start();
+        let mut name: typescript::ModuleName = try_token!(self.parse_binding_identifier()).into();
+        while let TokenResult::Some(_) = self.punc(tokens::PunctuatorToken::Period) {
+            let token_period = self.token_data();
+            let right = try_token!(self.parse_binding_identifier());
+
+            name = self.located(start, typescript::QualifiedModuleName {
+                left: NodeBox::new(name),
+                token_period,
+                right,
+                position: None,
+                comments: None,
+            }).into();
+        }
+
+        TokenResult::Some(name)
+    }
+
+    fn parse_module_block(&mut self) -> OptResult<typescript::ModuleBlock<'code>> {
+        let start = self.start();
+        let mut parser = self.without(Flag::Template);
+        try_value!(parser.punc(tokens::PunctuatorToken::CurlyOpen));
+
+        let mut body = vec![];
+        while let TokenResult::Some(item) = parser.parse_module_item()? {
+            body.push(item);
+        }
+
+        eat_value!(parser.punc(tokens::PunctuatorToken::CurlyClose));
+
+        Ok(TokenResult::Some(parser.located(start, typescript::ModuleBlock {
+            body,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    /// import a = require("a");
+    /// import b = a.b;
+    pub fn parse_import_equals_declaration(&mut self) -> OptResult<typescript::ImportEqualsDeclaration<'code>> {
+        let start = self.start();
+        let (token_import, id) = try_value!(self.speculate(|p| {
+            try_value!(p.keyword("import"));
+            let token_import = p.token_data();
+            let id = try_value!(p.parse_binding_identifier());
+            try_value!(p.punc(tokens::PunctuatorToken::Eq));
+            Ok(TokenResult::Some((token_import, id)))
+        }));
+        let token_eq = self.token_data();
+
+        let is_require = self.is_word_before("require", &[tokens::PunctuatorToken::ParenOpen]);
+        let value = if is_require {
+            let reference_start = self.start();
+            self.pop();
+            let token_require = self.token_data();
+            eat_value!(self.punc(tokens::PunctuatorToken::ParenOpen));
+            let source = eat_value!(self.parse_string_literal());
+            eat_value!(self.punc(tokens::PunctuatorToken::ParenClose));
+
+            self.located(reference_start, typescript::ExternalModuleReference {
+                token_require,
+                source,
+                position: None,
+                comments: None,
+            }).into()
+        } else {
+            eat_value!(self.parse_type_name()).into()
+        };
+        let token_semi = eat_value!(self.semicolon_token());
+
+        Ok(TokenResult::Some(self.located(start, typescript::ImportEqualsDeclaration {
+            token_import,
+            id,
+            token_eq,
+            value,
+            token_semi,
+            position: None,
+            comments: None,
+        })))
+    }
+
+    // abstract class Foo {}
+    fn parse_abstract_class_declaration(&mut self) -> OptResult<::ast::classes::ClassDeclaration<'code>> {
+        let start = self.start();
+        try_value!(self.keyword("abstract"));
+        let modifier = self.located(start, typescript::Modifier::from(typescript::ModifierKind::Abstract));
+
+        let mut class = eat_value!(self.parse_class_declaration()?);
+        class.modifiers.insert(0, modifier);
+
+        Ok(TokenResult::Some(self.located(start, class)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{format, FormatOptions};
+    use parser::{self, ParserOptions};
+
+    fn strip(code: &str) -> String {
+        strip_with(code, Default::default())
+    }
+
+    fn strip_pretty(code: &str) -> String {
+        strip_with(code, FormatOptions::pretty())
+    }
+
+    fn strip_with(code: &str, options: FormatOptions) -> String {
+        let mut parser_options = ParserOptions::module();
+        parser_options.extensions.typescript = true;
+        let ast = parser::parse(code, &parser_options).unwrap();
+
+        format(&ast, &FormatOptions { strip_types: true, ..options }).unwrap()
+    }
+
+    #[test]
+    fn it_requires_the_extension() {
+        assert!(parser::parse("let a: number;", &ParserOptions::module()).is_err());
+        assert!(parser::parse("interface A {}", &ParserOptions::module()).is_err());
+    }
+
+    #[test]
+    fn it_strips_annotations() {
+        assert_eq!(strip("let a: number = 1;"), "let a=1;");
+        assert_eq!(strip("function f(this: A, b?: B, ...c: C[]): D {}"), "function f(b,...c){}");
+        assert_eq!(strip("const f = async <T>(a: T): Promise<T> => a;"), "const f=async(a)=>a;");
+    }
+
+    #[test]
+    fn it_strips_declarations() {
+        assert_eq!(strip("interface A { b: B } type C = D; declare const e: E; export type F = G;"), "");
+        assert_eq!(strip("function f(a: string): void; function f(a) {}"), "function f(a){}");
+    }
+
+    #[test]
+    fn it_leaves_no_lines_for_stripped_declarations() {
+        assert_eq!(strip_pretty("function f() { type T = 1; interface I {} }"), "function f() {}");
+        assert_eq!(strip_pretty("abstract class A { protected abstract get x(): number; }"), "class A {}");
+        assert_eq!(strip_pretty("function g() { a(); type T = 1; b(); }"), "function g() {\n  a();\n  b();\n}");
+    }
+
+    #[test]
+    fn it_strips_enums() {
+        assert_eq!(
+            strip("enum S { A = 'a', B = A, C = A + 'c' }"),
+            "var S;(function(S){S['A']='a';S['B']='a';S['C']='ac';})(S||(S={}));"
+        );
+        assert_eq!(
+            strip("enum N { A = 1, B = A, C, D = A | 4, E = 1 << 3 }"),
+            "var N;(function(N){N[N['A']=1]='A';N[N['B']=1]='B';N[N['C']=2]='C';N[N['D']=5]='D';N[N['E']=8]='E';})(N||(N={}));"
+        );
+        assert_eq!(
+            strip("enum F { A = f(), B = g(A), C }"),
+            "var F;(function(F){F[F['A']=f()]='A';F[F['B']=g(F['A'])]='B';F[F['C']=F['B']+1]='C';})(F||(F={}));"
+        );
+    }
+
+    #[test]
+    fn it_strips_classes() {
+        assert_eq!(
+            strip("abstract class A<T> implements B { private c: T; abstract d(): void; constructor(public e) {} }"),
+            "class A{c;constructor(e){this.e=e;}}"
+        );
+    }
+
+    #[test]
+    fn it_strips_definite_assignments() {
+        assert_eq!(strip("let a!: number; class B { c!: string; }"), "let a;class B{c;}");
+        assert_eq!(strip("let a\n!b;"), "let a;!b;");
+    }
+
+    #[test]
+    fn it_strips_other_annotations() {
+        assert_eq!(strip("try {} catch (e: unknown) {}"), "try{}catch(e){}");
+        assert_eq!(strip("let a: typeof import('a'), b: import('b').B<C>;"), "let a,b;");
+        assert_eq!(strip("class A<in out T> {}"), "class A{}");
+    }
+
+    #[test]
+    fn it_tells_type_arguments_from_comparisons() {
+        assert_eq!(strip("f<T>(a);"), "f(a);");
+        assert_eq!(strip("a < b > c;"), "a<b>c;");
+        assert_eq!(strip("a < b >> c;"), "a<b>>c;");
+    }
+
+    #[test]
+    fn it_splits_closing_angles() {
+        assert_eq!(strip("let a: A<B<C>> = d;"), "let a=d;");
+        assert_eq!(strip("let a: A<B<C>>= d;"), "let a=d;");
+        assert_eq!(strip("let a: A<B<C<D>>> = d;"), "let a=d;");
+    }
+
+    #[test]
+    fn it_retries_arrows_in_conditionals() {
+        assert_eq!(strip("a ? (b) : c => d;"), "a?(b):c=>d;");
+        assert_eq!(strip("a ? (b): C => d : e;"), "a?(b)=>d:e;");
+    }
+
+    #[test]
+    fn it_strips_expressions() {
+        assert_eq!(strip("a as B;"), "a;");
+        assert_eq!(strip("(a as B)!.c satisfies D;"), "(a).c;");
+        assert_eq!(strip("<A>b + <C>(d, e);"), "b+(d,e);");
+    }
+
+    #[test]
+    fn it_strips_type_imports() {
+        assert_eq!(strip("import type { A } from 'a'; export type { A };"), "");
+        assert_eq!(strip("import { type A, B } from 'a';"), "import{B}from'a';");
+        assert_eq!(strip("import { type A } from 'a';"), "");
+        assert_eq!(strip("import A, { type B } from 'a';"), "import A from'a';");
+    }
+
+    #[test]
+    fn it_strips_namespaces() {
+        assert_eq!(
+            strip("namespace N { export const a = 1, b = 2; function f() {} }"),
+            "var N;(function(N){const a=1,b=2;N.a=a;N.b=b;function f(){}})(N||(N={}));"
+        );
+        assert_eq!(
+            strip("export namespace A.B { export namespace C { export enum E { X } } }"),
+            concat!(
+                "export var A;(function(A){let B;(function(B){let C;(function(C){",
+                "var E;(function(E){E[E['X']=0]='X';})(E||(E={}));C.E=E;",
+                "})(C=B.C||(B.C={}));})(B=A.B||(A.B={}));})(A||(A={}));",
+            )
+        );
+        assert_eq!(strip("namespace T { export type A = string; } declare namespace D {} declare global {}"), "");
+    }
+
+    #[test]
+    fn it_strips_import_equals() {
+        assert_eq!(strip("import a = require('a'); export = a;"), "const a=require('a');module.exports=a;");
+        assert_eq!(strip("namespace N { export import b = M.b; }"), "var N;(function(N){var b=M.b;N.b=b;})(N||(N={}));");
+    }
+
+    #[test]
+    fn it_tells_namespaces_from_identifiers() {
+        assert_eq!(strip("namespace\nN;"), "namespace;N;");
+        assert_eq!(strip("module.exports = {};"), "module.exports={};");
+    }
+}
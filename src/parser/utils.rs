@@ -97,3 +97,19 @@ macro_rules! opt_value {
         }
     );
 }
+
+// Execute a TokenResult-returning function, and "return" from a
+// TokenResult-returning function if not found.
+#[macro_export]
+macro_rules! try_token {
+    ($e:expr) => (
+        match $e {
+            $crate::parser::utils::TokenResult::Some(val) => {
+                val
+            }
+            $crate::parser::utils::TokenResult::None => {
+                return $crate::parser::utils::TokenResult::None;
+            }
+        }
+    );
+}
@@ -0,0 +1,19 @@
+//! Room on the stack for recursing through deeply nested code.
+//!
+//! A level of nesting takes up to a few dozen kilobytes of stack in
+//! unoptimized builds while parsing, and less but still a lot while folding,
+//! printing or serializing the tree, far more than the 2 MB that threads get
+//! by default has room for at the parser's nesting limit. Each recursive step
+//! runs through `grow`, which continues on a new stack segment once less than
+//! the red zone is left.
+
+use stacker;
+
+const RED_ZONE: usize = 256 << 10;
+const SEGMENT: usize = 4 << 20;
+
+/// Run a function that may recurse deeply, on a new stack segment if the
+/// current one is close to running out.
+pub fn grow<R, F: FnOnce() -> R>(f: F) -> R {
+    stacker::maybe_grow(RED_ZONE, SEGMENT, f)
+}
@@ -1,11 +1,29 @@
 mod slice;
 pub mod tokens;
 
+pub use self::slice::SliceTokenizer;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Hint {
     expression: bool,
     template: bool,
     strict: bool,
+
+    // Whether a "#!" line at the very start of the code is a comment.
+    hashbang: bool,
+
+    // Whether the code is inside of a JSX tag, or between the tags of a JSX
+    // element.
+    jsx_tag: bool,
+    jsx_text: bool,
+
+    // Whether Flow's comment syntax is read as code, like the type in
+    // "/*: string */".
+    flow_comments: bool,
+
+    // Whether "<!--" and "-->" start single-line comments, as Annex B allows
+    // outside of modules.
+    html_comments: bool,
 }
 impl Hint {
     pub fn expression(mut self, expression: bool) -> Hint {
@@ -20,6 +38,26 @@ impl Hint {
         self.strict = strict;
         self
     }
+    pub fn hashbang(mut self, hashbang: bool) -> Hint {
+        self.hashbang = hashbang;
+        self
+    }
+    pub fn jsx_tag(mut self, jsx_tag: bool) -> Hint {
+        self.jsx_tag = jsx_tag;
+        self
+    }
+    pub fn jsx_text(mut self, jsx_text: bool) -> Hint {
+        self.jsx_text = jsx_text;
+        self
+    }
+    pub fn flow_comments(mut self, flow_comments: bool) -> Hint {
+        self.flow_comments = flow_comments;
+        self
+    }
+    pub fn html_comments(mut self, html_comments: bool) -> Hint {
+        self.html_comments = html_comments;
+        self
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -42,6 +80,15 @@ pub struct TokenRange {
 
 pub trait Tokenizer<'code>: Clone + ::std::fmt::Debug {
     fn next_token(&mut self, &Hint, (&mut tokens::Token<'code>, &mut TokenRange));
+
+    /// The complete source text that tokens borrow from.
+    fn source(&self) -> &'code str;
+
+    /// The offset of the opening marker of the Flow comment, like "/*::",
+    /// that the last token was read from, if any.
+    fn flow_comment(&self) -> Option<usize> {
+        None
+    }
 }
 
 pub trait IntoTokenizer<'code> {
@@ -1,7 +1,8 @@
 use std::borrow::Cow;
 use tokenizer::tokens;
 use tokenizer::tokens::{PunctuatorToken,
-    TemplateFormat, CommentToken, CommentFormat};
+    TemplateFormat, CommentToken, CommentFormat, InvalidToken};
+use ucd::Codepoint;
 
 use tokenizer::{Hint, IntoTokenizer, Tokenizer, Position, TokenRange};
 
@@ -10,37 +11,54 @@ static NS_PS: &'static str = "\u{2029}";
 static WS_NBSP: &'static str = "\u{00A0}";
 static WS_ZWNBSP: &'static str = "\u{FEFF}";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SliceTokenizer<'code> {
     code: &'code str,
     position: Position,
+
+    // The offset of the opening marker when the tokenizer is between the
+    // markers of a Flow comment, like "/*:: type A = B; */", whose contents
+    // are read as code.
+    flow_comment: Option<usize>,
+
+    // Whether only whitespace and comments have been read since the last
+    // line terminator, where "-->" starts an HTML-like comment.
+    line_start: bool,
 }
 
-impl<'code> Clone for SliceTokenizer<'code> {
-    fn clone(&self) -> Self {
-        unimplemented!()
+impl<'code> SliceTokenizer<'code> {
+    /// A tokenizer that starts reading at the given position in the code,
+    /// which must be the start of a token or of whitespace before one.
+    pub fn at(code: &'code str, position: Position) -> SliceTokenizer<'code> {
+        let before = code[..position.offset].trim_end_matches(['\t', '\u{B}', '\u{C}', ' ', '\u{A0}', '\u{FEFF}']);
+        let line_start = before.is_empty() || before.ends_with(['\r', '\n', '\u{2028}', '\u{2029}']);
+
+        SliceTokenizer { code, position, flow_comment: None, line_start }
     }
 }
 
 fn eat_whitespace(code: &str, pos: &mut Position) {
+    let bytes = code.as_bytes();
     let mut index = pos.offset;
+    let mut column = pos.column;
 
-    for &b in code[pos.offset..].as_bytes() {
-        match b {
+    while index < bytes.len() {
+        match bytes[index] {
             b'\x09' | b'\x0B' | b'\x0C' | b'\x20' => {
                 index += 1;
             }
             b'\xEF' if code[index..].starts_with(WS_ZWNBSP) => {
-                index += 1;
+                index += WS_ZWNBSP.len();
             }
             b'\xC2' if code[index..].starts_with(WS_NBSP) => {
-                index += 1;
+                index += WS_NBSP.len();
             }
             _ => break,
         }
+        column += 1;
     }
 
-    pos.column += index - pos.offset;
+    pos.column = column;
     pos.offset = index;
 }
 
@@ -76,23 +94,87 @@ fn increment_position(code: &str, size: usize, pos: &mut Position) {
 }
 
 impl<'code> Tokenizer<'code> for SliceTokenizer<'code> {
-    fn next_token<'a, 'b, 'c>(&mut self, hint: &'a Hint, mut out: (&'b mut tokens::Token<'code>, &'c mut TokenRange)) {
-        eat_whitespace(&self.code, &mut self.position);
+    fn next_token<'a, 'b, 'c>(&mut self, hint: &'a Hint, out: (&'b mut tokens::Token<'code>, &'c mut TokenRange)) {
+        let start = self.position;
+
+        if hint.hashbang && start.offset == 0 && self.code.starts_with("#!") {
+            let size = line_len(self.code);
+            comment(Cow::from(&self.code[2..size]), CommentFormat::Hashbang, out.0);
+            increment_position(self.code, size, &mut self.position);
+            *out.1 = TokenRange {
+                start,
+                end: self.position,
+            };
+            return;
+        }
+
+        // Whitespace is part of the text in JSX elements.
+        if !hint.jsx_text {
+            eat_whitespace(self.code, &mut self.position);
+        }
+
+        if self.position.offset != start.offset {
+            // Whitespace is reported as its own token so that consumers can
+            // reconstruct the original source from the token stream.
+            *out.0 = tokens::WhitespaceToken {}.into();
+            *out.1 = TokenRange {
+                start,
+                end: self.position,
+            };
+            return;
+        }
 
         let s = &self.code[self.position.offset..];
 
-        let size = read_next(s, hint, &mut out.0);
+        let flow_comment = if hint.flow_comments && !hint.jsx_text && !hint.jsx_tag {
+            let mut in_comment = self.flow_comment.is_some();
+            let size = read_flow_comment_marker(s, &mut in_comment, out.0);
+            self.flow_comment = if in_comment { self.flow_comment.or(Some(start.offset)) } else { None };
+            size
+        } else {
+            None
+        };
+
+        let html_comment = if hint.html_comments && flow_comment.is_none() && !hint.jsx_text && !hint.jsx_tag {
+            read_html_comment(s, self.line_start, out.0)
+        } else {
+            None
+        };
+
+        let size = if let Some(size) = flow_comment.or(html_comment) {
+            size
+        } else if hint.jsx_text {
+            read_jsx_text(s, out.0)
+        } else if hint.jsx_tag {
+            read_jsx_tag(s, hint, out.0)
+        } else {
+            read_next(s, hint, out.0)
+        };
 
         // println!("Token: {:?} at {:?}", out.0, self.position);
 
-        let start = self.position;
-        increment_position(&s, size, &mut self.position);
+        increment_position(s, size, &mut self.position);
 
         let range = TokenRange {
             start,
             end: self.position,
         };
         *out.1 = range;
+
+        self.line_start = match *out.0 {
+            tokens::Token::LineTerminator(_) => true,
+            tokens::Token::Whitespace(_) => self.line_start,
+            tokens::Token::Comment(_) => self.line_start || range.start.line != range.end.line,
+            _ => false,
+        };
+    }
+
+    fn source(&self) -> &'code str {
+        self.code
+    }
+
+    fn flow_comment(&self) -> Option<usize> {
+        self.flow_comment
     }
 }
 
@@ -100,10 +182,11 @@ impl<'code> IntoTokenizer<'code> for &'code str {
     type Item = SliceTokenizer<'code>;
 
     fn into_tokenizer(self) -> Self::Item {
-        SliceTokenizer {
-            code: self,
-            position: Default::default(),
-        }
+        SliceTokenizer::at(self, Position {
+            offset: 0,
+            line: 1,
+            column: 0,
+        })
     }
 }
 
@@ -114,12 +197,14 @@ fn punc<'a, 'b>(tok: tokens::PunctuatorToken, size: usize, token: &mut tokens::T
 
 fn number<'a, 'b>(tok: f64, _raw: Cow<'a, str>, token: &mut tokens::Token<'a>){
     *token = tokens::NumericLiteralToken {
-        value: tok
+        value: tok,
+        legacy_octal: false,
     }.into();
 }
-fn string<'a, 'b>(tok: Cow<'a, str>, _raw: Cow<'a, str>, token: &mut tokens::Token<'a>) {
+fn string<'a, 'b>(tok: Cow<'a, str>, _raw: Cow<'a, str>, legacy_octal: bool, token: &mut tokens::Token<'a>) {
     *token = tokens::StringLiteralToken {
-        value: tok
+        value: tok,
+        legacy_octal,
     }.into();
 }
 
@@ -138,6 +223,69 @@ fn comment<'a, 'b>(tok: Cow<'a, str>, format: CommentFormat, token: &mut tokens:
     }.into();
 }
 
+fn invalid<'a>(tok: InvalidToken, size: usize, token: &mut tokens::Token<'a>) -> usize {
+    *token = tok.into();
+    size
+}
+
+// The markers around code in Flow's comment syntax, which are read as
+// whitespace so that the code between them is parsed as if they weren't
+// there. The exception is the ":" of "/*: T */", which is read as the colon
+// starting a type annotation.
+fn read_flow_comment_marker<'code>(code: &'code str, in_comment: &mut bool, token: &mut tokens::Token<'code>) -> Option<usize> {
+    if *in_comment {
+        if !code.starts_with("*/") {
+            return None;
+        }
+        *in_comment = false;
+        *token = tokens::WhitespaceToken {}.into();
+        return Some(2);
+    }
+
+    if !code.starts_with("/*") {
+        return None;
+    }
+    let rest = code[2..].trim_start_matches([' ', '\t']);
+    let start = code.len() - rest.len();
+
+    let size = if rest.starts_with("::") {
+        start + 2
+    } else if rest.starts_with("flow-include") {
+        start + "flow-include".len()
+    } else if rest.starts_with(':') {
+        *in_comment = true;
+        return Some(punc(PunctuatorToken::Colon, start + 1, token));
+    } else {
+        return None;
+    };
+
+    *in_comment = true;
+    *token = tokens::WhitespaceToken {}.into();
+    Some(size)
+}
+
+// Annex B's HTML-like comments, which run to the end of the line. "-->" is
+// only a comment at the start of a line, since elsewhere it is the "--" and
+// ">" of code like "a-->b".
+fn read_html_comment<'code>(code: &'code str, line_start: bool, token: &mut tokens::Token<'code>) -> Option<usize> {
+    let (format, marker) = if code.starts_with("<!--") {
+        (CommentFormat::HTMLOpen, 4)
+    } else if line_start && code.starts_with("-->") {
+        (CommentFormat::HTMLClose, 3)
+    } else {
+        return None;
+    };
+
+    let size = line_len(code);
+    comment(Cow::from(&code[marker..size]), format, token);
+    Some(size)
+}
+
+// The length of the code up to the first line terminator.
+fn line_len(code: &str) -> usize {
+    code.find(['\r', '\n', '\u{2028}', '\u{2029}']).unwrap_or(code.len())
+}
+
 pub fn read_next<'code, 'b, 'c, 'tok>(code: &'code str, hint: &'c Hint, token: &'tok mut tokens::Token<'code>) -> usize {
     let bytes = code.as_bytes();
     let len = bytes.len();
@@ -156,7 +304,14 @@ pub fn read_next<'code, 'b, 'c, 'tok>(code: &'code str, hint: &'c Hint, token: &
         b';' => punc(PunctuatorToken::Semicolon, 1, token),
         b',' => punc(PunctuatorToken::Comma, 1, token),
         b'?' => punc(PunctuatorToken::Question, 1, token),
-        b':' => punc(PunctuatorToken::Colon, 1, token),
+        b':' => {
+            if len > 1 && bytes[1] == b':' {
+                punc(PunctuatorToken::ColonColon, 2, token)
+            } else {
+                punc(PunctuatorToken::Colon, 1, token)
+            }
+        }
+        b'@' => punc(PunctuatorToken::At, 1, token),
         b'~' => punc(PunctuatorToken::Tilde, 1, token),
         b'.' => {
             if len > 2 && bytes[1] == b'.' && bytes[2] == b'.' {
@@ -234,11 +389,7 @@ pub fn read_next<'code, 'b, 'c, 'tok>(code: &'code str, hint: &'c Hint, token: &
         }
         b'-' => {
             if len > 1 && bytes[1] == b'-' {
-                if len > 2 && bytes[2] == b'>' {
-                    punc(PunctuatorToken::MinusMinusAngle, 3, token)
-                } else {
-                    punc(PunctuatorToken::MinusMinus, 2, token)
-                }
+                punc(PunctuatorToken::MinusMinus, 2, token)
             } else if len > 1 && bytes[1] == b'=' {
                 punc(PunctuatorToken::MinusEq, 2, token)
             } else {
@@ -331,51 +482,136 @@ pub fn read_next<'code, 'b, 'c, 'tok>(code: &'code str, hint: &'c Hint, token: &
     }
 }
 
-fn tok_fractional<'code, 'tok>(code: &'code str, token: &'tok mut tokens::Token<'code>) -> usize {
-    let bytes = code.as_bytes();
-    let mut val = 0f64;
-    let mut offset = 1;
-
-    let (frac, num) = parse_decimal_digits(&bytes[offset..]);
-
-    val += frac;
-    offset += num;
+// The text between the tags of a JSX element, up to the "{" or "<" that
+// ends it. Entities are left as they are in the source.
+fn read_jsx_text<'code>(code: &'code str, token: &mut tokens::Token<'code>) -> usize {
+    match code.as_bytes().first() {
+        None => {
+            *token = tokens::EOFToken {}.into();
+            0
+        }
+        Some(&b'{') => punc(PunctuatorToken::CurlyOpen, 1, token),
+        Some(&b'<') => punc(PunctuatorToken::LAngle, 1, token),
+        Some(&b'>') | Some(&b'}') => invalid(InvalidToken::JSXText, 1, token),
+        Some(_) => {
+            let end = code.find(['{', '<', '>', '}']).unwrap_or(code.len());
+            *token = tokens::JSXTextToken {
+                value: (&code[..end]).into(),
+            }.into();
+            end
+        }
+    }
+}
 
-    let (exp, num) = parse_exponent(&bytes[offset..]);
+// A token inside of a JSX tag, where names may contain "-", strings have no
+// escapes and may span lines, and ">" and "/" are never part of a longer
+// punctuator.
+fn read_jsx_tag<'code>(code: &'code str, hint: &Hint, token: &mut tokens::Token<'code>) -> usize {
+    let bytes = code.as_bytes();
 
-    if num != 0 {
-        val = val * 10f64.powi(exp);
-        offset += num;
+    match bytes.first() {
+        Some(&b'>') => punc(PunctuatorToken::RAngle, 1, token),
+        Some(&b'/') if bytes.get(1) != Some(&b'/') && bytes.get(1) != Some(&b'*') => {
+            punc(PunctuatorToken::Slash, 1, token)
+        }
+        Some(&quote) if quote == b'\'' || quote == b'"' => {
+            match code[1..].find(quote as char) {
+                Some(len) => {
+                    string(Cow::from(&code[1..len + 1]), Cow::from(&code[..len + 2]), false, token);
+                    len + 2
+                }
+                None => invalid(InvalidToken::String, code.len(), token),
+            }
+        }
+        _ => {
+            let mut size = read_next(code, hint, token);
+            if let tokens::Token::IdentifierName(_) = *token {
+                while bytes.get(size) == Some(&b'-') {
+                    size += 1;
+                    size += code[size..]
+                        .char_indices()
+                        .find(|&(_, c)| !(c == '$' || c == '_' || c.is_ascii_alphanumeric() || (!c.is_ascii() && c.is_id_continue())))
+                        .map(|(i, _)| i)
+                        .unwrap_or(code.len() - size);
+                }
+                *token = tokens::IdentifierNameToken {
+                    name: (&code[..size]).into(),
+                }.into();
+            }
+            size
+        }
     }
-
-    number(val, code[0..offset].into(), token);
-    offset
 }
 
-fn tok_ident<'code, 'tok>(code: &'code str, token: &mut tokens::Token<'code>) -> usize {
+fn tok_fractional<'code, 'tok>(code: &'code str, token: &'tok mut tokens::Token<'code>) -> usize {
     let bytes = code.as_bytes();
-    let index = 0;
+    let mut offset = 1;
 
-    let mut end = index;
+    offset += decimal_digits(&bytes[offset..]);
+    offset += exponent_len(&bytes[offset..]);
 
-    for (i, &b) in bytes.iter().enumerate() {
-        match b {
-            b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' | b'$' | b'_'  => {
-                end = i + 1;
-            }
+    decimal(&code[..offset], token)
+}
 
-            // TODO: Handle all the other ident characters
-            _ => {
-                break;
+fn tok_ident<'code, 'tok>(code: &'code str, token: &mut tokens::Token<'code>) -> usize {
+    let mut end = 0;
+    // The name with its escapes decoded, once the first one is found.
+    let mut escaped: Option<String> = None;
+
+    while let Some(c) = code[end..].chars().next() {
+        let (c, len) = if c == '\\' {
+            match ident_escape(&code[end + 1..]) {
+                Some((c, len)) => (c, 1 + len),
+                None => break,
             }
+        } else {
+            (c, c.len_utf8())
+        };
+
+        let valid = match c {
+            'a'..='z' | 'A'..='Z' | '$' | '_' => true,
+            '0'..='9' | '\u{200C}' | '\u{200D}' => end != 0,
+            _ if c.is_ascii() => false,
+            _ if end == 0 => c.is_id_start(),
+            _ => c.is_id_continue(),
+        };
+        if !valid {
+            break;
         }
+
+        if len != c.len_utf8() {
+            escaped.get_or_insert_with(|| code[..end].to_string()).push(c);
+        } else if let Some(ref mut name) = escaped {
+            name.push(c);
+        }
+        end += len;
+    }
+
+    if end == 0 {
+        let size = code.chars().next().map(char::len_utf8).unwrap_or(0);
+        return invalid(InvalidToken::Codepoint, size, token);
     }
 
     *token = tokens::IdentifierNameToken {
-        name: (&code[..end]).into(),
+        name: match escaped {
+            Some(name) => name.into(),
+            None => (&code[..end]).into(),
+        },
     }.into();
 
-    end - index
+    end
+}
+
+// The character and length of a "\u" escape following a backslash in an
+// identifier, or None if it is not a valid escape.
+fn ident_escape(code: &str) -> Option<(char, usize)> {
+    if !code.starts_with('u') {
+        return None;
+    }
+    let len = escape_len(code)?;
+    let digits = if code.as_bytes().get(1) == Some(&b'{') { &code[2..len - 1] } else { &code[1..len] };
+
+    u32::from_str_radix(digits, 16).ok().and_then(char::from_u32).map(|c| (c, len))
 }
 
 fn tok_template_head<'code, 'tok>(code: &'code str, token: &mut tokens::Token<'code>) -> usize {
@@ -403,7 +639,8 @@ fn tok_template_head<'code, 'tok>(code: &'code str, token: &mut tokens::Token<'c
         }
     }
 
-    unimplemented!("template string")
+    // Without a closing backtick, the rest of the code is in the template.
+    invalid(InvalidToken::Template, code.len(), token)
 
 }
 
@@ -411,22 +648,15 @@ fn tok_num<'code, 'tok>(code: &'code str, token: &mut tokens::Token<'code>) -> u
     let bytes = code.as_bytes();
     let len = code.len();
 
-    let (mut val, mut offset) = parse_int_literal(bytes);
+    let mut offset = decimal_digits(bytes);
 
     if offset < len && bytes[offset] == b'.' {
-        let (frac, num) = parse_decimal_digits(&bytes[offset + 1..]);
-        val += frac;
-        offset += num + 1;
+        offset += 1 + decimal_digits(&bytes[offset + 1..]);
     }
 
-    let (exp, num) = parse_exponent(&bytes[offset..]);
-    if num != 0 {
-        val = val * 10f64.powi(exp);
-        offset += num;
-    }
+    offset += exponent_len(&bytes[offset..]);
 
-    number(val, code[..offset].into(), token);
-    offset
+    decimal(&code[..offset], token)
 }
 
 fn tok_zero_num<'code, 'tok>(code: &'code str, token: &mut tokens::Token<'code>) -> usize {
@@ -434,114 +664,76 @@ fn tok_zero_num<'code, 'tok>(code: &'code str, token: &mut tokens::Token<'code>)
     let len = code.len();
     let index = 0;
 
-    let b = if index + 2 < len { bytes[index + 1] } else { 0 };
+    let b = if index + 1 < len { bytes[index + 1] } else { 0 };
     match b {
-        b'x' | b'X' => {
-            let mut val = (bytes[index + 2] - b'0') as f64;
+        b'x' | b'X' => tok_radix_num(code, 16, token),
+        b'o' | b'O' => tok_radix_num(code, 8, token),
+        b'b' | b'B' => tok_radix_num(code, 2, token),
+        b'.' => {
+            // 0.455
+            // 0.456e5
 
-            let mut i = index + 3;
-            loop {
-                if i == len { break }
+            let mut offset = 2;
 
-                match bytes[i] {
-                    v @ b'0'...b'9' => {
-                        val *= 16f64;
-                        val += (v - b'0') as f64;
-                    }
-                    v @ b'a'...b'f' => {
-                        val *= 16f64;
-                        val += (v - b'a') as f64;
-                    }
-                    v @ b'A'...b'F' => {
-                        val *= 16f64;
-                        val += (v - b'A') as f64;
-                    }
-                    _ => break,
-                }
+            offset += decimal_digits(&bytes[offset..]);
+            offset += exponent_len(&bytes[offset..]);
 
-                i += 1;
-            }
-
-            number(val, code[index..i].into(), token);
-            i
+            decimal(&code[..offset], token)
         }
-        b'o' | b'O' => {
-            let mut val = (bytes[index + 2] - b'0') as f64;
-
-            let mut i = index + 3;
-            loop {
-                if i == len { break }
-
-                match bytes[i] {
-                    v @ b'0'...b'7' => {
-                        val *= 8f64;
-                        val += (v - b'0') as f64;
-                    }
-                    b'8' | b'9' => {
-                        unimplemented!("invalid number error")
-                    }
-                    _ => break,
+        b'0'..=b'9' => {
+            // Legacy octals like "010", and decimals with a leading zero like
+            // "08", which the parser only allows in sloppy mode.
+            let digits = 1 + decimal_digits(&bytes[1..]);
+            let size = if bytes[1..digits].iter().all(|&b| b < b'8') {
+                let value = bytes[1..digits].iter().fold(0f64, |value, &b| value * 8f64 + (b - b'0') as f64);
+                number(value, code[..digits].into(), token);
+                digits
+            } else {
+                let mut offset = digits;
+                if offset < len && bytes[offset] == b'.' {
+                    offset += 1 + decimal_digits(&bytes[offset + 1..]);
                 }
+                offset += exponent_len(&bytes[offset..]);
 
-                i += 1;
-            }
-
-            number(val, code[index..i].into(), token);
-            i
-        }
-        b'b' | b'B' => {
-            let mut val = (bytes[index + 2] - b'0') as f64;
-
-            let mut i = index + 3;
-            loop {
-                if i == len { break }
-
-                match bytes[i] {
-                    v @ b'0'...b'1' => {
-                        val *= 2f64;
-                        val += (v - b'0') as f64;
-                    }
-                    b'2'...b'9' => {
-                        unimplemented!("invalid number error")
-                    }
-                    _ => break,
-                }
+                decimal(&code[..offset], token)
+            };
 
-                i += 1;
+            if let tokens::Token::NumericLiteral(ref mut n) = *token {
+                n.legacy_octal = true;
             }
-
-            number(val, code[index..i].into(), token);
-            i
+            size
         }
-        b'.' => {
-            // 0.455
-            // 0.456e5
-
-            let mut val = 0f64;
-            let mut offset = 2;
+        _ => {
+            let offset = 1 + exponent_len(&bytes[1..]);
 
-            let (frac, num) = parse_decimal_digits(&bytes[offset..]);
-            if num != 0 {
-                val += frac;
-                offset += num;
-            }
+            decimal(&code[..offset], token)
+        }
+    }
+}
 
-            let (exp, num) = parse_exponent(&bytes[offset..]);
-            if num != 0 {
-                val = val * 10f64.powi(exp);
-                offset += num;
-            }
+fn tok_radix_num<'code>(code: &'code str, radix: u32, token: &mut tokens::Token<'code>) -> usize {
+    let bytes = code.as_bytes();
+    let mut val = 0f64;
+    let mut valid = true;
 
-            number(val, code[..offset].into(), token);
-            offset
-        }
-        _ => {
-            let (_, num) = parse_exponent(bytes);
+    let mut i = 2;
+    while i < bytes.len() {
+        // Decimal digits outside of the radix are read to report the whole
+        // number as invalid.
+        let digit = match (bytes[i] as char).to_digit(radix.max(10)) {
+            Some(digit) => digit,
+            None => break,
+        };
+        valid = valid && digit < radix;
+        val = val * radix as f64 + digit as f64;
+        i += 1;
+    }
 
-            number(0f64, code[index..index + num + 1].into(), token);
-            index + num + 1
-        }
+    if i == 2 || !valid {
+        return invalid(InvalidToken::Numeric, i, token);
     }
+    number(val, code[..i].into(), token);
+    i
 }
 
 fn tok_slash<'code, 'tok>(code: &'code str, hint: &Hint, token: &mut tokens::Token<'code>) -> usize {
@@ -588,17 +780,17 @@ fn tok_slash<'code, 'tok>(code: &'code str, hint: &Hint, token: &mut tokens::Tok
             }
         }
 
-        unimplemented!("unterminated block comment");
+        invalid(InvalidToken::Comment, code.len(), token)
     } else if hint.expression {
+        let line = line_len(code);
         let mut end = index + 1;
 
         let mut in_escape = false;
         let mut in_class = false;
-        for (i, &b) in bytes.iter().enumerate().skip(1) {
+        for (i, &b) in bytes[..line].iter().enumerate().skip(1) {
             match b {
                 _ if in_escape => {
                     in_escape = false;
-                    // TODO: Throw if newlines?
                 }
                 b'\\' => {
                     in_escape = true;
@@ -617,7 +809,7 @@ fn tok_slash<'code, 'tok>(code: &'code str, hint: &Hint, token: &mut tokens::Tok
             }
         }
         if end == index + 1 {
-            unimplemented!("unterminated regex");
+            return invalid(InvalidToken::RegularExpression, line, token);
         }
 
         let mut flag_end = end + 1;
@@ -669,7 +861,7 @@ fn tok_curly_close<'code, 'tok>(code: &'code str, hint: &Hint, token: &mut token
             }
         }
 
-        unimplemented!("template tail")
+        invalid(InvalidToken::Template, code.len(), token)
     } else {
         punc(PunctuatorToken::CurlyClose, 1, token)
     }
@@ -677,207 +869,112 @@ fn tok_curly_close<'code, 'tok>(code: &'code str, hint: &Hint, token: &mut token
 }
 
 fn tok_str<'code, 'tok>(t: u8, code: &'code str, token: &mut tokens::Token<'code>) -> usize {
-    let index = 0;
     let bytes = code.as_bytes();
+    let mut valid = true;
+    let mut legacy_octal = false;
 
-    let mut pieces = vec![];
-
-    let start = index;
-    let mut end = start;
-
-    let mut in_escape = 0;
-    let mut in_hex_escape = false;
-    let mut in_unicode_escape = false;
-    let mut in_long_unicode_escape = false;
-    let mut ignore_nl = false;
-
-    let s: usize = index + 1;
-    for (i, &b) in bytes.iter().enumerate().skip(1) {
-        // TODO: Build state transition tables for parsing escapes
-
-        if in_escape == 1 {
-            match b {
-                b'\r' => {
-                    ignore_nl = true;
-                    in_escape = 0;
-                    continue;
-                }
-                b'\n' => {
-                    in_escape = 0;
-                    continue;
-                }
-                b'\xE2' | b'\xE2' if code[i..].starts_with(NS_PS) || code[i..].starts_with(NS_LS) => {
-                    in_escape = 0;
-                    continue;
-                }
-                b'\'' | b'"' | b'\\' | b'b' | b'f' | b'n' | b'r' | b't' | b'v' => {
-                    in_escape = 0;
-                    continue;
-                }
-                b'0' => {
-                    in_escape = 0;
-                    continue;
-                }
-                b'x' => {
-                    in_hex_escape = true;
-                }
-                b'u' => {
-                    in_unicode_escape = true;
-                }
-                b'1'...b'9' => {
-                    panic!("numbers not allowed");
-                }
-                _ => {
-                    unimplemented!("totally bad escapes");
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b if b == t => {
+                if !valid {
+                    return invalid(InvalidToken::String, i + 1, token);
                 }
-            }
-        }
-        if in_hex_escape && in_escape == 3 {
-            in_escape = 0;
-            in_hex_escape = false;
-        }
-        if in_unicode_escape {
-            if in_escape == 2 && b == b'{' {
-                in_long_unicode_escape = true;
-            }
-
-            if !in_long_unicode_escape && in_escape == 5 {
-                in_escape = 0;
-                in_unicode_escape = false;
-            }
 
-            if in_long_unicode_escape && b == b'}' {
-                in_escape = 0;
-                in_unicode_escape = false;
-                in_long_unicode_escape = false;
+                // String values are kept in their escaped source form.
+                string(Cow::from(&code[1..i]), Cow::from(&code[..i + 1]), legacy_octal, token);
+                return i + 1;
             }
-        }
-
-        if in_escape != 0 {
-            in_escape += 1;
-            continue;
-        }
-
-        match b {
             b'\\' => {
-                // pieces.push(Cow::from(&code[s..i]));
-                // start = i + 1;
-
-                in_escape = 1;
-            }
-            b'\"' if t == b'\"' => {
-                pieces.push(Cow::from(&code[s..i]));
-                end = i + 1;
-                break;
-            },
-            b'\'' if t == b'\'' => {
-                pieces.push(Cow::from(&code[s..i]));
-                end = i + 1;
-                break;
-            },
-            b'\n' if ignore_nl => {
-
+                legacy_octal |= is_legacy_octal_escape(&bytes[i + 1..]);
+
+                match escape_len(&code[i + 1..]) {
+                    Some(len) => i += 1 + len,
+                    None => {
+                        // Keep reading to the closing quote to report the
+                        // whole string as invalid.
+                        valid = false;
+                        i += 1;
+                    }
+                }
             }
+            // Unlike other line terminators, U+2028 and U+2029 are allowed
+            // in strings, as they are in JSON.
             b'\r' | b'\n' => {
-                // Invalid string
-                panic!("string with newlines")
+                return invalid(InvalidToken::String, i, token);
             }
-            b'\xE2' if code[i..].starts_with(NS_PS) || code[i..].starts_with(NS_LS) => {
-                // Invalid string
-                panic!("string with newlines")
-            }
-            _ => { }
+            _ => i += 1,
         }
     }
 
-    let raw = Cow::from(&code[start..end]);
-
-    if pieces.len() == 1 {
-        string(pieces.pop().unwrap(), raw, token);
-    } else {
-        let decoded: String = pieces.into_iter().collect();
-        string(decoded.into(), raw, token);
-    }
-    end
+    invalid(InvalidToken::String, code.len(), token)
 }
 
+// The length of the escape sequence following a backslash in a string, or
+// None if it is not a valid escape.
+fn escape_len(code: &str) -> Option<usize> {
+    let bytes = code.as_bytes();
+    let hex_digits = |bytes: &[u8]| bytes.iter().take_while(|b| b.is_ascii_hexdigit()).count();
 
-fn parse_int_literal(bytes: &[u8]) -> (f64, usize) {
-    let mut value = 0f64;
-
-    let mut i = 0;
-    while i < bytes.len() {
-        match bytes[i] {
-            b'0' => {
-                // i = 1;
-
-                if value == 0f64 {
-                    break;
-                }
-                value *= 10f64;
-            }
-            v @ b'1' ... b'9' => {
-                value *= 10f64;
-                value += (v - b'0') as f64;
-            }
-            _ => break,
+    match bytes.first() {
+        None => Some(0),
+        Some(&b'\r') if bytes.get(1) == Some(&b'\n') => Some(2),
+        Some(&b'x') => {
+            if hex_digits(&bytes[1..]) >= 2 { Some(3) } else { None }
         }
-        i += 1;
+        Some(&b'u') if bytes.get(1) == Some(&b'{') => {
+            let digits = hex_digits(&bytes[2..]);
+            let valid = digits > 0
+                && bytes.get(2 + digits) == Some(&b'}')
+                && u32::from_str_radix(&code[2..2 + digits], 16).map(|c| c <= 0x10FFFF).unwrap_or(false);
+            if valid { Some(3 + digits) } else { None }
+        }
+        Some(&b'u') => {
+            if hex_digits(&bytes[1..]) >= 4 { Some(5) } else { None }
+        }
+        Some(_) => code.chars().next().map(char::len_utf8),
     }
-
-    (value, i)
 }
-fn parse_decimal_digits(bytes: &[u8]) -> (f64, usize) {
-    let mut value = 0f64;
-    let mut factor = 0.1;
 
-    let mut i = 0;
-    while i < bytes.len() {
-        match bytes[i] {
-            v @ b'0' ... b'9' => {
-                value += factor * (v - b'0') as f64;
-                factor /= 10f64;
-            }
-            _ => break,
-        }
-        i += 1;
+// Whether the escape sequence following a backslash is a legacy octal like
+// "\1" or "\01", or one of "\8" and "\9". A "\0" on its own is a null
+// character instead.
+fn is_legacy_octal_escape(bytes: &[u8]) -> bool {
+    match bytes.first() {
+        Some(&b'0') => bytes.get(1).is_some_and(u8::is_ascii_digit),
+        Some(b) => (b'1'..=b'9').contains(b),
+        None => false,
     }
-
-    (value, i)
 }
 
-fn parse_exponent(bytes: &[u8]) -> (i32, usize) {
-    let b = if bytes.len() >= 2 { bytes[0] } else { 0 };
-    let (offset, sign) = match b {
-        b'e' | b'E' => {
-            match bytes[1] {
-                b'+' => (2, 1),
-                b'-' => (2, -1),
-                _ => (1, 1),
-            }
+// A decimal number, whose value is parsed from the literal as a whole since
+// adding up its digits would lose precision.
+fn decimal<'code>(raw: &'code str, token: &mut tokens::Token<'code>) -> usize {
+    match raw.parse() {
+        Ok(value) => {
+            number(value, raw.into(), token);
+            raw.len()
         }
-        _ => return (0, 0),
-    };
-
-    let mut value = 0;
+        // An exponent without digits, like "1e".
+        Err(_) => invalid(InvalidToken::Numeric, raw.len(), token),
+    }
+}
 
-    let mut i = offset;
-    while i < bytes.len() {
-        match bytes[i] {
-            v @ b'0' ... b'9' => {
-                let val = (v - b'0') as i32;
-                if val != 0 || value != 0 {
-                    value *= 10;
-                    value += val;
-                }
-            }
-            _ => break,
-        }
+fn decimal_digits(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+}
 
-        i += 1;
+fn exponent_len(bytes: &[u8]) -> usize {
+    match bytes.first() {
+        Some(&b'e') | Some(&b'E') => {}
+        _ => return 0,
     }
+    let sign = match bytes.get(1) {
+        Some(&b'+') | Some(&b'-') => 1,
+        _ => 0,
+    };
 
-    (sign * value, i)
+    1 + sign + decimal_digits(&bytes[1 + sign..])
 }
 
 #[cfg(test)]
@@ -1044,7 +1141,7 @@ mod tests {
 
     #[test]
     fn it_parses_strings() {
-        fn assert_string(code: &str, value: &str) {
+        fn assert_string(code: &str, value: &str, legacy_octal: bool) {
             let s: String = vec![code, " "].into_iter().collect();
 
             assert_eq!(
@@ -1052,16 +1149,23 @@ mod tests {
                 (
                     tokens::StringLiteralToken {
                         value: value.into(),
+                        legacy_octal,
                     }.into(),
                     code.len(),
                 ),
             );
         }
 
-        assert_string("'a real string'", "a real string");
-        assert_string("'a real\"string'", "a real\"string");
-        assert_string("\"a real string\"", "a real string");
-        assert_string("\"a real'string\"", "a real'string");
+        assert_string("'a real string'", "a real string", false);
+        assert_string("'a real\"string'", "a real\"string", false);
+        assert_string("\"a real string\"", "a real string", false);
+        assert_string("\"a real'string\"", "a real'string", false);
+        assert_string("'a\\0b'", "a\\0b", false);
+        assert_string("'a\\01'", "a\\01", true);
+        assert_string("'\\377'", "\\377", true);
+        assert_string("'\\8'", "\\8", true);
+        assert_string("'\\\\1'", "\\\\1", false);
+        assert_string("'a\u{2028}b\u{2029}c'", "a\u{2028}b\u{2029}c", false);
     }
 
     #[test]
@@ -1090,7 +1194,7 @@ mod tests {
 
     #[test]
     fn it_parses_numbers() {
-        fn assert_number(code: &str, value: f64) {
+        fn assert_number(code: &str, value: f64, legacy_octal: bool) {
             let s: String = vec![code, " "].into_iter().collect();
 
             assert_eq!(
@@ -1098,31 +1202,42 @@ mod tests {
                 (
                     tokens::NumericLiteralToken {
                         value,
+                        legacy_octal,
                     }.into(),
                     code.len(),
                 ),
             );
         }
 
-        assert_number("0x4", 4f64);
-        assert_number("0x40", 64f64);
-        assert_number("0o4", 4f64);
-        assert_number("0o40", 32f64);
-        assert_number("0b0101", 5f64);
-        assert_number("0b1100000", 96f64);
-        assert_number("1", 1f64);
-        assert_number("145", 145f64);
-        assert_number("14.5", 14.5f64);
-        assert_number("14.5e2", 1450f64);
-        assert_number("14.5e-2", 0.145f64);
-        assert_number("14.5e+2", 1450f64);
-        assert_number("14e2", 1400f64);
-        assert_number("14e-2", 0.14f64);
-        assert_number("14e+2", 1400f64);
-        assert_number(".14", 0.14f64);
-        assert_number(".14e2", 14.000000000000002f64); // TODO: Wrong
-        assert_number(".14e-2", 0.0014000000000000002f64); // TODO: Wrong
-        assert_number(".14e+2", 14.000000000000002f64); // TODO: Wrong
+        assert_number("0x4", 4f64, false);
+        assert_number("0x40", 64f64, false);
+        assert_number("0o4", 4f64, false);
+        assert_number("0o40", 32f64, false);
+        assert_number("0b0101", 5f64, false);
+        assert_number("0b1100000", 96f64, false);
+        assert_number("1", 1f64, false);
+        assert_number("145", 145f64, false);
+        assert_number("14.5", 14.5f64, false);
+        assert_number("14.5e2", 1450f64, false);
+        assert_number("14.5e-2", 0.145f64, false);
+        assert_number("14.5e+2", 1450f64, false);
+        assert_number("14e2", 1400f64, false);
+        assert_number("14e-2", 0.14f64, false);
+        assert_number("14e+2", 1400f64, false);
+        assert_number(".14", 0.14f64, false);
+        assert_number(".14e2", 14f64, false);
+        assert_number(".14e-2", 0.0014f64, false);
+        assert_number(".14e+2", 14f64, false);
+        assert_number("0xfF", 255f64, false);
+        assert_number("0.1", 0.1f64, false);
+        assert_number("5e120", 5e120f64, false);
+        assert_number("0", 0f64, false);
+        assert_number("010", 8f64, true);
+        assert_number("0777", 511f64, true);
+        assert_number("00", 0f64, true);
+        assert_number("08", 8f64, true);
+        assert_number("019.5", 19.5f64, true);
+        assert_number("09e1", 90f64, true);
     }
 
     #[test]
@@ -1166,6 +1281,118 @@ mod tests {
         }
 
         assert_identifier("omg", "omg");
+        assert_identifier("$_a1", "$_a1");
+        assert_identifier("café", "café");
+        assert_identifier("π\u{200C}x", "π\u{200C}x");
+        assert_identifier("\\u0061", "a");
+        assert_identifier("a\\u{62}c", "abc");
+        assert_identifier("\\u{3C0}\\u0031", "π1");
+    }
+
+    #[test]
+    fn it_reports_invalid_tokens() {
+        fn assert_invalid(code: &str, hint: &Hint, invalid: InvalidToken, size: usize) {
+            assert_eq!(read_token(code, hint), (invalid.into(), size));
+        }
+
+        let expression = Hint::default().expression(true);
+        let template = Hint::default().template(true);
+
+        assert_invalid("'abc", &Hint::default(), InvalidToken::String, 4);
+        assert_invalid("'ab\nc'", &Hint::default(), InvalidToken::String, 3);
+        assert_invalid("'\\x4' + a", &Hint::default(), InvalidToken::String, 5);
+        assert_invalid("'\\u{110000}'", &Hint::default(), InvalidToken::String, 12);
+        assert_invalid("`abc", &Hint::default(), InvalidToken::Template, 4);
+        assert_invalid("}abc", &template, InvalidToken::Template, 4);
+        assert_invalid("/* a", &Hint::default(), InvalidToken::Comment, 4);
+        assert_invalid("/abc\ndef/", &expression, InvalidToken::RegularExpression, 4);
+        assert_invalid("0x;", &Hint::default(), InvalidToken::Numeric, 2);
+        assert_invalid("0b102", &Hint::default(), InvalidToken::Numeric, 5);
+        assert_invalid("1e+;", &Hint::default(), InvalidToken::Numeric, 3);
+        assert_invalid("#a", &Hint::default(), InvalidToken::Codepoint, 1);
+        assert_invalid("😀", &Hint::default(), InvalidToken::Codepoint, 4);
+        assert_invalid("\\u0031", &Hint::default(), InvalidToken::Codepoint, 1);
+        assert_invalid("\\u{D800}", &Hint::default(), InvalidToken::Codepoint, 1);
+    }
+
+    #[test]
+    fn it_parses_jsx_text_and_tags() {
+        fn text(code: &str) -> (tokens::Token<'_>, usize) {
+            let mut t = tokens::EOFToken {}.into();
+            let size = read_jsx_text(code, &mut t);
+            (t, size)
+        }
+        fn tag(code: &str) -> (tokens::Token<'_>, usize) {
+            let mut t = tokens::EOFToken {}.into();
+            let size = read_jsx_tag(code, &Hint::default(), &mut t);
+            (t, size)
+        }
+
+        assert_eq!(text(" a\n b{c}"), (tokens::JSXTextToken { value: " a\n b".into() }.into(), 5));
+        assert_eq!(text("</a>"), (PunctuatorToken::LAngle.into(), 1));
+        assert_eq!(text("} a"), (InvalidToken::JSXText.into(), 1));
+        assert_eq!(tag("data-foo-bar='x'"), (tokens::IdentifierNameToken { name: "data-foo-bar".into() }.into(), 12));
+        assert_eq!(tag("'a\\b'"), (tokens::StringLiteralToken { value: "a\\b".into(), legacy_octal: false }.into(), 5));
+        assert_eq!(tag("/>"), (PunctuatorToken::Slash.into(), 1));
+        assert_eq!(tag(">>"), (PunctuatorToken::RAngle.into(), 1));
+    }
+
+    #[test]
+    fn it_parses_flow_comment_markers() {
+        fn marker(code: &str, in_comment: bool) -> (Option<(tokens::Token<'_>, usize)>, bool) {
+            let mut t = tokens::EOFToken {}.into();
+            let mut in_comment = in_comment;
+            let size = read_flow_comment_marker(code, &mut in_comment, &mut t);
+            (size.map(|size| (t, size)), in_comment)
+        }
+        let whitespace = tokens::Token::from(tokens::WhitespaceToken {});
+
+        assert_eq!(marker("/*: string */", false), (Some((PunctuatorToken::Colon.into(), 3)), true));
+        assert_eq!(marker("/* :: type A = B; */", false), (Some((whitespace.clone(), 5)), true));
+        assert_eq!(marker("/*flow-include type A = B; */", false), (Some((whitespace.clone(), 14)), true));
+        assert_eq!(marker("*/ a", true), (Some((whitespace, 2)), false));
+        assert_eq!(marker("/* a: b */", false), (None, false));
+        assert_eq!(marker("* b */", true), (None, true));
+    }
+
+    #[test]
+    fn it_parses_html_comments() {
+        fn tokens<'code>(code: &'code str, hint: &Hint) -> Vec<tokens::Token<'code>> {
+            let mut tokenizer = code.into_tokenizer();
+            let mut out = vec![];
+            loop {
+                let mut token = tokens::EOFToken {}.into();
+                tokenizer.next_token(hint, (&mut token, &mut TokenRange::default()));
+                match token {
+                    tokens::Token::EOF(_) => return out,
+                    tokens::Token::Whitespace(_) | tokens::Token::LineTerminator(_) => {}
+                    token => out.push(token),
+                }
+            }
+        }
+        fn html(format: CommentFormat, value: &str) -> tokens::Token<'_> {
+            CommentToken { format, value: value.into() }.into()
+        }
+        fn ident(name: &str) -> tokens::Token<'_> {
+            tokens::IdentifierNameToken { name: name.into() }.into()
+        }
+        let hint = Hint::default().html_comments(true);
+
+        assert_eq!(tokens("a <!-- b\nc", &hint), vec![ident("a"), html(CommentFormat::HTMLOpen, " b"), ident("c")]);
+        assert_eq!(tokens("--> a\nb", &hint), vec![html(CommentFormat::HTMLClose, " a"), ident("b")]);
+        assert_eq!(tokens("a\n  /* b */ --> c", &hint), vec![ident("a"), html(CommentFormat::Block, " b "), html(CommentFormat::HTMLClose, " c")]);
+        assert_eq!(tokens("/*\n*/--> a", &hint), vec![html(CommentFormat::Block, "\n"), html(CommentFormat::HTMLClose, " a")]);
+        assert_eq!(tokens("a-->b", &hint), vec![ident("a"), PunctuatorToken::MinusMinus.into(), PunctuatorToken::RAngle.into(), ident("b")]);
+        assert_eq!(
+            tokens("a <!-- b", &Hint::default()),
+            vec![ident("a"), PunctuatorToken::LAngle.into(), PunctuatorToken::Exclam.into(), PunctuatorToken::MinusMinus.into(), ident("b")],
+        );
+        assert_eq!(tokens("--> a", &Hint::default()), vec![PunctuatorToken::MinusMinus.into(), PunctuatorToken::RAngle.into(), ident("a")]);
+
+        let mut tokenizer = SliceTokenizer::at("a;\n  --> b", Position { offset: 5, line: 2, column: 2 });
+        let mut token = tokens::EOFToken {}.into();
+        tokenizer.next_token(&hint, (&mut token, &mut TokenRange::default()));
+        assert_eq!(token, html(CommentFormat::HTMLClose, " b"));
     }
 
     #[test]
@@ -1196,6 +1423,8 @@ mod tests {
         assert_punc(",", Comma);
         assert_punc("?", Question);
         assert_punc(":", Colon);
+        assert_punc("::", ColonColon);
+        assert_punc("@", At);
         assert_punc("~", Tilde);
         assert_punc("<", LAngle);
         assert_punc("<=", LAngleEq);
@@ -13,13 +13,14 @@ pub enum PunctuatorToken {
   Tilde, // ~
   Question, // ?
   Colon, // :
+  ColonColon, // ::
+  At, // @
   Period, // .
   Ellipsis, // ...
   LAngle, // <
   LAngleEq, // <=
   LAngleAngle, // <<
   LAngleAngleEq, // <<=
-  LAngleExclamDashDash, // <!--
   RAngle, // >
   RAngleEq, // >=
   RAngleAngle, // >>
@@ -39,7 +40,6 @@ pub enum PunctuatorToken {
   Minus, // -
   MinusEq, // -=
   MinusMinus, // --
-  MinusMinusAngle, // -->
   Percent, // %
   PercentEq, // %=
   Star, // *
@@ -69,6 +69,7 @@ pub enum CommentFormat {
   Block,
   HTMLOpen,
   HTMLClose,
+  Hashbang,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -123,6 +124,9 @@ impl<'a> From<IdentifierNameToken<'a>> for Token<'a> {
 #[derive(Debug, Clone, PartialEq)]
 pub struct NumericLiteralToken {
   pub value: f64,
+  // A legacy octal like "010", or a decimal with a leading zero like "08",
+  // which are only allowed in sloppy mode.
+  pub legacy_octal: bool,
 }
 impl From<NumericLiteralToken> for Token<'static> {
     fn from(t: NumericLiteralToken) -> Self {
@@ -133,6 +137,9 @@ impl From<NumericLiteralToken> for Token<'static> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StringLiteralToken<'a> {
   pub value: Cow<'a, str>,
+  // Whether the string has a legacy octal escape like "\1" or "\08", which
+  // are only allowed in sloppy mode.
+  pub legacy_octal: bool,
 }
 impl<'a> From<StringLiteralToken<'a>> for Token<'a> {
     fn from<'b>(t: StringLiteralToken<'b>) -> Token<'b> {
@@ -164,6 +171,16 @@ impl<'a> From<TemplateToken<'a>> for Token<'a> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JSXTextToken<'a> {
+  pub value: Cow<'a, str>,
+}
+impl<'a> From<JSXTextToken<'a>> for Token<'a> {
+    fn from<'b>(t: JSXTextToken<'b>) -> Token<'b> {
+        Token::JSXText(t)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct EOFToken { }
 impl From<EOFToken> for Token<'static> {
@@ -183,11 +200,9 @@ pub enum Token<'a> {
     NumericLiteral(NumericLiteralToken),
     StringLiteral(StringLiteralToken<'a>),
     Template(TemplateToken<'a>),
+    JSXText(JSXTextToken<'a>),
     EOF(EOFToken),
-
-    // Boxed so we can jam in helpful info without making the overall token
-    // structure larger than it needs to be.
-    // Invalid(Box<InvalidToken>),
+    Invalid(InvalidToken),
 }
 
 impl<'a> Default for Token<'a> {
@@ -196,32 +211,44 @@ impl<'a> Default for Token<'a> {
   }
 }
 
-// #[derive(Clone, Debug, PartialEq, Eq)]
-// pub enum InvalidToken {
-//   Codepoints(InvalidCodepoints),
-//   String(InvalidString),
-//   Template(InvalidTemplate),
-//   Numeric(InvalidNumeric),
-//   RegularExpression(InvalidRegularExpression),
-// }
-
-// // Has random unknown code points
-
-// #[derive(Clone, Debug, PartialEq, Eq)]
-// pub struct InvalidCodepoints {}
-
-// // Has a newline or unknown escape?
-// #[derive(Clone, Debug, PartialEq, Eq)]
-// pub struct InvalidString {}
-
-// // Has unknown escape
-// #[derive(Clone, Debug, PartialEq, Eq)]
-// pub struct InvalidTemplate {}
-
-// // Has unknown number format or trailing decimal
-// #[derive(Clone, Debug, PartialEq, Eq)]
-// pub struct InvalidNumeric {}
-
-// // Has a newline or escape in flags
-// #[derive(Clone, Debug, PartialEq, Eq)]
-// pub struct InvalidRegularExpression {}
+/// Code that could not be read as any token, reported as a token of its own
+/// so that the parser fails with an error where it is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidToken {
+  // A code point that cannot start a token.
+  Codepoint,
+  // A string with a line terminator or without a closing quote.
+  String,
+  // A template without a closing backtick.
+  Template,
+  // A number without digits or with digits outside of its radix.
+  Numeric,
+  // A regular expression without a closing slash.
+  RegularExpression,
+  // A block comment without a closing "*/".
+  Comment,
+  // A ">" or "}" in the text of a JSX element.
+  JSXText,
+  // A legacy octal number or escape in strict mode code, which the parser
+  // reports since the tokenizer doesn't know which code is strict.
+  LegacyOctal,
+}
+impl InvalidToken {
+    pub fn message(&self) -> &'static str {
+        match *self {
+            InvalidToken::Codepoint => "Invalid character",
+            InvalidToken::String => "Unterminated string",
+            InvalidToken::Template => "Unterminated template",
+            InvalidToken::Numeric => "Invalid number",
+            InvalidToken::RegularExpression => "Unterminated regular expression",
+            InvalidToken::Comment => "Unterminated comment",
+            InvalidToken::JSXText => "Unexpected '>' or '}' in JSX text",
+            InvalidToken::LegacyOctal => "Legacy octal literals and escapes are only allowed in sloppy mode",
+        }
+    }
+}
+impl From<InvalidToken> for Token<'static> {
+    fn from(t: InvalidToken) -> Self {
+        Token::Invalid(t)
+    }
+}
@@ -0,0 +1,246 @@
+//! Snapshot tests of the parser's output.
+//!
+//! Every directory under `tests/fixtures` with an `input.js` is a fixture.
+//! The input is parsed and compared with the snapshots next to it:
+//!
+//! - `output.json`, the ESTree AST, without the "loc" and "range" fields
+//!   since they repeat "start" and "end".
+//! - `output.js`, the code printed from the AST with the pretty options.
+//! - `error.txt`, the diagnostic, for inputs that fail to parse.
+//!
+//! Code that parses must also print code that parses to the same AST.
+//!
+//! Parser options come from `options.json` files, in the fixture's directory
+//! or any directory above it up to `tests/fixtures`, with the closest file
+//! taking precedence for each option. The options match the fields of
+//! `ParserOptions`:
+//!
+//! - `"sourceType"`, either `"script"`, the default, `"module"` or
+//!   `"unambiguous"`.
+//! - `"ecmaVersion"`, as a year or edition number.
+//! - `"jsx"`, `"decorators"`, `"experimentalOperators"`, `"typescript"` and
+//!   `"flow"`, to enable the syntax extensions.
+//! - `"allowReturnOutsideFunction"`, `"allowHashbang"`, `"preserveParens"`,
+//!   `"attachComments"` and `"annexB"`.
+//!
+//! Run with `UPDATE_FIXTURES=1` to write the snapshots from the parser's
+//! current output instead of comparing them.
+
+extern crate jsparse;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use jsparse::ast::{self, FormatOptions};
+use jsparse::estree::{self, Value};
+use jsparse::parser::{self, EcmaVersion, ParserOptions, SourceType};
+
+const FIXTURES: &str = "tests/fixtures";
+
+fn find_fixtures(dir: &Path, fixtures: &mut Vec<PathBuf>) {
+    let mut entries: Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    entries.sort();
+
+    if entries.iter().any(|path| path.ends_with("input.js")) {
+        fixtures.push(dir.to_path_buf());
+    }
+    for path in entries {
+        if path.is_dir() {
+            find_fixtures(&path, fixtures);
+        }
+    }
+}
+
+// The parser options from the options files for the fixture.
+fn parser_options(root: &Path, fixture: &Path) -> ParserOptions {
+    let mut options = ParserOptions::default();
+
+    let relative = fixture.strip_prefix(root).unwrap();
+    let mut dirs = vec![root.to_path_buf()];
+    for component in relative.components() {
+        let dir = dirs.last().unwrap().join(component);
+        dirs.push(dir);
+    }
+
+    for dir in dirs {
+        let path = dir.join("options.json");
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+        let value = estree::json::parse(&text).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        let fields = match value {
+            Value::Object(fields) => fields,
+            _ => panic!("{}: Expected an object", path.display()),
+        };
+        for (key, value) in fields {
+            let flag = match &key[..] {
+                "jsx" => Some(&mut options.extensions.jsx),
+                "decorators" => Some(&mut options.extensions.decorators),
+                "experimentalOperators" => Some(&mut options.extensions.experimental_operators),
+                "typescript" => Some(&mut options.extensions.typescript),
+                "flow" => Some(&mut options.extensions.flow),
+                "allowReturnOutsideFunction" => Some(&mut options.allow_return_outside_function),
+                "allowHashbang" => Some(&mut options.allow_hashbang),
+                "preserveParens" => Some(&mut options.preserve_parens),
+                "attachComments" => Some(&mut options.attach_comments),
+                "annexB" => Some(&mut options.annex_b),
+                _ => None,
+            };
+            let known = match (flag, &key[..]) {
+                (Some(flag), _) => value.as_bool().map(|value| *flag = value).is_some(),
+                (None, "sourceType") => {
+                    let source_type = match value.as_str() {
+                        Some("script") => Some(SourceType::Script),
+                        Some("module") => Some(SourceType::Module),
+                        Some("unambiguous") => Some(SourceType::Unambiguous),
+                        _ => None,
+                    };
+                    source_type.map(|source_type| options.source_type = source_type).is_some()
+                }
+                (None, "ecmaVersion") => {
+                    let version = value.as_f64().and_then(|number| EcmaVersion::from_number(number as u32));
+                    version.map(|version| options.ecma_version = version).is_some()
+                }
+                _ => false,
+            };
+            if !known {
+                panic!("{}: Unknown option {} = {}", path.display(), key, value);
+            }
+        }
+    }
+    options
+}
+
+// The contents of every snapshot for the input, or None for snapshots that
+// should not exist.
+fn snapshots(code: &str, options: &ParserOptions) -> Result<Vec<(&'static str, Option<String>)>, String> {
+    let ast = match parser::parse(code, options) {
+        Ok(ast) => ast,
+        Err(diagnostic) => {
+            return Ok(vec![
+                ("output.json", None),
+                ("output.js", None),
+                ("error.txt", Some(format!("{}\n", diagnostic))),
+            ]);
+        }
+    };
+
+    let tree = estree::to_value(&ast, code);
+    let printed = ast::format(&ast, &FormatOptions::pretty()).map_err(|e| format!("Failed to print: {:?}", e))?;
+
+    let reparsed = parser::parse(&printed, options).map_err(|e| format!("Printed code does not parse: {}", e))?;
+    if shape(&estree::to_value(&reparsed, &printed)) != shape(&tree) {
+        return Err("Printed code parses to a different AST".into());
+    }
+
+    Ok(vec![
+        ("output.json", Some(format!("{}\n", strip(&tree, &["loc", "range"]).to_pretty_string()))),
+        ("output.js", Some(format!("{}\n", printed.trim_end()))),
+        ("error.txt", None),
+    ])
+}
+
+fn strip(value: &Value, keys: &[&str]) -> Value {
+    match *value {
+        Value::Object(ref fields) => Value::Object(
+            fields
+                .iter()
+                .filter(|(key, _)| !keys.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), strip(value, keys)))
+                .collect(),
+        ),
+        Value::Array(ref items) => Value::Array(items.iter().map(|item| strip(item, keys)).collect()),
+        ref value => value.clone(),
+    }
+}
+
+// The tree without anything that changes when code is printed.
+fn shape(value: &Value) -> Value {
+    strip(value, &["start", "end", "loc", "range", "raw"])
+}
+
+// Show the lines that differ between two texts, with a little context.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+
+    let prefix = expected.iter().zip(&actual).take_while(|(a, b)| a == b).count();
+    let suffix = expected[prefix..].iter().rev().zip(actual[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+
+    let start = prefix.saturating_sub(2);
+    let mut out = format!("@@ line {} @@\n", start + 1);
+    for line in &expected[start..prefix] {
+        out.push_str(&format!(" {}\n", line));
+    }
+    for line in &expected[prefix..expected.len() - suffix] {
+        out.push_str(&format!("-{}\n", line));
+    }
+    for line in &actual[prefix..actual.len() - suffix] {
+        out.push_str(&format!("+{}\n", line));
+    }
+    for line in expected[expected.len() - suffix..].iter().take(2) {
+        out.push_str(&format!(" {}\n", line));
+    }
+    out
+}
+
+#[test]
+fn fixtures() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURES);
+    let update = env::var_os("UPDATE_FIXTURES").is_some();
+
+    let mut fixtures = vec![];
+    find_fixtures(&root, &mut fixtures);
+    assert!(!fixtures.is_empty(), "No fixtures found in {}", root.display());
+
+    let mut failures = vec![];
+    for fixture in &fixtures {
+        let name = fixture.strip_prefix(&root).unwrap().display();
+        let code = fs::read_to_string(fixture.join("input.js")).unwrap();
+
+        let snapshots = match snapshots(&code, &parser_options(&root, fixture)) {
+            Ok(snapshots) => snapshots,
+            Err(message) => {
+                failures.push(format!("{}: {}", name, message));
+                continue;
+            }
+        };
+        for (file, actual) in snapshots {
+            let path = fixture.join(file);
+            let expected = fs::read_to_string(&path).ok();
+            if expected == actual {
+                continue;
+            }
+
+            if update {
+                match actual {
+                    Some(actual) => fs::write(&path, actual).unwrap(),
+                    None => fs::remove_file(&path).unwrap(),
+                }
+                continue;
+            }
+            failures.push(match (expected, actual) {
+                (Some(expected), Some(actual)) => format!("{}/{} differs:\n{}", name, file, diff(&expected, &actual)),
+                (Some(_), None) => format!("{}/{} should not exist", name, file),
+                (None, Some(_)) => format!("{}/{} is missing", name, file),
+                (None, None) => unreachable!(),
+            });
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} fixtures failed, run with UPDATE_FIXTURES=1 to update the snapshots:\n\n{}",
+        failures.len(),
+        fixtures.len(),
+        failures.join("\n"),
+    );
+}
+
+#[test]
+fn it_diffs_changed_lines() {
+    assert_eq!(diff("a\nb\nc\nd\ne\nf\n", "a\nb\nc\nx\ne\nf\n"), "@@ line 2 @@\n b\n c\n-d\n+x\n e\n f\n");
+    assert_eq!(diff("a\n", "a\nb\n"), "@@ line 1 @@\n a\n+b\n");
+}
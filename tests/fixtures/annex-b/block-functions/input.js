@@ -0,0 +1,3 @@
+{ function f() {} }
+f();
+switch (a) { case 1: function g() {} }
@@ -0,0 +1,8 @@
+{
+  function f() {}
+}
+f();
+switch (a) {
+  case 1:
+    function g() {}
+}
@@ -0,0 +1 @@
+while (a) b: function f() {}
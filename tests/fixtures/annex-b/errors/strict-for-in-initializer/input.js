@@ -0,0 +1,2 @@
+"use strict";
+for (var a = 1 in b);
@@ -0,0 +1 @@
+function f() { "use strict"; return 010; }
@@ -0,0 +1,2 @@
+"use strict";
+a = 010;
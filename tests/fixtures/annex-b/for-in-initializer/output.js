@@ -0,0 +1,2 @@
+for (var a = 1 in b);
+for (var c = d ? e : f in g) {}
@@ -0,0 +1,4 @@
+a = 1; <!-- comment
+--> comment
+b = c-->0;
+/* block */ --> comment
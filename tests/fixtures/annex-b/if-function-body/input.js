@@ -0,0 +1,2 @@
+if (a) function f() {}
+if (a) function g() {} else function h() {}
@@ -0,0 +1,2 @@
+a: function f() {}
+b: c: function g() {}
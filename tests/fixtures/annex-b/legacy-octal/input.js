@@ -0,0 +1,4 @@
+a = 010;
+b = 019;
+c = 08.5;
+d = "\01\7";
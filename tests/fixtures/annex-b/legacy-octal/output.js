@@ -0,0 +1,4 @@
+a = 8;
+b = 19;
+c = 8.5;
+d = '\01\7';
@@ -0,0 +1,5 @@
+@a @b.c(1) class A {
+  @dec static foo(@inject x, y) {}
+  @(x[0]) get bar() {}
+}
+var B = @dec class {};
@@ -0,0 +1,2 @@
+export @dec class C {}
+export default @dec.x() class {}
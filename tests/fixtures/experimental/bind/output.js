@@ -0,0 +1,3 @@
+var f = obj::obj.method;
+var g = ::console.log;
+(obj::fn)(1);
@@ -0,0 +1,7 @@
+var x = do {
+  if (a) {
+    1;
+  } else {
+    2;
+  }
+};
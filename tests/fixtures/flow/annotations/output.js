@@ -0,0 +1,18 @@
+let a: ?string = null, b: ?number[] = [];
+const { c, d }: {|
+  c: boolean;
+  d?: number;
+|} = e;
+function f<T: Object>(x: T, y?: ?string, ...rest: Array<mixed>): void {}
+const g = (x: number, y = 2): number => x + y;
+let h: {|
+  ...A;
+  b: B;
+|} = i;
+let j: {||} | {|
+  k: ?K;
+|};
+let l: {
+  ...M;
+  n: N;
+};
@@ -0,0 +1,5 @@
+/*:: type A = { b: string }; */
+/* flow-include import type { C } from "c"; */
+let d /*: A */ = e;
+function f(x /*: number */, y /*: ?C */) /*: void */ {}
+/* A plain comment */
@@ -0,0 +1,6 @@
+type A = {
+  b: string;
+};
+import type { C } from 'c';
+let d: A = e;
+function f(x: number, y: ?C): void {}
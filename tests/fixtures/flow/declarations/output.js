@@ -0,0 +1,12 @@
+type A = {
+  b: string;
+};
+opaque type Id = string;
+opaque type Token: string = string;
+opaque type Pair<T>: Array<T> = [T, T];
+declare opaque type Handle;
+declare opaque type Key: string;
+export type C = A;
+interface D {
+  e(): void;
+}
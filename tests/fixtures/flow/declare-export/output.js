@@ -0,0 +1,6 @@
+declare export function f(x: number): string;
+declare export class A {}
+declare export var b: B;
+declare export default C;
+declare export default function g(): void;
+declare export type D = E;
@@ -0,0 +1,7 @@
+declare module "m" {
+  declare export function f(): void;
+  declare module.exports: { g: G };
+}
+declare module N {
+  declare var x: X;
+}
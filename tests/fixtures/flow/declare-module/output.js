@@ -0,0 +1,9 @@
+declare module 'm' {
+  declare export function f(): void;
+  declare module.exports: {
+    g: G;
+  };
+}
+declare module N {
+  declare var x: X;
+}
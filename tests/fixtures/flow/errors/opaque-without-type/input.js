@@ -0,0 +1 @@
+opaque A = B;
@@ -0,0 +1,3 @@
+import { type Y, typeof Z } from "m";
+import A, { type B as C, typeof D as E, F } from "n";
+import { type, typeof as G } from "o";
@@ -0,0 +1,7 @@
+import type A from 'a';
+import type B, { C, D as E } from 'b';
+import type { F } from 'f';
+import typeof * as G from 'g';
+import typeof H from 'h';
+import type from 'type';
+import type, { type as value } from 'type';
@@ -0,0 +1,16 @@
+type A = {
+  a: T;
+  ...;
+};
+type B = {
+  ...;
+};
+type C = {
+  ...D;
+  e: E;
+  ...;
+};
+function f(x: {
+  y: Y;
+  ...;
+}): void {}
@@ -0,0 +1,8 @@
+function isString(x: mixed): boolean %checks {
+  return typeof x === "string";
+}
+declare function isNumber(x: mixed): boolean %checks(typeof x === "number");
+const isBoolean = (x: mixed): %checks => typeof x === "boolean";
+function noReturnType(x: mixed): %checks {
+  return !!x;
+}
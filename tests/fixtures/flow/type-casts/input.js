@@ -0,0 +1,4 @@
+(a: any);
+const b = ((c: any): string);
+f((x: number), (y: ?Y));
+const d = (e + g: number) * 2;
@@ -0,0 +1,6 @@
+function f() {
+  type T = number;
+}
+class A {
+  /*:: b: number; */
+}
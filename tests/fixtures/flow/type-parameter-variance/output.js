@@ -0,0 +1,5 @@
+class K<+T, -U> {}
+type R<+T> = T;
+interface I<-T> {}
+function f<+T: Object>(x: T) {}
+type Fn = <-T>(x: T) => void;
@@ -0,0 +1,9 @@
+class A {
+  +p: T;
+  -q: T = r;
+  static +s: S;
+}
+type B = {
+  +c: C;
+  -d: D;
+};
@@ -0,0 +1 @@
+<input type="text" disabled value={value} {...props} data-foo-bar='a &amp; b' xlink:href="#" label=<b>bold</b> />;
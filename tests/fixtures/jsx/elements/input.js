@@ -0,0 +1,9 @@
+var a = <div>
+  Hello, {name}!
+  <br />
+  <Foo.Bar.Baz>{...children}</Foo.Bar.Baz>
+  <svg:rect />
+  {/* nothing */}
+</div>;
+var b = <a></a> / 2;
+var c = x < <b />;
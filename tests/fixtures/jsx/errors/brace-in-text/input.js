@@ -0,0 +1 @@
+var a = <a>x } y</a>;
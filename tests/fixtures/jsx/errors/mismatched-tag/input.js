@@ -0,0 +1 @@
+var a = <a>text</b>;
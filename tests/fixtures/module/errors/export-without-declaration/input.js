@@ -0,0 +1 @@
+export 1;
@@ -0,0 +1,8 @@
+export var a = 1;
+export let b = 2;
+export const c = 3;
+export function d() {}
+export class E {}
+export { a as f, b };
+export * from 'g';
+export { h } from 'h';
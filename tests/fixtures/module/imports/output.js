@@ -0,0 +1,5 @@
+import a from 'a';
+import * as b from 'b';
+import { c, d as e } from 'c';
+import f, { g } from 'f';
+import 'side-effect';
@@ -0,0 +1 @@
+var x = 2 ** 3;
@@ -0,0 +1,8 @@
+('use strict');
+var a = (1 + 2) * 3;
+var b = (a ? b : c) + (d, e);
+var c = (-1) ** 2, d = (a ** b) ** c;
+a = b;
+[a, b.c] = d;
+(function () {})();
+({}).x;
@@ -0,0 +1,2 @@
+if (done) return;
+return 1;
@@ -0,0 +1,2 @@
+var let = 1;
+with (a) {}
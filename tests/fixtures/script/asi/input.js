@@ -0,0 +1,10 @@
+let a = 1
+let b = a
+++b
+const c = b
+(function () {})
+function f() {
+  return
+  a
+}
+do {} while (false) a = 2
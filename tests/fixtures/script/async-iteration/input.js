@@ -0,0 +1,7 @@
+async function f() {
+  for await (const a of b) {}
+  for await (a of b);
+}
+async function* g() {
+  yield* h();
+}
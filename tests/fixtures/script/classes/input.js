@@ -0,0 +1,27 @@
+class A {
+  constructor(x) {
+    this.x = x;
+  }
+  get value() {
+    return this.x;
+  }
+  set value(v) {
+    this.x = v;
+  }
+  static create() {
+    return new A(1);
+  }
+  *items() {
+    yield this.x;
+  }
+  async load() {
+    await this.x;
+  }
+  ["computed" + 1]() {}
+}
+class B extends A {
+  constructor() {
+    super(2);
+  }
+}
+var C = class {};
@@ -0,0 +1,8 @@
+// A line comment
+var a = 1; /* A block comment */
+/**
+ * A doc comment
+ */
+function f(/* inline */ a) {
+  return a; // trailing
+}
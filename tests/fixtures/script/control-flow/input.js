@@ -0,0 +1,27 @@
+if (a) b(); else if (c) d(); else e();
+for (var i = 0; i < 10; i++) continue;
+for (var key in object) break;
+for (var item of list) {}
+while (a) a--;
+do a++; while (a < 10);
+outer: for (;;) {
+  inner: while (true) break outer;
+}
+switch (x) {
+  case 1:
+    a();
+  case 2: {
+    break;
+  }
+  default:
+    b();
+}
+try {
+  throw new Error("x");
+} catch (e) {
+} finally {
+  done();
+}
+try {} catch {}
+with (object) {}
+debugger;
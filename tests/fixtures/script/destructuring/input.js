@@ -0,0 +1,6 @@
+var { a, b: { c }, d = 1, ...rest } = obj;
+var [x, , y = 2, ...others] = arr;
+[a, b] = [b, a];
+({ a, b } = obj);
+function f({ p, q } = {}, [r] = []) {}
+for (const [key, value] of entries) {}
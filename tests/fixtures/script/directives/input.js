@@ -0,0 +1,6 @@
+"use strict";
+'use\x20strict';
+function f() {
+  "use asm";
+  return 1;
+}
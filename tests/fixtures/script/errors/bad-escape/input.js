@@ -0,0 +1 @@
+var a = "\x4";
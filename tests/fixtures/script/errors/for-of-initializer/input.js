@@ -0,0 +1 @@
+for (let a = 1 of b) {}
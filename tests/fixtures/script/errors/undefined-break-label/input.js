@@ -0,0 +1,3 @@
+a: while (b) {
+  break c;
+}
@@ -0,0 +1,28 @@
+a = b ? c : d ? e : f;
+a = (b, c);
+a = -(-b) + +(+c) - -d;
+a = !b && c || d;
+a = typeof b === 'string' && void 0 !== delete c.d;
+a = b ** c ** d;
+a = (-b) ** 2;
+a = b instanceof C && 'key' in d;
+a += b;
+a -= b;
+a **= b;
+a <<= b;
+a >>>= b;
+a |= b;
+a = new B;
+a = new B.C(1).d;
+a = (new B)();
+a = {
+  b,
+  c: 1,
+  [d]: 2,
+  e() {},
+  get f() {
+    return 1;
+  },
+  ...g
+};
+a = [1, , ...b];
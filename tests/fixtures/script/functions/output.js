@@ -0,0 +1,17 @@
+function declared(a, b = 1, ...rest) {
+  return a + b + rest.length;
+}
+var expression = function named() {};
+var arrow = (a, b) => a + b;
+var body = x => {
+  return x;
+};
+var object = () => ({ a: 1 });
+function* generator() {
+  yield 1;
+  yield* other();
+}
+async function asynchronous() {
+  await declared(1);
+}
+var asyncArrow = async x => await x;
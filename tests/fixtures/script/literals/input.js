@@ -0,0 +1,4 @@
+var numbers = [0, 1.5, .5, 5., 1e3, 1E-3, 0x1F, 0o17, 0b101, 1e999];
+var strings = ["double", 'single', "esc\"aped", 'it\'s', "é\x41\n", "\u{1F600}", "line para "];
+var others = [true, false, null, this, undefined];
+var regex = /a[b-d]\/e*/gimsuy;
@@ -0,0 +1,4 @@
+var numbers = [0, 1.5, 0.5, 5, 1000, 0.001, 31, 15, 5, 1e999];
+var strings = ['double', 'single', 'esc\"aped', 'it\'s', 'é\x41\n', '\u{1F600}', 'line\u2028para\u2029'];
+var others = [true, false, null, this, undefined];
+var regex = /a[b-d]\/e*/gimsuy;
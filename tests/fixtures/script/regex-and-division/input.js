@@ -0,0 +1,7 @@
+var a = 4 / 2 / 1;
+var r = /ab+c/gi.test("abbc");
+var s = a / /x/.source.length;
+if (/[/]/.test("/")) {}
+var t = (a) / 2;
+var u = [/=/, /\//];
+var v = typeof a / 2 + void 0 / 1;
@@ -0,0 +1,12 @@
+var let = 1;
+var async = 2;
+var of = 3;
+var static = 4;
+async;
+yield;
+let;
+let = 2;
+let in a;
+let(1);
+let
+b = 1;
@@ -0,0 +1,7 @@
+var name = "world";
+var a = `hello ${name}!`;
+var b = `line one
+line two ${1 + 2} and ${`nested ${name}`}`;
+var c = tag`raw \n ${a}`;
+var d = ``;
+var e = tag`\01`;
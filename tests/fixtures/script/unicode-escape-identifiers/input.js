@@ -0,0 +1,3 @@
+var \u0061 = 1;
+var b\u{63} = \u0061 + 1;
+var \u{3C0}2 = 3;
@@ -0,0 +1,3 @@
+var a = 1;
+var bc = a + 1;
+var π2 = 3;
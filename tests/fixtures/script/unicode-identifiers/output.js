@@ -0,0 +1,4 @@
+var café = 1;
+var 变量 = 2;
+var ab = 3;
+var ℮ = 5;
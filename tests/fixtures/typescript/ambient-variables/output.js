@@ -0,0 +1,16 @@
+declare module 'config' {
+  const version: number;
+  export const name: string;
+  let mutable: boolean;
+  var legacy;
+}
+declare namespace N {
+  const a: number, b = 1;
+  export const c: string;
+  const enum E {
+    A,
+  }
+}
+declare global {
+  const DEBUG: boolean;
+}
@@ -0,0 +1,15 @@
+let a: number = 1, b: string[] = [];
+const { c, d }: {
+  c: boolean;
+  d?: number;
+} = e;
+function f(this: Window, x: number, y?: string, ...rest: any[]): void {}
+function g(x: unknown): x is string {
+  return typeof x === 'string';
+}
+const h = (x: number, y = 2): number => x + y;
+const i = async (x: string): Promise<void> => {};
+const j = c ? (d) : k => k;
+const l = c ? (d): number => d : m;
+let n: (cb: (x: number) => void) => Promise<void>;
+let o: new (...args: any[]) => object;
@@ -0,0 +1,21 @@
+abstract class Shape<T> extends Base<T> implements Sized, Named {
+  private readonly sides: number = 3;
+  static count?: number;
+  declare foo: string;
+  [key: string]: any;
+  constructor(public name: string, private x = 1) {
+    super();
+  }
+  abstract area(): number;
+  get size(): number {
+    return 1;
+  }
+  set size(v: number) {}
+  protected async load<R>(url: string): Promise<R> {
+    return fetch(url) as any;
+  }
+  public static create(): Shape<number>;
+  optional?(): void;
+}
+export abstract class Exported {}
+export default class<T> {}
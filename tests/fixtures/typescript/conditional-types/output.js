@@ -0,0 +1,9 @@
+type A<T> = T extends string ? 's' : 'n';
+type B<T> = T extends (infer U)[] ? U : never;
+type C<T> = T extends [infer H, ...infer R] ? [H, R] : [];
+type D<T> = T extends infer U extends string ? U : never;
+type E<T> = T extends {
+  a: infer U extends number;
+} ? U : never;
+type F<T> = T extends () => infer R ? R : T extends string ? 1 : 2;
+type G<T> = T extends [infer U extends string ? 1 : 2] ? U : never;
@@ -0,0 +1,4 @@
+type A = new () => object;
+type B = abstract new () => object;
+type C<T> = abstract new (...args: any[]) => T;
+let abstract: number;
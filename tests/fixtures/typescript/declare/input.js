@@ -0,0 +1,13 @@
+declare const VERSION: string;
+declare let mutable: number, other: boolean;
+declare function f(a: number): string;
+declare class Foo {
+  bar(): void;
+}
+declare enum E { A }
+declare interface I {}
+declare type T = string;
+export declare const exported: number;
+function overload(a: string): void;
+function overload(a: number): void;
+function overload(a: any) {}
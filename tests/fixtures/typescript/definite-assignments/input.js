@@ -0,0 +1,6 @@
+let a!: number;
+var b!: string, c = 1;
+class A {
+  d!: number;
+  static e!: string;
+}
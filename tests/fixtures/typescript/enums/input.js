@@ -0,0 +1,3 @@
+enum Color { Red, Green = "g", Blue = 4 }
+const enum Flags { None = 0, A = 1 << 0, B = 1 << 1, }
+export enum Exported { A }
@@ -0,0 +1,3 @@
+class A {
+  b!: number = 1;
+}
@@ -0,0 +1,5 @@
+const a = b as string;
+const c = (d as any as number) + 1;
+const e = f satisfies Config;
+const g = h!.i!;
+for (const j of k as any[]) {}
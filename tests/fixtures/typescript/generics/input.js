@@ -0,0 +1,9 @@
+let a: Map<string, Array<number>> = new Map<string, Array<number>>();
+let b: Array<Array<number>>= [];
+const c = f<string>("a");
+const d = g<T>`x`;
+const e = a < b, f2 = a > b, g2 = a >> b;
+const id = <T,>(x: T): T => x;
+function h<T extends object, U = T>(x: T, y: U): [T, U] {
+  return [x, y];
+}
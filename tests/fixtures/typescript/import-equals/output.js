@@ -0,0 +1,4 @@
+import fs = require('fs');
+import Alias = N.Inner;
+export import Other = N.Other;
+export = fs;
@@ -0,0 +1,4 @@
+let a: typeof import("a");
+let b: import("b").B;
+let c: import("c").C.D<E>;
+type F = typeof import("f").G;
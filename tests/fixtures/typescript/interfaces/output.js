@@ -0,0 +1,14 @@
+interface Point {
+  readonly x: number;
+  y?: number;
+  [key: string]: unknown;
+  move(dx: number, dy?: number): void;
+  (a: string): boolean;
+  new (a: string): Point;
+}
+interface Point3<T = number> extends Point, Base<T> {
+  z: T;
+}
+export interface Exported {
+  a: Array<Map<string, number>>;
+}
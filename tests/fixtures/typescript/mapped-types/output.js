@@ -0,0 +1,9 @@
+type A<T> = {[K in keyof T]?: T[K];};
+type B<T> = {readonly [K in keyof T]: T[K];};
+type C<T> = {-readonly [K in keyof T]-?: T[K];};
+type D<T> = {+readonly [K in keyof T]+?: T[K];};
+type E<T> = {[K in keyof T as `get${K & string}`]: () => T[K];};
+type F = {[K in 'a' | 'b'];};
+type G = {
+  [k: string]: number;
+};
@@ -0,0 +1,28 @@
+namespace N {
+  export const a = 1;
+  export function f() {}
+}
+namespace A.B.C {
+  export let b = 2;
+}
+export namespace Types {
+  export interface I {}
+}
+module M {}
+declare namespace D {
+  let x: number;
+  function g(): void;
+}
+declare global {
+  interface Window {
+    n: N;
+  }
+}
+declare module 'm' {
+  export function h(a: string): void;
+  export default h;
+}
+declare module 'short';
+const namespace = 1, module = 2;
+namespace;
+module.exports = {};
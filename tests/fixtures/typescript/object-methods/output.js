@@ -0,0 +1,8 @@
+const o = {
+  m<T>(x: T): T {
+    return x;
+  },
+  async n<T, U extends T>(a: T, b: U) {},
+  *g<T>(): Iterator<T> {},
+  plain(x: number) {},
+};
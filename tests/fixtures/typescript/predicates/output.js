@@ -0,0 +1,14 @@
+function isString(x: unknown): x is string {
+  return typeof x === 'string';
+}
+function assert(x: unknown): asserts x {}
+function assertString(x: unknown): asserts x is string {}
+class A {
+  isB(): this is B {
+    return true;
+  }
+  check(): asserts this {}
+}
+function f(asserts: unknown): asserts is string {
+  return true;
+}
@@ -0,0 +1,5 @@
+type A = `plain`;
+type B<T extends string> = `prefix-${T}`;
+type C<T extends string, U extends string> = `${T}.${U}`;
+type D = `${"a" | "b"}${number}`;
+type E = `${{ a: string }["a"]}`;
@@ -0,0 +1,6 @@
+type A = [string, number?];
+type B = [first: string, second?: number];
+type C = [...string[]];
+type D = [head: string, ...rest: number[]];
+type E<T extends unknown[]> = [...T, boolean];
+type F = [];
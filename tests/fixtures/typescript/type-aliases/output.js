@@ -0,0 +1,11 @@
+type Pair<K extends keyof any, V> = [K, V] | null;
+type Fn = (a: string, ...rest: number[]) => void;
+type Keys = keyof typeof config;
+type Item = Items[number]['name'];
+type List = readonly string[];
+type Literal = -1 | 1 | 'a' | true;
+type Both = A & (B | C);
+type Qualified = ns.inner.Type<string>;
+export type Exported = {
+  [k: string]: number;
+};
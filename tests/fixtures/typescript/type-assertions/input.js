@@ -0,0 +1,5 @@
+const a = <number>b;
+const c = <any>(<unknown>d);
+const e = <T>f.g + 1;
+const h = <Array<string>>[];
+const i = <T,>(j: T) => j;
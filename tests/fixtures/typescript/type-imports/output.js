@@ -0,0 +1,9 @@
+import type { A } from 'a';
+import type B from 'b';
+import type * as C from 'c';
+import { type D, E, type F as G } from 'd';
+import H, { type I } from 'h';
+import { type } from 'type';
+import { type as J } from 'j';
+export type { A, B as K };
+export type { L } from 'l';
@@ -0,0 +1,4 @@
+interface A<in T, out U, in out V> {}
+class B<out T> {}
+type C<in T> = (value: T) => void;
+type D<out> = out;
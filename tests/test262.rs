@@ -0,0 +1,422 @@
+//! Runs the parser over a checkout of test262, the ECMAScript conformance
+//! suite (https://github.com/tc39/test262).
+//!
+//! Run with `TEST262_DIR=path/to/test262 cargo test --test test262`. Without
+//! `TEST262_DIR` the suite is skipped. `TEST262_FILTER` limits the run to
+//! tests whose path contains the given text.
+//!
+//! Each test is parsed in the modes that its frontmatter asks for, and is
+//! expected to fail to parse only if it is marked as a negative test for the
+//! parse phase. Tests of the proposals in `UNSUPPORTED_FEATURES` are skipped.
+//! Failures are compared against the known failures listed in
+//! `tests/test262-known-failures.txt`, and the run fails if any test fails
+//! that is not listed there or passes that is. Run with `TEST262_UPDATE=1`
+//! to rewrite the list instead.
+//!
+//! The list also records the test262 revision that it was generated from,
+//! and runs against a checkout of any other revision fail, since its tests
+//! differ.
+
+extern crate jsparse;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use jsparse::parser::{self, ParserOptions};
+
+const KNOWN_FAILURES: &str = "tests/test262-known-failures.txt";
+
+// How the revision is recorded in the known failures.
+const REVISION: &str = "# Revision: ";
+
+// The features of test262 for syntax that the parser does not support yet,
+// whose tests are skipped rather than listed as known failures.
+const UNSUPPORTED_FEATURES: &[&str] = &[
+    "arbitrary-module-namespace-names",
+    "BigInt",
+    "class-fields-private",
+    "class-fields-private-in",
+    "class-fields-public",
+    "class-methods-private",
+    "class-static-block",
+    "class-static-fields-private",
+    "class-static-fields-public",
+    "class-static-methods-private",
+    "coalesce-expression",
+    "decorators",
+    "dynamic-import",
+    "explicit-resource-management",
+    "export-star-as-namespace-from-module",
+    "import-assertions",
+    "import-attributes",
+    "import-defer",
+    "import.meta",
+    "json-modules",
+    "logical-assignment-operators",
+    "numeric-separator-literal",
+    "optional-chaining",
+    "source-phase-imports",
+    "source-phase-imports-module-source",
+    "top-level-await",
+];
+
+// The name of the threads that run the tests, whose panics are not reported.
+const WORKER: &str = "test262";
+
+// The metadata in the "/*--- ... ---*/" comment at the top of a test.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Frontmatter {
+    flags: Vec<String>,
+    features: Vec<String>,
+
+    // The phase in which a negative test is expected to throw.
+    negative_phase: Option<String>,
+}
+impl Frontmatter {
+    // Read the subset of YAML that test262 uses for the keys we need.
+    fn parse(code: &str) -> Option<Frontmatter> {
+        let start = code.find("/*---")? + "/*---".len();
+        let end = start + code[start..].find("---*/")?;
+
+        let mut frontmatter = Frontmatter::default();
+        let mut key = "";
+        for line in code[start..end].lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if !line.starts_with(char::is_whitespace) {
+                let (k, value) = match trimmed.split_once(':') {
+                    Some((k, value)) => (k.trim(), value.trim()),
+                    None => continue,
+                };
+                key = k;
+                if value.starts_with('[') {
+                    let items = value.trim_start_matches('[').trim_end_matches(']');
+                    for item in items.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+                        frontmatter.push(key, item);
+                    }
+                }
+            } else if let Some(item) = trimmed.strip_prefix("- ") {
+                frontmatter.push(key, item.trim());
+            } else if key == "negative" {
+                if let Some(("phase", phase)) = trimmed.split_once(':').map(|(k, v)| (k.trim(), v.trim())) {
+                    frontmatter.negative_phase = Some(phase.into());
+                }
+            }
+        }
+        Some(frontmatter)
+    }
+
+    fn push(&mut self, key: &str, item: &str) {
+        match key {
+            "flags" => self.flags.push(item.into()),
+            "features" => self.features.push(item.into()),
+            _ => {}
+        }
+    }
+
+    fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    fn is_supported(&self) -> bool {
+        !self.features.iter().any(|feature| UNSUPPORTED_FEATURES.contains(&&feature[..]))
+    }
+
+    // The ways the test should be run, as specified by its flags.
+    fn modes(&self) -> Vec<Mode> {
+        if self.has_flag("module") {
+            vec![Mode::Module]
+        } else if self.has_flag("onlyStrict") {
+            vec![Mode::Strict]
+        } else if self.has_flag("noStrict") || self.has_flag("raw") {
+            vec![Mode::Sloppy]
+        } else {
+            vec![Mode::Sloppy, Mode::Strict]
+        }
+    }
+
+    fn expects_parse_error(&self) -> bool {
+        // Older versions of test262 called the parse phase "early".
+        matches!(self.negative_phase.as_ref().map(|phase| &phase[..]), Some("parse") | Some("early"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Mode {
+    Sloppy,
+    Strict,
+    Module,
+}
+impl Mode {
+    fn name(&self) -> &'static str {
+        match *self {
+            Mode::Sloppy => "sloppy",
+            Mode::Strict => "strict",
+            Mode::Module => "module",
+        }
+    }
+
+    fn parse(&self, code: &str) -> Result<(), String> {
+        // Hashbang comments are part of the language, rather than an option.
+        let script = ParserOptions { allow_hashbang: true, ..ParserOptions::script() };
+        let module = ParserOptions { allow_hashbang: true, ..ParserOptions::module() };
+
+        let result = panic::catch_unwind(|| match *self {
+            Mode::Sloppy => parser::parse(code, &script).map(drop),
+            Mode::Strict => parser::parse(&format!("\"use strict\";\n{}", code), &script).map(drop),
+            Mode::Module => parser::parse(code, &module).map(drop),
+        });
+        match result {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(diagnostic)) => Err(diagnostic.to_string()),
+            Err(_) => Err("Parser panicked".into()),
+        }
+    }
+}
+
+// A test that did not have the expected outcome in some mode, as it is
+// listed in the known failures.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Failure {
+    path: String,
+    mode: Mode,
+}
+impl Failure {
+    fn line(&self) -> String {
+        format!("{} {}", self.path, self.mode.name())
+    }
+}
+
+// Run a single test, returning the modes in which it failed along with its
+// features, or None if it tests an unsupported feature.
+fn run_test(code: &str) -> Result<Option<(Vec<Mode>, Vec<String>)>, String> {
+    let frontmatter = Frontmatter::parse(code).ok_or("Missing frontmatter")?;
+    if !frontmatter.is_supported() {
+        return Ok(None);
+    }
+
+    let expects_error = frontmatter.expects_parse_error();
+    let failed = frontmatter.modes().into_iter()
+        .filter(|mode| mode.parse(code).is_ok() == expects_error)
+        .collect();
+    Ok(Some((failed, frontmatter.features)))
+}
+
+fn find_tests(dir: &Path, tests: &mut Vec<PathBuf>) {
+    let mut entries: Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            find_tests(&path, tests);
+        } else {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+            // Fixtures are imported by module tests rather than run on their own.
+            if name.ends_with(".js") && !name.ends_with("_FIXTURE.js") {
+                tests.push(path);
+            }
+        }
+    }
+}
+
+// The commit that a checkout of test262 is at.
+fn revision(test262: &Path) -> Option<String> {
+    let output = Command::new("git").arg("-C").arg(test262).args(["rev-parse", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().into())
+}
+
+// The revision of test262 that the known failures were generated from, and
+// the failures.
+fn known_failures(root: &Path) -> (Option<String>, BTreeSet<String>) {
+    let text = fs::read_to_string(root.join(KNOWN_FAILURES)).unwrap_or_default();
+    let revision = text.lines()
+        .find_map(|line| line.strip_prefix(REVISION))
+        .map(|revision| revision.trim().into());
+    let failures = text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    (revision, failures)
+}
+
+fn write_known_failures(root: &Path, revision: &str, failures: &BTreeSet<String>) {
+    let mut text = format!(
+        "# Tests in test262 that jsparse does not parse as expected, one per line\n\
+         # with the mode they fail in. Regenerate with:\n\
+         #   TEST262_DIR=path/to/test262 TEST262_UPDATE=1 cargo test --test test262\n\
+         # from a checkout of test262 at this revision, or at a new one to move\n\
+         # to it.\n\
+         {}{}\n",
+        REVISION, revision,
+    );
+    for failure in failures {
+        text.push_str(failure);
+        text.push('\n');
+    }
+    fs::write(root.join(KNOWN_FAILURES), text).unwrap();
+}
+
+#[test]
+fn test262() {
+    let test262 = match env::var_os("TEST262_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            println!("Skipping test262, set TEST262_DIR to a checkout of it to run");
+            return;
+        }
+    };
+    let filter = env::var("TEST262_FILTER").unwrap_or_default();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let update = env::var_os("TEST262_UPDATE").is_some();
+
+    let checkout = revision(&test262).expect("TEST262_DIR must be a git checkout of test262");
+    let (known_revision, known) = known_failures(root);
+    if !update {
+        let known_revision = known_revision.unwrap_or_else(|| {
+            panic!("{} does not record a test262 revision, run with TEST262_UPDATE=1 to generate it", KNOWN_FAILURES)
+        });
+        assert_eq!(
+            checkout, known_revision,
+            "The known failures are for a different revision of test262, check it out or run with TEST262_UPDATE=1",
+        );
+    }
+
+    let mut tests = vec![];
+    find_tests(&test262.join("test"), &mut tests);
+    let tests: Vec<_> = tests.into_iter()
+        .map(|path| path.strip_prefix(&test262).unwrap().to_string_lossy().replace('\\', "/"))
+        .filter(|path| path.contains(&filter[..]))
+        .collect();
+
+    // Run the tests on a thread per CPU. Panics are caught by each test, so
+    // the default hook would only add noise for the worker threads. Panics on
+    // any other thread still reach the previous hook, which is put back once
+    // the workers are done.
+    let previous = Arc::new(panic::take_hook());
+    let hook = previous.clone();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().name() != Some(WORKER) {
+            hook(info);
+        }
+    }));
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(vec![]);
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            thread::Builder::new().name(WORKER.into()).spawn_scoped(scope, || {
+                while let Some(path) = tests.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = fs::read_to_string(test262.join(path))
+                        .map_err(|e| e.to_string())
+                        .and_then(|code| run_test(&code));
+                    results.lock().unwrap().push((path.clone(), result));
+                }
+            }).unwrap();
+        }
+    });
+    drop(panic::take_hook());
+    panic::set_hook(Box::new(move |info| previous(info)));
+
+    let mut failures = BTreeSet::new();
+    let mut skipped = 0;
+    let mut by_feature: BTreeMap<String, usize> = BTreeMap::new();
+    for (path, result) in results.into_inner().unwrap() {
+        let (failed, features) = match result.unwrap_or_else(|error| panic!("{}: {}", path, error)) {
+            Some(result) => result,
+            None => {
+                skipped += 1;
+                continue;
+            }
+        };
+        for mode in failed {
+            failures.insert(Failure { path: path.clone(), mode }.line());
+            for feature in &features {
+                *by_feature.entry(feature.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    println!("test262: {} tests, {} skipped, {} failures", tests.len(), skipped, failures.len());
+    let mut features: Vec<_> = by_feature.into_iter().collect();
+    features.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (feature, count) in features.iter().take(20) {
+        println!("  {:>6}  {}", count, feature);
+    }
+
+    if update {
+        write_known_failures(root, &checkout, &failures);
+        return;
+    }
+
+    // Known failures are only compared for the tests that were run.
+    let known: BTreeSet<_> = known.into_iter()
+        .filter(|line| line.contains(&filter[..]))
+        .collect();
+    let new: Vec<_> = failures.difference(&known).collect();
+    let fixed: Vec<_> = known.difference(&failures).collect();
+    assert!(
+        new.is_empty() && fixed.is_empty(),
+        "{} new failures:\n{:#?}\n{} known failures now pass:\n{:#?}\nRun with TEST262_UPDATE=1 to update {}",
+        new.len(), new, fixed.len(), fixed, KNOWN_FAILURES,
+    );
+}
+
+#[test]
+fn it_reads_frontmatter() {
+    let code = "\
+// Copyright
+/*---
+esid: sec-example
+description: >
+  Something: with a colon
+flags: [onlyStrict, raw]
+negative:
+  phase: parse
+  type: SyntaxError
+features:
+  - class-fields-public
+  - BigInt
+---*/
+
+$DONOTEVALUATE();
+";
+    assert_eq!(Frontmatter::parse(code), Some(Frontmatter {
+        flags: vec!["onlyStrict".into(), "raw".into()],
+        features: vec!["class-fields-public".into(), "BigInt".into()],
+        negative_phase: Some("parse".into()),
+    }));
+    assert_eq!(Frontmatter::parse("var a;"), None);
+}
+
+#[test]
+fn it_runs_tests_in_every_mode() {
+    let test = |frontmatter: &str, body: &str| {
+        run_test(&format!("/*---\n{}\n---*/\n{}", frontmatter, body)).unwrap().unwrap().0
+    };
+
+    assert_eq!(test("description: x", "var a = 1;"), vec![]);
+    assert_eq!(test("flags: [module]", "import a from 'a';"), vec![]);
+    assert_eq!(test("flags: [noStrict]", "import a from 'a';"), vec![Mode::Sloppy]);
+    assert_eq!(test("negative:\n  phase: parse\n  type: SyntaxError", "var = 1;"), vec![]);
+    assert_eq!(test("negative:\n  phase: parse\n  type: SyntaxError", "var a;"), vec![Mode::Sloppy, Mode::Strict]);
+    assert_eq!(test("negative:\n  phase: runtime\n  type: Test262Error", "throw 1;"), vec![]);
+}
+
+#[test]
+fn it_skips_unsupported_features() {
+    assert_eq!(run_test("/*---\nfeatures: [BigInt]\n---*/\nvar a = 1n;"), Ok(None));
+    assert_eq!(run_test("/*---\nfeatures: [Symbol]\n---*/\nvar a = Symbol();"), Ok(Some((vec![], vec!["Symbol".into()]))));
+}